@@ -0,0 +1,34 @@
+//! Feed arbitrary files to the config parser and everything `main` does
+//! with the settings at startup, so a malformed config.toml is reported
+//! instead of panicking
+
+#![no_main]
+
+use cleansys::cleaners::custom::check_glob;
+use cleansys::config::{append_exclusion, Settings};
+use libfuzzer_sys::fuzz_target;
+
+fn use_settings(settings: &Settings) {
+    let _ = settings.run_limit();
+    let _ = settings.cleaner_timeouts();
+    for cleaner in &settings.custom_cleaners {
+        for glob in &cleaner.paths {
+            let _ = check_glob(glob, cleaner.scope);
+        }
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(contents) = std::str::from_utf8(data) else {
+        return;
+    };
+    if let Ok(settings) = toml::from_str::<Settings>(contents) {
+        use_settings(&settings);
+    }
+
+    // `cleansys exclude` edits the file in place and parses the result
+    let updated = append_exclusion(contents, "~/.cache/keep");
+    if let Ok(settings) = toml::from_str::<Settings>(&updated) {
+        use_settings(&settings);
+    }
+});
@@ -0,0 +1,59 @@
+//! Restore arbitrary state.json files into the TUI and draw the first
+//! frame, so a corrupt session can't stop the app from starting
+
+#![no_main]
+
+use anyhow::Result;
+use cleansys::app::{App, CleanerCategory, CleanerItem};
+use cleansys::cleaners::RiskLevel;
+use cleansys::render::ui;
+use cleansys::state::SessionState;
+use libfuzzer_sys::fuzz_target;
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+
+fn noop(_: bool) -> Result<u64> {
+    Ok(0)
+}
+
+fn category(name: &str, items: &[&str]) -> CleanerCategory {
+    CleanerCategory {
+        name: name.to_string(),
+        description: String::new(),
+        items: items
+            .iter()
+            .map(|item| CleanerItem {
+                name: item.to_string(),
+                description: String::new(),
+                docs: None,
+                requires_root: false,
+                risk: RiskLevel::Normal,
+                selected: false,
+                function: noop,
+                scan: None,
+                scan_state: None,
+                bytes_cleaned: 0,
+                bytes_deferred: 0,
+                status: None,
+                parent: None,
+                tags: vec![],
+            })
+            .collect(),
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(state) = serde_json::from_slice::<SessionState>(data) else {
+        return;
+    };
+
+    let mut app = App::new();
+    app.categories = vec![
+        category("User Land Cleaners", &["Application Caches", "Trash"]),
+        category("System Cleaners", &["System Logs"]),
+    ];
+    app.apply_session_state(&state);
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+});
@@ -0,0 +1,453 @@
+//! What the TUI can be asked to do. Events become [`Action`]s through the
+//! keymap of the [`Screen`] on top, and [`App::dispatch`] applies them, so
+//! key handling is split per screen and actions can be replayed without a
+//! terminal, for example by scripted UI tests.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::app::App;
+use crate::events::Event;
+
+/// A single thing the user asked the TUI to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Leave the TUI, cancelling a running clean
+    Quit,
+    /// Ask before quitting in the middle of a run
+    OpenQuitDialog,
+    CloseQuitDialog,
+
+    ShowHelp,
+    HideHelp,
+    ToggleHelp,
+    ScrollHelpDown(u16),
+    ScrollHelpUp(u16),
+
+    /// Type into the text field that has focus: the password prompt, the
+    /// schedule input, the bulk action menu or the search
+    TypeChar(char),
+    /// Delete the last character of the text field that has focus
+    DeleteChar,
+
+    OpenActionMenu,
+    CloseActionMenu,
+    SubmitActionMenu,
+    NextMenuEntry,
+    PreviousMenuEntry,
+
+    ShowAbout,
+    CloseAbout,
+    ToggleMessageHistory,
+    CloseMessageHistory,
+
+    ConfirmRiskyRun,
+    CancelRiskyRun,
+
+    ToggleSchedule,
+    SubmitSchedule,
+    CancelSchedule,
+
+    SubmitPassword,
+    CancelPassword,
+
+    /// Sudo authentication failed mid-run: ask for the password again
+    Reauthenticate,
+    /// Sudo authentication failed mid-run: skip the system cleaners left
+    SkipSystemCleaners,
+    /// Sudo authentication failed mid-run: stop the whole run
+    AbortRun,
+
+    /// A deletion hit the run's safety limit: go past it
+    ExceedLimit,
+    /// A deletion hit the run's safety limit: skip the cleaners left
+    StopAtLimit,
+
+    /// Run `fstrim` on the SSD mounts the finished run freed space on
+    TrimMounts,
+    SkipTrim,
+
+    // Cleaner list
+    NextItem,
+    PreviousItem,
+    FirstItem,
+    LastItem,
+    NextCategory,
+    PreviousCategory,
+    /// Jump to the category at this position, from the number keys
+    JumpToCategory(usize),
+    /// Shrink the category sidebar to numbers and counts, or expand it
+    ToggleCategoriesCollapsed,
+    /// Show or hide the system information panel
+    ToggleSystemInfo,
+    /// Stop the background scans, keeping the finished ones
+    CancelScans,
+    ToggleSelected,
+    SelectAll,
+    DeselectAll,
+    SelectRecommended,
+    RunSelected,
+    ToggleJournalVacuum,
+    ClearErrors,
+
+    // Progress and results screen
+    ScrollDown(usize),
+    ScrollUp(usize),
+    ScrollToTop,
+    ScrollToBottom,
+    CycleProgressPane,
+    NextLegendEntry,
+    PreviousLegendEntry,
+    ToggleLegendFilter,
+    ClearLegendFilter,
+    ToggleJournalGroup,
+    /// Grow (`true`) or shrink the progress overview
+    ResizeProgressSplit(bool),
+    /// Exclude the highlighted removed path, or its parent with `true`
+    ExcludeHighlighted(bool),
+    /// Open the highlighted removed path's folder, in a shell with `true`
+    OpenHighlightedFolder(bool),
+    TogglePause,
+    /// Stop the running clean
+    CancelRun,
+    /// Go back from the results to the cleaner list
+    CloseProgress,
+    /// Ask whether to undo the most recent run from the quarantine
+    UndoLastRun,
+    /// Restore the files the undo dialog asked about
+    ConfirmUndo,
+    CancelUndo,
+
+    ToggleSearch,
+    ClearSearch,
+
+    // View
+    ToggleCompactMode,
+    ToggleAutoScroll,
+    TogglePerformanceStats,
+    CycleViewMode,
+    CycleSortMode,
+    CycleFilterMode,
+    ToggleConfirmationMode,
+    ToggleChartType,
+    /// Show sizes as exact byte counts, or KB, MB and GB again
+    ToggleRawSizes,
+
+    /// Advance animations, collect scans and start scheduled runs
+    Tick,
+    Resize(u16, u16),
+}
+
+/// The screen or overlay on top, which gets the keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Screen {
+    Help,
+    ActionMenu,
+    About,
+    MessageHistory,
+    QuitDialog,
+    ConfirmDialog,
+    /// Sudo authentication failed while system cleaners were left to run
+    SudoDialog,
+    /// A deletion hit the run's safety limit
+    LimitDialog,
+    /// A finished run offers to trim SSD mounts
+    TrimDialog,
+    /// Asks before restoring the last run from the quarantine
+    UndoDialog,
+    ScheduleInput,
+    PasswordPrompt,
+    /// The progress screen of a run, and the results after it
+    Progress,
+    /// The cleaner list
+    Main,
+}
+
+impl Screen {
+    /// The screen on top in `app`. The help overlay sits above everything,
+    /// prompts included.
+    pub fn of(app: &App) -> Self {
+        if app.help.is_visible() {
+            Screen::Help
+        } else if app.action_menu.is_visible() {
+            Screen::ActionMenu
+        } else if app.show_about {
+            Screen::About
+        } else if app.toasts.is_history_visible() {
+            Screen::MessageHistory
+        } else if app.quit_dialog.is_visible() {
+            Screen::QuitDialog
+        } else if app.confirm_dialog.is_visible() {
+            Screen::ConfirmDialog
+        } else if app.sudo_dialog.is_visible() {
+            Screen::SudoDialog
+        } else if app.limit_dialog.is_visible() {
+            Screen::LimitDialog
+        } else if app.trim_dialog.is_visible() {
+            Screen::TrimDialog
+        } else if app.undo_dialog.is_visible() {
+            Screen::UndoDialog
+        } else if app.schedule_input.is_some() {
+            Screen::ScheduleInput
+        } else if app.password_prompt.is_visible() {
+            Screen::PasswordPrompt
+        } else if app.is_running || app.show_progress_screen {
+            Screen::Progress
+        } else {
+            Screen::Main
+        }
+    }
+}
+
+/// The action for an event, if it does anything
+pub fn for_event(app: &App, event: Event) -> Option<Action> {
+    match event {
+        Event::Input(key) => keymap(app, key),
+        Event::Tick => Some(Action::Tick),
+        Event::Resize(width, height) => Some(Action::Resize(width, height)),
+    }
+}
+
+/// The action a key press stands for on the screen on top
+pub fn keymap(app: &App, key: KeyEvent) -> Option<Action> {
+    let screen = Screen::of(app);
+    if key.code == KeyCode::F(1) && screen != Screen::Help {
+        return Some(Action::ShowHelp);
+    }
+    match screen {
+        Screen::Help => help_keymap(key),
+        Screen::ActionMenu => action_menu_keymap(app, key),
+        Screen::About => matches!(
+            key.code,
+            KeyCode::Char('A' | 'q') | KeyCode::Esc | KeyCode::Enter
+        )
+        .then_some(Action::CloseAbout),
+        Screen::MessageHistory => matches!(
+            key.code,
+            KeyCode::Char('M' | 'q') | KeyCode::Esc | KeyCode::Enter
+        )
+        .then_some(Action::CloseMessageHistory),
+        Screen::QuitDialog => quit_dialog_keymap(key),
+        Screen::ConfirmDialog => confirm_dialog_keymap(key),
+        Screen::SudoDialog => sudo_dialog_keymap(key),
+        Screen::LimitDialog => limit_dialog_keymap(key),
+        Screen::TrimDialog => trim_dialog_keymap(key),
+        Screen::UndoDialog => undo_dialog_keymap(key),
+        Screen::ScheduleInput => {
+            text_input_keymap(key, Action::SubmitSchedule, Action::CancelSchedule)
+        }
+        Screen::PasswordPrompt => {
+            text_input_keymap(key, Action::SubmitPassword, Action::CancelPassword)
+        }
+        Screen::Progress => search_keymap(app, key)
+            .or_else(|| progress_keymap(app, key))
+            .or_else(|| common_keymap(app, key)),
+        Screen::Main => search_keymap(app, key)
+            .or_else(|| main_keymap(app, key))
+            .or_else(|| common_keymap(app, key)),
+    }
+}
+
+/// While searching, typed characters go into the query whatever they are
+/// bound to otherwise
+fn search_keymap(app: &App, key: KeyEvent) -> Option<Action> {
+    if !app.search_active {
+        return None;
+    }
+    Some(match (key.code, key.modifiers) {
+        (KeyCode::Char(c), KeyModifiers::NONE | KeyModifiers::SHIFT) => Action::TypeChar(c),
+        (KeyCode::Backspace, _) => Action::DeleteChar,
+        (KeyCode::Esc, _) => Action::ClearSearch,
+        _ => return None,
+    })
+}
+
+fn help_keymap(key: KeyEvent) -> Option<Action> {
+    Some(match key.code {
+        KeyCode::Char('?' | 'h' | 'q') | KeyCode::Esc | KeyCode::F(1) => Action::HideHelp,
+        KeyCode::Down | KeyCode::Char('j') => Action::ScrollHelpDown(1),
+        KeyCode::Up | KeyCode::Char('k') => Action::ScrollHelpUp(1),
+        KeyCode::PageDown | KeyCode::Char(' ') => Action::ScrollHelpDown(10),
+        KeyCode::PageUp => Action::ScrollHelpUp(10),
+        KeyCode::Home => Action::ScrollHelpUp(u16::MAX),
+        KeyCode::End => Action::ScrollHelpDown(u16::MAX),
+        _ => return None,
+    })
+}
+
+fn action_menu_keymap(app: &App, key: KeyEvent) -> Option<Action> {
+    Some(match key.code {
+        KeyCode::Esc => Action::CloseActionMenu,
+        KeyCode::Enter => Action::SubmitActionMenu,
+        KeyCode::Backspace => Action::DeleteChar,
+        KeyCode::Char(c) if app.action_menu.is_editing() => Action::TypeChar(c),
+        KeyCode::Down | KeyCode::Char('j') => Action::NextMenuEntry,
+        KeyCode::Up | KeyCode::Char('k') => Action::PreviousMenuEntry,
+        _ => return None,
+    })
+}
+
+fn quit_dialog_keymap(key: KeyEvent) -> Option<Action> {
+    Some(match key.code {
+        KeyCode::Char('y' | 'Y') => Action::Quit,
+        KeyCode::Char('?') => Action::ShowHelp,
+        KeyCode::Char('n' | 'N') | KeyCode::Esc | KeyCode::Enter => Action::CloseQuitDialog,
+        _ => return None,
+    })
+}
+
+fn confirm_dialog_keymap(key: KeyEvent) -> Option<Action> {
+    Some(match key.code {
+        KeyCode::Char('?') => Action::ShowHelp,
+        KeyCode::Char('y' | 'Y') => Action::ConfirmRiskyRun,
+        KeyCode::Char('n' | 'N') | KeyCode::Esc => Action::CancelRiskyRun,
+        _ => return None,
+    })
+}
+
+fn sudo_dialog_keymap(key: KeyEvent) -> Option<Action> {
+    Some(match key.code {
+        KeyCode::Char('?') => Action::ShowHelp,
+        KeyCode::Char('r' | 'R') | KeyCode::Enter => Action::Reauthenticate,
+        KeyCode::Char('s' | 'S') => Action::SkipSystemCleaners,
+        KeyCode::Char('a' | 'A') | KeyCode::Esc => Action::AbortRun,
+        _ => return None,
+    })
+}
+
+fn limit_dialog_keymap(key: KeyEvent) -> Option<Action> {
+    Some(match key.code {
+        KeyCode::Char('?') => Action::ShowHelp,
+        KeyCode::Char('y' | 'Y') => Action::ExceedLimit,
+        KeyCode::Char('n' | 'N') | KeyCode::Esc => Action::StopAtLimit,
+        _ => return None,
+    })
+}
+
+fn trim_dialog_keymap(key: KeyEvent) -> Option<Action> {
+    Some(match key.code {
+        KeyCode::Char('?') => Action::ShowHelp,
+        KeyCode::Char('y' | 'Y') => Action::TrimMounts,
+        KeyCode::Char('n' | 'N') | KeyCode::Esc => Action::SkipTrim,
+        _ => return None,
+    })
+}
+
+fn undo_dialog_keymap(key: KeyEvent) -> Option<Action> {
+    Some(match key.code {
+        KeyCode::Char('?') => Action::ShowHelp,
+        KeyCode::Char('y' | 'Y') => Action::ConfirmUndo,
+        KeyCode::Char('n' | 'N') | KeyCode::Esc => Action::CancelUndo,
+        _ => return None,
+    })
+}
+
+fn text_input_keymap(key: KeyEvent, submit: Action, cancel: Action) -> Option<Action> {
+    Some(match key.code {
+        KeyCode::Enter => submit,
+        KeyCode::Esc => cancel,
+        KeyCode::Backspace => Action::DeleteChar,
+        KeyCode::Char(c) => Action::TypeChar(c),
+        _ => return None,
+    })
+}
+
+/// Keys that only mean something while a run is shown
+fn progress_keymap(app: &App, key: KeyEvent) -> Option<Action> {
+    let legend = app.legend_focused();
+    Some(match (key.code, key.modifiers) {
+        (KeyCode::Down | KeyCode::Char('j'), _) if legend => Action::NextLegendEntry,
+        (KeyCode::Up | KeyCode::Char('k'), _) if legend => Action::PreviousLegendEntry,
+        (KeyCode::Enter, _) if legend => Action::ToggleLegendFilter,
+        (KeyCode::Down, _) => Action::ScrollDown(1),
+        (KeyCode::Up, _) => Action::ScrollUp(1),
+        (KeyCode::PageDown, _) => Action::ScrollDown(10),
+        (KeyCode::PageUp, _) => Action::ScrollUp(10),
+        (KeyCode::Home, _) => Action::ScrollToTop,
+        (KeyCode::End, _) => Action::ScrollToBottom,
+        // Move focus between the removed items and the chart legend
+        (KeyCode::Tab | KeyCode::BackTab, _) => Action::CycleProgressPane,
+        (KeyCode::Char(' '), KeyModifiers::NONE) => Action::ToggleJournalGroup,
+        (KeyCode::Char(' '), KeyModifiers::CONTROL) if app.is_running => Action::TogglePause,
+        (KeyCode::Char('s'), _) if app.is_running => Action::ToggleAutoScroll,
+        // Resize the split between the progress overview and removed items
+        (KeyCode::Char(c @ ('+' | '=' | '-')), _) => Action::ResizeProgressSplit(c != '-'),
+        // Exclude the highlighted removed path, or its parent, from now on
+        (KeyCode::Char(c @ ('i' | 'I')), _) if !app.is_running => {
+            Action::ExcludeHighlighted(c == 'I')
+        }
+        // Look at what else lives next to the highlighted removed path
+        (KeyCode::Char(c @ ('o' | 'O')), _) if !app.is_running => {
+            Action::OpenHighlightedFolder(c == 'O')
+        }
+        (KeyCode::Esc, _) if app.legend_filter.is_some() => Action::ClearLegendFilter,
+        (KeyCode::Esc, _) if app.is_running => Action::CancelRun,
+        (KeyCode::Esc, _) => Action::CloseProgress,
+        _ => return None,
+    })
+}
+
+/// Keys that only mean something on the cleaner list
+fn main_keymap(app: &App, key: KeyEvent) -> Option<Action> {
+    Some(match (key.code, key.modifiers) {
+        (KeyCode::Down, _) => Action::NextItem,
+        (KeyCode::Up, _) => Action::PreviousItem,
+        (KeyCode::Home, _) => Action::FirstItem,
+        (KeyCode::End, _) => Action::LastItem,
+        (KeyCode::Tab, _) => Action::NextCategory,
+        (KeyCode::BackTab, _) => Action::PreviousCategory,
+        // 1-9 jump straight to a category
+        (KeyCode::Char(c @ '1'..='9'), KeyModifiers::NONE) => {
+            Action::JumpToCategory(c as usize - '1' as usize)
+        }
+        (KeyCode::Char('C'), _) => Action::ToggleCategoriesCollapsed,
+        (KeyCode::Char('i'), _) => Action::ToggleSystemInfo,
+        (KeyCode::Char(' '), KeyModifiers::NONE) => Action::ToggleSelected,
+        (KeyCode::Esc, _) if app.legend_filter.is_some() => Action::ClearLegendFilter,
+        (KeyCode::Esc, _) if app.scan_progress().is_some() => Action::CancelScans,
+        _ => return None,
+    })
+}
+
+/// Keys shared by the cleaner list and the progress screen
+fn common_keymap(app: &App, key: KeyEvent) -> Option<Action> {
+    Some(match (key.code, key.modifiers) {
+        (KeyCode::Char('q'), _) if app.is_running => Action::OpenQuitDialog,
+        (KeyCode::Char('q'), _) => Action::Quit,
+        (KeyCode::Enter, _) => Action::RunSelected,
+        (KeyCode::Char('?' | 'h'), _) => Action::ToggleHelp,
+        (KeyCode::Char('B'), _) => Action::OpenActionMenu,
+        (KeyCode::Char('b'), _) => Action::ToggleRawSizes,
+        (KeyCode::Char('A'), _) => Action::ShowAbout,
+        (KeyCode::Char('R'), _) => Action::SelectRecommended,
+        (KeyCode::Char('U'), _) if !app.is_running => Action::UndoLastRun,
+        (KeyCode::Char('M'), _) => Action::ToggleMessageHistory,
+        (KeyCode::Char('/'), _) => Action::ToggleSearch,
+        // Scroll the removed items list
+        (KeyCode::Char('j'), _) => Action::ScrollDown(1),
+        (KeyCode::Char('k'), _) => Action::ScrollUp(1),
+        (KeyCode::Char('a'), _) => Action::SelectAll,
+        (KeyCode::Char('n'), _) => Action::DeselectAll,
+        (KeyCode::Char('m'), _) => Action::ToggleCompactMode,
+        (KeyCode::Char('p'), _) => Action::TogglePerformanceStats,
+        (KeyCode::Char('v'), _) => Action::CycleViewMode,
+        (KeyCode::Char('o'), _) => Action::CycleSortMode,
+        (KeyCode::Char('f'), _) => Action::CycleFilterMode,
+        (KeyCode::Char('y'), _) => Action::ToggleConfirmationMode,
+        (KeyCode::Char('c'), _) => Action::ToggleChartType,
+        (KeyCode::Char('x'), _) => Action::ClearErrors,
+        // Schedule or cancel a delayed run
+        (KeyCode::Char('t'), _) if !app.is_running => Action::ToggleSchedule,
+        // Switch the journal vacuum mode of the System Logs cleaner
+        (KeyCode::Char('J'), _) if !app.is_running && app.journal_vacuum_selected() => {
+            Action::ToggleJournalVacuum
+        }
+        // Bound to something only during a run, never typed
+        (KeyCode::Char('s' | 't' | 'J'), _) | (KeyCode::Char(' '), KeyModifiers::CONTROL) => {
+            return None
+        }
+        // Other keys toggle the highlighted cleaner
+        (KeyCode::Char('+' | '=' | '-'), _) => return None,
+        (KeyCode::Char(_), _) => Action::ToggleSelected,
+        _ => return None,
+    })
+}
@@ -1,23 +1,76 @@
 use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::KeyEvent;
 use crossterm::terminal;
 use ratatui::widgets::ListState;
+use std::collections::HashSet;
+#[cfg(unix)]
 use std::io::Read;
+#[cfg(unix)]
 use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::path::PathBuf;
 use std::sync::mpsc;
-use std::time::Instant;
-
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::action::{self, Action};
+use crate::bulk::{BulkActionKind, Selectable};
+use crate::cancel;
+use crate::children;
+use crate::cleaners::cleaned;
+pub use crate::cleaners::cleaned::CleanedItemType;
+use crate::cleaners::log_backends;
+use crate::cleaners::{RiskLevel, ScanReport, Tag};
+use crate::components::action_menu::ActionMenu;
+use crate::components::confirm_dialog::ConfirmDialog;
+use crate::components::help_overlay::{HelpContext, HelpOverlay};
 use crate::components::password_prompt::PasswordPrompt;
-use crate::utils::{check_root, format_size};
-use once_cell::sync::Lazy;
-use regex::Regex;
+use crate::components::toasts::Toasts;
+use crate::config::Settings;
+use crate::deferred;
+use crate::deleter::{self, DeleteProgress};
+use crate::events::Event;
+use crate::exclude;
+use crate::helper;
+use crate::history::{CleanerRun, History, RunDiff, RunRecord};
+use crate::limits::{self, LimitReached};
+use crate::log_buffer::LogBuffer;
+use crate::pie_chart::FreedSlices;
+use crate::privileges;
+use crate::protection::{self, Skipped};
+use crate::quarantine::{self, Quarantine};
+use crate::sanitize;
+use crate::scanner;
+use crate::schedule::{local_seconds_since_midnight, Schedule};
+use crate::selection::{Candidate, Recommended, SelectionStrategy};
+use crate::state::{SelectedCleaner, SessionState};
+use crate::sysinfo::SystemInfo;
+use crate::trim;
+use crate::usage::UsageStats;
+use crate::utils::{
+    askpass_enabled, check_root, format_size, get_disk_spaces, has_graphical_session,
+    open_in_file_manager, raw_sizes, set_raw_sizes, DiskSpace, DISK_MOUNTS,
+};
+use crate::verify;
+use crate::watchdog::{self, TimedOut};
+use crate::watcher::{watch_dirs, CacheWatcher};
+use serde::{Deserialize, Serialize};
 use std::time::SystemTime;
 
-// Compile regex once at startup
-static SIZE_REGEX: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(\d+\.?\d*)\s*(KB|MB|GB|bytes)").unwrap());
+/// Minimum time between live size refreshes, so busy caches don't cause churn
+pub const LIVE_REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How far `+`/`-` move the progress screen split, in percent
+pub const PROGRESS_SPLIT_STEP: u16 = 5;
+/// Smallest and largest share of the progress screen the overview takes
+pub const PROGRESS_SPLIT_MIN: u16 = 20;
+pub const PROGRESS_SPLIT_MAX: u16 = 80;
+
+/// How long quitting waits for a cancelled cleaner to stop after its
+/// current file
+const QUIT_GRACE: Duration = Duration::from_secs(3);
 
 /// Capture stdout/stderr during function execution
+#[cfg(unix)]
 fn capture_output<F, T>(f: F) -> Result<(T, String)>
 where
     F: FnOnce() -> Result<T>,
@@ -78,6 +131,44 @@ where
     }
 }
 
+/// Without file descriptor redirection the output can't be captured
+#[cfg(not(unix))]
+fn capture_output<F, T>(f: F) -> Result<(T, String)>
+where
+    F: FnOnce() -> Result<T>,
+{
+    f().map(|r| (r, String::new()))
+}
+
+/// A cleaner's name in the charts. User and system cleaners can share a
+/// name, so system ones are marked.
+fn chart_name(category: &str, cleaner: &str) -> String {
+    if category.contains("System") {
+        format!("{} (System)", cleaner)
+    } else {
+        cleaner.to_string()
+    }
+}
+
+/// What the worker thread running a cleaner sends back
+enum WorkerMessage {
+    Progress(DeleteProgress),
+    /// The bytes it freed and what it printed
+    Finished(Result<(u64, String)>),
+}
+
+/// A cleaner running on the worker thread
+struct RunningCleaner {
+    cat_idx: usize,
+    item_idx: usize,
+    name: String,
+    requires_root: bool,
+    before: Option<verify::Snapshot>,
+    rx: mpsc::Receiver<WorkerMessage>,
+    /// Bytes its progress added to the freed-space chart so far
+    streamed: u64,
+}
+
 #[derive(Debug, Clone)]
 pub struct DetailedCleanedItem {
     pub path: String,
@@ -88,17 +179,24 @@ pub struct DetailedCleanedItem {
     pub item_type: CleanedItemType,
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum CleanedItemType {
-    File,
-    Directory,
-    Log,
+/// A row of the removed-items journal: a cleaner's header with its
+/// subtotal, or one of the items it removed
+#[derive(Debug, Clone)]
+pub enum JournalRow<'a> {
+    Header {
+        cleaner: &'a str,
+        category: &'a str,
+        count: usize,
+        size: u64,
+        collapsed: bool,
+    },
+    Item(&'a DetailedCleanedItem),
 }
 
 /// Type alias for pending operations: (category_index, item_index, name, function, requires_root)
 pub type PendingOperation = (usize, usize, String, fn(bool) -> Result<u64>, bool);
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ViewMode {
     Standard,
     Compact,
@@ -114,6 +212,17 @@ pub enum SortMode {
     Category,
 }
 
+impl SortMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SortMode::Name => "Name",
+            SortMode::Size => "Size",
+            SortMode::Status => "Status",
+            SortMode::Category => "Default",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum FilterMode {
     All,
@@ -122,18 +231,67 @@ pub enum FilterMode {
     Errors,
     UserOnly,
     SystemOnly,
+    /// Cleaners carrying this tag
+    Tagged(Tag),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl FilterMode {
+    pub fn name(&self) -> String {
+        match self {
+            FilterMode::All => "All".to_string(),
+            FilterMode::Selected => "Selected".to_string(),
+            FilterMode::Completed => "Completed".to_string(),
+            FilterMode::Errors => "Errors".to_string(),
+            FilterMode::UserOnly => "User only".to_string(),
+            FilterMode::SystemOnly => "System only".to_string(),
+            FilterMode::Tagged(tag) => format!("Tag {}", tag.name()),
+        }
+    }
+
+    /// Whether a cleaner is shown in the list under this filter
+    pub fn matches(&self, item: &CleanerItem) -> bool {
+        match self {
+            FilterMode::All => true,
+            FilterMode::Selected => item.selected,
+            FilterMode::Completed => matches!(
+                item.status,
+                Some(Status::Success(_) | Status::PartialSuccess(..))
+            ),
+            FilterMode::Errors => matches!(
+                item.status,
+                Some(Status::Error(_) | Status::PartialSuccess(..))
+            ),
+            FilterMode::UserOnly => !item.requires_root,
+            FilterMode::SystemOnly => item.requires_root,
+            FilterMode::Tagged(tag) => item.tags.contains(tag),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ChartType {
     Bar,
     PieCount,
     PieSize,
 }
 
+/// Pane of the progress and results screen that takes the arrow keys,
+/// switched with Tab
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProgressPane {
+    /// The removed-items list
+    #[default]
+    RemovedItems,
+    /// The chart legend: entries are highlighted and Enter filters the
+    /// removed items to one of them
+    Chart,
+}
+
 pub enum Status {
     Running,
     Success(String),
+    /// Removed some paths but failed on others, listed with why
+    PartialSuccess(String, Vec<(PathBuf, String)>),
     Error(String),
     Pending,
 }
@@ -146,20 +304,93 @@ impl Status {
                 SPINNER[frame % SPINNER.len()]
             }
             Status::Success(_) => "✓",
+            Status::PartialSuccess(..) => "◐",
             Status::Error(_) => "✗",
             Status::Pending => "•",
         }
     }
 }
 
+/// Progress of a read-only scan for a single cleaner
+pub enum ScanState {
+    Scanning,
+    Done(ScanReport),
+    Failed(String),
+    /// Stopped before it finished, so the size is unknown
+    Cancelled,
+}
+
+/// What the selection footer suggests doing next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FooterHint {
+    /// Nothing selected yet
+    Select,
+    /// Cleaners are selected; `estimate` sums the scans that have finished
+    Clean { count: usize, estimate: Option<u64> },
+}
+
+/// Scan result sent back from a background scan:
+/// (category_index, item_index, result, time taken)
+pub type ScanMessage = (usize, usize, Result<ScanReport>, Duration);
+
 pub struct CleanerItem {
     pub name: String,
     pub description: String,
+    /// Markdown documentation shown in the detail pane
+    pub docs: Option<&'static str>,
     pub requires_root: bool,
+    pub risk: RiskLevel,
     pub selected: bool,
     pub function: fn(bool) -> Result<u64>,
+    pub scan: Option<fn() -> Result<ScanReport>>,
+    pub scan_state: Option<ScanState>,
     pub bytes_cleaned: u64,
+    /// Part of `bytes_cleaned` only freed after a reboot
+    pub bytes_deferred: u64,
+    /// Paths the last run left in place for lack of permission, usually
+    /// other users' files in shared directories
+    pub permission_denied: usize,
     pub status: Option<Status>,
+    /// The cleaner this one is listed under, if it is a sub-item
+    pub parent: Option<String>,
+    pub tags: Vec<Tag>,
+}
+
+impl CleanerItem {
+    /// Bytes the cleaner is expected to free: the scan estimate, or what the
+    /// last run freed when there is no scan
+    pub fn estimated_size(&self) -> Option<u64> {
+        match &self.scan_state {
+            Some(ScanState::Done(report)) => Some(report.total_reclaimable()),
+            _ => (self.bytes_cleaned > 0).then_some(self.bytes_cleaned),
+        }
+    }
+
+    /// Rank used by [`SortMode::Status`]: active work first, untouched last
+    fn status_rank(&self) -> u8 {
+        match self.status {
+            Some(Status::Running) => 0,
+            Some(Status::Pending) => 1,
+            Some(Status::Error(_)) => 2,
+            Some(Status::PartialSuccess(..)) => 3,
+            Some(Status::Success(_)) => 4,
+            None => 5,
+        }
+    }
+}
+
+impl Selectable for CleanerItem {
+    fn label(&self) -> &str {
+        &self.name
+    }
+
+    fn is_selected(&self) -> bool {
+        self.selected
+    }
+
+    fn set_selected(&mut self, selected: bool) {
+        self.selected = selected;
+    }
 }
 
 pub struct CleanerCategory {
@@ -177,8 +408,35 @@ pub struct App {
     pub operation_start_time: Option<Instant>,
     pub operation_end_time: Option<Instant>,
     pub total_bytes_cleaned: u64,
-    pub show_help: bool,
-    pub result_messages: Vec<String>,
+    /// Part of `total_bytes_cleaned` only freed after a reboot
+    pub total_bytes_deferred: u64,
+    pub help: HelpOverlay,
+    /// Transient notifications shown in the footer
+    pub toasts: Toasts,
+    /// Outcome of the last run, shown on the results screen
+    pub run_results: Vec<String>,
+    /// Where finished runs are recorded and compared, if anywhere
+    pub history: Option<History>,
+    /// Local usage counters, saved with the session state
+    pub usage: UsageStats,
+    /// Whether the About screen with the usage statistics is open
+    pub show_about: bool,
+    /// Runs in quarantine and the bytes they take, read when the About
+    /// screen opens
+    pub quarantine_usage: Option<(usize, u64)>,
+    /// The system information panel above the cleaner list, when open
+    pub system_info: Option<SystemInfo>,
+    /// Usage of the mount points shown under the categories, read at
+    /// startup and after each run
+    pub disks: Vec<(PathBuf, DiskSpace)>,
+    /// A folder to open a shell in, taken by the event loop, which owns
+    /// the terminal
+    pub shell_request: Option<PathBuf>,
+    /// Files modified more recently than this are kept by the next run, set
+    /// by a selection strategy such as [`Recommended`]
+    pub min_age: Option<Duration>,
+    /// Config file exclusions added from the results screen are saved to
+    pub config_path: Option<PathBuf>,
     pub detailed_view: bool,
     pub current_cleaner_index: usize,
     pub animation_frame: usize,
@@ -186,9 +444,13 @@ pub struct App {
     pub terminal_width: u16,
     pub terminal_height: u16,
     pub compact_mode: bool,
+    /// The category sidebar only shows numbers and selected counts
+    pub categories_collapsed: bool,
     pub show_performance_stats: bool,
     pub operation_count: usize,
     pub errors_count: usize,
+    /// Cleaners that removed some paths but failed on others
+    pub partial_count: usize,
     pub paused: bool,
     pub confirmation_mode: bool,
     pub selected_cleaners_count: usize,
@@ -196,18 +458,71 @@ pub struct App {
     pub sort_mode: SortMode,
     pub filter_mode: FilterMode,
     pub detailed_cleaned_items: Vec<DetailedCleanedItem>,
+    /// Bytes freed per cleaner in this run, animated in the size chart
+    pub freed_slices: FreedSlices,
     pub detailed_list_scroll_state: ListState,
+    /// Cleaners whose items are hidden in the removed-items journal
+    pub collapsed_cleaners: HashSet<String>,
     pub search_query: String,
     pub search_active: bool,
     pub detailed_view_filter: String,
-    pub demo_operation_timer: Option<Instant>,
-    pub demo_operations_completed: usize,
+    /// The cleaner running on the worker thread, one at a time
+    running_cleaner: Option<RunningCleaner>,
+    /// How far the running cleaner got deleting a large directory
+    pub deletion_progress: Option<DeleteProgress>,
     pub chart_type: ChartType,
-    pub operation_logs: Vec<String>,
+    pub progress_pane: ProgressPane,
+    /// Share of the progress screen taken by the overview, in percent, once
+    /// set with `+`/`-`; otherwise it depends on the terminal height
+    pub progress_split: Option<u16>,
+    /// Highlighted chart legend entry while the chart pane has focus
+    pub legend_index: usize,
+    /// Chart entry the removed-items list is filtered to
+    pub legend_filter: Option<String>,
+    pub operation_logs: LogBuffer,
     pub show_progress_screen: bool,
     pub password_prompt: PasswordPrompt,
+    pub confirm_dialog: ConfirmDialog,
+    /// Asks before quitting while a run is active
+    pub quit_dialog: ConfirmDialog,
+    /// Asks what to do when sudo authentication fails in the middle of a run
+    pub sudo_dialog: ConfirmDialog,
+    /// Whether the system cleaners left in the run wait for a decision in
+    /// `sudo_dialog`, while the others keep running
+    pub sudo_paused: bool,
+    /// Asks whether to go past the safety limit once a deletion hit it
+    pub limit_dialog: ConfirmDialog,
+    /// Whether the cleaners left in the run wait for a decision in
+    /// `limit_dialog`
+    pub limit_paused: bool,
+    /// Offers `fstrim` after a run freed a lot on SSD mounts
+    pub trim_dialog: ConfirmDialog,
+    /// Asks before putting back the files of the last run
+    pub undo_dialog: ConfirmDialog,
+    /// The quarantine run `undo_dialog` offers to restore
+    undo_run: Option<PathBuf>,
+    /// The mounts `trim_dialog` offers to trim
+    pub trim_candidates: Vec<trim::Candidate>,
+    /// Bulk selection actions for the cleaner list
+    pub action_menu: ActionMenu,
     pub needs_sudo: bool,
+    /// Whether this run hands its system cleaners to the root helper
+    /// instead of sudo, see [`helper`]
+    pub use_helper: bool,
     pub pending_operations: Vec<PendingOperation>,
+    /// Text typed into the schedule prompt while it is open
+    pub schedule_input: Option<String>,
+    /// Deadline and description of a queued cleaning run
+    pub scheduled_run: Option<(Instant, String)>,
+    /// How long each finished background scan took, by cleaner name
+    pub scan_timings: Vec<(String, Duration)>,
+    /// Inotify watcher on the cache directories when live sizes are enabled
+    pub cache_watcher: Option<CacheWatcher>,
+    /// A watched directory changed and finished scans should be refreshed
+    pub live_refresh_pending: bool,
+    pub last_live_refresh: Instant,
+    scan_tx: mpsc::Sender<ScanMessage>,
+    scan_rx: mpsc::Receiver<ScanMessage>,
 }
 
 impl Default for App {
@@ -220,6 +535,7 @@ impl App {
     pub fn new() -> Self {
         // Get initial terminal size
         let (width, height) = terminal::size().unwrap_or((80, 24));
+        let (scan_tx, scan_rx) = mpsc::channel();
 
         let mut app = App {
             categories: Vec::new(),
@@ -230,8 +546,19 @@ impl App {
             operation_start_time: None,
             operation_end_time: None,
             total_bytes_cleaned: 0,
-            show_help: false,
-            result_messages: Vec::new(),
+            total_bytes_deferred: 0,
+            help: HelpOverlay::new(),
+            toasts: Toasts::new(),
+            run_results: Vec::new(),
+            history: None,
+            usage: UsageStats::default(),
+            show_about: false,
+            quarantine_usage: None,
+            system_info: None,
+            disks: Vec::new(),
+            shell_request: None,
+            min_age: None,
+            config_path: Settings::path(),
             detailed_view: false,
             current_cleaner_index: 0,
             animation_frame: 0,
@@ -239,9 +566,11 @@ impl App {
             terminal_width: width,
             terminal_height: height,
             compact_mode: height < 25,
+            categories_collapsed: false,
             show_performance_stats: false,
             operation_count: 0,
             errors_count: 0,
+            partial_count: 0,
             paused: false,
             confirmation_mode: true,
             selected_cleaners_count: 0,
@@ -253,18 +582,54 @@ impl App {
             sort_mode: SortMode::Category,
             filter_mode: FilterMode::All,
             detailed_cleaned_items: Vec::new(),
+            freed_slices: FreedSlices::default(),
             detailed_list_scroll_state: ListState::default(),
+            collapsed_cleaners: HashSet::new(),
             search_query: String::new(),
             search_active: false,
             detailed_view_filter: String::new(),
-            demo_operation_timer: None,
-            demo_operations_completed: 0,
+            running_cleaner: None,
+            deletion_progress: None,
             chart_type: ChartType::PieCount,
-            operation_logs: Vec::new(),
+            progress_pane: ProgressPane::default(),
+            progress_split: None,
+            legend_index: 0,
+            legend_filter: None,
+            operation_logs: LogBuffer::default(),
             show_progress_screen: false,
             password_prompt: PasswordPrompt::new(),
+            confirm_dialog: ConfirmDialog::new(),
+            action_menu: ActionMenu::new(),
+            quit_dialog: ConfirmDialog::new()
+                .with_heading("⚠ A cleaning run is active")
+                .with_hint("Press y to quit | n or ESC to keep cleaning"),
+            sudo_dialog: ConfirmDialog::new()
+                .with_heading("🔒 Sudo authentication failed")
+                .with_hint("r re-authenticate | s skip system cleaners | a or ESC abort"),
+            sudo_paused: false,
+            limit_dialog: ConfirmDialog::new()
+                .with_heading("⛔ This run reached its safety limit")
+                .with_hint("Press y to continue past it | n or ESC to stop the run"),
+            limit_paused: false,
+            undo_dialog: ConfirmDialog::new()
+                .with_heading("↩️ Undo the last run")
+                .with_hint("Press y to restore the files | n or ESC to cancel"),
+            undo_run: None,
+            trim_dialog: ConfirmDialog::new()
+                .with_heading("✂ Large deletions on SSD")
+                .with_hint("Press y to run fstrim | n or ESC to skip"),
+            trim_candidates: Vec::new(),
             needs_sudo: false,
+            use_helper: false,
             pending_operations: Vec::new(),
+            schedule_input: None,
+            scheduled_run: None,
+            scan_timings: Vec::new(),
+            cache_watcher: None,
+            live_refresh_pending: false,
+            last_live_refresh: Instant::now(),
+            scan_tx,
+            scan_rx,
         };
         app.item_list_state.select(Some(0));
 
@@ -274,6 +639,99 @@ impl App {
         app
     }
 
+    /// Add a message to the operation log, cleaned up for display and
+    /// split into several lines if it is long
+    pub fn log(&mut self, entry: impl AsRef<str>) {
+        self.operation_logs
+            .extend(sanitize::log_lines(entry.as_ref()));
+    }
+
+    /// Authenticate through `SUDO_ASKPASS` when it is in use. Returns false
+    /// when the password prompt should be shown instead.
+    fn authenticate_with_askpass(&mut self) -> bool {
+        if !askpass_enabled() {
+            return false;
+        }
+        match self.password_prompt.authenticate_with_askpass() {
+            Ok(true) => {
+                self.log("🔓 Authenticated through SUDO_ASKPASS");
+                true
+            }
+            Ok(false) => {
+                self.log("❌ SUDO_ASKPASS authentication failed");
+                false
+            }
+            Err(e) => {
+                self.log(format!("❌ Authentication error: {}", e));
+                false
+            }
+        }
+    }
+
+    /// Capture the parts of the UI that are restored on the next run
+    pub fn session_state(&self) -> SessionState {
+        SessionState {
+            selected: self
+                .categories
+                .iter()
+                .flat_map(|c| {
+                    c.items
+                        .iter()
+                        .filter(|i| i.selected)
+                        .map(|i| SelectedCleaner {
+                            category: c.name.clone(),
+                            name: i.name.clone(),
+                        })
+                })
+                .collect(),
+            view_mode: Some(self.view_mode.clone()),
+            chart_type: Some(self.chart_type.clone()),
+            progress_split: self.progress_split,
+            category_index: self.category_index,
+            item_index: self.selected_item_index(),
+            detailed_scroll: self.detailed_list_scroll_state.selected(),
+            usage: self.usage.clone(),
+        }
+    }
+
+    /// Restore a previous session. Cleaners that no longer exist and
+    /// out-of-range positions are ignored.
+    pub fn apply_session_state(&mut self, state: &SessionState) {
+        for category in &mut self.categories {
+            for item in &mut category.items {
+                item.selected = state
+                    .selected
+                    .iter()
+                    .any(|s| s.category == category.name && s.name == item.name);
+            }
+        }
+
+        if let Some(view_mode) = &state.view_mode {
+            self.view_mode = view_mode.clone();
+            self.detailed_view = self.view_mode == ViewMode::Detailed;
+            self.compact_mode = self.view_mode == ViewMode::Compact;
+        }
+        if let Some(chart_type) = &state.chart_type {
+            self.chart_type = chart_type.clone();
+        }
+        self.progress_split = state
+            .progress_split
+            .map(|split| split.clamp(PROGRESS_SPLIT_MIN, PROGRESS_SPLIT_MAX));
+
+        if state.category_index < self.categories.len() {
+            self.category_index = state.category_index;
+            let len = self.categories[self.category_index].items.len();
+            if let Some(item) = state.item_index.filter(|i| *i < len) {
+                self.select_item_index(Some(item));
+            }
+        }
+        self.detailed_list_scroll_state
+            .select(state.detailed_scroll);
+        self.usage = state.usage.clone();
+
+        self.update_counters();
+    }
+
     pub fn toggle_search(&mut self) {
         self.search_active = !self.search_active;
         if !self.search_active {
@@ -299,22 +757,21 @@ impl App {
         }
     }
 
+    /// Removed items and bytes freed per cleaner, largest first. Counts
+    /// come from the removed items, sizes from what each cleaner reported
+    /// as it finished, as currently shown by the chart animation.
     pub fn get_category_distribution(&self) -> Vec<(String, usize, u64)> {
         let mut category_map: std::collections::HashMap<String, (usize, u64)> =
             std::collections::HashMap::new();
 
         for item in &self.detailed_cleaned_items {
-            // Create a unique key that combines cleaner name with category type
-            // This differentiates between user and system cleaners with the same name
-            let display_name = if item.category.contains("System") {
-                format!("{} (System)", item.cleaner_name)
-            } else {
-                item.cleaner_name.clone()
-            };
-
-            let entry = category_map.entry(display_name).or_insert((0, 0));
+            let entry = category_map
+                .entry(chart_name(&item.category, &item.cleaner_name))
+                .or_insert((0, 0));
             entry.0 += 1;
-            entry.1 += item.size;
+        }
+        for (name, shown) in self.freed_slices.shown() {
+            category_map.entry(name).or_insert((0, 0)).1 = shown;
         }
 
         let mut categories: Vec<(String, usize, u64)> = category_map
@@ -322,15 +779,120 @@ impl App {
             .map(|(name, (count, size))| (name, count, size))
             .collect();
 
-        categories.sort_by(|a, b| b.2.cmp(&a.2)); // Sort by size descending
+        // By size descending, then name so equal slices don't swap places
+        categories.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
         categories
     }
 
-    pub fn next_item(&mut self) {
+    /// The entries the current chart shows: the bar chart only fits the
+    /// six largest
+    pub fn chart_entries(&self) -> Vec<(String, usize, u64)> {
+        let mut entries = self.get_category_distribution();
+        if self.chart_type == ChartType::Bar {
+            entries.truncate(6);
+        }
+        entries
+    }
+
+    /// Move focus between the removed-items list and the chart legend
+    pub fn cycle_progress_pane(&mut self) {
+        self.progress_pane = match self.progress_pane {
+            ProgressPane::RemovedItems => ProgressPane::Chart,
+            ProgressPane::Chart => ProgressPane::RemovedItems,
+        };
+        let len = self.chart_entries().len();
+        self.legend_index = self.legend_index.min(len.saturating_sub(1));
+    }
+
+    /// The highlighted legend entry, while the chart pane has focus
+    pub fn highlighted_legend(&self) -> Option<usize> {
+        (self.progress_pane == ProgressPane::Chart).then_some(self.legend_index)
+    }
+
+    pub fn next_legend_entry(&mut self) {
+        let len = self.chart_entries().len();
+        if len > 0 {
+            self.legend_index = (self.legend_index + 1) % len;
+        }
+    }
+
+    pub fn previous_legend_entry(&mut self) {
+        let len = self.chart_entries().len();
+        if len > 0 {
+            self.legend_index = (self.legend_index + len - 1) % len;
+        }
+    }
+
+    /// Show only the removed items of the highlighted legend entry, or all
+    /// of them again if the list is already filtered to it
+    pub fn toggle_legend_filter(&mut self) {
+        let Some((name, _, _)) = self.chart_entries().into_iter().nth(self.legend_index) else {
+            return;
+        };
+        self.legend_filter = if self.legend_filter.as_ref() == Some(&name) {
+            None
+        } else {
+            Some(name)
+        };
+        self.detailed_list_scroll_state.select(Some(0));
+    }
+
+    /// Indices into the current category's items that pass the filter, in
+    /// the order of the active sort mode
+    pub fn visible_items(&self) -> Vec<usize> {
         let items = &self.categories[self.category_index].items;
+        let mut visible: Vec<usize> = (0..items.len())
+            .filter(|&i| self.filter_mode.matches(&items[i]))
+            .collect();
+
+        // Stable sorts, so ties keep the registration order
+        match self.sort_mode {
+            SortMode::Name => visible.sort_by_key(|&i| items[i].name.to_lowercase()),
+            // Largest first, cleaners without an estimate last
+            SortMode::Size => {
+                visible.sort_by_key(|&i| std::cmp::Reverse(items[i].estimated_size()))
+            }
+            SortMode::Status => visible.sort_by_key(|&i| items[i].status_rank()),
+            SortMode::Category => {}
+        }
+        visible
+    }
+
+    /// Index into the current category's items of the highlighted cleaner
+    pub fn selected_item_index(&self) -> Option<usize> {
+        let position = self.item_list_state.selected()?;
+        self.visible_items().get(position).copied()
+    }
+
+    /// Highlight a cleaner by its item index, falling back to the first
+    /// visible one if it is filtered out
+    fn select_item_index(&mut self, item: Option<usize>) {
+        let visible = self.visible_items();
+        let position = item
+            .and_then(|item| visible.iter().position(|&i| i == item))
+            .or(if visible.is_empty() { None } else { Some(0) });
+        self.item_list_state.select(position);
+    }
+
+    /// Keep the highlight inside the visible list after it changed underneath
+    pub fn clamp_item_selection(&mut self) {
+        let len = self.visible_items().len();
+        match self.item_list_state.selected() {
+            _ if len == 0 => self.item_list_state.select(None),
+            Some(i) if i >= len => self.item_list_state.select(Some(len - 1)),
+            None => self.item_list_state.select(Some(0)),
+            _ => {}
+        }
+    }
+
+    pub fn next_item(&mut self) {
+        let len = self.visible_items().len();
+        if len == 0 {
+            return;
+        }
         let i = match self.item_list_state.selected() {
             Some(i) => {
-                if i >= items.len() - 1 {
+                if i >= len - 1 {
                     0
                 } else {
                     i + 1
@@ -342,11 +904,14 @@ impl App {
     }
 
     pub fn previous_item(&mut self) {
-        let items = &self.categories[self.category_index].items;
+        let len = self.visible_items().len();
+        if len == 0 {
+            return;
+        }
         let i = match self.item_list_state.selected() {
             Some(i) => {
                 if i == 0 {
-                    items.len() - 1
+                    len - 1
                 } else {
                     i - 1
                 }
@@ -357,7 +922,7 @@ impl App {
     }
 
     pub fn toggle_selected(&mut self) {
-        if let Some(i) = self.item_list_state.selected() {
+        if let Some(i) = self.selected_item_index() {
             let item = &mut self.categories[self.category_index].items[i];
             // Allow selection even for root items, will prompt for password later
             item.selected = !item.selected;
@@ -384,8 +949,72 @@ impl App {
         self.item_list_state.select(Some(0));
     }
 
+    /// How many cleaners are selected in the category at `index`, and the
+    /// space they are estimated to free
+    pub fn category_selection(&self, index: usize) -> (usize, u64) {
+        self.categories
+            .get(index)
+            .map(|category| {
+                category
+                    .items
+                    .iter()
+                    .filter(|item| item.selected)
+                    .fold((0, 0), |(count, bytes), item| {
+                        (count + 1, bytes + item.estimated_size().unwrap_or(0))
+                    })
+            })
+            .unwrap_or_default()
+    }
+
+    /// Read the usage of the mount points shown on the main screen again
+    pub fn refresh_disks(&mut self) {
+        self.disks = get_disk_spaces(&DISK_MOUNTS);
+    }
+
+    /// Open the system information panel, reading the system anew, or
+    /// close it
+    pub fn toggle_system_info(&mut self) {
+        self.system_info = match self.system_info {
+            Some(_) => None,
+            None => Some(SystemInfo::gather()),
+        };
+    }
+
+    pub fn toggle_categories_collapsed(&mut self) {
+        self.categories_collapsed = !self.categories_collapsed;
+    }
+
+    /// Show the category at `index`, if there is one
+    pub fn jump_to_category(&mut self, index: usize) {
+        if index < self.categories.len() && index != self.category_index {
+            self.category_index = index;
+            self.item_list_state.select(Some(0));
+        }
+    }
+
     pub fn toggle_help(&mut self) {
-        self.show_help = !self.show_help;
+        self.help.toggle();
+    }
+
+    /// The screen the help overlay describes
+    pub fn help_context(&self) -> HelpContext {
+        if self.confirm_dialog.is_visible()
+            || self.quit_dialog.is_visible()
+            || self.sudo_dialog.is_visible()
+            || self.limit_dialog.is_visible()
+            || self.trim_dialog.is_visible()
+            || self.undo_dialog.is_visible()
+            || self.password_prompt.is_visible()
+            || self.schedule_input.is_some()
+        {
+            HelpContext::Review
+        } else if self.is_running {
+            HelpContext::Progress
+        } else if self.show_progress_screen {
+            HelpContext::Results
+        } else {
+            HelpContext::Selection
+        }
     }
 
     pub fn select_all(&mut self) {
@@ -401,6 +1030,76 @@ impl App {
         }
     }
 
+    /// Replace the selection in every category with the cleaners `strategy`
+    /// picks, judged by their scan estimates
+    pub fn apply_strategy(&mut self, strategy: &dyn SelectionStrategy) {
+        if self.is_running || self.show_progress_screen {
+            return;
+        }
+        let mut selected = 0;
+        for item in self.categories.iter_mut().flat_map(|c| &mut c.items) {
+            let estimate = match &item.scan_state {
+                Some(ScanState::Done(report)) => Some(report.total_reclaimable()),
+                _ => None,
+            };
+            item.selected = strategy.selects(&Candidate {
+                name: &item.name,
+                risk: item.risk,
+                estimate,
+            });
+            selected += usize::from(item.selected);
+        }
+        self.min_age = strategy.min_age();
+        let kept = match self.min_age {
+            Some(age) => format!(
+                "; files changed in the last {} days are kept",
+                age.as_secs() / (24 * 60 * 60)
+            ),
+            None => String::new(),
+        };
+        self.toasts.push(format!(
+            "{}: {} cleaner(s) selected{}",
+            strategy.name(),
+            selected,
+            kept
+        ));
+        self.update_counters();
+    }
+
+    /// Select the curated safe set
+    pub fn select_recommended(&mut self) {
+        self.apply_strategy(&Recommended);
+    }
+
+    /// Open the bulk action menu for the cleaner list. Cleaners aren't
+    /// files, so only the search and invert actions apply.
+    pub fn open_action_menu(&mut self) {
+        if !self.is_running && !self.show_progress_screen {
+            self.action_menu
+                .show(&[BulkActionKind::SelectMatching, BulkActionKind::Invert]);
+        }
+    }
+
+    /// Apply the bulk action picked in the action menu to the cleaners the
+    /// filter shows
+    fn submit_action_menu(&mut self) {
+        if let Some(action) = self.action_menu.submit() {
+            let visible = self.visible_items();
+            let changed = action.apply(
+                self.categories[self.category_index]
+                    .items
+                    .iter_mut()
+                    .enumerate()
+                    .filter(|(i, _)| visible.contains(i))
+                    .map(|(_, item)| item),
+                SystemTime::now(),
+            );
+            self.toasts
+                .push(format!("Selection changed for {} cleaner(s)", changed));
+            self.update_counters();
+        }
+    }
+
     pub fn run_selected(&mut self) -> Result<()> {
         if self.is_running {
             return Ok(());
@@ -422,10 +1121,52 @@ impl App {
         }
 
         if !has_selected {
-            self.result_messages
-                .push("No items selected. Please select items to clean.".to_string());
+            self.toasts
+                .push("No items selected. Please select items to clean.");
+            return Ok(());
+        }
+
+        // Risky cleaners need an explicit confirmation before anything starts
+        let risky: Vec<(String, Option<u64>)> = self
+            .categories
+            .iter()
+            .flat_map(|c| &c.items)
+            .filter(|item| item.selected && item.risk == RiskLevel::High)
+            .map(|item| {
+                (
+                    format!("• {}: {}", item.name, item.description),
+                    item.estimated_size(),
+                )
+            })
+            .collect();
+
+        if !risky.is_empty() {
+            self.confirm_dialog
+                .show_with_sizes("Confirm Risky Cleaners", risky);
+            return Ok(());
+        }
+
+        self.start_selected()
+    }
+
+    /// Accept the risk confirmation dialog and start the selected cleaners
+    pub fn confirm_risky_run(&mut self) -> Result<()> {
+        self.confirm_dialog.hide();
+        self.start_selected()
+    }
+
+    fn start_selected(&mut self) -> Result<()> {
+        // A cancelled cleaner finishes its current file before it stops
+        if self.running_cleaner.is_some() {
+            self.toasts
+                .push("The cancelled cleaner is still stopping, try again in a moment.");
             return Ok(());
         }
+        limits::reset_run();
+        cancel::reset();
+        protection::take_skipped();
+        self.sudo_paused = false;
+        self.limit_paused = false;
 
         // Prepare the selected cleaners
         let mut selected_cleaners = Vec::new();
@@ -446,13 +1187,22 @@ impl App {
         }
 
         if selected_cleaners.is_empty() {
-            self.operation_logs
-                .push("No cleaners selected. Please select at least one cleaner.".to_string());
+            self.log("No cleaners selected. Please select at least one cleaner.");
             return Ok(());
         }
 
+        // The root helper runs system cleaners without sudo when installed
+        self.use_helper = has_root_operations && !self.is_root && helper::available();
+        if self.use_helper {
+            self.log("🔑 System cleaners run through the root helper");
+        }
+
         // Check if we need sudo and prompt for password
-        if has_root_operations && !self.is_root {
+        if has_root_operations
+            && !self.is_root
+            && !self.use_helper
+            && !self.authenticate_with_askpass()
+        {
             self.needs_sudo = true;
             self.password_prompt.show();
             // Store the selected cleaners for later execution after authentication
@@ -466,17 +1216,22 @@ impl App {
         self.operation_start_time = Some(Instant::now());
         self.operation_end_time = None;
         self.total_bytes_cleaned = 0;
-        self.demo_operation_timer = Some(Instant::now());
-        self.demo_operations_completed = 0;
-        self.result_messages.clear();
+        self.total_bytes_deferred = 0;
+        self.deletion_progress = None;
+        self.run_results.clear();
         self.operation_logs.clear();
         self.detailed_cleaned_items.clear(); // Clear previous cleaning results
+        self.freed_slices.clear();
+        self.legend_filter = None;
+        self.collapsed_cleaners.clear();
         self.current_cleaner_index = 0;
 
         // Reset bytes_cleaned for all items to start fresh
         for category in &mut self.categories {
             for item in &mut category.items {
                 item.bytes_cleaned = 0;
+                item.bytes_deferred = 0;
+                item.permission_denied = 0;
                 item.status = None;
             }
         }
@@ -486,22 +1241,8 @@ impl App {
             self.categories[*cat_idx].items[*item_idx].status = Some(Status::Pending);
         }
 
-        // Clone necessary data for the thread
-        let (_tx, _rx) = mpsc::channel::<(usize, usize, Status)>();
-
-        // Actual thread processing will be implemented in a future version
-        // For demo purposes, we'll simulate async operations
-        // Set all selected operations to pending first, then they'll progress over time
-        if !selected_cleaners.is_empty() {
-            // Set operations to pending initially - they'll be processed by update_demo_operations
-            for (cat_idx, item_idx, _, _, _) in &selected_cleaners {
-                self.categories[*cat_idx].items[*item_idx].status = Some(Status::Pending);
-            }
-        }
-
-        // Operations will be processed by update_demo_operations over time
-        // The is_running flag will be automatically turned off when all operations complete
-
+        // Each tick starts the next one on the worker thread once the last
+        // finished; the run ends when none are left, see update_counters
         Ok(())
     }
 
@@ -512,551 +1253,1041 @@ impl App {
             self.last_frame_time = now;
         }
 
-        // Update demo operations if running
-        if self.is_running {
-            self.update_demo_operations();
-        }
-    }
-
-    pub fn update_demo_operations(&mut self) {
-        if let Some(start_time) = self.demo_operation_timer {
-            let elapsed = start_time.elapsed().as_millis();
-
-            // Find next pending operation to start
-            type Operation = (usize, usize, String, fn(bool) -> anyhow::Result<u64>, bool);
-            let mut pending_operations: Vec<Operation> = Vec::new();
-            for (cat_idx, category) in self.categories.iter().enumerate() {
-                for (item_idx, item) in category.items.iter().enumerate() {
-                    if matches!(item.status, Some(Status::Pending)) {
-                        pending_operations.push((
-                            cat_idx,
-                            item_idx,
-                            item.name.to_string(),
-                            item.function,
-                            item.requires_root,
-                        ));
-                    }
-                }
-            }
-
-            // Start next operation every 1.5 seconds
-            let operations_to_start = (elapsed / 1500) as usize;
-            if operations_to_start > self.demo_operations_completed
-                && !pending_operations.is_empty()
-            {
-                if let Some((cat_idx, item_idx, _name, _function, _requires_root)) =
-                    pending_operations.first()
-                {
-                    // Set to running
-                    self.categories[*cat_idx].items[*item_idx].status = Some(Status::Running);
-                    self.demo_operations_completed += 1;
-                }
-            }
+        if self.is_running || self.running_cleaner.is_some() {
+            self.update_operations();
+        }
 
-            // Complete running operations after 2 seconds
-            let mut running_operations: Vec<Operation> = Vec::new();
-            for (cat_idx, category) in self.categories.iter().enumerate() {
-                for (item_idx, item) in category.items.iter().enumerate() {
-                    if matches!(item.status, Some(Status::Running)) {
-                        running_operations.push((
-                            cat_idx,
-                            item_idx,
-                            item.name.to_string(),
-                            item.function,
-                            item.requires_root,
-                        ));
-                    }
-                }
-            }
+        self.freed_slices.tick();
+        self.toasts.tick();
+        self.poll_scan_results();
+        self.refresh_live_sizes();
 
-            // Complete operations that have been running for at least 2 seconds
-            for (cat_idx, item_idx, name, function, requires_root) in running_operations {
-                self.operation_logs.push(format!("Starting: {}", name));
+        if let Err(e) = self.check_scheduled_run() {
+            self.toasts
+                .push(format!("❌ Scheduled run failed to start: {}", e));
+        }
+    }
 
-                // Check if operation requires root and we don't have it
-                let result: anyhow::Result<u64> = if requires_root
-                    && !self.is_root
-                    && !self.password_prompt.is_authenticated()
-                {
-                    // Show password prompt and pause operations
-                    self.needs_sudo = true;
-                    self.password_prompt.show();
-                    self.is_running = false;
-                    self.operation_logs
-                        .push(format!("🔒 {}: Waiting for sudo authentication...", name));
-                    // Return error to mark this operation as pending
-                    Err(anyhow::anyhow!("Waiting for sudo authentication"))
-                } else {
-                    self.operation_logs.push(format!("🔄 Executing: {}", name));
-
-                    // Capture output during execution
-                    let captured_result = capture_output(|| function(true));
-
-                    let result = match captured_result {
-                        Ok((bytes, output)) => {
-                            self.operation_logs
-                                .push(format!("✅ {}: Cleaned {} bytes", name, bytes));
-
-                            // Parse output for cleaned files and add to detailed items
-                            let category_name = self.categories[cat_idx].name.clone();
-                            let items_before = self.detailed_cleaned_items.len();
-
-                            for line in output.lines() {
-                                // Look for lines indicating files were removed
-                                if line.contains("Removed")
-                                    || line.contains("cleaned")
-                                    || line.contains("Cleaning")
-                                    || line.contains("freed")
-                                {
-                                    // Try to extract file path
-                                    if let Some(path_start) = line.find("/") {
-                                        let path_end = line[path_start..]
-                                            .find(|c: char| {
-                                                c == '"' || c == '\'' || c.is_whitespace()
-                                            })
-                                            .map(|i| path_start + i)
-                                            .unwrap_or(line.len());
-                                        let path = line[path_start..path_end].trim().to_string();
-
-                                        if !path.is_empty() && path.len() > 1 {
-                                            // Extract size if present using pre-compiled regex
-                                            let extracted_size = if let Some(cap) =
-                                                SIZE_REGEX.captures(line)
-                                            {
-                                                let num: f64 = cap
-                                                    .get(1)
-                                                    .and_then(|m| m.as_str().parse().ok())
-                                                    .unwrap_or(0.0);
-                                                let unit = cap
-                                                    .get(2)
-                                                    .map(|m| m.as_str())
-                                                    .unwrap_or("bytes");
-                                                match unit {
-                                                    "KB" => (num * 1024.0) as u64,
-                                                    "MB" => (num * 1024.0 * 1024.0) as u64,
-                                                    "GB" => (num * 1024.0 * 1024.0 * 1024.0) as u64,
-                                                    _ => num as u64,
-                                                }
-                                            } else {
-                                                bytes / 10 // Estimate
-                                            };
-
-                                            let item_type = if path.ends_with('/')
-                                                || line.contains("directory")
-                                            {
-                                                CleanedItemType::Directory
-                                            } else {
-                                                CleanedItemType::File
-                                            };
-
-                                            self.add_detailed_cleaned_item(
-                                                path,
-                                                extracted_size,
-                                                category_name.clone(),
-                                                name.clone(),
-                                                item_type,
-                                            );
-                                        }
-                                    }
-
-                                    // Also add to operation logs for visibility
-                                    if !line.trim().is_empty() {
-                                        self.operation_logs.push(format!("  → {}", line.trim()));
-                                    }
-                                }
-                            }
-
-                            // Fallback: If no detailed items were captured from this cleaner's output, create a summary item
-                            let items_after = self.detailed_cleaned_items.len();
-                            if items_after == items_before && bytes > 0 {
-                                // No items were parsed from output, create a summary item for this cleaner
-                                self.add_detailed_cleaned_item(
-                                    format!("{} (cleaned files)", name),
-                                    bytes,
-                                    category_name,
-                                    name.clone(),
-                                    CleanedItemType::Directory,
-                                );
-                            }
-
-                            Ok(bytes)
-                        }
-                        Err(e) => {
-                            self.operation_logs.push(format!("❌ {}: {}", name, e));
-                            Err(e)
-                        }
-                    };
+    /// Open the schedule prompt, or cancel the queued run if there is one
+    pub fn toggle_schedule(&mut self) {
+        if self.scheduled_run.take().is_some() {
+            self.toasts.push("Scheduled run cancelled.");
+        } else {
+            self.schedule_input = Some(String::new());
+        }
+    }
 
-                    result
-                };
+    /// Parse the schedule prompt and queue the run
+    pub fn submit_schedule(&mut self) {
+        let Some(input) = self.schedule_input.take() else {
+            return;
+        };
 
-                // Process result
-                match result {
-                    Ok(bytes) => {
-                        let msg = if requires_root {
-                            format!("Cleaned {} (root) ({})", name, format_size(bytes))
-                        } else {
-                            format!("Cleaned {} ({})", name, format_size(bytes))
-                        };
-                        self.categories[cat_idx].items[item_idx].status =
-                            Some(Status::Success(msg));
-                        self.categories[cat_idx].items[item_idx].bytes_cleaned = bytes;
-                        self.total_bytes_cleaned += bytes;
-                        self.operation_logs.push(format!(
-                            "✅ Completed {}: {} freed",
-                            name,
-                            format_size(bytes)
-                        ));
-                    }
-                    Err(e) => {
-                        let error_msg = if requires_root && !self.is_root {
-                            "Requires sudo - restart with 'sudo cleansys'".to_string()
-                        } else {
-                            format!(
-                                "Failed: {}",
-                                e.to_string()
-                                    .split(':')
-                                    .next_back()
-                                    .unwrap_or("Unknown error")
-                                    .trim()
-                            )
-                        };
-                        self.categories[cat_idx].items[item_idx].status =
-                            Some(Status::Error(error_msg.clone()));
-                        self.operation_logs
-                            .push(format!("❌ Failed {}: {}", name, error_msg));
-
-                        // Add helpful message for sudo requirement
-                        if requires_root
-                            && !self.is_root
-                            && !self
-                                .result_messages
-                                .iter()
-                                .any(|msg| msg.contains("sudo cleansys"))
-                        {
-                            self.result_messages.push(
-                                "💡 System cleaners require root privileges. Run 'sudo cleansys' to clean system files.".to_string()
-                            );
-                        }
-                    }
-                }
+        let scheduled = Schedule::parse(&input).and_then(|schedule| {
+            let delay = schedule.delay_from(local_seconds_since_midnight());
+            Some((schedule, Instant::now().checked_add(delay)?))
+        });
+        match scheduled {
+            Some((schedule, deadline)) => {
+                let description = schedule.describe();
+                self.toasts
+                    .push(format!("⏰ Selected cleaners will run {}", description));
+                self.scheduled_run = Some((deadline, description));
+            }
+            None => {
+                self.toasts.push(format!(
+                    "Invalid schedule '{}'. Use a time like 02:00 or a delay like 30m.",
+                    input
+                ));
             }
         }
     }
 
-    pub fn cancel_sudo_operations(&mut self) {
-        // Mark all operations as cancelled
-        for category in &mut self.categories {
-            for item in &mut category.items {
-                if item.selected && matches!(item.status, Some(Status::Running | Status::Pending)) {
-                    item.status = Some(Status::Error("Operation cancelled by user".to_string()));
-                    item.selected = false; // Deselect the item
-                }
+    /// Time left before the queued run starts
+    pub fn scheduled_countdown(&self) -> Option<std::time::Duration> {
+        self.scheduled_run
+            .as_ref()
+            .map(|(deadline, _)| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Start the queued run once its deadline has passed
+    pub fn check_scheduled_run(&mut self) -> Result<()> {
+        match &self.scheduled_run {
+            Some((deadline, _)) if Instant::now() >= *deadline && !self.is_running => {
+                self.scheduled_run = None;
+                self.purge_quarantine();
+                self.run_selected()
             }
+            _ => Ok(()),
         }
-
-        self.result_messages
-            .push("Cleaning operations cancelled by user.".to_string());
     }
 
-    pub fn handle_key(&mut self, key: KeyEvent) -> Result<bool> {
-        // If password prompt is visible, handle password input first
-        if self.password_prompt.is_visible() {
-            match key.code {
-                KeyCode::Enter => {
-                    // Submit password and authenticate
-                    match self.password_prompt.submit() {
-                        Ok(true) => {
-                            // Authentication successful, proceed with operations
-                            self.needs_sudo = false;
-                            self.password_prompt.hide();
-
-                            // Now start the actual cleaning operations
-                            let selected_cleaners = self.pending_operations.clone();
-                            self.pending_operations.clear();
-
-                            if !selected_cleaners.is_empty() {
-                                // Start processing
-                                self.is_running = true;
-                                self.show_progress_screen = true;
-                                self.operation_start_time = Some(Instant::now());
-                                self.operation_end_time = None;
-                                self.total_bytes_cleaned = 0;
-                                self.demo_operation_timer = Some(Instant::now());
-                                self.demo_operations_completed = 0;
-                                self.result_messages.clear();
-                                self.operation_logs.clear();
-                                self.detailed_cleaned_items.clear();
-                                self.current_cleaner_index = 0;
-
-                                // Reset bytes_cleaned for all items to start fresh
-                                for category in &mut self.categories {
-                                    for item in &mut category.items {
-                                        item.bytes_cleaned = 0;
-                                    }
-                                }
-
-                                // Set all selected cleaners to Pending
-                                for (cat_idx, item_idx, _, _, _) in &selected_cleaners {
-                                    self.categories[*cat_idx].items[*item_idx].status =
-                                        Some(Status::Pending);
-                                }
-
-                                self.update_counters();
-                            }
-                        }
-                        Ok(false) => {
-                            // Authentication failed, stay on prompt
-                        }
-                        Err(e) => {
-                            self.operation_logs
-                                .push(format!("❌ Authentication error: {}", e));
-                            self.password_prompt.hide();
-                            self.needs_sudo = false;
-                            self.pending_operations.clear();
-                        }
-                    }
-                }
-                KeyCode::Esc => {
-                    // Cancel password prompt
-                    self.password_prompt.cancel();
-                    self.needs_sudo = false;
-                    self.pending_operations.clear();
-                }
-                KeyCode::Char(c) => {
-                    self.password_prompt.add_char(c);
+    /// Purge the quarantine runs the configured retention no longer keeps
+    fn purge_quarantine(&mut self) {
+        match quarantine::enforce_retention() {
+            Ok(purge) => {
+                if !purge.purged.is_empty() {
+                    self.log(format!(
+                        "🗑️ Purged {} old quarantine runs, freeing {}",
+                        purge.purged.len(),
+                        format_size(purge.bytes_freed)
+                    ));
                 }
-                KeyCode::Backspace => {
-                    self.password_prompt.remove_char();
+                for (run, error) in &purge.failed {
+                    self.toasts
+                        .push(format!("Could not purge {}: {}", run.display(), error));
                 }
-                _ => {}
             }
-            return Ok(false);
+            Err(e) => self
+                .toasts
+                .push(format!("Could not purge quarantine: {:#}", e)),
         }
+    }
 
-        match (key.code, key.modifiers) {
-            // Quit
-            (KeyCode::Char('q'), _) => {
-                if self.show_help {
-                    self.show_help = false;
-                } else if self.is_running {
-                    // Cancel current cleaning operations
-                    self.is_running = false;
-                    self.cancel_sudo_operations();
-                } else {
-                    return Ok(true);
-                }
-            }
+    /// Start a background scan for the highlighted cleaner if it supports one
+    /// and hasn't been scanned yet.
+    pub fn ensure_selected_scan(&mut self) {
+        let Some(item_idx) = self.selected_item_index() else {
+            return;
+        };
+        let cat_idx = self.category_index;
+        let Some(item) = self
+            .categories
+            .get_mut(cat_idx)
+            .and_then(|c| c.items.get_mut(item_idx))
+        else {
+            return;
+        };
 
-            // Navigation
-            (KeyCode::Down, _) => {
-                if !self.show_help {
-                    if self.is_running || self.show_progress_screen {
-                        self.scroll_detailed_list_down();
-                    } else {
-                        self.next_item();
-                    }
+        if let (Some(scan), None) = (item.scan, &item.scan_state) {
+            item.scan_state = Some(ScanState::Scanning);
+            self.start_scan(cat_idx, item_idx, scan);
+        }
+    }
+
+    fn start_scan(&self, cat_idx: usize, item_idx: usize, scan: fn() -> Result<ScanReport>) {
+        let tx = self.scan_tx.clone();
+        std::thread::spawn(move || {
+            let start = Instant::now();
+            scanner::cancellable(|| {
+                let result = scan();
+                // What a cancelled scan counted is only part of the size
+                if !scanner::is_cancelled() {
+                    let _ = tx.send((cat_idx, item_idx, result, start.elapsed()));
                 }
+            });
+        });
+    }
+
+    /// Stop the background scans still running, keeping the ones that have
+    /// finished. The cleaners whose scans were stopped show their size as
+    /// unknown, and can still be selected and run.
+    pub fn cancel_scans(&mut self) {
+        scanner::cancel_scans();
+        let mut stopped = 0;
+        for item in self.categories.iter_mut().flat_map(|c| &mut c.items) {
+            if matches!(item.scan_state, Some(ScanState::Scanning)) {
+                item.scan_state = Some(ScanState::Cancelled);
+                stopped += 1;
             }
-            (KeyCode::Up, _) => {
-                if !self.show_help {
-                    if self.is_running || self.show_progress_screen {
-                        self.scroll_detailed_list_up();
-                    } else {
-                        self.previous_item();
-                    }
+        }
+        if stopped > 0 {
+            self.toasts.push(format!(
+                "Stopped {} scan(s), their sizes are unknown",
+                stopped
+            ));
+        }
+    }
+
+    /// Watch the cache directories and refresh size estimates as they change
+    pub fn enable_live_sizes(&mut self) -> Result<()> {
+        let watcher = CacheWatcher::start(&watch_dirs())?;
+        self.toasts.push(format!(
+            "👀 Watching {} cache directories for live size updates",
+            watcher.watched()
+        ));
+        self.cache_watcher = Some(watcher);
+        Ok(())
+    }
+
+    /// Rescan already scanned cleaners after a watched directory changed, at
+    /// most once per [`LIVE_REFRESH_INTERVAL`]. The previous estimate stays on
+    /// screen until the new one arrives.
+    pub fn refresh_live_sizes(&mut self) {
+        let Some(watcher) = &self.cache_watcher else {
+            return;
+        };
+        if watcher.take_changed() {
+            self.live_refresh_pending = true;
+        }
+        if !self.live_refresh_pending
+            || self.is_running
+            || self.last_live_refresh.elapsed() < LIVE_REFRESH_INTERVAL
+        {
+            return;
+        }
+
+        self.live_refresh_pending = false;
+        self.last_live_refresh = Instant::now();
+
+        let mut rescans = Vec::new();
+        for (cat_idx, category) in self.categories.iter().enumerate() {
+            for (item_idx, item) in category.items.iter().enumerate() {
+                if let (Some(scan), Some(ScanState::Done(_))) = (item.scan, &item.scan_state) {
+                    rescans.push((cat_idx, item_idx, scan));
                 }
             }
-            (KeyCode::Tab, _) => {
-                if !self.show_help {
-                    self.next_category();
-                }
+        }
+        for (cat_idx, item_idx, scan) in rescans {
+            self.start_scan(cat_idx, item_idx, scan);
+        }
+    }
+
+    /// Collect results from finished background scans
+    pub fn poll_scan_results(&mut self) {
+        while let Ok((cat_idx, item_idx, result, elapsed)) = self.scan_rx.try_recv() {
+            if let Some(item) = self
+                .categories
+                .get_mut(cat_idx)
+                .and_then(|c| c.items.get_mut(item_idx))
+            {
+                self.scan_timings.push((item.name.clone(), elapsed));
+                item.scan_state = Some(match result {
+                    Ok(report) => ScanState::Done(report),
+                    Err(e) => ScanState::Failed(e.to_string()),
+                });
             }
-            (KeyCode::BackTab, _) => {
-                if !self.show_help {
-                    self.previous_category();
+        }
+    }
+
+    /// Take in what the worker running a cleaner sent, and start the next
+    /// pending cleaner once none is running
+    pub fn update_operations(&mut self) {
+        let mut finished = None;
+        if let Some(running) = &mut self.running_cleaner {
+            let slice = chart_name(&self.categories[running.cat_idx].name, &running.name);
+            loop {
+                match running.rx.try_recv() {
+                    Ok(WorkerMessage::Progress(progress)) => {
+                        // The chart grows as the files go, not once it's done
+                        let before = match &self.deletion_progress {
+                            Some(last) if last.root == progress.root => last.bytes_done,
+                            _ => 0,
+                        };
+                        let bytes = progress.bytes_done.saturating_sub(before);
+                        self.freed_slices.record(&slice, bytes);
+                        running.streamed += bytes;
+                        self.deletion_progress = Some(progress);
+                    }
+                    Ok(WorkerMessage::Finished(result)) => {
+                        finished = Some(result);
+                        break;
+                    }
+                    Err(mpsc::TryRecvError::Empty) => break,
+                    Err(mpsc::TryRecvError::Disconnected) => {
+                        finished = Some(Err(anyhow::anyhow!("the cleaner stopped unexpectedly")));
+                        break;
+                    }
                 }
             }
-            // Selection
-            (KeyCode::Char(' '), KeyModifiers::NONE) => {
-                if !self.show_help {
-                    self.toggle_selected();
-                }
+        }
+        if let Some(result) = finished {
+            if let Some(running) = self.running_cleaner.take() {
+                deleter::clear_progress_handler();
+                protection::set_min_age(None);
+                self.deletion_progress = None;
+                self.finish_cleaner(running, result);
             }
-            // Run cleaners
-            (KeyCode::Enter, _) => {
-                if !self.show_help {
-                    self.run_selected()?;
-                }
+        }
+
+        if self.running_cleaner.is_none() && self.is_running && !self.limit_paused {
+            self.start_next_cleaner();
+        }
+    }
+
+    /// Whether a cleaner is still on the worker thread, which it can be for
+    /// a moment after the run was cancelled
+    pub fn cleaner_running(&self) -> bool {
+        self.running_cleaner.is_some()
+    }
+
+    /// Wait up to `grace` for the cleaner on the worker thread to stop, so
+    /// what it removed is recorded
+    pub fn wait_for_cleaner(&mut self, grace: Duration) {
+        let deadline = Instant::now() + grace;
+        while self.running_cleaner.is_some() && Instant::now() < deadline {
+            self.update_operations();
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    /// Start the first pending cleaner that isn't held back
+    fn start_next_cleaner(&mut self) {
+        let pending: Vec<(usize, usize, bool)> = self
+            .categories
+            .iter()
+            .enumerate()
+            .flat_map(|(cat_idx, category)| {
+                category
+                    .items
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, item)| matches!(item.status, Some(Status::Pending)))
+                    .map(move |(item_idx, item)| (cat_idx, item_idx, item.requires_root))
+            })
+            .collect();
+
+        for (cat_idx, item_idx, requires_root) in pending {
+            if requires_root && self.sudo_paused {
+                continue;
             }
-            // Help dialog
-            (KeyCode::Char('?' | 'h'), _) => {
-                self.toggle_help();
+            // Without sudo only the system cleaners wait, the rest go on
+            if requires_root && !self.is_root && !self.use_helper && !self.sudo_ready() {
+                self.pause_root_cleaners();
+                continue;
             }
+            self.start_cleaner(cat_idx, item_idx);
+            return;
+        }
+    }
 
-            // Toggle search in removed items view
-            (KeyCode::Char('/'), _) => {
-                if !self.show_help {
-                    self.toggle_search();
-                }
-            }
-            // Clear search or cancel operations or return to main menu
-            (KeyCode::Esc, _) => {
-                if self.search_active {
-                    self.clear_search();
-                } else if self.is_running {
-                    self.is_running = false;
-                    self.cancel_sudo_operations();
-                } else if self.show_progress_screen {
-                    // Return to main menu from completed operations screen
-                    self.show_progress_screen = false;
+    /// Run a cleaner on a worker thread, which reports large deletions as
+    /// they go and its result once done
+    fn start_cleaner(&mut self, cat_idx: usize, item_idx: usize) {
+        let category = self.categories[cat_idx].name.clone();
+        let item = &mut self.categories[cat_idx].items[item_idx];
+        item.status = Some(Status::Running);
+        let (name, function, requires_root) =
+            (item.name.clone(), item.function, item.requires_root);
+        self.log(format!("🔄 Executing: {}", name));
+
+        let before = verify::begin();
+        deferred::take();
+        cleaned::take();
+        cleaned::take_failures();
+        cleaned::take_denied();
+        protection::set_min_age(self.min_age);
+
+        let (tx, rx) = mpsc::channel();
+        let progress_tx = tx.clone();
+        deleter::set_progress_handler(move |progress: &DeleteProgress| {
+            let _ = progress_tx.send(WorkerMessage::Progress(progress.clone()));
+        });
+        let through_helper = requires_root && !self.is_root && self.use_helper;
+        let min_age = self.min_age;
+        let cleaner = name.clone();
+        thread::spawn(move || {
+            // Its output is captured for the log, the screen is drawn elsewhere
+            let result = capture_output(|| {
+                if through_helper {
+                    return helper::run_cleaner(&category, &cleaner, min_age);
                 }
-            }
-            // Scroll removed items list
-            (KeyCode::Char('j'), _) => {
-                if !self.show_help {
-                    self.scroll_detailed_list_down();
+                privileges::as_root_if(requires_root, || watchdog::run(&cleaner, || function(true)))
+            });
+            let _ = tx.send(WorkerMessage::Finished(result));
+        });
+
+        self.running_cleaner = Some(RunningCleaner {
+            cat_idx,
+            item_idx,
+            name,
+            requires_root,
+            before,
+            rx,
+            streamed: 0,
+        });
+    }
+
+    /// Show what a cleaner did once its worker finished
+    fn finish_cleaner(&mut self, running: RunningCleaner, captured_result: Result<(u64, String)>) {
+        let RunningCleaner {
+            cat_idx,
+            item_idx,
+            name,
+            requires_root,
+            before,
+            streamed,
+            ..
+        } = running;
+
+        // What it freed in the end is recorded below instead
+        let slice = chart_name(&self.categories[cat_idx].name, &name);
+        self.freed_slices.take_back(&slice, streamed);
+
+        let verification = match &captured_result {
+            Ok((bytes, _)) => verify::finish(before, *bytes),
+            Err(_) => None,
+        };
+
+        let result = match captured_result {
+            Ok((bytes, output)) => {
+                self.log(format!("✅ {}: Cleaned {} bytes", name, bytes));
+
+                // The cleaner's own record of what it removed
+                let category_name = self.categories[cat_idx].name.clone();
+                let entries = cleaned::take();
+                trim::record(&entries);
+                for entry in &entries {
+                    self.add_detailed_cleaned_item(
+                        entry.path.display().to_string(),
+                        entry.bytes,
+                        category_name.clone(),
+                        name.clone(),
+                        entry.kind,
+                    );
                 }
-            }
-            (KeyCode::Char('k'), _) => {
-                if !self.show_help {
-                    self.scroll_detailed_list_up();
+
+                // Show what it printed about removing things
+                for line in output.lines() {
+                    let line = sanitize::clean_line(line);
+                    let line = line.trim();
+                    if !line.is_empty()
+                        && (line.contains("Removed")
+                            || line.contains("cleaned")
+                            || line.contains("Cleaning")
+                            || line.contains("freed"))
+                    {
+                        self.log(format!("  → {}", line));
+                    }
                 }
-            }
-            // Select all in current category
-            (KeyCode::Char('a'), _) => {
-                if !self.show_help {
-                    self.select_all();
+
+                // Cleaners that don't record their paths yet get a
+                // summary item
+                if entries.is_empty() && bytes > 0 {
+                    self.add_detailed_cleaned_item(
+                        format!("{} (cleaned files)", name),
+                        bytes,
+                        category_name,
+                        name.clone(),
+                        CleanedItemType::Directory,
+                    );
                 }
+
+                Ok(bytes)
             }
-            // Deselect all in current category
-            (KeyCode::Char('n'), _) => {
-                if !self.show_help {
-                    self.deselect_all();
-                }
+            Err(e) => {
+                self.log(format!("❌ {}: {}", name, e));
+                Err(e)
             }
+        };
 
-            // Toggle compact mode
-            (KeyCode::Char('m'), _) => {
-                if !self.show_help {
-                    self.toggle_compact_mode();
+        // Protected paths are reported once per cleaner
+        let skipped = protection::summarize(&protection::take_skipped());
+        if let Some(skipped) = &skipped {
+            self.log(format!("⏭ {}: {}", name, skipped));
+        }
+
+        // Paths the cleaner couldn't remove
+        let failures = cleaned::take_failures();
+        for (path, error) in &failures {
+            self.log(format!("⚠ {}: couldn't remove {:?}: {}", name, path, error));
+        }
+        // Permission errors get one line, however many there were
+        let denied = cleaned::take_denied();
+        self.categories[cat_idx].items[item_idx].permission_denied = denied;
+        if denied > 0 {
+            self.log(format!(
+                "🔒 {}: permission denied on {} path(s), left in place",
+                name, denied
+            ));
+        }
+
+        // A cleaner that removed nothing it tried to has failed
+        let result = match result {
+            Ok(0) if !failures.is_empty() => Err(anyhow::anyhow!(
+                "none of {} paths could be removed",
+                failures.len()
+            )),
+            result => result,
+        };
+
+        // Process result
+        match result {
+            Ok(bytes) => {
+                let deferred = deferred::take().min(bytes);
+                let mut msg = if requires_root {
+                    format!("Cleaned {} (root) ({})", name, format_size(bytes))
+                } else {
+                    format!("Cleaned {} ({})", name, format_size(bytes))
+                };
+                if deferred > 0 {
+                    msg = format!("{}, {} after reboot", msg, format_size(deferred));
+                    self.log(format!(
+                        "⏳ {}: {} is still held open and comes free after a reboot",
+                        name,
+                        format_size(deferred)
+                    ));
                 }
-            }
-            // Toggle auto scroll log
-            (KeyCode::Char('s'), _) => {
-                if !self.show_help && self.is_running {
-                    self.toggle_auto_scroll();
+                if let Some(skipped) = skipped {
+                    msg = format!("{}, {}", msg, skipped);
                 }
-            }
-            // Toggle performance stats
-            (KeyCode::Char('p'), _) => {
-                if !self.show_help {
-                    self.toggle_performance_stats();
+                if denied > 0 {
+                    msg = format!("{}, {} permission denied", msg, denied);
                 }
-            }
-            // Cycle view mode
-            (KeyCode::Char('v'), _) => {
-                if !self.show_help {
-                    self.cycle_view_mode();
+                if let Some(verification) = &verification {
+                    if verification.disagrees() {
+                        msg = format!("{}, not confirmed on disk", msg);
+                        self.log(format!(
+                            "⚠ {}: freed size not confirmed on disk: {}",
+                            name,
+                            verification.summary()
+                        ));
+                    } else {
+                        self.log(format!("📏 {}: {}", name, verification.summary()));
+                    }
                 }
+                let status = if failures.is_empty() {
+                    Status::Success(msg)
+                } else {
+                    msg = format!("{}, {} failed", msg, failures.len());
+                    Status::PartialSuccess(msg, failures)
+                };
+                self.categories[cat_idx].items[item_idx].status = Some(status);
+                // Added up, a cleaner stopped at the safety limit runs again
+                self.categories[cat_idx].items[item_idx].bytes_cleaned += bytes;
+                self.categories[cat_idx].items[item_idx].bytes_deferred += deferred;
+                self.total_bytes_cleaned += bytes;
+                self.total_bytes_deferred += deferred;
+                self.freed_slices.record(&slice, bytes);
+                self.log(format!(
+                    "✅ Completed {}: {} freed",
+                    name,
+                    format_size(bytes)
+                ));
             }
-            // Cycle sort mode
-            (KeyCode::Char('o'), _) => {
-                if !self.show_help {
-                    self.cycle_sort_mode();
+            Err(e) => {
+                let protected = e.chain().find_map(|c| c.downcast_ref::<Skipped>());
+                let timed_out = e.chain().find_map(|c| c.downcast_ref::<TimedOut>());
+                let error_msg = if let Some(skip) = protected {
+                    format!("Skipped: {}", skip.protection)
+                } else if cancel::is_requested() && timed_out.is_none() {
+                    // What it removed before stopping still counts
+                    self.record_removed_so_far(cat_idx, item_idx);
+                    "Operation cancelled by user".to_string()
+                } else if let Some(timed_out) = timed_out {
+                    format!("Timed out after {}", watchdog::describe(timed_out.after))
+                } else if requires_root && !self.is_root && !self.use_helper {
+                    "Requires sudo - restart with 'sudo cleansys'".to_string()
+                } else {
+                    format!(
+                        "Failed: {}",
+                        e.to_string()
+                            .split(':')
+                            .next_back()
+                            .unwrap_or("Unknown error")
+                            .trim()
+                    )
+                };
+                self.categories[cat_idx].items[item_idx].status =
+                    Some(Status::Error(error_msg.clone()));
+                self.log(format!("❌ Failed {}: {}", name, error_msg));
+
+                // Add helpful message for sudo requirement
+                if requires_root
+                    && !self.is_root
+                    && !self.use_helper
+                    && !self
+                        .run_results
+                        .iter()
+                        .any(|msg| msg.contains("sudo cleansys"))
+                {
+                    self.run_results.push(
+                        "💡 System cleaners require root privileges. Run 'sudo cleansys' to clean system files.".to_string()
+                    );
                 }
             }
-            // Cycle filter mode
-            (KeyCode::Char('f'), _) => {
-                if !self.show_help {
-                    self.cycle_filter_mode();
+        }
+
+        if let Some(reached) = limits::take_refused() {
+            self.pause_at_limit(cat_idx, item_idx, reached);
+        }
+    }
+
+    /// Hold back the rest of the run after a deletion would have crossed
+    /// the safety limit, and ask whether to go past it. The cleaner that
+    /// hit it runs again if the user agrees, keeping what it freed so far.
+    fn pause_at_limit(&mut self, cat_idx: usize, item_idx: usize, reached: LimitReached) {
+        // A cleaner that failed on the limit left its removed paths behind
+        self.record_removed_so_far(cat_idx, item_idx);
+
+        let name = self.categories[cat_idx].items[item_idx].name.clone();
+        self.categories[cat_idx].items[item_idx].status = Some(Status::Pending);
+        self.limit_paused = true;
+        self.log(format!("⛔ {}: {}", name, reached));
+        self.limit_dialog.show(
+            "Safety Limit Reached",
+            vec![
+                reached.prompt,
+                format!("{} runs again from where it stopped if you continue.", name),
+            ],
+        );
+    }
+
+    /// Add up the paths a cleaner removed before it failed, stopped by the
+    /// safety limit or a cancel
+    fn record_removed_so_far(&mut self, cat_idx: usize, item_idx: usize) {
+        let category = self.categories[cat_idx].name.clone();
+        let name = self.categories[cat_idx].items[item_idx].name.clone();
+        let entries = cleaned::take();
+        let bytes: u64 = entries.iter().map(|entry| entry.bytes).sum();
+        for entry in &entries {
+            self.add_detailed_cleaned_item(
+                entry.path.display().to_string(),
+                entry.bytes,
+                category.clone(),
+                name.clone(),
+                entry.kind,
+            );
+        }
+        if bytes > 0 {
+            self.categories[cat_idx].items[item_idx].bytes_cleaned += bytes;
+            self.total_bytes_cleaned += bytes;
+            self.freed_slices
+                .record(&chart_name(&category, &name), bytes);
+        }
+    }
+
+    /// Go past the safety limit for the rest of the run
+    fn exceed_limit(&mut self) {
+        self.limit_dialog.hide();
+        self.limit_paused = false;
+        limits::approve_run();
+        self.log("⚠ Continuing past the safety limit for this run");
+    }
+
+    /// Stop the run at the safety limit, skipping the cleaners left
+    fn stop_at_limit(&mut self) {
+        self.limit_dialog.hide();
+        self.limit_paused = false;
+        let mut stopped = 0;
+        for item in self
+            .categories
+            .iter_mut()
+            .flat_map(|category| &mut category.items)
+            .filter(|item| matches!(item.status, Some(Status::Pending)))
+        {
+            item.status = Some(Status::Error("Skipped: safety limit reached".to_string()));
+            stopped += 1;
+        }
+        self.log(format!(
+            "⛔ Stopped at the safety limit, skipped {} cleaner(s)",
+            stopped
+        ));
+        self.toasts.push(
+            "Stopped at the safety limit; raise max_files_per_run/max_gb_per_run to allow more",
+        );
+    }
+
+    pub fn cancel_sudo_operations(&mut self) {
+        // Stop deletions after their current file, and external commands
+        cancel::request();
+        children::terminate_all();
+        self.sudo_dialog.hide();
+        self.sudo_paused = false;
+
+        // Mark all operations as cancelled
+        for category in &mut self.categories {
+            for item in &mut category.items {
+                if item.selected && matches!(item.status, Some(Status::Running | Status::Pending)) {
+                    item.status = Some(Status::Error("Operation cancelled by user".to_string()));
+                    item.selected = false; // Deselect the item
                 }
             }
-            // Toggle pause/resume operations
-            (KeyCode::Char(' '), KeyModifiers::CONTROL) => {
+        }
+
+        let message = "Cleaning operations cancelled by user.";
+        self.toasts.push(message);
+        self.run_results.push(message.to_string());
+    }
+
+    /// Whether the arrow keys and Enter go to the chart legend
+    pub(crate) fn legend_focused(&self) -> bool {
+        !self.help.is_visible()
+            && (self.is_running || self.show_progress_screen)
+            && self.progress_pane == ProgressPane::Chart
+    }
+
+    /// Handle a key press: look up its action on the screen on top and
+    /// apply it. Returns whether the TUI should quit.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Result<bool> {
+        match action::keymap(self, key) {
+            Some(action) => self.dispatch(action),
+            None => Ok(false),
+        }
+    }
+
+    /// Handle any terminal event, see [`Self::handle_key`]
+    pub fn handle_event(&mut self, event: Event) -> Result<bool> {
+        match action::for_event(self, event) {
+            Some(action) => self.dispatch(action),
+            None => Ok(false),
+        }
+    }
+
+    /// Apply an action. Returns whether the TUI should quit.
+    pub fn dispatch(&mut self, action: Action) -> Result<bool> {
+        match action {
+            Action::Quit => {
+                if self.quit_dialog.is_visible() {
+                    self.quit_dialog.hide();
+                }
                 if self.is_running {
-                    self.toggle_pause();
+                    self.is_running = false;
+                    self.cancel_sudo_operations();
                 }
+                self.wait_for_cleaner(QUIT_GRACE);
+                return Ok(true);
             }
-            // Toggle confirmation mode
-            (KeyCode::Char('y'), _) => {
-                if !self.show_help {
-                    self.toggle_confirmation_mode();
+            Action::OpenQuitDialog => self.quit_dialog.show(
+                "Quit",
+                vec!["Quit and cancel the remaining cleaners? [y/N]".to_string()],
+            ),
+            Action::CloseQuitDialog => self.quit_dialog.hide(),
+
+            Action::ShowHelp => self.help.show(),
+            Action::HideHelp => self.help.hide(),
+            Action::ToggleHelp => self.toggle_help(),
+            Action::ScrollHelpDown(lines) => self.help.scroll_down(lines),
+            Action::ScrollHelpUp(lines) => self.help.scroll_up(lines),
+
+            Action::TypeChar(c) => match action::Screen::of(self) {
+                action::Screen::PasswordPrompt => self.password_prompt.add_char(c),
+                action::Screen::ScheduleInput => {
+                    if let Some(input) = self.schedule_input.as_mut() {
+                        input.push(c);
+                    }
                 }
-            }
-            // Toggle chart type
-            (KeyCode::Char('c'), _) => {
-                if !self.show_help {
-                    self.toggle_chart_type();
+                action::Screen::ActionMenu => self.action_menu.add_char(c),
+                _ if self.search_active => self.add_search_char(c),
+                _ => {}
+            },
+            Action::DeleteChar => match action::Screen::of(self) {
+                action::Screen::PasswordPrompt => self.password_prompt.remove_char(),
+                action::Screen::ScheduleInput => {
+                    if let Some(input) = self.schedule_input.as_mut() {
+                        input.pop();
+                    }
                 }
+                action::Screen::ActionMenu => self.action_menu.remove_char(),
+                _ if self.search_active => self.remove_search_char(),
+                _ => {}
+            },
+
+            Action::OpenActionMenu => self.open_action_menu(),
+            Action::CloseActionMenu => self.action_menu.hide(),
+            Action::SubmitActionMenu => self.submit_action_menu(),
+            Action::NextMenuEntry => self.action_menu.next(),
+            Action::PreviousMenuEntry => self.action_menu.previous(),
+
+            Action::ShowAbout => self.show_about(),
+            Action::CloseAbout => self.show_about = false,
+            Action::ToggleMessageHistory => self.toasts.toggle_history(),
+            Action::CloseMessageHistory => self.toasts.hide_history(),
+
+            Action::ConfirmRiskyRun => self.confirm_risky_run()?,
+            Action::CancelRiskyRun => {
+                self.confirm_dialog.hide();
+                self.toasts.push("Cleaning cancelled at risk confirmation.");
             }
-            // Clear all errors
-            (KeyCode::Char('x'), _) => {
-                if !self.show_help {
-                    self.clear_errors();
+
+            Action::ToggleSchedule => self.toggle_schedule(),
+            Action::SubmitSchedule => self.submit_schedule(),
+            Action::CancelSchedule => self.schedule_input = None,
+
+            Action::SubmitPassword => self.submit_password(),
+            Action::CancelPassword => {
+                self.password_prompt.cancel();
+                self.needs_sudo = false;
+                self.pending_operations.clear();
+                // Back to the choice the prompt was opened from
+                if self.sudo_paused {
+                    self.show_sudo_dialog();
                 }
             }
-            // Handle search input (only when search is active)
-            (KeyCode::Char(c), _) => {
-                if self.search_active {
-                    self.add_search_char(c);
-                } else if !self.show_help {
-                    self.toggle_selected();
-                }
+
+            Action::Reauthenticate => self.reauthenticate(),
+            Action::SkipSystemCleaners => self.skip_root_cleaners(),
+            Action::AbortRun => self.cancel_sudo_operations(),
+
+            Action::ExceedLimit => self.exceed_limit(),
+            Action::StopAtLimit => self.stop_at_limit(),
+
+            Action::TrimMounts => self.trim_mounts(),
+            Action::SkipTrim => {
+                self.trim_dialog.hide();
+                self.trim_candidates.clear();
             }
-            // Backspace in search
-            (KeyCode::Backspace, _) => {
-                if self.search_active {
-                    self.remove_search_char();
+
+            Action::NextItem => self.next_item(),
+            Action::PreviousItem => self.previous_item(),
+            Action::FirstItem => self.item_list_state.select(Some(0)),
+            Action::LastItem => {
+                let len = self.visible_items().len();
+                if len > 0 {
+                    self.item_list_state.select(Some(len - 1));
                 }
             }
-            // Page scrolling for removed items (when in progress view)
-            (KeyCode::PageUp, _) => {
-                if self.is_running || self.show_progress_screen {
-                    // Scroll up by 10 items
-                    for _ in 0..10 {
-                        self.scroll_detailed_list_up();
-                    }
+            Action::NextCategory => self.next_category(),
+            Action::PreviousCategory => self.previous_category(),
+            Action::JumpToCategory(index) => self.jump_to_category(index),
+            Action::ToggleCategoriesCollapsed => self.toggle_categories_collapsed(),
+            Action::ToggleSystemInfo => self.toggle_system_info(),
+            Action::CancelScans => self.cancel_scans(),
+            Action::ToggleSelected => self.toggle_selected(),
+            Action::SelectAll => self.select_all(),
+            Action::DeselectAll => self.deselect_all(),
+            Action::SelectRecommended => self.select_recommended(),
+            Action::RunSelected => self.run_selected()?,
+            Action::ToggleJournalVacuum => self.toggle_journal_vacuum(),
+            Action::ClearErrors => self.clear_errors(),
+
+            Action::ScrollDown(rows) => {
+                for _ in 0..rows {
+                    self.scroll_detailed_list_down();
                 }
             }
-            (KeyCode::PageDown, _) => {
-                if self.is_running || self.show_progress_screen {
-                    // Scroll down by 10 items
-                    for _ in 0..10 {
-                        self.scroll_detailed_list_down();
-                    }
+            Action::ScrollUp(rows) => {
+                for _ in 0..rows {
+                    self.scroll_detailed_list_up();
                 }
             }
-            // Enhanced navigation with Ctrl modifiers
-            (KeyCode::Home, _) => {
-                if !self.show_help {
-                    if self.is_running || self.show_progress_screen {
-                        self.detailed_list_scroll_state.select(Some(0));
-                    } else {
-                        self.item_list_state.select(Some(0));
-                    }
+            Action::ScrollToTop => self.detailed_list_scroll_state.select(Some(0)),
+            Action::ScrollToBottom => {
+                let rows = self.journal_rows().len();
+                if rows > 0 {
+                    self.detailed_list_scroll_state.select(Some(rows - 1));
                 }
             }
-            (KeyCode::End, _) => {
-                if !self.show_help {
-                    if self.is_running || self.show_progress_screen {
-                        if !self.detailed_cleaned_items.is_empty() {
-                            let last_index =
-                                (self.detailed_cleaned_items.len() * 3).saturating_sub(1);
-                            self.detailed_list_scroll_state.select(Some(last_index));
-                        }
-                    } else {
-                        let len = self.categories[self.category_index].items.len();
-                        if len > 0 {
-                            self.item_list_state.select(Some(len - 1));
+            Action::CycleProgressPane => self.cycle_progress_pane(),
+            Action::NextLegendEntry => self.next_legend_entry(),
+            Action::PreviousLegendEntry => self.previous_legend_entry(),
+            Action::ToggleLegendFilter => self.toggle_legend_filter(),
+            Action::ClearLegendFilter => self.legend_filter = None,
+            Action::ToggleJournalGroup => self.toggle_journal_group(),
+            Action::ResizeProgressSplit(grow) => self.resize_progress_split(grow),
+            Action::ExcludeHighlighted(parent) => self.exclude_highlighted(parent),
+            Action::OpenHighlightedFolder(shell) => self.open_highlighted_folder(shell),
+            Action::UndoLastRun => self.offer_undo(),
+            Action::ConfirmUndo => self.undo_last_run(),
+            Action::CancelUndo => {
+                self.undo_dialog.hide();
+                self.undo_run = None;
+            }
+            Action::TogglePause => self.toggle_pause(),
+            Action::CancelRun => {
+                self.is_running = false;
+                self.cancel_sudo_operations();
+            }
+            Action::CloseProgress => {
+                self.show_progress_screen = false;
+                self.progress_pane = ProgressPane::RemovedItems;
+            }
+
+            Action::ToggleSearch => self.toggle_search(),
+            Action::ClearSearch => self.clear_search(),
+
+            Action::ToggleCompactMode => self.toggle_compact_mode(),
+            Action::ToggleAutoScroll => self.toggle_auto_scroll(),
+            Action::TogglePerformanceStats => self.toggle_performance_stats(),
+            Action::CycleViewMode => self.cycle_view_mode(),
+            Action::CycleSortMode => self.cycle_sort_mode(),
+            Action::CycleFilterMode => self.cycle_filter_mode(),
+            Action::ToggleConfirmationMode => self.toggle_confirmation_mode(),
+            Action::ToggleChartType => self.toggle_chart_type(),
+            Action::ToggleRawSizes => self.toggle_raw_sizes(),
+
+            Action::Tick => self.update_animation(),
+            Action::Resize(width, height) => self.handle_resize(width, height),
+        }
+        Ok(false)
+    }
+
+    /// Check the typed sudo password and start the cleaners waiting for it
+    fn submit_password(&mut self) {
+        match self.password_prompt.submit() {
+            Ok(true) if self.sudo_paused => {
+                self.password_prompt.hide();
+                self.resume_root_cleaners();
+            }
+            Ok(true) => {
+                // Authentication successful, proceed with operations
+                self.needs_sudo = false;
+                self.password_prompt.hide();
+
+                // Now start the actual cleaning operations
+                let selected_cleaners = self.pending_operations.clone();
+                self.pending_operations.clear();
+
+                if !selected_cleaners.is_empty() {
+                    // Start processing
+                    self.is_running = true;
+                    self.show_progress_screen = true;
+                    self.operation_start_time = Some(Instant::now());
+                    self.operation_end_time = None;
+                    self.total_bytes_cleaned = 0;
+                    self.total_bytes_deferred = 0;
+                    self.deletion_progress = None;
+                    self.run_results.clear();
+                    self.operation_logs.clear();
+                    self.detailed_cleaned_items.clear();
+                    self.freed_slices.clear();
+                    self.legend_filter = None;
+                    self.current_cleaner_index = 0;
+
+                    // Reset bytes_cleaned for all items to start fresh
+                    for category in &mut self.categories {
+                        for item in &mut category.items {
+                            item.bytes_cleaned = 0;
+                            item.bytes_deferred = 0;
+                            item.permission_denied = 0;
                         }
                     }
+
+                    // Set all selected cleaners to Pending
+                    for (cat_idx, item_idx, _, _, _) in &selected_cleaners {
+                        self.categories[*cat_idx].items[*item_idx].status = Some(Status::Pending);
+                    }
+
+                    self.update_counters();
+                }
+            }
+            Ok(false) => {
+                // Authentication failed, stay on prompt
+            }
+            Err(e) => {
+                self.log(format!("❌ Authentication error: {}", e));
+                self.password_prompt.hide();
+                self.needs_sudo = false;
+                self.pending_operations.clear();
+                if self.sudo_paused {
+                    self.show_sudo_dialog();
                 }
             }
-            _ => {}
         }
+    }
 
-        Ok(false)
+    /// Whether sudo still accepts the cached credentials, authenticating
+    /// through `SUDO_ASKPASS` again when it is in use
+    fn sudo_ready(&mut self) -> bool {
+        (self.password_prompt.is_authenticated() && self.password_prompt.revalidate())
+            || self.authenticate_with_askpass()
+    }
+
+    /// The system cleaners still waiting to run
+    fn waiting_root_cleaners(&self) -> Vec<String> {
+        self.categories
+            .iter()
+            .flat_map(|category| &category.items)
+            .filter(|item| item.requires_root && matches!(item.status, Some(Status::Pending)))
+            .map(|item| item.name.clone())
+            .collect()
+    }
+
+    /// Hold back the system cleaners left in the run after sudo
+    /// authentication failed or expired, and ask what to do about them.
+    /// The other cleaners keep running meanwhile.
+    pub fn pause_root_cleaners(&mut self) {
+        if self.sudo_paused {
+            return;
+        }
+        self.sudo_paused = true;
+        self.log("🔒 Sudo authentication failed, system cleaners paused");
+        self.show_sudo_dialog();
+    }
+
+    fn show_sudo_dialog(&mut self) {
+        let mut message = vec!["These system cleaners are waiting:".to_string()];
+        message.extend(
+            self.waiting_root_cleaners()
+                .into_iter()
+                .map(|name| format!("  • {}", name)),
+        );
+        message.push("Other cleaners keep running meanwhile.".to_string());
+        self.sudo_dialog.show("Authentication Required", message);
+    }
+
+    /// Offer to trim the SSD mounts the finished run freed enough on
+    fn offer_trim(&mut self) {
+        self.trim_candidates = trim::take_candidates();
+        if self.trim_candidates.is_empty() {
+            return;
+        }
+        let mut message: Vec<(String, Option<u64>)> = self
+            .trim_candidates
+            .iter()
+            .map(|candidate| {
+                (
+                    format!(
+                        "  • {} ({}) freed",
+                        candidate.mount.display(),
+                        candidate.storage.name()
+                    ),
+                    Some(candidate.freed),
+                )
+            })
+            .collect();
+        message.push((
+            "Run fstrim so the drive can reuse the space?".to_string(),
+            None,
+        ));
+        self.trim_dialog.show_with_sizes("Trim", message);
+    }
+
+    /// Run `fstrim` on the mounts offered in the trim dialog
+    fn trim_mounts(&mut self) {
+        self.trim_dialog.hide();
+        for candidate in std::mem::take(&mut self.trim_candidates) {
+            let mount = candidate.mount.display().to_string();
+            let message = match trim::trim(&candidate.mount) {
+                Ok(trimmed) => format!("✂ Trimmed {} on {}", format_size(trimmed), mount),
+                Err(e) => format!("⚠ Couldn't trim {}: {:#}", mount, e),
+            };
+            self.log(message.clone());
+            self.toasts.push(message.clone());
+            self.run_results.push(message);
+        }
+    }
+
+    /// Authenticate again from the sudo dialog, through `SUDO_ASKPASS` or
+    /// the password prompt
+    fn reauthenticate(&mut self) {
+        self.sudo_dialog.hide();
+        if self.authenticate_with_askpass() {
+            self.resume_root_cleaners();
+        } else {
+            self.password_prompt.show();
+        }
+    }
+
+    fn resume_root_cleaners(&mut self) {
+        self.sudo_paused = false;
+        self.log("🔓 Authenticated again, resuming system cleaners");
+    }
+
+    /// Give up on the system cleaners left in the run, letting the others
+    /// finish
+    fn skip_root_cleaners(&mut self) {
+        self.sudo_dialog.hide();
+        self.sudo_paused = false;
+        let skipped = self.waiting_root_cleaners();
+        for item in self
+            .categories
+            .iter_mut()
+            .flat_map(|category| &mut category.items)
+            .filter(|item| item.requires_root && matches!(item.status, Some(Status::Pending)))
+        {
+            item.status = Some(Status::Error(
+                "Skipped: sudo authentication failed".to_string(),
+            ));
+        }
+        self.log(format!(
+            "⏭ Skipped {} system cleaner(s) without sudo",
+            skipped.len()
+        ));
+        self.toasts.push(format!(
+            "Skipped {} system cleaner(s) without sudo",
+            skipped.len()
+        ));
     }
 
     pub fn handle_resize(&mut self, width: u16, height: u16) {
@@ -1088,26 +2319,35 @@ impl App {
             ViewMode::Detailed => ViewMode::Performance,
             ViewMode::Performance => ViewMode::Standard,
         };
+        self.detailed_view = self.view_mode == ViewMode::Detailed;
     }
 
     pub fn cycle_sort_mode(&mut self) {
+        let highlighted = self.selected_item_index();
         self.sort_mode = match self.sort_mode {
             SortMode::Name => SortMode::Size,
             SortMode::Size => SortMode::Status,
             SortMode::Status => SortMode::Category,
             SortMode::Category => SortMode::Name,
         };
+        self.select_item_index(highlighted);
     }
 
     pub fn cycle_filter_mode(&mut self) {
+        let highlighted = self.selected_item_index();
         self.filter_mode = match self.filter_mode {
             FilterMode::All => FilterMode::Selected,
             FilterMode::Selected => FilterMode::Completed,
             FilterMode::Completed => FilterMode::Errors,
             FilterMode::Errors => FilterMode::UserOnly,
             FilterMode::UserOnly => FilterMode::SystemOnly,
-            FilterMode::SystemOnly => FilterMode::All,
+            FilterMode::SystemOnly => FilterMode::Tagged(Tag::ALL[0]),
+            FilterMode::Tagged(tag) => match Tag::ALL.iter().position(|t| *t == tag) {
+                Some(i) if i + 1 < Tag::ALL.len() => FilterMode::Tagged(Tag::ALL[i + 1]),
+                _ => FilterMode::All,
+            },
         };
+        self.select_item_index(highlighted);
     }
 
     pub fn toggle_pause(&mut self) {
@@ -1118,6 +2358,43 @@ impl App {
         self.confirmation_mode = !self.confirmation_mode;
     }
 
+    /// The footer's next-step hint for the current selection
+    pub fn footer_hint(&self) -> FooterHint {
+        let selected: Vec<&CleanerItem> = self
+            .categories
+            .iter()
+            .flat_map(|c| &c.items)
+            .filter(|item| item.selected)
+            .collect();
+        if selected.is_empty() {
+            return FooterHint::Select;
+        }
+
+        let estimates: Vec<u64> = selected
+            .iter()
+            .filter_map(|item| item.estimated_size())
+            .collect();
+        FooterHint::Clean {
+            count: selected.len(),
+            estimate: (!estimates.is_empty()).then(|| estimates.iter().sum()),
+        }
+    }
+
+    /// Background scans as (finished, started) while any is still running
+    pub fn scan_progress(&self) -> Option<(usize, usize)> {
+        let states: Vec<&ScanState> = self
+            .categories
+            .iter()
+            .flat_map(|c| &c.items)
+            .filter_map(|item| item.scan_state.as_ref())
+            .collect();
+        let running = states
+            .iter()
+            .filter(|state| matches!(state, ScanState::Scanning))
+            .count();
+        (running > 0).then(|| (states.len() - running, states.len()))
+    }
+
     pub fn update_counters(&mut self) {
         self.selected_cleaners_count = self
             .categories
@@ -1133,6 +2410,13 @@ impl App {
             .filter(|item| matches!(item.status, Some(Status::Error(_))))
             .count();
 
+        self.partial_count = self
+            .categories
+            .iter()
+            .flat_map(|cat| &cat.items)
+            .filter(|item| matches!(item.status, Some(Status::PartialSuccess(..))))
+            .count();
+
         self.operation_count = self
             .categories
             .iter()
@@ -1166,25 +2450,80 @@ impl App {
             // If no operations are running or pending, and we have selected items, mark as complete
             if running_count == 0 && pending_count == 0 && selected_count > 0 {
                 self.is_running = false;
-                self.demo_operation_timer = None;
                 self.operation_end_time = Some(Instant::now());
 
                 // Add completion message
                 if !self
-                    .result_messages
+                    .run_results
                     .iter()
-                    .any(|msg| msg.contains("Completed"))
+                    .any(|msg| msg.contains("Cleaning completed"))
                 {
-                    self.result_messages.push(format!(
+                    self.run_results.push(format!(
                         "✅ Cleaning completed! Total space freed: {} (Press ESC to return to main menu)",
-                        format_size(self.total_bytes_cleaned)
+                        deferred::describe(self.total_bytes_cleaned, self.total_bytes_deferred)
                     ));
+                    if self.partial_count > 0 {
+                        self.run_results.push(format!(
+                            "⚠ {} cleaner(s) couldn't remove everything, see the log for the paths",
+                            self.partial_count
+                        ));
+                    }
+                    self.record_run();
+                    self.refresh_disks();
+                    self.min_age = None;
+                    self.offer_trim();
                 }
                 // Keep show_progress_screen true so user stays on details screen
             }
         }
     }
 
+    /// What each cleaner of the finished run did
+    pub fn run_record(&self) -> RunRecord {
+        let cleaners = self
+            .categories
+            .iter()
+            .flat_map(|cat| &cat.items)
+            .filter_map(|item| match &item.status {
+                Some(Status::Success(_)) => Some(
+                    CleanerRun::succeeded(item.name.clone(), item.bytes_cleaned)
+                        .with_deferred(item.bytes_deferred),
+                ),
+                Some(Status::PartialSuccess(_, failures)) => Some(
+                    CleanerRun::succeeded(item.name.clone(), item.bytes_cleaned)
+                        .with_deferred(item.bytes_deferred)
+                        .with_failed_paths(failures.len()),
+                ),
+                Some(Status::Error(error)) => Some(CleanerRun::failed(item.name.clone(), error)),
+                _ => None,
+            })
+            .collect();
+        RunRecord::new(cleaners)
+    }
+
+    /// Add the finished run to the history and show how it compares with
+    /// the previous one
+    fn record_run(&mut self) {
+        let run = self.run_record();
+        self.usage.record(&run);
+        let Some(history) = &self.history else {
+            return;
+        };
+        let recorded = history
+            .record(run)
+            .and_then(|run| Ok((history.previous(run.id)?, run)));
+        match recorded {
+            Ok((Some(previous), run)) => {
+                let diff = RunDiff::between(&previous, &run);
+                self.run_results.extend(diff.summary_lines());
+            }
+            Ok((None, _)) => {}
+            Err(e) => self
+                .toasts
+                .push(format!("Run not saved to history: {:#}", e)),
+        }
+    }
+
     pub fn clear_errors(&mut self) {
         for category in &mut self.categories {
             for item in &mut category.items {
@@ -1247,39 +2586,268 @@ impl App {
             }
         } else {
             // Start from the bottom when first navigating
-            let total_items = if !self.detailed_cleaned_items.is_empty() {
-                self.detailed_cleaned_items.len() * 3 // Account for spacing between items
-            } else {
-                45 // Sample items count for demo
-            };
-            if total_items > 0 {
-                self.detailed_list_scroll_state
-                    .select(Some(total_items - 1));
+            let total_rows = self.journal_rows().len();
+            if total_rows > 0 {
+                self.detailed_list_scroll_state.select(Some(total_rows - 1));
             }
         }
     }
 
     pub fn scroll_detailed_list_down(&mut self) {
-        let total_items = if !self.detailed_cleaned_items.is_empty() {
-            self.detailed_cleaned_items.len() * 3 // Account for spacing between items
-        } else {
-            45 // Sample items count for demo
-        };
+        let total_rows = self.journal_rows().len();
 
         if let Some(selected) = self.detailed_list_scroll_state.selected() {
-            if selected < total_items.saturating_sub(1) {
+            if selected < total_rows.saturating_sub(1) {
                 self.detailed_list_scroll_state.select(Some(selected + 1));
             }
-        } else if total_items > 0 {
+        } else if total_rows > 0 {
             self.detailed_list_scroll_state.select(Some(0));
         }
     }
 
+    /// The removed items grouped by cleaner, in the order the cleaners ran.
+    /// Items within a group follow the sort mode; collapsed groups only
+    /// show their header.
+    pub fn journal_rows(&self) -> Vec<JournalRow<'_>> {
+        let mut groups: Vec<(&str, Vec<&DetailedCleanedItem>)> = Vec::new();
+        for item in &self.detailed_cleaned_items {
+            if !groups.iter().any(|(name, _)| *name == item.cleaner_name) {
+                groups.push((item.cleaner_name.as_str(), Vec::new()));
+            }
+        }
+        for item in self.get_filtered_detailed_items() {
+            if let Some((_, items)) = groups
+                .iter_mut()
+                .find(|(name, _)| *name == item.cleaner_name)
+            {
+                items.push(item);
+            }
+        }
+
+        let mut rows = Vec::new();
+        for (cleaner, items) in groups {
+            let Some(first) = items.first() else {
+                continue;
+            };
+            let collapsed = self.collapsed_cleaners.contains(cleaner);
+            rows.push(JournalRow::Header {
+                cleaner,
+                category: first.category.as_str(),
+                count: items.len(),
+                size: items.iter().map(|item| item.size).sum(),
+                collapsed,
+            });
+            if !collapsed {
+                rows.extend(items.into_iter().map(JournalRow::Item));
+            }
+        }
+        rows
+    }
+
+    /// Collapse or expand the journal group under the cursor, keeping the
+    /// cursor on its header
+    pub fn toggle_journal_group(&mut self) {
+        let Some(selected) = self.detailed_list_scroll_state.selected() else {
+            return;
+        };
+        let cleaner = match self.journal_rows().get(selected) {
+            Some(JournalRow::Header { cleaner, .. }) => cleaner.to_string(),
+            Some(JournalRow::Item(item)) => item.cleaner_name.clone(),
+            None => return,
+        };
+
+        if !self.collapsed_cleaners.remove(&cleaner) {
+            self.collapsed_cleaners.insert(cleaner.clone());
+        }
+
+        let header = self.journal_rows().iter().position(
+            |row| matches!(row, JournalRow::Header { cleaner: name, .. } if *name == cleaner),
+        );
+        self.detailed_list_scroll_state.select(header);
+    }
+
+    /// Never clean the highlighted removed path again, or its parent
+    /// directory: add an exclusion for it to the config file and to this
+    /// session
+    pub fn exclude_highlighted(&mut self, parent: bool) {
+        let Some(selected) = self.detailed_list_scroll_state.selected() else {
+            return;
+        };
+        let path = match self.journal_rows().get(selected) {
+            Some(JournalRow::Item(item)) => PathBuf::from(&item.path),
+            _ => {
+                self.toasts.push("Highlight a removed path to exclude it");
+                return;
+            }
+        };
+        let path = match path.parent() {
+            Some(dir) if parent => dir.to_path_buf(),
+            _ => path,
+        };
+        let pattern = exclude::pattern_for(&path);
+        if exclude::covers_too_much(&pattern) {
+            self.toasts.push(format!(
+                "Not excluding {}, it would cover too much",
+                pattern
+            ));
+            return;
+        }
+
+        let Some(config_path) = self.config_path.clone() else {
+            self.toasts
+                .push("No config directory to save the exclusion in");
+            return;
+        };
+        // The user's config, so written as the user under sudo
+        match privileges::as_user(|| Settings::add_exclusion_to(&config_path, &pattern)) {
+            Ok(()) => {
+                exclude::add_pattern(pattern.clone());
+                self.toasts.push(format!(
+                    "Excluded {} (saved to {})",
+                    pattern,
+                    config_path.display()
+                ));
+            }
+            Err(e) => self
+                .toasts
+                .push(format!("Could not save the exclusion: {:#}", e)),
+        }
+    }
+
+    /// Open the folder that held the highlighted removed path, in the file
+    /// manager, or in a shell with `shell` or without a graphical session.
+    /// A folder that was removed as well gives way to the closest one left.
+    pub fn open_highlighted_folder(&mut self, shell: bool) {
+        let Some(selected) = self.detailed_list_scroll_state.selected() else {
+            return;
+        };
+        let path = match self.journal_rows().get(selected) {
+            Some(JournalRow::Item(item)) => PathBuf::from(&item.path),
+            _ => {
+                self.toasts
+                    .push("Highlight a removed path to open its folder");
+                return;
+            }
+        };
+        let Some(dir) = path.ancestors().skip(1).find(|dir| dir.is_dir()) else {
+            self.toasts
+                .push(format!("Nothing is left of {}'s folder", path.display()));
+            return;
+        };
+        if shell || !has_graphical_session() {
+            self.shell_request = Some(dir.to_path_buf());
+            return;
+        }
+        match open_in_file_manager(dir) {
+            Ok(()) => self.toasts.push(format!("Opened {}", dir.display())),
+            Err(e) => self
+                .toasts
+                .push(format!("Could not open {}: {:#}", dir.display(), e)),
+        }
+    }
+
+    /// Open the About screen with the usage statistics and what the
+    /// quarantine holds
+    pub fn show_about(&mut self) {
+        self.quarantine_usage = Quarantine::base_dir()
+            .and_then(|base| Quarantine::usage(&base))
+            .ok();
+        self.show_about = true;
+    }
+
+    /// Ask whether to undo the most recent run in the history, if it moved
+    /// files to the quarantine that are still there. Otherwise say why not.
+    pub fn offer_undo(&mut self) {
+        let runs = match &self.history {
+            Some(history) => history.load(),
+            None => Ok(Vec::new()),
+        };
+        let target = runs.and_then(|runs| {
+            let dir = Quarantine::undo_target(&Quarantine::base_dir()?, &runs)?;
+            Ok((dir, runs))
+        });
+        let (dir, runs) = match target {
+            Ok(target) => target,
+            Err(e) => {
+                self.toasts.push(format!("Nothing to undo: {:#}", e));
+                return;
+            }
+        };
+        // Found in the history, so there is a last run
+        let Some(run) = runs.last() else {
+            return;
+        };
+        let entries = Quarantine::load_manifest(&dir).unwrap_or_default();
+        let size = entries.iter().map(|entry| entry.size).sum();
+        self.undo_dialog.show_with_sizes(
+            "Undo",
+            vec![
+                (format!("Run #{} at {}", run.id, run.time), None),
+                (
+                    format!("Put back {} files from {}?", entries.len(), dir.display()),
+                    Some(size),
+                ),
+            ],
+        );
+        self.undo_run = Some(dir);
+    }
+
+    /// Put back the files of the run the undo dialog asked about, logging
+    /// each one and what couldn't be restored
+    pub fn undo_last_run(&mut self) {
+        self.undo_dialog.hide();
+        let Some(dir) = self.undo_run.take() else {
+            return;
+        };
+        let report = match Quarantine::restore(&dir) {
+            Ok(report) => report,
+            Err(e) => {
+                self.toasts.push(format!("Could not undo: {:#}", e));
+                return;
+            }
+        };
+        for path in &report.restored {
+            self.log(format!("↩️ Restored {}", path.display()));
+        }
+        for (path, problem) in &report.incomplete {
+            self.toasts.push(format!(
+                "{}: restored, but not all attributes: {}",
+                path.display(),
+                problem
+            ));
+        }
+        for (path, error) in &report.failed {
+            self.toasts
+                .push(format!("Not restored {}: {}", path.display(), error));
+        }
+        let mut summary = format!(
+            "Restored {} files from {}",
+            report.restored.len(),
+            dir.display()
+        );
+        if !report.failed.is_empty() {
+            summary.push_str(&format!(", {} failed (M for details)", report.failed.len()));
+        }
+        self.toasts.push(summary);
+    }
+
+    /// The folder a shell was asked for, clearing the request
+    pub fn take_shell_request(&mut self) -> Option<PathBuf> {
+        self.shell_request.take()
+    }
+
     pub fn get_filtered_detailed_items(&self) -> Vec<&DetailedCleanedItem> {
         let mut items: Vec<&DetailedCleanedItem> = self
             .detailed_cleaned_items
             .iter()
             .filter(|item| {
+                // Apply chart legend filter
+                if let Some(filter) = &self.legend_filter {
+                    if chart_name(&item.category, &item.cleaner_name) != *filter {
+                        return false;
+                    }
+                }
+
                 // Apply search filter
                 if !self.search_query.is_empty() {
                     let query_lower = self.search_query.to_lowercase();
@@ -1303,14 +2871,32 @@ impl App {
         // Sort based on current sort mode
         match self.sort_mode {
             SortMode::Name => items.sort_by(|a, b| a.path.cmp(&b.path)),
-            SortMode::Size => items.sort_by(|a, b| b.size.cmp(&a.size)), // Largest first
+            SortMode::Size => items.sort_by_key(|i| std::cmp::Reverse(i.size)), // Largest first
             SortMode::Category => items.sort_by(|a, b| a.category.cmp(&b.category)),
-            SortMode::Status => items.sort_by(|a, b| b.timestamp.cmp(&a.timestamp)), // Most recent first
+            SortMode::Status => items.sort_by_key(|i| std::cmp::Reverse(i.timestamp)), // Most recent first
         }
 
         items
     }
 
+    /// Whether the highlighted cleaner is the one vacuuming the journal
+    pub fn journal_vacuum_selected(&self) -> bool {
+        let category = &self.categories[self.category_index];
+        self.selected_item_index()
+            .and_then(|i| category.items.get(i))
+            .is_some_and(|item| item.name == "System Logs")
+    }
+
+    /// Switch the journal between vacuuming by time and by size
+    pub fn toggle_journal_vacuum(&mut self) {
+        let vacuum = log_backends::journal_vacuum().toggled();
+        self.toasts.push(format!(
+            "Journal cleaning now vacuums {}",
+            vacuum.describe()
+        ));
+        log_backends::set_journal_vacuum(vacuum);
+    }
+
     pub fn toggle_chart_type(&mut self) {
         self.chart_type = match self.chart_type {
             ChartType::Bar => ChartType::PieCount,
@@ -1319,6 +2905,42 @@ impl App {
         };
     }
 
+    /// Switch sizes between exact byte counts and KB, MB and GB. Lines
+    /// already logged keep the format they were written in.
+    pub fn toggle_raw_sizes(&mut self) {
+        let raw = !raw_sizes();
+        set_raw_sizes(raw);
+        self.toasts.push(if raw {
+            "Sizes shown in bytes"
+        } else {
+            "Sizes shown in KB, MB and GB"
+        });
+    }
+
+    /// Share of the progress screen the overview takes, in percent. Taller
+    /// terminals give it more room unless the user resized it.
+    pub fn progress_split(&self) -> u16 {
+        self.progress_split
+            .unwrap_or(if self.terminal_height >= 35 {
+                55
+            } else if self.terminal_height >= 25 {
+                50
+            } else {
+                45
+            })
+    }
+
+    /// Grow (`+`) or shrink (`-`) the progress overview by one step
+    pub fn resize_progress_split(&mut self, grow: bool) {
+        let split = self.progress_split();
+        let split = if grow {
+            split + PROGRESS_SPLIT_STEP
+        } else {
+            split.saturating_sub(PROGRESS_SPLIT_STEP)
+        };
+        self.progress_split = Some(split.clamp(PROGRESS_SPLIT_MIN, PROGRESS_SPLIT_MAX));
+    }
+
     pub fn add_sample_cleaned_items(&mut self) {
         // Add some sample cleaned items to demonstrate the detailed view
         let sample_items = vec![
@@ -1430,6 +3052,8 @@ impl App {
         ];
 
         for (path, size, category, cleaner, item_type) in sample_items {
+            self.freed_slices
+                .record(&chart_name(category, cleaner), size);
             self.add_detailed_cleaned_item(
                 path.to_string(),
                 size,
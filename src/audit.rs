@@ -0,0 +1,165 @@
+//! Audit mode: before a small file is deleted or quarantined, record its
+//! path, size and SHA-256 in a per-run log, so a file that turns out to be
+//! needed can at least be identified and a quarantine restore verified.
+
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::duplicates::hash_file;
+use crate::paths;
+
+/// Only files smaller than this are hashed (1 MB)
+pub const MAX_AUDIT_SIZE: u64 = 1024 * 1024;
+
+/// One recorded file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub path: PathBuf,
+    pub size: u64,
+    pub sha256: String,
+}
+
+enum AuditLog {
+    /// Appended to a file, created on the first entry
+    File { path: PathBuf, file: Option<File> },
+    /// Kept for [`take_captured`], as the root helper does for its client
+    Captured(Vec<AuditEntry>),
+}
+
+static LOG: Lazy<Mutex<Option<AuditLog>>> = Lazy::new(|| Mutex::new(None));
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Directory holding the audit log of every run
+pub fn log_dir() -> Result<PathBuf> {
+    Ok(paths::state_dir()?.join("audit"))
+}
+
+/// Turn on audit mode for this run, logging to `audit/<timestamp>.jsonl`
+pub fn enable() -> Result<PathBuf> {
+    let path = log_dir()?.join(format!("{}.jsonl", now()));
+    enable_at(&path);
+    Ok(path)
+}
+
+/// Turn on audit mode, logging to a specific file. It is created on the
+/// first recorded deletion.
+pub fn enable_at(path: &Path) {
+    *LOG.lock().unwrap_or_else(|e| e.into_inner()) = Some(AuditLog::File {
+        path: path.to_path_buf(),
+        file: None,
+    });
+}
+
+/// Turn on audit mode, keeping entries in memory instead of a log
+pub fn capture() {
+    *LOG.lock().unwrap_or_else(|e| e.into_inner()) = Some(AuditLog::Captured(Vec::new()));
+}
+
+/// The entries kept since [`capture`] or the last call
+pub fn take_captured() -> Vec<AuditEntry> {
+    match LOG.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        Some(AuditLog::Captured(entries)) => std::mem::take(entries),
+        _ => Vec::new(),
+    }
+}
+
+/// Turn audit mode off
+pub fn disable() {
+    *LOG.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+pub fn is_enabled() -> bool {
+    LOG.lock().unwrap_or_else(|e| e.into_inner()).is_some()
+}
+
+/// Record a file that is about to be removed. Does nothing unless audit
+/// mode is on and the path is a regular file under [`MAX_AUDIT_SIZE`].
+pub fn record(path: &Path) -> Result<()> {
+    if !is_enabled() {
+        return Ok(());
+    }
+
+    // Hash before taking the lock so parallel quarantine workers don't queue up
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return Ok(());
+    };
+    if !metadata.is_file() || metadata.len() >= MAX_AUDIT_SIZE {
+        return Ok(());
+    }
+    let Some(sha256) = hash_file(path) else {
+        return Ok(());
+    };
+
+    append(AuditEntry {
+        timestamp: now(),
+        path: std::path::absolute(path)?,
+        size: metadata.len(),
+        sha256,
+    })
+}
+
+/// Add an entry recorded elsewhere, such as by the root helper, to the log.
+/// Does nothing unless audit mode is on.
+pub fn append(entry: AuditEntry) -> Result<()> {
+    let mut log = LOG.lock().unwrap_or_else(|e| e.into_inner());
+    match log.as_mut() {
+        None => Ok(()),
+        Some(AuditLog::Captured(entries)) => {
+            entries.push(entry);
+            Ok(())
+        }
+        Some(AuditLog::File { path, file }) => {
+            if file.is_none() {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent).with_context(|| {
+                        format!("Failed to create audit directory {:?}", parent)
+                    })?;
+                }
+                let opened = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&*path)
+                    .with_context(|| format!("Failed to open audit log {:?}", path))?;
+                *file = Some(opened);
+            }
+            if let Some(file) = file.as_mut() {
+                writeln!(file, "{}", serde_json::to_string(&entry)?)
+                    .with_context(|| format!("Failed to write audit log {:?}", path))?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Read every entry of an audit log
+pub fn load(path: &Path) -> Result<Vec<AuditEntry>> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("Failed to read audit log {:?}", path))?;
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line).with_context(|| format!("Invalid audit log {:?}", path))
+        })
+        .collect()
+}
+
+/// Check a file against the last entry recorded for `original` in the log.
+/// `None` if the log has no entry for it, otherwise whether the contents match.
+pub fn verify(entries: &[AuditEntry], original: &Path, file: &Path) -> Option<bool> {
+    let entry = entries.iter().rev().find(|e| e.path == original)?;
+    Some(hash_file(file).is_some_and(|hash| hash == entry.sha256))
+}
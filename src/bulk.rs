@@ -0,0 +1,132 @@
+//! Bulk selection over lists of scan results: select everything matching a
+//! search, invert, select by extension or by age. Screens offer whichever
+//! of these make sense for their items through the action menu.
+
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// An entry in a list that can be selected in bulk
+pub trait Selectable {
+    /// Text matched by [`BulkAction::SelectMatching`]
+    fn label(&self) -> &str;
+
+    /// The file behind the entry, for extension matching
+    fn path(&self) -> Option<&Path> {
+        None
+    }
+
+    /// Last modification time, for age matching
+    fn modified(&self) -> Option<SystemTime> {
+        None
+    }
+
+    fn is_selected(&self) -> bool;
+
+    fn set_selected(&mut self, selected: bool);
+}
+
+/// Which bulk action, before its argument is known
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BulkActionKind {
+    SelectMatching,
+    Invert,
+    SelectExtension,
+    SelectOlderThan,
+}
+
+impl BulkActionKind {
+    /// Every action, for screens listing files
+    pub const ALL: [BulkActionKind; 4] = [
+        BulkActionKind::SelectMatching,
+        BulkActionKind::Invert,
+        BulkActionKind::SelectExtension,
+        BulkActionKind::SelectOlderThan,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BulkActionKind::SelectMatching => "Select all matching search",
+            BulkActionKind::Invert => "Invert selection",
+            BulkActionKind::SelectExtension => "Select by extension",
+            BulkActionKind::SelectOlderThan => "Select items older than N days",
+        }
+    }
+
+    /// Prompt for the argument, or `None` if the action takes none
+    pub fn prompt(&self) -> Option<&'static str> {
+        match self {
+            BulkActionKind::SelectMatching => Some("Search: "),
+            BulkActionKind::Invert => None,
+            BulkActionKind::SelectExtension => Some("Extension (e.g. log): "),
+            BulkActionKind::SelectOlderThan => Some("Days: "),
+        }
+    }
+
+    /// Build the action from the user's input. `None` if the input is invalid.
+    pub fn with_input(&self, input: &str) -> Option<BulkAction> {
+        let input = input.trim();
+        match self {
+            BulkActionKind::SelectMatching if !input.is_empty() => {
+                Some(BulkAction::SelectMatching(input.to_string()))
+            }
+            BulkActionKind::Invert => Some(BulkAction::Invert),
+            BulkActionKind::SelectExtension if !input.is_empty() => Some(
+                BulkAction::SelectExtension(input.trim_start_matches('.').to_string()),
+            ),
+            BulkActionKind::SelectOlderThan => input.parse().ok().map(BulkAction::SelectOlderThan),
+            _ => None,
+        }
+    }
+}
+
+/// A bulk selection change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BulkAction {
+    /// Select entries whose label contains the text, ignoring case
+    SelectMatching(String),
+    Invert,
+    /// Select files with this extension, ignoring case
+    SelectExtension(String),
+    /// Select entries last modified more than this many days ago
+    SelectOlderThan(u64),
+}
+
+impl BulkAction {
+    /// Apply the action to `items`, returning how many changed. Selecting
+    /// actions only ever add to the selection.
+    pub fn apply<'a, T: Selectable + 'a>(
+        &self,
+        items: impl IntoIterator<Item = &'a mut T>,
+        now: SystemTime,
+    ) -> usize {
+        let mut changed = 0;
+        for item in items {
+            let selected = match self {
+                BulkAction::Invert => !item.is_selected(),
+                action => item.is_selected() || action.matches(&*item, now),
+            };
+            if selected != item.is_selected() {
+                item.set_selected(selected);
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    fn matches(&self, item: &impl Selectable, now: SystemTime) -> bool {
+        match self {
+            BulkAction::SelectMatching(query) => {
+                item.label().to_lowercase().contains(&query.to_lowercase())
+            }
+            BulkAction::Invert => false,
+            BulkAction::SelectExtension(extension) => item
+                .path()
+                .and_then(Path::extension)
+                .is_some_and(|e| e.to_string_lossy().eq_ignore_ascii_case(extension)),
+            BulkAction::SelectOlderThan(days) => item.modified().is_some_and(|modified| {
+                now.duration_since(modified)
+                    .is_ok_and(|age| age > Duration::from_secs(days * 24 * 60 * 60))
+            }),
+        }
+    }
+}
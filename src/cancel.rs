@@ -0,0 +1,49 @@
+//! Cancellation of a running clean. Deletion loops and pipeline workers
+//! check the flag between files, so a cancelled run stops after the file in
+//! hand instead of being killed halfway through removing it. A cleaner that
+//! runs past its timeout is stopped the same way.
+
+use anyhow::{bail, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Set while the running cleaner is past its timeout
+static EXPIRED: AtomicBool = AtomicBool::new(false);
+
+/// Ask every deletion in progress to stop after its current file
+pub fn request() {
+    CANCELLED.store(true, Ordering::SeqCst);
+}
+
+/// Whether deletions should stop, because the run was cancelled or the
+/// running cleaner timed out
+pub fn is_requested() -> bool {
+    CANCELLED.load(Ordering::SeqCst) || EXPIRED.load(Ordering::SeqCst)
+}
+
+/// Clear a previous cancellation before starting a new run
+pub fn reset() {
+    CANCELLED.store(false, Ordering::SeqCst);
+}
+
+/// Stop the running cleaner, which has timed out
+pub fn expire() {
+    EXPIRED.store(true, Ordering::SeqCst);
+}
+
+/// Let the next cleaner run after one timed out
+pub fn clear_expired() {
+    EXPIRED.store(false, Ordering::SeqCst);
+}
+
+/// Error out if the run was cancelled or the cleaner timed out
+pub fn check() -> Result<()> {
+    if CANCELLED.load(Ordering::SeqCst) {
+        bail!("Operation cancelled by user");
+    }
+    if EXPIRED.load(Ordering::SeqCst) {
+        bail!("Cleaner timed out");
+    }
+    Ok(())
+}
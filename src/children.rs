@@ -0,0 +1,103 @@
+//! External commands spawned by cleaners (apt-get, journalctl, find, ...).
+//! Their pids are tracked while they run, so cancelling, quitting or a
+//! cleaner timing out terminates them instead of leaving them cleaning in
+//! the background after cleansys has exited.
+
+use once_cell::sync::Lazy;
+use std::io;
+use std::process::{Command, Output, Stdio};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// How long commands get to exit after SIGTERM before they are killed
+pub const TERM_GRACE: Duration = Duration::from_secs(2);
+
+static RUNNING: Lazy<Mutex<Vec<u32>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+fn running_mut() -> std::sync::MutexGuard<'static, Vec<u32>> {
+    RUNNING.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Pids of the tracked commands still running
+pub fn running() -> Vec<u32> {
+    running_mut().clone()
+}
+
+/// Run a command to completion like [`Command::output`], tracking it so
+/// [`terminate_all`] can stop it. The command stays in our process group,
+/// so a Ctrl+C in the terminal still reaches it.
+pub fn output(command: &mut Command) -> io::Result<Output> {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let child = command.spawn()?;
+    let pid = child.id();
+    running_mut().push(pid);
+    let output = child.wait_with_output();
+    running_mut().retain(|&p| p != pid);
+    output
+}
+
+/// Run a command to completion like [`output`], counting the bytes it
+/// writes to stdout instead of keeping them
+pub fn stdout_len(command: &mut Command) -> io::Result<u64> {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+
+    let mut child = command.spawn()?;
+    let pid = child.id();
+    running_mut().push(pid);
+    let len = match child.stdout.take() {
+        Some(mut stdout) => io::copy(&mut stdout, &mut io::sink()),
+        None => Ok(0),
+    };
+    let status = child.wait();
+    running_mut().retain(|&p| p != pid);
+    status?;
+    len
+}
+
+/// Stop every tracked command: SIGTERM first, then SIGKILL for those still
+/// running after [`TERM_GRACE`]. Returns how many had to be killed.
+#[cfg(unix)]
+pub fn terminate_all() -> usize {
+    let pids = running();
+    if pids.is_empty() {
+        return 0;
+    }
+    signal_all(&pids, libc::SIGTERM);
+
+    let deadline = std::time::Instant::now() + TERM_GRACE;
+    while std::time::Instant::now() < deadline {
+        if !running().iter().any(|pid| pids.contains(pid)) {
+            return 0;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+
+    let remaining: Vec<u32> = running()
+        .into_iter()
+        .filter(|pid| pids.contains(pid))
+        .collect();
+    signal_all(&remaining, libc::SIGKILL);
+    remaining.len()
+}
+
+#[cfg(not(unix))]
+pub fn terminate_all() -> usize {
+    0
+}
+
+#[cfg(unix)]
+fn signal_all(pids: &[u32], signal: libc::c_int) {
+    for &pid in pids {
+        // SAFETY: kill only sends a signal
+        unsafe {
+            libc::kill(pid as libc::pid_t, signal);
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use anyhow::Result;
+use log::warn;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::cache_dirs::{clean_cache_dirs, scan_cache_dirs};
+use super::{CleanerInfo, ScanReport, Tag};
+use crate::filesystem::{filesystem, home_dir, is_sandboxed};
+use crate::priority::throttle_deletion;
+use crate::utils::{confirm, format_size, print_success};
+
+/// Lists all available Android development cleaners with their descriptions.
+pub fn list_cleaners() -> Vec<String> {
+    get_cleaners()
+        .iter()
+        .map(|c| format!("{}: {}", c.name, c.description))
+        .collect()
+}
+
+/// Returns a vector of all available Android development cleaners.
+pub fn get_cleaners() -> Vec<CleanerInfo> {
+    vec![
+        CleanerInfo::new(
+            "Android Caches",
+            "Clean ~/.android/cache and the Android Gradle plugin build cache",
+            clean_android_caches,
+        )
+        .tags(&[Tag::Dev])
+        .scan(scan_android_caches)
+        .available(android_available),
+        CleanerInfo::new(
+            "AVD Snapshots",
+            "Remove emulator snapshots; AVDs cold boot next time",
+            clean_avd_snapshots,
+        )
+        .tags(&[Tag::Dev, Tag::Heavy])
+        .scan(scan_avd_snapshots)
+        .available(android_available)
+        .high_risk(),
+        CleanerInfo::new(
+            "Unused SDK System Images",
+            "Remove SDK system images that no AVD uses",
+            clean_unused_system_images,
+        )
+        .tags(&[Tag::Dev, Tag::Heavy])
+        .scan(scan_unused_system_images)
+        .available(android_available),
+    ]
+}
+
+/// The Android SDK: `$ANDROID_HOME`, `$ANDROID_SDK_ROOT` or ~/Android/Sdk
+fn sdk_dir() -> Result<PathBuf> {
+    if !is_sandboxed() {
+        let from_env = ["ANDROID_HOME", "ANDROID_SDK_ROOT"]
+            .iter()
+            .filter_map(std::env::var_os)
+            .find(|dir| !dir.is_empty());
+        if let Some(dir) = from_env {
+            return Ok(PathBuf::from(dir));
+        }
+    }
+    Ok(home_dir()?.join("Android/Sdk"))
+}
+
+/// Where AVDs live: `$ANDROID_AVD_HOME` or ~/.android/avd
+fn avd_dir() -> Result<PathBuf> {
+    if !is_sandboxed() {
+        if let Some(dir) = std::env::var_os("ANDROID_AVD_HOME").filter(|d| !d.is_empty()) {
+            return Ok(PathBuf::from(dir));
+        }
+    }
+    Ok(home_dir()?.join(".android/avd"))
+}
+
+fn cache_paths() -> Result<Vec<PathBuf>> {
+    let android = home_dir()?.join(".android");
+    Ok(vec![android.join("cache"), android.join("build-cache")])
+}
+
+fn snapshot_paths() -> Result<Vec<PathBuf>> {
+    Ok(avds(&avd_dir()?)
+        .into_iter()
+        .map(|avd| avd.join("snapshots"))
+        .collect())
+}
+
+fn android_available() -> bool {
+    home_dir().is_ok_and(|home| home.join(".android").exists())
+        || sdk_dir().is_ok_and(|sdk| sdk.exists())
+}
+
+/// The `<name>.avd` directories in `avd_dir`
+fn avds(avd_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(avd_dir) else {
+        return Vec::new();
+    };
+    let mut avds: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir() && path.extension().is_some_and(|e| e == "avd"))
+        .collect();
+    avds.sort();
+    avds
+}
+
+/// System images referenced by the AVDs in `avd_dir`, as paths relative to
+/// the SDK such as `system-images/android-34/google_apis/x86_64`
+pub fn used_system_images(avd_dir: &Path) -> HashSet<PathBuf> {
+    avds(avd_dir)
+        .iter()
+        .filter_map(|avd| fs::read_to_string(avd.join("config.ini")).ok())
+        .flat_map(|config| {
+            config
+                .lines()
+                .filter_map(|line| {
+                    let (key, value) = line.split_once('=')?;
+                    key.trim()
+                        .starts_with("image.sysdir.")
+                        .then(|| PathBuf::from(value.trim().trim_end_matches('/')))
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Installed system images (`system-images/<api>/<tag>/<abi>`) that no AVD
+/// uses, with their sizes
+pub fn unused_system_images(sdk_dir: &Path, avd_dir: &Path) -> Vec<(PathBuf, u64)> {
+    let used = used_system_images(avd_dir);
+    let mut unused = Vec::new();
+
+    for api in subdirs(&sdk_dir.join("system-images")) {
+        for tag in subdirs(&api) {
+            for abi in subdirs(&tag) {
+                let relative = abi.strip_prefix(sdk_dir).unwrap_or(&abi).to_path_buf();
+                if !used.contains(&relative) {
+                    let size = filesystem().size(&abi).unwrap_or(0);
+                    unused.push((abi, size));
+                }
+            }
+        }
+    }
+    unused
+}
+
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// `android-34/google_apis/x86_64` for an image path
+fn image_label(sdk_dir: &Path, image: &Path) -> String {
+    image
+        .strip_prefix(sdk_dir.join("system-images"))
+        .unwrap_or(image)
+        .to_string_lossy()
+        .to_string()
+}
+
+fn clean_android_caches(skip_confirmation: bool) -> Result<u64> {
+    clean_cache_dirs(&cache_paths()?, "Android", skip_confirmation)
+}
+
+fn clean_avd_snapshots(skip_confirmation: bool) -> Result<u64> {
+    clean_cache_dirs(&snapshot_paths()?, "AVD snapshot", skip_confirmation)
+}
+
+fn scan_android_caches() -> Result<ScanReport> {
+    scan_cache_dirs(&cache_paths()?)
+}
+
+fn scan_avd_snapshots() -> Result<ScanReport> {
+    scan_cache_dirs(&snapshot_paths()?)
+}
+
+fn scan_unused_system_images() -> Result<ScanReport> {
+    let sdk_dir = sdk_dir()?;
+    let mut report = ScanReport::new();
+    for (image, size) in unused_system_images(&sdk_dir, &avd_dir()?) {
+        report.add_entry(&image_label(&sdk_dir, &image), size, size);
+    }
+    Ok(report)
+}
+
+/// Ask about each unused image separately so the ones still wanted for new
+/// AVDs can be kept
+fn clean_unused_system_images(skip_confirmation: bool) -> Result<u64> {
+    let fs = filesystem();
+    let sdk_dir = sdk_dir()?;
+    let mut bytes_saved = 0;
+
+    for (image, size) in unused_system_images(&sdk_dir, &avd_dir()?) {
+        let label = image_label(&sdk_dir, &image);
+        if skip_confirmation
+            || confirm(
+                &format!(
+                    "Remove unused system image {} ({} to be freed)?",
+                    label,
+                    format_size(size)
+                ),
+                true,
+            )?
+        {
+            let freed = match fs.remove_dir_all(&image) {
+                Ok(freed) => freed,
+                Err(e) => {
+                    warn!("Failed to remove system image {}: {}", label, e);
+                    continue;
+                }
+            };
+            print_success(&format!("Removed system image {}", label));
+            bytes_saved += freed;
+            throttle_deletion();
+        }
+    }
+
+    Ok(bytes_saved)
+}
@@ -0,0 +1,102 @@
+//! Browser cache cleaners, part of the user cleaners. Built with the
+//! `browsers` feature.
+
+use anyhow::{Context, Result};
+use log::debug;
+
+use super::{CleanerInfo, Tag};
+use crate::filesystem::{filesystem, home_dir};
+use crate::running::{self, Processes};
+use crate::utils::{confirm, format_size, print_success};
+
+pub fn get_cleaners() -> Vec<CleanerInfo> {
+    vec![CleanerInfo::new(
+        "Browser Caches",
+        "Clean Firefox and Chrome/Chromium caches",
+        clean_browser_caches,
+    )
+    .tags(&[Tag::Browser, Tag::Privacy])
+    .docs(include_str!("docs/browser_caches.md"))]
+}
+
+fn clean_browser_caches(skip_confirmation: bool) -> Result<u64> {
+    let mut bytes_saved = 0;
+    let fs = filesystem();
+    let home_dir = home_dir()?;
+    let processes = Processes::snapshot();
+
+    // Firefox cache
+    let firefox_path = home_dir.join(".mozilla/firefox");
+    if fs.exists(&firefox_path) && !running::keep_cache("Browser Caches", "firefox", &processes) {
+        debug!("Firefox directory found at {:?}", firefox_path);
+
+        if let Ok(entries) = fs.read_dir(&firefox_path) {
+            for path in entries {
+                if path.is_dir()
+                    && path
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .ends_with(".default")
+                {
+                    let cache_path = path.join("cache2");
+
+                    if fs.exists(&cache_path) {
+                        let size = fs.size(&cache_path)?;
+
+                        if skip_confirmation
+                            || confirm(
+                                &format!(
+                                    "Clean Firefox cache ({} to be freed)?",
+                                    format_size(size)
+                                ),
+                                true,
+                            )?
+                        {
+                            bytes_saved += fs
+                                .remove_dir_all(&cache_path)
+                                .context("Failed to remove Firefox cache")?;
+                            print_success("Firefox cache cleaned");
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Chrome/Chromium cache
+    let chrome_paths = vec![
+        (
+            "google-chrome",
+            home_dir.join(".config/google-chrome/Default/Cache"),
+        ),
+        ("chromium", home_dir.join(".config/chromium/Default/Cache")),
+        ("google-chrome", home_dir.join(".cache/google-chrome")),
+        ("chromium", home_dir.join(".cache/chromium")),
+    ];
+
+    for (app, path) in chrome_paths {
+        if fs.exists(&path) && !running::keep_cache("Browser Caches", app, &processes) {
+            debug!("Chrome/Chromium cache found at {:?}", path);
+            let size = fs.size(&path)?;
+
+            if skip_confirmation
+                || confirm(
+                    &format!(
+                        "Clean Chrome/Chromium cache at {:?} ({} to be freed)?",
+                        path,
+                        format_size(size)
+                    ),
+                    true,
+                )?
+            {
+                bytes_saved += fs
+                    .remove_dir_all(&path)
+                    .context("Failed to remove Chrome/Chromium cache")?;
+                print_success(&format!("Chrome/Chromium cache at {:?} cleaned", path));
+            }
+        }
+    }
+
+    Ok(bytes_saved)
+}
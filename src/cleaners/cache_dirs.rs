@@ -0,0 +1,194 @@
+//! Scanning and emptying plain cache directories, shared by cleaners whose
+//! tools keep their caches in a few well-known places, and removing lists
+//! of files cleaners have picked out.
+
+use anyhow::Result;
+use log::debug;
+use std::path::{Path, PathBuf};
+
+use super::cleaned::{self, CleanedItemType};
+use super::ScanReport;
+use crate::filesystem::filesystem;
+use crate::priority::throttle_deletion;
+use crate::protection::{self, Skipped};
+use crate::scanner::Reclaimable;
+use crate::utils::{confirm, format_size, print_success, print_warning};
+
+/// Report the size of each existing cache directory, and what emptying it
+/// would free
+pub(crate) fn scan_cache_dirs(paths: &[PathBuf]) -> Result<ScanReport> {
+    let fs = filesystem();
+    let mut report = ScanReport::new();
+    let mut reclaimable = Reclaimable::new();
+    for path in paths.iter().filter(|p| fs.exists(p)) {
+        let (size, usage) = reclaimable.measure(path);
+        report.add_measured_entry(
+            &path.to_string_lossy(),
+            size,
+            usage.on_disk,
+            usage.reclaimable,
+        );
+    }
+    Ok(report)
+}
+
+/// Empty each cache directory, keeping the directory itself so the tool
+/// doesn't have to recreate it with the right permissions.
+pub(crate) fn clean_cache_dirs(
+    paths: &[PathBuf],
+    tool: &str,
+    skip_confirmation: bool,
+) -> Result<u64> {
+    let fs = filesystem();
+    let mut bytes_saved = 0;
+
+    for path in paths {
+        if !fs.exists(path) {
+            debug!("No {} cache at {:?}", tool, path);
+            continue;
+        }
+
+        let size = fs.size(path)?;
+        if size == 0 {
+            continue;
+        }
+        // One notice for the whole directory rather than a failure per entry
+        if let Some(protection) = protection::contents_protection(path) {
+            protection::record(path, protection);
+            print_warning(&format!(
+                "Skipped {} cache {}: {}",
+                tool,
+                path.display(),
+                protection
+            ));
+            continue;
+        }
+
+        if skip_confirmation
+            || confirm(
+                &format!(
+                    "Clean {} cache at {:?} ({} to be freed)?",
+                    tool,
+                    path,
+                    format_size(size)
+                ),
+                true,
+            )?
+        {
+            // Excluded and recent files stay, so only what went counts
+            let freed = match empty_dir(path) {
+                Ok(freed) => freed,
+                Err(e) => {
+                    cleaned::record_error(path, &e);
+                    continue;
+                }
+            };
+            print_success(&format!("Removed {} cache {}", tool, path.display()));
+            cleaned::record(path, freed, CleanedItemType::Directory);
+            bytes_saved += freed;
+            throttle_deletion();
+        }
+    }
+
+    Ok(bytes_saved)
+}
+
+/// Report each file with its size, and what removing it would free
+pub(crate) fn scan_files(files: &[(PathBuf, u64)]) -> ScanReport {
+    let mut report = ScanReport::new();
+    let mut reclaimable = Reclaimable::new();
+    for (path, size) in files {
+        let usage = reclaimable.add(path);
+        report.add_measured_entry(
+            &path.to_string_lossy(),
+            *size,
+            usage.on_disk,
+            usage.reclaimable,
+        );
+    }
+    report
+}
+
+/// Remove `files`, and directories with everything in them, after one
+/// confirmation for all of them
+pub(crate) fn remove_files(
+    files: Vec<(PathBuf, u64)>,
+    what: &str,
+    skip_confirmation: bool,
+) -> Result<u64> {
+    remove_files_unless(files, what, skip_confirmation, |_| false)
+}
+
+/// [`remove_files`], keeping those `keep` tells to right before each is
+/// removed
+pub(crate) fn remove_files_unless(
+    files: Vec<(PathBuf, u64)>,
+    what: &str,
+    skip_confirmation: bool,
+    keep: impl Fn(&Path) -> bool,
+) -> Result<u64> {
+    if files.is_empty() {
+        return Ok(0);
+    }
+    let size: u64 = files.iter().map(|(_, size)| size).sum();
+    if !skip_confirmation
+        && !confirm(
+            &format!(
+                "Remove {} {} ({} to be freed)?",
+                files.len(),
+                what,
+                format_size(size)
+            ),
+            true,
+        )?
+    {
+        return Ok(0);
+    }
+
+    let mut bytes_saved = 0;
+    let fs = filesystem();
+    for (path, _) in files {
+        if keep(&path) {
+            debug!("Keeping {:?}", path);
+            continue;
+        }
+        let is_dir = path.is_dir();
+        let removed = if is_dir {
+            fs.remove_dir_all(&path)
+        } else {
+            fs.remove_file(&path)
+        };
+        let size = match removed {
+            Ok(size) => size,
+            Err(e) => {
+                cleaned::record_error(&path, &e);
+                continue;
+            }
+        };
+        cleaned::record_path(&path, size, is_dir);
+        bytes_saved += size;
+        throttle_deletion();
+    }
+    print_success(&format!("Removed {}", what));
+    Ok(bytes_saved)
+}
+
+/// Remove everything in a directory, returning the bytes unlinked
+fn empty_dir(path: &Path) -> Result<u64> {
+    let fs = filesystem();
+    let mut freed = 0;
+    for entry_path in fs.read_dir(path)? {
+        let removed = if entry_path.is_dir() {
+            fs.remove_dir_all(&entry_path)
+        } else {
+            fs.remove_file(&entry_path)
+        };
+        match removed {
+            Ok(bytes) => freed += bytes,
+            // Excluded and protected entries stay, recorded by the deleter
+            Err(e) if e.is::<Skipped>() => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(freed)
+}
@@ -0,0 +1,116 @@
+//! What each cleaner removed, path by path. Cleaners record every file or
+//! directory they remove, with the bytes it freed, and whoever runs them
+//! takes the entries after each cleaner, like [`crate::deferred`] does for
+//! space freed after a reboot. The TUI lists them as removed items, so it
+//! doesn't have to pick paths and sizes out of what the cleaner printed.
+//! Paths a cleaner failed to remove are recorded too, so a cleaner that
+//! removed some and failed on others can be told apart from one that did
+//! everything it set out to. Permission errors are only counted: walking a
+//! shared directory such as /tmp can run into thousands of other users'
+//! files, and a warning for each would bury everything else in the log.
+
+use log::{debug, warn};
+use once_cell::sync::Lazy;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// What kind of thing a cleaner removed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CleanedItemType {
+    File,
+    /// A directory, or everything in it when the directory itself is kept
+    Directory,
+    Log,
+}
+
+/// A file or directory a cleaner removed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CleanedEntry {
+    /// What was removed: a path, or a package or unit whose files a tool
+    /// removed
+    pub path: PathBuf,
+    /// Bytes freed by removing it
+    pub bytes: u64,
+    pub kind: CleanedItemType,
+}
+
+/// Entries recorded since the last [`take`]
+static CLEANED: Lazy<Mutex<Vec<CleanedEntry>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Paths that couldn't be removed since the last [`take_failures`], with why
+static FAILED: Lazy<Mutex<Vec<(PathBuf, String)>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Paths left in place for lack of permission since the last [`take_denied`]
+static DENIED: Lazy<Mutex<usize>> = Lazy::new(|| Mutex::new(0));
+
+/// Note that `path` was removed, freeing `bytes`
+pub fn record(path: &Path, bytes: u64, kind: CleanedItemType) {
+    CLEANED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(CleanedEntry {
+            path: path.to_path_buf(),
+            bytes,
+            kind,
+        });
+}
+
+/// Note a removed file or directory, telling which from `was_dir`
+pub fn record_path(path: &Path, bytes: u64, was_dir: bool) {
+    let kind = if was_dir {
+        CleanedItemType::Directory
+    } else {
+        CleanedItemType::File
+    };
+    record(path, bytes, kind);
+}
+
+/// The entries recorded since the last call
+pub fn take() -> Vec<CleanedEntry> {
+    std::mem::take(&mut *CLEANED.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// Note that `path` couldn't be removed
+pub fn record_failure(path: &Path, error: impl std::fmt::Display) {
+    FAILED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push((path.to_path_buf(), error.to_string()));
+}
+
+/// The paths that couldn't be removed since the last call
+pub fn take_failures() -> Vec<(PathBuf, String)> {
+    std::mem::take(&mut *FAILED.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// Note that removing `path` failed with `error`: counted when permission
+/// was denied, otherwise warned about and recorded as a failure
+pub fn record_error(path: &Path, error: &anyhow::Error) {
+    if is_permission_denied(error) {
+        debug!("Permission denied removing {:?}: {:#}", path, error);
+        *DENIED.lock().unwrap_or_else(|e| e.into_inner()) += 1;
+    } else {
+        warn!("Failed to remove {:?}: {:#}", path, error);
+        record_failure(path, format!("{:#}", error));
+    }
+}
+
+/// Add `count` paths left for lack of permission, counted elsewhere
+pub fn add_denied(count: usize) {
+    *DENIED.lock().unwrap_or_else(|e| e.into_inner()) += count;
+}
+
+/// How many paths were left for lack of permission since the last call
+pub fn take_denied() -> usize {
+    std::mem::take(&mut *DENIED.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// Whether `error` comes from missing permissions (`EACCES` or `EPERM`)
+pub fn is_permission_denied(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause
+            .downcast_ref::<io::Error>()
+            .is_some_and(|e| e.kind() == io::ErrorKind::PermissionDenied)
+    })
+}
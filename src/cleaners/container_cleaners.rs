@@ -0,0 +1,178 @@
+use anyhow::{Context, Result};
+use log::{debug, info, warn};
+use std::process::Command;
+
+use super::{CleanerInfo, ScanReport, Tag};
+use crate::children;
+use crate::filesystem::is_sandboxed;
+use crate::utils::{
+    command_exists, confirm, format_size, parse_si_size, print_success, print_warning,
+};
+
+/// Lists all available container cleaners with their descriptions.
+pub fn list_cleaners() -> Vec<String> {
+    get_cleaners()
+        .iter()
+        .map(|c| format!("{}: {}", c.name, c.description))
+        .collect()
+}
+
+/// Returns a vector of all available container cleaners.
+pub fn get_cleaners() -> Vec<CleanerInfo> {
+    vec![
+        CleanerInfo::new(
+            "Docker",
+            "Prune stopped containers, dangling images, unused networks and build cache",
+            clean_docker,
+        )
+        .tags(&[Tag::Dev, Tag::Heavy])
+        .scan(scan_docker)
+        .available(docker_available),
+        CleanerInfo::new(
+            "Podman",
+            "Prune stopped containers, dangling images, unused networks and build cache",
+            clean_podman,
+        )
+        .tags(&[Tag::Dev, Tag::Heavy])
+        .scan(scan_podman)
+        .available(podman_available),
+    ]
+}
+
+fn clean_docker(skip_confirmation: bool) -> Result<u64> {
+    prune_engine("docker", skip_confirmation)
+}
+
+fn clean_podman(skip_confirmation: bool) -> Result<u64> {
+    prune_engine("podman", skip_confirmation)
+}
+
+fn scan_docker() -> Result<ScanReport> {
+    engine_disk_usage("docker")
+}
+
+fn scan_podman() -> Result<ScanReport> {
+    engine_disk_usage("podman")
+}
+
+fn docker_available() -> bool {
+    engine_available("docker")
+}
+
+fn podman_available() -> bool {
+    engine_available("podman")
+}
+
+fn engine_available(engine: &str) -> bool {
+    command_exists(engine)
+}
+
+/// Query `<engine> system df` and turn it into a breakdown of
+/// images, containers, volumes and build cache.
+fn engine_disk_usage(engine: &str) -> Result<ScanReport> {
+    if !engine_available(engine) {
+        return Err(anyhow::anyhow!("{} is not installed", engine));
+    }
+
+    let output = children::output(Command::new(engine).args([
+        "system",
+        "df",
+        "--format",
+        "{{.Type}}\t{{.Size}}\t{{.Reclaimable}}",
+    ]))
+    .context(format!("Failed to execute {} system df", engine))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow::anyhow!(
+            "{} system df failed: {}",
+            engine,
+            stderr.trim()
+        ));
+    }
+
+    Ok(parse_system_df(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse the tab separated `system df` output. The reclaimable column looks
+/// like "1.2GB (40%)", so only the leading size is used.
+fn parse_system_df(output: &str) -> ScanReport {
+    let mut report = ScanReport::new();
+
+    for line in output.lines() {
+        let columns: Vec<&str> = line.split('\t').collect();
+        if columns.len() < 3 {
+            continue;
+        }
+
+        let size = parse_si_size(columns[1]).unwrap_or(0);
+        let reclaimable = columns[2]
+            .split_whitespace()
+            .next()
+            .and_then(parse_si_size)
+            .unwrap_or(0);
+
+        report.add_entry(columns[0].trim(), size, reclaimable);
+    }
+
+    report
+}
+
+fn prune_engine(engine: &str, skip_confirmation: bool) -> Result<u64> {
+    // The engine daemon is real even when paths are sandboxed
+    if is_sandboxed() {
+        debug!("Sandboxed, skipping {} prune", engine);
+        return Ok(0);
+    }
+
+    if !engine_available(engine) {
+        debug!("{} not found, skipping", engine);
+        return Ok(0);
+    }
+
+    let estimate = match engine_disk_usage(engine) {
+        Ok(report) => report.total_reclaimable(),
+        Err(e) => {
+            warn!("Could not query {} disk usage: {}", engine, e);
+            0
+        }
+    };
+
+    if !skip_confirmation
+        && !confirm(
+            &format!(
+                "Prune unused {} data ({} reclaimable)?",
+                engine,
+                format_size(estimate)
+            ),
+            true,
+        )?
+    {
+        return Ok(0);
+    }
+
+    info!("Pruning unused {} data...", engine);
+    let output = children::output(Command::new(engine).args(["system", "prune", "--force"]))
+        .context(format!("Failed to execute {} system prune", engine))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        print_warning(&format!("{} prune failed: {}", engine, stderr.trim()));
+        return Ok(0);
+    }
+
+    // Both engines finish with "Total reclaimed space: 1.2GB"
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let reclaimed = stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("Total reclaimed space:"))
+        .and_then(parse_si_size)
+        .unwrap_or(estimate);
+
+    print_success(&format!(
+        "Pruned unused {} data ({} freed)",
+        engine,
+        format_size(reclaimed)
+    ));
+    Ok(reclaimed)
+}
@@ -0,0 +1,174 @@
+//! Print spool cleaner for CUPS. cupsd keeps a control file (`c<job>`) for
+//! every finished job so it can show the job history, plus the document
+//! itself (`d<job>-<n>`) with `PreserveJobFiles`, and caches PPD data in
+//! /var/cache/cups. Only jobs whose control file says they are completed,
+//! cancelled or aborted are removed; `print_jobs_keep_days` keeps recent
+//! ones in the history.
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use super::cache_dirs::{remove_files, scan_files};
+use super::{CleanerInfo, ScanReport};
+use crate::filesystem::root_path;
+
+/// IPP `job-state` values of jobs that are over: canceled, aborted and
+/// completed
+const FINISHED_JOB_STATES: [u32; 3] = [7, 8, 9];
+
+static KEEP_DAYS: Lazy<Mutex<Option<u64>>> = Lazy::new(|| Mutex::new(None));
+
+/// Keep finished jobs younger than this many days, from the config file
+pub fn set_keep_days(days: Option<u64>) {
+    *KEEP_DAYS.lock().unwrap_or_else(|e| e.into_inner()) = days;
+}
+
+fn keep_days() -> Option<u64> {
+    *KEEP_DAYS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn get_cleaners() -> Vec<CleanerInfo> {
+    vec![CleanerInfo::new(
+        "Print Spool",
+        "Clean finished CUPS jobs in /var/spool/cups and cached PPD data in /var/cache/cups",
+        clean_print_spool,
+    )
+    .scan(scan_print_spool)
+    .available(cups_available)]
+}
+
+fn spool_dir() -> PathBuf {
+    root_path("/var/spool/cups")
+}
+
+fn cache_dir() -> PathBuf {
+    root_path("/var/cache/cups")
+}
+
+fn cups_available() -> bool {
+    spool_dir().is_dir() || cache_dir().is_dir()
+}
+
+/// The `job-state` attribute in an IPP-encoded CUPS control file: the name
+/// is followed by a 4-byte length and the big-endian enum value
+pub fn job_state(control: &[u8]) -> Option<u32> {
+    const NAME: &[u8] = b"job-state";
+    control.windows(NAME.len() + 6).find_map(|window| {
+        let (name, rest) = window.split_at(NAME.len());
+        (name == NAME && rest[..2] == [0, 4])
+            .then(|| u32::from_be_bytes([rest[2], rest[3], rest[4], rest[5]]))
+    })
+}
+
+/// The job number of a control (`c00042`) or document (`d00042-001`) file
+fn job_id(file_name: &str) -> Option<(char, u32)> {
+    let kind = file_name
+        .chars()
+        .next()
+        .filter(|c| *c == 'c' || *c == 'd')?;
+    let digits = file_name[1..].split('-').next()?;
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some((kind, digits.parse().ok()?))
+}
+
+/// Files of finished jobs in `dir`, leaving jobs whose control file was
+/// written within `keep` before `now`
+pub fn finished_job_files(
+    dir: &Path,
+    keep: Option<Duration>,
+    now: SystemTime,
+) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let files: Vec<(PathBuf, char, u32, fs::Metadata)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let (kind, id) = job_id(&entry.file_name().to_string_lossy())?;
+            let metadata = entry.path().symlink_metadata().ok()?;
+            metadata
+                .is_file()
+                .then_some((entry.path(), kind, id, metadata))
+        })
+        .collect();
+
+    let finished: Vec<u32> = files
+        .iter()
+        .filter(|(_, kind, _, _)| *kind == 'c')
+        .filter(|(_, _, _, metadata)| {
+            let recent = keep.is_some_and(|keep| {
+                metadata
+                    .modified()
+                    .ok()
+                    .and_then(|modified| now.duration_since(modified).ok())
+                    .is_some_and(|age| age < keep)
+            });
+            !recent
+        })
+        .filter(|(path, _, _, _)| {
+            fs::read(path)
+                .ok()
+                .and_then(|control| job_state(&control))
+                .is_some_and(|state| FINISHED_JOB_STATES.contains(&state))
+        })
+        .map(|(_, _, id, _)| *id)
+        .collect();
+
+    let mut removable: Vec<(PathBuf, u64)> = files
+        .into_iter()
+        .filter(|(_, _, id, _)| finished.contains(id))
+        .map(|(path, _, _, metadata)| (path, metadata.len()))
+        .collect();
+    removable.sort();
+    removable
+}
+
+/// PPD data cupsd regenerates on demand: the PPD index `ppds.dat` and the
+/// per-printer `.data` capability caches
+fn ppd_caches() -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = fs::read_dir(cache_dir()) else {
+        return Vec::new();
+    };
+    let mut caches: Vec<(PathBuf, u64)> = entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name == "ppds.dat" || name.ends_with(".data")
+        })
+        .filter_map(|entry| {
+            let metadata = entry.path().symlink_metadata().ok()?;
+            metadata.is_file().then_some((entry.path(), metadata.len()))
+        })
+        .collect();
+    caches.sort();
+    caches
+}
+
+fn print_spool_files() -> Vec<(PathBuf, u64)> {
+    let keep = keep_days().map(|days| Duration::from_secs(days * 24 * 60 * 60));
+    let mut files = finished_job_files(&spool_dir(), keep, SystemTime::now());
+    files.extend(ppd_caches());
+    files
+}
+
+fn scan_print_spool() -> Result<ScanReport> {
+    let mut report = scan_files(&print_spool_files());
+    if let Some(days) = keep_days() {
+        report.add_note(format!("Jobs from the last {} days are kept", days));
+    }
+    Ok(report)
+}
+
+fn clean_print_spool(skip_confirmation: bool) -> Result<u64> {
+    remove_files(
+        print_spool_files(),
+        "finished print jobs and PPD caches",
+        skip_confirmation,
+    )
+}
@@ -0,0 +1,401 @@
+//! Cleaners declared in the config file, for application junk cleansys
+//! doesn't know about:
+//!
+//! ```toml
+//! [[custom_cleaners]]
+//! name = "Zoom Logs"
+//! paths = ["~/.zoom/logs/*.log"]
+//! older_than_days = 14
+//! ```
+//!
+//! Each one removes the files its globs match (using the exclusion glob
+//! syntax, see [`crate::exclude`]), optionally only those not modified for
+//! a number of days, and shows up next to the built-in cleaners: user ones
+//! in User Land Cleaners, `scope = "system"` ones in System Cleaners, run
+//! as root. Globs that are relative, climb out with `..`, or would reach
+//! into system directories, credentials or a whole home or data directory
+//! are rejected when the config is loaded.
+
+use anyhow::{bail, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use super::cache_dirs::remove_files;
+use super::{CleanerInfo, ScanReport};
+use crate::exclude::{expand, glob_matches};
+
+/// Custom cleaners past this many are ignored
+pub const MAX_CUSTOM_CLEANERS: usize = 16;
+
+/// Directories a glob may not reach into at all
+const NEVER_CLEANED: [&str; 12] = [
+    "/bin", "/boot", "/dev", "/etc", "/lib", "/lib64", "/proc", "/sbin", "/sys", "/usr", "~/.ssh",
+    "~/.gnupg",
+];
+
+/// Directories a glob may reach into, but not wipe as a whole: below them
+/// it has to name something, with at least [`MIN_LITERAL`] characters that
+/// aren't wildcards (`~/.config/myapp*` but not `~/.config/*`). Right
+/// below `/` and `~` the first component may not have wildcards at all.
+pub(crate) const NOT_WHOLE: [&str; 13] = [
+    "/",
+    "/home",
+    "/opt",
+    "/root",
+    "/run",
+    "/srv",
+    "/tmp",
+    "/var",
+    "/var/lib",
+    "~",
+    "~/.config",
+    "~/.local",
+    "~/.local/share",
+];
+
+/// Characters a glob component right below a [`NOT_WHOLE`] directory needs
+/// besides wildcards
+const MIN_LITERAL: usize = 3;
+
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// Whether a custom cleaner runs as the user or as root
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomScope {
+    #[default]
+    User,
+    System,
+}
+
+/// A cleaner from `[[custom_cleaners]]` in the config file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CustomCleaner {
+    pub name: String,
+    /// Shown in the details pane, defaults to the globs
+    pub description: Option<String>,
+    /// Globs for the files to remove, absolute or starting with `~/`
+    pub paths: Vec<String>,
+    /// Only remove files not modified for this many days
+    pub older_than_days: Option<u64>,
+    pub scope: CustomScope,
+}
+
+impl CustomCleaner {
+    /// Check the name, the age and every glob, see [`check_glob`]
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            bail!("a custom cleaner needs a name");
+        }
+        if self.paths.is_empty() {
+            bail!("{:?} has no paths", self.name);
+        }
+        if self
+            .older_than_days
+            .is_some_and(|days| days.checked_mul(DAY_SECS).is_none())
+        {
+            bail!("{:?}: older_than_days is too large", self.name);
+        }
+        for glob in &self.paths {
+            if let Err(e) = check_glob(glob, self.scope) {
+                bail!("{:?}: {}", self.name, e);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The directory part of a glob before its first wildcard
+fn literal_prefix(glob: &str) -> String {
+    let components: Vec<&str> = glob
+        .split('/')
+        .take_while(|component| !component.contains(['*', '?']))
+        .filter(|component| !component.is_empty())
+        .collect();
+    match glob.strip_prefix("~/") {
+        Some(_) => components.join("/"),
+        None => format!("/{}", components.join("/")),
+    }
+}
+
+/// Whether the directory `path` is `dir` or below it, both written as in
+/// the config (`~/...` or absolute)
+fn is_within(path: &str, dir: &str) -> bool {
+    path == dir
+        || path
+            .strip_prefix(dir)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Whether a glob can match `dir` or anything below it, wildcards included:
+/// its first components match those of `dir`, or the glob stops above it
+fn reaches_into(glob: &str, dir: &str) -> bool {
+    let depth = dir.split('/').filter(|c| !c.is_empty()).count();
+    let components: Vec<&str> = glob.split('/').filter(|c| !c.is_empty()).collect();
+    let head = components[..depth.min(components.len())].join("/");
+    glob_matches(&head, Path::new(dir))
+}
+
+/// Whether a glob is safe to clean: absolute or in the home directory,
+/// without `..`, outside system directories and credentials, and not
+/// matching the whole of a home or top-level directory. User cleaners stay
+/// in the home directory and the temporary directories.
+pub fn check_glob(glob: &str, scope: CustomScope) -> Result<()> {
+    if !glob.starts_with('/') && !glob.starts_with("~/") {
+        bail!("{:?} must be absolute or start with ~/", glob);
+    }
+    if glob.split('/').any(|component| component == "..") {
+        bail!("{:?} may not contain ..", glob);
+    }
+    let prefix = literal_prefix(glob);
+    if prefix == "/" || prefix == "~" {
+        bail!(
+            "{:?} needs a directory without wildcards below {}",
+            glob,
+            prefix
+        );
+    }
+    if let Some(dir) = NEVER_CLEANED.iter().find(|dir| reaches_into(glob, dir)) {
+        bail!("{:?} reaches into {}", glob, dir);
+    }
+    if NOT_WHOLE.contains(&prefix.as_str()) {
+        let named = glob
+            .split('/')
+            .find(|component| component.contains(['*', '?']))
+            .is_some_and(|component| {
+                component
+                    .chars()
+                    .filter(|c| !matches!(c, '*' | '?'))
+                    .count()
+                    >= MIN_LITERAL
+            });
+        if !named {
+            bail!("{:?} would clean all of {}", glob, prefix);
+        }
+    }
+    let in_home = prefix == "~" || prefix.starts_with("~/");
+    match scope {
+        CustomScope::User
+            if !in_home && !is_within(&prefix, "/tmp") && !is_within(&prefix, "/var/tmp") =>
+        {
+            bail!("{:?} is outside the home and temporary directories", glob)
+        }
+        CustomScope::System if in_home => {
+            bail!("{:?} is in a home directory; use scope = \"user\"", glob)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// A custom cleaner that passed validation, with its name and description
+/// kept for the rest of the process as [`CleanerInfo`] needs
+struct Registered {
+    name: &'static str,
+    description: &'static str,
+    cleaner: CustomCleaner,
+}
+
+static REGISTERED: Lazy<Mutex<Vec<Registered>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+fn registered() -> std::sync::MutexGuard<'static, Vec<Registered>> {
+    REGISTERED.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Register the custom cleaners from the config file. Invalid ones, those
+/// named like another cleaner and those past [`MAX_CUSTOM_CLEANERS`] are
+/// left out; the returned messages say why.
+pub fn set_custom_cleaners(cleaners: Vec<CustomCleaner>) -> Vec<String> {
+    registered().clear();
+    let taken: Vec<&str> = super::groups()
+        .iter()
+        .flat_map(|group| group.cleaners.iter().map(|cleaner| cleaner.name))
+        .collect();
+
+    let mut problems = Vec::new();
+    let mut accepted: Vec<Registered> = Vec::new();
+    for cleaner in cleaners {
+        let name = cleaner.name.trim().to_string();
+        if let Err(e) = cleaner.validate() {
+            problems.push(format!("Ignoring custom cleaner {:#}", e));
+        } else if taken.contains(&name.as_str()) || accepted.iter().any(|other| other.name == name)
+        {
+            problems.push(format!(
+                "Ignoring custom cleaner {:?}: another cleaner has that name",
+                name
+            ));
+        } else if accepted.len() == MAX_CUSTOM_CLEANERS {
+            problems.push(format!(
+                "Ignoring custom cleaner {:?}: only {} are supported",
+                name, MAX_CUSTOM_CLEANERS
+            ));
+        } else {
+            let description = cleaner
+                .description
+                .clone()
+                .unwrap_or_else(|| format!("Custom: remove {}", cleaner.paths.join(", ")));
+            accepted.push(Registered {
+                name: name.leak(),
+                description: description.leak(),
+                cleaner,
+            });
+        }
+    }
+    *registered() = accepted;
+    problems
+}
+
+/// The registered custom cleaners of one scope
+pub fn get_cleaners(scope: CustomScope) -> Vec<CleanerInfo> {
+    registered()
+        .iter()
+        .enumerate()
+        .filter(|(_, registered)| registered.cleaner.scope == scope)
+        .map(|(slot, registered)| {
+            CleanerInfo::new(registered.name, registered.description, CLEAN_SLOTS[slot])
+                .scan(SCAN_SLOTS[slot])
+        })
+        .collect()
+}
+
+// CleanerInfo takes plain function pointers, so each registered cleaner
+// gets its own pair from these tables, by position
+const CLEAN_SLOTS: [fn(bool) -> Result<u64>; MAX_CUSTOM_CLEANERS] = [
+    clean_slot::<0>,
+    clean_slot::<1>,
+    clean_slot::<2>,
+    clean_slot::<3>,
+    clean_slot::<4>,
+    clean_slot::<5>,
+    clean_slot::<6>,
+    clean_slot::<7>,
+    clean_slot::<8>,
+    clean_slot::<9>,
+    clean_slot::<10>,
+    clean_slot::<11>,
+    clean_slot::<12>,
+    clean_slot::<13>,
+    clean_slot::<14>,
+    clean_slot::<15>,
+];
+
+const SCAN_SLOTS: [fn() -> Result<ScanReport>; MAX_CUSTOM_CLEANERS] = [
+    scan_slot::<0>,
+    scan_slot::<1>,
+    scan_slot::<2>,
+    scan_slot::<3>,
+    scan_slot::<4>,
+    scan_slot::<5>,
+    scan_slot::<6>,
+    scan_slot::<7>,
+    scan_slot::<8>,
+    scan_slot::<9>,
+    scan_slot::<10>,
+    scan_slot::<11>,
+    scan_slot::<12>,
+    scan_slot::<13>,
+    scan_slot::<14>,
+    scan_slot::<15>,
+];
+
+fn slot(index: usize) -> Option<(&'static str, CustomCleaner)> {
+    registered()
+        .get(index)
+        .map(|registered| (registered.name, registered.cleaner.clone()))
+}
+
+fn clean_slot<const SLOT: usize>(skip_confirmation: bool) -> Result<u64> {
+    let Some((name, cleaner)) = slot(SLOT) else {
+        return Ok(0);
+    };
+    let files = matching_files(&cleaner, SystemTime::now())
+        .into_values()
+        .flatten()
+        .collect();
+    remove_files(files, &format!("files for {}", name), skip_confirmation)
+}
+
+fn scan_slot<const SLOT: usize>() -> Result<ScanReport> {
+    let mut report = ScanReport::new();
+    let Some((_, cleaner)) = slot(SLOT) else {
+        return Ok(report);
+    };
+    for (glob, files) in matching_files(&cleaner, SystemTime::now()) {
+        let size = files.iter().map(|(_, size)| size).sum();
+        report.add_entry(&glob, size, size);
+    }
+    if let Some(days) = cleaner.older_than_days {
+        report.add_note(format!("Only files unchanged for {} days", days));
+    }
+    Ok(report)
+}
+
+/// The files each glob of `cleaner` matches, old enough to remove as of
+/// `now`. A file matched by several globs is listed under the first.
+pub fn matching_files(
+    cleaner: &CustomCleaner,
+    now: SystemTime,
+) -> BTreeMap<String, Vec<(PathBuf, u64)>> {
+    // Too many days to count are older than any file can be
+    let min_age = cleaner
+        .older_than_days
+        .map(|days| Duration::from_secs(days.saturating_mul(DAY_SECS)));
+    let old_enough = |modified: std::io::Result<SystemTime>| match min_age {
+        None => true,
+        Some(min_age) => {
+            modified.is_ok_and(|time| now.duration_since(time).is_ok_and(|age| age >= min_age))
+        }
+    };
+
+    let mut seen = HashSet::new();
+    let mut matches = BTreeMap::new();
+    for glob in &cleaner.paths {
+        let pattern = expand(glob);
+        let mut files = Vec::new();
+        for (path, metadata) in walk(Path::new(&expand(&literal_prefix(glob)))) {
+            if glob_matches(&pattern, &path)
+                && old_enough(metadata.modified())
+                && seen.insert(path.clone())
+            {
+                files.push((path, metadata.len()));
+            }
+        }
+        matches.insert(glob.clone(), files);
+    }
+    matches
+}
+
+/// Every file below `dir`, without following symlinks, or `dir` itself
+/// when it is a file
+fn walk(dir: &Path) -> Vec<(PathBuf, fs::Metadata)> {
+    let mut found = Vec::new();
+    if let Ok(metadata) = dir.symlink_metadata() {
+        if !metadata.is_dir() {
+            found.push((dir.to_path_buf(), metadata));
+            return found;
+        }
+    }
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.path().symlink_metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                stack.push(entry.path());
+            } else {
+                found.push((entry.path(), metadata));
+            }
+        }
+    }
+    found.sort_by(|a, b| a.0.cmp(&b.0));
+    found
+}
@@ -0,0 +1,271 @@
+//! Developer package caches in the home directory: pip, npm, cargo and
+//! yarn, Composer, RubyGems and NuGet. Part of the user cleaners, built
+//! with the `dev-caches` feature.
+
+use anyhow::Result;
+use log::{debug, warn};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use super::cache_dirs::{clean_cache_dirs, scan_cache_dirs};
+use super::{CleanerInfo, ScanReport, Tag};
+use crate::children;
+use crate::filesystem::{filesystem, home_dir, is_sandboxed};
+use crate::priority::throttle_deletion;
+use crate::utils::{command_exists, confirm, format_size, print_success};
+
+/// NuGet packages not restored for this long are removed
+pub const NUGET_MAX_AGE: Duration = Duration::from_secs(90 * 24 * 60 * 60);
+
+pub fn get_cleaners() -> Vec<CleanerInfo> {
+    vec![
+        CleanerInfo::new(
+            "Package Manager Caches",
+            "Clean user package manager caches like pip, npm, cargo",
+            clean_package_caches,
+        )
+        .tags(&[Tag::Dev, Tag::Packages, Tag::Heavy]),
+        CleanerInfo::new(
+            "Composer Cache",
+            "Clean PHP Composer's download cache in ~/.cache/composer",
+            clean_composer_cache,
+        )
+        .tags(&[Tag::Dev, Tag::Packages])
+        .scan(scan_composer_cache)
+        .available(composer_available),
+        CleanerInfo::new(
+            "RubyGems Cache",
+            "Clean downloaded .gem files and Bundler's cache in ~/.gem and ~/.bundle/cache",
+            clean_gem_cache,
+        )
+        .tags(&[Tag::Dev, Tag::Packages])
+        .scan(scan_gem_cache)
+        .available(gem_available),
+        CleanerInfo::new(
+            "NuGet Cache",
+            "Clean NuGet's HTTP cache and packages unused for 90 days in ~/.nuget/packages",
+            clean_nuget_cache,
+        )
+        .tags(&[Tag::Dev, Tag::Packages])
+        .scan(scan_nuget_cache)
+        .available(nuget_available),
+    ]
+}
+
+fn clean_package_caches(skip_confirmation: bool) -> Result<u64> {
+    let fs = filesystem();
+    let home_dir = home_dir()?;
+
+    let cache_locations = vec![
+        (home_dir.join(".cache/pip"), "pip"),
+        (home_dir.join(".npm/_cacache"), "npm"),
+        (home_dir.join(".cargo/.crates.toml.lock"), "cargo lock file"),
+        (
+            home_dir.join(".cargo/.package-cache"),
+            "cargo package cache",
+        ),
+    ];
+
+    let mut bytes_saved = 0;
+
+    for (path, name) in cache_locations {
+        if fs.exists(&path) {
+            let size = fs.size(&path)?;
+            debug!(
+                "{} cache found: {:?}, size: {}",
+                name,
+                path,
+                format_size(size)
+            );
+
+            if skip_confirmation
+                || confirm(
+                    &format!("Clean {} cache ({} to be freed)?", name, format_size(size)),
+                    true,
+                )?
+            {
+                let is_dir = path.is_dir();
+                let removed = if is_dir {
+                    fs.remove_dir_all(&path)
+                } else {
+                    fs.remove_file(&path)
+                };
+                let freed = match removed {
+                    Ok(freed) => freed,
+                    Err(e) => {
+                        warn!("Failed to remove {} cache: {}", name, e);
+                        continue;
+                    }
+                };
+                if is_dir {
+                    fs.create_dir_all(&path).ok(); // Recreate empty directory
+                }
+
+                print_success(&format!("Cleaned {} cache", name));
+                bytes_saved += freed;
+            }
+        }
+    }
+
+    // Clean yarn cache with the yarn command if available, never in a sandbox
+    if !is_sandboxed() && (skip_confirmation || confirm("Clean yarn cache?", true)?) {
+        if let Ok(output) =
+            children::output(std::process::Command::new("yarn").arg("cache").arg("clean"))
+        {
+            if output.status.success() {
+                print_success("Cleaned yarn cache");
+                // Since we can't easily determine the size, estimate 10MB
+                bytes_saved += 10 * 1024 * 1024;
+            }
+        }
+    }
+
+    Ok(bytes_saved)
+}
+
+fn composer_paths() -> Result<Vec<PathBuf>> {
+    let home_dir = home_dir()?;
+    // ~/.composer/cache is where Composer 1 and non-XDG setups keep it
+    Ok(vec![
+        home_dir.join(".cache/composer"),
+        home_dir.join(".composer/cache"),
+    ])
+}
+
+/// Only the caches under ~/.gem: the rest of it holds gems installed with
+/// `gem install --user-install`
+fn gem_paths() -> Result<Vec<PathBuf>> {
+    let home_dir = home_dir()?;
+    let mut paths = vec![home_dir.join(".gem/specs")];
+    if let Ok(versions) = fs::read_dir(home_dir.join(".gem/ruby")) {
+        let mut caches: Vec<PathBuf> = versions
+            .flatten()
+            .map(|entry| entry.path().join("cache"))
+            .collect();
+        caches.sort();
+        paths.extend(caches);
+    }
+    paths.push(home_dir.join(".bundle/cache"));
+    Ok(paths)
+}
+
+fn nuget_http_cache() -> Result<PathBuf> {
+    Ok(home_dir()?.join(".local/share/NuGet/http-cache"))
+}
+
+fn nuget_packages_dir() -> Result<PathBuf> {
+    Ok(home_dir()?.join(".nuget/packages"))
+}
+
+fn composer_available() -> bool {
+    command_exists("composer") || composer_paths().is_ok_and(|p| p.iter().any(|p| p.exists()))
+}
+
+fn gem_available() -> bool {
+    command_exists("gem") || gem_paths().is_ok_and(|p| p.iter().any(|p| p.exists()))
+}
+
+fn nuget_available() -> bool {
+    command_exists("dotnet")
+        || command_exists("nuget")
+        || nuget_packages_dir().is_ok_and(|p| p.exists())
+}
+
+fn clean_composer_cache(skip_confirmation: bool) -> Result<u64> {
+    clean_cache_dirs(&composer_paths()?, "Composer", skip_confirmation)
+}
+
+fn clean_gem_cache(skip_confirmation: bool) -> Result<u64> {
+    clean_cache_dirs(&gem_paths()?, "RubyGems", skip_confirmation)
+}
+
+fn scan_composer_cache() -> Result<ScanReport> {
+    scan_cache_dirs(&composer_paths()?)
+}
+
+fn scan_gem_cache() -> Result<ScanReport> {
+    scan_cache_dirs(&gem_paths()?)
+}
+
+fn scan_nuget_cache() -> Result<ScanReport> {
+    let mut report = scan_cache_dirs(&[nuget_http_cache()?])?;
+    let stale = stale_nuget_packages(&nuget_packages_dir()?, NUGET_MAX_AGE, SystemTime::now());
+    for (path, size) in stale {
+        report.add_entry(&path.to_string_lossy(), size, size);
+    }
+    Ok(report)
+}
+
+fn clean_nuget_cache(skip_confirmation: bool) -> Result<u64> {
+    let fs = filesystem();
+    let mut bytes_saved = clean_cache_dirs(&[nuget_http_cache()?], "NuGet", skip_confirmation)?;
+
+    let stale = stale_nuget_packages(&nuget_packages_dir()?, NUGET_MAX_AGE, SystemTime::now());
+    if stale.is_empty() {
+        return Ok(bytes_saved);
+    }
+    let size: u64 = stale.iter().map(|(_, size)| size).sum();
+    if skip_confirmation
+        || confirm(
+            &format!(
+                "Remove {} NuGet packages unused for {} days ({} to be freed)?",
+                stale.len(),
+                NUGET_MAX_AGE.as_secs() / (24 * 60 * 60),
+                format_size(size)
+            ),
+            true,
+        )?
+    {
+        for (path, _) in stale {
+            match fs.remove_dir_all(&path) {
+                Ok(freed) => bytes_saved += freed,
+                Err(e) => {
+                    warn!("Failed to remove NuGet package {:?}: {}", path, e);
+                    continue;
+                }
+            }
+            throttle_deletion();
+        }
+        print_success("Removed unused NuGet packages");
+    }
+
+    Ok(bytes_saved)
+}
+
+/// Package versions in a NuGet global packages folder (`<id>/<version>`)
+/// last restored more than `max_age` before `now`, with their sizes.
+/// NuGet writes `.nupkg.metadata` when it extracts a package, so its
+/// modification time is used, falling back to the version directory's.
+pub fn stale_nuget_packages(
+    packages_dir: &Path,
+    max_age: Duration,
+    now: SystemTime,
+) -> Vec<(PathBuf, u64)> {
+    let Ok(ids) = fs::read_dir(packages_dir) else {
+        return Vec::new();
+    };
+
+    let mut stale = Vec::new();
+    for id in ids.flatten().filter(|e| e.path().is_dir()) {
+        let Ok(versions) = fs::read_dir(id.path()) else {
+            continue;
+        };
+        for version in versions.flatten().map(|e| e.path()) {
+            if !version.is_dir() {
+                continue;
+            }
+            let restored = fs::metadata(version.join(".nupkg.metadata"))
+                .or_else(|_| fs::metadata(&version))
+                .and_then(|m| m.modified());
+            let is_stale =
+                restored.is_ok_and(|time| now.duration_since(time).is_ok_and(|age| age > max_age));
+            if is_stale {
+                let size = filesystem().size(&version).unwrap_or(0);
+                stale.push((version, size));
+            }
+        }
+    }
+    stale.sort();
+    stale
+}
@@ -0,0 +1,77 @@
+use anyhow::Result;
+use log::{debug, info, warn};
+use std::path::PathBuf;
+
+use super::{CleanerInfo, ScanReport, Tag};
+use crate::filesystem::{filesystem, is_sandboxed, root_path};
+use crate::utils::{
+    command_exists, confirm, execute_with_sudo, format_size, get_size, print_success,
+};
+
+/// Returns the FreeBSD-only system cleaners. They are registered alongside
+/// the shared system cleaners on FreeBSD, where "System Logs" removes
+/// newsyslog's rotated files.
+pub fn get_cleaners() -> Vec<CleanerInfo> {
+    vec![CleanerInfo::new(
+        "pkg Cache",
+        "Clean downloaded packages in /var/cache/pkg (pkg clean)",
+        clean_pkg_cache,
+    )
+    .tags(&[Tag::Packages])
+    .scan(scan_pkg_cache)
+    .available(pkg_available)]
+}
+
+fn pkg_cache_dir() -> PathBuf {
+    root_path("/var/cache/pkg")
+}
+
+fn pkg_available() -> bool {
+    command_exists("pkg")
+}
+
+fn scan_pkg_cache() -> Result<ScanReport> {
+    let dir = pkg_cache_dir();
+    let mut report = ScanReport::new();
+    if filesystem().exists(&dir) {
+        let size = filesystem().size(&dir)?;
+        report.add_entry(&dir.to_string_lossy(), size, size);
+    }
+    Ok(report)
+}
+
+fn clean_pkg_cache(skip_confirmation: bool) -> Result<u64> {
+    // pkg acts on the real system, so never run it sandboxed
+    if is_sandboxed() {
+        debug!("Sandboxed, skipping pkg cache");
+        return Ok(0);
+    }
+
+    let dir = pkg_cache_dir();
+    let cache = dir.to_string_lossy();
+    let before = get_size(&cache).unwrap_or(0);
+    if before == 0 {
+        return Ok(0);
+    }
+    if !skip_confirmation
+        && !confirm(
+            &format!("Clean pkg cache ({} to be freed)?", format_size(before)),
+            true,
+        )?
+    {
+        return Ok(0);
+    }
+
+    info!("Cleaning pkg cache...");
+    let output = execute_with_sudo("pkg", &["clean", "-a", "-y"])?;
+    if !output.status.success() {
+        warn!(
+            "Failed to clean pkg cache: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        return Ok(0);
+    }
+    let freed = before.saturating_sub(get_size(&cache).unwrap_or(0));
+    print_success(&format!("Cleaned pkg cache, freed {}", format_size(freed)));
+    Ok(freed)
+}
@@ -0,0 +1,185 @@
+use anyhow::{Context, Result};
+use log::warn;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::cache_dirs::{clean_cache_dirs, scan_cache_dirs};
+use super::cleaned::{self, CleanedItemType};
+use super::{CleanerInfo, ScanReport, Tag};
+use crate::children;
+use crate::filesystem::{home_dir, is_sandboxed};
+use crate::utils::{command_exists, confirm, format_size, parse_si_size, print_success};
+
+/// Lists all available Cloud/K8s tool cleaners with their descriptions.
+pub fn list_cleaners() -> Vec<String> {
+    get_cleaners()
+        .iter()
+        .map(|c| format!("{}: {}", c.name, c.description))
+        .collect()
+}
+
+/// Returns a vector of all available Cloud/K8s tool cleaners.
+pub fn get_cleaners() -> Vec<CleanerInfo> {
+    vec![
+        CleanerInfo::new(
+            "Minikube Cache",
+            "Clean cached ISOs, preloaded images and binaries in ~/.minikube/cache",
+            clean_minikube_cache,
+        )
+        .tags(&[Tag::Dev, Tag::Heavy])
+        .scan(scan_minikube_cache)
+        .available(minikube_available),
+        CleanerInfo::new(
+            "kind Node Images",
+            "Remove unused kindest/node images from Docker",
+            clean_kind_images,
+        )
+        .tags(&[Tag::Dev, Tag::Heavy])
+        .scan(scan_kind_images)
+        .available(kind_available),
+        CleanerInfo::new(
+            "Helm Cache",
+            "Clean Helm repository and chart caches in ~/.cache/helm",
+            clean_helm_cache,
+        )
+        .tags(&[Tag::Dev])
+        .scan(scan_helm_cache)
+        .available(helm_available),
+        CleanerInfo::new(
+            "kubectl Cache",
+            "Clean kubectl HTTP and discovery caches in ~/.kube/cache",
+            clean_kubectl_cache,
+        )
+        .tags(&[Tag::Dev])
+        .scan(scan_kubectl_cache)
+        .available(kubectl_available),
+    ]
+}
+
+fn minikube_paths() -> Result<Vec<PathBuf>> {
+    Ok(vec![home_dir()?.join(".minikube/cache")])
+}
+
+fn helm_paths() -> Result<Vec<PathBuf>> {
+    Ok(vec![home_dir()?.join(".cache/helm")])
+}
+
+fn kubectl_paths() -> Result<Vec<PathBuf>> {
+    let kube_cache = home_dir()?.join(".kube/cache");
+    Ok(vec![kube_cache.join("http"), kube_cache.join("discovery")])
+}
+
+fn minikube_available() -> bool {
+    command_exists("minikube") || minikube_paths().is_ok_and(|p| p.iter().any(|p| p.exists()))
+}
+
+fn kind_available() -> bool {
+    command_exists("kind") && command_exists("docker")
+}
+
+fn helm_available() -> bool {
+    command_exists("helm") || helm_paths().is_ok_and(|p| p.iter().any(|p| p.exists()))
+}
+
+fn kubectl_available() -> bool {
+    command_exists("kubectl")
+}
+
+fn clean_minikube_cache(skip_confirmation: bool) -> Result<u64> {
+    clean_cache_dirs(&minikube_paths()?, "minikube", skip_confirmation)
+}
+
+fn clean_helm_cache(skip_confirmation: bool) -> Result<u64> {
+    clean_cache_dirs(&helm_paths()?, "helm", skip_confirmation)
+}
+
+fn clean_kubectl_cache(skip_confirmation: bool) -> Result<u64> {
+    clean_cache_dirs(&kubectl_paths()?, "kubectl", skip_confirmation)
+}
+
+fn scan_minikube_cache() -> Result<ScanReport> {
+    scan_cache_dirs(&minikube_paths()?)
+}
+
+fn scan_helm_cache() -> Result<ScanReport> {
+    scan_cache_dirs(&helm_paths()?)
+}
+
+fn scan_kubectl_cache() -> Result<ScanReport> {
+    scan_cache_dirs(&kubectl_paths()?)
+}
+
+/// List kindest/node images as (reference, size) pairs
+fn kind_images() -> Vec<(String, u64)> {
+    // Images live in the real docker daemon, which a sandbox can't fake
+    if is_sandboxed() {
+        return Vec::new();
+    }
+
+    let output = children::output(Command::new("docker").args([
+        "images",
+        "--filter",
+        "reference=kindest/node",
+        "--format",
+        "{{.Repository}}:{{.Tag}}\t{{.Size}}",
+    ]));
+
+    let Ok(output) = output else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (reference, size) = line.split_once('\t')?;
+            Some((reference.to_string(), parse_si_size(size).unwrap_or(0)))
+        })
+        .collect()
+}
+
+fn scan_kind_images() -> Result<ScanReport> {
+    let mut report = ScanReport::new();
+    for (reference, size) in kind_images() {
+        report.add_entry(&reference, size, size);
+    }
+    Ok(report)
+}
+
+fn clean_kind_images(skip_confirmation: bool) -> Result<u64> {
+    let mut bytes_saved = 0;
+
+    for (reference, size) in kind_images() {
+        if skip_confirmation
+            || confirm(
+                &format!(
+                    "Remove kind node image {} ({} to be freed)?",
+                    reference,
+                    format_size(size)
+                ),
+                true,
+            )?
+        {
+            // docker refuses to remove images used by a running cluster,
+            // which is exactly what we want
+            let output = children::output(Command::new("docker").args(["image", "rm", &reference]))
+                .context("Failed to execute docker image rm")?;
+
+            if output.status.success() {
+                print_success(&format!("Removed kind node image {}", reference));
+                cleaned::record(Path::new(&reference), size, CleanedItemType::File);
+                bytes_saved += size;
+            } else {
+                warn!(
+                    "Skipping kind node image {}: {}",
+                    reference,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+            }
+        }
+    }
+
+    Ok(bytes_saved)
+}
@@ -0,0 +1,232 @@
+//! Which installed kernels the Old Kernels cleaner removes and how much
+//! space that frees. The sizes come from dpkg's `Installed-Size`, or rpm's
+//! `SIZE`, of each package belonging to an old kernel version (image,
+//! modules, extra modules and headers), so the figure shown before
+//! confirming is what the packages actually take up. Removal goes through
+//! `purge-old-kernels` on dpkg-based systems and `dnf --oldinstallonly` on
+//! rpm-based ones.
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use std::cmp::Ordering;
+use std::process::Command;
+use std::sync::Mutex;
+
+use super::package_cache::{PackageManager, RpmFrontend};
+use crate::children;
+use crate::utils::command_exists;
+
+/// Kernel versions kept besides the running one unless the config sets
+/// `keep_kernels`, so an update that won't boot still has one to fall back to
+pub const KEEP_KERNELS: usize = 2;
+
+static KEEP: Lazy<Mutex<Option<usize>>> = Lazy::new(|| Mutex::new(None));
+
+/// Keep this many of the newest kernels besides the running one, from the
+/// config file
+pub fn set_keep(count: Option<usize>) {
+    *KEEP.lock().unwrap_or_else(|e| e.into_inner()) = count;
+}
+
+/// Kernel versions kept besides the running one, as passed to
+/// `purge-old-kernels --keep`. Never fewer than one.
+pub fn keep() -> usize {
+    KEEP.lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .unwrap_or(KEEP_KERNELS)
+        .max(1)
+}
+
+/// Package name prefixes of a kernel version's packages, longest first so
+/// `linux-modules-extra-6.1.0` isn't taken for `linux-modules-`
+const KERNEL_PACKAGE_PREFIXES: [&str; 5] = [
+    "linux-image-unsigned-",
+    "linux-modules-extra-",
+    "linux-modules-",
+    "linux-headers-",
+    "linux-image-",
+];
+
+/// An installed package belonging to one kernel version
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KernelPackage {
+    pub name: String,
+    /// The kernel release it belongs to, as `uname -r` prints it
+    pub version: String,
+    /// Installed size in bytes
+    pub size: u64,
+}
+
+/// Installed kernel packages in the output of
+/// `dpkg-query -W -f='${Package}\t${Installed-Size}\t${db:Status-Abbrev}\n'`.
+/// dpkg reports sizes in KiB. Meta packages such as `linux-image-amd64`
+/// don't name a version and are left out.
+pub fn parse_dpkg_kernels(output: &str) -> Vec<KernelPackage> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let name = fields.next()?.trim();
+            let size: u64 = fields.next()?.trim().parse().unwrap_or(0);
+            let status = fields.next()?.trim();
+            if !status.starts_with("ii") {
+                return None;
+            }
+            let version = KERNEL_PACKAGE_PREFIXES
+                .iter()
+                .find_map(|prefix| name.strip_prefix(prefix))?;
+            // Headers come in a common package and a flavour-specific one
+            // (linux-headers-6.1.0-18-common and -amd64)
+            let version = version.strip_suffix("-common").unwrap_or(version);
+            version
+                .starts_with(|c: char| c.is_ascii_digit())
+                .then(|| KernelPackage {
+                    name: name.to_string(),
+                    version: version.to_string(),
+                    size: size * 1024,
+                })
+        })
+        .collect()
+}
+
+/// Installed kernel packages in the output of `rpm -q --queryformat
+/// '%{NAME}\t%{VERSION}-%{RELEASE}.%{ARCH}\t%{SIZE}\n'`, named with their
+/// version so dnf removes just that one. rpm reports sizes in bytes and
+/// a line of its own for each package that isn't installed.
+pub fn parse_rpm_kernels(output: &str) -> Vec<KernelPackage> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let name = fields.next()?.trim();
+            let version = fields.next()?.trim();
+            let size = fields.next()?.trim().parse().ok()?;
+            Some(KernelPackage {
+                name: format!("{}-{}", name, version),
+                version: version.to_string(),
+                size,
+            })
+        })
+        .collect()
+}
+
+/// Whether a package is a kernel image, as opposed to its modules or
+/// headers: `linux-image-*` from dpkg, `kernel-core` (or `kernel` before
+/// it was split up) from rpm
+fn is_image(package: &KernelPackage) -> bool {
+    package.name.starts_with("linux-image-")
+        || package.name == format!("kernel-core-{}", package.version)
+        || package.name == format!("kernel-{}", package.version)
+}
+
+/// Compare kernel releases, with runs of digits compared as numbers so
+/// `6.1.0-18` sorts after `6.1.0-9`
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    fn chunks(version: &str) -> Vec<&str> {
+        let mut chunks = Vec::new();
+        let mut rest = version;
+        while let Some(first) = rest.chars().next() {
+            let digits = first.is_ascii_digit();
+            let end = rest
+                .find(|c: char| c.is_ascii_digit() != digits)
+                .unwrap_or(rest.len());
+            chunks.push(&rest[..end]);
+            rest = &rest[end..];
+        }
+        chunks
+    }
+    let (a, b) = (chunks(a), chunks(b));
+    for (x, y) in a.iter().zip(&b) {
+        let order = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            _ => x.cmp(y),
+        };
+        if order != Ordering::Equal {
+            return order;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+/// Whether a package of `version` belongs to the kernel image `image`.
+/// Headers shared by a kernel's flavours name the version without the
+/// flavour (`6.1.0-18` for `6.1.0-18-amd64`).
+fn belongs_to(version: &str, image: &str) -> bool {
+    image == version || image.starts_with(&format!("{}-", version))
+}
+
+/// Packages of the kernels that would be removed: every version except the
+/// running one and the newest `keep`
+pub fn old_kernels(packages: &[KernelPackage], running: &str, keep: usize) -> Vec<KernelPackage> {
+    let mut images: Vec<&str> = packages
+        .iter()
+        .filter(|package| is_image(package))
+        .map(|package| package.version.as_str())
+        .collect();
+    images.sort_by(|a, b| compare_versions(b, a));
+    images.dedup();
+    let mut kept: Vec<&str> = images.iter().take(keep).copied().collect();
+    kept.push(running);
+    images.retain(|image| !kept.contains(image));
+
+    packages
+        .iter()
+        .filter(|package| {
+            images
+                .iter()
+                .any(|image| belongs_to(&package.version, image))
+                && !kept.iter().any(|image| belongs_to(&package.version, image))
+        })
+        .cloned()
+        .collect()
+}
+
+/// The package manager old kernels are removed with: apt on dpkg-based
+/// systems, dnf on rpm-based ones. yum has no `--oldinstallonly`.
+pub fn package_manager() -> Option<PackageManager> {
+    if command_exists("apt") && command_exists("dpkg") {
+        return Some(PackageManager::Apt);
+    }
+    let dnf = matches!(
+        RpmFrontend::detect(),
+        Some(RpmFrontend::Dnf5 | RpmFrontend::Dnf4)
+    );
+    (dnf && command_exists("rpm")).then_some(PackageManager::Dnf)
+}
+
+/// The installed kernel packages, from dpkg or rpm
+pub fn installed_kernels(manager: PackageManager) -> Result<Vec<KernelPackage>> {
+    match manager {
+        PackageManager::Apt => {
+            let output = children::output(Command::new("dpkg-query").args([
+                "-W",
+                "-f=${Package}\t${Installed-Size}\t${db:Status-Abbrev}\n",
+                "linux-image-*",
+                "linux-modules-*",
+                "linux-headers-*",
+            ]))?;
+            Ok(parse_dpkg_kernels(&String::from_utf8_lossy(&output.stdout)))
+        }
+        PackageManager::Dnf => {
+            let output = children::output(Command::new("rpm").args([
+                "-q",
+                "--queryformat",
+                "%{NAME}\t%{VERSION}-%{RELEASE}.%{ARCH}\t%{SIZE}\n",
+                "kernel",
+                "kernel-core",
+                "kernel-modules",
+                "kernel-modules-core",
+                "kernel-modules-extra",
+                "kernel-devel",
+            ]))?;
+            Ok(parse_rpm_kernels(&String::from_utf8_lossy(&output.stdout)))
+        }
+        PackageManager::Pacman => Ok(Vec::new()),
+    }
+}
+
+/// The release of the running kernel
+pub fn running_kernel() -> Result<String> {
+    let output = Command::new("uname").arg("-r").output()?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
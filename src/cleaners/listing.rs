@@ -0,0 +1,148 @@
+use serde::Serialize;
+
+use super::{groups, RiskLevel, Tag};
+use crate::utils::format_size;
+
+/// One row of `cleansys list`
+#[derive(Debug, Clone, Serialize)]
+pub struct CleanerListing {
+    /// Stable id, matching the numbering of the text menu
+    pub id: usize,
+    /// Name of the group the cleaner belongs to
+    pub category: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Markdown documentation, for the cleaners that have it
+    pub docs: Option<&'static str>,
+    pub requires_root: bool,
+    /// Whether the tool the cleaner relies on was detected
+    pub available: bool,
+    pub risk: RiskLevel,
+    pub tags: &'static [Tag],
+    /// Estimated reclaimable bytes, only filled in when scanning
+    pub reclaimable: Option<u64>,
+}
+
+/// Describe every cleaner in display order. With `scan`, cleaners that are
+/// available and support scanning also report a reclaimable estimate.
+pub fn list_all(scan: bool) -> Vec<CleanerListing> {
+    let mut id = 0;
+    let mut listings = Vec::new();
+
+    for group in groups() {
+        for cleaner in group.cleaners {
+            id += 1;
+            let available = cleaner.is_available();
+            let reclaimable = match cleaner.scan {
+                Some(scan_fn) if scan && available => {
+                    scan_fn().ok().map(|report| report.total_reclaimable())
+                }
+                _ => None,
+            };
+
+            listings.push(CleanerListing {
+                id,
+                category: group.name,
+                name: cleaner.name,
+                description: cleaner.description,
+                docs: cleaner.docs,
+                requires_root: group.requires_root,
+                available,
+                risk: cleaner.risk,
+                tags: cleaner.tags,
+                reclaimable,
+            });
+        }
+    }
+
+    listings
+}
+
+/// The cleaner with the given id or name (case-insensitive), as
+/// `cleansys explain` looks it up. A name shared by a user and a system
+/// cleaner picks the user one; its id picks either.
+pub fn find(query: &str) -> Option<CleanerListing> {
+    let query = query.trim();
+    let listings = list_all(false);
+    if let Ok(id) = query.parse::<usize>() {
+        return listings.into_iter().find(|listing| listing.id == id);
+    }
+    listings
+        .into_iter()
+        .find(|listing| listing.name.eq_ignore_ascii_case(query))
+}
+
+/// Render listings as an aligned plain-text table
+pub fn format_table(listings: &[CleanerListing]) -> String {
+    let headers = [
+        "ID",
+        "CATEGORY",
+        "NAME",
+        "ROOT",
+        "AVAILABLE",
+        "RISK",
+        "TAGS",
+        "RECLAIMABLE",
+        "DESCRIPTION",
+    ];
+
+    let rows: Vec<[String; 9]> = listings
+        .iter()
+        .map(|l| {
+            [
+                l.id.to_string(),
+                l.category.to_string(),
+                l.name.to_string(),
+                yes_no(l.requires_root).to_string(),
+                yes_no(l.available).to_string(),
+                match l.risk {
+                    RiskLevel::Normal => "normal".to_string(),
+                    RiskLevel::High => "high".to_string(),
+                },
+                if l.tags.is_empty() {
+                    "-".to_string()
+                } else {
+                    let tags: Vec<&str> = l.tags.iter().map(Tag::name).collect();
+                    tags.join(",")
+                },
+                l.reclaimable.map_or_else(|| "-".to_string(), format_size),
+                l.description.to_string(),
+            ]
+        })
+        .collect();
+
+    let mut widths = headers.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: &[&str]| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        line.join("  ").trim_end().to_string()
+    };
+
+    let mut out = format_row(&headers);
+    out.push('\n');
+    out.push_str(&format_row(
+        &widths.map(|w| "-".repeat(w)).each_ref().map(String::as_str),
+    ));
+    for row in &rows {
+        out.push('\n');
+        out.push_str(&format_row(&row.each_ref().map(String::as_str)));
+    }
+    out
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
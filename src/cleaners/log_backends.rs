@@ -0,0 +1,721 @@
+//! Where system logs live and how old ones are removed. systemd keeps them
+//! in the journal, syslog daemons (rsyslog, syslog-ng, busybox syslogd)
+//! leave rotated files next to the live ones, and runit setups log through
+//! svlogd, which archives into `@<timestamp>.s` files. The platform module
+//! picks the backends for the running system.
+
+use anyhow::{bail, Context, Result};
+use log::{debug, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::cleaned::{self, CleanedItemType};
+use super::ScanReport;
+use crate::children;
+use crate::filesystem::{filesystem, is_sandboxed, root_path};
+use crate::priority::throttle_deletion;
+use crate::privileges;
+use crate::utils::{
+    confirm, execute_with_sudo, format_size, get_size, parse_size, print_success, print_warning,
+};
+
+/// Journal entries newer than this are kept when vacuuming by time
+pub const JOURNAL_RETENTION: &str = "7d";
+
+/// Limit offered when the journal has none and no size is configured
+pub const DEFAULT_JOURNAL_LIMIT: &str = "500M";
+
+/// Drop-in cleansys writes to cap the journal
+pub const JOURNALD_DROP_IN: &str = "/etc/systemd/journald.conf.d/50-cleansys.conf";
+
+/// journald's main config and drop-in directories, lowest priority first
+const JOURNALD_CONFIG_DIRS: [&str; 3] = [
+    "/usr/lib/systemd/journald.conf.d",
+    "/run/systemd/journald.conf.d",
+    "/etc/systemd/journald.conf.d",
+];
+
+static JOURNAL_LIMIT: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+static JOURNAL_VACUUM: Lazy<Mutex<JournalVacuum>> =
+    Lazy::new(|| Mutex::new(JournalVacuum::default()));
+
+/// Compression logrotate and newsyslog may apply to rotated logs
+const COMPRESSED_EXTENSIONS: [&str; 4] = ["bz2", "gz", "xz", "zst"];
+
+/// A way system logs are stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogBackend {
+    /// systemd-journald, vacuumed with `journalctl`
+    Journald,
+    /// Rotated files left by logrotate or newsyslog in /var/log
+    RotatedFiles,
+    /// svlogd archives, as used with runit and socklog
+    Svlogd,
+}
+
+impl LogBackend {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LogBackend::Journald => "systemd journal",
+            LogBackend::RotatedFiles => "rotated log files",
+            LogBackend::Svlogd => "svlogd archives",
+        }
+    }
+
+    /// What cleaning would free, without removing anything
+    pub fn scan(&self) -> Result<ScanReport> {
+        let mut report = ScanReport::new();
+        match self {
+            LogBackend::Journald => {
+                let dir = journal_dir();
+                let vacuum = journal_vacuum();
+                if filesystem().exists(&dir) {
+                    let size = filesystem().size(&dir)?;
+                    let archived = archived_journals(&dir).iter().map(|(_, s)| s).sum();
+                    report.add_entry(
+                        &dir.to_string_lossy(),
+                        size,
+                        vacuum.reclaimable(size, archived),
+                    );
+                }
+                report.add_note(format!("Vacuums {}", vacuum.describe()));
+                report.add_note(match configured_journal_limit() {
+                    Some(limit) => format!("Journal limit: SystemMaxUse={}", limit),
+                    None => {
+                        "Journal limit: none (journald allows 10% of the filesystem)".to_string()
+                    }
+                });
+            }
+            LogBackend::RotatedFiles | LogBackend::Svlogd => {
+                for (path, size) in self.stale_files() {
+                    report.add_entry(&path.to_string_lossy(), size, size);
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    /// Remove old logs, returning the bytes freed
+    pub fn clean(&self, skip_confirmation: bool) -> Result<u64> {
+        match self {
+            LogBackend::Journald => vacuum_journal(skip_confirmation),
+            LogBackend::RotatedFiles | LogBackend::Svlogd => {
+                remove_logs(self, self.stale_files(), skip_confirmation)
+            }
+        }
+    }
+
+    fn stale_files(&self) -> Vec<(PathBuf, u64)> {
+        let log_dir = root_path("/var/log");
+        match self {
+            LogBackend::Journald => Vec::new(),
+            LogBackend::RotatedFiles => rotated_logs(&log_dir),
+            LogBackend::Svlogd => svlogd_archives(&log_dir),
+        }
+    }
+}
+
+/// What `journalctl` vacuums the journal down to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VacuumMode {
+    /// Remove entries older than a time span (`--vacuum-time`)
+    #[default]
+    Time,
+    /// Remove the oldest entries until the journal fits a size (`--vacuum-size`)
+    Size,
+}
+
+/// How the journal is vacuumed. Both limits are kept so switching modes
+/// doesn't lose the other one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JournalVacuum {
+    pub mode: VacuumMode,
+    /// Age of the oldest entries kept, in journalctl's syntax (e.g. "7d")
+    pub time: String,
+    /// Size the journal is reduced to (e.g. "500M")
+    pub size: String,
+    /// Noisy units vacuumed on their own by the Unit Journals cleaner
+    /// (e.g. `["NetworkManager"]`)
+    pub units: Vec<String>,
+}
+
+impl Default for JournalVacuum {
+    fn default() -> Self {
+        Self {
+            mode: VacuumMode::Time,
+            time: JOURNAL_RETENTION.to_string(),
+            size: DEFAULT_JOURNAL_LIMIT.to_string(),
+            units: Vec::new(),
+        }
+    }
+}
+
+impl JournalVacuum {
+    /// The same limits with the other mode
+    pub fn toggled(&self) -> Self {
+        Self {
+            mode: match self.mode {
+                VacuumMode::Time => VacuumMode::Size,
+                VacuumMode::Size => VacuumMode::Time,
+            },
+            ..self.clone()
+        }
+    }
+
+    /// The `journalctl` option for this mode
+    pub fn argument(&self) -> Result<String> {
+        match self.mode {
+            VacuumMode::Time => {
+                if !self.time.starts_with(|c: char| c.is_ascii_digit()) {
+                    bail!(
+                        "Invalid journal vacuum time {:?}, expected e.g. 7d",
+                        self.time
+                    );
+                }
+                Ok(format!("--vacuum-time={}", self.time))
+            }
+            VacuumMode::Size => {
+                if parse_size(&self.size).is_none() {
+                    bail!(
+                        "Invalid journal vacuum size {:?}, expected e.g. 500M or 2G",
+                        self.size
+                    );
+                }
+                Ok(format!("--vacuum-size={}", self.size))
+            }
+        }
+    }
+
+    /// "entries older than 7d" or "the journal down to 500M"
+    pub fn describe(&self) -> String {
+        match self.mode {
+            VacuumMode::Time => format!("entries older than {}", self.time),
+            VacuumMode::Size => format!("the journal down to {}", self.size),
+        }
+    }
+
+    /// Archived journal bytes the vacuum would remove from a journal of
+    /// `size` bytes. Active journal files are never vacuumed.
+    pub fn reclaimable(&self, size: u64, archived: u64) -> u64 {
+        match self.mode {
+            VacuumMode::Time => archived,
+            VacuumMode::Size => match parse_size(&self.size) {
+                Some(limit) => archived.min(size.saturating_sub(limit)),
+                None => 0,
+            },
+        }
+    }
+}
+
+/// Set how the journal is vacuumed, from the config file or the TUI
+pub fn set_journal_vacuum(vacuum: JournalVacuum) {
+    *JOURNAL_VACUUM.lock().unwrap_or_else(|e| e.into_inner()) = vacuum;
+}
+
+/// How the journal is vacuumed
+pub fn journal_vacuum() -> JournalVacuum {
+    JOURNAL_VACUUM
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// Whether a file name is a log that has been rotated away: a numbered
+/// generation such as `messages.0` or `auth.log.3`, a dated one such as
+/// `syslog-20240101`, or `.old`, each optionally compressed
+pub fn is_rotated_log(file_name: &str) -> bool {
+    let name = match file_name.rsplit_once('.') {
+        Some((stem, ext)) if COMPRESSED_EXTENSIONS.contains(&ext) => stem,
+        _ => file_name,
+    };
+    let numbered = name.rsplit_once('.').is_some_and(|(stem, generation)| {
+        !stem.is_empty() && !generation.is_empty() && is_digits(generation)
+    });
+    let dated = name
+        .rsplit_once('-')
+        .is_some_and(|(stem, date)| !stem.is_empty() && date.len() == 8 && is_digits(date));
+    let old = name.len() > 4 && name.ends_with(".old");
+    numbered || dated || old
+}
+
+/// Whether a file name is an svlogd archive: `@<tai64n>.s` for a completed
+/// rotation or `.u` for one cut short
+pub fn is_svlogd_archive(file_name: &str) -> bool {
+    file_name.starts_with('@') && (file_name.ends_with(".s") || file_name.ends_with(".u"))
+}
+
+fn is_digits(text: &str) -> bool {
+    text.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Rotated logs below `dir`, with their sizes. Symlinks aren't followed.
+pub fn rotated_logs(dir: &Path) -> Vec<(PathBuf, u64)> {
+    find_files(dir, is_rotated_log)
+}
+
+/// svlogd archives below `dir`, with their sizes
+pub fn svlogd_archives(dir: &Path) -> Vec<(PathBuf, u64)> {
+    find_files(dir, is_svlogd_archive)
+}
+
+/// Journal files journald has archived (`system@...journal`, or
+/// `...journal~` after an unclean shutdown), which vacuuming may remove
+pub fn archived_journals(dir: &Path) -> Vec<(PathBuf, u64)> {
+    find_files(dir, |name| {
+        (name.contains('@') && name.ends_with(".journal")) || name.ends_with(".journal~")
+    })
+}
+
+/// Size reported by `journalctl --disk-usage`, e.g. "Archived and active
+/// journals take up 1.2G in the file system."
+pub fn parse_journal_usage(output: &str) -> Option<u64> {
+    let (_, rest) = output.split_once("take up ")?;
+    crate::utils::parse_size(rest.split_whitespace().next()?)
+}
+
+/// Set the journal size cap from the config file. Configuring one is the
+/// consent to write the drop-in without asking.
+pub fn set_journal_limit(limit: Option<String>) {
+    *JOURNAL_LIMIT.lock().unwrap_or_else(|e| e.into_inner()) = limit;
+}
+
+/// The journal size cap from the config file
+pub fn journal_limit() -> Option<String> {
+    JOURNAL_LIMIT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// `SystemMaxUse=` in the `[Journal]` section of a journald config. An
+/// empty value resets the limit, which is `Some("")`.
+pub fn parse_max_use(config: &str) -> Option<String> {
+    let mut in_journal = false;
+    let mut max_use = None;
+    for line in config.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_journal = line == "[Journal]";
+        } else if in_journal {
+            if let Some((key, value)) = line.split_once('=') {
+                if key.trim() == "SystemMaxUse" {
+                    max_use = Some(value.trim().to_string());
+                }
+            }
+        }
+    }
+    max_use
+}
+
+/// The effective `SystemMaxUse=`: journald.conf, then drop-ins in file
+/// name order across /usr/lib, /run and /etc, later ones winning
+pub fn configured_journal_limit() -> Option<String> {
+    let mut files = vec![root_path("/etc/systemd/journald.conf")];
+    let mut drop_ins: Vec<PathBuf> = JOURNALD_CONFIG_DIRS
+        .iter()
+        .filter_map(|dir| fs::read_dir(root_path(dir)).ok())
+        .flat_map(|entries| entries.flatten().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|e| e == "conf"))
+        .collect();
+    // The sort is stable, so of two drop-ins with the same name the one in
+    // /etc comes last and wins, as it masks the others
+    drop_ins.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+    files.extend(drop_ins);
+
+    files
+        .iter()
+        .rev()
+        .filter_map(|file| fs::read_to_string(file).ok())
+        .find_map(|config| parse_max_use(&config))
+        .filter(|limit| !limit.is_empty())
+}
+
+/// Contents of the drop-in capping the journal at `limit`
+pub fn journald_drop_in(limit: &str) -> String {
+    format!(
+        "# Written by cleansys so the journal doesn't regrow without bound.\n\
+         # Remove this file and restart systemd-journald to undo.\n\
+         [Journal]\n\
+         SystemMaxUse={}\n",
+        limit
+    )
+}
+
+/// Write the drop-in capping the journal at `limit`, returning its path
+pub fn write_journal_limit(limit: &str) -> Result<PathBuf> {
+    if parse_size(limit).is_none() {
+        bail!("Invalid journal size {:?}, expected e.g. 500M or 2G", limit);
+    }
+    let path = root_path(JOURNALD_DROP_IN);
+    if let Some(dir) = path.parent() {
+        filesystem().create_dir_all(dir)?;
+    }
+    fs::write(&path, journald_drop_in(limit))
+        .with_context(|| format!("Failed to write {:?}", path))?;
+    Ok(path)
+}
+
+/// After vacuuming, cap the journal so it doesn't grow back. Limits set
+/// by the admin are left alone; cleansys' own drop-in is written when a
+/// size is configured or the user agrees to the default.
+fn cap_journal(skip_confirmation: bool) -> Result<()> {
+    let ours = root_path(JOURNALD_DROP_IN);
+    let current = configured_journal_limit();
+    if current.is_some() && !ours.exists() {
+        return Ok(());
+    }
+
+    let limit = match journal_limit() {
+        Some(limit) => limit,
+        None if current.is_none() && !skip_confirmation => {
+            if !confirm(
+                &format!(
+                    "Limit the journal to {} so it doesn't regrow (writes {})?",
+                    DEFAULT_JOURNAL_LIMIT, JOURNALD_DROP_IN
+                ),
+                false,
+            )? {
+                return Ok(());
+            }
+            DEFAULT_JOURNAL_LIMIT.to_string()
+        }
+        None => return Ok(()),
+    };
+    if current.as_deref() == Some(limit.as_str()) {
+        return Ok(());
+    }
+
+    let path = write_journal_limit(&limit)?;
+    let output = execute_with_sudo("systemctl", &["restart", "systemd-journald"])?;
+    if output.status.success() {
+        print_success(&format!(
+            "Capped the journal at {} in {}",
+            limit,
+            path.display()
+        ));
+    } else {
+        print_warning(&format!(
+            "Wrote {}; the limit applies once systemd-journald restarts",
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Files below `dir` whose names `matches` accepts, with their sizes.
+/// Symlinks aren't followed.
+pub(crate) fn find_files(dir: &Path, matches: impl Fn(&str) -> bool) -> Vec<(PathBuf, u64)> {
+    let mut found = Vec::new();
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.path().symlink_metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                stack.push(entry.path());
+            } else if metadata.is_file() && matches(&entry.file_name().to_string_lossy()) {
+                found.push((entry.path(), metadata.len()));
+            }
+        }
+    }
+    found.sort();
+    found
+}
+
+fn journal_dir() -> PathBuf {
+    root_path("/var/log/journal")
+}
+
+fn vacuum_journal(skip_confirmation: bool) -> Result<u64> {
+    // journalctl acts on the real system, so never run it sandboxed
+    if is_sandboxed() {
+        debug!("Sandboxed, skipping journal vacuum");
+        return Ok(0);
+    }
+
+    let usage = || {
+        execute_with_sudo("journalctl", &["--disk-usage"])
+            .ok()
+            .and_then(|output| parse_journal_usage(&String::from_utf8_lossy(&output.stdout)))
+            .or_else(|| get_size(&journal_dir().to_string_lossy()).ok())
+            .unwrap_or(0)
+    };
+    let vacuum = journal_vacuum();
+    let argument = vacuum.argument()?;
+    let before = usage();
+
+    if !skip_confirmation
+        && !confirm(
+            &format!(
+                "Vacuum {} ({} in use)?",
+                vacuum.describe(),
+                format_size(before)
+            ),
+            true,
+        )?
+    {
+        return Ok(0);
+    }
+
+    let output = execute_with_sudo("journalctl", &[&argument])?;
+    if !output.status.success() {
+        warn!(
+            "Failed to vacuum the journal: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        return Ok(0);
+    }
+    let freed = before.saturating_sub(usage());
+    print_success(&format!(
+        "Vacuumed system journal, freed {}",
+        format_size(freed)
+    ));
+    cleaned::record(&journal_dir(), freed, CleanedItemType::Log);
+    if let Err(e) = cap_journal(skip_confirmation) {
+        warn!("Failed to limit the journal size: {:#}", e);
+    }
+    Ok(freed)
+}
+
+/// Whether `unit` can be passed to systemctl and journalctl as a unit name
+pub fn is_valid_unit_name(unit: &str) -> bool {
+    !unit.is_empty()
+        && !unit.starts_with('-')
+        && unit
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || ":-_.\\@".contains(c))
+}
+
+/// The journal namespace in `systemctl show --property=LogNamespace`
+/// output, None for units logging to the system journal
+pub fn parse_log_namespace(show: &str) -> Option<String> {
+    show.lines()
+        .find_map(|line| line.trim().strip_prefix("LogNamespace="))
+        .map(str::to_string)
+        .filter(|namespace| !namespace.is_empty())
+}
+
+/// `journalctl` arguments vacuuming one journal namespace. journalctl
+/// ignores `--unit` matches when vacuuming, as entries of all units share
+/// the same files; only a unit with its own `LogNamespace=` has a journal
+/// that can be vacuumed on its own.
+pub fn namespace_vacuum_args(vacuum: &JournalVacuum, namespace: &str) -> Result<Vec<String>> {
+    Ok(vec![
+        format!("--namespace={}", namespace),
+        vacuum.argument()?,
+    ])
+}
+
+fn unit_namespace(unit: &str) -> Option<String> {
+    let output = children::output(std::process::Command::new("systemctl").args([
+        "show",
+        "--property=LogNamespace",
+        unit,
+    ]))
+    .ok()?;
+    parse_log_namespace(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn namespace_usage(namespace: &str) -> u64 {
+    execute_with_sudo(
+        "journalctl",
+        &[&format!("--namespace={}", namespace), "--disk-usage"],
+    )
+    .ok()
+    .and_then(|output| parse_journal_usage(&String::from_utf8_lossy(&output.stdout)))
+    .unwrap_or(0)
+}
+
+/// Archived files of a namespace's journal, which lives in
+/// `/var/log/journal/<machine-id>.<namespace>`
+fn namespace_archived(namespace: &str) -> u64 {
+    let suffix = format!(".{}", namespace);
+    fs::read_dir(journal_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().ends_with(&suffix))
+        .flat_map(|entry| archived_journals(&entry.path()))
+        .map(|(_, size)| size)
+        .sum()
+}
+
+/// Bytes of a unit's entries in the system journal, exported uncompressed
+fn unit_entries_size(unit: &str) -> u64 {
+    privileges::as_root(|| {
+        Ok(children::stdout_len(
+            std::process::Command::new("journalctl").args([
+                "--unit",
+                unit,
+                "--output=export",
+                "--no-pager",
+            ]),
+        )?)
+    })
+    .unwrap_or(0)
+}
+
+fn journal_units() -> Vec<String> {
+    let units = journal_vacuum().units;
+    for unit in units.iter().filter(|unit| !is_valid_unit_name(unit)) {
+        warn!("Ignoring invalid unit name {:?} in journal_vacuum", unit);
+    }
+    units
+        .into_iter()
+        .filter(|unit| is_valid_unit_name(unit))
+        .collect()
+}
+
+/// Whether any units are configured for the Unit Journals cleaner
+pub fn unit_journals_configured() -> bool {
+    !journal_units().is_empty()
+}
+
+/// Journal usage of each configured unit. Units with their own namespace
+/// show what vacuuming it frees; the rest show the size of their entries
+/// in the system journal, which go when System Logs vacuums it.
+pub fn scan_unit_journals() -> Result<ScanReport> {
+    let mut report = ScanReport::new();
+    let vacuum = journal_vacuum();
+    let mut shared = false;
+    for unit in journal_units() {
+        match unit_namespace(&unit) {
+            Some(namespace) => {
+                let size = namespace_usage(&namespace);
+                report.add_entry(
+                    &format!("{} (namespace {})", unit, namespace),
+                    size,
+                    vacuum.reclaimable(size, namespace_archived(&namespace)),
+                );
+            }
+            None => {
+                shared = true;
+                report.add_entry(
+                    &format!("{} (system journal)", unit),
+                    unit_entries_size(&unit),
+                    0,
+                );
+            }
+        }
+    }
+    report.add_note(format!("Vacuums {}", vacuum.describe()));
+    if shared {
+        report.add_note(
+            "Units without LogNamespace= share the system journal's files, \
+             so their entries are vacuumed with System Logs",
+        );
+    }
+    Ok(report)
+}
+
+/// Vacuum the journals of the configured units that log to a namespace of
+/// their own, returning the bytes freed
+pub fn vacuum_unit_journals(skip_confirmation: bool) -> Result<u64> {
+    // journalctl acts on the real system, so never run it sandboxed
+    if is_sandboxed() {
+        debug!("Sandboxed, skipping unit journal vacuum");
+        return Ok(0);
+    }
+
+    let vacuum = journal_vacuum();
+    let mut freed = 0;
+    for unit in journal_units() {
+        let Some(namespace) = unit_namespace(&unit) else {
+            print_warning(&format!(
+                "{} logs to the system journal, which System Logs vacuums",
+                unit
+            ));
+            continue;
+        };
+        let args = namespace_vacuum_args(&vacuum, &namespace)?;
+        let before = namespace_usage(&namespace);
+        if !skip_confirmation
+            && !confirm(
+                &format!(
+                    "Vacuum {}'s journal: {} ({} in use)?",
+                    unit,
+                    vacuum.describe(),
+                    format_size(before)
+                ),
+                true,
+            )?
+        {
+            continue;
+        }
+
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let output = execute_with_sudo("journalctl", &args)?;
+        if !output.status.success() {
+            warn!(
+                "Failed to vacuum the journal of {}: {}",
+                unit,
+                String::from_utf8_lossy(&output.stderr)
+            );
+            continue;
+        }
+        let unit_freed = before.saturating_sub(namespace_usage(&namespace));
+        print_success(&format!(
+            "Vacuumed {}'s journal, freed {}",
+            unit,
+            format_size(unit_freed)
+        ));
+        cleaned::record(Path::new(&unit), unit_freed, CleanedItemType::Log);
+        freed += unit_freed;
+    }
+    Ok(freed)
+}
+
+fn remove_logs(
+    backend: &LogBackend,
+    logs: Vec<(PathBuf, u64)>,
+    skip_confirmation: bool,
+) -> Result<u64> {
+    if logs.is_empty() {
+        debug!("No {} to remove", backend.name());
+        return Ok(0);
+    }
+    let total: u64 = logs.iter().map(|(_, size)| size).sum();
+    if !skip_confirmation
+        && !confirm(
+            &format!(
+                "Remove {} {} ({} to be freed)?",
+                logs.len(),
+                backend.name(),
+                format_size(total)
+            ),
+            true,
+        )?
+    {
+        return Ok(0);
+    }
+
+    let fs = filesystem();
+    let mut bytes_saved = 0;
+    for (path, _) in logs {
+        match fs.remove_file(&path) {
+            Ok(size) => {
+                cleaned::record(&path, size, CleanedItemType::Log);
+                bytes_saved += size;
+                throttle_deletion();
+            }
+            Err(e) => {
+                cleaned::record_error(&path, &e);
+            }
+        }
+    }
+    print_success(&format!(
+        "Removed {}, freed {}",
+        backend.name(),
+        format_size(bytes_saved)
+    ));
+    Ok(bytes_saved)
+}
@@ -0,0 +1,103 @@
+use anyhow::Result;
+use std::path::PathBuf;
+
+use super::cache_dirs::{clean_cache_dirs, scan_cache_dirs};
+use super::{CleanerInfo, ScanReport, Tag};
+use crate::filesystem::{filesystem, home_dir};
+use crate::utils::command_exists;
+
+/// Lists all available macOS cleaners with their descriptions.
+pub fn list_cleaners() -> Vec<String> {
+    get_cleaners()
+        .iter()
+        .map(|c| format!("{}: {}", c.name, c.description))
+        .collect()
+}
+
+/// Returns a vector of all available macOS cleaners.
+pub fn get_cleaners() -> Vec<CleanerInfo> {
+    vec![
+        CleanerInfo::new(
+            "Homebrew Cache",
+            "Clean downloaded bottles and source archives in ~/Library/Caches/Homebrew",
+            clean_homebrew_cache,
+        )
+        .tags(&[Tag::Dev, Tag::Packages])
+        .scan(scan_homebrew_cache)
+        .available(homebrew_available),
+        CleanerInfo::new(
+            "Library Caches",
+            "Clean application caches in ~/Library/Caches (Apple system caches are skipped)",
+            clean_library_caches,
+        )
+        .tags(&[Tag::Browser])
+        .scan(scan_library_caches),
+        CleanerInfo::new("Trash", "Empty ~/.Trash", clean_trash)
+            .scan(scan_trash)
+            .tags(&[Tag::Privacy]),
+    ]
+}
+
+fn library_caches_dir() -> Result<PathBuf> {
+    Ok(home_dir()?.join("Library/Caches"))
+}
+
+fn homebrew_paths() -> Result<Vec<PathBuf>> {
+    Ok(vec![library_caches_dir()?.join("Homebrew")])
+}
+
+/// Each application's cache directory. Homebrew has its own cleaner, and
+/// `com.apple.*` caches belong to the system and are often protected.
+fn library_cache_paths() -> Result<Vec<PathBuf>> {
+    let fs = filesystem();
+    let dir = library_caches_dir()?;
+    if !fs.exists(&dir) {
+        return Ok(Vec::new());
+    }
+
+    let mut paths: Vec<PathBuf> = fs
+        .read_dir(&dir)?
+        .into_iter()
+        .filter(|path| path.is_dir() && !path.is_symlink())
+        .filter(|path| {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            name != "Homebrew" && !name.starts_with("com.apple.")
+        })
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+fn trash_paths() -> Result<Vec<PathBuf>> {
+    Ok(vec![home_dir()?.join(".Trash")])
+}
+
+fn homebrew_available() -> bool {
+    command_exists("brew") || homebrew_paths().is_ok_and(|p| p.iter().any(|p| p.exists()))
+}
+
+fn clean_homebrew_cache(skip_confirmation: bool) -> Result<u64> {
+    clean_cache_dirs(&homebrew_paths()?, "Homebrew", skip_confirmation)
+}
+
+fn clean_library_caches(skip_confirmation: bool) -> Result<u64> {
+    clean_cache_dirs(&library_cache_paths()?, "application", skip_confirmation)
+}
+
+/// Reading ~/.Trash needs Full Disk Access on recent macOS; without it the
+/// trash is reported as empty
+fn clean_trash(skip_confirmation: bool) -> Result<u64> {
+    clean_cache_dirs(&trash_paths()?, "Trash", skip_confirmation)
+}
+
+fn scan_homebrew_cache() -> Result<ScanReport> {
+    scan_cache_dirs(&homebrew_paths()?)
+}
+
+fn scan_library_caches() -> Result<ScanReport> {
+    scan_cache_dirs(&library_cache_paths()?)
+}
+
+fn scan_trash() -> Result<ScanReport> {
+    scan_cache_dirs(&trash_paths()?)
+}
@@ -1,7 +1,475 @@
 //! Cleaner modules for system and user-level cleanup operations.
 
+use anyhow::Result;
+use serde::Serialize;
+
+/// Android SDK, emulator and build cache cleaners.
+#[cfg(feature = "dev-caches")]
+pub mod android_cleaners;
+
+/// Firefox and Chrome/Chromium cache cleaners.
+#[cfg(feature = "browsers")]
+pub mod browser_cleaners;
+
+/// Shared helpers for scanning and emptying cache directories.
+mod cache_dirs;
+
+/// Per-path record of what each cleaner removed.
+pub mod cleaned;
+
+/// Container engine cleaners (Docker, Podman).
+#[cfg(feature = "containers")]
+pub mod container_cleaners;
+
+/// CUPS print spool cleaner for finished jobs and cached PPD data.
+pub mod cups;
+
+/// Cleaners declared by the user in the config file.
+pub mod custom;
+
+/// Developer package caches (pip, npm, cargo, Composer, RubyGems, NuGet).
+#[cfg(feature = "dev-caches")]
+pub mod dev_caches;
+
+/// FreeBSD system cleaners (pkg cache, newsyslog leftovers).
+pub mod freebsd_cleaners;
+
+/// Installed kernel packages and which ones Old Kernels removes.
+pub mod kernels;
+
+/// Structured listing of cleaners for `cleansys list`.
+pub mod listing;
+
+/// Kubernetes and cloud tooling cache cleaners (minikube, kind, helm, kubectl).
+#[cfg(feature = "containers")]
+pub mod k8s_cleaners;
+
+/// System log storage (journald, rotated syslog files, svlogd) and cleanup.
+pub mod log_backends;
+
+/// macOS cleaners (Homebrew, ~/Library/Caches, Trash).
+#[cfg(target_os = "macos")]
+pub mod macos_cleaners;
+
+/// Keep-last-N retention for package manager caches.
+pub mod package_cache;
+
+/// Rebuilding of font and icon caches after they are cleaned.
+pub mod rebuild;
+
+/// Structured reports produced by cleaner scans.
+pub mod scan;
+
+/// Opt-in web server, database log and PHP session cleaners for servers.
+#[cfg(feature = "server")]
+pub mod server_cleaners;
+
+/// Mail and spool cleaners for servers (cron output, dead letters, mailboxes).
+#[cfg(feature = "server")]
+pub mod spool_cleaners;
+
 /// System-level cleaners that require root privileges.
 pub mod system_cleaners;
 
+/// Opt-in cleanup of temp litter by name pattern, kept while in use.
+pub mod temp_litter;
+
 /// User-level cleaners that work without elevated permissions.
 pub mod user_cleaners;
+
+/// Windows user-level cleaners (temp, %LOCALAPPDATA% caches, Recycle Bin).
+#[cfg(windows)]
+pub mod windows_cleaners;
+
+pub use scan::ScanReport;
+
+use crate::deferred;
+use crate::history::{CleanerRun, RunRecord};
+use crate::platform::Platform;
+use crate::privileges;
+use crate::trim;
+use crate::utils::{confirm, print_error, print_success, print_warning};
+use crate::verify;
+use crate::watchdog;
+
+/// How much care a cleaner needs before it runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RiskLevel {
+    /// Removes caches and other data that is regenerated on demand.
+    #[default]
+    Normal,
+    /// Removes data that may be valuable, so an extra confirmation is asked.
+    High,
+}
+
+/// A label for picking cleaners by what they clean rather than by name,
+/// as in `cleansys clean --tags dev,browser`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tag {
+    /// Removes traces of what the user did (history, thumbnails, trash).
+    Privacy,
+    /// Caches of development tools and toolchains.
+    Dev,
+    /// Web browser data.
+    Browser,
+    /// Usually frees a lot of space.
+    Heavy,
+    /// Package manager caches and leftovers.
+    Packages,
+    /// Logs and crash reports.
+    Logs,
+}
+
+impl Tag {
+    /// Every tag, in the order the TUI filter cycles through them.
+    pub const ALL: [Tag; 6] = [
+        Tag::Privacy,
+        Tag::Dev,
+        Tag::Browser,
+        Tag::Heavy,
+        Tag::Packages,
+        Tag::Logs,
+    ];
+
+    /// The name used on the command line and in listings.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tag::Privacy => "privacy",
+            Tag::Dev => "dev",
+            Tag::Browser => "browser",
+            Tag::Heavy => "heavy",
+            Tag::Packages => "packages",
+            Tag::Logs => "logs",
+        }
+    }
+}
+
+impl std::str::FromStr for Tag {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim().to_lowercase();
+        Tag::ALL
+            .into_iter()
+            .find(|tag| tag.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Tag::ALL.iter().map(Tag::name).collect();
+                anyhow::anyhow!("Unknown tag '{}', expected one of: {}", s, names.join(", "))
+            })
+    }
+}
+
+/// Information about a cleaner.
+pub struct CleanerInfo {
+    /// The name of the cleaner.
+    pub name: &'static str,
+    /// A description of what the cleaner does.
+    pub description: &'static str,
+    /// Longer markdown documentation: what is removed, what is kept and
+    /// any caveats. Kept in `src/cleaners/docs`.
+    pub docs: Option<&'static str>,
+    /// The function that performs the cleaning operation.
+    pub function: fn(bool) -> Result<u64>,
+    /// Optional read-only scan describing what the cleaner would remove.
+    pub scan: Option<fn() -> Result<ScanReport>>,
+    /// How risky the cleaner is to run.
+    pub risk: RiskLevel,
+    /// Optional check whether the tool the cleaner relies on is present.
+    pub available: Option<fn() -> bool>,
+    /// The cleaner this one is listed under as a sub-item, if any.
+    pub parent: Option<&'static str>,
+    /// Tags describing what the cleaner removes.
+    pub tags: &'static [Tag],
+    /// Whether the cleaner runs as root, set for the cleaners of groups
+    /// that require it.
+    pub requires_root: bool,
+}
+
+impl CleanerInfo {
+    /// Create a new cleaner without a scan function.
+    pub const fn new(
+        name: &'static str,
+        description: &'static str,
+        function: fn(bool) -> Result<u64>,
+    ) -> Self {
+        Self {
+            name,
+            description,
+            docs: None,
+            function,
+            scan: None,
+            risk: RiskLevel::Normal,
+            available: None,
+            parent: None,
+            tags: &[],
+            requires_root: false,
+        }
+    }
+
+    /// Attach a scan function to the cleaner.
+    pub const fn scan(mut self, scan: fn() -> Result<ScanReport>) -> Self {
+        self.scan = Some(scan);
+        self
+    }
+
+    /// Attach markdown documentation, usually `include_str!("docs/<name>.md")`.
+    pub const fn docs(mut self, docs: &'static str) -> Self {
+        self.docs = Some(docs);
+        self
+    }
+
+    /// Attach a check for whether the cleaner applies to this system.
+    pub const fn available(mut self, available: fn() -> bool) -> Self {
+        self.available = Some(available);
+        self
+    }
+
+    /// Whether the cleaner applies to this system. Cleaners without a
+    /// check are always available.
+    pub fn is_available(&self) -> bool {
+        self.available.is_none_or(|check| check())
+    }
+
+    /// Mark the cleaner as high risk so it needs an extra confirmation.
+    pub const fn high_risk(mut self) -> Self {
+        self.risk = RiskLevel::High;
+        self
+    }
+
+    /// List the cleaner as a sub-item of another cleaner in the same
+    /// group. It keeps its own checkbox and must be registered right after
+    /// its parent.
+    pub const fn sub_item_of(mut self, parent: &'static str) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Tag the cleaner so it can be picked with `--tags` and the TUI filter.
+    pub const fn tags(mut self, tags: &'static [Tag]) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Whether the cleaner has at least one of `tags`.
+    pub fn has_any_tag(&self, tags: &[Tag]) -> bool {
+        self.tags.iter().any(|tag| tags.contains(tag))
+    }
+}
+
+/// Returns the groups with only the available cleaners that have at least
+/// one of `tags`. Groups left empty are dropped.
+pub fn tagged(tags: &[Tag]) -> Vec<CleanerGroup> {
+    groups()
+        .into_iter()
+        .filter_map(|mut group| {
+            group
+                .cleaners
+                .retain(|cleaner| cleaner.has_any_tag(tags) && cleaner.is_available());
+            (!group.cleaners.is_empty()).then_some(group)
+        })
+        .collect()
+}
+
+/// Run cleaners one after another on the command line, asking before each
+/// unless `skip_confirmation` is set. High risk cleaners are always
+/// confirmed.
+pub fn run_cleaners(cleaners: Vec<CleanerInfo>, skip_confirmation: bool) -> Result<RunRecord> {
+    let mut total_saved: u64 = 0;
+    let mut total_deferred: u64 = 0;
+    let mut runs = Vec::new();
+
+    for cleaner in cleaners {
+        // High risk cleaners are always confirmed, defaulting to no
+        let run = if cleaner.risk == RiskLevel::High {
+            confirm(
+                &format!("'{}' may remove valuable data. Run it?", cleaner.name),
+                false,
+            )?
+        } else {
+            skip_confirmation || confirm(&format!("Run '{}'?", cleaner.name), true)?
+        };
+
+        if run {
+            let before = verify::begin();
+            deferred::take();
+            cleaned::take();
+            cleaned::take_failures();
+            cleaned::take_denied();
+            let result = privileges::as_root_if(cleaner.requires_root, || {
+                watchdog::run(cleaner.name, || (cleaner.function)(skip_confirmation))
+            });
+            trim::record(&cleaned::take());
+            let failures = cleaned::take_failures();
+            let result = match result {
+                Ok(0) if !failures.is_empty() => Err(anyhow::anyhow!(
+                    "none of {} paths could be removed",
+                    failures.len()
+                )),
+                result => result,
+            };
+            match result {
+                Ok(bytes) => {
+                    let deferred = deferred::take().min(bytes);
+                    total_saved += bytes;
+                    total_deferred += deferred;
+                    runs.push(
+                        CleanerRun::succeeded(cleaner.name, bytes)
+                            .with_deferred(deferred)
+                            .with_failed_paths(failures.len()),
+                    );
+                    if failures.is_empty() {
+                        print_success(&format!(
+                            "{} completed: freed {}",
+                            cleaner.name,
+                            deferred::describe(bytes, deferred)
+                        ));
+                    } else {
+                        print_warning(&format!(
+                            "{} partly completed: freed {}, {} paths couldn't be removed",
+                            cleaner.name,
+                            deferred::describe(bytes, deferred),
+                            failures.len()
+                        ));
+                        for (path, error) in &failures {
+                            println!("  {:?}: {}", path, error);
+                        }
+                    }
+                    let denied = cleaned::take_denied();
+                    if denied > 0 {
+                        print_warning(&format!(
+                            "{}: permission denied on {} paths, left them in place",
+                            cleaner.name, denied
+                        ));
+                    }
+                    if let Some(verification) = verify::finish(before, bytes) {
+                        verify::print_verification(cleaner.name, &verification);
+                    }
+                }
+                Err(err) => {
+                    print_error(&format!("Error in {}: {}", cleaner.name, err));
+                    runs.push(CleanerRun::failed(cleaner.name, err.to_string()));
+                }
+            }
+        }
+    }
+
+    print_success(&format!(
+        "Total space freed: {}",
+        deferred::describe_total(total_saved, total_deferred)
+    ));
+    let partial = runs.iter().filter(|run| run.is_partial()).count();
+    if partial > 0 {
+        print_warning(&format!(
+            "{} cleaner(s) couldn't remove everything, see the warnings above",
+            partial
+        ));
+    }
+    trim::offer(skip_confirmation)?;
+    Ok(RunRecord::new(runs))
+}
+
+/// A group of cleaners shown together as one category.
+pub struct CleanerGroup {
+    /// The name of the group as shown in the TUI and menu.
+    pub name: &'static str,
+    /// A short description of the group.
+    pub description: &'static str,
+    /// Whether the cleaners in this group need root privileges.
+    pub requires_root: bool,
+    /// The cleaners belonging to this group.
+    pub cleaners: Vec<CleanerInfo>,
+}
+
+/// Returns all cleaner groups in display order. System cleaners and the
+/// platform's own cleaners are only registered where they apply, and
+/// groups behind a cargo feature only when it is enabled.
+pub fn groups() -> Vec<CleanerGroup> {
+    let mut groups = vec![CleanerGroup {
+        name: "User Land Cleaners",
+        description: "Clean user-specific files and caches",
+        requires_root: false,
+        cleaners: user_cleaners::get_cleaners(),
+    }];
+
+    if Platform::current().has_system_cleaners() {
+        groups.push(CleanerGroup {
+            name: "System Cleaners",
+            description: "Clean system files and caches (requires root)",
+            requires_root: true,
+            cleaners: system_cleaners::get_cleaners(),
+        });
+        #[cfg(feature = "server")]
+        groups.push(CleanerGroup {
+            name: "Mail & Spool",
+            description: "Clean stale cron output and dead letters, report oversized mailboxes",
+            requires_root: true,
+            cleaners: spool_cleaners::get_cleaners(),
+        });
+        #[cfg(feature = "server")]
+        if server_cleaners::enabled() {
+            groups.push(CleanerGroup {
+                name: "Web & Database Servers",
+                description: "Clean web server and database logs and expired PHP sessions",
+                requires_root: true,
+                cleaners: server_cleaners::get_cleaners(),
+            });
+        }
+        if temp_litter::enabled() {
+            groups.push(CleanerGroup {
+                name: "Advanced Temp Cleanup",
+                description: "Clean temp litter by name pattern, keeping anything still in use",
+                requires_root: true,
+                cleaners: temp_litter::get_cleaners(),
+            });
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    groups.push(CleanerGroup {
+        name: "macOS",
+        description: "Clean Homebrew, ~/Library/Caches and the Trash",
+        requires_root: false,
+        cleaners: macos_cleaners::get_cleaners(),
+    });
+
+    #[cfg(windows)]
+    groups.push(CleanerGroup {
+        name: "Windows",
+        description: "Clean temporary files, local app caches and the Recycle Bin",
+        requires_root: false,
+        cleaners: windows_cleaners::get_cleaners(),
+    });
+
+    #[cfg(feature = "containers")]
+    groups.extend([
+        CleanerGroup {
+            name: "Containers",
+            description: "Prune unused Docker and Podman data",
+            requires_root: false,
+            cleaners: container_cleaners::get_cleaners(),
+        },
+        CleanerGroup {
+            name: "Cloud/K8s Tools",
+            description: "Clean minikube, kind, helm and kubectl caches",
+            requires_root: false,
+            cleaners: k8s_cleaners::get_cleaners(),
+        },
+    ]);
+
+    #[cfg(feature = "dev-caches")]
+    groups.push(CleanerGroup {
+        name: "Android Development",
+        description: "Clean Android caches, emulator snapshots and unused system images",
+        requires_root: false,
+        cleaners: android_cleaners::get_cleaners(),
+    });
+
+    for group in groups.iter_mut().filter(|group| group.requires_root) {
+        for cleaner in &mut group.cleaners {
+            cleaner.requires_root = true;
+        }
+    }
+    groups
+}
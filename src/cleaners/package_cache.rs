@@ -0,0 +1,296 @@
+//! Retention for package manager caches: instead of wiping the whole cache,
+//! keep the newest N cached versions of each package so downgrades stay
+//! possible offline. Also finds apt's package lists and periodic stamps.
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use super::cleaned::{self, CleanedItemType};
+use crate::filesystem::filesystem;
+
+/// Package managers whose caches can be pruned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PackageManager {
+    Apt,
+    Pacman,
+    Dnf,
+}
+
+impl PackageManager {
+    /// Extension of the cached package files
+    fn extension(&self) -> &'static str {
+        match self {
+            PackageManager::Apt => ".deb",
+            PackageManager::Pacman => ".pkg.tar",
+            PackageManager::Dnf => ".rpm",
+        }
+    }
+
+    /// Package name of a cached file, e.g. `linux` for
+    /// `linux-6.9.1.arch1-1-x86_64.pkg.tar.zst`. `None` for anything that
+    /// isn't a package file (signatures, partial downloads, lock files).
+    pub fn package_name<'a>(&self, file_name: &'a str) -> Option<&'a str> {
+        if !file_name.contains(self.extension()) || file_name.ends_with(".sig") {
+            return None;
+        }
+        match self {
+            // name_version_arch.deb
+            PackageManager::Apt => file_name.split_once('_').map(|(name, _)| name),
+            // name-pkgver-pkgrel-arch.pkg.tar.*
+            PackageManager::Pacman => strip_dash_fields(file_name, 3),
+            // name-version-release.arch.rpm
+            PackageManager::Dnf => strip_dash_fields(file_name, 2),
+        }
+    }
+}
+
+/// The front end managing RPM packages. Fedora 41+ ships dnf5 (often as
+/// `/usr/bin/dnf`), older Fedora and RHEL 8/9 dnf4 (where `yum` is a link
+/// to it), and RHEL/CentOS 7 the original yum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpmFrontend {
+    Dnf5,
+    Dnf4,
+    Yum,
+}
+
+impl RpmFrontend {
+    /// Detect the installed front end, preferring the newest
+    pub fn detect() -> Option<Self> {
+        ["/usr/bin/dnf5", "/usr/bin/dnf", "/usr/bin/yum"]
+            .iter()
+            .map(Path::new)
+            .filter(|path| path.exists())
+            .find_map(Self::identify)
+    }
+
+    /// Tell which front end a binary is, following symlinks such as
+    /// `dnf -> dnf5` or `yum -> dnf-3`
+    pub fn identify(binary: &Path) -> Option<Self> {
+        let resolved = fs::canonicalize(binary).unwrap_or_else(|_| binary.to_path_buf());
+        match resolved.file_name()?.to_str()? {
+            "dnf5" => Some(RpmFrontend::Dnf5),
+            "dnf" | "dnf-3" | "dnf4" => Some(RpmFrontend::Dnf4),
+            "yum" => Some(RpmFrontend::Yum),
+            _ => None,
+        }
+    }
+
+    /// Command to run, e.g. `dnf5 clean all`
+    pub fn command(&self) -> &'static str {
+        match self {
+            RpmFrontend::Dnf5 => "dnf5",
+            RpmFrontend::Dnf4 => "dnf",
+            RpmFrontend::Yum => "yum",
+        }
+    }
+
+    /// Where downloaded metadata and packages are cached
+    pub fn cache_dir(&self) -> &'static str {
+        match self {
+            RpmFrontend::Dnf5 => "/var/cache/libdnf5",
+            RpmFrontend::Dnf4 => "/var/cache/dnf",
+            RpmFrontend::Yum => "/var/cache/yum",
+        }
+    }
+
+    /// Display name for log messages
+    pub fn name(&self) -> &'static str {
+        match self {
+            RpmFrontend::Dnf5 => "DNF5",
+            RpmFrontend::Dnf4 => "DNF",
+            RpmFrontend::Yum => "YUM",
+        }
+    }
+}
+
+/// `packages` directories below an RPM cache. dnf keeps them one level
+/// down (`<repo>/packages`), yum three (`<arch>/<release>/<repo>/packages`).
+pub fn rpm_package_dirs(cache_dir: &Path) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let mut stack = vec![(cache_dir.to_path_buf(), 0)];
+    while let Some((dir, depth)) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.symlink_metadata().is_ok_and(|m| m.is_dir()) {
+                continue;
+            }
+            if entry.file_name() == "packages" {
+                found.push(path);
+            } else if depth < 3 {
+                stack.push((path, depth + 1));
+            }
+        }
+    }
+    found.sort();
+    found
+}
+
+/// Stamps in `/var/lib/apt/periodic` older than this are stale
+pub const PERIODIC_STAMP_MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Package lists apt downloaded into `lists_dir`, with their sizes,
+/// including unfinished downloads in `partial/`. The lock file stays.
+pub fn apt_list_files(lists_dir: &Path) -> Vec<(PathBuf, u64)> {
+    let mut files = Vec::new();
+    for dir in [lists_dir.to_path_buf(), lists_dir.join("partial")] {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.path().symlink_metadata() else {
+                continue;
+            };
+            if metadata.is_file() && entry.file_name() != "lock" {
+                files.push((entry.path(), metadata.len()));
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Stale stamps in apt's periodic directory: older than `max_age`, or
+/// dated in the future after a clock jump, which holds off the daily
+/// update and upgrade jobs until that date
+pub fn stale_periodic_stamps(
+    dir: &Path,
+    max_age: Duration,
+    now: SystemTime,
+) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut stale: Vec<(PathBuf, u64)> = entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().ends_with("-stamp"))
+        .filter_map(|entry| {
+            let metadata = entry.path().symlink_metadata().ok()?;
+            let modified = metadata.modified().ok()?;
+            let is_stale = match now.duration_since(modified) {
+                Ok(age) => age > max_age,
+                Err(_) => true,
+            };
+            (metadata.is_file() && is_stale).then(|| (entry.path(), metadata.len()))
+        })
+        .collect();
+    stale.sort();
+    stale
+}
+
+/// Drop the last `count` dash-separated fields
+fn strip_dash_fields(file_name: &str, count: usize) -> Option<&str> {
+    let mut end = file_name.len();
+    for _ in 0..count {
+        end = file_name[..end].rfind('-')?;
+    }
+    (end > 0).then(|| &file_name[..end])
+}
+
+/// How many versions of each package to keep in the cache, globally or per
+/// manager. Unset means the whole cache is cleaned.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PackageRetention {
+    /// Versions kept for every manager without its own setting
+    pub keep: Option<u32>,
+    pub apt: Option<u32>,
+    pub pacman: Option<u32>,
+    /// Applies to dnf5, dnf and yum alike
+    pub dnf: Option<u32>,
+}
+
+impl PackageRetention {
+    /// Versions to keep for a manager, `None` to clean its cache completely
+    pub fn keep_for(&self, manager: PackageManager) -> Option<u32> {
+        let specific = match manager {
+            PackageManager::Apt => self.apt,
+            PackageManager::Pacman => self.pacman,
+            PackageManager::Dnf => self.dnf,
+        };
+        specific.or(self.keep)
+    }
+}
+
+static RETENTION: Lazy<Mutex<PackageRetention>> =
+    Lazy::new(|| Mutex::new(PackageRetention::default()));
+
+/// Set the retention used by the package cache cleaner
+pub fn set_retention(retention: PackageRetention) {
+    *RETENTION.lock().unwrap_or_else(|e| e.into_inner()) = retention;
+}
+
+/// The retention currently in effect
+pub fn retention() -> PackageRetention {
+    *RETENTION.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Cached package files in `dir` beyond the newest `keep` versions of each
+/// package, with their sizes. Versions are ordered by modification time,
+/// which is when the package was downloaded.
+pub fn stale_packages(dir: &Path, manager: PackageManager, keep: u32) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut packages: HashMap<String, Vec<(SystemTime, PathBuf, u64)>> = HashMap::new();
+    for entry in entries.flatten() {
+        let Ok(metadata) = entry.path().symlink_metadata() else {
+            continue;
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if let Some(name) = manager.package_name(&file_name) {
+            packages.entry(name.to_string()).or_default().push((
+                metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                entry.path(),
+                metadata.len(),
+            ));
+        }
+    }
+
+    let mut stale: Vec<(PathBuf, u64)> = packages
+        .into_values()
+        .flat_map(|mut versions| {
+            versions.sort_by_key(|v| std::cmp::Reverse(v.0));
+            versions
+                .into_iter()
+                .skip(keep as usize)
+                .map(|(_, path, size)| (path, size))
+        })
+        .collect();
+    stale.sort();
+    stale
+}
+
+/// Remove the stale packages in `dir`, along with their `.sig` files.
+/// Returns the bytes freed.
+pub fn prune(dir: &Path, manager: PackageManager, keep: u32) -> Result<u64> {
+    let fs = filesystem();
+    let mut freed = 0;
+    for (path, mut size) in stale_packages(dir, manager, keep) {
+        fs.remove_file(&path)?;
+
+        let mut signature = path.clone().into_os_string();
+        signature.push(".sig");
+        let signature = PathBuf::from(signature);
+        if fs.exists(&signature) {
+            size += fs.size(&signature).unwrap_or(0);
+            fs.remove_file(&signature)?;
+        }
+        cleaned::record(&path, size, CleanedItemType::File);
+        freed += size;
+    }
+    Ok(freed)
+}
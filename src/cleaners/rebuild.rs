@@ -0,0 +1,100 @@
+//! Rebuilding font and icon caches right after they are cleaned. Both are
+//! regenerated on demand, but then the next login or application start
+//! stalls while fontconfig rescans every font and GTK every icon theme. With
+//! `rebuild_caches = true` the cleaners that remove them run `fc-cache -f`
+//! and `gtk-update-icon-cache` afterwards and log how long that took.
+
+use log::info;
+use once_cell::sync::Lazy;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::children;
+use crate::filesystem::is_sandboxed;
+use crate::utils::{command_exists, execute_with_sudo, print_success, print_warning};
+
+/// The cache GTK keeps at the top of each icon theme
+pub const ICON_THEME_CACHE: &str = "icon-theme.cache";
+
+static ENABLED: Lazy<Mutex<bool>> = Lazy::new(|| Mutex::new(false));
+
+/// Rebuild font and icon caches after cleaning them, from the config file
+pub fn set_enabled(enabled: bool) {
+    *ENABLED.lock().unwrap_or_else(|e| e.into_inner()) = enabled;
+}
+
+/// Whether cleaned font and icon caches are rebuilt
+pub fn enabled() -> bool {
+    *ENABLED.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// `icon-theme.cache` files of the themes directly in `icons_dir`, with
+/// their sizes
+pub fn icon_theme_caches(icons_dir: &Path) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = fs::read_dir(icons_dir) else {
+        return Vec::new();
+    };
+    let mut caches: Vec<(PathBuf, u64)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path().join(ICON_THEME_CACHE);
+            let metadata = path.symlink_metadata().ok()?;
+            metadata.is_file().then_some((path, metadata.len()))
+        })
+        .collect();
+    caches.sort();
+    caches
+}
+
+/// Rebuild the fontconfig cache, the system-wide one through sudo
+pub fn font_cache(system: bool) {
+    let what = if system {
+        "system font cache"
+    } else {
+        "font cache"
+    };
+    run(what, "fc-cache", &["-f"], system);
+}
+
+/// Rebuild the icon caches of these theme directories
+pub fn icon_caches(themes: &[PathBuf], system: bool) {
+    for theme in themes {
+        let name = theme.file_name().unwrap_or_default().to_string_lossy();
+        // -t: also themes without an index.theme, as the cache was there
+        run(
+            &format!("icon cache for {}", name),
+            "gtk-update-icon-cache",
+            &["-f", "-q", "-t", &theme.to_string_lossy()],
+            system,
+        );
+    }
+}
+
+fn run(what: &str, command: &str, args: &[&str], system: bool) {
+    // Never run against the real system from a sandboxed run
+    if !enabled() || is_sandboxed() || !command_exists(command) {
+        return;
+    }
+    let start = Instant::now();
+    let output = if system {
+        execute_with_sudo(command, args)
+    } else {
+        children::output(Command::new(command).args(args)).map_err(Into::into)
+    };
+    match output {
+        Ok(out) if out.status.success() => {
+            let message = format!("Rebuilt {} in {:.1}s", what, start.elapsed().as_secs_f64());
+            info!("{}", message);
+            print_success(&message);
+        }
+        Ok(out) => print_warning(&format!(
+            "Failed to rebuild {}: {}",
+            what,
+            String::from_utf8_lossy(&out.stderr).trim()
+        )),
+        Err(e) => print_warning(&format!("Failed to rebuild {}: {}", what, e)),
+    }
+}
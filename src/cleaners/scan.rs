@@ -0,0 +1,64 @@
+/// A single line in a scan report, e.g. "Images" for a container engine
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanEntry {
+    /// What this entry describes
+    pub label: String,
+    /// Total size in bytes
+    pub size: u64,
+    /// Bytes the files take up on disk, less than `size` for sparse files,
+    /// where it was measured
+    pub on_disk: Option<u64>,
+    /// Bytes the cleaner would actually free
+    pub reclaimable: u64,
+}
+
+/// Result of scanning what a cleaner would remove, without removing anything
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScanReport {
+    /// Per-source breakdown of the scan
+    pub entries: Vec<ScanEntry>,
+    /// Extra facts shown under the breakdown, e.g. a configured size limit
+    pub notes: Vec<String>,
+}
+
+impl ScanReport {
+    /// Create a new empty report
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an entry to the report
+    pub fn add_entry(&mut self, label: &str, size: u64, reclaimable: u64) {
+        self.entries.push(ScanEntry {
+            label: label.to_string(),
+            size,
+            on_disk: None,
+            reclaimable,
+        });
+    }
+
+    /// Add an entry whose size on disk was measured as well
+    pub fn add_measured_entry(&mut self, label: &str, size: u64, on_disk: u64, reclaimable: u64) {
+        self.entries.push(ScanEntry {
+            label: label.to_string(),
+            size,
+            on_disk: Some(on_disk),
+            reclaimable,
+        });
+    }
+
+    /// Add a note shown with the report
+    pub fn add_note(&mut self, note: impl Into<String>) {
+        self.notes.push(note.into());
+    }
+
+    /// Total size of everything that was scanned
+    pub fn total_size(&self) -> u64 {
+        self.entries.iter().map(|e| e.size).sum()
+    }
+
+    /// Total number of bytes the cleaner would free
+    pub fn total_reclaimable(&self) -> u64 {
+        self.entries.iter().map(|e| e.reclaimable).sum()
+    }
+}
@@ -0,0 +1,247 @@
+//! Opt-in cleaners for headless servers: rotated web server logs, old
+//! MySQL/MariaDB and PostgreSQL logs under /var/log, and PHP session files
+//! past their garbage collection lifetime. The group only shows up with
+//! `server_cleaners = true` in the config, so desktops aren't offered
+//! cleaners for services they don't run.
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use super::cache_dirs::{remove_files, scan_files};
+use super::log_backends::{is_rotated_log, rotated_logs};
+use super::temp_litter::Liveness;
+use super::{CleanerInfo, ScanReport, Tag};
+use crate::filesystem::root_path;
+
+/// Database logs untouched for this long are removed
+pub const DB_LOG_MAX_AGE: Duration = Duration::from_secs(14 * 24 * 60 * 60);
+
+/// PHP's default `session.gc_maxlifetime`, in seconds
+pub const DEFAULT_SESSION_LIFETIME: u64 = 1440;
+
+const WEB_LOG_DIRS: [&str; 3] = ["/var/log/nginx", "/var/log/apache2", "/var/log/httpd"];
+
+const DB_LOG_DIRS: [&str; 3] = ["/var/log/mysql", "/var/log/mariadb", "/var/log/postgresql"];
+
+/// Debian keeps sessions in the first, Red Hat in the second
+const SESSION_DIRS: [&str; 2] = ["/var/lib/php/sessions", "/var/lib/php/session"];
+
+static ENABLED: Lazy<Mutex<bool>> = Lazy::new(|| Mutex::new(false));
+
+/// Register the server cleaners, from the config file
+pub fn set_enabled(enabled: bool) {
+    *ENABLED.lock().unwrap_or_else(|e| e.into_inner()) = enabled;
+}
+
+/// Whether the server cleaners are registered
+pub fn enabled() -> bool {
+    *ENABLED.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn get_cleaners() -> Vec<CleanerInfo> {
+    vec![
+        CleanerInfo::new(
+            "Web Server Logs",
+            "Clean rotated nginx and Apache logs",
+            clean_web_logs,
+        )
+        .tags(&[Tag::Logs])
+        .scan(scan_web_logs)
+        .available(web_logs_available),
+        CleanerInfo::new(
+            "Database Logs",
+            "Clean rotated MySQL/MariaDB and PostgreSQL logs and closed ones older than 14 days",
+            clean_db_logs,
+        )
+        .tags(&[Tag::Logs])
+        .scan(scan_db_logs)
+        .available(db_logs_available),
+        CleanerInfo::new(
+            "PHP Sessions",
+            "Clean PHP session files older than session.gc_maxlifetime",
+            clean_php_sessions,
+        )
+        .scan(scan_php_sessions)
+        .available(php_sessions_available),
+    ]
+}
+
+fn existing(dirs: &[&str]) -> Vec<PathBuf> {
+    dirs.iter()
+        .map(root_path)
+        .filter(|dir| dir.is_dir())
+        .collect()
+}
+
+fn web_logs() -> Vec<(PathBuf, u64)> {
+    existing(&WEB_LOG_DIRS)
+        .iter()
+        .flat_map(|dir| rotated_logs(dir))
+        .collect()
+}
+
+fn web_logs_available() -> bool {
+    !existing(&WEB_LOG_DIRS).is_empty()
+}
+
+/// Database logs in `dir` that can go: rotated ones, and `.log` files older
+/// than `max_age` that no process has open. MySQL keeps its error, slow and
+/// general logs open side by side however long they sit idle, and each
+/// PostgreSQL cluster its current log. Without `liveness` nothing can be
+/// known to be closed, so only rotated logs go.
+pub fn old_db_logs(
+    dir: &Path,
+    max_age: Duration,
+    now: SystemTime,
+    liveness: Option<&Liveness>,
+) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut logs: Vec<(PathBuf, u64)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.path().symlink_metadata().ok()?;
+            let modified = metadata.modified().ok()?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            let old = now.duration_since(modified).is_ok_and(|age| age > max_age);
+            let closed = || liveness.is_some_and(|live| !live.open.contains(&entry.path()));
+            (metadata.is_file()
+                && (is_rotated_log(&name) || (name.ends_with(".log") && old && closed())))
+            .then_some((entry.path(), metadata.len()))
+        })
+        .collect();
+    logs.sort();
+    logs
+}
+
+fn db_logs() -> Vec<(PathBuf, u64)> {
+    let now = SystemTime::now();
+    let liveness = Liveness::snapshot();
+    existing(&DB_LOG_DIRS)
+        .iter()
+        .flat_map(|dir| old_db_logs(dir, DB_LOG_MAX_AGE, now, liveness.as_ref()))
+        .collect()
+}
+
+fn db_logs_available() -> bool {
+    !existing(&DB_LOG_DIRS).is_empty()
+}
+
+/// `session.gc_maxlifetime` from a php.ini, in seconds
+pub fn parse_gc_maxlifetime(ini: &str) -> Option<u64> {
+    ini.lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with(';'))
+        .filter_map(|line| line.split_once('='))
+        .find(|(key, _)| key.trim() == "session.gc_maxlifetime")
+        .and_then(|(_, value)| value.trim().trim_matches('"').parse().ok())
+}
+
+/// The php.ini files of the installed PHP versions: /etc/php.ini, and
+/// php.ini up to two levels below /etc/php* (`/etc/php/8.2/fpm/php.ini`,
+/// `/etc/php82/php.ini`)
+fn php_ini_files() -> Vec<PathBuf> {
+    let mut files = vec![root_path("/etc/php.ini")];
+    let Ok(entries) = fs::read_dir(root_path("/etc")) else {
+        return files;
+    };
+    let mut stack: Vec<(PathBuf, usize)> = entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("php"))
+        .map(|entry| (entry.path(), 0))
+        .filter(|(path, _)| path.is_dir())
+        .collect();
+    while let Some((dir, depth)) = stack.pop() {
+        files.push(dir.join("php.ini"));
+        if depth < 2 {
+            if let Ok(entries) = fs::read_dir(&dir) {
+                stack.extend(
+                    entries
+                        .flatten()
+                        .map(|entry| (entry.path(), depth + 1))
+                        .filter(|(path, _)| path.is_dir()),
+                );
+            }
+        }
+    }
+    files.retain(|file| file.is_file());
+    files
+}
+
+/// The longest session lifetime any installed PHP is configured with, so
+/// no SAPI loses sessions it still considers alive
+fn session_lifetime() -> Duration {
+    let seconds = php_ini_files()
+        .iter()
+        .filter_map(|file| fs::read_to_string(file).ok())
+        .filter_map(|ini| parse_gc_maxlifetime(&ini))
+        .max()
+        .unwrap_or(DEFAULT_SESSION_LIFETIME);
+    Duration::from_secs(seconds)
+}
+
+/// Session files (`sess_*`) in `dir` last modified more than `lifetime`
+/// before `now`
+pub fn expired_sessions(dir: &Path, lifetime: Duration, now: SystemTime) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut sessions: Vec<(PathBuf, u64)> = entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("sess_"))
+        .filter_map(|entry| {
+            let metadata = entry.path().symlink_metadata().ok()?;
+            let age = now.duration_since(metadata.modified().ok()?).ok()?;
+            (metadata.is_file() && age > lifetime).then_some((entry.path(), metadata.len()))
+        })
+        .collect();
+    sessions.sort();
+    sessions
+}
+
+fn php_sessions() -> Vec<(PathBuf, u64)> {
+    let lifetime = session_lifetime();
+    let now = SystemTime::now();
+    existing(&SESSION_DIRS)
+        .iter()
+        .flat_map(|dir| expired_sessions(dir, lifetime, now))
+        .collect()
+}
+
+fn php_sessions_available() -> bool {
+    !existing(&SESSION_DIRS).is_empty()
+}
+
+fn scan_web_logs() -> Result<ScanReport> {
+    Ok(scan_files(&web_logs()))
+}
+
+fn scan_db_logs() -> Result<ScanReport> {
+    Ok(scan_files(&db_logs()))
+}
+
+fn scan_php_sessions() -> Result<ScanReport> {
+    let mut report = scan_files(&php_sessions());
+    report.add_note(format!(
+        "Session lifetime: {} s",
+        session_lifetime().as_secs()
+    ));
+    Ok(report)
+}
+
+fn clean_web_logs(skip_confirmation: bool) -> Result<u64> {
+    remove_files(web_logs(), "rotated web server logs", skip_confirmation)
+}
+
+fn clean_db_logs(skip_confirmation: bool) -> Result<u64> {
+    remove_files(db_logs(), "old database logs", skip_confirmation)
+}
+
+fn clean_php_sessions(skip_confirmation: bool) -> Result<u64> {
+    remove_files(php_sessions(), "expired PHP sessions", skip_confirmation)
+}
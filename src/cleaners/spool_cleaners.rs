@@ -0,0 +1,368 @@
+//! Cleaners for mail and spool leftovers on servers: cron output stuck in
+//! spool queues, `dead.letter` files mail(1) saves when it can't deliver,
+//! and mailboxes that grew out of hand. Mailboxes are only reported unless
+//! `truncate_large_mailboxes` is set, since they may be someone's only copy
+//! of their mail.
+
+use anyhow::{bail, Context, Result};
+use once_cell::sync::Lazy;
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use super::cache_dirs::{remove_files, scan_files};
+use super::cleaned::{self, CleanedItemType};
+use super::{CleanerInfo, ScanReport, Tag};
+use crate::filesystem::root_path;
+use crate::utils::{confirm, format_size, print_success, print_warning};
+use crate::{audit, limits, protection};
+
+/// Spool files and dead letters untouched for this long are removed
+pub const SPOOL_MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Mailboxes bigger than this are reported (100 MB)
+pub const MAILBOX_REPORT_SIZE: u64 = 100 * 1024 * 1024;
+
+/// How often to try for a mailbox's locks, and how long to wait in between
+const LOCK_ATTEMPTS: u32 = 10;
+const LOCK_RETRY: Duration = Duration::from_millis(500);
+
+/// Queues cron output ends up in: sendmail's client queue, filled with
+/// undeliverable cron mail on hosts without a working MTA, and the output
+/// spool of at jobs. Never the crontabs next to them.
+const CRON_SPOOL_DIRS: [&str; 2] = ["/var/spool/clientmqueue", "/var/spool/cron/atspool"];
+
+/// Where local mailboxes live; /var/mail is often a link to the other
+const MAILBOX_DIRS: [&str; 2] = ["/var/spool/mail", "/var/mail"];
+
+static TRUNCATE_MAILBOXES: Lazy<Mutex<bool>> = Lazy::new(|| Mutex::new(false));
+
+/// Let the mailbox cleaner empty oversized mailboxes instead of only
+/// reporting them, from the config file
+pub fn set_truncate_mailboxes(enabled: bool) {
+    *TRUNCATE_MAILBOXES.lock().unwrap_or_else(|e| e.into_inner()) = enabled;
+}
+
+fn truncate_mailboxes() -> bool {
+    *TRUNCATE_MAILBOXES.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn get_cleaners() -> Vec<CleanerInfo> {
+    vec![
+        CleanerInfo::new(
+            "Stale Cron Output",
+            "Clean cron and at output older than 30 days stuck in /var/spool queues",
+            clean_cron_spool,
+        )
+        .tags(&[Tag::Logs])
+        .scan(scan_cron_spool)
+        .available(cron_spool_available),
+        CleanerInfo::new(
+            "Dead Letters",
+            "Clean dead.letter files older than 30 days in home directories",
+            clean_dead_letters,
+        )
+        .tags(&[Tag::Logs, Tag::Privacy])
+        .scan(scan_dead_letters)
+        .available(dead_letters_available),
+        CleanerInfo::new(
+            "Oversized Mailboxes",
+            "Report mailboxes over 100 MB in /var/spool/mail (empties them only when configured)",
+            clean_mailboxes,
+        )
+        .scan(scan_mailboxes)
+        .available(mailboxes_available),
+    ]
+}
+
+/// Regular files directly in `dir` last modified more than `max_age` before
+/// `now`, with their sizes
+pub fn stale_files(dir: &Path, max_age: Duration, now: SystemTime) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(PathBuf, u64)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.path().symlink_metadata().ok()?;
+            let age = now.duration_since(metadata.modified().ok()?).ok()?;
+            (metadata.is_file() && age > max_age).then(|| (entry.path(), metadata.len()))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Regular files directly in `dir` bigger than `limit`, with their sizes
+pub fn large_files(dir: &Path, limit: u64) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(PathBuf, u64)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.path().symlink_metadata().ok()?;
+            (metadata.is_file() && metadata.len() > limit).then(|| (entry.path(), metadata.len()))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn cron_spool_files() -> Vec<(PathBuf, u64)> {
+    let now = SystemTime::now();
+    CRON_SPOOL_DIRS
+        .iter()
+        .flat_map(|dir| stale_files(&root_path(dir), SPOOL_MAX_AGE, now))
+        .collect()
+}
+
+fn cron_spool_available() -> bool {
+    CRON_SPOOL_DIRS.iter().any(|dir| root_path(dir).exists())
+}
+
+/// `dead.letter` files older than [`SPOOL_MAX_AGE`] in root's and the users'
+/// home directories
+fn dead_letters() -> Vec<(PathBuf, u64)> {
+    let mut homes = vec![root_path("/root")];
+    if let Ok(entries) = fs::read_dir(root_path("/home")) {
+        homes.extend(entries.flatten().map(|entry| entry.path()));
+    }
+    let now = SystemTime::now();
+    homes
+        .iter()
+        .filter_map(|home| {
+            let path = home.join("dead.letter");
+            let metadata = path.symlink_metadata().ok()?;
+            let age = now.duration_since(metadata.modified().ok()?).ok()?;
+            (metadata.is_file() && age > SPOOL_MAX_AGE).then_some((path, metadata.len()))
+        })
+        .collect()
+}
+
+fn dead_letters_available() -> bool {
+    !dead_letters().is_empty()
+}
+
+/// A mailbox as the scan found it. Spool directories are often
+/// world-writable, so before emptying one as root, the file opened is
+/// checked to still be this one and not a link a user swapped in meanwhile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mailbox {
+    pub path: PathBuf,
+    pub size: u64,
+    /// Device, inode and owner, see [`file_identity`]
+    identity: (u64, u64, u32),
+}
+
+impl Mailbox {
+    /// The regular file at `path`, without following a symlink there
+    pub fn at(path: &Path) -> Result<Self> {
+        let metadata = fs::symlink_metadata(path)
+            .with_context(|| format!("Failed to stat mailbox {:?}", path))?;
+        if !metadata.is_file() {
+            bail!("Mailbox {:?} is not a regular file", path);
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            size: metadata.len(),
+            identity: file_identity(&metadata),
+        })
+    }
+}
+
+#[cfg(unix)]
+fn file_identity(metadata: &fs::Metadata) -> (u64, u64, u32) {
+    use std::os::unix::fs::MetadataExt;
+    (metadata.dev(), metadata.ino(), metadata.uid())
+}
+
+#[cfg(not(unix))]
+fn file_identity(_metadata: &fs::Metadata) -> (u64, u64, u32) {
+    (0, 0, 0)
+}
+
+/// Mailboxes over [`MAILBOX_REPORT_SIZE`], each listed once even when
+/// /var/mail links to /var/spool/mail
+fn large_mailboxes() -> Vec<Mailbox> {
+    let mut seen = Vec::new();
+    let mut mailboxes = Vec::new();
+    for dir in MAILBOX_DIRS {
+        let dir = root_path(dir);
+        let Ok(real) = dir.canonicalize() else {
+            continue;
+        };
+        if seen.contains(&real) {
+            continue;
+        }
+        seen.push(real);
+        mailboxes.extend(
+            large_files(&dir, MAILBOX_REPORT_SIZE)
+                .into_iter()
+                .filter_map(|(path, _)| Mailbox::at(&path).ok()),
+        );
+    }
+    mailboxes
+}
+
+fn mailboxes_available() -> bool {
+    MAILBOX_DIRS.iter().any(|dir| root_path(dir).is_dir())
+}
+
+fn scan_cron_spool() -> Result<ScanReport> {
+    Ok(scan_files(&cron_spool_files()))
+}
+
+fn scan_dead_letters() -> Result<ScanReport> {
+    Ok(scan_files(&dead_letters()))
+}
+
+fn scan_mailboxes() -> Result<ScanReport> {
+    let truncate = truncate_mailboxes();
+    let mut report = ScanReport::new();
+    for mailbox in large_mailboxes() {
+        report.add_entry(
+            &mailbox.path.to_string_lossy(),
+            mailbox.size,
+            if truncate { mailbox.size } else { 0 },
+        );
+    }
+    if !truncate && !report.entries.is_empty() {
+        report.add_note("Only reported; set truncate_large_mailboxes = true to empty them");
+    }
+    Ok(report)
+}
+
+fn clean_cron_spool(skip_confirmation: bool) -> Result<u64> {
+    remove_files(
+        cron_spool_files(),
+        "stale cron spool files",
+        skip_confirmation,
+    )
+}
+
+fn clean_dead_letters(skip_confirmation: bool) -> Result<u64> {
+    remove_files(dead_letters(), "dead.letter files", skip_confirmation)
+}
+
+fn clean_mailboxes(skip_confirmation: bool) -> Result<u64> {
+    let truncate = truncate_mailboxes();
+    let mut bytes_saved = 0;
+    for mailbox in large_mailboxes() {
+        let (path, size) = (&mailbox.path, mailbox.size);
+        if !truncate {
+            print_warning(&format!("Mailbox {:?} holds {}", path, format_size(size)));
+            continue;
+        }
+        // Setting truncate_large_mailboxes counts as consent for --yes
+        if !skip_confirmation
+            && !confirm(
+                &format!(
+                    "Empty mailbox {:?} ({} to be freed)?",
+                    path,
+                    format_size(size)
+                ),
+                false,
+            )?
+        {
+            continue;
+        }
+        // A locked or vanished mailbox leaves the others to be emptied
+        if let Err(e) = truncate_mailbox(&mailbox) {
+            cleaned::record_error(path, &e);
+            continue;
+        }
+        cleaned::record(path, size, CleanedItemType::File);
+        print_success(&format!("Emptied mailbox {:?}", path));
+        bytes_saved += size;
+    }
+    Ok(bytes_saved)
+}
+
+/// Removes the dotlock it took when dropped
+struct DotLock(PathBuf);
+
+impl Drop for DotLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// Try `lock` a few times while someone else holds the mailbox
+fn retry_lock<T>(path: &Path, mut lock: impl FnMut() -> Option<T>) -> Result<T> {
+    for attempt in 0..LOCK_ATTEMPTS {
+        if let Some(locked) = lock() {
+            return Ok(locked);
+        }
+        if attempt + 1 < LOCK_ATTEMPTS {
+            std::thread::sleep(LOCK_RETRY);
+        }
+    }
+    bail!("Mailbox {:?} is locked, try again later", path)
+}
+
+/// Open `path` for writing, failing on a symlink instead of following it.
+/// Non-blocking, so a FIFO put in its place can't hang the run.
+#[cfg(unix)]
+fn open_nofollow(path: &Path) -> std::io::Result<fs::File> {
+    use std::os::unix::fs::OpenOptionsExt;
+    OpenOptions::new()
+        .write(true)
+        .custom_flags(libc::O_NOFOLLOW | libc::O_NONBLOCK)
+        .open(path)
+}
+
+#[cfg(not(unix))]
+fn open_nofollow(path: &Path) -> std::io::Result<fs::File> {
+    OpenOptions::new().write(true).open(path)
+}
+
+/// Hold `file` with `flock`, which some MTAs and mail readers use instead
+/// of or besides the dotlock
+#[cfg(unix)]
+fn flock(file: &fs::File) -> bool {
+    use std::os::fd::AsRawFd;
+    unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) == 0 }
+}
+
+#[cfg(not(unix))]
+fn flock(_file: &fs::File) -> bool {
+    true
+}
+
+/// Empty a mailbox in place, so the owner and mode stay as the MTA expects.
+/// Mail delivered meanwhile would be lost, so it first takes the locks
+/// delivery respects: the `<mailbox>.lock` dotlock, then `flock`. Like any
+/// deletion it counts towards the run limit and is audited. The mailbox is
+/// opened without following symlinks and must still be the file the scan
+/// saw, so root never empties a file someone linked in its place.
+pub fn truncate_mailbox(mailbox: &Mailbox) -> Result<()> {
+    let (path, size) = (mailbox.path.as_path(), mailbox.size);
+    protection::guard(path)?;
+    limits::check(1, size)?;
+    let mut name = path.as_os_str().to_owned();
+    name.push(".lock");
+    let dotlock = PathBuf::from(name);
+    let _dotlock = retry_lock(path, || {
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&dotlock)
+            .ok()
+            .map(|_| DotLock(dotlock.clone()))
+    })?;
+    let file = open_nofollow(path).with_context(|| format!("Failed to open mailbox {:?}", path))?;
+    let metadata = file
+        .metadata()
+        .with_context(|| format!("Failed to stat mailbox {:?}", path))?;
+    if !metadata.is_file() || file_identity(&metadata) != mailbox.identity {
+        bail!("Mailbox {:?} was replaced since it was scanned", path);
+    }
+    retry_lock(path, || flock(&file).then_some(()))?;
+    audit::record(path)?;
+    file.set_len(0)
+        .with_context(|| format!("Failed to empty mailbox {:?}", path))?;
+    limits::record(1, size);
+    Ok(())
+}
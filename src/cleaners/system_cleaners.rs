@@ -1,24 +1,33 @@
 use anyhow::Result;
 use log::{debug, info, warn};
 use std::fs::{self, read_dir};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
-
+use std::time::SystemTime;
+
+use super::cache_dirs::{remove_files, scan_files};
+use super::cleaned::{self, CleanedItemType};
+use super::cups;
+use super::custom::{self, CustomScope};
+use super::freebsd_cleaners;
+use super::kernels::{self, KernelPackage};
+use super::log_backends::{self, LogBackend};
+use super::package_cache::{self, PackageManager, RpmFrontend};
+use super::rebuild;
+use super::{CleanerInfo, ScanReport, Tag};
+use crate::cancel;
+use crate::exclude;
+use crate::filesystem::{filesystem, home_dir, is_sandboxed, root_path};
+use crate::history::RunRecord;
+use crate::platform::Platform;
+use crate::priority::throttle_deletion;
+use crate::privileges;
+use crate::protection::{self, Protection};
 use crate::utils::{
-    check_root, confirm, execute_with_sudo, format_size, get_size, print_error, print_success,
-    print_warning,
+    check_root, command_exists, confirm, execute_with_sudo, format_size, get_size, print_error,
+    print_success, print_warning,
 };
 
-/// Information about a system cleaner.
-pub struct CleanerInfo {
-    /// The name of the cleaner.
-    pub name: &'static str,
-    /// A description of what the cleaner does.
-    pub description: &'static str,
-    /// The function that performs the cleaning operation.
-    pub function: fn(bool) -> Result<u64>,
-}
-
 /// Lists all available system cleaners with their descriptions.
 pub fn list_cleaners() -> Vec<String> {
     get_cleaners()
@@ -27,70 +36,171 @@ pub fn list_cleaners() -> Vec<String> {
         .collect()
 }
 
-/// Returns a vector of all available system cleaners.
+/// Returns a vector of the system cleaners for the current platform.
+/// Linux-only cleaners (journalctl, apt/pacman/dnf, kernels, libvirt) are
+/// left out on FreeBSD, which gets its own `pkg` and newsyslog cleaners.
 pub fn get_cleaners() -> Vec<CleanerInfo> {
-    vec![
-        CleanerInfo {
-            name: "Package Manager Caches",
-            description: "Clean package manager caches (apt, pacman, dnf, etc.)",
-            function: clean_package_caches,
-        },
-        CleanerInfo {
-            name: "System Logs",
-            description: "Clean old system logs",
-            function: clean_system_logs,
-        },
-        CleanerInfo {
-            name: "System Caches",
-            description: "Clean system-wide cache directories",
-            function: clean_system_caches,
-        },
-        CleanerInfo {
-            name: "Temporary Files",
-            description: "Clean system temporary files",
-            function: clean_temp_files,
-        },
-        CleanerInfo {
-            name: "Old Kernels",
-            description: "Remove old unused kernels",
-            function: clean_old_kernels,
-        },
-        CleanerInfo {
-            name: "Crash Reports",
-            description: "Remove system crash reports and core dumps",
-            function: clean_crash_reports,
-        },
-    ]
-}
-
-/// Runs all system cleaners.
+    let platform = Platform::current();
+    let mut cleaners = Vec::new();
+
+    if platform == Platform::Linux {
+        cleaners.extend([
+            CleanerInfo::new(
+                "Package Manager Caches",
+                "Clean package manager caches (apt, pacman, dnf, etc.)",
+                clean_package_caches,
+            )
+            .tags(&[Tag::Packages, Tag::Heavy])
+            .available(package_manager_available),
+            CleanerInfo::new(
+                "APT Package Lists",
+                "Opt-in: remove package lists in /var/lib/apt/lists; apt can't install \
+                 or upgrade until 'apt update' downloads them again",
+                clean_apt_lists,
+            )
+            .tags(&[Tag::Packages])
+            .scan(scan_apt_lists)
+            .available(apt_available)
+            .high_risk()
+            .sub_item_of("Package Manager Caches"),
+            CleanerInfo::new(
+                "APT Periodic Stamps",
+                "Remove stale stamps in /var/lib/apt/periodic so apt's daily jobs run again",
+                clean_apt_periodic_stamps,
+            )
+            .tags(&[Tag::Packages])
+            .scan(scan_apt_periodic_stamps)
+            .available(apt_available)
+            .sub_item_of("Package Manager Caches"),
+            CleanerInfo::new(
+                "System Caches",
+                "Clean system-wide cache directories",
+                clean_system_caches,
+            ),
+        ]);
+    }
+    if platform == Platform::FreeBsd {
+        cleaners.extend(freebsd_cleaners::get_cleaners());
+    }
+
+    cleaners.extend([
+        CleanerInfo::new(
+            "System Logs",
+            "Clean old system logs (journal, rotated syslog files, svlogd archives)",
+            clean_system_logs,
+        )
+        .tags(&[Tag::Logs])
+        .scan(scan_system_logs)
+        .docs(include_str!("docs/system_logs.md")),
+        CleanerInfo::new(
+            "Unit Journals",
+            "Vacuum the journals of noisy units listed in journal_vacuum.units",
+            log_backends::vacuum_unit_journals,
+        )
+        .tags(&[Tag::Logs])
+        .scan(log_backends::scan_unit_journals)
+        .available(unit_journals_available)
+        .sub_item_of("System Logs")
+        .docs(include_str!("docs/unit_journals.md")),
+        CleanerInfo::new(
+            "Temporary Files",
+            "Clean system temporary files",
+            clean_temp_files,
+        ),
+    ]);
+    cleaners.extend(cups::get_cleaners());
+
+    if platform == Platform::Linux {
+        cleaners.extend([
+            CleanerInfo::new(
+                "Old Kernels",
+                "Remove old unused kernels (dpkg and dnf-based systems)",
+                clean_old_kernels,
+            )
+            .tags(&[Tag::Packages, Tag::Heavy])
+            .scan(scan_old_kernels)
+            .available(kernel_cleanup_available)
+            .docs(include_str!("docs/old_kernels.md")),
+            CleanerInfo::new(
+                "Crash Reports",
+                "Remove system crash reports and core dumps",
+                clean_crash_reports,
+            )
+            .tags(&[Tag::Logs]),
+            CleanerInfo::new(
+                "VM Artifacts",
+                "Remove stale libvirt save/dump files and orphaned GNOME Boxes ISOs",
+                clean_vm_artifacts,
+            )
+            .tags(&[Tag::Heavy])
+            .scan(scan_vm_artifacts)
+            .available(vm_tools_available)
+            .high_risk(),
+        ]);
+    }
+    cleaners.extend(custom::get_cleaners(CustomScope::System));
+    cleaners
+}
+
+/// Runs all system cleaners, returning what each one did.
 ///
 /// # Arguments
 /// * `skip_confirmation` - If true, skip confirmation prompts.
-pub fn run_all(skip_confirmation: bool) -> Result<()> {
-    let cleaners = get_cleaners();
-    let mut total_saved: u64 = 0;
-
-    for cleaner in cleaners {
-        if skip_confirmation || confirm(&format!("Run '{}'?", cleaner.name), true)? {
-            match (cleaner.function)(skip_confirmation) {
-                Ok(bytes) => {
-                    total_saved += bytes;
-                    print_success(&format!(
-                        "{} completed: freed {}",
-                        cleaner.name,
-                        format_size(bytes)
-                    ));
-                }
-                Err(err) => {
-                    print_error(&format!("Error in {}: {}", cleaner.name, err));
-                }
-            }
-        }
-    }
+pub fn run_all(skip_confirmation: bool) -> Result<RunRecord> {
+    privileges::as_root(|| super::run_cleaners(get_cleaners(), skip_confirmation))
+}
+
+fn package_manager_available() -> bool {
+    ["apt-get", "pacman", "dnf5", "dnf", "yum"]
+        .iter()
+        .any(|pm| command_exists(pm))
+}
+
+fn apt_available() -> bool {
+    command_exists("apt-get")
+}
+
+fn apt_lists() -> Vec<(PathBuf, u64)> {
+    package_cache::apt_list_files(&root_path("/var/lib/apt/lists"))
+}
+
+fn apt_periodic_stamps() -> Vec<(PathBuf, u64)> {
+    package_cache::stale_periodic_stamps(
+        &root_path("/var/lib/apt/periodic"),
+        package_cache::PERIODIC_STAMP_MAX_AGE,
+        SystemTime::now(),
+    )
+}
 
-    print_success(&format!("Total space freed: {}", format_size(total_saved)));
-    Ok(())
+fn scan_apt_lists() -> Result<ScanReport> {
+    Ok(scan_files(&apt_lists()))
+}
+
+fn scan_apt_periodic_stamps() -> Result<ScanReport> {
+    Ok(scan_files(&apt_periodic_stamps()))
+}
+
+fn clean_apt_lists(skip_confirmation: bool) -> Result<u64> {
+    remove_files(apt_lists(), "APT package lists", skip_confirmation)
+}
+
+fn clean_apt_periodic_stamps(skip_confirmation: bool) -> Result<u64> {
+    remove_files(
+        apt_periodic_stamps(),
+        "stale APT periodic stamps",
+        skip_confirmation,
+    )
+}
+
+fn unit_journals_available() -> bool {
+    Platform::current()
+        .log_backends()
+        .contains(&LogBackend::Journald)
+        && log_backends::unit_journals_configured()
+}
+
+fn kernel_cleanup_available() -> bool {
+    kernels::package_manager().is_some()
 }
 
 fn clean_package_caches(_skip_confirmation: bool) -> Result<u64> {
@@ -98,6 +208,12 @@ fn clean_package_caches(_skip_confirmation: bool) -> Result<u64> {
 
     info!("Starting package cache cleaning...");
 
+    // Package managers act on the real system, so never run them sandboxed
+    if is_sandboxed() {
+        debug!("Sandboxed, skipping package manager caches");
+        return Ok(0);
+    }
+
     // Check if we have root privileges
     if !check_root() {
         return Err(anyhow::anyhow!(
@@ -109,55 +225,92 @@ fn clean_package_caches(_skip_confirmation: bool) -> Result<u64> {
     if std::path::Path::new("/usr/bin/apt-get").exists()
         || std::path::Path::new("/usr/bin/apt").exists()
     {
-        info!("Found APT package manager, cleaning cache...");
-        let cache_size = get_size("/var/cache/apt/archives/").unwrap_or(5 * 1024 * 1024);
-
-        let output = execute_with_sudo("apt-get", &["clean"])?;
-
-        if output.status.success() {
-            info!("Successfully cleaned APT cache");
-            bytes_saved += cache_size;
+        if let Some(keep) = package_cache::retention().keep_for(PackageManager::Apt) {
+            info!(
+                "Found APT package manager, keeping the newest {} versions...",
+                keep
+            );
+            bytes_saved += package_cache::prune(
+                &root_path("/var/cache/apt/archives"),
+                PackageManager::Apt,
+                keep,
+            )?;
         } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            warn!("Failed to clean APT cache: {}", stderr);
-        }
+            info!("Found APT package manager, cleaning cache...");
+            let archives = Path::new("/var/cache/apt/archives");
+            let before = log_backends::find_files(archives, |_| true);
+            if !holds_excluded(&before, "apt-get clean") {
+                let output = execute_with_sudo("apt-get", &["clean"])?;
+
+                if output.status.success() {
+                    info!("Successfully cleaned APT cache");
+                } else {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    warn!("Failed to clean APT cache: {}", stderr);
+                }
 
-        // Also clean autoclean
-        let output = execute_with_sudo("apt-get", &["autoclean"])?;
+                // Also clean autoclean
+                let output = execute_with_sudo("apt-get", &["autoclean"])?;
 
-        if output.status.success() {
-            info!("Successfully cleaned APT autoclean");
-            bytes_saved += cache_size / 2;
+                if output.status.success() {
+                    info!("Successfully cleaned APT autoclean");
+                }
+                bytes_saved += record_removed(&before);
+            }
         }
     }
 
     if std::path::Path::new("/usr/bin/pacman").exists() {
-        info!("Found Pacman package manager, cleaning cache...");
-        let cache_size = get_size("/var/cache/pacman/pkg/").unwrap_or(10 * 1024 * 1024);
-
-        let output = execute_with_sudo("pacman", &["-Sc", "--noconfirm"])?;
-
-        if output.status.success() {
-            info!("Successfully cleaned Pacman cache");
-            bytes_saved += cache_size;
+        let retention = package_cache::retention().keep_for(PackageManager::Pacman);
+        if let Some(keep) = retention {
+            info!(
+                "Found Pacman package manager, keeping the newest {} versions...",
+                keep
+            );
+            bytes_saved += clean_pacman_cache_keeping(keep)?;
         } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            warn!("Failed to clean Pacman cache: {}", stderr);
+            info!("Found Pacman package manager, cleaning cache...");
+            let before = log_backends::find_files(Path::new("/var/cache/pacman/pkg"), |_| true);
+            if !holds_excluded(&before, "pacman -Sc") {
+                let output = execute_with_sudo("pacman", &["-Sc", "--noconfirm"])?;
+
+                if output.status.success() {
+                    info!("Successfully cleaned Pacman cache");
+                    bytes_saved += record_removed(&before);
+                } else {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    warn!("Failed to clean Pacman cache: {}", stderr);
+                }
+            }
         }
     }
 
-    if std::path::Path::new("/usr/bin/dnf").exists() {
-        info!("Found DNF package manager, cleaning cache...");
-        let cache_size = get_size("/var/cache/dnf/").unwrap_or(10 * 1024 * 1024);
-
-        let output = execute_with_sudo("dnf", &["clean", "all"])?;
+    if let Some(frontend) = RpmFrontend::detect() {
+        bytes_saved += clean_rpm_cache(frontend)?;
+    }
 
-        if output.status.success() {
-            info!("Successfully cleaned DNF cache");
-            bytes_saved += cache_size;
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            warn!("Failed to clean DNF cache: {}", stderr);
+    // PackageKit (GNOME Software, Discover) keeps its own downloads, often
+    // larger than the package manager's cache
+    let packagekit = root_path("/var/cache/PackageKit");
+    if packagekit.exists() {
+        info!("Cleaning PackageKit cache...");
+        let fs = filesystem();
+        for entry in fs.read_dir(&packagekit)? {
+            let is_dir = entry.is_dir();
+            let removed = if is_dir {
+                fs.remove_dir_all(&entry)
+            } else {
+                fs.remove_file(&entry)
+            };
+            match removed {
+                Ok(size) => {
+                    cleaned::record_path(&entry, size, is_dir);
+                    bytes_saved += size;
+                }
+                Err(e) => {
+                    cleaned::record_error(&entry, &e);
+                }
+            }
         }
     }
 
@@ -168,102 +321,89 @@ fn clean_package_caches(_skip_confirmation: bool) -> Result<u64> {
     Ok(bytes_saved)
 }
 
-fn clean_system_logs(skip_confirmation: bool) -> Result<u64> {
-    let log_paths = vec!["/var/log"];
-
-    let mut bytes_saved = 0;
-
-    for log_path in log_paths {
-        let path = Path::new(log_path);
-        if path.exists() {
-            // Calculate size of files we can safely remove
-            let mut size_to_clean = 0;
-
-            if let Ok(entries) = read_dir(path) {
-                for entry in entries.flatten() {
-                    let file_path = entry.path();
-                    let filename = file_path.file_name().unwrap_or_default().to_string_lossy();
-
-                    // Skip current log files and only target rotated logs
-                    if file_path.is_file()
-                        && (filename.ends_with(".gz")
-                            || filename.ends_with(".old")
-                            || filename.contains(".1")
-                            || filename.contains(".2"))
-                    {
-                        if let Ok(metadata) = fs::metadata(&file_path) {
-                            size_to_clean += metadata.len();
-                        }
-                    }
-                }
-            }
-
-            if size_to_clean > 0 {
-                if skip_confirmation
-                    || confirm(
-                        &format!(
-                            "Clean old logs in {} ({} to be freed)?",
-                            log_path,
-                            format_size(size_to_clean)
-                        ),
-                        true,
-                    )?
-                {
-                    // Use find to delete old log files
-                    let output = execute_with_sudo(
-                        "find",
-                        &[
-                            log_path, "-type", "f", "-name", "*.gz", "-o", "-name", "*.old", "-o",
-                            "-name", "*.1", "-o", "-name", "*.2", "-o", "-name", "*.3", "-o",
-                            "-name", "*.4", "-delete",
-                        ],
-                    )?;
-
-                    if output.status.success() {
-                        print_success(&format!("Cleaned old logs in {}", log_path));
-                        bytes_saved += size_to_clean;
-                    } else {
-                        print_error(&format!("Failed to clean logs in {}", log_path));
-                    }
-                }
-            } else {
-                debug!("No old logs found in {}", log_path);
-            }
+/// Clean the dnf5/dnf/yum cache with the front end's own command, or prune
+/// old packages when a retention is configured
+fn clean_rpm_cache(frontend: RpmFrontend) -> Result<u64> {
+    let cache_dir = frontend.cache_dir();
+
+    if let Some(keep) = package_cache::retention().keep_for(PackageManager::Dnf) {
+        info!(
+            "Found {} package manager, keeping the newest {} versions...",
+            frontend.name(),
+            keep
+        );
+        // Packages are only cached with keepcache=1
+        let mut freed = 0;
+        for dir in package_cache::rpm_package_dirs(&root_path(cache_dir)) {
+            freed += package_cache::prune(&dir, PackageManager::Dnf, keep)?;
         }
+        return Ok(freed);
     }
 
-    // Additionally, use journalctl to vacuum logs if available
-    if Command::new("which")
-        .arg("journalctl")
-        .output()?
-        .status
-        .success()
-    {
-        // Get current journal size
-        let output = execute_with_sudo("journalctl", &["--disk-usage"])?;
-
-        let disk_usage = String::from_utf8_lossy(&output.stdout);
-        debug!("Journal disk usage: {}", disk_usage);
-
-        // Estimate size - this is a rough approximation as we can't easily parse the output
-        let journal_size: u64 = 100 * 1024 * 1024; // Default 100MB estimation
+    info!(
+        "Found {} package manager, cleaning cache...",
+        frontend.name()
+    );
+    let before = log_backends::find_files(Path::new(cache_dir), |_| true);
+    if holds_excluded(&before, &format!("{} clean all", frontend.command())) {
+        return Ok(0);
+    }
+    let output = execute_with_sudo(frontend.command(), &["clean", "all"])?;
+    if !output.status.success() {
+        warn!(
+            "Failed to clean {} cache: {}",
+            frontend.name(),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        return Ok(0);
+    }
+    info!("Successfully cleaned {} cache", frontend.name());
+    Ok(record_removed(&before))
+}
 
-        if skip_confirmation || confirm("Vacuum system journal logs?", true)? {
-            // Keep only logs from the last week
-            let output = execute_with_sudo("journalctl", &["--vacuum-time=7d"])?;
+/// Keep the newest `keep` versions of each package in pacman's cache, with
+/// `paccache` when it is installed so versions are compared properly
+fn clean_pacman_cache_keeping(keep: u32) -> Result<u64> {
+    let cache = "/var/cache/pacman/pkg";
+    if !command_exists("paccache") {
+        return package_cache::prune(&root_path(cache), PackageManager::Pacman, keep);
+    }
 
-            if output.status.success() {
-                print_success("Cleaned system journal logs");
-                bytes_saved += journal_size / 2; // Estimate we saved half of the journal size
-            } else {
-                print_error("Failed to clean system journal logs");
-            }
-        }
+    let before = log_backends::find_files(Path::new(cache), |_| true);
+    if holds_excluded(&before, "paccache") {
+        return Ok(0);
+    }
+    let output = execute_with_sudo("paccache", &["-r", "-k", &keep.to_string()])?;
+    if !output.status.success() {
+        warn!(
+            "Failed to run paccache: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
     }
+    Ok(record_removed(&before))
+}
 
+/// Clean old logs with each backend the platform uses, so journald,
+/// syslog and svlogd setups are all covered
+fn clean_system_logs(skip_confirmation: bool) -> Result<u64> {
+    let mut bytes_saved = 0;
+    for backend in Platform::current().log_backends() {
+        info!("Cleaning {}...", backend.name());
+        bytes_saved += backend.clean(skip_confirmation)?;
+    }
     Ok(bytes_saved)
 }
 
+fn scan_system_logs() -> Result<ScanReport> {
+    let mut report = ScanReport::new();
+    for backend in Platform::current().log_backends() {
+        let scan = backend.scan()?;
+        report.entries.extend(scan.entries);
+        report.notes.extend(scan.notes);
+    }
+    Ok(report)
+}
+
 fn clean_system_caches(skip_confirmation: bool) -> Result<u64> {
     let cache_paths = vec![
         "/var/cache/ldconfig",
@@ -274,7 +414,8 @@ fn clean_system_caches(skip_confirmation: bool) -> Result<u64> {
     let mut bytes_saved = 0;
 
     for cache_path in cache_paths {
-        let path = Path::new(cache_path);
+        let path = root_path(cache_path);
+        let cache_path = path.to_str().unwrap_or(cache_path);
         if path.exists() {
             let size = get_size(cache_path)?;
 
@@ -289,37 +430,32 @@ fn clean_system_caches(skip_confirmation: bool) -> Result<u64> {
                         true,
                     )?)
             {
-                // Use rm with sudo to remove files
-                let output = if path.is_dir() {
+                let freed = if path.is_dir() {
                     // Remove contents but keep the directory
-                    execute_with_sudo("sh", &["-c", &format!("rm -rf {}/*", cache_path)])
+                    empty_system_dir(&path)?
                 } else {
-                    // Remove the file
-                    execute_with_sudo("rm", &["-f", cache_path])
+                    remove_system_paths(path.parent().unwrap_or(&path), &[(path.clone(), size)])
                 };
-
-                match output {
-                    Ok(out) if out.status.success() => {
-                        print_success(&format!("Cleaned system cache in {}", cache_path));
-                        bytes_saved += size;
-                    }
-                    Ok(_) => {
-                        warn!("Failed to clean cache in {}", cache_path);
-                    }
-                    Err(e) => {
-                        warn!("Failed to execute rm for {}: {}", cache_path, e);
+                if freed > 0 {
+                    print_success(&format!("Cleaned system cache in {}", cache_path));
+                    bytes_saved += freed;
+                    if cache_path.ends_with("/var/cache/fontconfig") {
+                        rebuild::font_cache(true);
                     }
+                } else {
+                    warn!("Failed to clean cache in {}", cache_path);
                 }
             }
         }
     }
 
     // Run updatedb to update locate database if it exists
-    if Command::new("which")
-        .arg("updatedb")
-        .output()?
-        .status
-        .success()
+    if !is_sandboxed()
+        && Command::new("which")
+            .arg("updatedb")
+            .output()?
+            .status
+            .success()
         && (skip_confirmation || confirm("Update locate database?", true)?)
     {
         let output = execute_with_sudo("updatedb", &[])?;
@@ -334,33 +470,141 @@ fn clean_system_caches(skip_confirmation: bool) -> Result<u64> {
     Ok(bytes_saved)
 }
 
+/// Remove everything in `dir` but the directory itself, recording each
+/// entry removed
+fn empty_system_dir(dir: &Path) -> Result<u64> {
+    let fs = filesystem();
+    let entries: Vec<(PathBuf, u64)> = fs
+        .read_dir(dir)?
+        .into_iter()
+        .map(|path| {
+            let size = fs.size(&path).unwrap_or(0);
+            (path, size)
+        })
+        .collect();
+    Ok(remove_system_paths(dir, &entries))
+}
+
+/// Files below `dir` that `wanted` picks, with their sizes. Symlinks are
+/// never followed and directories in `prune` aren't entered. Paths are
+/// kept as they are read, never passed through another program's output.
+fn find_system_files(
+    dir: &Path,
+    prune: &[PathBuf],
+    wanted: impl Fn(&Path, &fs::Metadata) -> bool,
+) -> Vec<(PathBuf, u64)> {
+    let mut files = Vec::new();
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        // Unreadable directories are left out, the rest still counts
+        let Ok(entries) = read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(metadata) = path.symlink_metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                if !prune.contains(&path) {
+                    stack.push(path);
+                }
+            } else if metadata.is_file() && wanted(&path, &metadata) {
+                files.push((path, metadata.len()));
+            }
+        }
+    }
+    files
+}
+
+/// Remove `paths`, found below `root`, through the deleter, recording each
+/// one removed with the bytes it actually unlinked, and return those.
+/// Files are unlinked through
+/// directories opened from `root` down, so a directory swapped for a
+/// symlink meanwhile can't redirect the deletion; directories must be
+/// directly in `root`.
+fn remove_system_paths(root: &Path, paths: &[(PathBuf, u64)]) -> u64 {
+    let fs = filesystem();
+    let mut freed = 0;
+    for (path, _) in paths {
+        let is_dir = path.symlink_metadata().is_ok_and(|m| m.is_dir());
+        let result = if is_dir && path.parent() == Some(root) {
+            fs.remove_dir_all(path)
+        } else if is_dir {
+            Err(anyhow::anyhow!(
+                "Refusing to remove {:?} from {:?}",
+                path,
+                root
+            ))
+        } else {
+            fs.remove_file_below(root, path)
+        };
+        match result {
+            Ok(size) => {
+                cleaned::record_path(path, size, is_dir);
+                freed += size;
+                throttle_deletion();
+            }
+            Err(e) => cleaned::record_error(path, &e),
+        }
+        if cancel::is_requested() {
+            break;
+        }
+    }
+    freed
+}
+
+/// Whether an exclusion covers one of the files a package manager
+/// `command` would remove. It can't be told to keep it, so it isn't run
+/// and the file is reported as skipped.
+fn holds_excluded(files: &[(PathBuf, u64)], command: &str) -> bool {
+    let Some((path, _)) = files.iter().find(|(path, _)| exclude::is_excluded(path)) else {
+        return false;
+    };
+    protection::record(path, Protection::Excluded);
+    print_warning(&format!(
+        "Not running {}, it would remove the excluded {}",
+        command,
+        path.display()
+    ));
+    true
+}
+
+/// Record the files of `before` a package manager removed, returning
+/// their bytes
+fn record_removed(before: &[(PathBuf, u64)]) -> u64 {
+    let mut freed = 0;
+    for (path, size) in before {
+        if path
+            .symlink_metadata()
+            .is_err_and(|e| e.kind() == std::io::ErrorKind::NotFound)
+        {
+            cleaned::record_path(path, *size, false);
+            freed += size;
+        }
+    }
+    freed
+}
+
 fn clean_temp_files(skip_confirmation: bool) -> Result<u64> {
     let temp_paths = vec!["/tmp", "/var/tmp"];
 
     let mut bytes_saved = 0;
 
     for temp_path in temp_paths {
-        let path = Path::new(temp_path);
+        let path = root_path(temp_path);
+        let temp_path = path.to_str().unwrap_or(temp_path);
         if path.exists() {
-            // Calculate size of old files we can safely remove (older than 7 days)
-            let output = Command::new("find")
-                .args([
-                    temp_path, "-type", "f", "-atime",
-                    "+1", // Files not accessed in the last day
-                    "-exec", "du", "-sc", "{}", ";",
-                ])
-                .output()?;
-
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let mut size_to_clean: u64 = 0;
-
-            // Try to parse the total size from du output
-            if let Some(total_line) = stdout.lines().last() {
-                if let Some(size_str) = total_line.split_whitespace().next() {
-                    size_to_clean = size_str.parse::<u64>().unwrap_or(0) * 1024;
-                    // Convert KB to bytes
-                }
-            }
+            // Files not accessed in the last day
+            let files = find_system_files(&path, &[], |_, metadata| {
+                // Two whole days since last access, like find -atime +1
+                metadata
+                    .accessed()
+                    .ok()
+                    .and_then(|accessed| SystemTime::now().duration_since(accessed).ok())
+                    .is_some_and(|age| age.as_secs() >= 2 * 24 * 60 * 60)
+            });
+            let size_to_clean: u64 = files.iter().map(|(_, size)| size).sum();
 
             if size_to_clean > 0 {
                 if skip_confirmation
@@ -373,19 +617,10 @@ fn clean_temp_files(skip_confirmation: bool) -> Result<u64> {
                         true,
                     )?
                 {
-                    // Use find to delete old temporary files with sudo
-                    let output = execute_with_sudo(
-                        "find",
-                        &[
-                            temp_path, "-type", "f", "-atime",
-                            "+1", // Files not accessed in the last day
-                            "-delete",
-                        ],
-                    )?;
-
-                    if output.status.success() {
+                    let freed = remove_system_paths(&path, &files);
+                    if freed > 0 {
                         print_success(&format!("Cleaned old temporary files in {}", temp_path));
-                        bytes_saved += size_to_clean;
+                        bytes_saved += freed;
                     } else {
                         print_error(&format!("Failed to clean temporary files in {}", temp_path));
                     }
@@ -399,70 +634,96 @@ fn clean_temp_files(skip_confirmation: bool) -> Result<u64> {
     Ok(bytes_saved)
 }
 
+/// Packages of the kernels `manager` would remove, with their installed
+/// sizes
+fn old_kernel_packages(manager: PackageManager) -> Result<Vec<KernelPackage>> {
+    let running = kernels::running_kernel()?;
+    debug!("Current kernel: {}", running);
+    let installed = kernels::installed_kernels(manager)?;
+    Ok(kernels::old_kernels(&installed, &running, kernels::keep()))
+}
+
+fn scan_old_kernels() -> Result<ScanReport> {
+    let mut report = ScanReport::new();
+    let Some(manager) = kernels::package_manager().filter(|_| !is_sandboxed()) else {
+        return Ok(report);
+    };
+    for package in old_kernel_packages(manager)? {
+        report.add_entry(&package.name, package.size, package.size);
+    }
+    report.add_note(format!(
+        "Keeps the running kernel and the newest {}",
+        kernels::keep()
+    ));
+    Ok(report)
+}
+
 fn clean_old_kernels(skip_confirmation: bool) -> Result<u64> {
     let mut bytes_saved = 0;
 
-    // Only try to clean kernels on systems with apt (Debian/Ubuntu)
-    if Command::new("which").arg("apt").output()?.status.success()
-        && Command::new("which").arg("dpkg").output()?.status.success()
+    // Package managers act on the real system, so never run them sandboxed
+    let Some(manager) = kernels::package_manager().filter(|_| !is_sandboxed()) else {
+        return Ok(0);
+    };
+    let packages = old_kernel_packages(manager)?;
+    debug!("Found {} old kernel packages", packages.len());
+    if packages.is_empty() {
+        debug!("No old kernels to clean");
+        return Ok(0);
+    }
+
+    let size: u64 = packages.iter().map(|package| package.size).sum();
+    if !skip_confirmation
+        && !confirm(
+            &format!(
+                "Remove {} old kernel packages ({} to be freed)?",
+                packages.len(),
+                format_size(size)
+            ),
+            true,
+        )?
     {
-        // Get current kernel version
-        let output = Command::new("uname").arg("-r").output()?;
-        let current_kernel = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        debug!("Current kernel: {}", current_kernel);
-
-        // List installed kernels
-        let output = Command::new("dpkg")
-            .args(["-l", "linux-image-*"])
-            .output()?;
-
-        let installed_kernels = String::from_utf8_lossy(&output.stdout);
-
-        // Count how many kernels are installed
-        let kernel_count = installed_kernels
-            .lines()
-            .filter(|l| l.contains("linux-image-") && l.starts_with("ii"))
-            .count();
-
-        debug!("Found {} installed kernels", kernel_count);
-
-        // Only clean if we have more than 2 kernels (current + previous)
-        if kernel_count > 2 {
-            // Estimate size to be cleaned (average kernel size is around 200MB)
-            let estimated_size = (kernel_count - 2) as u64 * 200 * 1024 * 1024;
-
-            if skip_confirmation
-                || confirm(
-                    &format!(
-                        "Remove old kernels (approximately {} to be freed)?",
-                        format_size(estimated_size)
-                    ),
-                    true,
-                )?
-            {
-                // Check if we have purge-old-kernels command (from byobu package)
-                if Command::new("which")
-                    .arg("purge-old-kernels")
-                    .output()?
-                    .status
-                    .success()
-                {
-                    let output = execute_with_sudo("purge-old-kernels", &["--keep", "1"])?;
+        return Ok(0);
+    }
 
-                    if output.status.success() {
-                        print_success("Removed old kernels");
-                        bytes_saved += estimated_size;
-                    } else {
-                        print_error("Failed to remove old kernels");
-                    }
-                } else {
-                    // Use apt to clean old kernels - this is less safe, so we'll skip it
-                    print_warning("purge-old-kernels not found. Install byobu package for safer kernel cleanup.");
-                }
-            }
-        } else {
-            debug!("Not enough kernels installed to clean");
+    let keep = kernels::keep();
+    let output = if manager == PackageManager::Dnf {
+        // Like --keep, the limit leaves the running kernel alone on top of
+        // the newest ones, but dnf won't take a limit below 2
+        let dnf = RpmFrontend::detect().map_or("dnf", |frontend| frontend.command());
+        let limit = format!("installonly_limit={}", keep.max(2));
+        execute_with_sudo(
+            dnf,
+            &["remove", "-y", "--oldinstallonly", "--setopt", &limit],
+        )?
+    } else if command_exists("purge-old-kernels") {
+        // purge-old-kernels comes with the byobu package
+        execute_with_sudo("purge-old-kernels", &["--keep", &keep.to_string()])?
+    } else {
+        // Removing them with apt directly is less safe, so we'll skip it
+        print_warning(
+            "purge-old-kernels not found. Install byobu package for safer kernel cleanup.",
+        );
+        return Ok(0);
+    };
+
+    if output.status.success() {
+        // Count what is really gone, in case the tool kept more
+        let remaining = old_kernel_packages(manager).unwrap_or_default();
+        for package in packages.iter().filter(|p| !remaining.contains(p)) {
+            cleaned::record(
+                Path::new(&package.name),
+                package.size,
+                CleanedItemType::File,
+            );
+            bytes_saved += package.size;
         }
+        print_success(&format!(
+            "Removed old kernels, freed {}",
+            format_size(bytes_saved)
+        ));
+    } else {
+        print_error("Failed to remove old kernels");
     }
 
     Ok(bytes_saved)
@@ -474,7 +735,8 @@ fn clean_crash_reports(skip_confirmation: bool) -> Result<u64> {
     let mut bytes_saved = 0;
 
     for crash_path in crash_paths {
-        let path = Path::new(crash_path);
+        let path = root_path(crash_path);
+        let crash_path = path.to_str().unwrap_or(crash_path);
         if path.exists() {
             let size = get_size(crash_path)?;
 
@@ -489,78 +751,190 @@ fn clean_crash_reports(skip_confirmation: bool) -> Result<u64> {
                         true,
                     )?)
             {
-                // Use rm with sudo to remove crash reports
-                let output = if path.is_dir() {
+                let freed = if path.is_dir() {
                     // Remove contents but keep the directory
-                    execute_with_sudo("sh", &["-c", &format!("rm -rf {}/*", crash_path)])
+                    empty_system_dir(&path)?
                 } else {
-                    // Remove the file
-                    execute_with_sudo("rm", &["-f", crash_path])
+                    remove_system_paths(path.parent().unwrap_or(&path), &[(path.clone(), size)])
                 };
-
-                match output {
-                    Ok(out) if out.status.success() => {
-                        print_success(&format!("Cleaned crash reports in {}", crash_path));
-                        bytes_saved += size;
-                    }
-                    Ok(_) => {
-                        warn!("Failed to clean crash reports in {}", crash_path);
-                    }
-                    Err(e) => {
-                        warn!("Failed to execute rm for {}: {}", crash_path, e);
-                    }
+                if freed > 0 {
+                    print_success(&format!("Cleaned crash reports in {}", crash_path));
+                    bytes_saved += freed;
+                } else {
+                    warn!("Failed to clean crash reports in {}", crash_path);
                 }
             }
         }
     }
 
-    // Clean core dumps if we can find any
-    if Command::new("which").arg("find").output()?.status.success() {
-        let output = execute_with_sudo(
-            "find",
-            &[
-                "/", "-name", "core", "-o", "-name", "core.*", "-type", "f", "-size",
-                "+10k", // Only files larger than 10KB
-                "-exec", "du", "-sc", "{}", ";",
-            ],
-        )?;
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let mut size_to_clean: u64 = 0;
-
-        // Try to parse the total size from du output
-        if let Some(total_line) = stdout.lines().last() {
-            if let Some(size_str) = total_line.split_whitespace().next() {
-                size_to_clean = size_str.parse::<u64>().unwrap_or(0) * 1024; // Convert KB to bytes
-            }
+    // Clean core dumps if we can find any: files named core or core.<pid>
+    // over 10 KB, outside the kernel's pseudo filesystems
+    let root = root_path("/");
+    let pruned: Vec<PathBuf> = ["/proc", "/sys", "/dev", "/run"]
+        .iter()
+        .map(root_path)
+        .collect();
+    let core_dumps = find_system_files(&root, &pruned, |path, metadata| {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let is_core = name == "core"
+            || name
+                .strip_prefix("core.")
+                .is_some_and(|pid| pid.starts_with(|c: char| c.is_ascii_digit()));
+        is_core && metadata.len() > 10 * 1024
+    });
+    let size_to_clean: u64 = core_dumps.iter().map(|(_, size)| size).sum();
+
+    if size_to_clean > 0
+        && (skip_confirmation
+            || confirm(
+                &format!(
+                    "Clean {} core dumps across system ({} to be freed)?",
+                    core_dumps.len(),
+                    format_size(size_to_clean)
+                ),
+                true,
+            )?)
+    {
+        let freed = remove_system_paths(&root, &core_dumps);
+        if freed > 0 {
+            print_success("Cleaned core dumps");
+            bytes_saved += freed;
+        } else {
+            print_error("Failed to clean core dumps");
         }
+    }
+
+    Ok(bytes_saved)
+}
+
+fn vm_tools_available() -> bool {
+    root_path("/var/lib/libvirt").exists() || command_exists("gnome-boxes")
+}
+
+const LIBVIRT_SAVE_DIR: &str = "/var/lib/libvirt/qemu/save";
+const LIBVIRT_DUMP_DIR: &str = "/var/lib/libvirt/qemu/dump";
+const LIBVIRT_SYSTEM_CONFIG: &str = "/etc/libvirt/qemu";
+
+fn list_files(dir: &Path) -> Vec<PathBuf> {
+    read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_file())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Domain XML definitions from the system and session libvirt instances
+/// (including the flatpak GNOME Boxes one).
+fn libvirt_domain_configs() -> Vec<PathBuf> {
+    let mut dirs = vec![root_path(LIBVIRT_SYSTEM_CONFIG)];
+    if let Ok(home_dir) = home_dir() {
+        dirs.push(home_dir.join(".config/libvirt/qemu"));
+        dirs.push(home_dir.join(".var/app/org.gnome.Boxes/config/libvirt/qemu"));
+    }
+
+    dirs.iter()
+        .flat_map(|dir| list_files(dir))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "xml"))
+        .collect()
+}
+
+/// Save files whose domain is no longer defined, plus every dump file.
+/// A save file of a defined domain is its suspended state, so it is kept.
+fn stale_libvirt_files() -> Vec<PathBuf> {
+    let defined = libvirt_domain_configs();
+    let is_defined = |name: &str| {
+        defined
+            .iter()
+            .any(|p| p.file_stem().is_some_and(|stem| stem == name))
+    };
+
+    let mut files: Vec<PathBuf> = list_files(&root_path(LIBVIRT_SAVE_DIR))
+        .into_iter()
+        .filter(|p| {
+            let name = p.file_stem().unwrap_or_default().to_string_lossy();
+            !is_defined(&name)
+        })
+        .collect();
+    files.extend(list_files(&root_path(LIBVIRT_DUMP_DIR)));
+    files
+}
+
+/// ISOs in the GNOME Boxes download cache that no domain references.
+fn orphaned_boxes_isos() -> Vec<PathBuf> {
+    let Ok(home_dir) = home_dir() else {
+        return Vec::new();
+    };
+    let cache_dirs = [
+        home_dir.join(".cache/gnome-boxes"),
+        home_dir.join(".var/app/org.gnome.Boxes/cache/gnome-boxes"),
+    ];
+
+    let configs: Vec<String> = libvirt_domain_configs()
+        .iter()
+        .filter_map(|p| fs::read_to_string(p).ok())
+        .collect();
+
+    cache_dirs
+        .iter()
+        .flat_map(|dir| list_files(dir))
+        .filter(|p| {
+            p.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("iso"))
+        })
+        .filter(|p| {
+            let path = p.to_string_lossy();
+            !configs.iter().any(|xml| xml.contains(path.as_ref()))
+        })
+        .collect()
+}
 
-        if size_to_clean > 0
-            && (skip_confirmation
-                || confirm(
-                    &format!(
-                        "Clean core dumps across system ({} to be freed)?",
-                        format_size(size_to_clean)
-                    ),
-                    true,
-                )?)
+fn vm_artifacts() -> Vec<(PathBuf, u64)> {
+    stale_libvirt_files()
+        .into_iter()
+        .chain(orphaned_boxes_isos())
+        .map(|p| {
+            let size = fs::metadata(&p).map(|m| m.len()).unwrap_or(0);
+            (p, size)
+        })
+        .collect()
+}
+
+fn scan_vm_artifacts() -> Result<ScanReport> {
+    let mut report = ScanReport::new();
+    for (path, size) in vm_artifacts() {
+        report.add_entry(&path.to_string_lossy(), size, size);
+    }
+    Ok(report)
+}
+
+fn clean_vm_artifacts(skip_confirmation: bool) -> Result<u64> {
+    let mut bytes_saved = 0;
+
+    for (path, size) in vm_artifacts() {
+        // VM state and images can be valuable, so default to keeping them
+        if !skip_confirmation
+            && !confirm(
+                &format!("Remove {:?} ({} to be freed)?", path, format_size(size)),
+                false,
+            )?
         {
-            let output = execute_with_sudo(
-                "find",
-                &[
-                    "/", "-name", "core", "-o", "-name", "core.*", "-type", "f", "-size",
-                    "+10k", // Only files larger than 10KB
-                    "-delete",
-                ],
-            )?;
+            continue;
+        }
 
-            if output.status.success() {
-                print_success("Cleaned core dumps");
-                bytes_saved += size_to_clean;
-            } else {
-                print_error("Failed to clean core dumps");
-            }
+        // As a system cleaner this runs as root, so libvirt's files go
+        // through the deleter like the user's Boxes ISOs
+        if let Err(e) = filesystem().remove_file(&path) {
+            cleaned::record_error(&path, &e);
+            continue;
         }
+        print_success(&format!("Removed VM artifact {}", path.display()));
+        cleaned::record(&path, size, CleanedItemType::File);
+        bytes_saved += size;
+        throttle_deletion();
     }
 
     Ok(bytes_saved)
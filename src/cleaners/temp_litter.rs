@@ -0,0 +1,375 @@
+//! Opt-in cleanup of temp litter that the age-based Temporary Files cleaner
+//! can't judge: old `*.tmp` files, `tmp.XXXXXX` directories left by
+//! `mktemp -d`, the `.org.chromium.*` directories of Chromium-based
+//! browsers, and `systemd-private-*` directories of services that crashed
+//! before systemd could remove them. Every candidate is checked against the
+//! running processes right before it is removed, and kept while anything
+//! still uses it. Without root, other users' litter is left out. The group
+//! only shows up with `advanced_temp_cleanup = true` in the config.
+
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use std::cell::OnceCell;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use super::cache_dirs::{remove_files_unless, scan_files};
+use super::{CleanerInfo, ScanReport};
+use crate::filesystem::{filesystem, root_path};
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+const TEMP_DIRS: [&str; 2] = ["/tmp", "/var/tmp"];
+
+/// Chromium, and Google Chrome which names its directories after itself
+const CHROMIUM_PREFIXES: [&str; 2] = [".org.chromium.Chromium.", ".com.google.Chrome."];
+
+static ENABLED: Lazy<Mutex<bool>> = Lazy::new(|| Mutex::new(false));
+
+/// Register the advanced temp cleaners, from the config file
+pub fn set_enabled(enabled: bool) {
+    *ENABLED.lock().unwrap_or_else(|e| e.into_inner()) = enabled;
+}
+
+/// Whether the advanced temp cleaners are registered
+pub fn enabled() -> bool {
+    *ENABLED.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// A pattern of temp litter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LitterKind {
+    /// `*.tmp` files
+    TmpFile,
+    /// `tmp.XXXXXX` directories from `mktemp -d`
+    MktempDir,
+    /// `.org.chromium.Chromium.XXXXXX` and `.com.google.Chrome.XXXXXX` directories
+    ChromiumDir,
+    /// `systemd-private-<boot id>-<unit>-XXXXXX` directories from `PrivateTmp=`
+    SystemdPrivate,
+}
+
+impl LitterKind {
+    /// How long litter of this kind must have been untouched to be removed
+    pub fn min_age(self) -> Duration {
+        match self {
+            LitterKind::TmpFile => 7 * DAY,
+            LitterKind::MktempDir | LitterKind::ChromiumDir => DAY,
+            // Whether the service runs is known exactly, the age only
+            // covers one that is starting up
+            LitterKind::SystemdPrivate => Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+/// The kind of litter a temp directory entry's name matches, if any
+pub fn classify(name: &str, is_dir: bool) -> Option<LitterKind> {
+    if !is_dir {
+        return (name.len() > ".tmp".len() && name.ends_with(".tmp"))
+            .then_some(LitterKind::TmpFile);
+    }
+    if let Some(random) = name.strip_prefix("tmp.") {
+        let is_random =
+            (6..=12).contains(&random.len()) && random.chars().all(|c| c.is_ascii_alphanumeric());
+        return is_random.then_some(LitterKind::MktempDir);
+    }
+    if CHROMIUM_PREFIXES
+        .iter()
+        .any(|prefix| name.len() > prefix.len() && name.starts_with(prefix))
+    {
+        return Some(LitterKind::ChromiumDir);
+    }
+    systemd_private_parts(name).map(|_| LitterKind::SystemdPrivate)
+}
+
+/// The boot id and unit in a `systemd-private-<boot id>-<unit>-XXXXXX` name
+pub fn systemd_private_parts(name: &str) -> Option<(&str, &str)> {
+    let rest = name.strip_prefix("systemd-private-")?;
+    let (boot_id, rest) = rest.split_once('-')?;
+    let (unit, _) = rest.rsplit_once('-')?;
+    let valid = boot_id.len() == 32 && boot_id.chars().all(|c| c.is_ascii_hexdigit());
+    (valid && !unit.is_empty()).then_some((boot_id, unit))
+}
+
+/// What the running processes use, read right before scanning or cleaning
+#[derive(Debug, Clone, Default)]
+pub struct Liveness {
+    /// Files processes have open, and their working directories
+    pub open: Vec<PathBuf>,
+    /// Names of the running processes
+    pub processes: Vec<String>,
+    /// systemd units with at least one running process
+    pub units: HashSet<String>,
+    /// The current boot id, without dashes
+    pub boot_id: Option<String>,
+}
+
+impl Liveness {
+    /// The processes running now. None where `/proc` can't be read, since
+    /// nothing can then be known to be unused. These are the real
+    /// processes even in a sandbox, which may well have sandbox files open.
+    pub fn snapshot() -> Option<Self> {
+        fs::read_dir("/proc/self/fd").ok()?;
+        let mut liveness = Self {
+            boot_id: fs::read_to_string("/proc/sys/kernel/random/boot_id")
+                .ok()
+                .map(|id| id.trim().replace('-', "")),
+            ..Self::default()
+        };
+        for process in fs::read_dir("/proc").ok()?.flatten() {
+            if process
+                .file_name()
+                .to_string_lossy()
+                .parse::<u32>()
+                .is_err()
+            {
+                continue;
+            }
+            let dir = process.path();
+            // Other users' processes can't be looked into without root
+            if let Ok(fds) = fs::read_dir(dir.join("fd")) {
+                liveness
+                    .open
+                    .extend(fds.flatten().filter_map(|fd| fs::read_link(fd.path()).ok()));
+            }
+            liveness.open.extend(fs::read_link(dir.join("cwd")).ok());
+            if let Ok(comm) = fs::read_to_string(dir.join("comm")) {
+                liveness.processes.push(comm.trim().to_string());
+            }
+            if let Ok(cgroup) = fs::read_to_string(dir.join("cgroup")) {
+                liveness.units.extend(cgroup_units(&cgroup));
+            }
+        }
+        Some(liveness)
+    }
+
+    /// Whether something still uses `path`, litter of `kind`
+    pub fn in_use(&self, path: &Path, kind: LitterKind) -> bool {
+        if self.open.iter().any(|open| open.starts_with(path)) {
+            return true;
+        }
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        match kind {
+            LitterKind::TmpFile | LitterKind::MktempDir => false,
+            // The browser keeps its directory for as long as it runs, without
+            // holding anything in it open
+            LitterKind::ChromiumDir => self
+                .processes
+                .iter()
+                .any(|process| process.starts_with("chrom")),
+            // The service sees its directory as /tmp in its own mount
+            // namespace, so only the unit tells whether it is alive
+            LitterKind::SystemdPrivate => match systemd_private_parts(&name) {
+                Some((boot_id, _))
+                    if self
+                        .boot_id
+                        .as_deref()
+                        .is_some_and(|current| current != boot_id) =>
+                {
+                    false
+                }
+                Some((_, unit)) => self.units.contains(unit),
+                None => true,
+            },
+        }
+    }
+}
+
+/// The units in a `/proc/<pid>/cgroup`: every `.service` or `.scope`
+/// component of each path, so `foo.service` in
+/// `0::/system.slice/foo.service/payload` where it delegates sub-cgroups
+pub fn cgroup_units(cgroup: &str) -> impl Iterator<Item = String> + '_ {
+    cgroup
+        .lines()
+        .filter_map(|line| line.splitn(3, ':').nth(2))
+        .flat_map(|path| path.split('/'))
+        .filter(|unit| unit.ends_with(".service") || unit.ends_with(".scope"))
+        .map(str::to_string)
+}
+
+/// Litter of `kind` directly in `dir` that has been untouched long enough
+/// and that nothing uses, with how many candidates were kept because
+/// something still does
+pub fn find_litter(
+    dir: &Path,
+    kind: LitterKind,
+    liveness: &Liveness,
+    now: SystemTime,
+) -> (Vec<(PathBuf, u64)>, usize) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return (Vec::new(), 0);
+    };
+    let mut litter = Vec::new();
+    let mut in_use = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(metadata) = path.symlink_metadata() else {
+            continue;
+        };
+        // Symlinks could lead out of the temp directory
+        if metadata.is_symlink()
+            || classify(&entry.file_name().to_string_lossy(), metadata.is_dir()) != Some(kind)
+        {
+            continue;
+        }
+        // Without root other users' litter can't be removed, so it isn't
+        // counted either
+        if owned_by_other_user(&metadata) {
+            continue;
+        }
+        let old = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age > kind.min_age());
+        if !old {
+            continue;
+        }
+        if liveness.in_use(&path, kind) {
+            in_use += 1;
+            continue;
+        }
+        let size = if metadata.is_dir() {
+            filesystem().size(&path).unwrap_or(0)
+        } else {
+            metadata.len()
+        };
+        litter.push((path, size));
+    }
+    litter.sort();
+    (litter, in_use)
+}
+
+#[cfg(unix)]
+fn owned_by_other_user(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    !crate::utils::check_root() && metadata.uid() != users::get_effective_uid()
+}
+
+#[cfg(not(unix))]
+fn owned_by_other_user(_metadata: &fs::Metadata) -> bool {
+    false
+}
+
+pub fn get_cleaners() -> Vec<CleanerInfo> {
+    vec![
+        CleanerInfo::new(
+            "Stale .tmp Files",
+            "Clean *.tmp files in /tmp and /var/tmp older than 7 days that no process has open",
+            clean_tmp_files,
+        )
+        .scan(scan_tmp_files)
+        .available(supported),
+        CleanerInfo::new(
+            "mktemp Directories",
+            "Clean tmp.XXXXXX directories untouched for a day that no process uses",
+            clean_mktemp_dirs,
+        )
+        .scan(scan_mktemp_dirs)
+        .available(supported),
+        CleanerInfo::new(
+            "Chromium Temp Directories",
+            "Clean .org.chromium.* directories in /tmp while no Chromium-based browser runs",
+            clean_chromium_dirs,
+        )
+        .scan(scan_chromium_dirs)
+        .available(supported),
+        CleanerInfo::new(
+            "Stale systemd PrivateTmp",
+            "Clean systemd-private-* directories of services that are no longer running",
+            clean_systemd_private,
+        )
+        .docs(include_str!("docs/systemd_private_tmp.md"))
+        .scan(scan_systemd_private)
+        .available(supported),
+    ]
+}
+
+/// Liveness checks need `/proc`
+fn supported() -> bool {
+    cfg!(target_os = "linux")
+}
+
+/// Litter of `kind` in all temp directories, with how many were kept
+fn litter(kind: LitterKind) -> Result<(Vec<(PathBuf, u64)>, usize)> {
+    let liveness =
+        Liveness::snapshot().context("Can't tell which temp files are in use without /proc")?;
+    let now = SystemTime::now();
+    let mut found = Vec::new();
+    let mut in_use = 0;
+    for dir in TEMP_DIRS.iter().map(root_path) {
+        let (litter, used) = find_litter(&dir, kind, &liveness, now);
+        found.extend(litter);
+        in_use += used;
+    }
+    Ok((found, in_use))
+}
+
+fn scan(kind: LitterKind) -> Result<ScanReport> {
+    let (litter, in_use) = litter(kind)?;
+    let mut report = scan_files(&litter);
+    if in_use > 0 {
+        report.add_note(format!("Kept {} still in use", in_use));
+    }
+    Ok(report)
+}
+
+fn clean(kind: LitterKind, what: &str, skip_confirmation: bool) -> Result<u64> {
+    let (litter, _) = litter(kind)?;
+    // The confirmation may have waited a while, so look at the processes
+    // again once, right after it, and check every candidate against that
+    let fresh = OnceCell::new();
+    remove_files_unless(litter, what, skip_confirmation, |path| {
+        fresh
+            .get_or_init(Liveness::snapshot)
+            .as_ref()
+            .is_none_or(|liveness| liveness.in_use(path, kind))
+    })
+}
+
+fn scan_tmp_files() -> Result<ScanReport> {
+    scan(LitterKind::TmpFile)
+}
+
+fn scan_mktemp_dirs() -> Result<ScanReport> {
+    scan(LitterKind::MktempDir)
+}
+
+fn scan_chromium_dirs() -> Result<ScanReport> {
+    scan(LitterKind::ChromiumDir)
+}
+
+fn scan_systemd_private() -> Result<ScanReport> {
+    scan(LitterKind::SystemdPrivate)
+}
+
+fn clean_tmp_files(skip_confirmation: bool) -> Result<u64> {
+    clean(LitterKind::TmpFile, "stale .tmp files", skip_confirmation)
+}
+
+fn clean_mktemp_dirs(skip_confirmation: bool) -> Result<u64> {
+    clean(
+        LitterKind::MktempDir,
+        "abandoned mktemp directories",
+        skip_confirmation,
+    )
+}
+
+fn clean_chromium_dirs(skip_confirmation: bool) -> Result<u64> {
+    clean(
+        LitterKind::ChromiumDir,
+        "Chromium temp directories",
+        skip_confirmation,
+    )
+}
+
+fn clean_systemd_private(skip_confirmation: bool) -> Result<u64> {
+    clean(
+        LitterKind::SystemdPrivate,
+        "stale systemd PrivateTmp directories",
+        skip_confirmation,
+    )
+}
@@ -1,18 +1,20 @@
 use anyhow::{Context, Result};
-use directories::BaseDirs;
-use log::{debug, warn};
-use std::fs::{self, read_dir, remove_dir_all, remove_file};
+use log::debug;
+use std::fs;
 #[cfg(unix)]
 use std::os::unix::fs::MetadataExt;
-use std::path::Path;
-
-use crate::utils::{confirm, format_size, get_size, print_error, print_success};
-
-pub struct CleanerInfo {
-    pub name: &'static str,
-    pub description: &'static str,
-    pub function: fn(bool) -> Result<u64>,
-}
+use std::path::{Path, PathBuf};
+
+use super::cache_dirs::{clean_cache_dirs, remove_files, scan_cache_dirs, scan_files};
+use super::cleaned::{self, CleanedItemType};
+use super::custom::{self, CustomScope};
+use super::rebuild;
+use super::{CleanerInfo, ScanReport, Tag};
+use crate::filesystem::{cache_dir, filesystem, home_dir, root_path};
+use crate::history::RunRecord;
+use crate::priority::throttle_deletion;
+use crate::running::{self, Processes};
+use crate::utils::{confirm, format_size, print_success};
 
 pub fn list_cleaners() -> Vec<String> {
     get_cleaners()
@@ -22,157 +24,69 @@ pub fn list_cleaners() -> Vec<String> {
 }
 
 pub fn get_cleaners() -> Vec<CleanerInfo> {
-    vec![
-        CleanerInfo {
-            name: "Browser Caches",
-            description: "Clean Firefox and Chrome/Chromium caches",
-            function: clean_browser_caches,
-        },
-        CleanerInfo {
-            name: "Application Caches",
-            description: "Clean application caches in ~/.cache",
-            function: clean_app_caches,
-        },
-        CleanerInfo {
-            name: "Thumbnail Caches",
-            description: "Clean thumbnail caches",
-            function: clean_thumbnail_caches,
-        },
-        CleanerInfo {
-            name: "Temporary Files",
-            description: "Clean temporary files in /tmp owned by the user",
-            function: clean_temp_files,
-        },
-        CleanerInfo {
-            name: "Package Manager Caches",
-            description: "Clean user package manager caches like pip, npm, cargo",
-            function: clean_package_caches,
-        },
-        CleanerInfo {
-            name: "Trash",
-            description: "Empty trash folder",
-            function: clean_trash,
-        },
-    ]
-}
-
-pub fn run_all(skip_confirmation: bool) -> Result<()> {
-    let cleaners = get_cleaners();
-    let mut total_saved: u64 = 0;
-
-    for cleaner in cleaners {
-        if skip_confirmation || confirm(&format!("Run '{}'?", cleaner.name), true)? {
-            match (cleaner.function)(skip_confirmation) {
-                Ok(bytes) => {
-                    total_saved += bytes;
-                    print_success(&format!(
-                        "{} completed: freed {}",
-                        cleaner.name,
-                        format_size(bytes)
-                    ));
-                }
-                Err(err) => {
-                    print_error(&format!("Error in {}: {}", cleaner.name, err));
-                }
-            }
-        }
-    }
-
-    print_success(&format!("Total space freed: {}", format_size(total_saved)));
-    Ok(())
+    let mut cleaners = Vec::new();
+    #[cfg(feature = "browsers")]
+    cleaners.extend(super::browser_cleaners::get_cleaners());
+    cleaners.extend([
+        CleanerInfo::new(
+            "Application Caches",
+            "Clean application caches in ~/.cache",
+            clean_app_caches,
+        ),
+        CleanerInfo::new(
+            "Thumbnail Caches",
+            "Clean thumbnail caches",
+            clean_thumbnail_caches,
+        )
+        .tags(&[Tag::Privacy])
+        .docs(include_str!("docs/thumbnail_caches.md")),
+        CleanerInfo::new(
+            "Font & Icon Caches",
+            "Clean your fontconfig cache and icon theme caches (rebuilt afterwards when configured)",
+            clean_font_icon_caches,
+        )
+        .scan(scan_font_icon_caches),
+        CleanerInfo::new(
+            "Temporary Files",
+            "Clean temporary files in /tmp owned by the user",
+            clean_temp_files,
+        ),
+    ]);
+    #[cfg(feature = "dev-caches")]
+    cleaners.extend(super::dev_caches::get_cleaners());
+    cleaners.extend([
+        CleanerInfo::new(
+            "User Crash Reports",
+            "Clean your apport and ABRT crash reports and core dumps",
+            clean_crash_files,
+        )
+        .tags(&[Tag::Logs, Tag::Privacy])
+        .scan(scan_crash_files)
+        .available(crash_files_available),
+        CleanerInfo::new("Trash", "Empty trash folder", clean_trash)
+            .tags(&[Tag::Privacy])
+            .docs(include_str!("docs/trash.md")),
+    ]);
+    cleaners.extend(custom::get_cleaners(CustomScope::User));
+    cleaners
 }
 
-fn clean_browser_caches(skip_confirmation: bool) -> Result<u64> {
-    let mut bytes_saved = 0;
-    let base_dirs = BaseDirs::new().context("Failed to get base directories")?;
-    let home_dir = base_dirs.home_dir();
-
-    // Firefox cache
-    let firefox_path = home_dir.join(".mozilla/firefox");
-    if firefox_path.exists() {
-        debug!("Firefox directory found at {:?}", firefox_path);
-
-        if let Ok(entries) = read_dir(&firefox_path) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.is_dir()
-                    && path
-                        .file_name()
-                        .unwrap_or_default()
-                        .to_string_lossy()
-                        .ends_with(".default")
-                {
-                    let cache_path = path.join("cache2");
-
-                    if cache_path.exists() {
-                        let size = get_size(cache_path.to_str().unwrap_or(""))?;
-
-                        if skip_confirmation
-                            || confirm(
-                                &format!(
-                                    "Clean Firefox cache ({} to be freed)?",
-                                    format_size(size)
-                                ),
-                                true,
-                            )?
-                        {
-                            remove_dir_all(&cache_path)
-                                .context("Failed to remove Firefox cache")?;
-                            print_success("Firefox cache cleaned");
-                            bytes_saved += size;
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    // Chrome/Chromium cache
-    let chrome_paths = vec![
-        home_dir.join(".config/google-chrome/Default/Cache"),
-        home_dir.join(".config/chromium/Default/Cache"),
-        home_dir.join(".cache/google-chrome"),
-        home_dir.join(".cache/chromium"),
-    ];
-
-    for path in chrome_paths {
-        if path.exists() {
-            debug!("Chrome/Chromium cache found at {:?}", path);
-            let size = get_size(path.to_str().unwrap_or(""))?;
-
-            if skip_confirmation
-                || confirm(
-                    &format!(
-                        "Clean Chrome/Chromium cache at {:?} ({} to be freed)?",
-                        path,
-                        format_size(size)
-                    ),
-                    true,
-                )?
-            {
-                remove_dir_all(&path).context("Failed to remove Chrome/Chromium cache")?;
-                print_success(&format!("Chrome/Chromium cache at {:?} cleaned", path));
-                bytes_saved += size;
-            }
-        }
-    }
-
-    Ok(bytes_saved)
+pub fn run_all(skip_confirmation: bool) -> Result<RunRecord> {
+    super::run_cleaners(get_cleaners(), skip_confirmation)
 }
 
 fn clean_app_caches(skip_confirmation: bool) -> Result<u64> {
-    let base_dirs = BaseDirs::new().context("Failed to get base directories")?;
-    let cache_dir = base_dirs.cache_dir();
+    let fs = filesystem();
+    let cache_dir = cache_dir()?;
     let mut bytes_saved = 0;
+    let processes = Processes::snapshot();
 
     debug!("Cache directory: {:?}", cache_dir);
 
-    if cache_dir.exists() {
+    if fs.exists(&cache_dir) {
         // Get list of directories in cache_dir
-        if let Ok(entries) = read_dir(cache_dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-
+        if let Ok(entries) = fs.read_dir(&cache_dir) {
+            for path in entries {
                 // Skip certain critical directories
                 let name = path.file_name().unwrap_or_default().to_string_lossy();
                 if ["dconf", "fontconfig", "mesa_shader_cache"].contains(&name.as_ref()) {
@@ -180,8 +94,8 @@ fn clean_app_caches(skip_confirmation: bool) -> Result<u64> {
                     continue;
                 }
 
-                if path.is_dir() {
-                    let size = get_size(path.to_str().unwrap_or(""))?;
+                if path.is_dir() && !running::keep_cache("Application Caches", &name, &processes) {
+                    let size = fs.size(&path)?;
 
                     if skip_confirmation
                         || confirm(
@@ -193,13 +107,18 @@ fn clean_app_caches(skip_confirmation: bool) -> Result<u64> {
                             true,
                         )?
                     {
-                        if let Err(e) = remove_dir_all(&path) {
-                            warn!("Failed to remove cache directory {:?}: {}", path, e);
-                            continue;
-                        }
+                        let freed = match fs.remove_dir_all(&path) {
+                            Ok(freed) => freed,
+                            Err(e) => {
+                                cleaned::record_error(&path, &e);
+                                continue;
+                            }
+                        };
 
                         print_success(&format!("Cleaned '{}' cache", name));
-                        bytes_saved += size;
+                        cleaned::record(&path, freed, CleanedItemType::Directory);
+                        bytes_saved += freed;
+                        throttle_deletion();
                     }
                 }
             }
@@ -210,8 +129,8 @@ fn clean_app_caches(skip_confirmation: bool) -> Result<u64> {
 }
 
 fn clean_thumbnail_caches(skip_confirmation: bool) -> Result<u64> {
-    let base_dirs = BaseDirs::new().context("Failed to get base directories")?;
-    let home_dir = base_dirs.home_dir();
+    let fs = filesystem();
+    let home_dir = home_dir()?;
     let thumbnail_dirs = vec![
         home_dir.join(".thumbnails"),
         home_dir.join(".cache/thumbnails"),
@@ -220,8 +139,8 @@ fn clean_thumbnail_caches(skip_confirmation: bool) -> Result<u64> {
     let mut bytes_saved = 0;
 
     for dir in thumbnail_dirs {
-        if dir.exists() {
-            let size = get_size(dir.to_str().unwrap_or(""))?;
+        if fs.exists(&dir) {
+            let size = fs.size(&dir)?;
             debug!(
                 "Thumbnail cache found at {:?}, size: {}",
                 dir,
@@ -238,10 +157,14 @@ fn clean_thumbnail_caches(skip_confirmation: bool) -> Result<u64> {
                     true,
                 )?
             {
-                remove_dir_all(&dir).context("Failed to remove thumbnail cache")?;
-                fs::create_dir_all(&dir).context("Failed to recreate thumbnail directory")?;
+                let freed = fs
+                    .remove_dir_all(&dir)
+                    .context("Failed to remove thumbnail cache")?;
+                fs.create_dir_all(&dir)
+                    .context("Failed to recreate thumbnail directory")?;
                 print_success(&format!("Cleaned thumbnail cache at {:?}", dir));
-                bytes_saved += size;
+                cleaned::record(&dir, freed, CleanedItemType::Directory);
+                bytes_saved += freed;
             }
         }
     }
@@ -249,22 +172,69 @@ fn clean_thumbnail_caches(skip_confirmation: bool) -> Result<u64> {
     Ok(bytes_saved)
 }
 
+fn fontconfig_cache() -> Result<PathBuf> {
+    Ok(cache_dir()?.join("fontconfig"))
+}
+
+/// Icon theme caches in the user's icon directories, and KDE's shared icon
+/// cache
+fn icon_cache_files() -> Result<Vec<(PathBuf, u64)>> {
+    let home_dir = home_dir()?;
+    let mut files = rebuild::icon_theme_caches(&home_dir.join(".local/share/icons"));
+    files.extend(rebuild::icon_theme_caches(&home_dir.join(".icons")));
+    let kcache = cache_dir()?.join("icon-cache.kcache");
+    if let Ok(metadata) = kcache.symlink_metadata() {
+        if metadata.is_file() {
+            files.push((kcache, metadata.len()));
+        }
+    }
+    Ok(files)
+}
+
+fn scan_font_icon_caches() -> Result<ScanReport> {
+    let mut report = scan_cache_dirs(&[fontconfig_cache()?])?;
+    let icons = scan_files(&icon_cache_files()?);
+    report.entries.extend(icons.entries);
+    if rebuild::enabled() {
+        report.add_note("Rebuilt with fc-cache and gtk-update-icon-cache after cleaning");
+    }
+    Ok(report)
+}
+
+fn clean_font_icon_caches(skip_confirmation: bool) -> Result<u64> {
+    let fonts = clean_cache_dirs(&[fontconfig_cache()?], "fontconfig", skip_confirmation)?;
+    if fonts > 0 {
+        rebuild::font_cache(false);
+    }
+
+    let icons = icon_cache_files()?;
+    let themes: Vec<PathBuf> = icons
+        .iter()
+        .filter(|(path, _)| path.ends_with(rebuild::ICON_THEME_CACHE))
+        .filter_map(|(path, _)| path.parent().map(Path::to_path_buf))
+        .collect();
+    let removed = remove_files(icons, "icon caches", skip_confirmation)?;
+    if removed > 0 {
+        rebuild::icon_caches(&themes, false);
+    }
+    Ok(fonts + removed)
+}
+
 #[cfg(unix)]
 fn clean_temp_files(skip_confirmation: bool) -> Result<u64> {
-    let tmp_dir = Path::new("/tmp");
+    let fs = filesystem();
+    let tmp_dir = root_path("/tmp");
     let mut bytes_saved = 0;
 
-    if tmp_dir.exists() {
-        if let Ok(entries) = read_dir(tmp_dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-
+    if fs.exists(&tmp_dir) {
+        if let Ok(entries) = fs.read_dir(&tmp_dir) {
+            for path in entries {
                 // Check if the file or directory is owned by the current user
                 if let Ok(metadata) = fs::metadata(&path) {
                     let uid = metadata.uid();
 
                     if uid == users::get_current_uid() {
-                        let size = get_size(path.to_str().unwrap_or(""))?;
+                        let size = fs.size(&path)?;
 
                         if skip_confirmation
                             || confirm(
@@ -276,18 +246,24 @@ fn clean_temp_files(skip_confirmation: bool) -> Result<u64> {
                                 true,
                             )?
                         {
-                            if path.is_dir() {
-                                if let Err(e) = remove_dir_all(&path) {
-                                    warn!("Failed to remove directory {:?}: {}", path, e);
+                            let is_dir = path.is_dir();
+                            let removed = if is_dir {
+                                fs.remove_dir_all(&path)
+                            } else {
+                                fs.remove_file(&path)
+                            };
+                            let freed = match removed {
+                                Ok(freed) => freed,
+                                Err(e) => {
+                                    cleaned::record_error(&path, &e);
                                     continue;
                                 }
-                            } else if let Err(e) = remove_file(&path) {
-                                warn!("Failed to remove file {:?}: {}", path, e);
-                                continue;
-                            }
+                            };
 
                             print_success(&format!("Removed temporary {:?}", path));
-                            bytes_saved += size;
+                            cleaned::record_path(&path, freed, is_dir);
+                            bytes_saved += freed;
+                            throttle_deletion();
                         }
                     }
                 }
@@ -303,86 +279,199 @@ fn clean_temp_files(_skip_confirmation: bool) -> Result<u64> {
     Ok(0)
 }
 
-fn clean_package_caches(skip_confirmation: bool) -> Result<u64> {
-    let base_dirs = BaseDirs::new().context("Failed to get base directories")?;
-    let home_dir = base_dirs.home_dir();
-
-    let cache_locations = vec![
-        (home_dir.join(".cache/pip"), "pip"),
-        (home_dir.join(".npm/_cacache"), "npm"),
-        (home_dir.join(".cargo/.crates.toml.lock"), "cargo lock file"),
-        (
-            home_dir.join(".cargo/.package-cache"),
-            "cargo package cache",
-        ),
-    ];
+/// Where apport and ABRT keep crash reports for a user without root
+fn crash_report_dirs() -> Result<Vec<PathBuf>> {
+    let home_dir = home_dir()?;
+    Ok(vec![
+        home_dir.join(".local/share/apport"),
+        home_dir.join(".cache/abrt"),
+    ])
+}
 
-    let mut bytes_saved = 0;
+fn crash_files_available() -> bool {
+    crash_report_dirs().is_ok_and(|p| p.iter().any(|p| p.exists()))
+        || root_path("/var/crash").exists()
+        || root_path("/var/lib/systemd/coredump").exists()
+}
 
-    for (path, name) in cache_locations {
-        if path.exists() {
-            let size = get_size(path.to_str().unwrap_or(""))?;
-            debug!(
-                "{} cache found: {:?}, size: {}",
-                name,
-                path,
-                format_size(size)
-            );
+/// The uid in a systemd-coredump file name,
+/// `core.<comm>.<uid>.<boot id>.<pid>.<usec>` plus a compression suffix.
+/// The command name may contain dots, so the uid is taken from just before
+/// the 32-digit boot id.
+pub fn coredump_uid(file_name: &str) -> Option<u32> {
+    let rest = file_name.strip_prefix("core.")?;
+    let parts: Vec<&str> = rest.split('.').collect();
+    let boot_id = parts
+        .iter()
+        .position(|p| p.len() == 32 && p.bytes().all(|b| b.is_ascii_hexdigit()))?;
+    // At least a command name before the uid
+    if boot_id < 2 {
+        return None;
+    }
+    parts[boot_id - 1].parse().ok()
+}
 
-            if skip_confirmation
-                || confirm(
-                    &format!("Clean {} cache ({} to be freed)?", name, format_size(size)),
-                    true,
-                )?
-            {
-                if path.is_dir() {
-                    if let Err(e) = remove_dir_all(&path) {
-                        warn!("Failed to remove {} cache: {}", name, e);
-                        continue;
-                    }
-                    fs::create_dir_all(&path).ok(); // Recreate empty directory
-                } else if let Err(e) = remove_file(&path) {
-                    warn!("Failed to remove {} cache: {}", name, e);
-                    continue;
-                }
+/// Crash reports apport wrote for user `uid` in `dir` (`/var/crash`), with
+/// their sizes: `.crash` files they own and the `.upload`/`.uploaded`
+/// markers next to them. /var/crash is sticky and world-writable, so the
+/// owner can remove them without root.
+#[cfg(unix)]
+pub fn owned_crash_reports(dir: &Path, uid: u32) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<(PathBuf, u64)> = entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            [".crash", ".upload", ".uploaded"]
+                .iter()
+                .any(|ext| name.ends_with(ext))
+        })
+        .filter_map(|entry| {
+            let metadata = entry.path().symlink_metadata().ok()?;
+            (metadata.is_file() && metadata.uid() == uid).then(|| (entry.path(), metadata.len()))
+        })
+        .collect();
+    reports.sort();
+    reports
+}
 
-                print_success(&format!("Cleaned {} cache", name));
-                bytes_saved += size;
-            }
+/// Core dumps systemd-coredump stored in `dir` for processes of user `uid`
+pub fn user_coredumps(dir: &Path, uid: u32) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut dumps: Vec<(PathBuf, u64)> = entries
+        .flatten()
+        .filter(|entry| coredump_uid(&entry.file_name().to_string_lossy()) == Some(uid))
+        .filter_map(|entry| {
+            let metadata = entry.path().symlink_metadata().ok()?;
+            metadata.is_file().then(|| (entry.path(), metadata.len()))
+        })
+        .collect();
+    dumps.sort();
+    dumps
+}
+
+/// Whether files in `dir` can be removed by this process
+#[cfg(unix)]
+fn dir_writable(dir: &Path) -> bool {
+    use std::os::unix::ffi::OsStrExt;
+    let Ok(path) = std::ffi::CString::new(dir.as_os_str().as_bytes()) else {
+        return false;
+    };
+    // SAFETY: access only reads the path
+    unsafe { libc::access(path.as_ptr(), libc::W_OK | libc::X_OK) == 0 }
+}
+
+/// Crash files outside the home directory belonging to the current user
+#[derive(Default)]
+struct SystemCrashFiles {
+    removable: Vec<(PathBuf, u64)>,
+    /// Core dumps in a directory only root can remove them from
+    needs_root: Vec<(PathBuf, u64)>,
+}
+
+#[cfg(unix)]
+fn system_crash_files() -> SystemCrashFiles {
+    let uid = users::get_current_uid();
+    let mut removable = owned_crash_reports(&root_path("/var/crash"), uid);
+    let coredump_dir = root_path("/var/lib/systemd/coredump");
+    let dumps = user_coredumps(&coredump_dir, uid);
+    if dir_writable(&coredump_dir) {
+        removable.extend(dumps);
+        SystemCrashFiles {
+            removable,
+            needs_root: Vec::new(),
+        }
+    } else {
+        SystemCrashFiles {
+            removable,
+            needs_root: dumps,
         }
     }
+}
 
-    // Clean yarn cache with the yarn command if available
-    if skip_confirmation || confirm("Clean yarn cache?", true)? {
-        if let Ok(output) = std::process::Command::new("yarn")
-            .arg("cache")
-            .arg("clean")
-            .output()
-        {
-            if output.status.success() {
-                print_success("Cleaned yarn cache");
-                // Since we can't easily determine the size, estimate 10MB
-                bytes_saved += 10 * 1024 * 1024;
+#[cfg(not(unix))]
+fn system_crash_files() -> SystemCrashFiles {
+    SystemCrashFiles::default()
+}
+
+fn scan_crash_files() -> Result<ScanReport> {
+    let mut report = scan_cache_dirs(&crash_report_dirs()?)?;
+    let SystemCrashFiles {
+        removable,
+        needs_root,
+    } = system_crash_files();
+    for (path, size) in removable {
+        report.add_entry(&path.to_string_lossy(), size, size);
+    }
+    if !needs_root.is_empty() {
+        let size: u64 = needs_root.iter().map(|(_, size)| size).sum();
+        report.add_note(format!(
+            "{} of your core dumps ({}) need root: run the system Crash Reports cleaner",
+            needs_root.len(),
+            format_size(size)
+        ));
+    }
+    Ok(report)
+}
+
+fn clean_crash_files(skip_confirmation: bool) -> Result<u64> {
+    let fs = filesystem();
+    let mut bytes_saved =
+        clean_cache_dirs(&crash_report_dirs()?, "crash report", skip_confirmation)?;
+
+    let SystemCrashFiles {
+        removable,
+        needs_root,
+    } = system_crash_files();
+    if !needs_root.is_empty() {
+        debug!(
+            "Leaving {} core dumps that need root to remove",
+            needs_root.len()
+        );
+    }
+    if removable.is_empty() {
+        return Ok(bytes_saved);
+    }
+    let size: u64 = removable.iter().map(|(_, size)| size).sum();
+    if skip_confirmation
+        || confirm(
+            &format!(
+                "Remove {} of your crash reports and core dumps ({} to be freed)?",
+                removable.len(),
+                format_size(size)
+            ),
+            true,
+        )?
+    {
+        for (path, size) in removable {
+            if let Err(e) = fs.remove_file(&path) {
+                cleaned::record_error(&path, &e);
+                continue;
             }
+            cleaned::record(&path, size, CleanedItemType::File);
+            bytes_saved += size;
+            throttle_deletion();
         }
+        print_success("Removed your crash reports and core dumps");
     }
 
     Ok(bytes_saved)
 }
 
 fn clean_trash(skip_confirmation: bool) -> Result<u64> {
-    let base_dirs = BaseDirs::new().context("Failed to get base directories")?;
-    let home_dir = base_dirs.home_dir();
-    let trash_dirs = vec![
-        home_dir.join(".local/share/Trash"),
-        Path::new("~/.Trash").to_path_buf(),
-    ];
+    let fs = filesystem();
+    let home_dir = home_dir()?;
+    let trash_dirs = vec![home_dir.join(".local/share/Trash"), home_dir.join(".Trash")];
 
     let mut bytes_saved = 0;
 
     for dir in trash_dirs {
-        if dir.exists() {
-            let size = get_size(dir.to_str().unwrap_or(""))?;
+        if fs.exists(&dir) {
+            let size = fs.size(&dir)?;
             debug!("Trash found at {:?}, size: {}", dir, format_size(size));
 
             if skip_confirmation
@@ -399,18 +488,24 @@ fn clean_trash(skip_confirmation: bool) -> Result<u64> {
                 let files_dir = dir.join("files");
                 let info_dir = dir.join("info");
 
-                if files_dir.exists() {
-                    remove_dir_all(&files_dir).context("Failed to empty trash files")?;
-                    fs::create_dir_all(&files_dir).ok();
+                let mut freed = 0;
+                if fs.exists(&files_dir) {
+                    freed += fs
+                        .remove_dir_all(&files_dir)
+                        .context("Failed to empty trash files")?;
+                    fs.create_dir_all(&files_dir).ok();
                 }
 
-                if info_dir.exists() {
-                    remove_dir_all(&info_dir).context("Failed to empty trash info")?;
-                    fs::create_dir_all(&info_dir).ok();
+                if fs.exists(&info_dir) {
+                    freed += fs
+                        .remove_dir_all(&info_dir)
+                        .context("Failed to empty trash info")?;
+                    fs.create_dir_all(&info_dir).ok();
                 }
 
                 print_success(&format!("Emptied trash at {:?}", dir));
-                bytes_saved += size;
+                cleaned::record(&dir, freed, CleanedItemType::Directory);
+                bytes_saved += freed;
             }
         }
     }
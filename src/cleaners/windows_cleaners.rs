@@ -0,0 +1,199 @@
+use anyhow::{bail, Result};
+use log::{debug, warn};
+use std::path::{Path, PathBuf};
+
+use super::{CleanerInfo, ScanReport, Tag};
+use crate::filesystem::{data_dir, filesystem};
+use crate::priority::throttle_deletion;
+use crate::utils::{confirm, format_size, print_success};
+
+/// Lists all available Windows cleaners with their descriptions.
+pub fn list_cleaners() -> Vec<String> {
+    get_cleaners()
+        .iter()
+        .map(|c| format!("{}: {}", c.name, c.description))
+        .collect()
+}
+
+/// Returns a vector of all available Windows cleaners.
+pub fn get_cleaners() -> Vec<CleanerInfo> {
+    vec![
+        CleanerInfo::new(
+            "Temporary Files",
+            "Clean the user's temporary folder (%TEMP%)",
+            clean_temp_files,
+        )
+        .scan(scan_temp_files),
+        CleanerInfo::new(
+            "Local App Caches",
+            "Clean browser, shader and crash dump caches in %LOCALAPPDATA%",
+            clean_local_caches,
+        )
+        .tags(&[Tag::Browser])
+        .scan(scan_local_caches),
+        CleanerInfo::new("Recycle Bin", "Empty the Recycle Bin", clean_recycle_bin)
+            .scan(scan_recycle_bin)
+            .tags(&[Tag::Privacy]),
+    ]
+}
+
+/// Cache folders below %LOCALAPPDATA%
+const LOCAL_CACHES: [&str; 6] = [
+    "Microsoft/Windows/INetCache",
+    "D3DSCache",
+    "CrashDumps",
+    "Google/Chrome/User Data/Default/Cache",
+    "Microsoft/Edge/User Data/Default/Cache",
+    "BraveSoftware/Brave-Browser/User Data/Default/Cache",
+];
+
+fn temp_paths() -> Vec<PathBuf> {
+    vec![std::env::temp_dir()]
+}
+
+fn local_cache_paths() -> Result<Vec<PathBuf>> {
+    let local = data_dir()?;
+    Ok(LOCAL_CACHES.iter().map(|dir| local.join(dir)).collect())
+}
+
+fn clean_temp_files(skip_confirmation: bool) -> Result<u64> {
+    clean_dirs(&temp_paths(), "temporary files", skip_confirmation)
+}
+
+fn clean_local_caches(skip_confirmation: bool) -> Result<u64> {
+    clean_dirs(&local_cache_paths()?, "cache", skip_confirmation)
+}
+
+fn scan_temp_files() -> Result<ScanReport> {
+    scan_dirs(&temp_paths())
+}
+
+fn scan_local_caches() -> Result<ScanReport> {
+    scan_dirs(&local_cache_paths()?)
+}
+
+fn scan_dirs(paths: &[PathBuf]) -> Result<ScanReport> {
+    let fs = filesystem();
+    let mut report = ScanReport::new();
+    for path in paths.iter().filter(|p| fs.exists(p)) {
+        let size = fs.size(path)?;
+        report.add_entry(&path.to_string_lossy(), size, size);
+    }
+    Ok(report)
+}
+
+/// Empty each directory entry by entry. Files held open by running
+/// programs can't be removed on Windows, so those are skipped rather than
+/// stopping the whole directory.
+fn clean_dirs(paths: &[PathBuf], what: &str, skip_confirmation: bool) -> Result<u64> {
+    let fs = filesystem();
+    let mut bytes_saved = 0;
+
+    for dir in paths {
+        if !fs.exists(dir) {
+            debug!("No {} at {:?}", what, dir);
+            continue;
+        }
+        let size = fs.size(dir)?;
+        if size == 0 {
+            continue;
+        }
+
+        if skip_confirmation
+            || confirm(
+                &format!(
+                    "Clean {} in {:?} ({} to be freed)?",
+                    what,
+                    dir,
+                    format_size(size)
+                ),
+                true,
+            )?
+        {
+            let freed = remove_entries(dir)?;
+            print_success(&format!(
+                "Removed {} from {}",
+                format_size(freed),
+                dir.display()
+            ));
+            bytes_saved += freed;
+        }
+    }
+
+    Ok(bytes_saved)
+}
+
+fn remove_entries(dir: &Path) -> Result<u64> {
+    let fs = filesystem();
+    let mut freed = 0;
+    for entry in fs.read_dir(dir)? {
+        let removed = if entry.is_dir() {
+            fs.remove_dir_all(&entry)
+        } else {
+            fs.remove_file(&entry)
+        };
+        match removed {
+            Ok(size) => {
+                freed += size;
+                throttle_deletion();
+            }
+            Err(e) => debug!("Skipping {:?}, probably in use: {}", entry, e),
+        }
+    }
+    Ok(freed)
+}
+
+/// Size and item count of the Recycle Bin across all drives
+fn query_recycle_bin() -> Result<(u64, u64)> {
+    use windows_sys::Win32::UI::Shell::{SHQueryRecycleBinW, SHQUERYRBINFO};
+
+    let mut info = SHQUERYRBINFO {
+        cbSize: std::mem::size_of::<SHQUERYRBINFO>() as u32,
+        i64Size: 0,
+        i64NumItems: 0,
+    };
+    let result = unsafe { SHQueryRecycleBinW(std::ptr::null(), &mut info) };
+    if result != 0 {
+        bail!("Failed to query the Recycle Bin (HRESULT {:#x})", result);
+    }
+    Ok((info.i64Size.max(0) as u64, info.i64NumItems.max(0) as u64))
+}
+
+fn scan_recycle_bin() -> Result<ScanReport> {
+    let (size, _) = query_recycle_bin()?;
+    let mut report = ScanReport::new();
+    report.add_entry("Recycle Bin", size, size);
+    Ok(report)
+}
+
+fn clean_recycle_bin(skip_confirmation: bool) -> Result<u64> {
+    use windows_sys::Win32::UI::Shell::{
+        SHEmptyRecycleBinW, SHERB_NOCONFIRMATION, SHERB_NOPROGRESSUI, SHERB_NOSOUND,
+    };
+
+    let (size, items) = query_recycle_bin()?;
+    if items == 0 {
+        return Ok(0);
+    }
+    if !skip_confirmation
+        && !confirm(
+            &format!(
+                "Empty the Recycle Bin ({} items, {} to be freed)?",
+                items,
+                format_size(size)
+            ),
+            true,
+        )?
+    {
+        return Ok(0);
+    }
+
+    let flags = SHERB_NOCONFIRMATION | SHERB_NOPROGRESSUI | SHERB_NOSOUND;
+    let result = unsafe { SHEmptyRecycleBinW(std::ptr::null_mut(), std::ptr::null(), flags) };
+    if result != 0 {
+        warn!("Failed to empty the Recycle Bin (HRESULT {:#x})", result);
+        return Ok(0);
+    }
+    print_success("Emptied the Recycle Bin");
+    Ok(size)
+}
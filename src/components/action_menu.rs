@@ -0,0 +1,168 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::bulk::{BulkAction, BulkActionKind};
+
+/// Popup listing the bulk actions a screen supports, asking for the
+/// action's argument when it needs one
+#[derive(Default)]
+pub struct ActionMenu {
+    actions: Vec<BulkActionKind>,
+    index: usize,
+    /// Argument being typed for the chosen action
+    input: Option<String>,
+    error: Option<String>,
+    visible: bool,
+}
+
+impl ActionMenu {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open the menu with the actions that apply to the current screen
+    pub fn show(&mut self, actions: &[BulkActionKind]) {
+        self.actions = actions.to_vec();
+        self.index = 0;
+        self.input = None;
+        self.error = None;
+        self.visible = !self.actions.is_empty();
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.input = None;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn next(&mut self) {
+        if self.input.is_none() && !self.actions.is_empty() {
+            self.index = (self.index + 1) % self.actions.len();
+        }
+    }
+
+    pub fn previous(&mut self) {
+        if self.input.is_none() && !self.actions.is_empty() {
+            self.index = (self.index + self.actions.len() - 1) % self.actions.len();
+        }
+    }
+
+    /// Type into the argument prompt
+    pub fn add_char(&mut self, c: char) {
+        if let Some(input) = self.input.as_mut() {
+            input.push(c);
+        }
+    }
+
+    pub fn remove_char(&mut self) {
+        if let Some(input) = self.input.as_mut() {
+            input.pop();
+        }
+    }
+
+    /// Whether the menu is asking for an argument
+    pub fn is_editing(&self) -> bool {
+        self.input.is_some()
+    }
+
+    /// Confirm the highlighted action or the typed argument. Returns the
+    /// action once it is complete, closing the menu.
+    pub fn submit(&mut self) -> Option<BulkAction> {
+        let kind = *self.actions.get(self.index)?;
+        let action = match (&self.input, kind.prompt()) {
+            // Needs an argument: ask for it first
+            (None, Some(_)) => {
+                self.input = Some(String::new());
+                return None;
+            }
+            (None, None) => kind.with_input(""),
+            (Some(input), _) => kind.with_input(input),
+        };
+        match action {
+            Some(action) => {
+                self.hide();
+                Some(action)
+            }
+            None => {
+                self.error = Some("Invalid value".to_string());
+                None
+            }
+        }
+    }
+
+    /// Render the menu as an overlay
+    pub fn render(&self, f: &mut Frame, area: Rect) {
+        if !self.visible {
+            return;
+        }
+
+        let popup_width = 48.min(area.width.saturating_sub(4));
+        let popup_height = (self.actions.len() as u16 + 6).min(area.height.saturating_sub(2));
+        let popup_area = Rect {
+            x: area.x + (area.width.saturating_sub(popup_width)) / 2,
+            y: area.y + (area.height.saturating_sub(popup_height)) / 2,
+            width: popup_width,
+            height: popup_height,
+        };
+
+        let mut lines: Vec<Line> = self
+            .actions
+            .iter()
+            .enumerate()
+            .map(|(i, action)| {
+                if i == self.index {
+                    Line::from(Span::styled(
+                        format!("> {}", action.label()),
+                        Style::default()
+                            .add_modifier(Modifier::BOLD)
+                            .bg(Color::DarkGray),
+                    ))
+                } else {
+                    Line::from(format!("  {}", action.label()))
+                }
+            })
+            .collect();
+        lines.push(Line::from(""));
+
+        if let (Some(input), Some(prompt)) = (
+            &self.input,
+            self.actions.get(self.index).and_then(|a| a.prompt()),
+        ) {
+            lines.push(Line::from(vec![
+                Span::styled(prompt, Style::default().fg(Color::Yellow)),
+                Span::raw(input.as_str()),
+                Span::styled("_", Style::default().fg(Color::Yellow)),
+            ]));
+        }
+        if let Some(error) = &self.error {
+            lines.push(Line::from(Span::styled(
+                error.as_str(),
+                Style::default().fg(Color::Red),
+            )));
+        }
+        lines.push(Line::from(Span::styled(
+            "Enter: Apply | ESC: Close",
+            Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::ITALIC),
+        )));
+
+        let popup = Paragraph::new(lines).block(
+            Block::default()
+                .title("⚡ Bulk Actions")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        );
+
+        f.render_widget(Clear, popup_area);
+        f.render_widget(popup, popup_area);
+    }
+}
@@ -0,0 +1,130 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::size_heat::sized_span;
+use crate::utils::format_size;
+
+/// Yes/no confirmation dialog shown before risky operations
+pub struct ConfirmDialog {
+    /// Title shown in the dialog border
+    title: String,
+    /// Lines explaining what is about to happen, each with an optional size
+    message: Vec<(String, Option<u64>)>,
+    /// Warning shown above the message
+    heading: String,
+    /// Key hint shown below the message
+    hint: String,
+    /// Whether the dialog is currently visible
+    visible: bool,
+}
+
+impl Default for ConfirmDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfirmDialog {
+    /// Create a new, hidden confirmation dialog
+    pub fn new() -> Self {
+        Self {
+            title: String::new(),
+            message: Vec::new(),
+            heading: "⚠ This operation may remove valuable data".to_string(),
+            hint: "Press y to continue | n or ESC to cancel".to_string(),
+            visible: false,
+        }
+    }
+
+    /// Replace the warning shown above the message
+    pub fn with_heading(mut self, heading: &str) -> Self {
+        self.heading = heading.to_string();
+        self
+    }
+
+    /// Replace the key hint shown below the message
+    pub fn with_hint(mut self, hint: &str) -> Self {
+        self.hint = hint.to_string();
+        self
+    }
+
+    /// Show the dialog with the given title and message lines
+    pub fn show(&mut self, title: &str, message: Vec<String>) {
+        self.show_with_sizes(title, message.into_iter().map(|m| (m, None)).collect());
+    }
+
+    /// Show the dialog with message lines that end in a size, colored by
+    /// how large it is
+    pub fn show_with_sizes(&mut self, title: &str, message: Vec<(String, Option<u64>)>) {
+        self.title = title.to_string();
+        self.message = message;
+        self.visible = true;
+    }
+
+    /// Hide the dialog
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.message.clear();
+    }
+
+    /// Check if the dialog is visible
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Render the dialog as an overlay
+    pub fn render(&self, f: &mut Frame, area: Rect) {
+        if !self.visible {
+            return;
+        }
+
+        let popup_width = 64.min(area.width.saturating_sub(4));
+        let popup_height = (self.message.len() as u16 + 6).min(area.height.saturating_sub(4));
+
+        let popup_area = Rect {
+            x: area.x + (area.width.saturating_sub(popup_width)) / 2,
+            y: area.y + (area.height.saturating_sub(popup_height)) / 2,
+            width: popup_width,
+            height: popup_height,
+        };
+
+        let mut lines = vec![
+            Line::from(vec![Span::styled(
+                self.heading.as_str(),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )]),
+            Line::from(""),
+        ];
+        lines.extend(self.message.iter().map(|(text, size)| {
+            let mut spans = vec![Span::raw(text.as_str())];
+            if let Some(size) = size {
+                spans.push(sized_span(format!(" ({})", format_size(*size)), *size));
+            }
+            Line::from(spans)
+        }));
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![Span::styled(
+            self.hint.as_str(),
+            Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::ITALIC),
+        )]));
+
+        let popup = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(self.title.as_str())
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Red)),
+            )
+            .wrap(Wrap { trim: true });
+
+        f.render_widget(Clear, popup_area);
+        f.render_widget(popup, popup_area);
+    }
+}
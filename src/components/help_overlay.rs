@@ -0,0 +1,398 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// Inner width from which the help is laid out in two columns
+pub const TWO_COLUMN_MIN_WIDTH: u16 = 100;
+
+/// Which screen the help is opened from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HelpContext {
+    /// Choosing cleaners in the main view
+    Selection,
+    /// Reviewing a run before it starts (risk confirmation, schedule, password)
+    Review,
+    /// Cleaners are running
+    Progress,
+    /// A run has finished and its results are shown
+    Results,
+}
+
+impl HelpContext {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HelpContext::Selection => "Selection",
+            HelpContext::Review => "Review",
+            HelpContext::Progress => "Progress",
+            HelpContext::Results => "Results",
+        }
+    }
+}
+
+/// A titled group of key bindings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HelpSection {
+    pub title: &'static str,
+    /// (keys, description) pairs
+    pub bindings: Vec<(&'static str, &'static str)>,
+}
+
+impl HelpSection {
+    fn new(title: &'static str, bindings: &[(&'static str, &'static str)]) -> Self {
+        Self {
+            title,
+            bindings: bindings.to_vec(),
+        }
+    }
+
+    /// Lines taken up when rendered: title, bindings and a blank separator
+    fn height(&self) -> usize {
+        self.bindings.len() + 2
+    }
+}
+
+/// The key bindings relevant to a screen
+pub fn help_sections(context: HelpContext) -> Vec<HelpSection> {
+    let help = HelpSection::new(
+        "🔄 Help",
+        &[
+            ("?/h/F1", "Show/hide help"),
+            ("↑/↓ j/k", "Scroll help"),
+            ("PgUp/PgDn", "Scroll help by a page"),
+            ("ESC/q", "Close help"),
+        ],
+    );
+    let legend = HelpSection::new(
+        "📊 Chart Legend",
+        &[
+            ("Tab/Shift+Tab", "Switch between removed items and chart"),
+            ("↑/↓ j/k", "Highlight a legend entry (chart focused)"),
+            ("Enter", "Show only that cleaner's items; again for all"),
+        ],
+    );
+
+    match context {
+        HelpContext::Selection => vec![
+            HelpSection::new(
+                "📍 Navigation",
+                &[
+                    ("↑/↓", "Navigate items"),
+                    ("Tab/Shift+Tab", "Switch categories"),
+                    ("1-9", "Jump to a category"),
+                    ("C", "Collapse/expand the categories sidebar"),
+                    ("i", "Show/hide system information"),
+                    ("Home/End", "Jump to first/last item"),
+                ],
+            ),
+            HelpSection::new(
+                "🔧 Selection",
+                &[
+                    ("Space", "Toggle selection"),
+                    ("a", "Select all in current category"),
+                    ("n", "Deselect all in current category"),
+                    ("B", "Bulk actions: select matching, invert"),
+                    ("R", "Select the recommended safe set"),
+                    ("U", "Undo the last run from the quarantine, after asking"),
+                    ("Enter", "Run selected cleaners"),
+                    ("ESC", "Stop the running scans"),
+                    ("t", "Schedule selected cleaners (02:00, 30m) / cancel"),
+                    ("A", "About: how much cleansys has freed overall"),
+                    ("J", "System Logs: vacuum journal by time / by size"),
+                    ("y", "Toggle confirmation prompts"),
+                ],
+            ),
+            HelpSection::new(
+                "🎛️ View",
+                &[
+                    (
+                        "v",
+                        "Cycle view mode (Standard/Compact/Detailed/Performance)",
+                    ),
+                    ("m", "Toggle compact mode"),
+                    ("p", "Toggle performance statistics"),
+                    ("c", "Cycle chart type (Bar → Count Pie → Size Pie)"),
+                    ("b", "Show sizes in bytes / KB, MB, GB"),
+                    ("o", "Cycle sort mode"),
+                    ("f", "Cycle filter mode, including one per tag"),
+                    ("x", "Clear all errors"),
+                    ("M", "Message history"),
+                ],
+            ),
+            HelpSection::new(
+                "🔒 System Operations",
+                &[
+                    ("(sudo)", "Items that request elevated privileges"),
+                    (
+                        "sudo cleansys",
+                        "Run as root, or enter the password when prompted",
+                    ),
+                ],
+            ),
+            help,
+            HelpSection::new("🚪 Exit", &[("q", "Exit application")]),
+        ],
+        HelpContext::Review => vec![
+            HelpSection::new(
+                "⚠ Risk Confirmation",
+                &[
+                    ("y", "Run the listed cleaners, quit the run or trim"),
+                    ("n/ESC", "Cancel and go back"),
+                ],
+            ),
+            HelpSection::new(
+                "⏰ Schedule Prompt",
+                &[
+                    ("02:00", "Run at a time of day"),
+                    ("30m, 2h", "Run after a delay"),
+                    ("Enter", "Confirm the schedule"),
+                    ("ESC", "Cancel"),
+                ],
+            ),
+            HelpSection::new(
+                "🔑 Password Prompt",
+                &[
+                    ("Enter", "Authenticate and start system cleaners"),
+                    ("Backspace", "Remove a character"),
+                    ("ESC", "Cancel the run"),
+                ],
+            ),
+            HelpSection::new(
+                "🔒 Sudo Failed Mid-Run",
+                &[
+                    ("r/Enter", "Authenticate again and resume system cleaners"),
+                    ("s", "Skip the remaining system cleaners"),
+                    ("a/ESC", "Abort the run"),
+                ],
+            ),
+            help,
+        ],
+        HelpContext::Progress => vec![
+            HelpSection::new(
+                "⏯ Operation",
+                &[
+                    ("Ctrl+Space", "Pause/resume operations"),
+                    ("ESC", "Cancel operation"),
+                    ("q", "Cancel and quit, after confirming"),
+                    ("s", "Toggle auto-scroll log"),
+                ],
+            ),
+            HelpSection::new(
+                "📜 Removed Items",
+                &[
+                    ("↑/↓ j/k", "Scroll items"),
+                    ("PgUp/PgDn", "Scroll by 10 items"),
+                    ("Home/End", "Jump to first/last item"),
+                    ("Space", "Collapse/expand cleaner group"),
+                    ("/", "Search files, categories and cleaners"),
+                ],
+            ),
+            HelpSection::new(
+                "🎛️ View",
+                &[
+                    ("v", "Cycle view mode"),
+                    ("p", "Toggle performance statistics"),
+                    ("c", "Cycle chart type"),
+                    ("+/-", "Grow/shrink the progress overview"),
+                    ("b", "Show sizes in bytes / KB, MB, GB"),
+                ],
+            ),
+            legend,
+            help,
+        ],
+        HelpContext::Results => vec![
+            HelpSection::new(
+                "📜 Removed Items",
+                &[
+                    ("↑/↓ j/k", "Scroll items"),
+                    ("PgUp/PgDn", "Scroll by 10 items"),
+                    ("Home/End", "Jump to first/last item"),
+                    ("Space", "Collapse/expand cleaner group"),
+                    ("i", "Never clean the highlighted path again"),
+                    ("I", "Never clean the highlighted path's folder again"),
+                    ("o", "Open the highlighted path's folder"),
+                    ("O", "Open a shell in the highlighted path's folder"),
+                    ("U", "Undo the last run from the quarantine, after asking"),
+                ],
+            ),
+            HelpSection::new(
+                "🔍 Search",
+                &[
+                    ("/", "Search files, categories and cleaners"),
+                    ("Backspace", "Remove search character"),
+                    ("ESC", "Clear search"),
+                ],
+            ),
+            HelpSection::new(
+                "🎛️ View",
+                &[
+                    ("c", "Cycle chart type (Bar → Count Pie → Size Pie)"),
+                    ("v", "Cycle view mode"),
+                    ("p", "Toggle performance statistics"),
+                    ("+/-", "Grow/shrink the progress overview"),
+                    ("x", "Clear all errors"),
+                    ("M", "Message history"),
+                ],
+            ),
+            legend,
+            help,
+            HelpSection::new(
+                "🚪 Exit",
+                &[("ESC", "Return to selection"), ("q", "Exit application")],
+            ),
+        ],
+    }
+}
+
+/// Split sections into columns of roughly equal height, keeping their order
+pub fn split_columns(sections: &[HelpSection], columns: usize) -> Vec<Vec<HelpSection>> {
+    let columns = columns.max(1);
+    let total: usize = sections.iter().map(HelpSection::height).sum();
+    let target = total.div_ceil(columns);
+
+    let mut result = vec![Vec::new()];
+    let mut height = 0;
+    for section in sections {
+        if height >= target && result.len() < columns {
+            result.push(Vec::new());
+            height = 0;
+        }
+        height += section.height();
+        if let Some(column) = result.last_mut() {
+            column.push(section.clone());
+        }
+    }
+    result
+}
+
+/// Context-sensitive help overlay with scrolling
+#[derive(Default)]
+pub struct HelpOverlay {
+    visible: bool,
+    scroll: u16,
+    /// Largest useful scroll offset at the last render
+    max_scroll: u16,
+}
+
+impl HelpOverlay {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open the help at the top
+    pub fn show(&mut self) {
+        self.visible = true;
+        self.scroll = 0;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    pub fn toggle(&mut self) {
+        if self.visible {
+            self.hide();
+        } else {
+            self.show();
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn scroll(&self) -> u16 {
+        self.scroll
+    }
+
+    pub fn scroll_down(&mut self, lines: u16) {
+        self.scroll = self.scroll.saturating_add(lines).min(self.max_scroll);
+    }
+
+    pub fn scroll_up(&mut self, lines: u16) {
+        self.scroll = self.scroll.saturating_sub(lines);
+    }
+
+    /// Render the help for `context` as an overlay
+    pub fn render(&mut self, f: &mut Frame, area: Rect, context: HelpContext) {
+        if !self.visible {
+            return;
+        }
+
+        let popup_width = area.width.saturating_sub(4).min(120);
+        let popup_height = area.height.saturating_sub(2);
+        let popup_area = Rect {
+            x: area.x + (area.width.saturating_sub(popup_width)) / 2,
+            y: area.y + (area.height.saturating_sub(popup_height)) / 2,
+            width: popup_width,
+            height: popup_height,
+        };
+
+        let block = Block::default()
+            .title(format!("📚 Help: {}", context.name()))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+        let inner = block.inner(popup_area);
+
+        let sections = help_sections(context);
+        let columns = if inner.width >= TWO_COLUMN_MIN_WIDTH {
+            split_columns(&sections, 2)
+        } else {
+            vec![sections]
+        };
+        let column_lines: Vec<Vec<Line>> = columns.iter().map(|c| section_lines(c)).collect();
+
+        let content_height = column_lines.iter().map(Vec::len).max().unwrap_or(0) as u16;
+        self.max_scroll = content_height.saturating_sub(inner.height);
+        self.scroll = self.scroll.min(self.max_scroll);
+
+        let block = if self.max_scroll > 0 {
+            block.title_bottom(
+                Line::from(format!(" ↑/↓ scroll {}/{} ", self.scroll, self.max_scroll))
+                    .right_aligned(),
+            )
+        } else {
+            block
+        };
+
+        f.render_widget(Clear, popup_area);
+        f.render_widget(block, popup_area);
+
+        let areas = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![
+                Constraint::Ratio(1, column_lines.len() as u32);
+                column_lines.len()
+            ])
+            .split(inner);
+        for (lines, column_area) in column_lines.into_iter().zip(areas.iter()) {
+            f.render_widget(Paragraph::new(lines).scroll((self.scroll, 0)), *column_area);
+        }
+    }
+}
+
+fn section_lines(sections: &[HelpSection]) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    for section in sections {
+        lines.push(Line::from(Span::styled(
+            format!("{}:", section.title),
+            Style::default().add_modifier(Modifier::BOLD),
+        )));
+        for (keys, description) in &section.bindings {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {:<14}", keys),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(*description),
+            ]));
+        }
+        lines.push(Line::from(""));
+    }
+    lines
+}
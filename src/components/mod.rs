@@ -1 +1,5 @@
+pub mod action_menu;
+pub mod confirm_dialog;
+pub mod help_overlay;
 pub mod password_prompt;
+pub mod toasts;
@@ -102,6 +102,32 @@ impl PasswordPrompt {
         }
     }
 
+    /// Authenticate through the `SUDO_ASKPASS` helper instead of the
+    /// prompt, so a graphical helper can ask for the password
+    pub fn authenticate_with_askpass(&mut self) -> Result<bool> {
+        let status = Command::new("sudo")
+            .args(["-A", "-v"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()?;
+        self.authenticated = status.success();
+        Ok(self.authenticated)
+    }
+
+    /// Check that sudo still accepts the cached credentials, which expire
+    /// after a while, without asking for the password
+    pub fn revalidate(&mut self) -> bool {
+        self.authenticated = Command::new("sudo")
+            .args(["-n", "-v"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success());
+        self.authenticated
+    }
+
     /// Handle the submit action (Enter key)
     pub fn submit(&mut self) -> Result<bool> {
         self.verify_password()
@@ -0,0 +1,155 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem},
+    Frame,
+};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::schedule::local_seconds_since_midnight;
+
+/// How long each toast stays in the footer
+pub const TOAST_DURATION: Duration = Duration::from_secs(4);
+
+/// Toasts waiting beyond this are dropped, oldest first
+const MAX_QUEUED: usize = 5;
+
+/// Messages kept in the history popup
+pub const HISTORY_LIMIT: usize = 200;
+
+/// A message in the history, with the local time it was posted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    /// Seconds since local midnight
+    pub time: u64,
+    pub text: String,
+}
+
+/// Transient footer notifications shown one after another, plus a history
+/// of every message posted
+#[derive(Default)]
+pub struct Toasts {
+    /// The toast on screen and when it appeared
+    current: Option<(String, Instant)>,
+    queue: VecDeque<String>,
+    history: VecDeque<HistoryEntry>,
+    show_history: bool,
+}
+
+impl Toasts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Post a message; it is shown once the toasts before it have expired
+    pub fn push(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        if self.history.len() >= HISTORY_LIMIT {
+            self.history.pop_front();
+        }
+        self.history.push_back(HistoryEntry {
+            time: local_seconds_since_midnight(),
+            text: text.clone(),
+        });
+
+        if self.queue.len() >= MAX_QUEUED {
+            self.queue.pop_front();
+        }
+        self.queue.push_back(text);
+        self.tick();
+    }
+
+    /// Retire the current toast once it has been up for [`TOAST_DURATION`]
+    /// and bring in the next one
+    pub fn tick(&mut self) {
+        if self
+            .current
+            .as_ref()
+            .is_some_and(|(_, shown)| shown.elapsed() >= TOAST_DURATION)
+        {
+            self.current = None;
+        }
+        if self.current.is_none() {
+            self.current = self.queue.pop_front().map(|text| (text, Instant::now()));
+        }
+    }
+
+    /// The toast to show in the footer, if any
+    pub fn current(&self) -> Option<&str> {
+        self.current.as_ref().map(|(text, _)| text.as_str())
+    }
+
+    /// Every message posted, oldest first
+    pub fn history(&self) -> impl Iterator<Item = &HistoryEntry> {
+        self.history.iter()
+    }
+
+    pub fn toggle_history(&mut self) {
+        self.show_history = !self.show_history;
+    }
+
+    pub fn hide_history(&mut self) {
+        self.show_history = false;
+    }
+
+    pub fn is_history_visible(&self) -> bool {
+        self.show_history
+    }
+
+    /// Render the message history popup, newest message first
+    pub fn render_history(&self, f: &mut Frame, area: Rect) {
+        if !self.show_history {
+            return;
+        }
+
+        let popup_width = 80.min(area.width.saturating_sub(4));
+        let popup_height = (self.history.len() as u16 + 2)
+            .clamp(3, 20)
+            .min(area.height.saturating_sub(2));
+        let popup_area = Rect {
+            x: area.x + (area.width.saturating_sub(popup_width)) / 2,
+            y: area.y + (area.height.saturating_sub(popup_height)) / 2,
+            width: popup_width,
+            height: popup_height,
+        };
+
+        let items: Vec<ListItem> = if self.history.is_empty() {
+            vec![ListItem::new(Span::styled(
+                "No messages yet",
+                Style::default().fg(Color::DarkGray),
+            ))]
+        } else {
+            self.history
+                .iter()
+                .rev()
+                .map(|entry| {
+                    ListItem::new(Line::from(vec![
+                        Span::styled(
+                            format!(
+                                "{:02}:{:02}:{:02} ",
+                                entry.time / 3600,
+                                entry.time / 60 % 60,
+                                entry.time % 60
+                            ),
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                        Span::raw(entry.text.as_str()),
+                    ]))
+                })
+                .collect()
+        };
+
+        let list = List::new(items).block(
+            Block::default()
+                .title("💬 Messages (M or ESC to close)")
+                .title_style(Style::default().add_modifier(Modifier::BOLD))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        );
+
+        f.render_widget(Clear, popup_area);
+        f.render_widget(list, popup_area);
+    }
+}
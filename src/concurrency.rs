@@ -0,0 +1,196 @@
+//! How many threads scans and deletions use, and what kind of storage they
+//! run on. Both default to one thread per CPU, two per CPU on NVMe, which
+//! keeps deep queues busy, and one on a spinning disk, where parallel walks
+//! only make the head seek back and forth. Either can be set in the config
+//! file, or both at once with `--jobs`:
+//!
+//! ```toml
+//! scan_threads = 4
+//! delete_threads = 2
+//! ```
+//!
+//! The storage type is detected per mount: deletions pick their thread count
+//! from the disk holding the tree, scans from the one holding the home
+//! directory.
+
+use once_cell::sync::Lazy;
+#[cfg(unix)]
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::thread;
+
+use crate::filesystem::home_dir;
+use crate::mounts;
+
+/// The kind of device a filesystem lives on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageKind {
+    /// A spinning disk
+    Hdd,
+    /// A SATA or SAS SSD, or any other non-rotational device
+    Ssd,
+    /// An NVMe drive
+    Nvme,
+    /// Couldn't be told, e.g. network filesystems or non-Linux systems
+    Unknown,
+}
+
+impl StorageKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StorageKind::Hdd => "HDD",
+            StorageKind::Ssd => "SSD",
+            StorageKind::Nvme => "NVMe",
+            StorageKind::Unknown => "unknown",
+        }
+    }
+}
+
+static SCAN_THREADS: Lazy<Mutex<Option<usize>>> = Lazy::new(|| Mutex::new(None));
+static DELETE_THREADS: Lazy<Mutex<Option<usize>>> = Lazy::new(|| Mutex::new(None));
+
+/// Storage kind of each filesystem seen so far, by device id
+#[cfg(unix)]
+static KINDS: Lazy<Mutex<HashMap<u64, StorageKind>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Set the number of concurrent scans, from `--jobs` or the config file.
+/// None or 0 picks it from the CPU count and storage type.
+pub fn set_scan_threads(threads: Option<usize>) {
+    *SCAN_THREADS.lock().unwrap_or_else(|e| e.into_inner()) = threads.filter(|&n| n > 0);
+}
+
+/// Set the number of threads removing files, like [`set_scan_threads`]
+pub fn set_delete_threads(threads: Option<usize>) {
+    *DELETE_THREADS.lock().unwrap_or_else(|e| e.into_inner()) = threads.filter(|&n| n > 0);
+}
+
+/// How many scans may run at once
+pub fn scan_threads() -> usize {
+    SCAN_THREADS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .unwrap_or_else(|| auto_threads(home_storage()))
+}
+
+/// How many threads remove the files of a large directory in the home
+/// directory's filesystem
+pub fn delete_threads() -> usize {
+    delete_threads_for(&home_dir().unwrap_or_default())
+}
+
+/// How many threads remove the files of a large directory below `path`
+pub fn delete_threads_for(path: &Path) -> usize {
+    DELETE_THREADS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .unwrap_or_else(|| auto_threads(storage_kind(path)))
+}
+
+/// The default thread count for a kind of storage
+pub fn auto_threads(kind: StorageKind) -> usize {
+    let cpus = thread::available_parallelism().map_or(1, |n| n.get());
+    match kind {
+        StorageKind::Hdd => 1,
+        StorageKind::Nvme => cpus * 2,
+        StorageKind::Ssd | StorageKind::Unknown => cpus,
+    }
+}
+
+/// The storage the home directory is on
+pub fn home_storage() -> StorageKind {
+    home_dir().map_or(StorageKind::Unknown, |home| storage_kind(&home))
+}
+
+/// The storage `path` is on, detected once per filesystem
+#[cfg(unix)]
+pub fn storage_kind(path: &Path) -> StorageKind {
+    use std::os::unix::fs::MetadataExt;
+
+    let Ok(metadata) = fs::metadata(path) else {
+        return StorageKind::Unknown;
+    };
+    let dev = metadata.dev();
+    if let Some(kind) = KINDS.lock().unwrap_or_else(|e| e.into_inner()).get(&dev) {
+        return *kind;
+    }
+    let kind = detect(path, dev);
+    KINDS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(dev, kind);
+    kind
+}
+
+#[cfg(not(unix))]
+pub fn storage_kind(_path: &Path) -> StorageKind {
+    StorageKind::Unknown
+}
+
+#[cfg(target_os = "linux")]
+fn detect(path: &Path, dev: u64) -> StorageKind {
+    let sys = Path::new("/sys");
+    let major = ((dev >> 8) & 0xfff) | ((dev >> 32) & !0xfff);
+    let minor = (dev & 0xff) | ((dev >> 12) & !0xff);
+    if major != 0 {
+        return storage_in(sys, major, minor);
+    }
+    // Btrfs and other filesystems spanning devices report an anonymous
+    // device id, so look up what the mount was made from instead
+    let Ok(mountinfo) = fs::read_to_string("/proc/self/mountinfo") else {
+        return StorageKind::Unknown;
+    };
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    // Follows /dev/mapper/* to the dm-N device sysfs knows it by
+    mount_source_in(&mountinfo, &path)
+        .and_then(|source| fs::canonicalize(source).ok())
+        .and_then(|device| Some(device.file_name()?.to_owned()))
+        .map_or(StorageKind::Unknown, |name| {
+            block_kind(&sys.join("class/block").join(name))
+        })
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn detect(_path: &Path, _dev: u64) -> StorageKind {
+    StorageKind::Unknown
+}
+
+/// The kind of the block device `major:minor`, from a sysfs tree at `sys`
+pub fn storage_in(sys: &Path, major: u64, minor: u64) -> StorageKind {
+    block_kind(&sys.join(format!("dev/block/{}:{}", major, minor)))
+}
+
+/// The kind of the block device whose sysfs directory is `device`. A
+/// partition has no queue of its own, so its disk's is read.
+fn block_kind(device: &Path) -> StorageKind {
+    let flag = fs::read_to_string(device.join("queue/rotational"))
+        .or_else(|_| fs::read_to_string(device.join("../queue/rotational")));
+    let nvme = fs::canonicalize(device)
+        .ok()
+        .and_then(|dir| Some(dir.file_name()?.to_string_lossy().starts_with("nvme")))
+        .unwrap_or(false);
+    match flag.as_deref().map(str::trim) {
+        Ok("1") => StorageKind::Hdd,
+        Ok("0") if nvme => StorageKind::Nvme,
+        Ok("0") => StorageKind::Ssd,
+        _ => StorageKind::Unknown,
+    }
+}
+
+/// The source of the mount holding `path`, from `/proc/self/mountinfo`
+/// contents: the mount with the longest mount point `path` is below
+pub fn mount_source_in(mountinfo: &str, path: &Path) -> Option<String> {
+    mountinfo
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(' ').collect();
+            let mount_point = mounts::unescape(fields.get(4)?);
+            let separator = fields.iter().position(|field| *field == "-")?;
+            let source = fields.get(separator + 2)?;
+            path.starts_with(&mount_point)
+                .then(|| (mount_point.len(), source.to_string()))
+        })
+        .max_by_key(|(len, _)| *len)
+        .map(|(_, source)| source)
+}
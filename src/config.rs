@@ -0,0 +1,295 @@
+//! User settings loaded from `~/.config/cleansys/config.toml`.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::cleaners::custom::CustomCleaner;
+use crate::cleaners::log_backends::JournalVacuum;
+use crate::cleaners::package_cache::PackageRetention;
+use crate::limits::RunLimit;
+use crate::netfs::NetworkAction;
+use crate::paths;
+use crate::privileges;
+use crate::quarantine::{QuarantineRetention, QuarantineTarget};
+use crate::running::RunningApps;
+use crate::watchdog::CleanerTimeouts;
+
+/// Persistent defaults for command line options
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Run at idle CPU and IO priority, like `--low-priority`
+    pub low_priority: bool,
+    /// Pause between deletions in low priority mode, in milliseconds
+    pub deletion_delay_ms: u64,
+    /// Scans running at once, 0 or unset for one per CPU (one on a
+    /// spinning disk)
+    pub scan_threads: Option<usize>,
+    /// Threads removing the files of a large directory, picked like
+    /// `scan_threads`
+    pub delete_threads: Option<usize>,
+    /// Best-effort IO priority from 0 (highest) to 7 (lowest), like
+    /// `ionice -c2 -n`
+    pub io_nice: Option<u8>,
+    /// Offer `fstrim` after a run as root freed a lot on an SSD mount
+    pub offer_trim: bool,
+    /// Run inside a transient systemd scope, like `--scope`
+    pub systemd_scope: bool,
+    /// `IOWeight=`/`CPUWeight=` for the scope, defaults to a low weight
+    pub scope_weight: Option<u16>,
+    /// Watch cache directories in the TUI and refresh sizes live, like `--watch`
+    pub live_sizes: bool,
+    /// Files a single run may delete before asking, 0 for no limit
+    pub max_files_per_run: Option<u64>,
+    /// Gigabytes a single run may delete before asking, 0 for no limit
+    pub max_gb_per_run: Option<u64>,
+    /// Record path and SHA-256 of small deleted files, like `--audit`
+    pub audit: bool,
+    /// Where quarantined files go: "private" or "system-trash"
+    pub quarantine: QuarantineTarget,
+    /// Purge quarantine runs older than this many days, 0 for no limit
+    pub quarantine_max_age_days: Option<u64>,
+    /// Purge the oldest quarantine runs while all of them take more
+    /// gigabytes than this, 0 for no limit
+    pub quarantine_max_gb: Option<u64>,
+    /// Versions of each package to keep in package manager caches
+    pub package_retention: PackageRetention,
+    /// Cap the systemd journal at this size (e.g. "500M") after vacuuming,
+    /// by writing a journald.conf.d drop-in
+    pub journal_max_use: Option<String>,
+    /// Vacuum the journal by age or down to a size, e.g.
+    /// `[journal_vacuum] mode = "size"` and `size = "500M"`
+    pub journal_vacuum: JournalVacuum,
+    /// Run `fc-cache` and `gtk-update-icon-cache` after font and icon
+    /// caches are cleaned
+    pub rebuild_caches: bool,
+    /// Keep finished print jobs from this many recent days in the CUPS
+    /// job history
+    pub print_jobs_keep_days: Option<u64>,
+    /// Keep this many of the newest kernels besides the running one
+    /// (default 2)
+    pub keep_kernels: Option<usize>,
+    /// Register the web server, database log and PHP session cleaners
+    pub server_cleaners: bool,
+    /// Register the cleaners for temp litter found by name pattern
+    pub advanced_temp_cleanup: bool,
+    /// Let the Oversized Mailboxes cleaner empty mailboxes over 100 MB
+    /// instead of only reporting them
+    pub truncate_large_mailboxes: bool,
+    /// Authenticate through the `SUDO_ASKPASS` helper even on a terminal,
+    /// instead of cleansys' own password prompt
+    pub sudo_askpass: bool,
+    /// Minutes a cleaner may run before it is stopped, 0 for no timeout
+    pub cleaner_timeout_minutes: Option<u64>,
+    /// Timeouts for individual cleaners by name, in minutes
+    pub cleaner_timeouts: BTreeMap<String, u64>,
+    /// Globs for paths that are never cleaned, see [`crate::exclude`]
+    pub exclude: Vec<String>,
+    /// Warn about or skip caches of running applications, see
+    /// [`crate::running`]
+    pub running_apps: RunningApps,
+    /// Skip, warn about or ignore paths on network filesystems, see
+    /// [`crate::netfs`]
+    pub network_filesystems: NetworkAction,
+    /// Cleaners for paths cleansys doesn't know about, see
+    /// [`crate::cleaners::custom`]
+    pub custom_cleaners: Vec<CustomCleaner>,
+}
+
+impl Settings {
+    /// The per-run safety limit, using the defaults for unset values. A
+    /// size too large to count in bytes is no cap, like 0.
+    pub fn run_limit(&self) -> RunLimit {
+        let defaults = RunLimit::default();
+        RunLimit {
+            max_files: self.max_files_per_run.unwrap_or(defaults.max_files),
+            max_bytes: self.max_gb_per_run.map_or(defaults.max_bytes, |gb| {
+                gb.checked_mul(1024 * 1024 * 1024).unwrap_or(0)
+            }),
+        }
+    }
+
+    /// How long quarantine runs are kept, with unset and 0 values meaning
+    /// no limit. So do values too large to count, rather than wrapping
+    /// around to a small one.
+    pub fn quarantine_retention(&self) -> QuarantineRetention {
+        QuarantineRetention {
+            max_age: self
+                .quarantine_max_age_days
+                .filter(|&days| days > 0)
+                .and_then(|days| days.checked_mul(24 * 60 * 60))
+                .map(Duration::from_secs),
+            max_bytes: self
+                .quarantine_max_gb
+                .filter(|&gb| gb > 0)
+                .and_then(|gb| gb.checked_mul(1024 * 1024 * 1024)),
+        }
+    }
+
+    /// The per-cleaner timeouts, using the default for unset values
+    pub fn cleaner_timeouts(&self) -> CleanerTimeouts {
+        let minutes = |m: u64| Duration::from_secs(m * 60);
+        CleanerTimeouts {
+            default: match self.cleaner_timeout_minutes {
+                Some(0) => None,
+                Some(m) => Some(minutes(m)),
+                None => CleanerTimeouts::default().default,
+            },
+            per_cleaner: self
+                .cleaner_timeouts
+                .iter()
+                .map(|(name, m)| (name.clone(), minutes(*m)))
+                .collect(),
+        }
+    }
+
+    /// Location of the config file, if a home directory is available
+    pub fn path() -> Option<PathBuf> {
+        paths::config_dir().ok().map(|dir| dir.join("config.toml"))
+    }
+
+    /// Load settings from the default location, falling back to defaults
+    /// when the file doesn't exist.
+    pub fn load() -> Result<Self> {
+        match Self::path() {
+            Some(path) if path.exists() => Self::load_from(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Load settings from a specific file
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {:?}", path))?;
+        toml::from_str(&contents).with_context(|| format!("Invalid config file {:?}", path))
+    }
+
+    /// Add an exclusion pattern to the config file at the default location
+    pub fn add_exclusion(pattern: &str) -> Result<PathBuf> {
+        let path = Self::path().context("No config directory available")?;
+        privileges::as_user(|| Self::add_exclusion_to(&path, pattern))?;
+        Ok(path)
+    }
+
+    /// Add an exclusion pattern to a specific config file, creating it if
+    /// needed. The rest of the file, comments included, is left as it was.
+    pub fn add_exclusion_to(path: &Path, pattern: &str) -> Result<()> {
+        let contents = if path.exists() {
+            fs::read_to_string(path)
+                .with_context(|| format!("Failed to read config file {:?}", path))?
+        } else {
+            String::new()
+        };
+        let updated = append_exclusion(&contents, pattern);
+        let settings: Self = toml::from_str(&updated)
+            .with_context(|| format!("Could not add the exclusion to {:?}", path))?;
+        if !settings.exclude.iter().any(|p| p == pattern) {
+            bail!("Could not add the exclusion to {:?}", path);
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+        }
+        fs::write(path, updated).with_context(|| format!("Failed to write config file {:?}", path))
+    }
+}
+
+/// Config file contents with `pattern` added to the top-level `exclude`
+/// array, which is created before the first table if there is none
+pub fn append_exclusion(contents: &str, pattern: &str) -> String {
+    let quoted = toml::Value::String(pattern.to_string()).to_string();
+
+    let mut offset = 0;
+    for line in contents.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with('[') {
+            break;
+        }
+        let is_exclude = trimmed
+            .strip_prefix("exclude")
+            .is_some_and(|rest| rest.trim_start().starts_with('='));
+        if is_exclude {
+            if let Some((at, separator)) = array_insert_position(&contents[offset..]) {
+                let at = offset + at;
+                return format!(
+                    "{}{}{}{}",
+                    &contents[..at],
+                    separator,
+                    quoted,
+                    &contents[at..]
+                );
+            }
+        }
+        offset += line.len();
+    }
+
+    // No exclude key: add one before the first table, where it is still
+    // top-level
+    let line = format!("exclude = [{}]\n", quoted);
+    let mut result = contents[..offset].to_string();
+    if !result.is_empty() && !result.ends_with('\n') {
+        result.push('\n');
+    }
+    result.push_str(&line);
+    if offset < contents.len() {
+        result.push('\n');
+        result.push_str(&contents[offset..]);
+    }
+    result
+}
+
+/// Where a new element goes in the array assigned on the first line of
+/// `text`: just after the last element, and what to put before it
+fn array_insert_position(text: &str) -> Option<(usize, &'static str)> {
+    let start = text.find('[')?;
+    let mut last_value = None;
+    let mut trailing_comma = false;
+    let mut chars = text[start + 1..].char_indices();
+    while let Some((i, c)) = chars.next() {
+        let at = start + 1 + i;
+        match c {
+            ']' => {
+                return Some(match last_value {
+                    Some(end) if trailing_comma => (end, " "),
+                    Some(end) => (end, ", "),
+                    None => (at, ""),
+                })
+            }
+            '#' => {
+                for (_, c) in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '"' | '\'' => {
+                let mut escaped = false;
+                for (j, d) in chars.by_ref() {
+                    if c == '"' && d == '\\' && !escaped {
+                        escaped = true;
+                        continue;
+                    }
+                    if d == c && !escaped {
+                        last_value = Some(start + 1 + j + 1);
+                        break;
+                    }
+                    escaped = false;
+                }
+                trailing_comma = false;
+            }
+            ',' => {
+                trailing_comma = true;
+                last_value = Some(at + 1);
+            }
+            c if c.is_whitespace() => {}
+            _ => {
+                trailing_comma = false;
+                last_value = Some(at + c.len_utf8());
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,303 @@
+//! The `org.cleansys.Manager` D-Bus service. `cleansys dbus` serves it on
+//! the session bus, so desktop widgets, shell extensions and other programs
+//! can scan, clean and read the history without running the CLI and parsing
+//! what it prints:
+//!
+//! ```text
+//! Scan() -> a(ssbt)              category, cleaner, needs root, reclaimable bytes
+//! Clean(as cleaners) -> u        starts the cleaners, returns how many
+//! GetHistory(u limit) -> a(usttuu)  id, time, bytes, deferred, cleaners, failed
+//! Busy                           property, whether a clean is running
+//! Progress(s cleaner, u index, u total, t bytes)  signal before each cleaner
+//! Finished(t bytes, as failed)   signal once a clean is done
+//! ```
+//!
+//! Cleaning runs in the background and reports through the signals, so a
+//! long run doesn't run into the caller's D-Bus timeout. Nobody is there to
+//! confirm anything, so high risk cleaners are refused, and system cleaners
+//! are only offered when the service runs as root or the root
+//! [`helper`](crate::helper) is installed.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use zbus::object_server::SignalEmitter;
+use zbus::zvariant::Type;
+use zbus::{fdo, interface};
+
+use crate::cleaners::cleaned;
+use crate::cleaners::{groups, CleanerGroup, CleanerInfo, RiskLevel};
+use crate::deferred;
+use crate::helper;
+use crate::history::{CleanerRun, History, RunRecord};
+use crate::privileges;
+use crate::scanner;
+use crate::usage;
+use crate::utils::check_root;
+use crate::watchdog;
+
+/// The well-known name the service owns
+pub const BUS_NAME: &str = "org.cleansys.Manager";
+
+/// Where the manager object lives
+pub const OBJECT_PATH: &str = "/org/cleansys/Manager";
+
+/// One cleaner's estimate, as returned by `Scan`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct ScanEntry {
+    pub category: String,
+    pub cleaner: String,
+    pub requires_root: bool,
+    pub reclaimable: u64,
+}
+
+/// One recorded run, as returned by `GetHistory`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct HistoryEntry {
+    pub id: u64,
+    pub time: String,
+    pub bytes: u64,
+    pub deferred: u64,
+    pub cleaners: u32,
+    pub failed: u32,
+}
+
+impl From<&RunRecord> for HistoryEntry {
+    fn from(run: &RunRecord) -> Self {
+        HistoryEntry {
+            id: run.id,
+            time: run.time.clone(),
+            bytes: run.total(),
+            deferred: run.deferred(),
+            cleaners: run.cleaners.len() as u32,
+            failed: run.cleaners.iter().filter(|c| c.error.is_some()).count() as u32,
+        }
+    }
+}
+
+/// A cleaner a `Clean` call runs
+pub struct Job {
+    /// Name of the group it belongs to, which the root helper asks for
+    pub category: &'static str,
+    pub cleaner: CleanerInfo,
+}
+
+/// The cleaners named in a `Clean` call, in the order given. `root` tells
+/// whether system cleaners can run, as root or through the helper.
+pub fn resolve(
+    names: &[String],
+    groups: Vec<CleanerGroup>,
+    root: bool,
+) -> std::result::Result<Vec<Job>, String> {
+    if names.is_empty() {
+        return Err("No cleaners given".to_string());
+    }
+    let mut available: Vec<Job> = groups
+        .into_iter()
+        .flat_map(|group| {
+            let category = group.name;
+            group
+                .cleaners
+                .into_iter()
+                .map(move |cleaner| Job { category, cleaner })
+        })
+        .collect();
+
+    let mut jobs = Vec::new();
+    for name in names {
+        let Some(index) = available.iter().position(|job| job.cleaner.name == name) else {
+            return Err(format!("Unknown cleaner '{}'", name));
+        };
+        let job = available.remove(index);
+        if !job.cleaner.is_available() {
+            return Err(format!("'{}' doesn't apply to this system", name));
+        }
+        if job.cleaner.risk == RiskLevel::High {
+            return Err(format!("'{}' is high risk, run it from the TUI", name));
+        }
+        if job.cleaner.requires_root && !root {
+            return Err(format!(
+                "'{}' needs root, install the cleansys helper to run it",
+                name
+            ));
+        }
+        jobs.push(job);
+    }
+    Ok(jobs)
+}
+
+/// The last `limit` runs of a history, newest first
+pub fn recent_runs(runs: &[RunRecord], limit: usize) -> Vec<HistoryEntry> {
+    runs.iter()
+        .rev()
+        .take(limit)
+        .map(HistoryEntry::from)
+        .collect()
+}
+
+/// Estimate what every available cleaner that can scan would free
+fn scan_all() -> Vec<ScanEntry> {
+    let mut entries = Vec::new();
+    for group in groups() {
+        for cleaner in group.cleaners {
+            let Some(scan) = cleaner.scan.filter(|_| cleaner.is_available()) else {
+                continue;
+            };
+            if let Ok(report) = scan() {
+                entries.push(ScanEntry {
+                    category: group.name.to_string(),
+                    cleaner: cleaner.name.to_string(),
+                    requires_root: group.requires_root,
+                    reclaimable: report.total_reclaimable(),
+                });
+            }
+        }
+    }
+    if let Err(e) = scanner::save_cache() {
+        log::debug!("Failed to save scan cache: {:#}", e);
+    }
+    entries
+}
+
+/// Run one cleaner without confirmations, through the helper when it needs
+/// root the service doesn't have
+fn run_job(job: &Job) -> CleanerRun {
+    let name = job.cleaner.name;
+    deferred::take();
+    cleaned::take();
+    cleaned::take_failures();
+    cleaned::take_denied();
+    let result = if job.cleaner.requires_root && !check_root() {
+        helper::run_cleaner(job.category, name, None)
+    } else {
+        privileges::as_root_if(job.cleaner.requires_root, || {
+            watchdog::run(name, || (job.cleaner.function)(true))
+        })
+    };
+    cleaned::take();
+    cleaned::take_denied();
+    let failures = cleaned::take_failures();
+    match result {
+        Ok(bytes) => CleanerRun::succeeded(name, bytes)
+            .with_deferred(deferred::take().min(bytes))
+            .with_failed_paths(failures.len()),
+        Err(e) => CleanerRun::failed(name, format!("{:#}", e)),
+    }
+}
+
+/// Save a finished run to the history and usage statistics
+fn record(run: RunRecord) {
+    if let Err(e) = usage::record_run(&run) {
+        log::debug!("Run not counted in usage statistics: {:#}", e);
+    }
+    if let Err(e) = History::open().and_then(|history| history.record(run)) {
+        log::debug!("Run not saved to history: {:#}", e);
+    }
+}
+
+#[derive(Default)]
+struct Manager {
+    busy: Arc<AtomicBool>,
+}
+
+#[interface(name = "org.cleansys.Manager")]
+impl Manager {
+    /// Estimate what each available cleaner would free
+    async fn scan(&self) -> Vec<ScanEntry> {
+        blocking::unblock(scan_all).await
+    }
+
+    /// Start the named cleaners one after another, returning how many run.
+    /// `Progress` and `Finished` report how it goes.
+    async fn clean(
+        &self,
+        #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+        cleaners: Vec<String>,
+    ) -> fdo::Result<u32> {
+        let root = check_root() || helper::available();
+        let jobs = resolve(&cleaners, groups(), root).map_err(fdo::Error::InvalidArgs)?;
+        if self.busy.swap(true, Ordering::SeqCst) {
+            return Err(fdo::Error::Failed("A clean is already running".into()));
+        }
+        self.busy_changed(&emitter).await?;
+
+        let count = jobs.len() as u32;
+        let busy = Arc::clone(&self.busy);
+        let emitter = emitter.into_owned();
+        let connection = emitter.connection().clone();
+        let task = async move {
+            let mut runs = Vec::new();
+            let mut bytes = 0;
+            for (index, job) in jobs.into_iter().enumerate() {
+                let _ =
+                    Self::progress(&emitter, job.cleaner.name, index as u32, count, bytes).await;
+                let run = blocking::unblock(move || run_job(&job)).await;
+                bytes += run.bytes;
+                runs.push(run);
+            }
+            let failed: Vec<String> = runs
+                .iter()
+                .filter(|run| run.error.is_some())
+                .map(|run| run.name.clone())
+                .collect();
+            blocking::unblock(move || record(RunRecord::new(runs))).await;
+            busy.store(false, Ordering::SeqCst);
+            let _ = Self::finished(&emitter, bytes, failed).await;
+            if let Ok(iface) = emitter
+                .connection()
+                .object_server()
+                .interface::<_, Manager>(OBJECT_PATH)
+                .await
+            {
+                let _ = iface.get().await.busy_changed(&emitter).await;
+            }
+        };
+        connection.executor().spawn(task, "cleansys clean").detach();
+        Ok(count)
+    }
+
+    /// The last `limit` recorded runs, newest first
+    fn get_history(&self, limit: u32) -> fdo::Result<Vec<HistoryEntry>> {
+        let runs = History::open()
+            .and_then(|history| history.load())
+            .map_err(|e| fdo::Error::Failed(format!("{:#}", e)))?;
+        Ok(recent_runs(&runs, limit as usize))
+    }
+
+    /// Whether a clean is running
+    #[zbus(property)]
+    fn busy(&self) -> bool {
+        self.busy.load(Ordering::SeqCst)
+    }
+
+    /// Sent before each cleaner of a clean runs, with the bytes freed so far
+    #[zbus(signal)]
+    async fn progress(
+        emitter: &SignalEmitter<'_>,
+        cleaner: &str,
+        index: u32,
+        total: u32,
+        bytes: u64,
+    ) -> zbus::Result<()>;
+
+    /// Sent when a clean is done, with the cleaners that failed
+    #[zbus(signal)]
+    async fn finished(
+        emitter: &SignalEmitter<'_>,
+        bytes: u64,
+        failed: Vec<String>,
+    ) -> zbus::Result<()>;
+}
+
+/// Serve the manager on the session bus until the process is stopped
+pub fn serve() -> Result<()> {
+    let _connection = zbus::blocking::connection::Builder::session()?
+        .name(BUS_NAME)?
+        .serve_at(OBJECT_PATH, Manager::default())?
+        .build()?;
+    log::info!("Serving {} on the session bus", BUS_NAME);
+    loop {
+        std::thread::park();
+    }
+}
@@ -0,0 +1,123 @@
+//! Space that is only freed after a reboot. A file deleted while a process
+//! still has it open keeps its blocks until the last descriptor closes, so
+//! the caches of a running browser or a log a daemon still writes show up
+//! in `df` only once that program restarts, at the latest after a reboot.
+//! Deletions check such files against the open files of all processes
+//! (from `/proc/*/fd`, read once per cleaner) and count them here, so the
+//! freed total can be split into what is free now and what comes later.
+
+use once_cell::sync::Lazy;
+use std::collections::HashSet;
+use std::fs::Metadata;
+use std::sync::Mutex;
+
+use crate::utils::{format_size, format_size_exact};
+
+/// Device and inode of a file
+type FileId = (u64, u64);
+
+/// Every file a process has open, read on the first deletion after [`take`]
+static OPEN_FILES: Lazy<Mutex<Option<HashSet<FileId>>>> = Lazy::new(|| Mutex::new(None));
+
+/// Bytes deleted while held open since the last [`take`]
+static DEFERRED: Lazy<Mutex<u64>> = Lazy::new(|| Mutex::new(0));
+
+/// Note a file about to be deleted. When a process has it open and this is
+/// its last link, its size is only freed once that process lets go.
+pub fn record(metadata: &Metadata) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        if !metadata.is_file() || metadata.nlink() > 1 {
+            return;
+        }
+        let held_open = OPEN_FILES
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_or_insert_with(open_files)
+            .contains(&(metadata.dev(), metadata.ino()));
+        if held_open {
+            *DEFERRED.lock().unwrap_or_else(|e| e.into_inner()) += metadata.len();
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = metadata;
+}
+
+/// Add bytes held open that were measured elsewhere, such as by the root
+/// helper
+pub fn add(bytes: u64) {
+    *DEFERRED.lock().unwrap_or_else(|e| e.into_inner()) += bytes;
+}
+
+/// Bytes recorded since the last call. The open files are read again for
+/// the next cleaner, since programs may have started or exited meanwhile.
+pub fn take() -> u64 {
+    *OPEN_FILES.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    std::mem::take(&mut *DEFERRED.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// A freed size, split when part of it only comes free after a reboot:
+/// "1.20 GB" or "1.20 GB freed now, 600.00 MB after reboot"
+pub fn describe(total: u64, deferred: u64) -> String {
+    let deferred = deferred.min(total);
+    if deferred == 0 {
+        format_size(total)
+    } else {
+        format!(
+            "{} freed now, {} after reboot",
+            format_size(total - deferred),
+            format_size(deferred)
+        )
+    }
+}
+
+/// [`describe`] for the total of a run, followed by the exact number of
+/// bytes freed
+pub fn describe_total(total: u64, deferred: u64) -> String {
+    if deferred.min(total) == 0 {
+        format_size_exact(total)
+    } else {
+        format!("{} ({} bytes)", describe(total, deferred), total)
+    }
+}
+
+/// Device and inode of the files open in every process we may look at.
+/// Following an fd link stats the open file itself, even once deleted.
+#[cfg(target_os = "linux")]
+fn open_files() -> HashSet<FileId> {
+    use std::fs;
+    use std::os::unix::fs::MetadataExt;
+
+    let mut open = HashSet::new();
+    let Ok(processes) = fs::read_dir("/proc") else {
+        return open;
+    };
+    for process in processes.flatten() {
+        let is_pid = process
+            .file_name()
+            .to_str()
+            .is_some_and(|name| name.bytes().all(|b| b.is_ascii_digit()));
+        if !is_pid {
+            continue;
+        }
+        // Other users' processes can't be looked into without root
+        let Ok(fds) = fs::read_dir(process.path().join("fd")) else {
+            continue;
+        };
+        for fd in fds.flatten() {
+            if let Ok(metadata) = fs::metadata(fd.path()) {
+                if metadata.is_file() {
+                    open.insert((metadata.dev(), metadata.ino()));
+                }
+            }
+        }
+    }
+    open
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn open_files() -> HashSet<FileId> {
+    HashSet::new()
+}
@@ -0,0 +1,524 @@
+//! Recursive deletion for cleaners. Symlinks are removed, never followed,
+//! and on Unix the walk goes through open directories: subdirectories are
+//! opened relative to their parent without following symlinks and entries
+//! unlinked relative to the directory holding them, so swapping part of a
+//! tree for a symlink while it is deleted (say, a user's tree in /tmp being
+//! deleted as root) can't lead the deletion anywhere else. The tree is
+//! counted before anything is deleted so huge directories (e.g. 300k
+//! thumbnails) can report progress by file count and bytes instead of
+//! appearing hung. A cancelled run stops between files, and
+//! protected paths (read-only mounts, immutable files) are skipped up front.
+//! Excluded paths and files younger than the run's minimum age are left in
+//! place along with the directories holding them. Files a process still
+//! holds open are counted as freed only after a reboot, see [`deferred`].
+//! Directories with many files are emptied on several threads, and files on
+//! a spinning disk are unlinked in inode order.
+
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::audit;
+use crate::cancel;
+use crate::concurrency;
+use crate::deferred;
+use crate::exclude;
+use crate::limits;
+use crate::pipeline::Pipeline;
+use crate::protection::{self, Protection};
+
+/// Trees with fewer files than this finish too quickly to need progress
+pub const PROGRESS_MIN_FILES: u64 = 1000;
+
+/// Directories with fewer files than this are emptied on one thread, see
+/// [`concurrency::delete_threads_for`]
+const PARALLEL_MIN_FILES: usize = 64;
+
+/// Minimum time between progress events
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How far a deletion has got
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeleteProgress {
+    /// The directory being deleted
+    pub root: PathBuf,
+    pub files_done: u64,
+    pub files_total: u64,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+}
+
+impl DeleteProgress {
+    /// Percentage complete by bytes, or by file count for trees of empty files
+    pub fn percent(&self) -> f64 {
+        let (done, total) = if self.bytes_total > 0 {
+            (self.bytes_done, self.bytes_total)
+        } else {
+            (self.files_done, self.files_total)
+        };
+        if total == 0 {
+            100.0
+        } else {
+            done as f64 * 100.0 / total as f64
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.files_done >= self.files_total
+    }
+}
+
+type ProgressHandler = Box<dyn Fn(&DeleteProgress) + Send>;
+
+static PROGRESS_HANDLER: Lazy<Mutex<Option<ProgressHandler>>> = Lazy::new(|| Mutex::new(None));
+
+/// Receive progress events for every large deletion made through [`remove_dir_all`]
+pub fn set_progress_handler(handler: impl Fn(&DeleteProgress) + Send + 'static) {
+    *PROGRESS_HANDLER.lock().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(handler));
+}
+
+/// Stop reporting progress
+pub fn clear_progress_handler() {
+    *PROGRESS_HANDLER.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Delete a single file, counting it against the per-run safety limit.
+/// Returns its size.
+pub fn remove_file(path: &Path) -> Result<u64> {
+    remove_file_with(path, || fs::remove_file(path))
+}
+
+/// [`remove_file`] for a file somewhere below `root`, reached through open
+/// directories from `root` down. A directory on the way swapped for a
+/// symlink fails the deletion instead of leading it outside `root`.
+pub fn remove_file_below(root: &Path, path: &Path) -> Result<u64> {
+    let relative = path
+        .strip_prefix(root)
+        .ok()
+        .filter(|relative| {
+            relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        })
+        .with_context(|| format!("Refusing to remove {:?}, it isn't below {:?}", path, root))?;
+    let name = relative
+        .file_name()
+        .with_context(|| format!("Refusing to remove {:?}", path))?;
+    remove_file_with(path, || {
+        let mut dir = TreeDir::open(root)?;
+        for component in relative.parent().unwrap_or(Path::new("")).components() {
+            dir = dir.open_child(component.as_os_str())?;
+        }
+        dir.remove(name, false)
+    })
+}
+
+/// Delete the file at `path` with `unlink`, after the checks every deletion
+/// goes through, returning its size
+fn remove_file_with(path: &Path, unlink: impl FnOnce() -> io::Result<()>) -> Result<u64> {
+    cancel::check()?;
+    protection::guard(path)?;
+    let metadata = fs::symlink_metadata(path).ok();
+    let size = metadata.as_ref().map_or(0, |m| m.len());
+    limits::check(1, size)?;
+    audit::record(path)?;
+    unlink().with_context(|| format!("Failed to remove {:?}", path))?;
+    limits::record(1, size);
+    if let Some(metadata) = &metadata {
+        deferred::record(metadata);
+    }
+    Ok(size)
+}
+
+/// Delete a directory tree, counting it against the per-run safety limit
+/// and reporting progress to the global handler when the tree holds at
+/// least [`PROGRESS_MIN_FILES`] files. Returns the bytes of the files
+/// unlinked, which leaves out excluded and recent files kept in the tree.
+pub fn remove_dir_all(path: &Path) -> Result<u64> {
+    remove_dir_all_with_progress(path, |progress| {
+        if progress.files_total >= PROGRESS_MIN_FILES {
+            if let Some(handler) = PROGRESS_HANDLER
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .as_ref()
+            {
+                handler(progress);
+            }
+        }
+    })
+}
+
+/// [`remove_dir_all`], calling `on_progress` instead of the global handler,
+/// at most every 100ms and once more when done. A symlink at `path` is
+/// removed without touching its target.
+pub fn remove_dir_all_with_progress(
+    path: &Path,
+    on_progress: impl FnMut(&DeleteProgress),
+) -> Result<u64> {
+    let metadata =
+        fs::symlink_metadata(path).with_context(|| format!("Failed to remove {:?}", path))?;
+    if !metadata.is_dir() {
+        return remove_file(path);
+    }
+    protection::guard(path)?;
+
+    let (files_total, bytes_total) = count_tree(path);
+    limits::check(files_total, bytes_total)?;
+
+    let mut progress = DeleteProgress {
+        root: path.to_path_buf(),
+        files_done: 0,
+        files_total,
+        bytes_done: 0,
+        bytes_total,
+    };
+    let result = delete_tree(path, &mut progress, on_progress);
+    // Count partial deletions too, so a failed run can't dodge the limit.
+    // Every unlinked file is counted, not just those progress was sent for.
+    limits::record(progress.files_done, progress.bytes_done);
+    result.map(|()| progress.bytes_done)
+}
+
+fn delete_tree(
+    path: &Path,
+    progress: &mut DeleteProgress,
+    mut on_progress: impl FnMut(&DeleteProgress),
+) -> Result<()> {
+    on_progress(progress);
+    let mut last_event = Instant::now();
+
+    #[cfg(unix)]
+    let storage = concurrency::storage_kind(path);
+    let threads = concurrency::delete_threads_for(path);
+
+    // The directory holding the tree is trusted like the path given, so it
+    // may be reached through symlinks; nothing below it is
+    let name = path
+        .file_name()
+        .with_context(|| format!("Refusing to remove {:?}", path))?;
+    let parent = path.parent().unwrap_or(Path::new(""));
+    let parent = TreeDir::open(parent).with_context(|| format!("Failed to read {:?}", parent))?;
+
+    // Depth-first, removing each directory once all of its children are
+    // gone. Each entry holds the open directory it is in, its name there,
+    // and once its children are done, the directory itself.
+    let mut stack: Vec<(Rc<TreeDir>, OsString, Option<Rc<TreeDir>>)> =
+        vec![(Rc::new(parent), name.to_os_string(), None)];
+    while let Some((parent, name, opened)) = stack.pop() {
+        if let Some(dir) = opened {
+            match parent.remove(&name, true) {
+                // Directories holding kept paths stay, and so do the
+                // directories above them
+                Err(e) if dir.holds_kept() && e.kind() == io::ErrorKind::DirectoryNotEmpty => {
+                    parent.keep();
+                }
+                result => result
+                    .with_context(|| format!("Failed to remove {:?}", parent.path.join(&name)))?,
+            }
+            continue;
+        }
+
+        let dir = parent
+            .open_child(&name)
+            .with_context(|| format!("Failed to read {:?}", parent.path.join(&name)))?;
+        let dir = Rc::new(dir);
+        stack.push((parent, name, Some(Rc::clone(&dir))));
+        // A directory's files are unlinked together once it has been read
+        let mut files = Vec::new();
+        let names = dir
+            .names()
+            .with_context(|| format!("Failed to read {:?}", dir.path))?;
+        for name in names {
+            let entry_path = dir.path.join(&name);
+            if exclude::is_excluded(&entry_path) {
+                protection::record(&entry_path, Protection::Excluded);
+                dir.keep();
+                continue;
+            }
+            // Looked up by path, which only the counts rely on: what is
+            // opened and unlinked is always found through `dir`
+            let metadata = fs::symlink_metadata(&entry_path)
+                .with_context(|| format!("Failed to stat {:?}", entry_path))?;
+            if protection::is_recent(&metadata) {
+                protection::record(&entry_path, Protection::Recent);
+                dir.keep();
+                continue;
+            }
+            if metadata.is_dir() {
+                stack.push((Rc::clone(&dir), name, None));
+            } else {
+                files.push((name, metadata));
+            }
+        }
+
+        // Inode order follows the on-disk layout, sparing a spinning disk
+        // most of the seeks
+        #[cfg(unix)]
+        if storage == concurrency::StorageKind::Hdd {
+            use std::os::unix::fs::MetadataExt;
+            files.sort_unstable_by_key(|(_, metadata)| metadata.ino());
+        }
+
+        let mut removed = |metadata: &fs::Metadata| {
+            deferred::record(metadata);
+            progress.files_done += 1;
+            progress.bytes_done += metadata.len();
+            if last_event.elapsed() >= PROGRESS_INTERVAL {
+                on_progress(progress);
+                last_event = Instant::now();
+            }
+        };
+        let dir: &TreeDir = &dir;
+        if threads > 1 && files.len() >= PARALLEL_MIN_FILES {
+            let mut first_error = None;
+            Pipeline::new().threads(threads).run(
+                |tx| {
+                    for file in files {
+                        if tx.send(file).is_err() {
+                            break;
+                        }
+                    }
+                },
+                // Each worker stops between files, like the loop below
+                |(name, metadata)| {
+                    Some(
+                        cancel::check()
+                            .and_then(|()| remove_tree_file(dir, &name))
+                            .map(|()| metadata),
+                    )
+                },
+                |result| match result {
+                    Ok(metadata) => removed(&metadata),
+                    Err(e) => {
+                        first_error.get_or_insert(e);
+                    }
+                },
+            );
+            if let Some(e) = first_error {
+                return Err(e);
+            }
+            cancel::check()?;
+        } else {
+            for (name, metadata) in files {
+                // Stop between files, never halfway through one
+                cancel::check()?;
+                remove_tree_file(dir, &name)?;
+                removed(&metadata);
+            }
+        }
+    }
+
+    // Files created while we were deleting can push the count past the total
+    progress.files_total = progress.files_total.max(progress.files_done);
+    progress.bytes_total = progress.bytes_total.max(progress.bytes_done);
+    on_progress(progress);
+    Ok(())
+}
+
+/// Remove the file `name` in `dir`, logging it to the audit trail first
+fn remove_tree_file(dir: &TreeDir, name: &OsStr) -> Result<()> {
+    let path = dir.path.join(name);
+    audit::record(&path)?;
+    dir.remove(name, false)
+        .with_context(|| format!("Failed to remove {:?}", path))
+}
+
+/// Number of non-directory entries and their total size below a directory
+fn count_tree(path: &Path) -> (u64, u64) {
+    let mut files = 0;
+    let mut bytes = 0;
+    let mut stack = vec![path.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if exclude::is_excluded(&entry.path()) {
+                continue;
+            }
+            let Ok(metadata) = entry.path().symlink_metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                stack.push(entry.path());
+            } else if !protection::is_recent(&metadata) {
+                files += 1;
+                bytes += metadata.len();
+            }
+        }
+    }
+
+    (files, bytes)
+}
+
+/// A directory of a tree being deleted, open so that what is in it is
+/// opened and unlinked relative to it rather than by path
+#[cfg(unix)]
+struct TreeDir {
+    fd: std::os::fd::OwnedFd,
+    /// Where the directory was when opened, for messages and exclusions
+    path: PathBuf,
+    /// Whether something in the directory is being kept
+    kept: AtomicBool,
+}
+
+#[cfg(unix)]
+impl TreeDir {
+    /// Open the directory at `path`, following symlinks to it
+    fn open(path: &Path) -> io::Result<Self> {
+        use std::os::unix::fs::OpenOptionsExt;
+
+        let at = if path.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            path
+        };
+        let file = fs::OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_DIRECTORY)
+            .open(at)?;
+        Ok(Self {
+            fd: file.into(),
+            path: path.to_path_buf(),
+            kept: AtomicBool::new(false),
+        })
+    }
+
+    /// Open the subdirectory `name`, failing if it is a symlink
+    fn open_child(&self, name: &OsStr) -> io::Result<Self> {
+        use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+        let c_name = c_name(name)?;
+        // SAFETY: the descriptor is open and the name is NUL-terminated
+        let fd = unsafe {
+            libc::openat(
+                self.fd.as_raw_fd(),
+                c_name.as_ptr(),
+                libc::O_RDONLY | libc::O_DIRECTORY | libc::O_NOFOLLOW | libc::O_CLOEXEC,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self {
+            // SAFETY: openat just returned this descriptor and nothing else owns it
+            fd: unsafe { OwnedFd::from_raw_fd(fd) },
+            path: self.path.join(name),
+            kept: AtomicBool::new(false),
+        })
+    }
+
+    /// The names of the entries in the directory
+    fn names(&self) -> io::Result<Vec<OsString>> {
+        use std::ffi::CStr;
+        use std::os::fd::IntoRawFd;
+        use std::os::unix::ffi::OsStrExt;
+
+        // The directory stream takes over the descriptor it reads, so it
+        // gets a copy
+        let fd = self.fd.try_clone()?.into_raw_fd();
+        // SAFETY: fd is an open directory descriptor nothing else owns
+        let stream = unsafe { libc::fdopendir(fd) };
+        if stream.is_null() {
+            let error = io::Error::last_os_error();
+            // SAFETY: fdopendir failed, so fd is still ours to close
+            unsafe { libc::close(fd) };
+            return Err(error);
+        }
+        let mut names = Vec::new();
+        loop {
+            // SAFETY: stream is open until closedir below
+            let entry = unsafe { libc::readdir(stream) };
+            if entry.is_null() {
+                break;
+            }
+            // SAFETY: readdir returned a valid entry with a NUL-terminated name
+            let name = unsafe { CStr::from_ptr((*entry).d_name.as_ptr()) }.to_bytes();
+            if name != b"." && name != b".." {
+                names.push(OsStr::from_bytes(name).to_os_string());
+            }
+        }
+        // SAFETY: stream came from fdopendir and is closed only here
+        unsafe { libc::closedir(stream) };
+        Ok(names)
+    }
+
+    /// Unlink `name`, a directory if `is_dir`. A symlink is removed itself.
+    fn remove(&self, name: &OsStr, is_dir: bool) -> io::Result<()> {
+        use std::os::fd::AsRawFd;
+
+        let c_name = c_name(name)?;
+        let flags = if is_dir { libc::AT_REMOVEDIR } else { 0 };
+        // SAFETY: the descriptor is open and the name is NUL-terminated
+        if unsafe { libc::unlinkat(self.fd.as_raw_fd(), c_name.as_ptr(), flags) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+impl TreeDir {
+    /// Leave the directory in place, as something in it is kept
+    fn keep(&self) {
+        self.kept.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether [`TreeDir::keep`] was called
+    fn holds_kept(&self) -> bool {
+        self.kept.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(unix)]
+fn c_name(name: &OsStr) -> io::Result<std::ffi::CString> {
+    use std::os::unix::ffi::OsStrExt;
+    std::ffi::CString::new(name.as_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
+
+/// Elsewhere the tree is walked by path
+#[cfg(not(unix))]
+struct TreeDir {
+    path: PathBuf,
+    kept: AtomicBool,
+}
+
+#[cfg(not(unix))]
+impl TreeDir {
+    fn open(path: &Path) -> io::Result<Self> {
+        Ok(Self {
+            path: path.to_path_buf(),
+            kept: AtomicBool::new(false),
+        })
+    }
+
+    fn open_child(&self, name: &OsStr) -> io::Result<Self> {
+        Ok(Self {
+            path: self.path.join(name),
+            kept: AtomicBool::new(false),
+        })
+    }
+
+    fn names(&self) -> io::Result<Vec<OsString>> {
+        fs::read_dir(&self.path)?
+            .map(|entry| entry.map(|entry| entry.file_name()))
+            .collect()
+    }
+
+    fn remove(&self, name: &OsStr, is_dir: bool) -> io::Result<()> {
+        let path = self.path.join(name);
+        if is_dir {
+            fs::remove_dir(path)
+        } else {
+            fs::remove_file(path)
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//! Duplicate file finder. Files are first grouped by size, then only the
+//! files sharing a size are hashed, across a [`Pipeline`] worker pool.
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::Read;
+#[cfg(unix)]
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+use crate::pipeline::Pipeline;
+
+/// Files with identical contents
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DuplicateGroup {
+    /// Size of each copy in bytes
+    pub size: u64,
+    /// Hex SHA-256 of the contents
+    pub hash: String,
+    /// Every copy, sorted
+    pub paths: Vec<PathBuf>,
+}
+
+impl DuplicateGroup {
+    /// Bytes that would be freed by keeping only one copy
+    pub fn wasted(&self) -> u64 {
+        self.size * (self.paths.len() as u64).saturating_sub(1)
+    }
+}
+
+/// Find files with identical contents under `roots`, largest waste first.
+/// Empty files, symlinks and extra hard links to the same inode are ignored.
+pub fn find_duplicates(roots: &[PathBuf], pipeline: &Pipeline) -> Vec<DuplicateGroup> {
+    let mut by_hash: HashMap<(u64, String), Vec<PathBuf>> = HashMap::new();
+
+    pipeline.run(
+        |tx| {
+            for (size, paths) in files_by_size(roots) {
+                if paths.len() < 2 {
+                    continue;
+                }
+                for path in paths {
+                    if tx.send((size, path)).is_err() {
+                        return;
+                    }
+                }
+            }
+        },
+        |(size, path): (u64, PathBuf)| hash_file(&path).map(|hash| (size, hash, path)),
+        |(size, hash, path)| by_hash.entry((size, hash)).or_default().push(path),
+    );
+
+    let mut groups: Vec<DuplicateGroup> = by_hash
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|((size, hash), mut paths)| {
+            paths.sort();
+            DuplicateGroup { size, hash, paths }
+        })
+        .collect();
+    groups.sort_by(|a, b| b.wasted().cmp(&a.wasted()).then(a.paths.cmp(&b.paths)));
+    groups
+}
+
+/// Walk the roots and bucket regular files by size
+fn files_by_size(roots: &[PathBuf]) -> HashMap<u64, Vec<PathBuf>> {
+    let mut seen = HashSet::new();
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    let mut stack: Vec<PathBuf> = roots.to_vec();
+
+    while let Some(path) = stack.pop() {
+        let Ok(metadata) = fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if metadata.is_dir() {
+            if let Ok(entries) = fs::read_dir(&path) {
+                stack.extend(entries.flatten().map(|e| e.path()));
+            }
+        } else if metadata.is_file()
+            && metadata.len() > 0
+            && file_id(&metadata).is_none_or(|id| seen.insert(id))
+        {
+            by_size.entry(metadata.len()).or_default().push(path);
+        }
+    }
+
+    by_size
+}
+
+/// (device, inode) identifying a file across hard links
+#[cfg(unix)]
+fn file_id(metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Hex SHA-256 of a file, or `None` if it can't be read
+pub fn hash_file(path: &Path) -> Option<String> {
+    let mut file = File::open(path).ok()?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        match file.read(&mut buf).ok()? {
+            0 => break,
+            n => hasher.update(&buf[..n]),
+        }
+    }
+    Some(
+        hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect(),
+    )
+}
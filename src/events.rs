@@ -1,4 +1,5 @@
-use std::sync::mpsc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
 use std::thread;
 use std::time::{Duration, Instant};
 
@@ -17,6 +18,10 @@ pub struct Events {
     rx: mpsc::Receiver<Event>,
     /// To make sure only one instance of Events exists at a time
     _tx: mpsc::Sender<Event>,
+    /// Set to stop reading the terminal, while another program uses it
+    paused: Arc<AtomicBool>,
+    /// Set by the reader once it no longer reads the terminal
+    idle: Arc<AtomicBool>,
 }
 
 impl Events {
@@ -25,10 +30,20 @@ impl Events {
         let (tx, rx) = mpsc::channel();
         let event_tx = tx.clone();
         let tick_rate = config.tick_rate;
+        let paused = Arc::new(AtomicBool::new(false));
+        let idle = Arc::new(AtomicBool::new(false));
+        let (reader_paused, reader_idle) = (paused.clone(), idle.clone());
 
         thread::spawn(move || {
             let mut last_tick = Instant::now();
             loop {
+                if reader_paused.load(Ordering::SeqCst) {
+                    reader_idle.store(true, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(10));
+                    continue;
+                }
+                reader_idle.store(false, Ordering::SeqCst);
+
                 // Poll for events with a timeout matching tick rate
                 let timeout = tick_rate
                     .checked_sub(last_tick.elapsed())
@@ -53,7 +68,29 @@ impl Events {
             }
         });
 
-        Self { rx, _tx: tx }
+        Self {
+            rx,
+            _tx: tx,
+            paused,
+            idle,
+        }
+    }
+
+    /// Stop reading the terminal, returning once the reader has stopped, so
+    /// a program run in the foreground gets every key
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+        while !self.idle.load(Ordering::SeqCst) {
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    /// Read the terminal again after [`Self::pause`], dropping what arrived
+    /// for the TUI meanwhile
+    pub fn resume(&self) {
+        while self.rx.try_recv().is_ok() {}
+        self.idle.store(false, Ordering::SeqCst);
+        self.paused.store(false, Ordering::SeqCst);
     }
 
     /// Attempts to read an event.
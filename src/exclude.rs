@@ -0,0 +1,137 @@
+//! Paths that are never cleaned, given as globs in the config file
+//! (`exclude = ["~/.cache/keep/**", "*.important"]`). Every deletion
+//! cleansys makes itself, through the deleter or with sudo, checks them and
+//! skips matching paths, and package managers aren't run on a cache holding
+//! an excluded file. Other commands cleaners run (`journalctl`, `docker`,
+//! ...) don't know about them. Paths excluded from the TUI results screen
+//! are appended to the config file for the next run.
+//!
+//! Patterns are matched per path component: `*` and `?` stay within one
+//! component and `**` spans any number of them. A pattern matching a
+//! directory excludes everything below it. Patterns starting with `~/` are
+//! relative to the home directory; patterns without a leading `/` match at
+//! any depth, like in `.gitignore`.
+
+use once_cell::sync::Lazy;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::cleaners::custom::NOT_WHOLE;
+use crate::filesystem::{home_dir, root_path, sandbox_root};
+
+static PATTERNS: Lazy<Mutex<Vec<String>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+fn patterns_mut() -> std::sync::MutexGuard<'static, Vec<String>> {
+    PATTERNS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Set the exclusion patterns, from the config file
+pub fn set_patterns(patterns: Vec<String>) {
+    *patterns_mut() = patterns;
+}
+
+/// Add a pattern for the rest of this session
+pub fn add_pattern(pattern: impl Into<String>) {
+    let pattern = pattern.into();
+    let mut patterns = patterns_mut();
+    if !patterns.contains(&pattern) {
+        patterns.push(pattern);
+    }
+}
+
+/// The exclusion patterns in effect
+pub fn patterns() -> Vec<String> {
+    patterns_mut().clone()
+}
+
+/// Whether `path` matches one of the exclusion patterns
+pub fn is_excluded(path: &Path) -> bool {
+    let patterns = patterns_mut();
+    !patterns.is_empty()
+        && patterns
+            .iter()
+            .any(|pattern| glob_matches(&expand(pattern), path))
+}
+
+/// A pattern as an absolute glob: `~/` becomes the home directory, absolute
+/// paths move into the `CLEANSYS_ROOT` sandbox, and anything else may match
+/// at any depth
+pub fn expand(pattern: &str) -> String {
+    if let Some(rest) = pattern.strip_prefix("~/") {
+        match home_dir() {
+            Ok(home) => format!("{}/{}", home.to_string_lossy().trim_end_matches('/'), rest),
+            Err(_) => pattern.to_string(),
+        }
+    } else if pattern.starts_with('/') {
+        root_path(pattern).to_string_lossy().to_string()
+    } else {
+        format!("**/{}", pattern)
+    }
+}
+
+/// Whether an absolute glob matches `path` or one of its ancestors
+pub fn glob_matches(pattern: &str, path: &Path) -> bool {
+    let pattern: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+    let path: Vec<String> = path
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect();
+    let path: Vec<&str> = path.iter().map(String::as_str).collect();
+    matches_components(&pattern, &path)
+}
+
+fn matches_components(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        // The pattern matched an ancestor (or the path itself)
+        None => true,
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| matches_components(rest, &path[skip..])),
+        Some((first, rest)) => match path.split_first() {
+            Some((name, path_rest)) => {
+                let pattern: Vec<char> = first.chars().collect();
+                let name: Vec<char> = name.chars().collect();
+                matches_name(&pattern, &name) && matches_components(rest, path_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+/// `*` and `?` wildcards within one path component
+fn matches_name(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|skip| matches_name(rest, &name[skip..])),
+        Some(('?', rest)) => !name.is_empty() && matches_name(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && matches_name(rest, &name[1..]),
+    }
+}
+
+/// Whether a pattern would exclude everything below `/`, the home
+/// directory or another directory that holds more than one application's
+/// files, such as `/var` or `~/.config`
+pub fn covers_too_much(pattern: &str) -> bool {
+    let pattern = pattern.trim_end_matches('/');
+    NOT_WHOLE.contains(&pattern)
+        || pattern
+            .split('/')
+            .all(|component| component == "~" || component.chars().all(|c| matches!(c, '*' | '?')))
+}
+
+/// The pattern excluding exactly `path`, written with `~/` for paths in the
+/// home directory and without the sandbox root when sandboxed
+pub fn pattern_for(path: &Path) -> String {
+    if let Ok(rest) = home_dir().and_then(|home| Ok(path.strip_prefix(home)?.to_path_buf())) {
+        return format!("~/{}", rest.to_string_lossy());
+    }
+    let path = match sandbox_root() {
+        Some(root) => match path.strip_prefix(&root) {
+            Ok(rest) => PathBuf::from("/").join(rest),
+            Err(_) => path.to_path_buf(),
+        },
+        None => path.to_path_buf(),
+    };
+    path.to_string_lossy().to_string()
+}
@@ -0,0 +1,194 @@
+//! Filesystem access used by the cleaners.
+//!
+//! All removals go through the [`FileSystem`] trait, which tests can swap
+//! for a fake with [`set_filesystem`], and absolute system paths go through
+//! [`root_path`]. Setting `CLEANSYS_ROOT` rebases every
+//! path (including `$HOME`, which becomes `$CLEANSYS_ROOT/home`) into a fake
+//! tree, so tests can run any cleaner against a tempdir without root.
+
+use anyhow::{Context, Result};
+use directories::BaseDirs;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::utils::get_size;
+
+/// Environment variable that sandboxes all cleaner paths under a directory
+pub const ROOT_ENV: &str = "CLEANSYS_ROOT";
+
+/// Operations cleaners perform on files and directories
+pub trait FileSystem: Send + Sync {
+    /// Whether the path exists
+    fn exists(&self, path: &Path) -> bool;
+    /// Disk usage of a file or directory tree in bytes
+    fn size(&self, path: &Path) -> Result<u64>;
+    /// Entries directly inside a directory
+    fn read_dir(&self, path: &Path) -> Result<Vec<PathBuf>>;
+    /// Remove a single file
+    fn remove_file(&self, path: &Path) -> Result<u64>;
+    /// Remove a file somewhere below `root` without following symlinks
+    /// on the way down from `root`
+    fn remove_file_below(&self, root: &Path, path: &Path) -> Result<u64>;
+    /// Remove a directory and everything below it
+    fn remove_dir_all(&self, path: &Path) -> Result<u64>;
+    /// Create a directory and any missing parents
+    fn create_dir_all(&self, path: &Path) -> Result<()>;
+}
+
+/// The real filesystem
+pub struct RealFileSystem;
+
+impl FileSystem for RealFileSystem {
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn size(&self, path: &Path) -> Result<u64> {
+        get_size(path.to_str().unwrap_or(""))
+    }
+
+    fn read_dir(&self, path: &Path) -> Result<Vec<PathBuf>> {
+        let entries =
+            fs::read_dir(path).with_context(|| format!("Failed to read directory {:?}", path))?;
+        Ok(entries.flatten().map(|e| e.path()).collect())
+    }
+
+    fn remove_file(&self, path: &Path) -> Result<u64> {
+        crate::deleter::remove_file(path)
+    }
+
+    fn remove_file_below(&self, root: &Path, path: &Path) -> Result<u64> {
+        crate::deleter::remove_file_below(root, path)
+    }
+
+    fn remove_dir_all(&self, path: &Path) -> Result<u64> {
+        crate::deleter::remove_dir_all(path)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> Result<()> {
+        fs::create_dir_all(path).with_context(|| format!("Failed to create {:?}", path))
+    }
+}
+
+/// The filesystem installed with [`set_filesystem`], if any
+static INSTALLED: Mutex<Option<&'static dyn FileSystem>> = Mutex::new(None);
+
+/// Have cleaners operate on `fs` instead of the real filesystem until
+/// [`reset_filesystem`]
+pub fn set_filesystem(fs: &'static dyn FileSystem) {
+    *INSTALLED.lock().unwrap_or_else(|e| e.into_inner()) = Some(fs);
+}
+
+/// Have cleaners operate on the real filesystem again
+pub fn reset_filesystem() {
+    *INSTALLED.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// The filesystem cleaners operate on
+pub fn filesystem() -> &'static dyn FileSystem {
+    INSTALLED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .unwrap_or(&RealFileSystem)
+}
+
+/// The sandbox root from `CLEANSYS_ROOT`, if set
+pub fn sandbox_root() -> Option<PathBuf> {
+    std::env::var_os(ROOT_ENV)
+        .filter(|root| !root.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Whether cleaners are running against a sandbox instead of the real system
+pub fn is_sandboxed() -> bool {
+    sandbox_root().is_some()
+}
+
+/// Map an absolute system path into the sandbox, or return it unchanged
+/// when not sandboxed. `root_path("/var/log")` becomes `$CLEANSYS_ROOT/var/log`.
+pub fn root_path(path: impl AsRef<Path>) -> PathBuf {
+    let path = path.as_ref();
+    match sandbox_root() {
+        Some(root) => root.join(path.strip_prefix("/").unwrap_or(path)),
+        None => path.to_path_buf(),
+    }
+}
+
+/// The home directory of the user who ran sudo, when running as root with
+/// `SUDO_USER` set to someone else. `lookup` finds a user's home; a user it
+/// can't find is an error, so user cleaners never fall back to root's home.
+pub fn sudo_user_home(
+    sudo_user: Option<&str>,
+    is_root: bool,
+    lookup: impl FnOnce(&str) -> Option<PathBuf>,
+) -> Result<Option<PathBuf>> {
+    let Some(user) = sudo_user.filter(|user| is_root && !user.is_empty() && *user != "root") else {
+        return Ok(None);
+    };
+    lookup(user).map(Some).with_context(|| {
+        format!(
+            "Running under sudo, but the home directory of {} (SUDO_USER) is unknown; \
+             run user cleaners without sudo",
+            user
+        )
+    })
+}
+
+/// `dir` moved from below `from_home` to below `home`. Directories outside
+/// `from_home`, like an absolute `XDG_CACHE_HOME`, stay where they are.
+pub fn rebase_dir(dir: &Path, from_home: &Path, home: &Path) -> PathBuf {
+    dir.strip_prefix(from_home)
+        .map_or_else(|_| dir.to_path_buf(), |relative| home.join(relative))
+}
+
+#[cfg(unix)]
+fn invoking_user_home() -> Result<Option<PathBuf>> {
+    use users::os::unix::UserExt;
+
+    sudo_user_home(
+        std::env::var("SUDO_USER").ok().as_deref(),
+        users::get_effective_uid() == 0,
+        |name| users::get_user_by_name(name).map(|user| user.home_dir().to_path_buf()),
+    )
+}
+
+#[cfg(not(unix))]
+fn invoking_user_home() -> Result<Option<PathBuf>> {
+    Ok(None)
+}
+
+/// One of the user's base directories. Under sudo, BaseDirs describes
+/// root's home, so the directory is moved into the invoking user's home.
+fn base_dir(dir: impl FnOnce(&BaseDirs) -> &Path) -> Result<PathBuf> {
+    let base_dirs = BaseDirs::new().context("Failed to get base directories")?;
+    let path = dir(&base_dirs);
+    Ok(match invoking_user_home()? {
+        Some(home) => rebase_dir(path, base_dirs.home_dir(), &home),
+        None => path.to_path_buf(),
+    })
+}
+
+/// The user's home directory, `$CLEANSYS_ROOT/home` when sandboxed
+pub fn home_dir() -> Result<PathBuf> {
+    if let Some(root) = sandbox_root() {
+        return Ok(root.join("home"));
+    }
+    base_dir(BaseDirs::home_dir)
+}
+
+/// The user's cache directory, `$CLEANSYS_ROOT/home/.cache` when sandboxed
+pub fn cache_dir() -> Result<PathBuf> {
+    if is_sandboxed() {
+        return Ok(home_dir()?.join(".cache"));
+    }
+    base_dir(BaseDirs::cache_dir)
+}
+
+/// The user's local data directory, `$CLEANSYS_ROOT/home/.local/share` when sandboxed
+pub fn data_dir() -> Result<PathBuf> {
+    if is_sandboxed() {
+        return Ok(home_dir()?.join(".local/share"));
+    }
+    base_dir(BaseDirs::data_local_dir)
+}
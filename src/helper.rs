@@ -0,0 +1,479 @@
+//! Root helper for system cleaners. `cleansys helper` runs as a systemd
+//! service (see `contrib/systemd`) listening on [`SOCKET_PATH`], and the TUI
+//! hands it the system cleaners of a run instead of going through sudo, so
+//! scheduled runs at night don't fail on expired credentials.
+//!
+//! Each connection carries one request and one reply, both a line of JSON.
+//! The helper only runs cleaners of the root groups it registered itself,
+//! looked up by group and cleaner name, and only for root or members of
+//! [`HELPER_GROUP`]. What the cleaner removed, failed on and left for lack
+//! of permission travels back in the reply and is recorded on the client
+//! as if the cleaner had run there. The client's exclusions, run limit and
+//! audit mode go along with each request, and a cleaner run through the
+//! helper follows them where they are stricter than the helper's own
+//! config. An unprivileged client can't loosen what root allows.
+
+#[cfg(unix)]
+use anyhow::Context;
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+#[cfg(unix)]
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+#[cfg(unix)]
+use crate::cleaners::cleaned::{self, CleanedItemType};
+#[cfg(unix)]
+use crate::cleaners::groups;
+use crate::cleaners::log_backends::{self, JournalVacuum};
+use crate::cleaners::{CleanerGroup, RiskLevel};
+#[cfg(unix)]
+use crate::deferred;
+use crate::limits::{self, RunLimit};
+use crate::protection::{self, Skipped};
+use crate::{audit, exclude, watchdog};
+
+/// Where the helper listens
+pub const SOCKET_PATH: &str = "/run/cleansys-helper.sock";
+
+/// Users in this group may ask the helper to run cleaners
+pub const HELPER_GROUP: &str = "cleansys";
+
+/// How long the helper waits for a client to send its request
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Requests longer than this are refused, in bytes
+pub const MAX_REQUEST_LEN: u64 = 64 * 1024;
+
+/// What a client asks the helper
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Request {
+    /// Whether the helper is there and accepts this client
+    Ping,
+    /// Run a system cleaner without confirmations
+    Run {
+        category: String,
+        cleaner: String,
+        /// Keep files changed more recently than this, in seconds
+        min_age_secs: Option<u64>,
+        #[serde(default)]
+        settings: Box<RunSettings>,
+    },
+}
+
+/// The client's settings a cleaner run through the helper follows
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunSettings {
+    /// Exclusion patterns, `~/` already expanded to the client's home
+    pub exclude: Vec<String>,
+    /// Caps of the run, zero for none
+    pub max_files: u64,
+    pub max_bytes: u64,
+    /// Files and bytes the run deleted before this cleaner
+    pub deleted: (u64, u64),
+    /// Whether to hash files before removing them, see [`crate::audit`]
+    pub audit: bool,
+    pub journal_vacuum: JournalVacuum,
+    pub journal_limit: Option<String>,
+}
+
+impl RunSettings {
+    /// The settings in effect in this process
+    pub fn current() -> Self {
+        let limit = limits::run_limit();
+        RunSettings {
+            exclude: exclude::patterns()
+                .into_iter()
+                .map(|pattern| {
+                    if pattern.starts_with("~/") {
+                        exclude::expand(&pattern)
+                    } else {
+                        pattern
+                    }
+                })
+                .collect(),
+            max_files: limit.max_files,
+            max_bytes: limit.max_bytes,
+            deleted: limits::deleted(),
+            audit: audit::is_enabled(),
+            journal_vacuum: log_backends::journal_vacuum(),
+            journal_limit: log_backends::journal_limit(),
+        }
+    }
+
+    /// Make these the settings of this process where they are stricter
+    /// than `own`, the helper's settings from its config file: the tighter
+    /// cap of the two, the exclusions of both, and auditing if either asks.
+    /// The journal is vacuumed as the helper's config says, whatever the
+    /// client asks. Every request brings its own settings, so nothing
+    /// carries over from one client to the next.
+    fn apply(&self, own: &RunSettings) {
+        let mut patterns = own.exclude.clone();
+        patterns.extend(self.exclude.iter().cloned());
+        exclude::set_patterns(patterns);
+        limits::set_run_limit(RunLimit {
+            max_files: tighter(own.max_files, self.max_files),
+            max_bytes: tighter(own.max_bytes, self.max_bytes),
+        });
+        limits::reset_run();
+        limits::record(self.deleted.0, self.deleted.1);
+        if self.audit || own.audit {
+            audit::capture();
+        } else {
+            audit::disable();
+        }
+        log_backends::set_journal_vacuum(own.journal_vacuum.clone());
+        log_backends::set_journal_limit(own.journal_limit.clone());
+    }
+}
+
+/// The tighter of two caps, where zero is none
+fn tighter(a: u64, b: u64) -> u64 {
+    match (a, b) {
+        (0, cap) | (cap, 0) => cap,
+        (a, b) => a.min(b),
+    }
+}
+
+/// The helper's own settings, as its config file set them before the
+/// first request
+fn own_settings() -> &'static RunSettings {
+    static OWN: OnceLock<RunSettings> = OnceLock::new();
+    OWN.get_or_init(RunSettings::current)
+}
+
+/// A path the cleaner removed, as recorded by [`cleaned::record`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemovedPath {
+    pub path: PathBuf,
+    pub bytes: u64,
+    pub is_dir: bool,
+}
+
+/// The helper's answer
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reply {
+    /// Why the request failed, if it did
+    pub error: Option<String>,
+    /// Bytes the cleaner freed
+    pub bytes: u64,
+    /// Part of `bytes` only freed after a reboot
+    pub deferred: u64,
+    pub removed: Vec<RemovedPath>,
+    /// Paths the cleaner couldn't remove, with why
+    pub failures: Vec<(PathBuf, String)>,
+    /// Paths left in place for lack of permission
+    pub denied: usize,
+    /// Files and bytes the cleaner unlinked, counted against the run limit
+    pub deleted: (u64, u64),
+    /// Paths left in place as protected or excluded
+    pub skipped: Vec<Skipped>,
+    /// Files hashed before removal, when the client audits
+    pub audited: Vec<audit::AuditEntry>,
+}
+
+impl Reply {
+    fn error(error: impl std::fmt::Display) -> Self {
+        Reply {
+            error: Some(error.to_string()),
+            ..Reply::default()
+        }
+    }
+}
+
+/// Answer `request` with the cleaners in `groups`. Only groups that need
+/// root are considered, whatever the client asks for, and high risk
+/// cleaners are refused since nobody confirms them over the socket.
+pub fn reply_to(request: &Request, groups: &[CleanerGroup]) -> Reply {
+    let Request::Run {
+        category,
+        cleaner,
+        min_age_secs,
+        settings,
+    } = request
+    else {
+        return Reply::default();
+    };
+    let found = groups
+        .iter()
+        .filter(|group| group.requires_root && group.name == category)
+        .flat_map(|group| &group.cleaners)
+        .find(|info| info.name == cleaner && info.is_available());
+    let Some(info) = found else {
+        return Reply::error(format!("The helper doesn't offer '{}'", cleaner));
+    };
+    if info.risk == RiskLevel::High {
+        return Reply::error(format!("'{}' is high risk, run it with sudo", cleaner));
+    }
+
+    take_records();
+    settings.apply(own_settings());
+    let before = limits::deleted();
+    protection::set_min_age(min_age_secs.map(Duration::from_secs));
+    let result = watchdog::run(info.name, || (info.function)(true));
+    protection::set_min_age(None);
+    let mut reply = take_records();
+    let after = limits::deleted();
+    reply.deleted = (
+        after.0.saturating_sub(before.0),
+        after.1.saturating_sub(before.1),
+    );
+    match result {
+        Ok(bytes) => {
+            reply.bytes = bytes;
+            reply.deferred = reply.deferred.min(bytes);
+        }
+        Err(e) => reply.error = Some(format!("{:#}", e)),
+    }
+    reply
+}
+
+/// What the cleaners recorded since the last call, as a reply
+#[cfg(unix)]
+fn take_records() -> Reply {
+    Reply {
+        deferred: deferred::take(),
+        removed: cleaned::take()
+            .into_iter()
+            .map(|entry| RemovedPath {
+                is_dir: entry.kind == CleanedItemType::Directory,
+                path: entry.path,
+                bytes: entry.bytes,
+            })
+            .collect(),
+        failures: cleaned::take_failures(),
+        denied: cleaned::take_denied(),
+        skipped: protection::take_skipped(),
+        audited: audit::take_captured(),
+        ..Reply::default()
+    }
+}
+
+#[cfg(not(unix))]
+fn take_records() -> Reply {
+    Reply::default()
+}
+
+/// Whether the helper is running and accepts this user
+#[cfg(unix)]
+pub fn available() -> bool {
+    Path::new(SOCKET_PATH).exists()
+        && request(Path::new(SOCKET_PATH), &Request::Ping).is_ok_and(|reply| reply.error.is_none())
+}
+
+#[cfg(not(unix))]
+pub fn available() -> bool {
+    false
+}
+
+/// Run a system cleaner through the helper, recording what it removed as
+/// the cleaner itself would have
+#[cfg(unix)]
+pub fn run_cleaner(category: &str, cleaner: &str, min_age: Option<Duration>) -> Result<u64> {
+    let reply = request(
+        Path::new(SOCKET_PATH),
+        &Request::Run {
+            category: category.to_string(),
+            cleaner: cleaner.to_string(),
+            min_age_secs: min_age.map(|age| age.as_secs()),
+            settings: Box::new(RunSettings::current()),
+        },
+    )?;
+    replay(reply)
+}
+
+#[cfg(not(unix))]
+pub fn run_cleaner(_category: &str, cleaner: &str, _min_age: Option<Duration>) -> Result<u64> {
+    bail!(
+        "The root helper isn't supported here, can't run '{}'",
+        cleaner
+    )
+}
+
+/// Record what a reply says the cleaner did, returning the bytes it freed
+#[cfg(unix)]
+pub fn replay(reply: Reply) -> Result<u64> {
+    for removed in &reply.removed {
+        cleaned::record_path(&removed.path, removed.bytes, removed.is_dir);
+    }
+    for (path, error) in &reply.failures {
+        cleaned::record_failure(path, error);
+    }
+    cleaned::add_denied(reply.denied);
+    deferred::add(reply.deferred);
+    for skipped in &reply.skipped {
+        protection::record(&skipped.path, skipped.protection);
+    }
+    limits::record(reply.deleted.0, reply.deleted.1);
+    for entry in reply.audited {
+        audit::append(entry)?;
+    }
+    match reply.error {
+        Some(error) => bail!("{}", error),
+        None => Ok(reply.bytes),
+    }
+}
+
+/// Send one request to the helper listening on `socket`
+#[cfg(unix)]
+pub fn request(socket: &Path, request: &Request) -> Result<Reply> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = UnixStream::connect(socket)
+        .with_context(|| format!("Can't reach the root helper at {:?}", socket))?;
+    let mut line = serde_json::to_string(request)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply)?;
+    if reply.is_empty() {
+        bail!("The root helper closed the connection without replying");
+    }
+    serde_json::from_str(&reply).context("Unreadable reply from the root helper")
+}
+
+/// Serve requests until the process is stopped, on the socket systemd
+/// passed in or else on [`SOCKET_PATH`]
+#[cfg(unix)]
+pub fn serve() -> Result<()> {
+    use log::{info, warn};
+
+    if !crate::utils::check_root() {
+        bail!("The root helper has to run as root");
+    }
+    // Taken before any client's settings replace them
+    own_settings();
+    let listener = listener()?;
+    info!("Root helper listening on {}", SOCKET_PATH);
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                warn!("Failed to accept a connection: {}", e);
+                continue;
+            }
+        };
+        if let Err(e) = serve_connection(stream, &groups()) {
+            warn!("Failed to serve a request: {:#}", e);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn serve() -> Result<()> {
+    bail!("The root helper needs Unix sockets")
+}
+
+/// The listening socket: from systemd socket activation when there is one
+#[cfg(unix)]
+fn listener() -> Result<std::os::unix::net::UnixListener> {
+    use std::os::fd::FromRawFd;
+    use std::os::unix::net::UnixListener;
+
+    /// The first socket systemd passes, see sd_listen_fds(3)
+    const SD_LISTEN_FDS_START: i32 = 3;
+
+    let pid = std::env::var("LISTEN_PID")
+        .ok()
+        .and_then(|pid| pid.parse().ok());
+    if pid == Some(std::process::id()) {
+        return Ok(unsafe { UnixListener::from_raw_fd(SD_LISTEN_FDS_START) });
+    }
+    let _ = std::fs::remove_file(SOCKET_PATH);
+    let listener = UnixListener::bind(SOCKET_PATH)
+        .with_context(|| format!("Failed to listen on {}", SOCKET_PATH))?;
+    Ok(listener)
+}
+
+/// Read one request from `stream`, answer it with the cleaners in `groups`
+/// and reply, refusing clients that aren't allowed
+#[cfg(unix)]
+pub fn serve_connection(
+    mut stream: std::os::unix::net::UnixStream,
+    groups: &[CleanerGroup],
+) -> Result<()> {
+    use std::io::{BufRead, BufReader, Write};
+
+    use std::io::Read;
+
+    // The helper serves one client at a time, so one that never finishes
+    // its request mustn't hold up the others
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+    let mut line = String::new();
+    BufReader::new((&stream).take(MAX_REQUEST_LEN)).read_line(&mut line)?;
+    let reply = match peer_uid(&stream) {
+        Some(uid) if allowed(uid) => match serde_json::from_str::<Request>(&line) {
+            Ok(request) => {
+                log::info!("uid {} asked for {:?}", uid, request);
+                reply_to(&request, groups)
+            }
+            Err(e) => Reply::error(format!("Invalid request: {}", e)),
+        },
+        Some(uid) => Reply::error(format!(
+            "uid {} is neither root nor in the {} group",
+            uid, HELPER_GROUP
+        )),
+        None => Reply::error("Can't tell who is asking"),
+    };
+    let mut reply = serde_json::to_string(&reply)?;
+    reply.push('\n');
+    stream.write_all(reply.as_bytes())?;
+    Ok(())
+}
+
+/// Whether `uid` may use the helper: root, the helper's own user (so it
+/// can be tried out unprivileged), or a member of [`HELPER_GROUP`]
+#[cfg(unix)]
+fn allowed(uid: u32) -> bool {
+    if uid == 0 || uid == users::get_effective_uid() {
+        return true;
+    }
+    let Some(user) = users::get_user_by_uid(uid) else {
+        return false;
+    };
+    users::get_user_groups(user.name(), user.primary_group_id())
+        .unwrap_or_default()
+        .iter()
+        .any(|group| group.name() == HELPER_GROUP)
+}
+
+/// The user on the other end of `stream`
+#[cfg(target_os = "linux")]
+fn peer_uid(stream: &std::os::unix::net::UnixStream) -> Option<u32> {
+    use std::os::fd::AsRawFd;
+
+    let mut cred = libc::ucred {
+        pid: 0,
+        uid: 0,
+        gid: 0,
+    };
+    let mut len = std::mem::size_of::<libc::ucred>() as libc::socklen_t;
+    let result = unsafe {
+        libc::getsockopt(
+            stream.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_PEERCRED,
+            &mut cred as *mut libc::ucred as *mut libc::c_void,
+            &mut len,
+        )
+    };
+    (result == 0).then_some(cred.uid)
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn peer_uid(stream: &std::os::unix::net::UnixStream) -> Option<u32> {
+    use std::os::fd::AsRawFd;
+
+    let mut uid = 0;
+    let mut gid = 0;
+    let result = unsafe { libc::getpeereid(stream.as_raw_fd(), &mut uid, &mut gid) };
+    (result == 0).then_some(uid)
+}
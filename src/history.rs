@@ -0,0 +1,392 @@
+//! History of cleaning runs, kept in `~/.local/state/cleansys/history.jsonl`,
+//! and the comparison of a run with the one before it: which cleaners freed
+//! more or less, which kinds of errors are new, and how the total moved.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::paths;
+use crate::privileges;
+use crate::utils::{format_size, local_time};
+
+/// Runs kept in the history; older ones are dropped
+pub const HISTORY_LIMIT: usize = 100;
+
+/// Changes smaller than this are not worth mentioning (1 MB)
+pub const NOTABLE_CHANGE: u64 = 1024 * 1024;
+
+/// What one cleaner did in a run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CleanerRun {
+    pub name: String,
+    pub bytes: u64,
+    /// Part of `bytes` only freed after a reboot, see [`crate::deferred`]
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub deferred: u64,
+    /// The error it failed with, if it did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Paths it couldn't remove although it succeeded with others
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub failed_paths: usize,
+}
+
+fn is_zero<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+impl CleanerRun {
+    pub fn succeeded(name: impl Into<String>, bytes: u64) -> Self {
+        Self {
+            name: name.into(),
+            bytes,
+            deferred: 0,
+            error: None,
+            failed_paths: 0,
+        }
+    }
+
+    /// Note that `bytes` of the freed space only come free after a reboot
+    pub fn with_deferred(mut self, bytes: u64) -> Self {
+        self.deferred = bytes.min(self.bytes);
+        self
+    }
+
+    /// Note that `count` paths couldn't be removed
+    pub fn with_failed_paths(mut self, count: usize) -> Self {
+        self.failed_paths = count;
+        self
+    }
+
+    /// Whether it succeeded but couldn't remove everything
+    pub fn is_partial(&self) -> bool {
+        self.error.is_none() && self.failed_paths > 0
+    }
+
+    pub fn failed(name: impl Into<String>, error: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            bytes: 0,
+            deferred: 0,
+            error: Some(error.into()),
+            failed_paths: 0,
+        }
+    }
+}
+
+/// One recorded run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// Assigned when the run is recorded, counting up from 1
+    pub id: u64,
+    /// Local time the run was recorded, `YYYY-MM-DD hh:mm:ss`
+    pub time: String,
+    pub cleaners: Vec<CleanerRun>,
+    /// The quarantine run its files were moved to, when it quarantined
+    /// them instead of deleting them, see [`crate::quarantine`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quarantine: Option<String>,
+}
+
+impl RunRecord {
+    /// A run that finished now, not yet recorded
+    pub fn new(cleaners: Vec<CleanerRun>) -> Self {
+        Self {
+            id: 0,
+            time: local_timestamp(),
+            cleaners,
+            quarantine: None,
+        }
+    }
+
+    /// Note that the run moved its files to the quarantine run `name`
+    pub fn with_quarantine(mut self, name: impl Into<String>) -> Self {
+        self.quarantine = Some(name.into());
+        self
+    }
+
+    /// Bytes freed by all cleaners
+    pub fn total(&self) -> u64 {
+        self.cleaners.iter().map(|c| c.bytes).sum()
+    }
+
+    /// Part of [`Self::total`] only freed after a reboot
+    pub fn deferred(&self) -> u64 {
+        self.cleaners.iter().map(|c| c.deferred).sum()
+    }
+
+    fn cleaner(&self, name: &str) -> Option<&CleanerRun> {
+        self.cleaners.iter().find(|c| c.name == name)
+    }
+}
+
+fn local_timestamp() -> String {
+    format_timestamp(&local_time().unwrap_or_else(|| unsafe { std::mem::zeroed() }))
+}
+
+/// A local time as recorded in the history, `YYYY-MM-DD hh:mm:ss`
+pub fn format_timestamp(tm: &libc::tm) -> String {
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec
+    )
+}
+
+/// The run history file
+#[derive(Debug, Clone)]
+pub struct History {
+    path: PathBuf,
+}
+
+impl History {
+    /// The history in the user's state directory
+    pub fn open() -> Result<Self> {
+        Ok(Self::at(&paths::state_dir()?.join("history.jsonl")))
+    }
+
+    /// A history kept in a specific file
+    pub fn at(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Every recorded run, oldest first. Lines that can't be read are
+    /// skipped rather than losing the whole history.
+    pub fn load(&self) -> Result<Vec<RunRecord>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let contents = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read history {:?}", self.path))?;
+        Ok(contents
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    /// Record a run, giving it the next id. Returns it as recorded. Written
+    /// as the user who ran sudo.
+    pub fn record(&self, run: RunRecord) -> Result<RunRecord> {
+        privileges::as_user(|| self.append(run))
+    }
+
+    fn append(&self, mut run: RunRecord) -> Result<RunRecord> {
+        let mut runs = self.load()?;
+        run.id = runs.last().map_or(1, |last| last.id + 1);
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+        }
+        if runs.len() >= HISTORY_LIMIT {
+            // Rewrite without the oldest runs
+            runs.push(run.clone());
+            let keep = &runs[runs.len() - HISTORY_LIMIT..];
+            let mut contents = String::new();
+            for record in keep {
+                contents.push_str(&serde_json::to_string(record)?);
+                contents.push('\n');
+            }
+            fs::write(&self.path, contents)
+                .with_context(|| format!("Failed to write history {:?}", self.path))?;
+        } else {
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)
+                .with_context(|| format!("Failed to open history {:?}", self.path))?;
+            writeln!(file, "{}", serde_json::to_string(&run)?)?;
+        }
+        Ok(run)
+    }
+
+    /// The run with this id
+    pub fn get(&self, id: u64) -> Result<Option<RunRecord>> {
+        Ok(self.load()?.into_iter().find(|run| run.id == id))
+    }
+
+    /// The run recorded just before the run with this id
+    pub fn previous(&self, id: u64) -> Result<Option<RunRecord>> {
+        Ok(self
+            .load()?
+            .into_iter()
+            .take_while(|run| run.id != id)
+            .last())
+    }
+}
+
+/// Broad kinds of cleaner errors, to tell new problems from old ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ErrorCategory {
+    PermissionDenied,
+    RequiresRoot,
+    TimedOut,
+    Protected,
+    NotFound,
+    Cancelled,
+    Other,
+}
+
+impl ErrorCategory {
+    /// The category of an error message
+    pub fn of(error: &str) -> Self {
+        let error = error.to_lowercase();
+        if error.contains("timed out") {
+            ErrorCategory::TimedOut
+        } else if error.contains("sudo") || error.contains("root") {
+            ErrorCategory::RequiresRoot
+        } else if error.contains("permission denied") || error.contains("not permitted") {
+            ErrorCategory::PermissionDenied
+        } else if error.contains("skipped") || error.contains("read-only") {
+            ErrorCategory::Protected
+        } else if error.contains("not found") || error.contains("not installed") {
+            ErrorCategory::NotFound
+        } else if error.contains("cancel") {
+            ErrorCategory::Cancelled
+        } else {
+            ErrorCategory::Other
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ErrorCategory::PermissionDenied => "permission denied",
+            ErrorCategory::RequiresRoot => "requires root",
+            ErrorCategory::TimedOut => "timed out",
+            ErrorCategory::Protected => "protected paths",
+            ErrorCategory::NotFound => "missing tools",
+            ErrorCategory::Cancelled => "cancelled",
+            ErrorCategory::Other => "other errors",
+        }
+    }
+}
+
+/// How much one cleaner freed in two runs; `None` where it didn't run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CleanerChange {
+    pub name: String,
+    pub previous: Option<u64>,
+    pub current: Option<u64>,
+}
+
+impl CleanerChange {
+    /// Bytes freed this time compared with last time
+    pub fn change(&self) -> i64 {
+        self.current.unwrap_or(0) as i64 - self.previous.unwrap_or(0) as i64
+    }
+}
+
+/// A run compared with the one before it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunDiff {
+    pub previous_id: u64,
+    pub previous_total: u64,
+    pub current_total: u64,
+    /// Cleaners in either run whose freed size changed notably, biggest
+    /// change first
+    pub changes: Vec<CleanerChange>,
+    /// Kinds of errors this run had and the previous one didn't
+    pub new_errors: Vec<ErrorCategory>,
+}
+
+impl RunDiff {
+    /// Compare `current` with `previous`
+    pub fn between(previous: &RunRecord, current: &RunRecord) -> Self {
+        let mut names: Vec<&str> = current.cleaners.iter().map(|c| c.name.as_str()).collect();
+        for cleaner in &previous.cleaners {
+            if !names.contains(&cleaner.name.as_str()) {
+                names.push(&cleaner.name);
+            }
+        }
+
+        let mut changes: Vec<CleanerChange> = names
+            .into_iter()
+            .map(|name| CleanerChange {
+                name: name.to_string(),
+                previous: previous.cleaner(name).map(|c| c.bytes),
+                current: current.cleaner(name).map(|c| c.bytes),
+            })
+            .filter(|change| change.change().unsigned_abs() >= NOTABLE_CHANGE)
+            .collect();
+        changes.sort_by_key(|change| std::cmp::Reverse(change.change().unsigned_abs()));
+
+        let categories = |run: &RunRecord| -> Vec<ErrorCategory> {
+            run.cleaners
+                .iter()
+                .filter_map(|c| c.error.as_deref().map(ErrorCategory::of))
+                .collect()
+        };
+        let old_errors = categories(previous);
+        let mut new_errors: Vec<ErrorCategory> = categories(current)
+            .into_iter()
+            .filter(|category| !old_errors.contains(category))
+            .collect();
+        new_errors.sort();
+        new_errors.dedup();
+
+        Self {
+            previous_id: previous.id,
+            previous_total: previous.total(),
+            current_total: current.total(),
+            changes,
+            new_errors,
+        }
+    }
+
+    /// Lines describing the comparison, for the results screen and the
+    /// command line
+    pub fn summary_lines(&self) -> Vec<String> {
+        let total_change = self.current_total as i64 - self.previous_total as i64;
+        let trend = if total_change.unsigned_abs() < NOTABLE_CHANGE {
+            "about the same as"
+        } else if total_change > 0 {
+            "more than"
+        } else {
+            "less than"
+        };
+        let mut lines = vec![format!(
+            "📈 Freed {} ({}), {} run #{} ({})",
+            format_size(self.current_total),
+            signed_size(total_change),
+            trend,
+            self.previous_id,
+            format_size(self.previous_total)
+        )];
+        for change in &self.changes {
+            let arrow = if change.change() > 0 { "▲" } else { "▼" };
+            let detail = match (change.previous, change.current) {
+                (None, Some(current)) => format!("{} (not run before)", format_size(current)),
+                (Some(previous), None) => format!("not run ({} before)", format_size(previous)),
+                (previous, current) => format!(
+                    "{} → {} ({})",
+                    format_size(previous.unwrap_or(0)),
+                    format_size(current.unwrap_or(0)),
+                    signed_size(change.change())
+                ),
+            };
+            lines.push(format!("  {} {}: {}", arrow, change.name, detail));
+        }
+        if !self.new_errors.is_empty() {
+            let labels: Vec<&str> = self.new_errors.iter().map(|c| c.label()).collect();
+            lines.push(format!("  ⚠ New errors: {}", labels.join(", ")));
+        }
+        lines
+    }
+}
+
+fn signed_size(bytes: i64) -> String {
+    let sign = if bytes < 0 { "-" } else { "+" };
+    format!("{}{}", sign, format_size(bytes.unsigned_abs()))
+}
@@ -25,6 +25,7 @@
 //! - Browser caches (Firefox, Chrome/Chromium)
 //! - Application caches
 //! - Thumbnail caches
+//! - Font and icon caches, optionally rebuilt right after cleaning
 //! - Temporary files owned by the user
 //! - Package manager caches (pip, npm, cargo)
 //! - User trash
@@ -36,6 +37,14 @@
 //! - Temporary files
 //! - Old kernels (on supported systems)
 //! - Crash reports and core dumps
+//! - Stale libvirt save/dump files and orphaned GNOME Boxes ISOs
+//! - Finished CUPS print jobs and cached PPD data
+//! - Mail & Spool: stale cron output, dead letters, oversized mailboxes
+//! - Web & Database Servers (opt-in): web server and database logs, PHP sessions
+//! - Advanced Temp Cleanup (opt-in): temp litter by name pattern, kept while in use
+//!
+//! ### Container Cleaning
+//! - Docker and Podman unused data (`system prune`)
 //!
 //! ## Quick Start
 //!
@@ -70,6 +79,12 @@
 //! cleansys user          # User-level cleaners
 //! sudo cleansys system   # System-level cleaners
 //!
+//! # Run only the cleaners tagged dev or browser
+//! cleansys clean --tags dev,browser
+//!
+//! # Run the recommended safe set
+//! cleansys clean --recommended
+//!
 //! # List all available cleaners
 //! cleansys list
 //!
@@ -78,6 +93,40 @@
 //!
 //! # Show verbose output
 //! cleansys --verbose
+//!
+//! # Run at idle CPU/IO priority so the desktop stays responsive
+//! cleansys --low-priority user
+//!
+//! # Contain CPU/IO in a transient systemd scope when available
+//! cleansys --scope user
+//!
+//! # Refresh size estimates live while cache directories change
+//! cleansys --watch
+//!
+//! # Log the SHA-256 of every small file before it is deleted
+//! cleansys --audit user
+//!
+//! # Check each cleaner's reported size against the free space on disk
+//! cleansys --verify-freed user
+//!
+//! # Find duplicate files and move the extra copies into quarantine
+//! cleansys duplicates ~/Downloads --threads 4 --quarantine
+//!
+//! # Time every cleaner's scan with the native walker and with du
+//! cleansys bench-scan
+//!
+//! # Report oversized or unused swapfiles and stale hibernation images
+//! cleansys swap
+//!
+//! # List what fsck recovered into lost+found, with sizes and ages
+//! sudo cleansys lost-found
+//!
+//! # Show where cleansys keeps its config, cache, state and logs
+//! cleansys paths
+//!
+//! # List past runs and compare run 3 with the run before it
+//! cleansys history list
+//! cleansys history show 3
 //! ```
 //!
 //! ## Terminal UI Controls
@@ -149,6 +198,9 @@
 //! - Fedora/RHEL (dnf/yum-based)
 //! - Other Linux distributions
 //!
+//! FreeBSD gets the shared system cleaners plus `pkg clean` and removal of
+//! logs rotated by newsyslog.
+//!
 //! ## Examples
 //!
 //! ### Running the TUI
@@ -175,6 +227,9 @@
 //!
 //! ```bash
 //! cleansys list
+//!
+//! # Include reclaimable estimates, as JSON
+//! cleansys list --scan --output json
 //! ```
 //!
 //! ## License
@@ -196,32 +251,181 @@
 #![allow(missing_docs)]
 #![doc(html_root_url = "https://docs.rs/cleansys/0.2.1")]
 
+/// Actions the TUI performs, and the keymaps that turn key presses into them
+pub mod action;
+
 /// Application state and logic for the TUI
 pub mod app;
 
+/// Audit log of small files removed during a run
+pub mod audit;
+
+/// Bulk selection actions over result lists
+pub mod bulk;
+
+/// Cancellation of a running clean between files
+pub mod cancel;
+
+/// Tracking and termination of external commands run by cleaners
+pub mod children;
+
 /// Cleaner implementations for system and user-level cleanup operations
 pub mod cleaners;
 
 /// Reusable UI components
 pub mod components;
 
+/// Thread counts for scans and deletions, from the CPU count and disk type
+pub mod concurrency;
+
+/// User settings loaded from the config file
+pub mod config;
+
+/// The org.cleansys.Manager D-Bus service for desktop integrations
+#[cfg(all(unix, feature = "dbus"))]
+pub mod dbus;
+
+/// Space freed only after a reboot, from files deleted while held open
+pub mod deferred;
+
+/// Symlink-safe recursive deletion with progress reporting
+pub mod deleter;
+
+/// Duplicate file finder
+pub mod duplicates;
+
+/// Exclusion patterns for paths that are never cleaned
+pub mod exclude;
+
 /// Event handling for terminal input and resize events
 pub mod events;
 
+/// Socket-activated root helper that runs system cleaners without sudo
+pub mod helper;
+
+/// History of cleaning runs and comparison with the previous run
+pub mod history;
+
+/// Per-run safety limit on files and bytes deleted
+pub mod limits;
+
+/// Bounded, rate-limited buffer for the operation log
+pub mod log_buffer;
+
+/// Reporting what fsck left in lost+found directories
+pub mod lost_found;
+
+/// Basic markdown rendering for cleaner documentation
+pub mod markdown;
+
 /// Menu system for text-based interactive interface
 pub mod menu;
 
+/// Mount table parsing and filesystem statistics
+pub mod mounts;
+
+/// Detecting paths on network filesystems, which aren't cleaned by default
+pub mod netfs;
+
+/// XDG locations of cleansys' own config, cache, state and logs
+pub mod paths;
+
 /// Pie chart component for data visualization
 pub mod pie_chart;
 
+/// Filesystem abstraction and `CLEANSYS_ROOT` sandboxing for cleaners
+pub mod filesystem;
+
+/// Bounded producer/consumer worker pipeline
+pub mod pipeline;
+
+/// The operating system the binary was built for
+pub mod platform;
+
+/// Low priority mode and deletion throttling
+pub mod priority;
+
+/// Dropping root to the invoking user under sudo, and taking it back for
+/// system cleaners
+pub mod privileges;
+
+/// Read-only mount and immutable attribute checks before deleting
+pub mod protection;
+
+/// Moving files aside into a restorable quarantine
+pub mod quarantine;
+
 /// Rendering logic for the terminal UI
 pub mod render;
 
+/// Summary reports of past runs in markdown or HTML
+pub mod report;
+
+/// Detecting applications whose caches are about to be cleaned
+pub mod running;
+
+/// Cleaning up external command output for display
+pub mod sanitize;
+
+/// Scheduling of delayed cleaning runs
+pub mod schedule;
+
+/// Scripted TUI sessions for demos, screenshots and end-to-end tests
+pub mod script;
+
+/// Strategies that select cleaners automatically, like the recommended set
+pub mod selection;
+
+/// Directory size scanning and scan benchmarks
+pub mod scanner;
+
+/// Heat colors for sizes shown in listings
+pub mod size_heat;
+
+/// Transient systemd scopes for containing CPU and IO usage
+pub mod scope;
+
+/// TUI session state persisted between runs
+pub mod state;
+
+/// Swapfile and hibernation image advisor
+pub mod swap;
+
+/// Host, distribution, kernel and disk usage for the system information panel
+pub mod sysinfo;
+
+/// Terminal setup and crash-resistant restoration for the TUI
+pub mod terminal;
+
+/// Moving files to the desktop Trash
+pub mod trash;
+
+/// System tray indicator showing reclaimable space
+#[cfg(all(unix, feature = "tray"))]
+pub mod tray;
+
+/// Offering `fstrim` on SSD mounts after large deletions
+pub mod trim;
+
+/// Usage statistics kept locally, never sent anywhere
+pub mod usage;
+
 /// Utility functions for permissions, formatting, and error handling
 pub mod utils;
 
+/// Free space measured around each cleaner to verify reported sizes
+pub mod verify;
+
+/// Per-cleaner timeouts that stop hung cleaners
+pub mod watchdog;
+
+/// Inotify watches on cache directories for live size updates
+pub mod watcher;
+
 /// Re-export commonly used types for convenience
 pub use cleaners::{system_cleaners, user_cleaners};
+pub use components::confirm_dialog::ConfirmDialog;
+pub use components::help_overlay::{HelpContext, HelpOverlay};
 pub use components::password_prompt::PasswordPrompt;
 pub use menu::Menu;
 pub use utils::{check_root, print_error, print_header};
@@ -0,0 +1,181 @@
+//! Soft limit on how much a single run may delete. Crossing it pauses and
+//! asks for confirmation, which protects against a misconfigured cleaner or
+//! exclusion pattern quietly wiping far more than intended.
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+
+use crate::utils::format_size;
+
+/// Default cap on files deleted per run
+pub const DEFAULT_MAX_FILES: u64 = 1_000_000;
+
+/// Default cap on bytes deleted per run (200 GB)
+pub const DEFAULT_MAX_BYTES: u64 = 200 * 1024 * 1024 * 1024;
+
+/// Per-run caps; zero disables a cap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunLimit {
+    pub max_files: u64,
+    pub max_bytes: u64,
+}
+
+impl Default for RunLimit {
+    fn default() -> Self {
+        Self {
+            max_files: DEFAULT_MAX_FILES,
+            max_bytes: DEFAULT_MAX_BYTES,
+        }
+    }
+}
+
+impl RunLimit {
+    /// Whether deleting `files`/`bytes` more on top of what was already
+    /// deleted would cross a cap
+    pub fn exceeded_by(&self, deleted: (u64, u64), files: u64, bytes: u64) -> bool {
+        let over = |max: u64, done: u64, more: u64| max > 0 && done + more > max;
+        over(self.max_files, deleted.0, files) || over(self.max_bytes, deleted.1, bytes)
+    }
+
+    /// Human readable description, e.g. "1000000 files or 200.00 GB"
+    pub fn describe(&self) -> String {
+        match (self.max_files, self.max_bytes) {
+            (0, 0) => "no limit".to_string(),
+            (files, 0) => format!("{} files", files),
+            (0, bytes) => format_size(bytes),
+            (files, bytes) => format!("{} files or {}", files, format_size(bytes)),
+        }
+    }
+}
+
+/// Why a deletion was refused: it would cross the limit and nobody agreed
+/// to go past it. The TUI asks then, and runs the cleaner again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LimitReached {
+    pub limit: RunLimit,
+    /// The question to ask before going past the limit
+    pub prompt: String,
+}
+
+impl std::fmt::Display for LimitReached {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Safety limit of {} per run reached; raise max_files_per_run/max_gb_per_run to allow more",
+            self.limit.describe()
+        )
+    }
+}
+
+impl std::error::Error for LimitReached {}
+
+type Approver = Box<dyn Fn(&str) -> bool + Send>;
+
+struct RunState {
+    limit: RunLimit,
+    files_deleted: u64,
+    bytes_deleted: u64,
+    /// Set once the user agreed to go past the limit in this run
+    approved: bool,
+    approver: Option<Approver>,
+    /// The last deletion refused for crossing the limit
+    refused: Option<LimitReached>,
+}
+
+static STATE: Lazy<Mutex<RunState>> = Lazy::new(|| {
+    Mutex::new(RunState {
+        limit: RunLimit::default(),
+        files_deleted: 0,
+        bytes_deleted: 0,
+        approved: false,
+        approver: None,
+        refused: None,
+    })
+});
+
+fn state() -> std::sync::MutexGuard<'static, RunState> {
+    STATE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Set the caps used from now on
+pub fn set_run_limit(limit: RunLimit) {
+    state().limit = limit;
+}
+
+/// The caps currently in effect
+pub fn run_limit() -> RunLimit {
+    state().limit
+}
+
+/// Ask this function whether to continue past the limit. Without one the
+/// deletion is refused with [`LimitReached`]; the TUI, where stdin prompts
+/// aren't possible, asks in a dialog afterwards, see [`take_refused`].
+pub fn set_approver(approver: impl Fn(&str) -> bool + Send + 'static) {
+    state().approver = Some(Box::new(approver));
+}
+
+/// Let the rest of this run go past the limit, as if the approver agreed
+pub fn approve_run() {
+    state().approved = true;
+}
+
+/// Start counting a new run from zero
+pub fn reset_run() {
+    let mut state = state();
+    state.files_deleted = 0;
+    state.bytes_deleted = 0;
+    state.approved = false;
+    state.refused = None;
+}
+
+/// The deletion refused for crossing the limit since the last call, if any.
+/// Cleaners that carry on past a failed path only log the error, so this
+/// is how the TUI learns it has something to ask.
+pub fn take_refused() -> Option<LimitReached> {
+    state().refused.take()
+}
+
+/// Files and bytes deleted so far in this run
+pub fn deleted() -> (u64, u64) {
+    let state = state();
+    (state.files_deleted, state.bytes_deleted)
+}
+
+/// Check that deleting `files`/`bytes` more stays within the limit, asking
+/// for confirmation if it doesn't. Errors if the deletion must not go ahead.
+pub fn check(files: u64, bytes: u64) -> Result<()> {
+    let mut state = state();
+    let deleted = (state.files_deleted, state.bytes_deleted);
+    if state.approved || !state.limit.exceeded_by(deleted, files, bytes) {
+        return Ok(());
+    }
+
+    let prompt = format!(
+        "This run would delete {} files ({}), over the safety limit of {}. Continue?",
+        deleted.0 + files,
+        format_size(deleted.1 + bytes),
+        state.limit.describe()
+    );
+    let approved = state
+        .approver
+        .as_ref()
+        .is_some_and(|approve| approve(&prompt));
+    if !approved {
+        let reached = LimitReached {
+            limit: state.limit,
+            prompt,
+        };
+        state.refused = Some(reached.clone());
+        return Err(reached.into());
+    }
+    state.approved = true;
+    Ok(())
+}
+
+/// Count a completed deletion towards the run totals
+pub fn record(files: u64, bytes: u64) {
+    let mut state = state();
+    state.files_deleted += files;
+    state.bytes_deleted += bytes;
+}
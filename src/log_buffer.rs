@@ -0,0 +1,138 @@
+//! Bounded storage for the operation log. A cleanup touching hundreds of
+//! thousands of files can log a line for each; keeping them all would grow
+//! memory without bound and slow down every frame. The buffer keeps the
+//! newest lines, caps how many are accepted per second, and counts what it
+//! dropped so the log can say so.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Lines kept by default
+pub const DEFAULT_CAPACITY: usize = 2000;
+
+/// Lines accepted per second by default; the rest are counted as suppressed
+pub const DEFAULT_RATE: usize = 200;
+
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Fixed-capacity, rate-limited ring buffer of log lines
+#[derive(Debug, Clone)]
+pub struct LogBuffer {
+    lines: VecDeque<String>,
+    capacity: usize,
+    rate: usize,
+    /// Start of the current rate window and lines accepted in it
+    window: Option<(Instant, usize)>,
+    /// Lines suppressed in the current window, reported when it ends
+    suppressed_in_window: u64,
+    /// Old lines pushed out to make room
+    evicted: u64,
+    /// Lines never stored because they came in too fast
+    suppressed: u64,
+}
+
+impl Default for LogBuffer {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY, DEFAULT_RATE)
+    }
+}
+
+impl LogBuffer {
+    /// A buffer keeping `capacity` lines and accepting `rate` lines per
+    /// second. A rate of 0 turns rate limiting off.
+    pub fn new(capacity: usize, rate: usize) -> Self {
+        Self {
+            lines: VecDeque::with_capacity(capacity.min(DEFAULT_CAPACITY)),
+            capacity: capacity.max(1),
+            rate,
+            window: None,
+            suppressed_in_window: 0,
+            evicted: 0,
+            suppressed: 0,
+        }
+    }
+
+    pub fn push(&mut self, line: impl Into<String>) {
+        self.push_at(line, Instant::now());
+    }
+
+    /// Push a line as if it arrived at `now`
+    pub fn push_at(&mut self, line: impl Into<String>, now: Instant) {
+        if self.rate > 0 {
+            match &mut self.window {
+                Some((start, count)) if now.duration_since(*start) < RATE_WINDOW => {
+                    if *count >= self.rate {
+                        self.suppressed += 1;
+                        self.suppressed_in_window += 1;
+                        return;
+                    }
+                    *count += 1;
+                }
+                _ => {
+                    self.window = Some((now, 1));
+                    if self.suppressed_in_window > 0 {
+                        let note = format!("⋯ {} lines suppressed", self.suppressed_in_window);
+                        self.suppressed_in_window = 0;
+                        self.store(note);
+                    }
+                }
+            }
+        }
+        self.store(line.into());
+    }
+
+    fn store(&mut self, line: String) {
+        if self.lines.len() >= self.capacity {
+            self.lines.pop_front();
+            self.evicted += 1;
+        }
+        self.lines.push_back(line);
+    }
+
+    pub fn extend(&mut self, lines: impl IntoIterator<Item = String>) {
+        for line in lines {
+            self.push(line);
+        }
+    }
+
+    /// Forget every line and reset the counters, for a new run
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.window = None;
+        self.suppressed_in_window = 0;
+        self.evicted = 0;
+        self.suppressed = 0;
+    }
+
+    /// Lines kept, oldest first
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &String> + ExactSizeIterator {
+        self.lines.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Old lines pushed out of the buffer
+    pub fn evicted(&self) -> u64 {
+        self.evicted
+    }
+
+    /// Lines dropped for arriving too fast
+    pub fn suppressed(&self) -> u64 {
+        self.suppressed
+    }
+
+    /// Every line logged but not kept
+    pub fn dropped(&self) -> u64 {
+        self.evicted + self.suppressed
+    }
+}
@@ -0,0 +1,141 @@
+//! Reporter for `lost+found` directories. fsck moves the orphaned inodes it
+//! recovers into `lost+found` at the root of each ext filesystem, named only
+//! by inode number, and nothing ever clears them out, so they can hold
+//! gigabytes no other tool points at. Like the swap advisor this is not a
+//! cleaner: the contents are listed with sizes and ages, and each entry is
+//! removed only after it has been reviewed and confirmed.
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::filesystem::{filesystem, root_path};
+use crate::mounts;
+use crate::scanner::native_size;
+
+/// Filesystems fsck fills a `lost+found` for
+const EXT_FILESYSTEMS: [&str; 3] = ["ext2", "ext3", "ext4"];
+
+const DAY: u64 = 24 * 60 * 60;
+
+/// Something fsck recovered into a `lost+found` directory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LostEntry {
+    pub path: PathBuf,
+    pub size: u64,
+    pub is_dir: bool,
+    /// Days since it was last modified, if the time can be read
+    pub age_days: Option<u64>,
+}
+
+/// The `lost+found` directory of one filesystem
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LostFound {
+    /// Where the filesystem is mounted
+    pub mount: PathBuf,
+    pub path: PathBuf,
+    /// Only root may read `lost+found`, so without root it can't be listed
+    pub unreadable: bool,
+    /// Biggest first
+    pub entries: Vec<LostEntry>,
+}
+
+impl LostFound {
+    /// Bytes held by all entries
+    pub fn total(&self) -> u64 {
+        self.entries.iter().map(|entry| entry.size).sum()
+    }
+}
+
+/// Mount points of ext filesystems in /proc/mounts
+pub fn parse_ext_mounts(contents: &str) -> Vec<PathBuf> {
+    let mut mounts: Vec<PathBuf> = Vec::new();
+    for (mount, fstype) in mounts::parse(contents) {
+        if EXT_FILESYSTEMS.contains(&fstype) && !mounts.contains(&mount) {
+            mounts.push(mount);
+        }
+    }
+    mounts
+}
+
+/// The `lost+found` directories at these mount points. `mounts` are the
+/// paths the system sees; `real_path` maps one to the path to read.
+pub fn analyze(mounts: &[PathBuf], real_path: impl Fn(&Path) -> PathBuf) -> Vec<LostFound> {
+    let now = SystemTime::now();
+    let mut found = Vec::new();
+
+    for mount in mounts {
+        let path = mount.join("lost+found");
+        let dir = real_path(&path);
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::PermissionDenied => {
+                found.push(LostFound {
+                    mount: mount.clone(),
+                    path,
+                    unreadable: true,
+                    entries: Vec::new(),
+                });
+                continue;
+            }
+            // No lost+found here, as on filesystems created without one
+            Err(_) => continue,
+        };
+
+        let mut lost: Vec<LostEntry> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let metadata = entry.path().symlink_metadata().ok()?;
+                Some(LostEntry {
+                    path: path.join(entry.file_name()),
+                    size: native_size(&entry.path()),
+                    is_dir: metadata.is_dir(),
+                    age_days: metadata
+                        .modified()
+                        .ok()
+                        .and_then(|modified| now.duration_since(modified).ok())
+                        .map(|age| age.as_secs() / DAY),
+                })
+            })
+            .collect();
+        if lost.is_empty() {
+            continue;
+        }
+        lost.sort_by(|a, b| b.size.cmp(&a.size).then(a.path.cmp(&b.path)));
+        found.push(LostFound {
+            mount: mount.clone(),
+            path,
+            unreadable: false,
+            entries: lost,
+        });
+    }
+    found
+}
+
+/// Inspect the `lost+found` directories of this system's ext filesystems
+pub fn scan() -> Vec<LostFound> {
+    let mounts = fs::read_to_string(root_path("/proc/mounts")).unwrap_or_default();
+    analyze(&parse_ext_mounts(&mounts), |path| root_path(path))
+}
+
+/// Delete a reviewed entry. Refuses anything outside a `lost+found`
+/// directory.
+pub fn remove(entry: &LostEntry) -> Result<u64> {
+    let in_lost_found = entry
+        .path
+        .parent()
+        .is_some_and(|parent| parent.file_name().is_some_and(|name| name == "lost+found"));
+    if !in_lost_found {
+        bail!("{} is not in a lost+found directory", entry.path.display());
+    }
+    let path = root_path(&entry.path);
+    if entry.is_dir {
+        filesystem().remove_dir_all(&path)?;
+    } else {
+        filesystem().remove_file(&path)?;
+    }
+    Ok(entry.size)
+}
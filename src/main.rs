@@ -1,29 +1,62 @@
-use anyhow::Result;
-use clap::{Parser, Subcommand};
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use indicatif::{ProgressBar, ProgressStyle};
 use log::debug;
-use std::io;
-
-mod app;
-mod cleaners;
-mod components;
-mod events;
-mod menu;
-mod pie_chart;
-mod render;
-mod utils;
-
-use app::{App, CleanerCategory, CleanerItem};
-use cleaners::{system_cleaners, user_cleaners};
-use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use cleansys::app::{App, CleanerCategory, CleanerItem};
+use cleansys::audit;
+use cleansys::children;
+use cleansys::cleaners::{
+    self, cups, custom, kernels, listing, log_backends, package_cache, rebuild, system_cleaners,
+    temp_litter, user_cleaners, Tag,
+};
+#[cfg(feature = "server")]
+use cleansys::cleaners::{server_cleaners, spool_cleaners};
+use cleansys::concurrency;
+use cleansys::config::Settings;
+#[cfg(all(unix, feature = "dbus"))]
+use cleansys::dbus;
+use cleansys::deferred;
+use cleansys::deleter::{self, DeleteProgress};
+use cleansys::duplicates;
+use cleansys::events::{Config, Event, Events};
+use cleansys::exclude;
+use cleansys::helper;
+use cleansys::history::{CleanerRun, History, RunDiff, RunRecord};
+use cleansys::limits;
+use cleansys::lost_found;
+use cleansys::markdown;
+use cleansys::menu::Menu;
+use cleansys::netfs;
+use cleansys::paths;
+use cleansys::pipeline::Pipeline;
+use cleansys::priority;
+use cleansys::privileges;
+use cleansys::protection;
+use cleansys::quarantine::{self, Quarantine, QuarantineTarget, RestoreReport};
+use cleansys::render::ui;
+use cleansys::report::{self, Report};
+use cleansys::running;
+use cleansys::scanner;
+use cleansys::scope;
+use cleansys::script::{self, Script};
+use cleansys::selection::{self, Recommended, SelectionStrategy};
+use cleansys::state::SessionState;
+use cleansys::swap;
+use cleansys::terminal;
+use cleansys::trash::Trash;
+#[cfg(all(unix, feature = "tray"))]
+use cleansys::tray;
+use cleansys::trim;
+use cleansys::usage;
+use cleansys::utils::{
+    check_root, confirm, elevate_if_needed, format_size, init_color, print_error, print_header,
+    print_success, print_warning, set_prefer_askpass, set_raw_sizes,
 };
-use events::{Config, Event, Events};
-use menu::Menu;
-use ratatui::{prelude::CrosstermBackend, Terminal};
-use render::ui;
-use utils::{check_root, elevate_if_needed, print_error, print_header};
+use cleansys::verify;
+use cleansys::watchdog;
 
 #[derive(Parser)]
 #[command(
@@ -40,6 +73,51 @@ struct Cli {
     #[arg(short, long)]
     verbose: bool,
 
+    /// Run at idle CPU and IO priority so the desktop stays responsive
+    #[arg(long, global = true)]
+    low_priority: bool,
+
+    /// Threads for scanning and for deleting, defaults to one per CPU or
+    /// one on a spinning disk
+    #[arg(short, long, global = true, value_name = "N")]
+    jobs: Option<usize>,
+
+    /// Best-effort IO priority from 0 (highest) to 7 (lowest)
+    #[arg(long, global = true, value_name = "LEVEL",
+          value_parser = clap::value_parser!(u8).range(0..=7))]
+    io_priority: Option<u8>,
+
+    /// Run inside a transient systemd scope with a low IO/CPU weight
+    #[arg(long, global = true)]
+    scope: bool,
+
+    /// Record path and SHA-256 of small files before they are deleted
+    #[arg(long, global = true)]
+    audit: bool,
+
+    /// Measure free disk space around each cleaner and flag reported sizes
+    /// the disk doesn't confirm
+    #[arg(long, global = true)]
+    verify_freed: bool,
+
+    /// Show sizes as exact byte counts instead of KB, MB and GB
+    #[arg(long, global = true)]
+    bytes: bool,
+
+    /// Start the TUI without restoring the previous session
+    #[arg(long)]
+    fresh: bool,
+
+    /// Watch cache directories and refresh size estimates live in the TUI
+    #[arg(long)]
+    watch: bool,
+
+    /// Drive the TUI from a script of actions (select, run, wait, snapshot,
+    /// export), for demos and end-to-end tests. Without a terminal it runs
+    /// headless.
+    #[arg(long, value_name = "FILE")]
+    script: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -58,14 +136,187 @@ enum Commands {
         #[arg(short, long)]
         yes: bool,
     },
+    /// Run the cleaners carrying any of the given tags
+    Clean {
+        /// Comma-separated tags: privacy, dev, browser, heavy, packages, logs
+        #[arg(
+            long,
+            value_delimiter = ',',
+            required_unless_present = "recommended",
+            conflicts_with = "recommended"
+        )]
+        tags: Vec<Tag>,
+
+        /// Run the curated safe set instead, keeping files changed in the
+        /// last 30 days
+        #[arg(long)]
+        recommended: bool,
+
+        /// Skip confirmation prompts
+        #[arg(short, long)]
+        yes: bool,
+    },
     /// List all available cleaners
-    List,
+    List {
+        /// Scan each available cleaner for an estimate of reclaimable space
+        #[arg(long)]
+        scan: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        output: OutputFormat,
+    },
+    /// Explain what a cleaner removes, what it keeps and its caveats
+    Explain {
+        /// Cleaner id from `cleansys list`, or its name
+        cleaner: String,
+    },
+    /// Time the size scan of every cleaner, native walker vs du
+    BenchScan {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        output: OutputFormat,
+    },
+    /// Find files with identical contents
+    Duplicates {
+        /// Directories to search
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+
+        /// Worker threads for hashing and moving, defaults to one per CPU
+        #[arg(long)]
+        threads: Option<usize>,
+
+        /// Keep the first copy in each group and quarantine the others
+        #[arg(long)]
+        quarantine: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        output: OutputFormat,
+    },
+    /// Put quarantined files back with their owner, permissions and
+    /// extended attributes
+    Restore {
+        /// Quarantine run directory, or its name in the quarantine directory
+        run: PathBuf,
+    },
+    /// Undo the most recent run, restoring everything it moved to the
+    /// quarantine that is still there
+    Undo,
+    /// Report large swapfiles, unused ones and stale hibernation images
+    Swap {
+        /// Walk through deleting the files that are safe to remove
+        #[arg(long)]
+        remove: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        output: OutputFormat,
+    },
+    /// Report what fsck left in the lost+found directories of ext filesystems
+    LostFound {
+        /// Review each entry and delete the confirmed ones
+        #[arg(long)]
+        remove: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        output: OutputFormat,
+    },
+    /// Show where cleansys keeps its config, cache, state and logs
+    Paths {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        output: OutputFormat,
+    },
+    /// Show past runs and how each compared with the one before
+    History {
+        #[command(subcommand)]
+        action: HistoryAction,
+    },
+    /// Serve the org.cleansys.Manager D-Bus API on the session bus, for
+    /// desktop widgets and other programs
+    #[cfg(all(unix, feature = "dbus"))]
+    Dbus,
+    /// Sit in the system tray, showing how much the cleaners could free
+    #[cfg(all(unix, feature = "tray"))]
+    Tray {
+        /// Minutes between scans
+        #[arg(long, default_value_t = 60, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+
+        /// Ask for attention once this much could be freed, e.g. 500MB or 2GB
+        #[arg(long, default_value = "2GB", value_parser = parse_size_arg)]
+        threshold: u64,
+    },
+    /// Serve system cleaners to the TUI over a socket, as root. Started by
+    /// the systemd units in contrib/systemd.
+    Helper,
+    /// Summarize recent runs from the history, e.g. to mail from cron
+    Report {
+        /// Period to cover, e.g. 7d, 2w or 12h
+        #[arg(long, default_value = "7d", value_parser = parse_period_arg)]
+        since: std::time::Duration,
+
+        /// Report format
+        #[arg(long, value_enum, default_value_t = ReportFormat::Md)]
+        format: ReportFormat,
+    },
     /// Interactive menu to select specific cleaners (text-based)
     Menu,
     /// Interactive terminal UI (default)
     Tui,
 }
 
+#[derive(Subcommand)]
+enum HistoryAction {
+    /// List recorded runs
+    List {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        output: OutputFormat,
+    },
+    /// Show one run compared with the run before it
+    Show {
+        /// Run id, as shown by `history list`
+        id: u64,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        output: OutputFormat,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Aligned plain-text table
+    Table,
+    /// JSON array, one object per cleaner
+    Json,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ReportFormat {
+    /// Markdown with text bar charts
+    Md,
+    /// Standalone HTML page with SVG charts
+    Html,
+}
+
+/// A period argument such as `7d`
+fn parse_period_arg(text: &str) -> std::result::Result<std::time::Duration, String> {
+    report::parse_period(text)
+        .ok_or_else(|| format!("'{}' isn't a period like 7d, 2w or 12h", text))
+}
+
+/// A size argument such as `500MB` or `2 GB`
+#[cfg(all(unix, feature = "tray"))]
+fn parse_size_arg(text: &str) -> std::result::Result<u64, String> {
+    cleansys::utils::parse_size(text)
+        .ok_or_else(|| format!("'{}' isn't a size like 500MB or 2GB", text))
+}
+
 fn setup_logger(verbose: bool) {
     let env = env_logger::Env::default()
         .filter_or("CLEANSYS_LOG", if verbose { "debug" } else { "info" });
@@ -75,61 +326,69 @@ fn setup_logger(verbose: bool) {
 }
 
 fn load_cleaners(app: &mut App) {
-    // Add user cleaners
-    let mut user_items = Vec::new();
-    for cleaner in user_cleaners::get_cleaners() {
-        user_items.push(CleanerItem {
-            name: cleaner.name.to_string(),
-            description: cleaner.description.to_string(),
-            requires_root: false,
-            selected: false,
-            function: cleaner.function,
-            bytes_cleaned: 0,
-            status: None,
-        });
-    }
-
-    // Add system cleaners
-    let mut system_items = Vec::new();
-    for cleaner in system_cleaners::get_cleaners() {
-        system_items.push(CleanerItem {
-            name: cleaner.name.to_string(),
-            description: cleaner.description.to_string(),
-            requires_root: true,
-            selected: false,
-            function: cleaner.function,
-            bytes_cleaned: 0,
-            status: None,
-        });
-    }
-
-    app.categories = vec![
-        CleanerCategory {
-            name: "User Land Cleaners".to_string(),
-            description: "Clean user-specific files and caches".to_string(),
-            items: user_items,
-        },
-        CleanerCategory {
-            name: "System Cleaners".to_string(),
-            description: "Clean system files and caches (requires root)".to_string(),
-            items: system_items,
-        },
-    ];
-}
-
-fn run_tui() -> Result<()> {
-    // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    app.categories = cleaners::groups()
+        .into_iter()
+        .map(|group| CleanerCategory {
+            name: group.name.to_string(),
+            description: group.description.to_string(),
+            items: group
+                .cleaners
+                .into_iter()
+                .map(|cleaner| CleanerItem {
+                    name: cleaner.name.to_string(),
+                    description: cleaner.description.to_string(),
+                    docs: cleaner.docs,
+                    requires_root: group.requires_root,
+                    risk: cleaner.risk,
+                    selected: false,
+                    function: cleaner.function,
+                    scan: cleaner.scan,
+                    scan_state: None,
+                    bytes_cleaned: 0,
+                    bytes_deferred: 0,
+                    permission_denied: 0,
+                    status: None,
+                    parent: cleaner.parent.map(str::to_string),
+                    tags: cleaner.tags.to_vec(),
+                })
+                .collect(),
+        })
+        .collect();
+}
 
-    // Create app state
+/// The TUI's app with the cleaners loaded, and the previous session
+/// restored unless `fresh`
+fn tui_app(fresh: bool) -> App {
     let mut app = App::new();
 
     // Load cleaners into app
     load_cleaners(&mut app);
+    app.history = History::open().ok();
+    app.refresh_disks();
+
+    // Restore where the previous session left off
+    if !fresh {
+        match SessionState::load() {
+            Ok(state) => app.apply_session_state(&state),
+            Err(e) => debug!("Not restoring session: {:#}", e),
+        }
+    }
+    app
+}
+
+fn run_tui(fresh: bool, watch: bool, mut script: Option<Script>) -> Result<()> {
+    // Setup terminal, restoring it on panic or signals
+    let mut terminal = terminal::setup()?;
+
+    // Create app state
+    let mut app = tui_app(fresh);
+
+    if watch {
+        if let Err(e) = app.enable_live_sizes() {
+            app.toasts
+                .push(format!("Live size updates unavailable: {:#}", e));
+        }
+    }
 
     // Event loop with frequent ticks for smooth animations
     let events = Events::with_config(Config {
@@ -142,48 +401,156 @@ fn run_tui() -> Result<()> {
             break Err(e.into());
         }
 
-        // Handle events
-        match events.next() {
-            Ok(Event::Input(key)) => match app.handle_key(key) {
-                Ok(should_quit) => {
-                    if should_quit {
-                        break Ok(());
-                    }
-                }
+        // Scripted actions go first, the keyboard still works alongside
+        if let Some(script) = script.as_mut() {
+            match script.step(&mut app) {
+                Ok(true) => break Ok(()),
+                Ok(false) => {}
                 Err(e) => break Err(e),
-            },
-            Ok(Event::Tick) => {
-                // Update animation frame on tick
-                if app.is_running {
-                    app.update_animation();
-                }
-            }
-            Ok(Event::Resize(width, height)) => {
-                // Handle terminal resize
-                app.handle_resize(width, height);
-                // Force immediate redraw on resize
-                if let Err(e) = terminal.draw(|f| ui(f, &mut app)) {
-                    break Err(e.into());
-                }
             }
+        }
+
+        // Handle events
+        let event = match events.next() {
+            Ok(event) => event,
+            Err(e) => break Err(e),
+        };
+        let resized = matches!(event, Event::Resize(..));
+        match app.handle_event(event) {
+            Ok(true) => break Ok(()),
+            Ok(false) => {}
             Err(e) => break Err(e),
         }
+        // The shell gets the terminal to itself until it exits
+        if let Some(dir) = app.take_shell_request() {
+            events.pause();
+            let result = terminal::run_shell(&mut terminal, &dir);
+            events.resume();
+            if let Err(e) = result {
+                app.toasts.push(format!("Could not open a shell: {:#}", e));
+            }
+        }
+
+        // Force immediate redraw on resize
+        if resized {
+            if let Err(e) = terminal.draw(|f| ui(f, &mut app)) {
+                break Err(e.into());
+            }
+        }
+
+        if terminal::received_signal().is_some() {
+            break Ok(());
+        }
     };
 
     // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    terminal::restore()?;
+
+    // Nothing started by a cleaner outlives the TUI
+    children::terminate_all();
+
+    if let Err(e) = app.session_state().save() {
+        debug!("Failed to save session: {:#}", e);
+    }
+    if let Err(e) = scanner::save_cache() {
+        debug!("Failed to save scan cache: {:#}", e);
+    }
 
+    if let Some(signal) = terminal::received_signal() {
+        std::process::exit(128 + signal);
+    }
     result
 }
 
+/// Add a command line run to the history and print how it compares with
+/// the previous one
+fn record_run(run: RunRecord) {
+    if run.cleaners.is_empty() {
+        return;
+    }
+    if let Err(e) = usage::record_run(&run) {
+        debug!("Run not counted in usage statistics: {:#}", e);
+    }
+    let recorded = History::open().and_then(|history| {
+        let run = history.record(run)?;
+        Ok((history.previous(run.id)?, run))
+    });
+    match recorded {
+        Ok((Some(previous), run)) => {
+            for line in RunDiff::between(&previous, &run).summary_lines() {
+                println!("{}", line);
+            }
+        }
+        Ok((None, _)) => {}
+        Err(e) => debug!("Run not saved to history: {:#}", e),
+    }
+}
+
+/// Print what a quarantine restore did, file by file
+fn print_restore_report(report: &RestoreReport, dir: &Path) {
+    for path in &report.restored {
+        println!("  restored {}", path.display());
+    }
+    for (path, problem) in &report.incomplete {
+        print_warning(&format!(
+            "{}: restored, but not all attributes: {}",
+            path.display(),
+            problem
+        ));
+    }
+    for (path, error) in &report.failed {
+        print_error(&format!("{}: {}", path.display(), error));
+    }
+    print_success(&format!(
+        "Restored {} files from {}",
+        report.restored.len(),
+        dir.display()
+    ));
+}
+
+/// Prompt on the terminal when a run is about to cross the safety limit.
+/// Without this (TUI, `--yes`) such deletions are refused.
+fn ask_before_exceeding_limit() {
+    limits::set_approver(|prompt| confirm(prompt, false).unwrap_or(false));
+}
+
+/// Show a progress bar on stderr while large directories are deleted
+fn show_deletion_progress() {
+    let current: Mutex<Option<(PathBuf, ProgressBar)>> = Mutex::new(None);
+    deleter::set_progress_handler(move |progress: &DeleteProgress| {
+        let mut current = current.lock().unwrap_or_else(|e| e.into_inner());
+        if current
+            .as_ref()
+            .is_none_or(|(root, _)| *root != progress.root)
+        {
+            let bar = ProgressBar::new(progress.files_total);
+            bar.set_style(
+                ProgressStyle::with_template("{msg} [{bar:30}] {percent}% ({pos}/{len} files)")
+                    .unwrap_or_else(|_| ProgressStyle::default_bar()),
+            );
+            *current = Some((progress.root.clone(), bar));
+        }
+
+        if let Some((_, bar)) = current.as_ref() {
+            bar.set_length(progress.files_total);
+            bar.set_position(progress.files_done);
+            bar.set_message(format!(
+                "Deleting {} {}/{}",
+                progress.root.display(),
+                format_size(progress.bytes_done),
+                format_size(progress.bytes_total)
+            ));
+            if progress.is_finished() {
+                bar.finish_and_clear();
+                *current = None;
+            }
+        }
+    });
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
+    init_color();
 
     setup_logger(cli.verbose);
     debug!(
@@ -191,12 +558,103 @@ fn main() -> Result<()> {
         std::env::args().collect::<Vec<_>>()
     );
 
+    // Under sudo, run as the invoking user and take root back only for
+    // system cleaners, so files in their home never end up owned by root
+    if let Err(e) = privileges::drop_to_invoker() {
+        print_warning(&format!("Keeping root privileges: {:#}", e));
+    }
+
+    // History and audit logs moved to the state directory
+    if let Err(e) = paths::migrate_legacy() {
+        debug!("Not migrating old data: {:#}", e);
+    }
+
+    let settings = Settings::load().unwrap_or_else(|e| {
+        print_error(&format!("Ignoring config: {:#}", e));
+        Settings::default()
+    });
+
+    if cli.scope || settings.systemd_scope {
+        let weight = settings.scope_weight.unwrap_or(scope::DEFAULT_WEIGHT);
+        // The new process starts as root again and drops it itself
+        privileges::as_root(|| scope::reexec_in_scope(weight, weight, !check_root()))?;
+    }
+
+    concurrency::set_scan_threads(cli.jobs.or(settings.scan_threads));
+    concurrency::set_delete_threads(cli.jobs.or(settings.delete_threads));
+    if let Some(level) = cli.io_priority.or(settings.io_nice) {
+        if !priority::set_io_priority(level) {
+            debug!("Could not set the IO priority to {}", level);
+        }
+    }
+
+    if cli.low_priority || settings.low_priority {
+        let state = priority::enable_low_priority(std::time::Duration::from_millis(
+            settings.deletion_delay_ms,
+        ));
+        debug!("Low priority mode: {:?}", state);
+        // Parallel deletions would undo the pause between them
+        concurrency::set_delete_threads(Some(1));
+    }
+
+    limits::set_run_limit(settings.run_limit());
+    package_cache::set_retention(settings.package_retention);
+    log_backends::set_journal_limit(settings.journal_max_use.clone());
+    log_backends::set_journal_vacuum(settings.journal_vacuum.clone());
+    #[cfg(feature = "server")]
+    {
+        spool_cleaners::set_truncate_mailboxes(settings.truncate_large_mailboxes);
+        server_cleaners::set_enabled(settings.server_cleaners);
+    }
+    temp_litter::set_enabled(settings.advanced_temp_cleanup);
+    rebuild::set_enabled(settings.rebuild_caches);
+    trim::set_enabled(settings.offer_trim);
+    cups::set_keep_days(settings.print_jobs_keep_days);
+    kernels::set_keep(settings.keep_kernels);
+    watchdog::set_timeouts(settings.cleaner_timeouts());
+    exclude::set_patterns(settings.exclude.clone());
+    running::set_running_apps(settings.running_apps.clone());
+    netfs::set_action(settings.network_filesystems);
+    quarantine::set_retention(settings.quarantine_retention());
+    match quarantine::enforce_retention() {
+        Ok(purge) if !purge.purged.is_empty() => debug!(
+            "Purged {} quarantine runs, freeing {}",
+            purge.purged.len(),
+            format_size(purge.bytes_freed)
+        ),
+        Ok(_) => {}
+        Err(e) => debug!("Not purging the quarantine: {:#}", e),
+    }
+    for problem in custom::set_custom_cleaners(settings.custom_cleaners.clone()) {
+        print_warning(&problem);
+    }
+    set_prefer_askpass(settings.sudo_askpass);
+
+    // Audit mode is a safety net, so refuse to run without it when asked for
+    if cli.audit || settings.audit {
+        let path = audit::enable().context("Cannot enable audit mode")?;
+        debug!("Audit log: {:?}", path);
+    }
+
+    if cli.verify_freed {
+        verify::enable();
+    }
+    set_raw_sizes(cli.bytes);
+
     let is_root = check_root();
 
+    // The TUI owns the terminal, so only the command line shows progress bars
+    if !matches!(cli.command, Some(Commands::Tui) | None) {
+        show_deletion_progress();
+    }
+
     match cli.command {
         Some(Commands::User { yes }) => {
             print_header("USER CLEANER");
-            user_cleaners::run_all(yes)?;
+            if !yes {
+                ask_before_exceeding_limit();
+            }
+            record_run(user_cleaners::run_all(yes)?);
         }
         Some(Commands::System { yes }) => {
             print_header("SYSTEM CLEANER");
@@ -213,27 +671,444 @@ fn main() -> Result<()> {
                     return Ok(());
                 }
             }
-            system_cleaners::run_all(yes)?;
+            if !yes {
+                ask_before_exceeding_limit();
+            }
+            record_run(system_cleaners::run_all(yes)?);
+        }
+        Some(Commands::Clean {
+            tags,
+            recommended,
+            yes,
+        }) => {
+            let groups = if recommended {
+                print_header("RECOMMENDED CLEANERS");
+                protection::set_min_age(Recommended.min_age());
+                selection::pick(&Recommended)
+            } else {
+                print_header("TAGGED CLEANERS");
+                cleaners::tagged(&tags)
+            };
+            let mut selected = Vec::new();
+            for group in groups {
+                if group.requires_root && !is_root {
+                    let names: Vec<&str> = group.cleaners.iter().map(|c| c.name).collect();
+                    print_warning(&format!(
+                        "Skipping {} (requires root): {}",
+                        group.name,
+                        names.join(", ")
+                    ));
+                    continue;
+                }
+                selected.extend(group.cleaners);
+            }
+            if selected.is_empty() {
+                print_warning(if recommended {
+                    "None of the recommended cleaners has anything to clean"
+                } else {
+                    "No available cleaners carry these tags"
+                });
+                return Ok(());
+            }
+            if !yes {
+                ask_before_exceeding_limit();
+            }
+            record_run(cleaners::run_cleaners(selected, yes)?);
+        }
+        Some(Commands::List { scan, output }) => {
+            let listings = listing::list_all(scan);
+            if let Err(e) = scanner::save_cache() {
+                debug!("Failed to save scan cache: {:#}", e);
+            }
+            match output {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&listings)?);
+                }
+                OutputFormat::Table => {
+                    print_header("AVAILABLE CLEANERS");
+                    println!("{}", listing::format_table(&listings));
+                }
+            }
+        }
+        Some(Commands::Explain { cleaner }) => {
+            let Some(listing) = listing::find(&cleaner) else {
+                print_error(&format!(
+                    "No cleaner {:?}; `cleansys list` shows their ids and names",
+                    cleaner
+                ));
+                return Ok(());
+            };
+            print_header(&listing.name.to_uppercase());
+            println!("{}", listing.description);
+            println!(
+                "Category: {}{}",
+                listing.category,
+                if listing.requires_root {
+                    " (requires root)"
+                } else {
+                    ""
+                }
+            );
+            println!();
+            match listing.docs {
+                Some(docs) => println!("{}", markdown::to_terminal(docs)),
+                None => println!("No further documentation for this cleaner."),
+            }
+        }
+        Some(Commands::BenchScan { output }) => {
+            let results = scanner::bench_scan();
+            match output {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&results)?);
+                }
+                OutputFormat::Table => {
+                    print_header("SCAN BENCHMARK");
+                    println!("{}", scanner::format_bench_table(&results));
+                }
+            }
+        }
+        Some(Commands::Duplicates {
+            paths,
+            threads,
+            quarantine,
+            output,
+        }) => {
+            let pipeline = match threads {
+                Some(threads) => Pipeline::new().threads(threads),
+                None => Pipeline::new().threads(concurrency::scan_threads()),
+            };
+            let groups = duplicates::find_duplicates(&paths, &pipeline);
+
+            match output {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&groups)?);
+                }
+                OutputFormat::Table => {
+                    print_header("DUPLICATE FILES");
+                    for group in &groups {
+                        println!(
+                            "{} x {} ({} wasted)",
+                            group.paths.len(),
+                            format_size(group.size),
+                            format_size(group.wasted())
+                        );
+                        for path in &group.paths {
+                            println!("  {}", path.display());
+                        }
+                    }
+                    let wasted: u64 = groups.iter().map(|g| g.wasted()).sum();
+                    println!(
+                        "{} groups, {} reclaimable",
+                        groups.len(),
+                        format_size(wasted)
+                    );
+                }
+            }
+
+            if quarantine && !groups.is_empty() {
+                let extra: Vec<PathBuf> = groups
+                    .into_iter()
+                    .flat_map(|g| g.paths.into_iter().skip(1))
+                    .collect();
+                let (report, destination, run) = match settings.quarantine {
+                    QuarantineTarget::Private => {
+                        let quarantine = Quarantine::create()?;
+                        let report = quarantine.move_files(extra, &pipeline)?;
+                        let run = RunRecord::new(vec![CleanerRun::succeeded(
+                            "Duplicate Files",
+                            report.bytes_moved(),
+                        )]);
+                        // Named after the run directory, for `cleansys undo`
+                        let run = match quarantine.dir().file_name() {
+                            Some(name) => run.with_quarantine(name.to_string_lossy()),
+                            None => run,
+                        };
+                        (report, quarantine.dir().to_path_buf(), run)
+                    }
+                    QuarantineTarget::SystemTrash => {
+                        let trash = Trash::home()?;
+                        let report = trash.move_files(extra, &pipeline);
+                        let run = RunRecord::new(vec![CleanerRun::succeeded(
+                            "Duplicate Files",
+                            report.bytes_moved(),
+                        )]);
+                        (report, trash.dir().to_path_buf(), run)
+                    }
+                };
+                if !report.moved.is_empty() {
+                    if let Err(e) = History::open().and_then(|history| history.record(run)) {
+                        debug!("Run not saved to history: {:#}", e);
+                    }
+                }
+                for (path, error) in &report.failed {
+                    print_error(&format!("{}: {}", path.display(), error));
+                }
+                print_success(&format!(
+                    "Moved {} files ({}) to {}",
+                    report.moved.len(),
+                    format_size(report.bytes_moved()),
+                    destination.display()
+                ));
+            }
+        }
+        Some(Commands::Restore { run }) => {
+            let dir = if run.is_dir() {
+                run
+            } else {
+                Quarantine::base_dir()?.join(run)
+            };
+            print_restore_report(&Quarantine::restore(&dir)?, &dir);
+        }
+        Some(Commands::Undo) => {
+            let history = History::open()?.load()?;
+            let dir = match Quarantine::undo_target(&Quarantine::base_dir()?, &history) {
+                Ok(dir) => dir,
+                Err(e) => {
+                    print_warning(&format!("{:#}", e));
+                    println!("Older quarantine runs can be put back with `cleansys restore <run>`");
+                    return Ok(());
+                }
+            };
+            print_header("UNDO");
+            print_restore_report(&Quarantine::restore(&dir)?, &dir);
+        }
+        Some(Commands::Swap { remove, output }) => {
+            let findings = swap::scan();
+            match output {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&findings)?);
+                }
+                OutputFormat::Table => {
+                    print_header("SWAP ADVISOR");
+                    for finding in &findings {
+                        println!(
+                            "{} ({})\n  {}",
+                            finding.path.display(),
+                            format_size(finding.size),
+                            finding.advice()
+                        );
+                    }
+                    if findings.is_empty() {
+                        println!("Nothing to report");
+                    }
+                }
+            }
+
+            let removable: Vec<_> = findings.iter().filter(|f| f.removable()).collect();
+            if remove && !removable.is_empty() {
+                if !is_root && (!elevate_if_needed()? || !check_root()) {
+                    print_error("Removing swapfiles requires root privileges.");
+                    println!("Please run: sudo cleansys swap --remove");
+                    return Ok(());
+                }
+                for finding in removable {
+                    let path = finding.path.display();
+                    let size = format_size(finding.size);
+                    if !confirm(&format!("Delete {} ({})?", path, size), false)?
+                        || !confirm(
+                            &format!("{} cannot be restored. Really delete it?", path),
+                            false,
+                        )?
+                    {
+                        println!("Kept {}", path);
+                        continue;
+                    }
+                    match privileges::as_root(|| swap::remove(finding)) {
+                        Ok(freed) => {
+                            print_success(&format!("Deleted {} ({})", path, format_size(freed)))
+                        }
+                        Err(e) => print_error(&format!("{}: {:#}", path, e)),
+                    }
+                }
+            }
         }
-        Some(Commands::List) => {
-            print_header("AVAILABLE CLEANERS");
-            println!("\nUser cleaners (no root required):");
-            for cleaner in user_cleaners::list_cleaners() {
-                println!("  • {}", cleaner);
+        Some(Commands::LostFound { remove, output }) => {
+            let found = lost_found::scan();
+            match output {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&found)?);
+                }
+                OutputFormat::Table => {
+                    print_header("LOST+FOUND");
+                    for dir in &found {
+                        if dir.unreadable {
+                            println!("{}\n  Only root can read it", dir.path.display());
+                            continue;
+                        }
+                        println!(
+                            "{} ({} in {} entries)",
+                            dir.path.display(),
+                            format_size(dir.total()),
+                            dir.entries.len()
+                        );
+                        for entry in &dir.entries {
+                            println!(
+                                "  {:>10}  {:>6}  {}{}",
+                                format_size(entry.size),
+                                entry
+                                    .age_days
+                                    .map_or("-".to_string(), |days| format!("{}d", days)),
+                                entry.path.display(),
+                                if entry.is_dir { "/" } else { "" }
+                            );
+                        }
+                    }
+                    if found.is_empty() {
+                        println!("Nothing to report");
+                    } else if found.iter().any(|dir| dir.unreadable) && !is_root {
+                        println!("Run as root to see everything: sudo cleansys lost-found");
+                    }
+                }
             }
 
-            println!("\nSystem cleaners (root required):");
-            for cleaner in system_cleaners::list_cleaners() {
-                println!("  • {}", cleaner);
+            let entries: Vec<_> = found.iter().flat_map(|dir| &dir.entries).collect();
+            if remove && !entries.is_empty() {
+                if !is_root && (!elevate_if_needed()? || !check_root()) {
+                    print_error("Removing lost+found entries requires root privileges.");
+                    println!("Please run: sudo cleansys lost-found --remove");
+                    return Ok(());
+                }
+                println!("fsck couldn't tell where these came from; check before deleting.");
+                for entry in entries {
+                    let path = entry.path.display();
+                    let size = format_size(entry.size);
+                    if !confirm(&format!("Delete {} ({})?", path, size), false)? {
+                        println!("Kept {}", path);
+                        continue;
+                    }
+                    match privileges::as_root(|| lost_found::remove(entry)) {
+                        Ok(freed) => {
+                            print_success(&format!("Deleted {} ({})", path, format_size(freed)))
+                        }
+                        Err(e) => print_error(&format!("{}: {:#}", path, e)),
+                    }
+                }
+            }
+        }
+        Some(Commands::Paths { output }) => {
+            let own = paths::all()?;
+            match output {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&own)?);
+                }
+                OutputFormat::Table => {
+                    for entry in &own {
+                        println!("{:<14} {}", entry.name, entry.path.display());
+                    }
+                }
+            }
+        }
+        Some(Commands::History { action }) => {
+            let history = History::open()?;
+            match action {
+                HistoryAction::List { output } => {
+                    let runs = history.load()?;
+                    match output {
+                        OutputFormat::Json => {
+                            println!("{}", serde_json::to_string_pretty(&runs)?);
+                        }
+                        OutputFormat::Table => {
+                            print_header("RUN HISTORY");
+                            for run in &runs {
+                                let errors = run.cleaners.iter().filter(|c| c.error.is_some());
+                                let partial = run.cleaners.iter().filter(|c| c.is_partial());
+                                println!(
+                                    "#{:<4} {}  {:>10}  {} cleaners, {} partial, {} failed",
+                                    run.id,
+                                    run.time,
+                                    format_size(run.total()),
+                                    run.cleaners.len(),
+                                    partial.count(),
+                                    errors.count()
+                                );
+                            }
+                            if runs.is_empty() {
+                                println!("No runs recorded yet");
+                            }
+                        }
+                    }
+                }
+                HistoryAction::Show { id, output } => {
+                    let Some(run) = history.get(id)? else {
+                        print_error(&format!("No run #{} in the history", id));
+                        return Ok(());
+                    };
+                    match output {
+                        OutputFormat::Json => {
+                            println!("{}", serde_json::to_string_pretty(&run)?);
+                        }
+                        OutputFormat::Table => {
+                            print_header(&format!("RUN #{} ({})", run.id, run.time));
+                            for cleaner in &run.cleaners {
+                                match &cleaner.error {
+                                    Some(error) => println!("  ✗ {}: {}", cleaner.name, error),
+                                    None if cleaner.is_partial() => println!(
+                                        "  ◐ {}: {}, {} paths couldn't be removed",
+                                        cleaner.name,
+                                        deferred::describe(cleaner.bytes, cleaner.deferred),
+                                        cleaner.failed_paths
+                                    ),
+                                    None => println!(
+                                        "  ✓ {}: {}",
+                                        cleaner.name,
+                                        deferred::describe(cleaner.bytes, cleaner.deferred)
+                                    ),
+                                }
+                            }
+                            println!(
+                                "Total freed: {}",
+                                deferred::describe_total(run.total(), run.deferred())
+                            );
+                            if let Some(previous) = history.previous(id)? {
+                                println!();
+                                for line in RunDiff::between(&previous, &run).summary_lines() {
+                                    println!("{}", line);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        #[cfg(all(unix, feature = "dbus"))]
+        Some(Commands::Dbus) => dbus::serve()?,
+        #[cfg(all(unix, feature = "tray"))]
+        Some(Commands::Tray {
+            interval,
+            threshold,
+        }) => tray::run(std::time::Duration::from_secs(interval * 60), threshold)?,
+        Some(Commands::Helper) => helper::serve()?,
+        Some(Commands::Report { since, format }) => {
+            let report = Report::since(&History::open()?.load()?, since);
+            match format {
+                ReportFormat::Md => print!("{}", report.markdown()),
+                ReportFormat::Html => print!("{}", report.html()),
             }
         }
         Some(Commands::Menu) => {
-            let menu = Menu::new();
-            menu.run_interactive()?;
+            ask_before_exceeding_limit();
+            record_run(Menu::new().run_interactive()?);
         }
         Some(Commands::Tui) | None => {
-            // Default behavior - show terminal UI
-            run_tui()?;
+            // Default behavior - show terminal UI, or the text menu where
+            // the terminal can't draw it
+            // Scripts start from a fresh session so they play back the same
+            let script = cli.script.as_deref().map(Script::load).transpose()?;
+            let fresh = cli.fresh || script.is_some();
+            match (script, terminal::tui_unsupported()) {
+                (Some(mut script), Some(reason)) => {
+                    debug!("{}; running the script headless", reason);
+                    let mut app = tui_app(fresh);
+                    let tick_rate = std::time::Duration::from_millis(100);
+                    script::run_headless(&mut app, &mut script, tick_rate)?;
+                }
+                (None, Some(reason)) => {
+                    print_warning(&format!("{}; using the text menu instead", reason));
+                    ask_before_exceeding_limit();
+                    record_run(Menu::new().run_interactive()?);
+                }
+                (script, None) => run_tui(fresh, cli.watch || settings.live_sizes, script)?,
+            }
         }
     }
 
@@ -0,0 +1,145 @@
+//! The bit of markdown cleaner documentation is written in: `#` headings,
+//! `-` bullet lists, paragraphs, `**bold**` and `` `code` ``. Lines of a
+//! paragraph or bullet are joined so the text rewraps to the width it is
+//! shown at. The TUI detail pane renders it as styled lines and
+//! `cleansys explain` with terminal colors.
+
+use colored::Colorize;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// A block of a markdown document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Block {
+    Heading(String),
+    Bullet(String),
+    Paragraph(String),
+    /// Space between blocks, from one or more empty lines
+    Blank,
+}
+
+/// How a run of inline text is emphasised
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Emphasis {
+    Plain,
+    Bold,
+    Code,
+}
+
+/// Split a document into blocks
+pub fn parse(text: &str) -> Vec<Block> {
+    let mut blocks: Vec<Block> = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            if !matches!(blocks.last(), None | Some(Block::Blank)) {
+                blocks.push(Block::Blank);
+            }
+        } else if line.starts_with('#') {
+            blocks.push(Block::Heading(
+                line.trim_start_matches('#').trim().to_string(),
+            ));
+        } else if let Some(item) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+            blocks.push(Block::Bullet(item.trim().to_string()));
+        } else if let Some(Block::Paragraph(text) | Block::Bullet(text)) = blocks.last_mut() {
+            text.push(' ');
+            text.push_str(line);
+        } else {
+            blocks.push(Block::Paragraph(line.to_string()));
+        }
+    }
+    if blocks.last() == Some(&Block::Blank) {
+        blocks.pop();
+    }
+    blocks
+}
+
+/// Split a line into runs of plain, bold and code text. Markers without a
+/// closing one are kept as plain text.
+pub fn inline(text: &str) -> Vec<(Emphasis, &str)> {
+    let mut runs = Vec::new();
+    let mut rest = text;
+    loop {
+        let next = [("**", Emphasis::Bold), ("`", Emphasis::Code)]
+            .into_iter()
+            .filter_map(|(marker, emphasis)| {
+                let start = rest.find(marker)?;
+                let len = rest[start + marker.len()..].find(marker)?;
+                Some((start, marker.len(), len, emphasis))
+            })
+            .min_by_key(|&(start, ..)| start);
+        let Some((start, marker, len, emphasis)) = next else {
+            break;
+        };
+        if start > 0 {
+            runs.push((Emphasis::Plain, &rest[..start]));
+        }
+        runs.push((emphasis, &rest[start + marker..start + marker + len]));
+        rest = &rest[start + marker + len + marker..];
+    }
+    if !rest.is_empty() {
+        runs.push((Emphasis::Plain, rest));
+    }
+    runs
+}
+
+/// Styled lines for a ratatui paragraph
+pub fn to_lines(text: &str) -> Vec<Line<'static>> {
+    let spans = |text: &str, base: Style| -> Vec<Span<'static>> {
+        inline(text)
+            .into_iter()
+            .map(|(emphasis, run)| {
+                let style = match emphasis {
+                    Emphasis::Plain => base,
+                    Emphasis::Bold => base.add_modifier(Modifier::BOLD),
+                    Emphasis::Code => base.fg(Color::Cyan),
+                };
+                Span::styled(run.to_string(), style)
+            })
+            .collect()
+    };
+
+    parse(text)
+        .into_iter()
+        .map(|block| match block {
+            Block::Heading(text) => Line::from(spans(
+                &text,
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Block::Bullet(text) => {
+                let mut line = vec![Span::raw("  • ")];
+                line.extend(spans(&text, Style::default().fg(Color::White)));
+                Line::from(line)
+            }
+            Block::Paragraph(text) => Line::from(spans(&text, Style::default().fg(Color::White))),
+            Block::Blank => Line::from(""),
+        })
+        .collect()
+}
+
+/// The document with terminal colors, for printing
+pub fn to_terminal(text: &str) -> String {
+    let styled = |text: &str| -> String {
+        inline(text)
+            .into_iter()
+            .map(|(emphasis, run)| match emphasis {
+                Emphasis::Plain => run.to_string(),
+                Emphasis::Bold => run.bold().to_string(),
+                Emphasis::Code => run.cyan().to_string(),
+            })
+            .collect()
+    };
+
+    let lines: Vec<String> = parse(text)
+        .into_iter()
+        .map(|block| match block {
+            Block::Heading(text) => styled(&text).yellow().bold().to_string(),
+            Block::Bullet(text) => format!("  • {}", styled(&text)),
+            Block::Paragraph(text) => styled(&text),
+            Block::Blank => String::new(),
+        })
+        .collect();
+    lines.join("\n")
+}
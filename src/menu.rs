@@ -3,15 +3,14 @@ use colored::*;
 use std::collections::HashMap;
 use std::io::{self, Write};
 
-use crate::cleaners::{system_cleaners, user_cleaners};
-use crate::utils::{check_root, confirm, print_error, print_header, print_success, print_warning};
+use crate::cleaners::{self, CleanerInfo};
+use crate::history::RunRecord;
+use crate::utils::{check_root, print_header, print_warning};
 
 pub struct MenuItem {
     id: usize,
-    name: String,
-    description: String,
-    requires_root: bool,
-    function: fn(bool) -> Result<u64>,
+    group: &'static str,
+    cleaner: CleanerInfo,
 }
 
 pub struct Menu {
@@ -31,28 +30,19 @@ impl Menu {
         let mut items = Vec::new();
         let mut id = 1;
 
-        // Add user cleaner items
-        for cleaner in user_cleaners::get_cleaners() {
-            items.push(MenuItem {
-                id,
-                name: cleaner.name.to_string(),
-                description: cleaner.description.to_string(),
-                requires_root: false,
-                function: cleaner.function,
-            });
-            id += 1;
-        }
-
-        // Add system cleaner items
-        for cleaner in system_cleaners::get_cleaners() {
-            items.push(MenuItem {
-                id,
-                name: cleaner.name.to_string(),
-                description: cleaner.description.to_string(),
-                requires_root: true,
-                function: cleaner.function,
-            });
-            id += 1;
+        // Cleaners for tools this system doesn't have aren't offered
+        for group in cleaners::groups() {
+            for cleaner in group.cleaners {
+                if !cleaner.is_available() {
+                    continue;
+                }
+                items.push(MenuItem {
+                    id,
+                    group: group.name,
+                    cleaner,
+                });
+                id += 1;
+            }
         }
 
         Menu { items, is_root }
@@ -72,30 +62,31 @@ impl Menu {
             }
         );
 
-        // Group items by user/system
-        println!("\n{}", "USER CLEANERS:".blue().bold());
-        for item in &self.items {
-            if !item.requires_root {
-                println!("{}: [{}] {}", item.id, item.name.green(), item.description);
+        // Group items by cleaner group
+        for group in cleaners::groups() {
+            let header = format!("{}:", group.name.to_uppercase());
+            if group.requires_root {
+                println!("\n{}", header.red().bold());
+            } else {
+                println!("\n{}", header.blue().bold());
             }
-        }
 
-        println!("\n{}", "SYSTEM CLEANERS:".red().bold());
-        for item in &self.items {
-            if item.requires_root {
-                let status = if self.is_root {
-                    item.name.green()
+            for item in self.items.iter().filter(|item| item.group == group.name) {
+                let cleaner = &item.cleaner;
+                let status = if !cleaner.requires_root || self.is_root {
+                    cleaner.name.green()
                 } else {
-                    format!("{} (requires root)", item.name).red()
+                    format!("{} (requires root)", cleaner.name).red()
                 };
-                println!("{}: [{}] {}", item.id, status, item.description);
+                println!("{}: [{}] {}", item.id, status, cleaner.description);
             }
         }
 
         Ok(())
     }
 
-    pub fn run_interactive(&self) -> Result<()> {
+    /// Ask which cleaners to run and run them, returning the run to record
+    pub fn run_interactive(self) -> Result<RunRecord> {
         self.display()?;
 
         print!("\nEnter your choices (or 'q' to quit): ");
@@ -106,13 +97,11 @@ impl Menu {
 
         let input = input.trim();
         if input.eq_ignore_ascii_case("q") {
-            return Ok(());
+            return Ok(RunRecord::new(Vec::new()));
         }
 
         let selections = self.parse_selections(input);
-        self.run_selected_cleaners(selections)?;
-
-        Ok(())
+        self.run_selected_cleaners(selections)
     }
 
     fn parse_selections(&self, input: &str) -> Vec<usize> {
@@ -121,7 +110,7 @@ impl Menu {
             return self
                 .items
                 .iter()
-                .filter(|item| !item.requires_root || self.is_root)
+                .filter(|item| !item.cleaner.requires_root || self.is_root)
                 .map(|item| item.id)
                 .collect();
         }
@@ -133,60 +122,42 @@ impl Menu {
             .collect()
     }
 
-    fn run_selected_cleaners(&self, selections: Vec<usize>) -> Result<()> {
+    /// Run the selected cleaners like `cleansys clean` does, asking before
+    /// each one
+    fn run_selected_cleaners(self, selections: Vec<usize>) -> Result<RunRecord> {
         if selections.is_empty() {
             print_warning("No valid selections made. Exiting.");
-            return Ok(());
+            return Ok(RunRecord::new(Vec::new()));
         }
 
-        let mut total_saved: u64 = 0;
+        let mut by_id: HashMap<usize, CleanerInfo> = self
+            .items
+            .into_iter()
+            .map(|item| (item.id, item.cleaner))
+            .collect();
+        let mut selected = Vec::new();
         let mut skipped_items = Vec::new();
-
-        // Create a map of id to items for easier lookup
-        let id_map: HashMap<usize, &MenuItem> =
-            self.items.iter().map(|item| (item.id, item)).collect();
-
         for id in selections {
-            if let Some(item) = id_map.get(&id) {
-                // Skip system cleaners if not root
-                if item.requires_root && !self.is_root {
-                    skipped_items.push(item.name.clone());
-                    continue;
-                }
-
-                print_header(&format!("RUNNING: {}", item.name.to_uppercase()));
-
-                if confirm(&format!("Run '{}'?", item.name), true)? {
-                    match (item.function)(false) {
-                        Ok(bytes) => {
-                            total_saved += bytes;
-                            print_success(&format!(
-                                "{} completed: freed {}",
-                                item.name,
-                                crate::utils::format_size(bytes)
-                            ));
-                        }
-                        Err(err) => {
-                            print_error(&format!("Error in {}: {}", item.name, err));
-                        }
-                    }
-                }
+            let Some(cleaner) = by_id.remove(&id) else {
+                continue;
+            };
+            // Skip system cleaners if not root
+            if cleaner.requires_root && !self.is_root {
+                skipped_items.push(cleaner.name);
+            } else {
+                selected.push(cleaner);
             }
         }
 
+        print_header("CLEANING");
+        let run = cleaners::run_cleaners(selected, false)?;
+
         if !skipped_items.is_empty() {
             print_warning(&format!(
                 "The following cleaners were skipped because they require root privileges: {}",
                 skipped_items.join(", ")
             ));
         }
-
-        print_header("CLEANING COMPLETE");
-        print_success(&format!(
-            "Total space freed: {}",
-            crate::utils::format_size(total_saved)
-        ));
-
-        Ok(())
+        Ok(run)
     }
 }
@@ -0,0 +1,71 @@
+//! Reading mount tables such as `/proc/mounts`, whose fields escape
+//! spaces, tabs, newlines and backslashes as octal (`\040` for a space),
+//! and asking the kernel about the filesystem holding a path.
+
+use std::path::{Path, PathBuf};
+
+/// The mount points in a mount table in `/proc/mounts` format, with their
+/// filesystem types
+pub fn parse(contents: &str) -> impl Iterator<Item = (PathBuf, &str)> {
+    contents.lines().filter_map(|line| {
+        let mut fields = line.split_whitespace();
+        let mount_point = PathBuf::from(unescape(fields.nth(1)?));
+        let fstype = fields.next()?;
+        Some((mount_point, fstype))
+    })
+}
+
+/// Undo the octal escapes (`\040` for a space) of a mount table field
+pub fn unescape(field: &str) -> String {
+    let mut result = String::new();
+    let mut rest = field;
+    while let Some(at) = rest.find('\\') {
+        result.push_str(&rest[..at]);
+        let code = rest.get(at + 1..at + 4);
+        match code.and_then(|code| u8::from_str_radix(code, 8).ok()) {
+            Some(byte) => {
+                result.push(byte as char);
+                rest = &rest[at + 4..];
+            }
+            None => {
+                result.push('\\');
+                rest = &rest[at + 1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// `statfs` of the filesystem holding `path`
+#[cfg(unix)]
+pub fn statfs(path: &Path) -> Option<libc::statfs> {
+    stat_path(path, libc::statfs)
+}
+
+/// `statvfs` of the filesystem holding `path`
+#[cfg(unix)]
+pub fn statvfs(path: &Path) -> Option<libc::statvfs> {
+    stat_path(path, libc::statvfs)
+}
+
+/// Call `stat`, one of the libc functions filling in a struct about the
+/// filesystem holding a path
+#[cfg(unix)]
+fn stat_path<T>(
+    path: &Path,
+    stat: unsafe extern "C" fn(*const libc::c_char, *mut T) -> libc::c_int,
+) -> Option<T> {
+    use std::ffi::CString;
+    use std::mem::MaybeUninit;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut buf = MaybeUninit::<T>::uninit();
+    // SAFETY: c_path is NUL-terminated and buf is a valid out pointer
+    if unsafe { stat(c_path.as_ptr(), buf.as_mut_ptr()) } != 0 {
+        return None;
+    }
+    // SAFETY: the call succeeded, so it filled in buf
+    Some(unsafe { buf.assume_init() })
+}
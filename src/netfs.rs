@@ -0,0 +1,170 @@
+//! Paths on network filesystems (NFS, SMB/CIFS, sshfs, ...). Walking them
+//! is slow, and a cache on a network share usually belongs to another
+//! machine or is shared between several, so by default they are neither
+//! scanned nor cleaned. What happens is configurable:
+//!
+//! ```toml
+//! network_filesystems = "warn"
+//! ```
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::mounts;
+use crate::utils::print_warning;
+
+/// Filesystem types, as in `/proc/mounts`, whose files live on another
+/// machine
+const NETWORK_TYPES: [&str; 19] = [
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb3",
+    "smbfs",
+    "ncpfs",
+    "afs",
+    "afpfs",
+    "9p",
+    "ceph",
+    "glusterfs",
+    "lustre",
+    "davfs",
+    "webdav",
+    "fuse.sshfs",
+    "fuse.rclone",
+    "fuse.s3fs",
+    "fuse.gcsfuse",
+    "fuse.glusterfs",
+];
+
+/// What to do with paths on a network filesystem
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkAction {
+    /// Leave them out of scans and cleaning
+    #[default]
+    Skip,
+    /// Scan and clean them, but say they are on the network
+    Warn,
+    /// Don't look at the filesystem type
+    Ignore,
+}
+
+static ACTION: Lazy<Mutex<NetworkAction>> = Lazy::new(|| Mutex::new(NetworkAction::default()));
+
+/// Filesystem types already warned about in this run
+static WARNED: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// Set what happens to paths on network filesystems, from the config file
+pub fn set_action(action: NetworkAction) {
+    *ACTION.lock().unwrap_or_else(|e| e.into_inner()) = action;
+}
+
+/// What happens to paths on network filesystems
+pub fn action() -> NetworkAction {
+    *ACTION.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Whether a filesystem type from the mount table is a network filesystem
+pub fn is_network_type(fstype: &str) -> bool {
+    NETWORK_TYPES.contains(&fstype)
+}
+
+/// Whether `path` should be left alone because it is on a network
+/// filesystem. With `warn` it isn't, and the first path on each type of
+/// network filesystem is reported.
+pub fn skip(path: &Path) -> bool {
+    let action = action();
+    if action == NetworkAction::Ignore {
+        return false;
+    }
+    let Some(fstype) = network_type(path) else {
+        return false;
+    };
+    if action == NetworkAction::Skip {
+        return true;
+    }
+    let first = WARNED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(fstype.clone());
+    if first {
+        print_warning(&format!(
+            "{:?} is on a network filesystem ({}); scanning and cleaning it may be slow",
+            path, fstype
+        ));
+    }
+    false
+}
+
+/// The filesystem type of the mount holding `path` in a mount table in
+/// `/proc/mounts` format, from the deepest mount point above it
+pub fn mount_type(mounts: &str, path: &Path) -> Option<String> {
+    deepest_mount(mounts, path).map(|(_, fstype)| fstype.to_string())
+}
+
+/// The mount point of the mount holding `path`, like [`mount_type`]
+pub fn mount_point(mounts: &str, path: &Path) -> Option<PathBuf> {
+    deepest_mount(mounts, path).map(|(mount_point, _)| mount_point)
+}
+
+/// The deepest mount point above `path` in a mount table, with its type
+fn deepest_mount<'a>(mounts: &'a str, path: &Path) -> Option<(PathBuf, &'a str)> {
+    mounts::parse(mounts)
+        .filter(|(mount_point, _)| path.starts_with(mount_point))
+        .max_by_key(|(mount_point, _)| mount_point.components().count())
+}
+
+/// The type of network filesystem `path` is on, if it is on one. The
+/// filesystem magic says which, except for FUSE, where only the mount
+/// table tells sshfs from a local NTFS drive.
+#[cfg(target_os = "linux")]
+pub fn network_type(path: &Path) -> Option<String> {
+    const NFS: u32 = 0x6969;
+    const SMB: u32 = 0x517b;
+    const CIFS: u32 = 0xff53_4d42;
+    const SMB2: u32 = 0xfe53_4d42;
+    const AFS: u32 = 0x5346_414f;
+    const CEPH: u32 = 0x00c3_6400;
+    const V9FS: u32 = 0x0102_1997;
+    const FUSE: u32 = 0x6573_5546;
+
+    let stat = mounts::statfs(path)?;
+    // The magic numbers all fit in 32 bits, whatever the width of f_type
+    let fstype = match stat.f_type as u32 {
+        NFS => "nfs".to_string(),
+        SMB => "smbfs".to_string(),
+        CIFS | SMB2 => "cifs".to_string(),
+        AFS => "afs".to_string(),
+        CEPH => "ceph".to_string(),
+        V9FS => "9p".to_string(),
+        FUSE => {
+            let mounts = std::fs::read_to_string("/proc/self/mounts").ok()?;
+            mount_type(&mounts, path)?
+        }
+        _ => return None,
+    };
+    is_network_type(&fstype).then_some(fstype)
+}
+
+/// The type of network filesystem `path` is on, if it is on one, from the
+/// type name `statfs` reports
+#[cfg(any(target_os = "macos", target_os = "freebsd"))]
+pub fn network_type(path: &Path) -> Option<String> {
+    use std::ffi::CStr;
+
+    let stat = mounts::statfs(path)?;
+    // SAFETY: the kernel NUL-terminates the type name within the array
+    let fstype = unsafe { CStr::from_ptr(stat.f_fstypename.as_ptr()) }
+        .to_string_lossy()
+        .to_string();
+    is_network_type(&fstype).then_some(fstype)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "freebsd")))]
+pub fn network_type(_path: &Path) -> Option<String> {
+    None
+}
@@ -0,0 +1,128 @@
+//! Where cleansys keeps its own files, following the XDG Base Directory
+//! spec: settings in `$XDG_CONFIG_HOME/cleansys`, the scan cache in
+//! `$XDG_CACHE_HOME/cleansys`, session state, run history and audit logs in
+//! `$XDG_STATE_HOME/cleansys`, and quarantined files in
+//! `$XDG_DATA_HOME/cleansys`. `cleansys paths` prints them. Older versions
+//! kept history and audit logs in the data directory; they are moved over
+//! on startup.
+
+use anyhow::{Context, Result};
+use directories::ProjectDirs;
+use log::{debug, info, warn};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::filesystem::{self, home_dir, is_sandboxed};
+
+/// One of cleansys' own files or directories
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OwnPath {
+    pub name: &'static str,
+    pub path: PathBuf,
+}
+
+fn project_dirs() -> Result<ProjectDirs> {
+    ProjectDirs::from("", "", "cleansys").context("Failed to get base directories")
+}
+
+/// `$XDG_CONFIG_HOME/cleansys`
+pub fn config_dir() -> Result<PathBuf> {
+    if is_sandboxed() {
+        return Ok(home_dir()?.join(".config/cleansys"));
+    }
+    Ok(project_dirs()?.config_dir().to_path_buf())
+}
+
+/// `$XDG_CACHE_HOME/cleansys`
+pub fn cache_dir() -> Result<PathBuf> {
+    if is_sandboxed() {
+        return Ok(home_dir()?.join(".cache/cleansys"));
+    }
+    Ok(project_dirs()?.cache_dir().to_path_buf())
+}
+
+/// `$XDG_STATE_HOME/cleansys`, or the local data directory on systems
+/// without a state directory
+pub fn state_dir() -> Result<PathBuf> {
+    if is_sandboxed() {
+        return Ok(home_dir()?.join(".local/state/cleansys"));
+    }
+    let dirs = project_dirs()?;
+    Ok(dirs
+        .state_dir()
+        .unwrap_or_else(|| dirs.data_local_dir())
+        .to_path_buf())
+}
+
+/// `$XDG_DATA_HOME/cleansys`
+pub fn data_dir() -> Result<PathBuf> {
+    if is_sandboxed() {
+        return Ok(home_dir()?.join(".local/share/cleansys"));
+    }
+    Ok(project_dirs()?.data_local_dir().to_path_buf())
+}
+
+/// Every file and directory cleansys writes, in the order `cleansys paths`
+/// prints them
+pub fn all() -> Result<Vec<OwnPath>> {
+    let own = |name, path| OwnPath { name, path };
+    Ok(vec![
+        own("config", config_dir()?.join("config.toml")),
+        own("scan cache", cache_dir()?.join("scan.db")),
+        own("session state", state_dir()?.join("state.json")),
+        own("history", state_dir()?.join("history.jsonl")),
+        own("audit logs", state_dir()?.join("audit")),
+        own("quarantine", data_dir()?.join("quarantine")),
+    ])
+}
+
+/// Moves from where older versions kept files to where they go now, given
+/// the cache and data directories those versions used. Nothing to do where
+/// both are the same.
+pub fn legacy_moves(old_cache: &Path, old_data: &Path) -> Result<Vec<(PathBuf, PathBuf)>> {
+    let moves = vec![
+        (old_cache.join("scan.db"), cache_dir()?.join("scan.db")),
+        (
+            old_data.join("history.jsonl"),
+            state_dir()?.join("history.jsonl"),
+        ),
+        (old_data.join("audit"), state_dir()?.join("audit")),
+        (old_data.join("quarantine"), data_dir()?.join("quarantine")),
+    ];
+    Ok(moves.into_iter().filter(|(old, new)| old != new).collect())
+}
+
+/// Move what exists at the old paths to the new ones, leaving anything
+/// already at a new path alone. Returns the new paths that were filled.
+pub fn migrate(moves: &[(PathBuf, PathBuf)]) -> Vec<PathBuf> {
+    let mut moved = Vec::new();
+    for (old, new) in moves {
+        if old.symlink_metadata().is_err() {
+            continue;
+        }
+        if new.symlink_metadata().is_ok() {
+            debug!("Not moving {:?}: {:?} already exists", old, new);
+            continue;
+        }
+        let result = new
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::rename(old, new));
+        match result {
+            Ok(()) => moved.push(new.clone()),
+            Err(e) => warn!("Couldn't move {:?} to {:?}: {}", old, new, e),
+        }
+    }
+    moved
+}
+
+/// Move files older versions left in the old locations, logging what moved
+pub fn migrate_legacy() -> Result<()> {
+    let old_cache = filesystem::cache_dir()?.join("cleansys");
+    let old_data = filesystem::data_dir()?.join("cleansys");
+    for path in migrate(&legacy_moves(&old_cache, &old_data)?) {
+        info!("Moved cleansys data to {}", path.display());
+    }
+    Ok(())
+}
@@ -6,6 +6,66 @@ use ratatui::{
 };
 use tui_piechart::{PieChart as TuiPieChart, PieSlice};
 
+/// Share of the remaining distance a growing slice covers per animation tick
+pub const EASE_PER_TICK: f64 = 0.35;
+
+/// Bytes each cleaner freed, streamed in while it deletes and settled once
+/// it reports, and the sizes the chart currently shows for them. Every animation tick moves
+/// the shown sizes closer, so slices grow and the chart re-slices smoothly
+/// instead of jumping when a cleaner reports.
+#[derive(Debug, Clone, Default)]
+pub struct FreedSlices {
+    /// (cleaner, bytes freed, bytes shown)
+    slices: Vec<(String, u64, f64)>,
+}
+
+impl FreedSlices {
+    pub fn clear(&mut self) {
+        self.slices.clear();
+    }
+
+    /// Add bytes a cleaner freed
+    pub fn record(&mut self, name: &str, bytes: u64) {
+        match self.slices.iter_mut().find(|(n, _, _)| n == name) {
+            Some((_, freed, _)) => *freed += bytes,
+            None => self.slices.push((name.to_string(), bytes, 0.0)),
+        }
+    }
+
+    /// Remove bytes recorded for a cleaner before it finished
+    pub fn take_back(&mut self, name: &str, bytes: u64) {
+        if let Some((_, freed, _)) = self.slices.iter_mut().find(|(n, _, _)| n == name) {
+            *freed = freed.saturating_sub(bytes);
+        }
+    }
+
+    /// Move the shown sizes one step toward the freed ones. Returns whether
+    /// any slice is still growing.
+    pub fn tick(&mut self) -> bool {
+        let mut moving = false;
+        for (_, freed, shown) in &mut self.slices {
+            let target = *freed as f64;
+            let gap = target - *shown;
+            // Snap once the rest would be invisible
+            if gap.abs() <= (target * 0.005).max(1.0) {
+                *shown = target;
+            } else {
+                *shown += gap * EASE_PER_TICK;
+                moving = true;
+            }
+        }
+        moving
+    }
+
+    /// The sizes currently shown, by cleaner
+    pub fn shown(&self) -> Vec<(String, u64)> {
+        self.slices
+            .iter()
+            .map(|(name, _, shown)| (name.clone(), *shown as u64))
+            .collect()
+    }
+}
+
 pub struct PieChartData {
     pub name: String,
     pub value: f64,
@@ -17,6 +77,10 @@ pub struct PieChart {
     pub data: Vec<PieChartData>,
     pub show_percentages: bool,
     pub show_legend: bool,
+    /// Slice to highlight; the others are dimmed
+    pub highlight: Option<usize>,
+    /// Whether the chart has keyboard focus
+    pub focused: bool,
 }
 
 impl Default for PieChart {
@@ -26,6 +90,8 @@ impl Default for PieChart {
             data: Vec::new(),
             show_percentages: true,
             show_legend: true,
+            highlight: None,
+            focused: false,
         }
     }
 }
@@ -53,6 +119,16 @@ impl PieChart {
         self
     }
 
+    pub fn highlight(mut self, index: Option<usize>) -> Self {
+        self.highlight = index;
+        self
+    }
+
+    pub fn focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+
     pub fn render(&self, frame: &mut Frame, area: Rect) {
         if area.width < 20 || area.height < 8 {
             // Too small to render anything meaningful
@@ -68,8 +144,20 @@ impl PieChart {
         let slices: Vec<PieSlice> = self
             .data
             .iter()
-            .map(|d| PieSlice::new(&d.name, d.value, d.color))
+            .enumerate()
+            .map(|(i, d)| {
+                let color = match self.highlight {
+                    Some(highlight) if highlight != i => Color::DarkGray,
+                    _ => d.color,
+                };
+                PieSlice::new(&d.name, d.value, color)
+            })
             .collect();
+        let border = if self.focused {
+            Color::Yellow
+        } else {
+            Color::Cyan
+        };
 
         // Create block with title, borders, and padding
         let block = Block::default()
@@ -81,7 +169,7 @@ impl PieChart {
                     .add_modifier(Modifier::BOLD),
             )
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Cyan))
+            .border_style(Style::default().fg(border))
             .padding(Padding::new(1, 1, 0, 0));
 
         // Create the tui-piechart widget with legend and percentages always enabled
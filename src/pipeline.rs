@@ -0,0 +1,98 @@
+//! Producer/consumer pipeline used by the duplicates finder and the
+//! quarantine mover: one thread produces work, a pool of workers processes
+//! it, and the calling thread consumes the results.
+//!
+//! Both channels are bounded, so a fast producer blocks once the workers
+//! fall behind and memory stays flat no matter how large the tree is.
+
+use crossbeam_channel::{bounded, Sender};
+use std::thread;
+
+use crate::cancel;
+
+/// Thread count and queue depth for a pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pipeline {
+    threads: usize,
+    queue_depth: usize,
+}
+
+impl Default for Pipeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pipeline {
+    /// One worker per available CPU, with a queue of 256 items per stage
+    pub fn new() -> Self {
+        Self {
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            queue_depth: 256,
+        }
+    }
+
+    /// Number of worker threads, at least one
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Items each stage may buffer before the previous one blocks
+    pub fn queue_depth(mut self, depth: usize) -> Self {
+        self.queue_depth = depth.max(1);
+        self
+    }
+
+    pub fn thread_count(&self) -> usize {
+        self.threads
+    }
+
+    /// Run `produce` on its own thread, `work` on every item across the
+    /// worker pool, and `consume` on each result on the calling thread.
+    /// Results arrive in completion order, not production order. Once the
+    /// run is cancelled the workers stop taking new items.
+    pub fn run<T, R, P, W, C>(&self, produce: P, work: W, mut consume: C)
+    where
+        T: Send,
+        R: Send,
+        P: FnOnce(&Sender<T>) + Send,
+        W: Fn(T) -> Option<R> + Sync,
+        C: FnMut(R),
+    {
+        let (work_tx, work_rx) = bounded::<T>(self.queue_depth);
+        let (result_tx, result_rx) = bounded::<R>(self.queue_depth);
+        let work = &work;
+
+        thread::scope(|scope| {
+            scope.spawn(move || produce(&work_tx));
+
+            for _ in 0..self.threads {
+                let work_rx = work_rx.clone();
+                let result_tx = result_tx.clone();
+                scope.spawn(move || {
+                    for item in work_rx {
+                        // Finish the item in hand, then stop taking new ones
+                        if cancel::is_requested() {
+                            break;
+                        }
+                        if let Some(result) = work(item) {
+                            if result_tx.send(result).is_err() {
+                                break;
+                            }
+                        }
+                    }
+                });
+            }
+            // Only the workers hold senders now, so the loop below ends
+            // once they have all finished. Dropping our receiver lets the
+            // producer stop if the workers bail out early.
+            drop(work_rx);
+            drop(result_tx);
+
+            for result in result_rx {
+                consume(result);
+            }
+        });
+    }
+}
@@ -0,0 +1,113 @@
+//! The operating system cleansys was built for. Cleaners that only make
+//! sense on one system (journalctl, pacman, `pkg`) are registered based on
+//! this, so each platform only lists what can actually run there.
+
+use std::path::Path;
+
+use crate::cleaners::log_backends::LogBackend;
+use crate::filesystem::root_path;
+
+/// Operating systems with their own set of system cleaners
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    Linux,
+    FreeBsd,
+    MacOs,
+    Windows,
+    /// Anything else; only the portable user cleaners are offered
+    Other,
+}
+
+impl Platform {
+    /// The platform of the running binary
+    pub const fn current() -> Self {
+        if cfg!(target_os = "linux") {
+            Platform::Linux
+        } else if cfg!(target_os = "freebsd") {
+            Platform::FreeBsd
+        } else if cfg!(target_os = "macos") {
+            Platform::MacOs
+        } else if cfg!(windows) {
+            Platform::Windows
+        } else {
+            Platform::Other
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Platform::Linux => "Linux",
+            Platform::FreeBsd => "FreeBSD",
+            Platform::MacOs => "macOS",
+            Platform::Windows => "Windows",
+            Platform::Other => "Unknown",
+        }
+    }
+
+    /// Whether the root-only system cleaners apply
+    pub fn has_system_cleaners(&self) -> bool {
+        matches!(self, Platform::Linux | Platform::FreeBsd)
+    }
+
+    /// How system logs are kept here. On Linux this depends on the init
+    /// system; FreeBSD rotates with newsyslog.
+    pub fn log_backends(&self) -> Vec<LogBackend> {
+        match self {
+            Platform::Linux => InitSystem::detect().log_backends(),
+            Platform::FreeBsd => vec![LogBackend::RotatedFiles],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// The init system of a Linux host, which decides where its logs go
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitSystem {
+    Systemd,
+    /// runit (Void, Artix), usually logging through socklog/svlogd
+    Runit,
+    /// OpenRC (Alpine, Gentoo, Artix), logging through a syslog daemon
+    OpenRc,
+    /// SysV init or anything unrecognised
+    Other,
+}
+
+impl InitSystem {
+    /// The init system running on this host (or in the sandbox)
+    pub fn detect() -> Self {
+        Self::detect_at(&root_path("/"))
+    }
+
+    /// The init system of the root filesystem at `root`, from the runtime
+    /// directories each one creates
+    pub fn detect_at(root: &Path) -> Self {
+        if root.join("run/systemd/system").is_dir() {
+            InitSystem::Systemd
+        } else if root.join("run/runit").is_dir() || root.join("etc/runit").is_dir() {
+            InitSystem::Runit
+        } else if root.join("run/openrc").is_dir() {
+            InitSystem::OpenRc
+        } else {
+            InitSystem::Other
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            InitSystem::Systemd => "systemd",
+            InitSystem::Runit => "runit",
+            InitSystem::OpenRc => "OpenRC",
+            InitSystem::Other => "unknown",
+        }
+    }
+
+    /// Log backends to clean. Rotated files are always included since
+    /// rsyslog and syslog-ng often run alongside journald or svlogd.
+    pub fn log_backends(&self) -> Vec<LogBackend> {
+        match self {
+            InitSystem::Systemd => vec![LogBackend::Journald, LogBackend::RotatedFiles],
+            InitSystem::Runit => vec![LogBackend::Svlogd, LogBackend::RotatedFiles],
+            InitSystem::OpenRc | InitSystem::Other => vec![LogBackend::RotatedFiles],
+        }
+    }
+}
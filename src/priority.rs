@@ -0,0 +1,96 @@
+//! Low priority mode: idle CPU/IO scheduling and throttled deletions.
+
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Nice value used in low priority mode
+const LOW_PRIORITY_NICE: i32 = 19;
+
+/// Current throttling state of the process
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ThrottleState {
+    /// Whether low priority mode was requested
+    pub low_priority: bool,
+    /// Nice value applied to the process, if it could be set
+    pub nice: Option<i32>,
+    /// Whether the idle IO scheduling class was applied
+    pub io_idle: bool,
+    /// Pause inserted after each deletion
+    pub deletion_delay: Duration,
+}
+
+static THROTTLE: Lazy<Mutex<ThrottleState>> = Lazy::new(|| Mutex::new(ThrottleState::default()));
+
+/// Lower the CPU and IO priority of this process and enable the deletion
+/// delay. Failing to change a priority is not fatal; the returned state shows
+/// what actually took effect.
+pub fn enable_low_priority(deletion_delay: Duration) -> ThrottleState {
+    let nice = set_nice(LOW_PRIORITY_NICE);
+    let io_idle = set_io_idle();
+
+    let state = ThrottleState {
+        low_priority: true,
+        nice,
+        io_idle,
+        deletion_delay,
+    };
+    *THROTTLE.lock().unwrap_or_else(|e| e.into_inner()) = state.clone();
+    state
+}
+
+/// Snapshot of the current throttle state
+pub fn current() -> ThrottleState {
+    THROTTLE.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Pause after a deletion when low priority mode asks for it
+pub fn throttle_deletion() {
+    let delay = current().deletion_delay;
+    if !delay.is_zero() {
+        std::thread::sleep(delay);
+    }
+}
+
+#[cfg(unix)]
+fn set_nice(nice: i32) -> Option<i32> {
+    let result = unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, nice) };
+    (result == 0).then_some(nice)
+}
+
+#[cfg(not(unix))]
+fn set_nice(_nice: i32) -> Option<i32> {
+    None
+}
+
+/// Lowest level of the best-effort IO scheduling class
+pub const LOWEST_IO_LEVEL: u8 = 7;
+
+/// Put this process in the best-effort IO class at `level`, from 0 (highest)
+/// to 7 (lowest), like `ionice -c2 -n`. Low priority mode's idle class
+/// replaces it. Returns whether it took effect.
+pub fn set_io_priority(level: u8) -> bool {
+    set_io_class(IOPRIO_CLASS_BE, level.min(LOWEST_IO_LEVEL))
+}
+
+const IOPRIO_CLASS_BE: u8 = 2;
+const IOPRIO_CLASS_IDLE: u8 = 3;
+
+fn set_io_idle() -> bool {
+    set_io_class(IOPRIO_CLASS_IDLE, 0)
+}
+
+#[cfg(target_os = "linux")]
+fn set_io_class(class: u8, level: u8) -> bool {
+    const IOPRIO_WHO_PROCESS: libc::c_long = 1;
+    const IOPRIO_CLASS_SHIFT: libc::c_long = 13;
+
+    let priority = (libc::c_long::from(class) << IOPRIO_CLASS_SHIFT) | libc::c_long::from(level);
+    let result = unsafe { libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, priority) };
+    result == 0
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_io_class(_class: u8, _level: u8) -> bool {
+    false
+}
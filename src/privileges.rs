@@ -0,0 +1,283 @@
+//! Dropping root to the user who ran `sudo cleansys`. Running everything as
+//! root leaves the history, session state and scan cache in that user's
+//! home owned by root, and user cleaners would recreate directories such as
+//! the thumbnail cache as root too. So on startup under sudo the process
+//! switches to `SUDO_UID`/`SUDO_GID` (and the user's home directory) and
+//! keeps root only as its saved user ID. System cleaners and the commands
+//! they run through [`crate::utils::execute_with_sudo`] switch back to root
+//! for as long as they run.
+//!
+//! The switch changes the IDs of every thread in the process, not just the
+//! one that asked for root, so a scan or save on another thread would
+//! create files as root too. Writes of the user's own files go through
+//! [`as_user`], which waits until root is dropped again and holds off
+//! [`as_root`] until the write is done.
+//!
+//! Linux and FreeBSD only; elsewhere cleansys keeps running as root.
+
+use anyhow::{Context, Result};
+use log::debug;
+use once_cell::sync::Lazy;
+use std::cell::Cell;
+use std::sync::{Condvar, Mutex};
+
+/// The user who ran cleansys through sudo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Invoker {
+    pub uid: u32,
+    pub gid: u32,
+}
+
+/// The invoking user from `SUDO_UID` and `SUDO_GID`. None when either is
+/// missing or malformed, or when root itself ran sudo.
+pub fn parse_invoker(uid: Option<&str>, gid: Option<&str>) -> Option<Invoker> {
+    let uid = uid?.trim().parse().ok()?;
+    let gid = gid?.trim().parse().ok()?;
+    (uid != 0).then_some(Invoker { uid, gid })
+}
+
+#[derive(Debug)]
+struct Dropped {
+    invoker: Invoker,
+    /// Supplementary groups of the invoking user, and of root to restore
+    user_groups: Vec<u32>,
+    root_groups: Vec<u32>,
+    /// How many [`as_root`] calls are running
+    elevated: usize,
+    /// How many [`as_user`] calls are running
+    writing: usize,
+}
+
+static DROPPED: Lazy<Mutex<Option<Dropped>>> = Lazy::new(|| Mutex::new(None));
+
+/// Signalled when root is dropped again or an [`as_user`] call finishes
+static SWITCHED: Condvar = Condvar::new();
+
+thread_local! {
+    /// [`as_root`] and [`as_user`] calls running on this thread, which
+    /// mustn't wait for each other
+    static ELEVATED_HERE: Cell<usize> = const { Cell::new(0) };
+    static WRITING_HERE: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Whether root was dropped and can be taken back
+pub fn dropped() -> bool {
+    DROPPED.lock().unwrap_or_else(|e| e.into_inner()).is_some()
+}
+
+/// Switch to the user who ran sudo, if cleansys runs as root through sudo.
+/// Returns that user.
+pub fn drop_to_invoker() -> Result<Option<Invoker>> {
+    let var = |name| std::env::var(name).ok();
+    let Some(invoker) = parse_invoker(var("SUDO_UID").as_deref(), var("SUDO_GID").as_deref())
+    else {
+        return Ok(None);
+    };
+    let mut dropped = DROPPED.lock().unwrap_or_else(|e| e.into_inner());
+    if dropped.is_some() || !imp::is_root() || !imp::SUPPORTED {
+        return Ok(None);
+    }
+
+    let (home, user_groups) = imp::user_details(invoker);
+    let root_groups = imp::groups();
+    imp::switch_to(invoker.uid, invoker.gid, &user_groups)?;
+    if let Some(home) = home {
+        std::env::set_var("HOME", home);
+    }
+    debug!("Dropped root to uid {} gid {}", invoker.uid, invoker.gid);
+    *dropped = Some(Dropped {
+        invoker,
+        user_groups,
+        root_groups,
+        elevated: 0,
+        writing: 0,
+    });
+    Ok(Some(invoker))
+}
+
+/// Run `f` as root when root was dropped, switching back to the invoking
+/// user afterwards. The whole process runs as root meanwhile, so this first
+/// waits for [`as_user`] calls on other threads to finish. Calls may nest;
+/// without a dropped root `f` just runs. Fails without running `f` when
+/// root can't be taken back, rather than running it as the user.
+pub fn as_root<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    {
+        let mut dropped = DROPPED.lock().unwrap_or_else(|e| e.into_inner());
+        if WRITING_HERE.get() == 0 {
+            while dropped
+                .as_ref()
+                .is_some_and(|state| state.elevated == 0 && state.writing > 0)
+            {
+                dropped = SWITCHED.wait(dropped).unwrap_or_else(|e| e.into_inner());
+            }
+        }
+        let Some(state) = dropped.as_mut() else {
+            drop(dropped);
+            return f();
+        };
+        if state.elevated == 0 {
+            if let Err(e) = imp::switch_to(0, 0, &state.root_groups) {
+                // A switch that failed halfway may have left some IDs root
+                let Invoker { uid, gid } = state.invoker;
+                if let Err(e) = imp::switch_to(uid, gid, &state.user_groups) {
+                    debug!("Failed to drop root again: {}", e);
+                }
+                return Err(e).context("Failed to take back root");
+            }
+        }
+        state.elevated += 1;
+        ELEVATED_HERE.set(ELEVATED_HERE.get() + 1);
+    }
+
+    // Switches back even if `f` panics
+    struct Restore;
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            ELEVATED_HERE.set(ELEVATED_HERE.get() - 1);
+            let mut dropped = DROPPED.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(state) = dropped.as_mut() {
+                state.elevated -= 1;
+                if state.elevated == 0 {
+                    let Invoker { uid, gid } = state.invoker;
+                    if let Err(e) = imp::switch_to(uid, gid, &state.user_groups) {
+                        debug!("Failed to drop root again: {}", e);
+                    }
+                    SWITCHED.notify_all();
+                }
+            }
+        }
+    }
+    let _restore = Restore;
+    f()
+}
+
+/// Run `f`, which writes files of the invoking user, as that user: wait
+/// until no [`as_root`] call is running, and keep new ones from starting
+/// until `f` returns. On a thread already running as root `f` just runs,
+/// since waiting for itself would never end.
+pub fn as_user<T>(f: impl FnOnce() -> T) -> T {
+    if ELEVATED_HERE.get() > 0 {
+        return f();
+    }
+    {
+        let mut dropped = DROPPED.lock().unwrap_or_else(|e| e.into_inner());
+        while dropped.as_ref().is_some_and(|state| state.elevated > 0) {
+            dropped = SWITCHED.wait(dropped).unwrap_or_else(|e| e.into_inner());
+        }
+        let Some(state) = dropped.as_mut() else {
+            drop(dropped);
+            return f();
+        };
+        state.writing += 1;
+        WRITING_HERE.set(WRITING_HERE.get() + 1);
+    }
+
+    // Lets root be taken again even if `f` panics
+    struct Done;
+    impl Drop for Done {
+        fn drop(&mut self) {
+            WRITING_HERE.set(WRITING_HERE.get() - 1);
+            let mut dropped = DROPPED.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(state) = dropped.as_mut() {
+                state.writing -= 1;
+                SWITCHED.notify_all();
+            }
+        }
+    }
+    let _done = Done;
+    f()
+}
+
+/// Run `f` through [`as_root`] when `root` is set
+pub fn as_root_if<T>(root: bool, f: impl FnOnce() -> Result<T>) -> Result<T> {
+    if root {
+        as_root(f)
+    } else {
+        f()
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "freebsd"))]
+mod imp {
+    use std::io;
+    use std::path::PathBuf;
+    use users::os::unix::UserExt;
+
+    use super::Invoker;
+
+    pub const SUPPORTED: bool = true;
+
+    pub fn is_root() -> bool {
+        users::get_effective_uid() == 0
+    }
+
+    /// The home directory and supplementary groups of the invoking user
+    pub fn user_details(invoker: Invoker) -> (Option<PathBuf>, Vec<u32>) {
+        let Some(user) = users::get_user_by_uid(invoker.uid) else {
+            return (None, vec![invoker.gid]);
+        };
+        let groups = users::get_user_groups(user.name(), invoker.gid)
+            .map(|groups| groups.iter().map(|group| group.gid()).collect())
+            .unwrap_or_else(|| vec![invoker.gid]);
+        (Some(user.home_dir().to_path_buf()), groups)
+    }
+
+    /// The current supplementary groups
+    pub fn groups() -> Vec<u32> {
+        let count = unsafe { libc::getgroups(0, std::ptr::null_mut()) };
+        let mut groups = vec![0; count.max(0) as usize];
+        let count = unsafe { libc::getgroups(count, groups.as_mut_ptr()) };
+        groups.truncate(count.max(0) as usize);
+        groups
+    }
+
+    /// Make `uid` and `gid` the real and effective IDs, keeping root as the
+    /// saved IDs so it can be taken back. Going back to root has to set the
+    /// user ID first, leaving it has to set it last.
+    pub fn switch_to(uid: u32, gid: u32, groups: &[u32]) -> io::Result<()> {
+        let check = |result: libc::c_int| {
+            if result == 0 {
+                Ok(())
+            } else {
+                Err(io::Error::last_os_error())
+            }
+        };
+        unsafe {
+            if uid == 0 {
+                check(libc::setresuid(0, 0, 0))?;
+            }
+            check(libc::setgroups(groups.len() as _, groups.as_ptr()))?;
+            check(libc::setresgid(gid, gid, 0))?;
+            if uid != 0 {
+                check(libc::setresuid(uid, uid, 0))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "freebsd")))]
+mod imp {
+    use std::io;
+    use std::path::PathBuf;
+
+    use super::Invoker;
+
+    pub const SUPPORTED: bool = false;
+
+    pub fn is_root() -> bool {
+        false
+    }
+
+    pub fn user_details(invoker: Invoker) -> (Option<PathBuf>, Vec<u32>) {
+        (None, vec![invoker.gid])
+    }
+
+    pub fn groups() -> Vec<u32> {
+        Vec::new()
+    }
+
+    pub fn switch_to(_uid: u32, _gid: u32, _groups: &[u32]) -> io::Result<()> {
+        Ok(())
+    }
+}
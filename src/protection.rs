@@ -0,0 +1,235 @@
+//! Detecting paths no one can delete: files on read-only mounts, and files
+//! or directories with the immutable or append-only attribute (`chattr +i`
+//! / `+a`, as shown by `lsattr`). Deletions check first, so a read-only
+//! cache is reported as one skipped path instead of an EPERM per file.
+//! Paths the user excluded in the config are skipped the same way, as are
+//! files younger than the minimum age a run may set and, unless configured
+//! otherwise, files on network filesystems.
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use crate::{exclude, netfs};
+
+/// Why a path can't be deleted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Protection {
+    /// The filesystem is mounted read-only
+    ReadOnly,
+    /// The file, or the directory holding it, is immutable
+    Immutable,
+    /// The file, or the directory holding it, only allows appending
+    AppendOnly,
+    /// The path matches an exclusion pattern from the config
+    Excluded,
+    /// The file was modified more recently than the run's minimum age
+    Recent,
+    /// The filesystem is on another machine, see [`crate::netfs`]
+    Network,
+}
+
+impl Protection {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Protection::ReadOnly => "read-only",
+            Protection::Immutable => "immutable",
+            Protection::AppendOnly => "append-only",
+            Protection::Excluded => "excluded",
+            Protection::Recent => "recently modified",
+            Protection::Network => "network filesystem",
+        }
+    }
+}
+
+impl fmt::Display for Protection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// Error for a deletion that was skipped because the path is protected
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Skipped {
+    pub path: PathBuf,
+    pub protection: Protection,
+}
+
+impl fmt::Display for Skipped {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} skipped: {}", self.path, self.protection)
+    }
+}
+
+impl std::error::Error for Skipped {}
+
+/// Files modified more recently than this are kept, when set
+static MIN_AGE: Lazy<Mutex<Option<Duration>>> = Lazy::new(|| Mutex::new(None));
+
+/// Keep files modified within `age`, or delete regardless of age with `None`
+pub fn set_min_age(age: Option<Duration>) {
+    *MIN_AGE.lock().unwrap_or_else(|e| e.into_inner()) = age;
+}
+
+/// The minimum age in effect
+pub fn min_age() -> Option<Duration> {
+    *MIN_AGE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Whether a file is younger than the minimum age. Directories never are;
+/// the files in them are checked one by one.
+pub fn is_recent(metadata: &Metadata) -> bool {
+    let Some(age) = min_age() else {
+        return false;
+    };
+    !metadata.is_dir()
+        && metadata.modified().is_ok_and(|modified| {
+            SystemTime::now()
+                .duration_since(modified)
+                .map_or(true, |elapsed| elapsed < age)
+        })
+}
+
+/// Paths skipped since the last [`take_skipped`]
+static SKIPPED: Lazy<Mutex<Vec<Skipped>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Why `path` can't be deleted, if it can't. Unlinking needs the directory
+/// holding the path to be writable, so that directory is checked as well.
+pub fn check(path: &Path) -> Option<Protection> {
+    if exclude::is_excluded(path) {
+        return Some(Protection::Excluded);
+    }
+    if std::fs::symlink_metadata(path).is_ok_and(|metadata| is_recent(&metadata)) {
+        return Some(Protection::Recent);
+    }
+    attributes(path).or_else(|| path.parent().and_then(contents_protection))
+}
+
+/// Why nothing inside `dir` can be deleted, if that is the case
+pub fn contents_protection(dir: &Path) -> Option<Protection> {
+    if exclude::is_excluded(dir) {
+        return Some(Protection::Excluded);
+    }
+    if is_read_only_mount(dir) {
+        return Some(Protection::ReadOnly);
+    }
+    if netfs::skip(dir) {
+        return Some(Protection::Network);
+    }
+    attributes(dir)
+}
+
+/// Fail with [`Skipped`] if `path` is protected, remembering it for the
+/// run summary
+pub fn guard(path: &Path) -> anyhow::Result<()> {
+    match check(path) {
+        Some(protection) => Err(record(path, protection).into()),
+        None => Ok(()),
+    }
+}
+
+/// Remember a skipped path for the run summary
+pub fn record(path: &Path, protection: Protection) -> Skipped {
+    let skipped = Skipped {
+        path: path.to_path_buf(),
+        protection,
+    };
+    SKIPPED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(skipped.clone());
+    skipped
+}
+
+/// The paths skipped since the last call, clearing the list
+pub fn take_skipped() -> Vec<Skipped> {
+    std::mem::take(&mut *SKIPPED.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// Summary of skipped paths such as "3 skipped: read-only", most common
+/// reason first. `None` when nothing was skipped.
+pub fn summarize(skipped: &[Skipped]) -> Option<String> {
+    let mut counts: Vec<(Protection, usize)> = Vec::new();
+    for skip in skipped {
+        match counts.iter_mut().find(|(p, _)| *p == skip.protection) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((skip.protection, 1)),
+        }
+    }
+    counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    let reasons: Vec<&str> = counts.iter().map(|(p, _)| p.label()).collect();
+    (!skipped.is_empty()).then(|| format!("{} skipped: {}", skipped.len(), reasons.join(", ")))
+}
+
+#[cfg(unix)]
+fn is_read_only_mount(dir: &Path) -> bool {
+    crate::mounts::statvfs(dir).is_some_and(|stat| stat.f_flag & libc::ST_RDONLY != 0)
+}
+
+#[cfg(not(unix))]
+fn is_read_only_mount(_dir: &Path) -> bool {
+    false
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "freebsd"))]
+fn from_flags(immutable: bool, append_only: bool) -> Option<Protection> {
+    if immutable {
+        Some(Protection::Immutable)
+    } else if append_only {
+        Some(Protection::AppendOnly)
+    } else {
+        None
+    }
+}
+
+/// Inode flags as read by `lsattr`. Symlinks have none, and are not followed.
+#[cfg(target_os = "linux")]
+fn attributes(path: &Path) -> Option<Protection> {
+    use std::os::fd::AsRawFd;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    const FS_IMMUTABLE_FL: libc::c_long = 0x10;
+    const FS_APPEND_FL: libc::c_long = 0x20;
+
+    let file = std::fs::OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_NOFOLLOW | libc::O_NONBLOCK)
+        .open(path)
+        .ok()?;
+    let mut flags: libc::c_long = 0;
+    // SAFETY: the descriptor is open and flags outlives the call
+    let result = unsafe { libc::ioctl(file.as_raw_fd(), libc::FS_IOC_GETFLAGS, &mut flags) };
+    if result != 0 {
+        return None;
+    }
+    from_flags(flags & FS_IMMUTABLE_FL != 0, flags & FS_APPEND_FL != 0)
+}
+
+/// BSD file flags (`chflags uchg`, `schg`, `uappnd`, `sappnd`)
+#[cfg(any(target_os = "macos", target_os = "freebsd"))]
+fn attributes(path: &Path) -> Option<Protection> {
+    #[cfg(target_os = "freebsd")]
+    use std::os::freebsd::fs::MetadataExt;
+    #[cfg(target_os = "macos")]
+    use std::os::macos::fs::MetadataExt;
+
+    const UF_IMMUTABLE: u32 = 0x2;
+    const UF_APPEND: u32 = 0x4;
+    const SF_IMMUTABLE: u32 = 0x20000;
+    const SF_APPEND: u32 = 0x40000;
+
+    let flags = std::fs::symlink_metadata(path).ok()?.st_flags();
+    from_flags(
+        flags & (UF_IMMUTABLE | SF_IMMUTABLE) != 0,
+        flags & (UF_APPEND | SF_APPEND) != 0,
+    )
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "freebsd")))]
+fn attributes(_path: &Path) -> Option<Protection> {
+    None
+}
@@ -0,0 +1,579 @@
+//! Quarantine: instead of deleting files outright, move them into
+//! `~/.local/share/cleansys/quarantine/<run>/` together with a manifest of
+//! where each one came from, so they can be inspected or put back.
+//!
+//! The manifest also keeps each file's owner, permissions and extended
+//! attributes (POSIX ACLs and the SELinux context among them). A copy
+//! across filesystems loses those, so [`Quarantine::restore`] sets them
+//! again and restored system files are usable as before.
+
+use anyhow::{bail, Context, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::audit;
+use crate::filesystem::filesystem;
+use crate::history::RunRecord;
+use crate::paths;
+use crate::pipeline::Pipeline;
+
+/// Where quarantined files go
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum QuarantineTarget {
+    /// cleansys' own quarantine directory, with a manifest per run
+    #[default]
+    Private,
+    /// The desktop Trash, restorable from the file manager
+    SystemTrash,
+}
+
+/// How long quarantine runs are kept. Without either limit they stay
+/// until restored or removed by hand.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QuarantineRetention {
+    /// Purge runs started longer ago than this
+    pub max_age: Option<Duration>,
+    /// Purge the oldest runs while all runs together take more bytes
+    pub max_bytes: Option<u64>,
+}
+
+static RETENTION: Lazy<Mutex<QuarantineRetention>> =
+    Lazy::new(|| Mutex::new(QuarantineRetention::default()));
+
+/// Set how long quarantine runs are kept, from the config file
+pub fn set_retention(retention: QuarantineRetention) {
+    *RETENTION.lock().unwrap_or_else(|e| e.into_inner()) = retention;
+}
+
+/// How long quarantine runs are kept
+pub fn retention() -> QuarantineRetention {
+    *RETENTION.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Purge the runs the configured retention no longer keeps. Done at
+/// startup and before scheduled runs, so the quarantine doesn't keep the
+/// space cleaning freed.
+pub fn enforce_retention() -> Result<PurgeReport> {
+    let retention = retention();
+    if retention == QuarantineRetention::default() {
+        return Ok(PurgeReport::default());
+    }
+    Quarantine::purge(&Quarantine::base_dir()?, &retention, SystemTime::now())
+}
+
+/// A file moved into quarantine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuarantineEntry {
+    /// Where the file was before
+    pub original: PathBuf,
+    /// Where it is stored inside the quarantine run directory
+    pub stored: PathBuf,
+    pub size: u64,
+    /// What to set again on restore; missing in manifests of older versions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<FileAttributes>,
+}
+
+/// Ownership, permissions and extended attributes of a quarantined file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileAttributes {
+    /// Permission bits, including setuid, setgid and sticky
+    pub mode: u32,
+    pub uid: u32,
+    pub gid: u32,
+    /// Extended attributes by name, such as `security.selinux` and
+    /// `system.posix_acl_access`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub xattrs: BTreeMap<String, Vec<u8>>,
+}
+
+impl FileAttributes {
+    /// The attributes of `path`, not following symlinks. None where there
+    /// are no Unix attributes to keep.
+    #[cfg(unix)]
+    pub fn capture(path: &Path) -> Option<Self> {
+        use std::os::unix::fs::MetadataExt;
+
+        let metadata = fs::symlink_metadata(path).ok()?;
+        Some(Self {
+            mode: metadata.mode() & 0o7777,
+            uid: metadata.uid(),
+            gid: metadata.gid(),
+            xattrs: xattr::list(path).unwrap_or_default(),
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn capture(_path: &Path) -> Option<Self> {
+        None
+    }
+
+    /// Set the attributes on `path` where they differ, returning what
+    /// couldn't be set. The owner goes first, since changing it clears the
+    /// setuid and setgid bits.
+    #[cfg(unix)]
+    pub fn apply(&self, path: &Path) -> Vec<String> {
+        use std::os::unix::fs::{lchown, MetadataExt, PermissionsExt};
+
+        let mut problems = Vec::new();
+        let metadata = match fs::symlink_metadata(path) {
+            Ok(metadata) => metadata,
+            Err(e) => return vec![e.to_string()],
+        };
+        if (metadata.uid(), metadata.gid()) != (self.uid, self.gid) {
+            if let Err(e) = lchown(path, Some(self.uid), Some(self.gid)) {
+                problems.push(format!("owner {}:{}: {}", self.uid, self.gid, e));
+            }
+        }
+        if !metadata.is_symlink() {
+            let permissions = fs::Permissions::from_mode(self.mode);
+            if let Err(e) = fs::set_permissions(path, permissions) {
+                problems.push(format!("mode {:o}: {}", self.mode, e));
+            }
+        }
+        let current = xattr::list(path).unwrap_or_default();
+        for (name, value) in &self.xattrs {
+            if current.get(name) != Some(value) {
+                if let Err(e) = xattr::set(path, name, value) {
+                    problems.push(format!("{}: {}", name, e));
+                }
+            }
+        }
+        problems
+    }
+
+    #[cfg(not(unix))]
+    pub fn apply(&self, _path: &Path) -> Vec<String> {
+        vec!["Unix attributes can't be set on this system".to_string()]
+    }
+}
+
+/// Outcome of restoring a quarantine run
+#[derive(Debug, Default)]
+pub struct RestoreReport {
+    /// Files put back where they came from
+    pub restored: Vec<PathBuf>,
+    /// Restored files whose attributes couldn't all be set, with what failed
+    pub incomplete: Vec<(PathBuf, String)>,
+    /// Files that couldn't be put back, with the reason. They stay in the
+    /// run and in its manifest.
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+/// Outcome of moving a batch of files
+#[derive(Debug, Default)]
+pub struct QuarantineReport {
+    pub moved: Vec<QuarantineEntry>,
+    /// Files that couldn't be moved, with the reason
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+impl QuarantineReport {
+    /// Total bytes moved into quarantine
+    pub fn bytes_moved(&self) -> u64 {
+        self.moved.iter().map(|e| e.size).sum()
+    }
+}
+
+/// Outcome of purging old quarantine runs
+#[derive(Debug, Default)]
+pub struct PurgeReport {
+    /// Run directories removed for good
+    pub purged: Vec<PathBuf>,
+    pub bytes_freed: u64,
+    /// Runs that couldn't be removed, with the reason
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+/// One quarantine run directory
+pub struct Quarantine {
+    dir: PathBuf,
+}
+
+impl Quarantine {
+    /// Directory holding every quarantine run
+    pub fn base_dir() -> Result<PathBuf> {
+        Ok(paths::data_dir()?.join("quarantine"))
+    }
+
+    /// Start a new run directory named after the current time
+    pub fn create() -> Result<Self> {
+        let base = Self::base_dir()?;
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+
+        let mut dir = base.join(stamp.to_string());
+        let mut suffix = 1;
+        while dir.exists() {
+            dir = base.join(format!("{}-{}", stamp, suffix));
+            suffix += 1;
+        }
+        Self::at(dir)
+    }
+
+    /// Use a specific run directory, creating it if needed
+    pub fn at(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create quarantine directory {:?}", dir))?;
+        Ok(Self { dir })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Path of the manifest listing what this run moved
+    pub fn manifest_path(&self) -> PathBuf {
+        self.dir.join("manifest.json")
+    }
+
+    /// Where a file is stored in this run: its absolute path below `files/`
+    pub fn stored_path(&self, original: &Path) -> PathBuf {
+        let relative = original.strip_prefix("/").unwrap_or(original);
+        self.dir.join("files").join(relative)
+    }
+
+    /// Move files into quarantine across the pipeline's workers and write the
+    /// manifest. Individual failures are reported rather than aborting the batch.
+    pub fn move_files(&self, paths: Vec<PathBuf>, pipeline: &Pipeline) -> Result<QuarantineReport> {
+        let mut report = QuarantineReport::default();
+
+        pipeline.run(
+            |tx| {
+                for path in paths {
+                    if tx.send(path).is_err() {
+                        return;
+                    }
+                }
+            },
+            |path: PathBuf| {
+                Some(
+                    self.move_file(&path)
+                        .map_err(|e| (path, format!("{:#}", e))),
+                )
+            },
+            |result| match result {
+                Ok(entry) => report.moved.push(entry),
+                Err(failure) => report.failed.push(failure),
+            },
+        );
+
+        report.moved.sort_by(|a, b| a.original.cmp(&b.original));
+        self.write_manifest(&report.moved)?;
+        Ok(report)
+    }
+
+    fn move_file(&self, original: &Path) -> Result<QuarantineEntry> {
+        // Record absolute paths so the manifest is usable from anywhere
+        let original = &std::path::absolute(original)?;
+        let size = fs::symlink_metadata(original)
+            .with_context(|| format!("Failed to stat {:?}", original))?
+            .len();
+        let attributes = FileAttributes::capture(original);
+        let stored = self.stored_path(original);
+        audit::record(original)?;
+        if let Some(parent) = stored.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        move_across(original, &stored)?;
+
+        Ok(QuarantineEntry {
+            original: original.to_path_buf(),
+            stored,
+            size,
+            attributes,
+        })
+    }
+
+    fn write_manifest(&self, moved: &[QuarantineEntry]) -> Result<()> {
+        let path = self.manifest_path();
+        fs::write(&path, serde_json::to_string_pretty(moved)?)
+            .with_context(|| format!("Failed to write quarantine manifest {:?}", path))
+    }
+
+    /// Read the manifest of a run directory
+    pub fn load_manifest(dir: &Path) -> Result<Vec<QuarantineEntry>> {
+        let path = dir.join("manifest.json");
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read quarantine manifest {:?}", path))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Invalid quarantine manifest {:?}", path))
+    }
+
+    /// Run directories, newest first. Names are the time the run started,
+    /// with a counter for runs started in the same second.
+    pub fn runs(base: &Path) -> Result<Vec<PathBuf>> {
+        if !base.exists() {
+            return Ok(Vec::new());
+        }
+        let entries = fs::read_dir(base)
+            .with_context(|| format!("Failed to read quarantine directory {:?}", base))?;
+        let mut runs: Vec<((u64, u64), PathBuf)> = entries
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| Some((run_order(&entry.path())?, entry.path())))
+            .collect();
+        runs.sort_by_key(|(order, _)| std::cmp::Reverse(*order));
+        Ok(runs.into_iter().map(|(_, path)| path).collect())
+    }
+
+    /// How many runs `base` holds and the bytes they take together
+    pub fn usage(base: &Path) -> Result<(usize, u64)> {
+        let runs = Self::runs(base)?;
+        let bytes = runs
+            .iter()
+            .map(|run| filesystem().size(run).unwrap_or(0))
+            .sum();
+        Ok((runs.len(), bytes))
+    }
+
+    /// Remove the runs in `base` that `retention` no longer keeps: those
+    /// started more than `max_age` before `now`, then the oldest of the
+    /// rest while together they take more than `max_bytes`
+    pub fn purge(
+        base: &Path,
+        retention: &QuarantineRetention,
+        now: SystemTime,
+    ) -> Result<PurgeReport> {
+        let now = now.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        let mut runs: Vec<(PathBuf, u64)> = Self::runs(base)?
+            .into_iter()
+            .map(|run| {
+                let size = filesystem().size(&run).unwrap_or(0);
+                (run, size)
+            })
+            .collect();
+        let mut total: u64 = runs.iter().map(|(_, size)| size).sum();
+
+        // Oldest first
+        runs.reverse();
+        let mut report = PurgeReport::default();
+        for (run, size) in runs {
+            let expired = retention.max_age.is_some_and(|max_age| {
+                run_order(&run)
+                    .is_some_and(|(started, _)| now.saturating_sub(started) > max_age.as_secs())
+            });
+            let over_cap = retention.max_bytes.is_some_and(|max| total > max);
+            if !expired && !over_cap {
+                continue;
+            }
+            match fs::remove_dir_all(&run) {
+                Ok(()) => {
+                    total -= size;
+                    report.bytes_freed += size;
+                    report.purged.push(run);
+                }
+                Err(e) => report.failed.push((run, e.to_string())),
+            }
+        }
+        Ok(report)
+    }
+
+    /// The newest run in `base` that still holds files to restore
+    pub fn latest(base: &Path) -> Result<Option<PathBuf>> {
+        Ok(Self::runs(base)?
+            .into_iter()
+            .find(|run| Self::load_manifest(run).is_ok_and(|entries| !entries.is_empty())))
+    }
+
+    /// The run directory in `base` to restore to undo the most recent run
+    /// in `history`, oldest first. Fails saying why when that run can't be
+    /// undone: it deleted its files rather than quarantining them, or they
+    /// were restored or purged since.
+    pub fn undo_target(base: &Path, history: &[RunRecord]) -> Result<PathBuf> {
+        let Some(last) = history.last() else {
+            bail!("No run in the history to undo");
+        };
+        let Some(name) = &last.quarantine else {
+            bail!(
+                "The last run (#{} at {}) deleted its files instead of quarantining them, so it can't be undone",
+                last.id,
+                last.time
+            );
+        };
+        let dir = base.join(name);
+        if !Self::load_manifest(&dir).is_ok_and(|entries| !entries.is_empty()) {
+            bail!(
+                "Nothing of the last run (#{} at {}) is left in quarantine to restore",
+                last.id,
+                last.time
+            );
+        }
+        Ok(dir)
+    }
+
+    /// Put the files of a run directory back where they came from, with the
+    /// attributes they had. Files whose original path is taken again are
+    /// left in the run, and the manifest is rewritten to list only those
+    /// still there.
+    pub fn restore(dir: &Path) -> Result<RestoreReport> {
+        let run = Self {
+            dir: dir.to_path_buf(),
+        };
+        let mut report = RestoreReport::default();
+        let mut remaining = Vec::new();
+
+        for entry in Self::load_manifest(dir)? {
+            match restore_entry(&entry) {
+                Ok(problems) => {
+                    if !problems.is_empty() {
+                        report
+                            .incomplete
+                            .push((entry.original.clone(), problems.join(", ")));
+                    }
+                    report.restored.push(entry.original);
+                }
+                Err(e) => {
+                    report
+                        .failed
+                        .push((entry.original.clone(), format!("{:#}", e)));
+                    remaining.push(entry);
+                }
+            }
+        }
+
+        run.write_manifest(&remaining)?;
+        Ok(report)
+    }
+}
+
+/// Move one file back, returning the attributes that couldn't be set
+fn restore_entry(entry: &QuarantineEntry) -> Result<Vec<String>> {
+    let original = &entry.original;
+    if fs::symlink_metadata(original).is_ok() {
+        anyhow::bail!("{:?} exists again", original);
+    }
+    if let Some(parent) = original.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    move_across(&entry.stored, original)?;
+    Ok(entry
+        .attributes
+        .as_ref()
+        .map(|attributes| attributes.apply(original))
+        .unwrap_or_default())
+}
+
+/// The start time and counter in the name of a run directory
+fn run_order(run: &Path) -> Option<(u64, u64)> {
+    let name = run.file_name()?.to_string_lossy();
+    let (stamp, counter) = name.split_once('-').unwrap_or((&name, "0"));
+    Some((stamp.parse().ok()?, counter.parse().ok()?))
+}
+
+/// Rename `from` to `to`, or copy and remove it on another filesystem
+fn move_across(from: &Path, to: &Path) -> Result<()> {
+    match fs::rename(from, to) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == ErrorKind::CrossesDevices => {
+            fs::copy(from, to).with_context(|| format!("Failed to copy {:?}", from))?;
+            fs::remove_file(from).with_context(|| format!("Failed to remove {:?}", from))
+        }
+        Err(e) => Err(e).with_context(|| format!("Failed to move {:?}", from)),
+    }
+}
+
+/// Extended attributes, read and written without following symlinks
+#[cfg(target_os = "linux")]
+mod xattr {
+    use std::collections::BTreeMap;
+    use std::ffi::CString;
+    use std::io;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+
+    fn c_string(bytes: &[u8]) -> io::Result<CString> {
+        CString::new(bytes).map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))
+    }
+
+    /// Every extended attribute of `path` this process may read
+    pub fn list(path: &Path) -> io::Result<BTreeMap<String, Vec<u8>>> {
+        let path = c_string(path.as_os_str().as_bytes())?;
+        // SAFETY: path is NUL-terminated; a null buffer asks for the size
+        let size = unsafe { libc::llistxattr(path.as_ptr(), std::ptr::null_mut(), 0) };
+        if size < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut names = vec![0u8; size as usize];
+        // SAFETY: names is valid for names.len() bytes
+        let size =
+            unsafe { libc::llistxattr(path.as_ptr(), names.as_mut_ptr().cast(), names.len()) };
+        if size < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        names.truncate(size as usize);
+
+        let mut attributes = BTreeMap::new();
+        for name in names.split(|&b| b == 0).filter(|name| !name.is_empty()) {
+            let c_name = c_string(name)?;
+            // SAFETY: as above, a null buffer asks for the size
+            let size =
+                unsafe { libc::lgetxattr(path.as_ptr(), c_name.as_ptr(), std::ptr::null_mut(), 0) };
+            if size < 0 {
+                continue;
+            }
+            let mut value = vec![0u8; size as usize];
+            // SAFETY: value is valid for value.len() bytes
+            let size = unsafe {
+                libc::lgetxattr(
+                    path.as_ptr(),
+                    c_name.as_ptr(),
+                    value.as_mut_ptr().cast(),
+                    value.len(),
+                )
+            };
+            if size < 0 {
+                continue;
+            }
+            value.truncate(size as usize);
+            attributes.insert(String::from_utf8_lossy(name).into_owned(), value);
+        }
+        Ok(attributes)
+    }
+
+    pub fn set(path: &Path, name: &str, value: &[u8]) -> io::Result<()> {
+        let path = c_string(path.as_os_str().as_bytes())?;
+        let name = c_string(name.as_bytes())?;
+        // SAFETY: both strings are NUL-terminated and value is valid for its length
+        let result = unsafe {
+            libc::lsetxattr(
+                path.as_ptr(),
+                name.as_ptr(),
+                value.as_ptr().cast(),
+                value.len(),
+                0,
+            )
+        };
+        if result == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+}
+
+/// Other systems keep no extended attributes in the manifest
+#[cfg(all(unix, not(target_os = "linux")))]
+mod xattr {
+    use std::collections::BTreeMap;
+    use std::io;
+    use std::path::Path;
+
+    pub fn list(_path: &Path) -> io::Result<BTreeMap<String, Vec<u8>>> {
+        Ok(BTreeMap::new())
+    }
+
+    pub fn set(_path: &Path, _name: &str, _value: &[u8]) -> io::Result<()> {
+        Err(io::Error::from(io::ErrorKind::Unsupported))
+    }
+}
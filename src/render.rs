@@ -1,22 +1,62 @@
+use std::path::PathBuf;
+
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     symbols,
     text::{Line, Span},
-    widgets::{Axis, Block, Borders, Chart, Dataset, List, ListItem, Paragraph, Wrap},
+    widgets::{
+        Axis, Block, Borders, Chart, Clear, Dataset, Gauge, List, ListItem, Paragraph, Wrap,
+    },
     Frame,
 };
 // Using tui-checkbox library for consistent checkbox symbols across the application
 use tui_checkbox::{symbols as checkbox_symbols, Checkbox};
 
-use crate::app::{App, ChartType, CleanedItemType, Status};
+use crate::app::{
+    App, ChartType, CleanedItemType, FilterMode, FooterHint, JournalRow, ProgressPane, ScanState,
+    SortMode, Status, ViewMode,
+};
+use crate::cleaners::log_backends;
+use crate::concurrency::{self, StorageKind};
+use crate::deferred;
+use crate::markdown;
 use crate::pie_chart::create_pie_chart_from_distribution;
-use crate::utils::format_size;
+use crate::priority;
+use crate::scanner;
+use crate::schedule::format_countdown;
+use crate::scope;
+use crate::size_heat::{size_span, sized_span};
+use crate::sysinfo::{usage_bar, SystemInfo};
+use crate::terminal;
+use crate::utils::{color_enabled, format_size, DiskSpace};
 
 pub fn ui(f: &mut Frame, app: &mut App) {
     // Update animation frame if needed
     app.update_animation();
 
+    // Shrunk below what the layout can handle: say so instead of drawing a
+    // broken interface
+    let area = f.area();
+    if area.width < terminal::MIN_WIDTH || area.height < terminal::MIN_HEIGHT {
+        let message = Paragraph::new(vec![
+            Line::from(Span::styled(
+                "Terminal too small",
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            Line::from(format!("{}x{}", area.width, area.height)),
+            Line::from(format!(
+                "Resize to {}x{} or press q",
+                terminal::MIN_WIDTH,
+                terminal::MIN_HEIGHT
+            )),
+        ])
+        .alignment(ratatui::layout::Alignment::Center)
+        .wrap(Wrap { trim: true });
+        f.render_widget(message, area);
+        return;
+    }
+
     // Adjust title and footer heights based on terminal size
     let (title_height, footer_height, min_content_height) = if app.terminal_height < 20 {
         // Very small terminals: minimal UI
@@ -43,9 +83,7 @@ pub fn ui(f: &mut Frame, app: &mut App) {
 
     render_title(f, app, chunks[0]);
 
-    if app.show_help {
-        render_help(f, chunks[1]);
-    } else if app.is_running || app.show_progress_screen {
+    if app.is_running || app.show_progress_screen {
         render_progress_screen(f, app, chunks[1]);
     } else {
         render_main_content(f, app, chunks[1]);
@@ -53,10 +91,50 @@ pub fn ui(f: &mut Frame, app: &mut App) {
 
     render_footer(f, app, chunks[2]);
 
-    // Render password prompt as overlay if visible
+    // Render dialogs as overlays if visible
+    if app.confirm_dialog.is_visible() {
+        app.confirm_dialog.render(f, f.area());
+    }
+
+    if app.sudo_dialog.is_visible() {
+        app.sudo_dialog.render(f, f.area());
+    }
+
+    if app.limit_dialog.is_visible() {
+        app.limit_dialog.render(f, f.area());
+    }
+
+    if app.trim_dialog.is_visible() {
+        app.trim_dialog.render(f, f.area());
+    }
+
+    if app.undo_dialog.is_visible() {
+        app.undo_dialog.render(f, f.area());
+    }
+
+    if app.quit_dialog.is_visible() {
+        app.quit_dialog.render(f, f.area());
+    }
+
     if app.password_prompt.is_visible() {
         app.password_prompt.render(f, f.area());
     }
+
+    app.action_menu.render(f, f.area());
+    app.toasts.render_history(f, f.area());
+    if app.show_about {
+        render_about(f, app, f.area());
+    }
+
+    let help_context = app.help_context();
+    app.help.render(f, chunks[1], help_context);
+
+    // NO_COLOR: keep modifiers like bold, drop every color
+    if !color_enabled() {
+        for cell in f.buffer_mut().content.iter_mut() {
+            cell.set_fg(Color::Reset).set_bg(Color::Reset);
+        }
+    }
 }
 
 fn render_title(f: &mut Frame, app: &App, area: Rect) {
@@ -118,6 +196,18 @@ fn render_title(f: &mut Frame, app: &App, area: Rect) {
 }
 
 fn render_main_content(f: &mut Frame, app: &mut App, area: Rect) {
+    let area = match &app.system_info {
+        Some(info) => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(5), Constraint::Min(0)])
+                .split(area);
+            render_system_info(f, info, chunks[0]);
+            chunks[1]
+        }
+        None => area,
+    };
+
     // Adjust layout based on terminal width
     let (categories_percent, content_percent) = if app.terminal_width < 80 {
         // Narrow terminals: give more space to content
@@ -130,18 +220,38 @@ fn render_main_content(f: &mut Frame, app: &mut App, area: Rect) {
         (35, 65)
     };
 
-    let horizontal_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
+    let constraints = if app.categories_collapsed {
+        // Room for "9 (99)" inside the borders
+        [Constraint::Length(8), Constraint::Min(0)]
+    } else {
+        [
             Constraint::Percentage(categories_percent), // Categories
             Constraint::Percentage(content_percent),    // Cleaners/Details
-        ])
+        ]
+    };
+    let horizontal_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(constraints)
         .split(area);
 
     render_categories(f, app, horizontal_chunks[0]);
 
     if app.detailed_view {
-        render_details(f, app, horizontal_chunks[1]);
+        // Keep the cleaner list visible above the detail pane
+        app.ensure_selected_scan();
+        let detail_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+            .split(horizontal_chunks[1]);
+        render_cleaners(f, app, detail_chunks[0]);
+        render_details(f, app, detail_chunks[1]);
+    } else if app.view_mode == ViewMode::Performance || app.show_performance_stats {
+        let perf_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(5), Constraint::Length(10)])
+            .split(horizontal_chunks[1]);
+        render_cleaners(f, app, perf_chunks[0]);
+        render_performance(f, app, perf_chunks[1]);
     } else {
         render_cleaners(f, app, horizontal_chunks[1]);
     }
@@ -166,20 +276,8 @@ fn render_unified_progress_view(f: &mut Frame, app: &mut App, area: Rect) {
     let main_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Percentage(if app.terminal_height >= 35 {
-                55
-            } else if app.terminal_height >= 25 {
-                50
-            } else {
-                45
-            }), // Combined progress overview - responsive percentage
-            Constraint::Percentage(if app.terminal_height >= 35 {
-                45
-            } else if app.terminal_height >= 25 {
-                50
-            } else {
-                55
-            }), // Removed items window - responsive percentage
+            Constraint::Percentage(app.progress_split()), // Combined progress overview
+            Constraint::Percentage(100 - app.progress_split()), // Removed items window
         ])
         .margin(1)
         .split(area);
@@ -237,11 +335,7 @@ fn render_progress_stats_and_chart(f: &mut Frame, app: &App, area: Rect) {
     let elapsed_time = app.get_elapsed_time();
     let total_ops = app.operation_count;
     let completed_ops = total_ops.saturating_sub(app.errors_count);
-    let progress_percent = if total_ops > 0 {
-        (completed_ops * 100) / total_ops
-    } else {
-        0
-    };
+    let progress_percent = (completed_ops * 100).checked_div(total_ops).unwrap_or(0);
 
     // Responsive layout based on terminal width - give chart much more space
     let show_chart = area.width >= 80; // Hide chart on narrow terminals
@@ -271,7 +365,7 @@ fn render_progress_stats_and_chart(f: &mut Frame, app: &App, area: Rect) {
     };
 
     // Left side: Progress stats
-    let stats_lines = vec![
+    let mut stats_lines = vec![
         Line::from(vec![
             Span::styled(
                 "Progress: ",
@@ -304,7 +398,7 @@ fn render_progress_stats_and_chart(f: &mut Frame, app: &App, area: Rect) {
         Line::from(vec![
             Span::styled("✅ ", Style::default().fg(Color::Green)),
             Span::styled(
-                format!("{} OK", completed_ops),
+                format!("{} OK", completed_ops.saturating_sub(app.partial_count)),
                 Style::default().fg(Color::Green),
             ),
             Span::raw("  "),
@@ -326,6 +420,11 @@ fn render_progress_stats_and_chart(f: &mut Frame, app: &App, area: Rect) {
                 format!("{} Errors", app.errors_count),
                 Style::default().fg(Color::Red),
             ),
+            Span::raw("  "),
+            Span::styled(
+                format!("◐ {} Partial", app.partial_count),
+                Style::default().fg(Color::Yellow),
+            ),
         ]),
         Line::from(vec![
             Span::styled(
@@ -342,6 +441,26 @@ fn render_progress_stats_and_chart(f: &mut Frame, app: &App, area: Rect) {
             ),
         ]),
     ];
+    // A large directory being deleted by the running cleaner
+    if let Some(progress) = &app.deletion_progress {
+        stats_lines.push(Line::from(vec![
+            Span::styled("🗑 ", Style::default().fg(Color::Yellow)),
+            Span::styled(
+                format!("{:.0}%", progress.percent()),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!(
+                " {}/{} files, {}/{} of {}",
+                progress.files_done,
+                progress.files_total,
+                format_size(progress.bytes_done),
+                format_size(progress.bytes_total),
+                progress.root.display()
+            )),
+        ]));
+    }
 
     let stats_para = Paragraph::new(stats_lines);
     f.render_widget(stats_para, horizontal_chunks[0]);
@@ -366,11 +485,7 @@ fn render_ultra_compact_view(f: &mut Frame, app: &App, area: Rect) {
     let elapsed_time = app.get_elapsed_time();
     let total_ops = app.operation_count;
     let completed_ops = total_ops.saturating_sub(app.errors_count);
-    let progress_percent = if total_ops > 0 {
-        (completed_ops * 100) / total_ops
-    } else {
-        0
-    };
+    let progress_percent = (completed_ops * 100).checked_div(total_ops).unwrap_or(0);
 
     // Ultra-compact single block with essential info only
     let compact_lines = vec![
@@ -393,7 +508,12 @@ fn render_ultra_compact_view(f: &mut Frame, app: &App, area: Rect) {
         ]),
         Line::from(vec![
             Span::styled(
-                format!("✅{} ❌{} ", completed_ops, app.errors_count),
+                format!(
+                    "✅{} ◐{} ❌{} ",
+                    completed_ops.saturating_sub(app.partial_count),
+                    app.partial_count,
+                    app.errors_count
+                ),
                 Style::default().fg(Color::White),
             ),
             Span::styled(
@@ -432,7 +552,7 @@ fn render_ultra_compact_view(f: &mut Frame, app: &App, area: Rect) {
 
 fn render_vertical_bar_chart(f: &mut Frame, app: &App, area: Rect) {
     // Get real data from cleaned items
-    let category_distribution = app.get_category_distribution();
+    let category_distribution = app.chart_entries();
 
     // Only show chart if we have real data
     if !category_distribution.is_empty() {
@@ -484,6 +604,24 @@ fn render_vertical_bar_chart(f: &mut Frame, app: &App, area: Rect) {
                     .add_modifier(Modifier::BOLD),
             )
             .data(&chart_data);
+        let highlighted: Vec<(f64, f64)> = app
+            .highlighted_legend()
+            .and_then(|i| chart_data.get(i).copied())
+            .into_iter()
+            .collect();
+        let highlight = Dataset::default()
+            .marker(symbols::Marker::Block)
+            .style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .data(&highlighted);
+        let border = if app.progress_pane == ProgressPane::Chart {
+            Color::Yellow
+        } else {
+            Color::Cyan
+        };
 
         // Create x-axis labels
         let x_labels = if category_names.len() <= 3 {
@@ -513,7 +651,7 @@ fn render_vertical_bar_chart(f: &mut Frame, app: &App, area: Rect) {
             Span::raw(format!("{}", y_max as u64)),
         ];
 
-        let chart = Chart::new(vec![dataset])
+        let chart = Chart::new(vec![dataset, highlight])
             .block(
                 Block::default()
                     .title(if area.width < 50 {
@@ -527,7 +665,7 @@ fn render_vertical_bar_chart(f: &mut Frame, app: &App, area: Rect) {
                             .add_modifier(Modifier::BOLD),
                     )
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::Cyan)),
+                    .border_style(Style::default().fg(border)),
             )
             .x_axis(
                 Axis::default()
@@ -549,6 +687,23 @@ fn render_vertical_bar_chart(f: &mut Frame, app: &App, area: Rect) {
 }
 
 fn render_operations_summary(f: &mut Frame, app: &App, area: Rect) {
+    // Once a run is over its results take the place of the operations list
+    if !app.is_running && !app.run_results.is_empty() {
+        let mut results = vec![ListItem::new(Line::from(vec![Span::styled(
+            "📝 RESULTS",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )]))];
+        results.extend(
+            app.run_results
+                .iter()
+                .map(|msg| ListItem::new(Line::from(msg.as_str()))),
+        );
+        f.render_widget(List::new(results), area);
+        return;
+    }
+
     // Split into user and system operations columns
     let columns = Layout::default()
         .direction(Direction::Horizontal)
@@ -662,7 +817,9 @@ fn render_pie_chart_distribution(f: &mut Frame, app: &App, area: Rect) {
 
         let responsive_chart = pie_chart
             .show_percentages(area.width >= 40)
-            .show_legend(area.width >= 50 || area.height >= 16);
+            .show_legend(area.width >= 50 || area.height >= 16)
+            .highlight(app.highlighted_legend())
+            .focused(app.progress_pane == ProgressPane::Chart);
 
         responsive_chart.render(f, area);
     }
@@ -681,7 +838,9 @@ fn render_pie_chart_size_distribution(f: &mut Frame, app: &App, area: Rect) {
 
         let responsive_chart = pie_chart
             .show_percentages(area.width >= 40)
-            .show_legend(area.width >= 50 || area.height >= 16);
+            .show_legend(area.width >= 50 || area.height >= 16)
+            .highlight(app.highlighted_legend())
+            .focused(app.progress_pane == ProgressPane::Chart);
 
         responsive_chart.render(f, area);
     }
@@ -689,11 +848,23 @@ fn render_pie_chart_size_distribution(f: &mut Frame, app: &App, area: Rect) {
 
 fn render_removed_items_window(f: &mut Frame, app: &mut App, area: Rect) {
     let title = if app.is_running {
-        "📋 Operation Progress"
+        match app.operation_logs.dropped() {
+            0 => "📋 Operation Progress".to_string(),
+            dropped => format!("📋 Operation Progress ({} lines dropped)", dropped),
+        }
     } else if app.show_progress_screen {
-        "📋 Cleaning Results - Removed Items"
+        "📋 Cleaning Results - Removed Items".to_string()
+    } else {
+        "📋 Removed Items Details".to_string()
+    };
+    let title = match &app.legend_filter {
+        Some(filter) => format!("{} - {} only (ESC shows all)", title, filter),
+        None => title,
+    };
+    let border = if app.progress_pane == ProgressPane::Chart {
+        Color::DarkGray
     } else {
-        "📋 Removed Items Details"
+        Color::Yellow
     };
 
     let block = Block::default()
@@ -704,7 +875,7 @@ fn render_removed_items_window(f: &mut Frame, app: &mut App, area: Rect) {
                 .add_modifier(Modifier::BOLD),
         )
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Yellow));
+        .border_style(Style::default().fg(border));
 
     let inner_area = block.inner(area);
 
@@ -731,44 +902,53 @@ fn render_removed_items_window(f: &mut Frame, app: &mut App, area: Rect) {
             ])));
         }
     } else {
-        // Get sample cleaned items for display plus additional entries for demo
-        let filtered_items = app.get_filtered_detailed_items();
-
-        if !filtered_items.is_empty() {
-            for (index, item) in filtered_items.iter().enumerate() {
-                let icon = match item.item_type {
-                    CleanedItemType::File => "📄",
-                    CleanedItemType::Directory => "📁",
-                    CleanedItemType::Log => "📝",
-                };
-
-                // File path and size on one line
-                display_items.push(ListItem::new(Line::from(vec![
-                    Span::styled(format!("{} ", icon), Style::default().fg(Color::Yellow)),
-                    Span::styled(item.path.clone(), Style::default().fg(Color::White)),
-                    Span::raw(" "),
-                    Span::styled(
-                        format!("({})", format_size(item.size)),
-                        Style::default()
-                            .fg(Color::Green)
-                            .add_modifier(Modifier::BOLD),
-                    ),
-                ])));
-
-                // Category and cleaner info on next line (indented)
-                display_items.push(ListItem::new(Line::from(vec![
-                    Span::raw("   "),
-                    Span::styled("📂 ", Style::default().fg(Color::Blue)),
-                    Span::styled(item.category.clone(), Style::default().fg(Color::Blue)),
-                    Span::raw(" • "),
-                    Span::styled("🔧 ", Style::default().fg(Color::Cyan)),
-                    Span::styled(item.cleaner_name.clone(), Style::default().fg(Color::Cyan)),
-                ])));
-
-                // Add spacing between entries
-                if index < filtered_items.len() - 1 {
-                    display_items.push(ListItem::new(Line::from(vec![])));
-                }
+        // Removed items grouped by cleaner, one collapsible group each
+        let rows = app.journal_rows();
+
+        if !rows.is_empty() {
+            for row in rows {
+                display_items.push(match row {
+                    JournalRow::Header {
+                        cleaner,
+                        category,
+                        count,
+                        size,
+                        collapsed,
+                    } => ListItem::new(Line::from(vec![
+                        Span::styled(
+                            if collapsed { "▶ " } else { "▼ " },
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                        Span::styled("🔧 ", Style::default().fg(Color::Cyan)),
+                        Span::styled(
+                            cleaner.to_string(),
+                            Style::default()
+                                .fg(Color::Cyan)
+                                .add_modifier(Modifier::BOLD),
+                        ),
+                        Span::styled(format!(" ({})", category), Style::default().fg(Color::Blue)),
+                        Span::raw(format!(
+                            " • {} item{} • ",
+                            count,
+                            if count == 1 { "" } else { "s" }
+                        )),
+                        size_span(size),
+                    ])),
+                    JournalRow::Item(item) => {
+                        let icon = match item.item_type {
+                            CleanedItemType::File => "📄",
+                            CleanedItemType::Directory => "📁",
+                            CleanedItemType::Log => "📝",
+                        };
+                        ListItem::new(Line::from(vec![
+                            Span::raw("    "),
+                            Span::styled(format!("{} ", icon), Style::default().fg(Color::Yellow)),
+                            Span::styled(item.path.clone(), Style::default().fg(Color::White)),
+                            Span::raw(" "),
+                            sized_span(format!("({})", format_size(item.size)), item.size),
+                        ]))
+                    }
+                });
             }
         } else if !app.is_running && app.show_progress_screen && app.total_bytes_cleaned > 0 {
             // Show summary when cleaning is complete but no detailed items
@@ -787,7 +967,7 @@ fn render_removed_items_window(f: &mut Frame, app: &mut App, area: Rect) {
                 Span::styled("📊 ", Style::default().fg(Color::Cyan)),
                 Span::styled("Total space freed: ", Style::default().fg(Color::White)),
                 Span::styled(
-                    format_size(app.total_bytes_cleaned),
+                    deferred::describe(app.total_bytes_cleaned, app.total_bytes_deferred),
                     Style::default()
                         .fg(Color::Green)
                         .add_modifier(Modifier::BOLD),
@@ -803,10 +983,7 @@ fn render_removed_items_window(f: &mut Frame, app: &mut App, area: Rect) {
                             Span::styled("🔧 ", Style::default().fg(Color::Yellow)),
                             Span::styled(item.name.clone(), Style::default().fg(Color::White)),
                             Span::raw(": "),
-                            Span::styled(
-                                format_size(item.bytes_cleaned),
-                                Style::default().fg(Color::Green),
-                            ),
+                            size_span(item.bytes_cleaned),
                         ])));
                     }
                 }
@@ -839,14 +1016,144 @@ fn render_removed_items_window(f: &mut Frame, app: &mut App, area: Rect) {
     f.render_widget(block, area);
 }
 
+fn render_disk_gauges(f: &mut Frame, disks: &[(PathBuf, DiskSpace)], area: Rect) {
+    let block = Block::default().title("💽 Disks").borders(Borders::ALL);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(1); disks.len()])
+        .split(inner);
+    for ((mount, space), row) in disks.iter().zip(rows.iter()) {
+        let ratio = space.used_ratio();
+        let color = if ratio > 0.9 {
+            Color::Red
+        } else {
+            Color::Green
+        };
+        let gauge = Gauge::default()
+            .gauge_style(Style::default().fg(color).bg(Color::Black))
+            .ratio(ratio.clamp(0.0, 1.0))
+            .label(format!(
+                "{} {:.0}% ({} free)",
+                mount.display(),
+                ratio * 100.0,
+                format_size(space.available)
+            ));
+        f.render_widget(gauge, *row);
+    }
+}
+
+fn render_system_info(f: &mut Frame, info: &SystemInfo, area: Rect) {
+    let label = Style::default().fg(Color::DarkGray);
+    let usage = |name: &str, space: &DiskSpace| {
+        let ratio = space.used_ratio();
+        let color = if ratio > 0.9 {
+            Color::Red
+        } else if ratio > 0.75 {
+            Color::Yellow
+        } else {
+            Color::Green
+        };
+        vec![
+            Span::styled(format!("{} ", name), label),
+            Span::styled(usage_bar(ratio, 10), Style::default().fg(color)),
+            Span::raw(format!(
+                " {:.0}%, {} free  ",
+                ratio * 100.0,
+                format_size(space.available)
+            )),
+        ]
+    };
+
+    let mut disks = Vec::new();
+    if let Some(root) = &info.root {
+        disks.extend(usage("/", root));
+    }
+    if let Some(home) = &info.home {
+        disks.extend(usage("~", home));
+    }
+    if disks.is_empty() {
+        disks.push(Span::styled("Disk usage unavailable", label));
+    }
+
+    let mut host = vec![
+        Span::styled(
+            info.hostname.clone(),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::styled("  •  ", label),
+        Span::raw(info.distro.clone()),
+    ];
+    if !info.kernel.is_empty() {
+        host.push(Span::styled("  •  kernel ", label));
+        host.push(Span::raw(info.kernel.clone()));
+    }
+
+    let lines = vec![
+        Line::from(host),
+        Line::from(disks),
+        Line::from(vec![
+            Span::styled("Last clean: ", label),
+            Span::raw(info.last_clean.as_deref().unwrap_or("never").to_string()),
+        ]),
+    ];
+    let panel = Paragraph::new(lines).block(
+        Block::default()
+            .title("🖥️ System (i to hide)")
+            .borders(Borders::ALL),
+    );
+    f.render_widget(panel, area);
+}
+
 fn render_categories(f: &mut Frame, app: &App, area: Rect) {
-    // Add icons to category names
+    // Disk gauges go under the list when there is room for both
+    let gauges_height = app.disks.len() as u16 + 2;
+    let area = if !app.disks.is_empty()
+        && !app.categories_collapsed
+        && area.height >= gauges_height + app.categories.len() as u16 + 2
+    {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(gauges_height)])
+            .split(area);
+        render_disk_gauges(f, &app.disks, chunks[1]);
+        chunks[0]
+    } else {
+        area
+    };
+
     let categories: Vec<ListItem> = app
         .categories
         .iter()
         .enumerate()
         .map(|(i, category)| {
-            let content = Line::from(format!("{} ({})", category.name, category.description));
+            // The first nine are a number key away
+            let key = if i < 9 {
+                format!("{} ", i + 1)
+            } else {
+                "  ".to_string()
+            };
+            let (selected, bytes) = app.category_selection(i);
+            let summary = if app.categories_collapsed {
+                format!("({})", selected)
+            } else if selected == 0 {
+                format!("{} (none selected)", category.name)
+            } else if bytes == 0 {
+                format!("{} ({} selected)", category.name, selected)
+            } else {
+                format!(
+                    "{} ({} selected, ~{})",
+                    category.name,
+                    selected,
+                    format_size(bytes)
+                )
+            };
+            let content = Line::from(vec![
+                Span::styled(key, Style::default().fg(Color::DarkGray)),
+                Span::raw(summary),
+            ]);
             let style = if i == app.category_index {
                 Style::default()
                     .fg(Color::Yellow)
@@ -858,12 +1165,13 @@ fn render_categories(f: &mut Frame, app: &App, area: Rect) {
         })
         .collect();
 
+    let title = if app.categories_collapsed {
+        "📂"
+    } else {
+        "📂 Categories"
+    };
     let categories_list = List::new(categories)
-        .block(
-            Block::default()
-                .title("📂 Categories")
-                .borders(Borders::ALL),
-        )
+        .block(Block::default().title(title).borders(Borders::ALL))
         .highlight_style(
             Style::default()
                 .add_modifier(Modifier::BOLD)
@@ -874,14 +1182,21 @@ fn render_categories(f: &mut Frame, app: &App, area: Rect) {
 }
 
 fn render_cleaners(f: &mut Frame, app: &mut App, area: Rect) {
+    app.clamp_item_selection();
+    let visible = app.visible_items();
     let current_category = &app.categories[app.category_index];
 
-    let items: Vec<ListItem> = current_category
-        .items
+    let items: Vec<ListItem> = visible
         .iter()
+        .map(|&i| &current_category.items[i])
         .map(|item| {
             let mut parts = vec![];
 
+            // Sub-items sit indented under their parent in registration order
+            if item.parent.is_some() && app.sort_mode == SortMode::Category {
+                parts.push(Span::styled("  ↳ ", Style::default().fg(Color::DarkGray)));
+            }
+
             // Create checkbox using tui-checkbox with predefined symbols
             // We use the ASCII bracket symbols for maximum terminal compatibility
             let checkbox_style = if item.selected {
@@ -935,6 +1250,12 @@ fn render_cleaners(f: &mut Frame, app: &mut App, area: Rect) {
                             Style::default().fg(Color::Green),
                         ));
                     }
+                    Status::PartialSuccess(msg, _) => {
+                        parts.push(Span::styled(
+                            format!(" [{}]", msg),
+                            Style::default().fg(Color::Yellow),
+                        ));
+                    }
                     Status::Error(msg) => {
                         parts.push(Span::styled(
                             format!(" [Error: {}]", msg),
@@ -950,11 +1271,21 @@ fn render_cleaners(f: &mut Frame, app: &mut App, area: Rect) {
                 }
             }
 
-            // If item has cleaned bytes, show it
+            // Freed space once run, otherwise the scan estimate
             if item.bytes_cleaned > 0 {
-                parts.push(Span::styled(
+                parts.push(sized_span(
                     format!(" (Freed: {})", format_size(item.bytes_cleaned)),
-                    Style::default().fg(Color::Green),
+                    item.bytes_cleaned,
+                ));
+            } else if let Some(ScanState::Done(report)) = &item.scan_state {
+                let estimate = report.total_reclaimable();
+                if estimate > 0 {
+                    parts.push(sized_span(format!(" ~{}", format_size(estimate)), estimate));
+                }
+            } else if let Some(ScanState::Cancelled) = &item.scan_state {
+                parts.push(Span::styled(
+                    " (size unknown)",
+                    Style::default().fg(Color::DarkGray),
                 ));
             }
 
@@ -965,7 +1296,7 @@ fn render_cleaners(f: &mut Frame, app: &mut App, area: Rect) {
     let items_list = List::new(items)
         .block(
             Block::default()
-                .title(format!("{} Items", current_category.name))
+                .title(cleaner_list_title(app, visible.len()))
                 .borders(Borders::ALL),
         )
         .highlight_style(
@@ -978,10 +1309,27 @@ fn render_cleaners(f: &mut Frame, app: &mut App, area: Rect) {
     f.render_stateful_widget(items_list, area, &mut app.item_list_state);
 }
 
+/// List title with the active sort and filter, e.g. "User Land Cleaners
+/// Items (3/12) · Sort: Size · Filter: Selected"
+fn cleaner_list_title(app: &App, shown: usize) -> String {
+    let category = &app.categories[app.category_index];
+    let mut title = format!("{} Items", category.name);
+    if app.filter_mode != FilterMode::All {
+        title.push_str(&format!(" ({}/{})", shown, category.items.len()));
+    }
+    if app.sort_mode != SortMode::Category {
+        title.push_str(&format!(" · Sort: {}", app.sort_mode.name()));
+    }
+    if app.filter_mode != FilterMode::All {
+        title.push_str(&format!(" · Filter: {}", app.filter_mode.name()));
+    }
+    title
+}
+
 fn render_details(f: &mut Frame, app: &App, area: Rect) {
     let current_category = &app.categories[app.category_index];
 
-    if let Some(selected) = app.item_list_state.selected() {
+    if let Some(selected) = app.selected_item_index() {
         if selected < current_category.items.len() {
             let item = &current_category.items[selected];
 
@@ -1019,6 +1367,9 @@ fn render_details(f: &mut Frame, app: &App, area: Rect) {
                         Some(Status::Success(msg)) => {
                             Span::styled(format!("✓ {}", msg), Style::default().fg(Color::Green))
                         }
+                        Some(Status::PartialSuccess(msg, _)) => {
+                            Span::styled(format!("◐ {}", msg), Style::default().fg(Color::Yellow))
+                        }
                         Some(Status::Error(msg)) => Span::styled(
                             format!("✗ Error: {}", msg),
                             Style::default().fg(Color::Red),
@@ -1031,16 +1382,140 @@ fn render_details(f: &mut Frame, app: &App, area: Rect) {
                 ]),
             ];
 
-            if item.bytes_cleaned > 0 {
+            if let Some(parent) = &item.parent {
+                text.insert(
+                    3,
+                    Line::from(vec![
+                        Span::raw("Sub-item of: "),
+                        Span::styled(parent.as_str(), Style::default().fg(Color::White)),
+                    ]),
+                );
+            }
+
+            if !item.tags.is_empty() {
+                let tags: Vec<&str> = item.tags.iter().map(|tag| tag.name()).collect();
                 text.push(Line::from(vec![
-                    Span::raw("Space freed: "),
+                    Span::raw("Tags: "),
+                    Span::styled(tags.join(", "), Style::default().fg(Color::White)),
+                ]));
+            }
+
+            if app.journal_vacuum_selected() {
+                text.push(Line::from(vec![
+                    Span::raw("Journal: "),
                     Span::styled(
-                        format!("{:.2} GB", item.bytes_cleaned as f64 / 1_073_741_824.0),
-                        Style::default().fg(Color::Green),
+                        format!("vacuums {}", log_backends::journal_vacuum().describe()),
+                        Style::default().fg(Color::White),
                     ),
+                    Span::styled(" (J to switch)", Style::default().fg(Color::DarkGray)),
+                ]));
+            }
+
+            if item.bytes_cleaned > 0 {
+                text.push(Line::from(vec![
+                    Span::raw("Space freed: "),
+                    size_span(item.bytes_cleaned),
                 ]));
             }
 
+            if item.permission_denied > 0 {
+                text.push(Line::from(vec![Span::styled(
+                    format!(
+                        "Permission denied: {} path(s) left in place",
+                        item.permission_denied
+                    ),
+                    Style::default().fg(Color::Yellow),
+                )]));
+            }
+
+            if let Some(Status::PartialSuccess(_, failures)) = &item.status {
+                text.push(Line::from(vec![Span::raw("")]));
+                text.push(Line::from(vec![Span::styled(
+                    format!("Couldn't remove {}:", failures.len()),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                )]));
+                for (path, error) in failures {
+                    text.push(Line::from(vec![
+                        Span::raw(format!("  {} ", path.display())),
+                        Span::styled(error.as_str(), Style::default().fg(Color::DarkGray)),
+                    ]));
+                }
+            }
+
+            match &item.scan_state {
+                Some(ScanState::Scanning) => {
+                    text.push(Line::from(vec![Span::raw("")]));
+                    text.push(Line::from(vec![Span::styled(
+                        format!(
+                            "{} Scanning...",
+                            Status::Running.get_animation_frame(app.animation_frame)
+                        ),
+                        Style::default().fg(Color::Yellow),
+                    )]));
+                }
+                Some(ScanState::Done(report)) => {
+                    text.push(Line::from(vec![Span::raw("")]));
+                    text.push(Line::from(vec![Span::styled(
+                        "Breakdown:",
+                        Style::default().add_modifier(Modifier::BOLD),
+                    )]));
+                    for entry in &report.entries {
+                        let mut line = vec![
+                            Span::raw(format!("  {:<16}", entry.label)),
+                            Span::styled(
+                                format!("{:>12}", format_size(entry.size)),
+                                Style::default().fg(Color::White),
+                            ),
+                        ];
+                        // Sparse files take up less than their length, small
+                        // files a whole block
+                        if let Some(on_disk) = entry.on_disk.filter(|&bytes| bytes != entry.size) {
+                            line.push(Span::styled(
+                                format!("  {} on disk", format_size(on_disk)),
+                                Style::default().fg(Color::DarkGray),
+                            ));
+                        }
+                        line.push(sized_span(
+                            format!("  ({} reclaimable)", format_size(entry.reclaimable)),
+                            entry.reclaimable,
+                        ));
+                        text.push(Line::from(line));
+                    }
+                    text.push(Line::from(vec![
+                        Span::raw("Reclaimable: "),
+                        size_span(report.total_reclaimable()),
+                    ]));
+                    for note in &report.notes {
+                        text.push(Line::from(vec![Span::styled(
+                            note.as_str(),
+                            Style::default().fg(Color::Cyan),
+                        )]));
+                    }
+                }
+                Some(ScanState::Failed(error)) => {
+                    text.push(Line::from(vec![Span::raw("")]));
+                    text.push(Line::from(vec![Span::styled(
+                        format!("Scan unavailable: {}", error),
+                        Style::default().fg(Color::DarkGray),
+                    )]));
+                }
+                Some(ScanState::Cancelled) => {
+                    text.push(Line::from(vec![Span::raw("")]));
+                    text.push(Line::from(vec![Span::styled(
+                        "Scan stopped, size unknown",
+                        Style::default().fg(Color::DarkGray),
+                    )]));
+                }
+                None => {}
+            }
+
+            if let Some(docs) = item.docs {
+                text.push(Line::from(vec![Span::raw("")]));
+                text.extend(markdown::to_lines(docs));
+            }
+
             let details = Paragraph::new(text)
                 .block(Block::default().title("Details").borders(Borders::ALL))
                 .wrap(Wrap { trim: true });
@@ -1050,6 +1525,219 @@ fn render_details(f: &mut Frame, app: &App, area: Rect) {
     }
 }
 
+/// Popup with the version and the usage statistics kept on this machine
+fn render_about(f: &mut Frame, app: &App, area: Rect) {
+    let mut stats = app.usage.summary_lines();
+    if let Some((runs, bytes)) = app.quarantine_usage {
+        stats.push(String::new());
+        stats.push(format!(
+            "In quarantine: {} ({} runs)",
+            format_size(bytes),
+            runs
+        ));
+    }
+    let popup_width = 64.min(area.width.saturating_sub(4));
+    let popup_height = (stats.len() as u16 + 6).min(area.height.saturating_sub(2));
+    let popup_area = Rect {
+        x: area.x + (area.width.saturating_sub(popup_width)) / 2,
+        y: area.y + (area.height.saturating_sub(popup_height)) / 2,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    let mut text = vec![
+        Line::from(Span::styled(
+            format!("cleansys {}", env!("CARGO_PKG_VERSION")),
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    text.extend(stats.into_iter().map(Line::from));
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        "Counted on this machine only, never sent anywhere",
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let about = Paragraph::new(text).block(
+        Block::default()
+            .title("ℹ About (A or ESC to close)")
+            .title_style(Style::default().add_modifier(Modifier::BOLD))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+    f.render_widget(Clear, popup_area);
+    f.render_widget(about, popup_area);
+}
+
+fn render_performance(f: &mut Frame, app: &App, area: Rect) {
+    let throttle = priority::current();
+    let label = Style::default().fg(Color::White);
+
+    let priority_span = match throttle.nice {
+        Some(nice) => Span::styled(
+            format!("low (nice {})", nice),
+            Style::default().fg(Color::Yellow),
+        ),
+        None if throttle.low_priority => {
+            Span::styled("low (nice not applied)", Style::default().fg(Color::Red))
+        }
+        None => Span::styled("normal", Style::default().fg(Color::Green)),
+    };
+    let io_span = if throttle.io_idle {
+        Span::styled("idle", Style::default().fg(Color::Yellow))
+    } else {
+        Span::styled("default", Style::default().fg(Color::Green))
+    };
+    let delay_span = if throttle.deletion_delay.is_zero() {
+        Span::styled("off", Style::default().fg(Color::Green))
+    } else {
+        Span::styled(
+            format!("{} ms per item", throttle.deletion_delay.as_millis()),
+            Style::default().fg(Color::Yellow),
+        )
+    };
+
+    let storage = concurrency::home_storage();
+    let storage_span = Span::styled(
+        storage.name(),
+        Style::default().fg(match storage {
+            StorageKind::Hdd => Color::Yellow,
+            StorageKind::Unknown => Color::Gray,
+            StorageKind::Ssd | StorageKind::Nvme => Color::Green,
+        }),
+    );
+
+    let mut text = vec![
+        Line::from(vec![Span::styled("CPU priority: ", label), priority_span]),
+        Line::from(vec![Span::styled("IO class:     ", label), io_span]),
+        Line::from(vec![Span::styled("Deletions:    ", label), delay_span]),
+        Line::from(vec![
+            Span::styled("cgroup scope: ", label),
+            if scope::is_scoped() {
+                Span::styled(
+                    "systemd transient scope",
+                    Style::default().fg(Color::Yellow),
+                )
+            } else {
+                Span::styled("none", Style::default().fg(Color::Green))
+            },
+        ]),
+        Line::from(vec![
+            Span::styled("Storage:      ", label),
+            storage_span,
+            Span::raw(format!(
+                ", {} scan / {} delete threads",
+                concurrency::scan_threads(),
+                concurrency::delete_threads()
+            )),
+        ]),
+        Line::from(vec![
+            Span::styled("Operations:   ", label),
+            Span::raw(format!(
+                "{} run, {} errors, {} partial, {} elapsed",
+                app.operation_count,
+                app.errors_count,
+                app.partial_count,
+                app.get_elapsed_time()
+            )),
+        ]),
+        Line::from(vec![
+            Span::styled("Total freed:  ", label),
+            Span::styled(
+                format_size(app.total_bytes_cleaned),
+                Style::default().fg(Color::Green),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Scanner:      ", label),
+            Span::raw(format!(
+                "{}, {} scans in {:.1}s",
+                scanner::backend().name(),
+                app.scan_timings.len(),
+                app.scan_timings
+                    .iter()
+                    .map(|(_, elapsed)| elapsed.as_secs_f64())
+                    .sum::<f64>()
+            )),
+        ]),
+    ];
+
+    if let Some((name, elapsed)) = app.scan_timings.iter().max_by_key(|(_, elapsed)| *elapsed) {
+        text.push(Line::from(vec![
+            Span::styled("Slowest scan: ", label),
+            Span::styled(
+                format!("{} ({:.1}s)", name, elapsed.as_secs_f64()),
+                Style::default().fg(Color::Yellow),
+            ),
+        ]));
+    }
+
+    let panel = Paragraph::new(text)
+        .block(Block::default().title("Performance").borders(Borders::ALL))
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(panel, area);
+}
+
+fn toast_line(text: &str) -> Line<'_> {
+    Line::from(vec![Span::styled(
+        text,
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    )])
+}
+
+/// Scan progress and the next step for the selection, e.g. "Scanning 2/5
+/// Enter: clean 3 items (~1.20 GB)"
+fn footer_hint_spans(app: &App) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    if let Some((done, total)) = app.scan_progress() {
+        spans.push(Span::styled(
+            format!(
+                "{} Scanning {}/{} (Esc: stop)  ",
+                Status::Running.get_animation_frame(app.animation_frame),
+                done,
+                total
+            ),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+
+    match app.footer_hint() {
+        FooterHint::Select => {
+            spans.push(Span::styled(
+                "Space",
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ));
+            spans.push(Span::raw(": select  "));
+        }
+        FooterHint::Clean { count, estimate } => {
+            spans.push(Span::styled(
+                "Enter",
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD),
+            ));
+            spans.push(Span::raw(format!(
+                ": clean {} item{}",
+                count,
+                if count == 1 { "" } else { "s" }
+            )));
+            if let Some(estimate) = estimate {
+                spans.push(Span::raw(" ("));
+                spans.push(sized_span(format!("~{}", format_size(estimate)), estimate));
+                spans.push(Span::raw(")"));
+            }
+            spans.push(Span::raw("  "));
+        }
+    }
+    spans
+}
+
 fn render_footer(f: &mut Frame, app: &App, area: Rect) {
     let block = Block::default()
         .borders(Borders::TOP)
@@ -1067,52 +1755,56 @@ fn render_footer(f: &mut Frame, app: &App, area: Rect) {
             ])
             .split(inner_area);
 
-        // Status information
-        let status_text = vec![Line::from(vec![
-            Span::styled(
-                "Status: ",
-                Style::default()
-                    .fg(Color::White)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            if app.paused {
+        // Status information, replaced by a toast while one is up
+        let status_text = if let Some(toast) = app.toasts.current() {
+            vec![toast_line(toast)]
+        } else {
+            vec![Line::from(vec![
                 Span::styled(
-                    "PAUSED",
+                    "Status: ",
                     Style::default()
-                        .fg(Color::Yellow)
+                        .fg(Color::White)
                         .add_modifier(Modifier::BOLD),
-                )
-            } else if app.is_running {
+                ),
+                if app.paused {
+                    Span::styled(
+                        "PAUSED",
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                } else if app.is_running {
+                    Span::styled(
+                        "CLEANING",
+                        Style::default()
+                            .fg(Color::Green)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                } else if app.operation_end_time.is_some() {
+                    Span::styled(
+                        "FINISHED",
+                        Style::default()
+                            .fg(Color::Cyan)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                } else {
+                    Span::styled(
+                        "READY",
+                        Style::default()
+                            .fg(Color::White)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                },
+                Span::raw("  •  "),
+                Span::styled("Total Freed: ", Style::default().fg(Color::White)),
                 Span::styled(
-                    "CLEANING",
+                    format_size(app.total_bytes_cleaned),
                     Style::default()
                         .fg(Color::Green)
                         .add_modifier(Modifier::BOLD),
-                )
-            } else if app.operation_end_time.is_some() {
-                Span::styled(
-                    "FINISHED",
-                    Style::default()
-                        .fg(Color::Cyan)
-                        .add_modifier(Modifier::BOLD),
-                )
-            } else {
-                Span::styled(
-                    "READY",
-                    Style::default()
-                        .fg(Color::White)
-                        .add_modifier(Modifier::BOLD),
-                )
-            },
-            Span::raw("  •  "),
-            Span::styled("Total Freed: ", Style::default().fg(Color::White)),
-            Span::styled(
-                format_size(app.total_bytes_cleaned),
-                Style::default()
-                    .fg(Color::Green)
-                    .add_modifier(Modifier::BOLD),
-            ),
-        ])];
+                ),
+            ])]
+        };
 
         // Controls - different for running vs completed operations
         let controls_text = if app.is_running {
@@ -1178,53 +1870,72 @@ fn render_footer(f: &mut Frame, app: &App, area: Rect) {
             ])
             .split(inner_area);
 
-        // Status information
-        let status_text = vec![Line::from(vec![
-            Span::styled(
-                "User: ",
-                Style::default()
-                    .fg(Color::White)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            if app.is_root {
+        // Status information, replaced by the schedule prompt, a toast or
+        // the countdown
+        let status_text = if let Some(input) = &app.schedule_input {
+            vec![Line::from(vec![
                 Span::styled(
-                    "root",
-                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
-                )
-            } else {
+                    "Run at/in (e.g. 02:00, 30m): ",
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(input.as_str()),
+                Span::styled("_", Style::default().fg(Color::Yellow)),
+            ])]
+        } else if let Some(toast) = app.toasts.current() {
+            vec![toast_line(toast)]
+        } else if let (Some((_, description)), Some(remaining)) =
+            (&app.scheduled_run, app.scheduled_countdown())
+        {
+            vec![Line::from(vec![
                 Span::styled(
-                    "standard",
+                    format!("⏰ Run {} ", description),
                     Style::default()
-                        .fg(Color::Green)
+                        .fg(Color::Yellow)
                         .add_modifier(Modifier::BOLD),
-                )
-            },
-            Span::raw("  •  "),
-            Span::styled("Selected: ", Style::default().fg(Color::White)),
-            Span::styled(
-                format!("{}", app.selected_cleaners_count),
-                Style::default()
-                    .fg(Color::Blue)
-                    .add_modifier(Modifier::BOLD),
-            ),
-        ])];
+                ),
+                Span::styled(
+                    format!("starts in {}", format_countdown(remaining)),
+                    Style::default().fg(Color::White),
+                ),
+                Span::raw("  •  t: Cancel"),
+            ])]
+        } else {
+            vec![Line::from(vec![
+                Span::styled(
+                    "User: ",
+                    Style::default()
+                        .fg(Color::White)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                if app.is_root {
+                    Span::styled(
+                        "root",
+                        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                    )
+                } else {
+                    Span::styled(
+                        "standard",
+                        Style::default()
+                            .fg(Color::Green)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                },
+                Span::raw("  •  "),
+                Span::styled("Selected: ", Style::default().fg(Color::White)),
+                Span::styled(
+                    format!("{}", app.selected_cleaners_count),
+                    Style::default()
+                        .fg(Color::Blue)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ])]
+        };
 
-        // Controls - organized by function
-        let controls_text = vec![Line::from(vec![
-            Span::styled(
-                "Space",
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(": Select  "),
-            Span::styled(
-                "Enter",
-                Style::default()
-                    .fg(Color::Green)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(": Run  "),
+        // Controls, led by the next step for the current selection
+        let mut controls = footer_hint_spans(app);
+        controls.extend([
             Span::styled(
                 "Tab",
                 Style::default()
@@ -1244,7 +1955,8 @@ fn render_footer(f: &mut Frame, app: &App, area: Rect) {
                 Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
             ),
             Span::raw(": Quit"),
-        ])];
+        ]);
+        let controls_text = vec![Line::from(controls)];
 
         let status_para = Paragraph::new(status_text);
         let controls_para =
@@ -1256,115 +1968,3 @@ fn render_footer(f: &mut Frame, app: &App, area: Rect) {
 
     f.render_widget(block, area);
 }
-
-fn render_help(f: &mut Frame, area: Rect) {
-    let help_text = vec![
-        Line::from(vec![Span::styled(
-            "🔍 Cleansys Help",
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        )]),
-        Line::from(vec![Span::raw("")]),
-        Line::from(vec![Span::styled(
-            "📍 Navigation:",
-            Style::default().add_modifier(Modifier::BOLD),
-        )]),
-        Line::from(vec![Span::raw("  ↑/↓: Navigate items")]),
-        Line::from(vec![Span::raw("  Tab/Shift+Tab: Switch categories")]),
-        Line::from(vec![Span::raw("")]),
-        Line::from(vec![Span::styled(
-            "🔧 Actions:",
-            Style::default().add_modifier(Modifier::BOLD),
-        )]),
-        Line::from(vec![Span::raw("  Space: Toggle selection")]),
-        Line::from(vec![Span::raw("  Enter: Run selected cleaners")]),
-        Line::from(vec![Span::raw("  a: Select all in current category")]),
-        Line::from(vec![Span::raw("  n: Deselect all in current category")]),
-        Line::from(vec![Span::raw(
-            "  c: Cycle chart type (Bar → Count Pie → Size Pie → Bar)",
-        )]),
-        Line::from(vec![Span::raw("  /: Search in detailed view")]),
-        Line::from(vec![Span::raw("")]),
-        Line::from(vec![Span::styled(
-            "🎛️ Advanced Controls:",
-            Style::default().add_modifier(Modifier::BOLD),
-        )]),
-        Line::from(vec![Span::raw("  m: Toggle compact mode")]),
-        Line::from(vec![Span::raw(
-            "  v: Cycle view mode (Standard/Compact/Detailed/Performance)",
-        )]),
-        Line::from(vec![Span::raw("  p: Toggle performance statistics")]),
-        Line::from(vec![Span::raw(
-            "  s: Toggle auto-scroll log (during operations)",
-        )]),
-        Line::from(vec![Span::raw("  o: Cycle sort mode")]),
-        Line::from(vec![Span::raw("  f: Cycle filter mode")]),
-        Line::from(vec![Span::raw("  y: Toggle confirmation prompts")]),
-        Line::from(vec![Span::raw("  x: Clear all errors")]),
-        Line::from(vec![Span::raw(
-            "  j/k: Scroll detailed items list (vi-style)",
-        )]),
-        Line::from(vec![Span::raw("  /: Search files/paths in detailed view")]),
-        Line::from(vec![Span::raw(
-            "  ESC: Clear search / Cancel operation / Return to menu",
-        )]),
-        Line::from(vec![Span::raw("  Backspace: Remove search character")]),
-        Line::from(vec![Span::raw("  PgUp/PgDn: Scroll operation log")]),
-        Line::from(vec![Span::raw("  Home/End: Jump to first/last item")]),
-        Line::from(vec![Span::raw("  Ctrl+Space: Pause/Resume operations")]),
-        Line::from(vec![Span::raw("")]),
-        Line::from(vec![Span::styled(
-            "🔍 Search Features:",
-            Style::default().add_modifier(Modifier::BOLD),
-        )]),
-        Line::from(vec![Span::raw(
-            "  Search matches file paths, categories, and cleaner names",
-        )]),
-        Line::from(vec![Span::raw(
-            "  Real-time filtering with highlighted results",
-        )]),
-        Line::from(vec![Span::raw("  Category distribution shown at bottom")]),
-        Line::from(vec![Span::raw("")]),
-        Line::from(vec![Span::styled(
-            "📊 Chart Types (press 'c' to cycle):",
-            Style::default().add_modifier(Modifier::BOLD),
-        )]),
-        Line::from(vec![Span::raw(
-            "  Bar Chart: Traditional vertical bars for comparison",
-        )]),
-        Line::from(vec![Span::raw(
-            "  Pie Count: Circular chart showing item distribution by count",
-        )]),
-        Line::from(vec![Span::raw(
-            "  Pie Size: Circular chart showing space usage by category",
-        )]),
-        Line::from(vec![Span::raw("")]),
-        Line::from(vec![Span::styled(
-            "🔒 System Operations:",
-            Style::default().add_modifier(Modifier::BOLD),
-        )]),
-        Line::from(vec![Span::raw(
-            "  System cleaners require sudo/root privileges",
-        )]),
-        Line::from(vec![Span::raw(
-            "  Run 'sudo cleansys' or provide password when prompted",
-        )]),
-        Line::from(vec![Span::raw(
-            "  Items marked (sudo) will request elevated privileges",
-        )]),
-        Line::from(vec![Span::raw("")]),
-        Line::from(vec![Span::styled(
-            "🔄 Other:",
-            Style::default().add_modifier(Modifier::BOLD),
-        )]),
-        Line::from(vec![Span::raw("  ?: Show/hide help")]),
-        Line::from(vec![Span::raw("  q: Exit application")]),
-    ];
-
-    let help = Paragraph::new(help_text)
-        .block(Block::default().title("📚 Help").borders(Borders::ALL))
-        .wrap(Wrap { trim: true });
-
-    f.render_widget(help, area);
-}
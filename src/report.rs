@@ -0,0 +1,327 @@
+//! Summary reports of past runs, for `cleansys report`. A report covers the
+//! runs recorded in the history over a period: what was freed in total and
+//! per day, per cleaner, and which cleaners failed with what. It renders as
+//! markdown with text bar charts, or as a self-contained HTML page with SVG
+//! charts, either of which a cron job can mail as is:
+//!
+//! ```text
+//! 0 8 * * 1  cleansys report --since 7d --format html | mail -s "Weekly cleanup" root
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+use crate::history::{format_timestamp, RunRecord};
+use crate::schedule;
+use crate::utils::{format_size, local_time, local_time_ago};
+
+/// Width of the bars in markdown charts, in characters
+const BAR_WIDTH: usize = 30;
+
+/// Freed bytes of one cleaner over the period
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CleanerTotal {
+    pub name: String,
+    pub runs: usize,
+    pub bytes: u64,
+    /// Runs that failed outright
+    pub failed: usize,
+    /// Runs that left some paths behind
+    pub partial: usize,
+}
+
+/// A failed cleaner run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    pub time: String,
+    pub cleaner: String,
+    pub error: String,
+}
+
+/// What happened over a period of the history
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    /// Start and end of the period, as history timestamps
+    pub from: String,
+    pub to: String,
+    pub runs: usize,
+    pub bytes: u64,
+    /// Part of `bytes` only freed after a reboot
+    pub deferred: u64,
+    /// Bytes freed per day, `YYYY-MM-DD`, oldest first
+    pub days: Vec<(String, u64)>,
+    /// Largest first
+    pub cleaners: Vec<CleanerTotal>,
+    pub failures: Vec<Failure>,
+}
+
+impl Report {
+    /// The report of the runs recorded between `from` and `to`, both
+    /// history timestamps
+    pub fn of(runs: &[RunRecord], from: &str, to: &str) -> Self {
+        let mut report = Report {
+            from: from.to_string(),
+            to: to.to_string(),
+            ..Report::default()
+        };
+        let mut days: BTreeMap<String, u64> = BTreeMap::new();
+        let mut cleaners: BTreeMap<&str, CleanerTotal> = BTreeMap::new();
+
+        // Timestamps sort as text
+        for run in runs
+            .iter()
+            .filter(|run| run.time.as_str() >= from && run.time.as_str() <= to)
+        {
+            report.runs += 1;
+            report.bytes += run.total();
+            report.deferred += run.deferred();
+            let day = run.time.get(..10).unwrap_or(&run.time);
+            *days.entry(day.to_string()).or_default() += run.total();
+
+            for cleaner in &run.cleaners {
+                let total = cleaners
+                    .entry(&cleaner.name)
+                    .or_insert_with(|| CleanerTotal {
+                        name: cleaner.name.clone(),
+                        ..CleanerTotal::default()
+                    });
+                total.runs += 1;
+                total.bytes += cleaner.bytes;
+                if let Some(error) = &cleaner.error {
+                    total.failed += 1;
+                    report.failures.push(Failure {
+                        time: run.time.clone(),
+                        cleaner: cleaner.name.clone(),
+                        error: error.clone(),
+                    });
+                } else if cleaner.is_partial() {
+                    total.partial += 1;
+                }
+            }
+        }
+
+        report.days = days.into_iter().collect();
+        report.cleaners = cleaners.into_values().collect();
+        report
+            .cleaners
+            .sort_by_key(|total| std::cmp::Reverse(total.bytes));
+        report
+    }
+
+    /// The report of the runs in the last `period`
+    pub fn since(runs: &[RunRecord], period: Duration) -> Self {
+        let stamp = |tm: Option<libc::tm>| {
+            format_timestamp(&tm.unwrap_or_else(|| unsafe { std::mem::zeroed() }))
+        };
+        Self::of(runs, &stamp(local_time_ago(period)), &stamp(local_time()))
+    }
+
+    fn title(&self) -> String {
+        format!(
+            "CleanSys report {} to {}",
+            self.from.get(..10).unwrap_or(&self.from),
+            self.to.get(..10).unwrap_or(&self.to)
+        )
+    }
+
+    fn summary(&self) -> String {
+        let mut summary = format!("{} freed in {} runs", format_size(self.bytes), self.runs);
+        if self.deferred > 0 {
+            let _ = write!(
+                summary,
+                ", {} of it only after a reboot",
+                format_size(self.deferred)
+            );
+        }
+        if !self.failures.is_empty() {
+            let _ = write!(summary, ", {} failed cleaner run(s)", self.failures.len());
+        }
+        summary
+    }
+
+    /// The report as markdown, charts drawn with block characters
+    pub fn markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# {}\n", self.title());
+        let _ = writeln!(out, "{}.\n", self.summary());
+        if self.runs == 0 {
+            return out;
+        }
+
+        let _ = writeln!(out, "## Freed per day\n\n```text");
+        let peak = self.days.iter().map(|(_, bytes)| *bytes).max().unwrap_or(0);
+        for (day, bytes) in &self.days {
+            let _ = writeln!(
+                out,
+                "{} {:<width$} {}",
+                day,
+                "█".repeat(bar(*bytes, peak, BAR_WIDTH)),
+                format_size(*bytes),
+                width = BAR_WIDTH
+            );
+        }
+        let _ = writeln!(out, "```\n");
+
+        let _ = writeln!(out, "## Per cleaner\n");
+        let _ = writeln!(out, "| Cleaner | Runs | Freed | Partial | Failed |");
+        let _ = writeln!(out, "|---------|-----:|------:|--------:|-------:|");
+        for total in &self.cleaners {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} | {} |",
+                total.name.replace('|', "\\|"),
+                total.runs,
+                format_size(total.bytes),
+                total.partial,
+                total.failed
+            );
+        }
+
+        if !self.failures.is_empty() {
+            let _ = writeln!(out, "\n## Errors\n");
+            for failure in &self.failures {
+                let _ = writeln!(
+                    out,
+                    "- {} **{}**: {}",
+                    failure.time, failure.cleaner, failure.error
+                );
+            }
+        }
+        out
+    }
+
+    /// The report as a standalone HTML page with inline SVG charts
+    pub fn html(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>",
+            escape(&self.title())
+        );
+        let _ = writeln!(
+            out,
+            "<style>body{{font-family:sans-serif;max-width:50em;margin:2em auto;color:#222}}\
+             table{{border-collapse:collapse;width:100%}}\
+             th,td{{padding:.3em .6em;border-bottom:1px solid #ddd;text-align:right}}\
+             th:first-child,td:first-child{{text-align:left}}\
+             .failed{{color:#b00}}</style>\n</head>\n<body>"
+        );
+        let _ = writeln!(out, "<h1>{}</h1>", escape(&self.title()));
+        let _ = writeln!(out, "<p>{}.</p>", escape(&self.summary()));
+
+        if self.runs > 0 {
+            let _ = writeln!(out, "<h2>Freed per day</h2>");
+            out.push_str(&svg_bars(&self.days));
+            let _ = writeln!(out, "<h2>Per cleaner</h2>");
+            let largest: Vec<(String, u64)> = self
+                .cleaners
+                .iter()
+                .take(10)
+                .map(|total| (total.name.clone(), total.bytes))
+                .collect();
+            out.push_str(&svg_bars(&largest));
+            let _ = writeln!(
+                out,
+                "<table>\n<tr><th>Cleaner</th><th>Runs</th><th>Freed</th><th>Partial</th><th>Failed</th></tr>"
+            );
+            for total in &self.cleaners {
+                let _ = writeln!(
+                    out,
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td{}>{}</td></tr>",
+                    escape(&total.name),
+                    total.runs,
+                    format_size(total.bytes),
+                    total.partial,
+                    if total.failed > 0 {
+                        " class=\"failed\""
+                    } else {
+                        ""
+                    },
+                    total.failed
+                );
+            }
+            let _ = writeln!(out, "</table>");
+        }
+
+        if !self.failures.is_empty() {
+            let _ = writeln!(out, "<h2>Errors</h2>\n<ul>");
+            for failure in &self.failures {
+                let _ = writeln!(
+                    out,
+                    "<li>{} <strong>{}</strong>: <span class=\"failed\">{}</span></li>",
+                    escape(&failure.time),
+                    escape(&failure.cleaner),
+                    escape(&failure.error)
+                );
+            }
+            let _ = writeln!(out, "</ul>");
+        }
+        let _ = writeln!(out, "</body>\n</html>");
+        out
+    }
+}
+
+/// Length of a bar for `value` out of `peak`, at least one for anything
+/// freed so small amounts still show
+fn bar(value: u64, peak: u64, width: usize) -> usize {
+    if value == 0 || peak == 0 {
+        return 0;
+    }
+    ((value as f64 / peak as f64 * width as f64).round() as usize).max(1)
+}
+
+/// A horizontal bar chart of labelled sizes
+fn svg_bars(rows: &[(String, u64)]) -> String {
+    const LABEL: usize = 180;
+    const WIDTH: usize = 360;
+    const ROW: usize = 22;
+
+    let peak = rows.iter().map(|(_, bytes)| *bytes).max().unwrap_or(0);
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-size=\"12\">",
+        LABEL + WIDTH + 100,
+        rows.len() * ROW
+    );
+    for (i, (label, bytes)) in rows.iter().enumerate() {
+        let y = i * ROW;
+        let _ = writeln!(
+            svg,
+            "<text x=\"0\" y=\"{}\">{}</text>\
+             <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#3b82f6\"/>\
+             <text x=\"{}\" y=\"{}\">{}</text>",
+            y + 15,
+            escape(label),
+            LABEL,
+            y + 4,
+            bar(*bytes, peak, WIDTH),
+            ROW - 8,
+            LABEL + bar(*bytes, peak, WIDTH) + 6,
+            y + 15,
+            format_size(*bytes)
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Escape text for HTML and SVG
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Parse a period such as `7d`, `2w`, `12h` or `30m`
+pub fn parse_period(text: &str) -> Option<Duration> {
+    schedule::parse_duration(text, |unit| match unit {
+        "m" => Some(60),
+        "h" => Some(60 * 60),
+        "d" => Some(24 * 60 * 60),
+        "w" => Some(7 * 24 * 60 * 60),
+        _ => None,
+    })
+}
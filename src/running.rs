@@ -0,0 +1,227 @@
+//! Applications running while their caches are cleaned. Deleting a cache
+//! under a running browser or editor can leave it in a corrupted state, so
+//! cache cleaners look for the owning process first: the cache directory
+//! name is matched against `/proc/*/comm`, and against the programs that
+//! `.desktop` entries of the same name start. What happens on a match is
+//! configurable per cleaner:
+//!
+//! ```toml
+//! [running_apps]
+//! action = "skip"
+//! cleaners = { "Application Caches" = "warn" }
+//! ```
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::filesystem::{home_dir, root_path};
+use crate::utils::print_warning;
+
+/// Names shorter than this only match exactly, so `go` or `pip` don't match
+/// every process containing them
+const MIN_PARTIAL_MATCH: usize = 4;
+
+/// Programs `.desktop` entries start other programs through, which say
+/// nothing about the application
+const LAUNCHERS: [&str; 5] = ["env", "flatpak", "snap", "sh", "bash"];
+
+/// What to do with a cache whose application is running
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunningAction {
+    /// Clean it, but say the application is running
+    #[default]
+    Warn,
+    /// Leave it for the next run
+    Skip,
+    /// Don't look for running applications
+    Ignore,
+}
+
+/// The `[running_apps]` config table
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunningApps {
+    /// What cleaners without their own setting do
+    pub action: RunningAction,
+    /// Settings for individual cleaners by name
+    pub cleaners: BTreeMap<String, RunningAction>,
+}
+
+impl RunningApps {
+    /// What `cleaner` does when an application is running
+    pub fn action_for(&self, cleaner: &str) -> RunningAction {
+        self.cleaners.get(cleaner).copied().unwrap_or(self.action)
+    }
+}
+
+static RUNNING_APPS: Lazy<Mutex<RunningApps>> = Lazy::new(|| Mutex::new(RunningApps::default()));
+
+/// Set what cleaners do about running applications, from the config file
+pub fn set_running_apps(settings: RunningApps) {
+    *RUNNING_APPS.lock().unwrap_or_else(|e| e.into_inner()) = settings;
+}
+
+/// What cleaners do about running applications
+pub fn running_apps() -> RunningApps {
+    RUNNING_APPS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// The running processes and the installed `.desktop` entries, read once
+/// per cleaner
+#[derive(Debug, Clone, Default)]
+pub struct Processes {
+    names: Vec<String>,
+    /// `.desktop` entry names and `StartupWMClass`es with the program each
+    /// entry starts
+    desktop: Vec<(Vec<String>, String)>,
+}
+
+impl Processes {
+    /// The processes running now. Empty where there is no `/proc`.
+    pub fn snapshot() -> Self {
+        let names = fs::read_dir(root_path("/proc"))
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter(|entry| entry.file_name().to_string_lossy().parse::<u32>().is_ok())
+                    .filter_map(|entry| fs::read_to_string(entry.path().join("comm")).ok())
+                    .map(|comm| comm.trim().to_string())
+                    .filter(|comm| !comm.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            desktop: desktop_entries(),
+            ..Self::from_names(names)
+        }
+    }
+
+    /// Processes with these names, without `.desktop` entries
+    pub fn from_names(mut names: Vec<String>) -> Self {
+        names.sort();
+        names.dedup();
+        Self {
+            names,
+            desktop: Vec::new(),
+        }
+    }
+
+    /// The running process that owns the cache of `app`, if any
+    pub fn owner(&self, app: &str) -> Option<&str> {
+        let mut programs = vec![app];
+        programs.extend(
+            self.desktop
+                .iter()
+                .filter(|(names, _)| names.iter().any(|name| names_match(app, name)))
+                .map(|(_, program)| program.as_str()),
+        );
+        self.names
+            .iter()
+            .find(|process| programs.iter().any(|program| names_match(program, process)))
+            .map(String::as_str)
+    }
+}
+
+/// Whether a cache or program name and a process name refer to the same
+/// application: equal ignoring case and punctuation, or one containing the
+/// other when it is long enough (`google-chrome` and `chrome`)
+pub fn names_match(name: &str, process: &str) -> bool {
+    let normalize = |s: &str| -> String {
+        s.chars()
+            .filter(char::is_ascii_alphanumeric)
+            .map(|c| c.to_ascii_lowercase())
+            .collect()
+    };
+    let (name, process) = (normalize(name), normalize(process));
+    if name.is_empty() || process.is_empty() {
+        return false;
+    }
+    let (shorter, longer) = if name.len() <= process.len() {
+        (&name, &process)
+    } else {
+        (&process, &name)
+    };
+    shorter == longer || (shorter.len() >= MIN_PARTIAL_MATCH && longer.contains(shorter.as_str()))
+}
+
+/// The installed `.desktop` entries as their names (file name and
+/// `StartupWMClass`) and the program they start
+fn desktop_entries() -> Vec<(Vec<String>, String)> {
+    let mut dirs = vec![root_path("/usr/share/applications")];
+    if let Ok(home) = home_dir() {
+        dirs.push(home.join(".local/share/applications"));
+    }
+    dirs.iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.flatten())
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "desktop") {
+                return None;
+            }
+            let contents = fs::read_to_string(&path).ok()?;
+            let exec = desktop_key(&contents, "Exec")?.split_whitespace().next()?;
+            let program = Path::new(exec).file_name()?.to_string_lossy().to_string();
+            if LAUNCHERS.contains(&program.as_str()) {
+                return None;
+            }
+            let mut names = vec![path.file_stem()?.to_string_lossy().to_string()];
+            names.extend(desktop_key(&contents, "StartupWMClass").map(str::to_string));
+            Some((names, program))
+        })
+        .collect()
+}
+
+/// A key from the `[Desktop Entry]` group
+fn desktop_key<'a>(contents: &'a str, key: &str) -> Option<&'a str> {
+    let mut in_entry = false;
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+        } else if in_entry {
+            if let Some((k, value)) = line.split_once('=') {
+                if k.trim() == key {
+                    return Some(value.trim());
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Whether `cleaner` should leave the cache of `app` alone because the
+/// application is running, warning about it either way
+pub fn keep_cache(cleaner: &str, app: &str, processes: &Processes) -> bool {
+    let action = running_apps().action_for(cleaner);
+    if action == RunningAction::Ignore {
+        return false;
+    }
+    let Some(process) = processes.owner(app) else {
+        return false;
+    };
+    match action {
+        RunningAction::Skip => {
+            print_warning(&format!(
+                "Skipping the '{}' cache: {} is running",
+                app, process
+            ));
+            true
+        }
+        _ => {
+            print_warning(&format!(
+                "{} is running; cleaning the '{}' cache may disturb it",
+                process, app
+            ));
+            false
+        }
+    }
+}
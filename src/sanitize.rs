@@ -0,0 +1,92 @@
+//! Cleaning up text from external commands before it is shown in the TUI.
+//! Color codes, cursor movement and other control characters would corrupt
+//! the screen, and very long lines (a `find` listing on one line, a base64
+//! blob) would push everything else out of the log.
+
+/// Widest log line in characters; longer lines are split
+pub const MAX_LOG_WIDTH: usize = 160;
+
+/// Pieces a single long line is split into at most; the rest is cut off
+pub const MAX_SPLIT_LINES: usize = 4;
+
+/// Remove ANSI escape sequences: CSI (`ESC [ ... m`), OSC (`ESC ] ... BEL`)
+/// and two-character escapes
+pub fn strip_ansi(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            // Parameters and intermediates up to a final byte in @..~
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // Terminated by BEL or ESC \
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    result
+}
+
+/// One line of command output made safe to render: escape sequences and
+/// control characters removed, tabs expanded, and only the text after the
+/// last carriage return kept, as a terminal would show a progress line
+pub fn clean_line(line: &str) -> String {
+    let line = strip_ansi(line);
+    let line = line.trim_end_matches('\r');
+    let line = line.rsplit('\r').next().unwrap_or(line);
+    line.chars()
+        .filter_map(|c| match c {
+            '\t' => Some(' '),
+            c if c.is_control() => None,
+            c => Some(c),
+        })
+        .collect()
+}
+
+/// Split a clean line into pieces of at most `width` characters, keeping
+/// at most [`MAX_SPLIT_LINES`] and marking a cut with "…"
+pub fn split_line(line: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let chars: Vec<char> = line.chars().collect();
+    let mut pieces: Vec<String> = chars
+        .chunks(width)
+        .take(MAX_SPLIT_LINES)
+        .map(|chunk| chunk.iter().collect())
+        .collect();
+    if chars.len() > width * MAX_SPLIT_LINES {
+        if let Some(last) = pieces.last_mut() {
+            last.pop();
+            last.push('…');
+        }
+    }
+    if pieces.is_empty() {
+        pieces.push(String::new());
+    }
+    pieces
+}
+
+/// Log lines for a message, which may span several lines
+pub fn log_lines(text: &str) -> Vec<String> {
+    text.lines()
+        .flat_map(|line| split_line(&clean_line(line), MAX_LOG_WIDTH))
+        .collect()
+}
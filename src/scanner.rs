@@ -0,0 +1,736 @@
+//! Directory size scanning used to estimate reclaimable space.
+//!
+//! Sizes come from a native walker by default. The `du` backend is kept so
+//! `cleansys bench-scan` can compare the two and catch regressions in the
+//! walker.
+//!
+//! The native walker remembers each directory's mtime and entries in
+//! `~/.cache/cleansys/scan.db`, and only lists directories again whose mtime
+//! changed since the last scan. Files are still looked at every time, since
+//! one growing in place leaves its directory's mtime alone.
+//!
+//! Scans run through [`cancellable`] can be stopped with [`cancel_scans`]:
+//! their walks end at the next directory, and the size they return is only
+//! what was counted so far. At most [`concurrency::scan_threads`] of them
+//! run at once.
+
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+#[cfg(unix)]
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::cleaners::groups;
+use crate::concurrency;
+use crate::netfs;
+use crate::paths;
+use crate::privileges;
+use crate::utils::format_size;
+
+/// How directory sizes are measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScanBackend {
+    /// Walk the tree in-process
+    Native,
+    /// Shell out to `du -sb`
+    Du,
+}
+
+impl ScanBackend {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScanBackend::Native => "native",
+            ScanBackend::Du => "du",
+        }
+    }
+}
+
+static USE_DU: AtomicBool = AtomicBool::new(false);
+static CACHE_ENABLED: AtomicBool = AtomicBool::new(true);
+static CACHE: Lazy<Mutex<Option<ScanCache>>> = Lazy::new(|| Mutex::new(None));
+
+/// Bumped by [`cancel_scans`], which stops every scan started before
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    /// The generation the scan on this thread started in, if it can be
+    /// cancelled
+    static STARTED_IN: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// Scans running in [`cancellable`], and the signal that one has finished
+static RUNNING: Mutex<usize> = Mutex::new(0);
+static SLOT_FREED: Condvar = Condvar::new();
+
+/// Frees a scan slot even if the scan panics
+struct ScanSlot;
+
+impl ScanSlot {
+    /// Wait until fewer than [`concurrency::scan_threads`] scans are running.
+    /// A cancelled scan doesn't wait, it has nothing left to walk.
+    fn acquire() -> Self {
+        let limit = concurrency::scan_threads();
+        let mut running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+        while *running >= limit && !is_cancelled() {
+            running = SLOT_FREED.wait(running).unwrap_or_else(|e| e.into_inner());
+        }
+        *running += 1;
+        ScanSlot
+    }
+}
+
+impl Drop for ScanSlot {
+    fn drop(&mut self) {
+        *RUNNING.lock().unwrap_or_else(|e| e.into_inner()) -= 1;
+        SLOT_FREED.notify_one();
+    }
+}
+
+/// Run `scan` on this thread so that [`cancel_scans`] can stop it. Once
+/// [`concurrency::scan_threads`] scans are running, the next one waits for
+/// one of them to finish. Scans must not be nested.
+pub fn cancellable<T>(scan: impl FnOnce() -> T) -> T {
+    let previous = STARTED_IN.replace(Some(GENERATION.load(Ordering::SeqCst)));
+    let slot = ScanSlot::acquire();
+    let result = scan();
+    drop(slot);
+    STARTED_IN.set(previous);
+    result
+}
+
+/// Stop every scan running in [`cancellable`], including the ones still
+/// waiting to start. Scans started afterwards run normally.
+pub fn cancel_scans() {
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+    // Take the lock so a scan can't miss the wakeup between its check and
+    // its wait
+    let _running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+    SLOT_FREED.notify_all();
+}
+
+/// Whether the scan on this thread has been cancelled
+pub fn is_cancelled() -> bool {
+    STARTED_IN
+        .get()
+        .is_some_and(|started| started != GENERATION.load(Ordering::SeqCst))
+}
+
+/// The backend used by [`size`]
+pub fn backend() -> ScanBackend {
+    if USE_DU.load(Ordering::Relaxed) {
+        ScanBackend::Du
+    } else {
+        ScanBackend::Native
+    }
+}
+
+/// Switch the backend used by [`size`] for the whole process
+pub fn set_backend(backend: ScanBackend) {
+    USE_DU.store(backend == ScanBackend::Du, Ordering::Relaxed);
+}
+
+/// Turn the scan cache on or off for the whole process
+pub fn set_cache_enabled(enabled: bool) {
+    CACHE_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Apparent size of a file or directory tree in bytes, using the current
+/// backend. Missing or unreadable paths count as zero, and so do paths on
+/// network filesystems that aren't cleaned.
+pub fn size(path: &Path) -> Result<u64> {
+    if netfs::skip(path) {
+        return Ok(0);
+    }
+    match backend() {
+        ScanBackend::Native => {
+            let mut seen = HashSet::new();
+            let mut total = 0;
+            walk(path, |_, metadata| {
+                total += apparent_size(metadata, &mut seen)
+            });
+            Ok(total)
+        }
+        ScanBackend::Du => du_size(path),
+    }
+}
+
+/// Hand `visit` every entry of a tree, taking directory listings from the
+/// scan cache while it's on
+fn walk(path: &Path, visit: impl FnMut(&Path, &fs::Metadata)) {
+    if !CACHE_ENABLED.load(Ordering::Relaxed) {
+        cached_walk(path, |_| None, visit);
+        return;
+    }
+    let lock = || CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    lock().get_or_insert_with(|| ScanCache::load().unwrap_or_default());
+    // The lock is only taken to look up one directory at a time, so scans of
+    // other trees don't wait for this walk
+    let read = cached_walk(path, |dir| lock().as_ref().and_then(|c| c.get(dir)), visit);
+    if let Some(cache) = lock().as_mut() {
+        cache.merge(read);
+    }
+}
+
+/// Write the scan cache to disk if any scan changed it. Scans only hold the
+/// cache for single lookups, so this never waits for a slow walk.
+pub fn save_cache() -> Result<()> {
+    let contents = match CACHE.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        Some(cache) if cache.dirty => serde_json::to_string(cache)?,
+        _ => return Ok(()),
+    };
+    // Written without the cache held, since a scan running as root would
+    // otherwise wait for this write to wait for it
+    let path = ScanCache::path()?;
+    privileges::as_user(|| write_cache(&path, &contents))
+}
+
+/// Write serialized scan cache contents, creating parent directories
+fn write_cache(path: &Path, contents: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create cache directory {:?}", parent))?;
+    }
+    fs::write(path, contents).with_context(|| format!("Failed to write scan cache {:?}", path))
+}
+
+/// Walk a tree and add up apparent sizes like `du -sb` does: symlinks are
+/// not followed and hard-linked files are only counted once.
+pub fn native_size(path: &Path) -> u64 {
+    let mut seen = HashSet::new();
+    let mut total = 0;
+    let mut stack = vec![path.to_path_buf()];
+
+    while let Some(path) = stack.pop() {
+        if is_cancelled() {
+            break;
+        }
+        let Ok(metadata) = fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if !metadata.is_dir() {
+            total += file_size(&metadata, &mut seen);
+            continue;
+        }
+
+        total += metadata.len();
+        if let Ok(entries) = fs::read_dir(&path) {
+            stack.extend(entries.flatten().map(|e| e.path()));
+        }
+    }
+
+    total
+}
+
+/// Size of a non-directory entry, counting hard-linked files only once
+#[cfg(unix)]
+fn file_size(metadata: &fs::Metadata, seen: &mut HashSet<(u64, u64)>) -> u64 {
+    if metadata.nlink() > 1 && !seen.insert((metadata.dev(), metadata.ino())) {
+        0
+    } else {
+        metadata.len()
+    }
+}
+
+#[cfg(not(unix))]
+fn file_size(metadata: &fs::Metadata, _seen: &mut HashSet<(u64, u64)>) -> u64 {
+    metadata.len()
+}
+
+/// What an entry adds to the apparent size of a tree
+fn apparent_size(metadata: &fs::Metadata, seen: &mut HashSet<(u64, u64)>) -> u64 {
+    if metadata.is_dir() {
+        metadata.len()
+    } else {
+        file_size(metadata, seen)
+    }
+}
+
+/// Modification time as (seconds, nanoseconds) since the epoch
+#[cfg(unix)]
+fn mtime(metadata: &fs::Metadata) -> (i64, i64) {
+    (metadata.mtime(), metadata.mtime_nsec())
+}
+
+#[cfg(not(unix))]
+fn mtime(metadata: &fs::Metadata) -> (i64, i64) {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or((0, 0), |d| (d.as_secs() as i64, d.subsec_nanos() as i64))
+}
+
+/// What the cache remembers about one directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedDir {
+    /// Directory mtime (seconds, nanoseconds) and size when it was read
+    mtime: (i64, i64),
+    len: u64,
+    /// Names of the non-directory entries directly inside it
+    files: Vec<PathBuf>,
+    subdirs: Vec<PathBuf>,
+}
+
+/// Directory sizes from previous scans, keyed on path, mtime and size
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ScanCache {
+    dirs: HashMap<PathBuf, CachedDir>,
+    #[serde(skip)]
+    dirty: bool,
+}
+
+impl ScanCache {
+    /// Location of the cache, `~/.cache/cleansys/scan.db`
+    pub fn path() -> Result<PathBuf> {
+        Ok(paths::cache_dir()?.join("scan.db"))
+    }
+
+    /// Load the cache from the default location, or an empty one
+    pub fn load() -> Result<Self> {
+        let path = Self::path()?;
+        if path.exists() {
+            Self::load_from(&path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// Load a cache from a specific file
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read scan cache {:?}", path))?;
+        serde_json::from_str(&contents).with_context(|| format!("Invalid scan cache {:?}", path))
+    }
+
+    /// Save the cache to the default location, as the user who ran sudo
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        privileges::as_user(|| self.save_to(&path))
+    }
+
+    /// Save the cache to a specific file, creating parent directories
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        write_cache(path, &serde_json::to_string(self)?)
+    }
+
+    /// Number of directories in the cache
+    pub fn len(&self) -> usize {
+        self.dirs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dirs.is_empty()
+    }
+
+    /// Like [`native_size`], but directories whose mtime and size match the
+    /// cache reuse their remembered entries instead of being listed again.
+    /// Their subdirectories are still checked, since a change deep in the
+    /// tree doesn't touch the mtime of its ancestors.
+    pub fn size(&mut self, path: &Path) -> u64 {
+        let mut seen = HashSet::new();
+        let mut total = 0;
+        let read = cached_walk(
+            path,
+            |dir| self.get(dir),
+            |_, metadata| total += apparent_size(metadata, &mut seen),
+        );
+        self.merge(read);
+        total
+    }
+
+    fn get(&self, dir: &Path) -> Option<CachedDir> {
+        self.dirs.get(dir).cloned()
+    }
+
+    /// Remember directories read by a walk
+    fn merge(&mut self, read: Vec<(PathBuf, CachedDir)>) {
+        self.dirty |= !read.is_empty();
+        self.dirs.extend(read);
+    }
+}
+
+/// Walk a tree like [`native_size`], handing `visit` every entry, and taking
+/// directory listings from `lookup` when their mtime and size still match.
+/// Returns the directories that had to be listed.
+fn cached_walk(
+    path: &Path,
+    lookup: impl Fn(&Path) -> Option<CachedDir>,
+    mut visit: impl FnMut(&Path, &fs::Metadata),
+) -> Vec<(PathBuf, CachedDir)> {
+    let mut read = Vec::new();
+    let mut stack = vec![path.to_path_buf()];
+
+    while let Some(path) = stack.pop() {
+        // Directories read so far stay cached, each with all its files
+        if is_cancelled() {
+            break;
+        }
+        let Ok(metadata) = fs::symlink_metadata(&path) else {
+            continue;
+        };
+        visit(&path, &metadata);
+        if !metadata.is_dir() {
+            continue;
+        }
+
+        let mtime = mtime(&metadata);
+        let cached = lookup(&path).filter(|c| c.mtime == mtime && c.len == metadata.len());
+        if let Some(cached) = cached {
+            for name in &cached.files {
+                let file = path.join(name);
+                if let Ok(m) = fs::symlink_metadata(&file) {
+                    visit(&file, &m);
+                }
+            }
+            stack.extend(cached.subdirs);
+            continue;
+        }
+
+        let mut files = Vec::new();
+        let mut subdirs = Vec::new();
+        if let Ok(entries) = fs::read_dir(&path) {
+            for entry in entries.flatten() {
+                match fs::symlink_metadata(entry.path()) {
+                    Ok(m) if m.is_dir() => subdirs.push(entry.path()),
+                    Ok(m) => {
+                        visit(&entry.path(), &m);
+                        files.push(PathBuf::from(entry.file_name()));
+                    }
+                    Err(_) => {}
+                }
+            }
+        }
+
+        stack.extend(subdirs.iter().cloned());
+        read.push((
+            path,
+            CachedDir {
+                mtime,
+                len: metadata.len(),
+                files,
+                subdirs,
+            },
+        ));
+    }
+
+    read
+}
+
+/// What removing files and directory trees would actually free, as
+/// opposed to their apparent size. Files count with the blocks they take up
+/// on disk, so the holes of sparse files like VM images and core dumps
+/// don't. A hard-linked file's data is only freed with its last link, so it
+/// counts once every link has been added, and not at all while a link lives
+/// elsewhere. On btrfs and XFS, extents shared with reflinked copies stay in
+/// use by the copies and don't count either, even when the copies are
+/// removed too.
+#[derive(Debug, Default)]
+#[cfg_attr(not(unix), allow(dead_code))]
+pub struct Reclaimable {
+    /// Links added so far of each file with more than one, by (device, inode)
+    links: HashMap<(u64, u64), u64>,
+    /// Whether files on each device can share extents
+    reflinks: HashMap<u64, bool>,
+}
+
+/// How much of the disk a file or directory tree takes up
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiskUsage {
+    /// Bytes in allocated blocks, each hard-linked file counted once
+    pub on_disk: u64,
+    /// Part of `on_disk` that removing the tree would free
+    pub reclaimable: u64,
+}
+
+impl Reclaimable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How much removing `path` would free, together with the paths added
+    /// before it. Missing or unreadable paths free nothing.
+    pub fn add(&mut self, path: &Path) -> DiskUsage {
+        self.measure(path).1
+    }
+
+    /// [`add`](Self::add), along with the apparent size of `path` as
+    /// [`size`] measures it, from the same walk of the tree
+    pub fn measure(&mut self, path: &Path) -> (u64, DiskUsage) {
+        let mut usage = DiskUsage::default();
+        if netfs::skip(path) {
+            return (0, usage);
+        }
+        let mut seen = HashSet::new();
+        let mut size = 0;
+        walk(path, |path, metadata| {
+            size += apparent_size(metadata, &mut seen);
+            if metadata.is_dir() {
+                usage.on_disk += disk_size(metadata);
+                usage.reclaimable += disk_size(metadata);
+            } else {
+                self.file(path, metadata, &mut usage);
+            }
+        });
+        if backend() == ScanBackend::Du {
+            size = du_size(path).unwrap_or(0);
+        }
+        (size, usage)
+    }
+
+    #[cfg(unix)]
+    fn file(&mut self, path: &Path, metadata: &fs::Metadata, usage: &mut DiskUsage) {
+        let size = disk_size(metadata);
+        if metadata.nlink() > 1 {
+            let added = self
+                .links
+                .entry((metadata.dev(), metadata.ino()))
+                .or_insert(0);
+            *added += 1;
+            if *added == 1 {
+                usage.on_disk += size;
+            }
+            if *added != metadata.nlink() {
+                return;
+            }
+        } else {
+            usage.on_disk += size;
+        }
+        if !metadata.file_type().is_file() {
+            usage.reclaimable += size;
+            return;
+        }
+        let reflinks = *self
+            .reflinks
+            .entry(metadata.dev())
+            .or_insert_with(|| supports_reflinks(path));
+        usage.reclaimable += if reflinks {
+            size.saturating_sub(shared_bytes(path))
+        } else {
+            size
+        };
+    }
+
+    #[cfg(not(unix))]
+    fn file(&mut self, _path: &Path, metadata: &fs::Metadata, usage: &mut DiskUsage) {
+        usage.on_disk += metadata.len();
+        usage.reclaimable += metadata.len();
+    }
+}
+
+/// Bytes in the blocks allocated to a file, which for a sparse file is
+/// less than its length
+#[cfg(unix)]
+fn disk_size(metadata: &fs::Metadata) -> u64 {
+    metadata.blocks() * 512
+}
+
+#[cfg(not(unix))]
+fn disk_size(metadata: &fs::Metadata) -> u64 {
+    metadata.len()
+}
+
+/// Whether `path` is on a filesystem where files can share extents
+#[cfg(target_os = "linux")]
+fn supports_reflinks(path: &Path) -> bool {
+    const BTRFS: u32 = 0x9123_683e;
+    const XFS: u32 = 0x5846_5342;
+
+    // The magic numbers fit in 32 bits, whatever the width of f_type
+    crate::mounts::statfs(path).is_some_and(|stat| matches!(stat.f_type as u32, BTRFS | XFS))
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn supports_reflinks(_path: &Path) -> bool {
+    false
+}
+
+/// Bytes of a file in extents it shares with other files, from FIEMAP
+#[cfg(target_os = "linux")]
+fn shared_bytes(path: &Path) -> u64 {
+    use std::os::unix::io::AsRawFd;
+
+    const FS_IOC_FIEMAP: u64 = 0xc020_660b;
+    const EXTENT_LAST: u32 = 0x1;
+    const EXTENT_SHARED: u32 = 0x2000;
+    const BATCH: usize = 64;
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Default)]
+    struct Extent {
+        logical: u64,
+        physical: u64,
+        length: u64,
+        reserved64: [u64; 2],
+        flags: u32,
+        reserved: [u32; 3],
+    }
+
+    #[repr(C)]
+    struct Fiemap {
+        start: u64,
+        length: u64,
+        flags: u32,
+        mapped_extents: u32,
+        extent_count: u32,
+        reserved: u32,
+        extents: [Extent; BATCH],
+    }
+
+    let Ok(file) = fs::File::open(path) else {
+        return 0;
+    };
+    let mut shared = 0;
+    let mut start = 0;
+    loop {
+        let mut map = Fiemap {
+            start,
+            length: u64::MAX - start,
+            flags: 0,
+            mapped_extents: 0,
+            extent_count: BATCH as u32,
+            reserved: 0,
+            extents: [Extent::default(); BATCH],
+        };
+        // SAFETY: map is a fiemap header followed by room for extent_count
+        // extents, as the ioctl expects
+        if unsafe { libc::ioctl(file.as_raw_fd(), FS_IOC_FIEMAP as _, &mut map) } != 0 {
+            return shared;
+        }
+        let extents = &map.extents[..(map.mapped_extents as usize).min(BATCH)];
+        let Some(last) = extents.last() else {
+            return shared;
+        };
+        shared += extents
+            .iter()
+            .filter(|extent| extent.flags & EXTENT_SHARED != 0)
+            .map(|extent| extent.length)
+            .sum::<u64>();
+        if last.flags & EXTENT_LAST != 0 {
+            return shared;
+        }
+        start = last.logical + last.length;
+    }
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn shared_bytes(_path: &Path) -> u64 {
+    0
+}
+
+/// Size of a file or directory tree as reported by `du -sb`
+pub fn du_size(path: &Path) -> Result<u64> {
+    let output = Command::new("du").arg("-sb").arg(path).output()?;
+    if !output.status.success() {
+        return Ok(0);
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout
+        .split_whitespace()
+        .next()
+        .and_then(|size| size.parse().ok())
+        .unwrap_or(0))
+}
+
+/// Timing of one cleaner's scan with both backends
+#[derive(Debug, Clone, Serialize)]
+pub struct CleanerBench {
+    pub category: &'static str,
+    pub name: &'static str,
+    /// Scan time with the native walker, in milliseconds
+    pub native_ms: f64,
+    /// Scan time with `du`, in milliseconds
+    pub du_ms: f64,
+    /// Reclaimable bytes found by the native walker
+    pub native_bytes: u64,
+    /// Reclaimable bytes found by `du`
+    pub du_bytes: u64,
+}
+
+/// Run the scan of every available cleaner once per backend and time it.
+/// The scan cache is bypassed so the walker itself is measured, and the
+/// backend in use beforehand is restored afterwards.
+pub fn bench_scan() -> Vec<CleanerBench> {
+    let previous = backend();
+    let cache_enabled = CACHE_ENABLED.swap(false, Ordering::Relaxed);
+    let mut results = Vec::new();
+
+    for group in groups() {
+        for cleaner in group.cleaners {
+            let Some(scan) = cleaner.scan else {
+                continue;
+            };
+            if !cleaner.is_available() {
+                continue;
+            }
+
+            let timed = |backend| {
+                set_backend(backend);
+                let start = Instant::now();
+                let bytes = scan().map(|r| r.total_reclaimable()).unwrap_or(0);
+                (start.elapsed(), bytes)
+            };
+            let (native, native_bytes) = timed(ScanBackend::Native);
+            let (du, du_bytes) = timed(ScanBackend::Du);
+
+            results.push(CleanerBench {
+                category: group.name,
+                name: cleaner.name,
+                native_ms: millis(native),
+                du_ms: millis(du),
+                native_bytes,
+                du_bytes,
+            });
+        }
+    }
+
+    set_backend(previous);
+    set_cache_enabled(cache_enabled);
+    results
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Render benchmark results as an aligned plain-text table with totals
+pub fn format_bench_table(results: &[CleanerBench]) -> String {
+    let mut out = format!(
+        "{:<20}  {:<24}  {:>10}  {:>10}  {:>10}",
+        "CATEGORY", "NAME", "NATIVE", "DU", "SIZE"
+    );
+
+    for r in results {
+        let mismatch = if r.native_bytes != r.du_bytes {
+            format!(" (du: {})", format_size(r.du_bytes))
+        } else {
+            String::new()
+        };
+        out.push_str(&format!(
+            "\n{:<20}  {:<24}  {:>8.1}ms  {:>8.1}ms  {:>10}{}",
+            r.category,
+            r.name,
+            r.native_ms,
+            r.du_ms,
+            format_size(r.native_bytes),
+            mismatch
+        ));
+    }
+
+    let native: f64 = results.iter().map(|r| r.native_ms).sum();
+    let du: f64 = results.iter().map(|r| r.du_ms).sum();
+    out.push_str(&format!(
+        "\n{:<20}  {:<24}  {:>8.1}ms  {:>8.1}ms",
+        "TOTAL", "", native, du
+    ));
+    out
+}
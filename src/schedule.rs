@@ -0,0 +1,110 @@
+//! Parsing and timing for cleaning runs scheduled from the TUI.
+
+use std::time::Duration;
+
+use crate::utils::local_time;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Longest countdown a schedule accepts, a year
+pub const MAX_DELAY: Duration = Duration::from_secs(365 * SECONDS_PER_DAY);
+
+/// When a scheduled run should start
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Schedule {
+    /// Start after a countdown
+    After(Duration),
+    /// Start at the next occurrence of a local wall-clock time
+    At { hour: u32, minute: u32 },
+}
+
+impl Schedule {
+    /// Parse a schedule such as `02:00`, `at 23:30`, `30m`, `+90s` or `in 2h`.
+    /// A bare number is taken as minutes. Countdowns past [`MAX_DELAY`] are
+    /// refused.
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim().to_lowercase();
+        let input = input.trim_start_matches("at ").trim();
+
+        if let Some((hour, minute)) = input.split_once(':') {
+            let hour: u32 = hour.trim().parse().ok()?;
+            let minute: u32 = minute.trim().parse().ok()?;
+            if hour > 23 || minute > 59 {
+                return None;
+            }
+            return Some(Schedule::At { hour, minute });
+        }
+
+        let input = input
+            .trim_start_matches('+')
+            .trim_start_matches("in ")
+            .trim();
+        let delay = parse_duration(input, |unit| match unit {
+            "s" | "sec" | "secs" => Some(1),
+            "" | "m" | "min" | "mins" => Some(60),
+            "h" | "hr" | "hrs" => Some(60 * 60),
+            _ => None,
+        })?;
+        (delay <= MAX_DELAY).then_some(Schedule::After(delay))
+    }
+
+    /// Delay until the run should start, given the current local time as
+    /// seconds since midnight.
+    pub fn delay_from(&self, seconds_since_midnight: u64) -> Duration {
+        match *self {
+            Schedule::After(delay) => delay,
+            Schedule::At { hour, minute } => {
+                let target = u64::from(hour) * 3600 + u64::from(minute) * 60;
+                let now = seconds_since_midnight % SECONDS_PER_DAY;
+                let secs = if target > now {
+                    target - now
+                } else {
+                    target + SECONDS_PER_DAY - now
+                };
+                Duration::from_secs(secs)
+            }
+        }
+    }
+
+    /// Short human readable description, e.g. "at 02:00" or "in 30m"
+    pub fn describe(&self) -> String {
+        match *self {
+            Schedule::After(delay) => format!("in {}", format_countdown(delay)),
+            Schedule::At { hour, minute } => format!("at {:02}:{:02}", hour, minute),
+        }
+    }
+}
+
+/// Parse a number followed by a unit, such as `30m`, with `unit_seconds`
+/// giving the length of each unit it accepts. Durations too long to count
+/// are refused rather than wrapped around.
+pub fn parse_duration(text: &str, unit_seconds: impl Fn(&str) -> Option<u64>) -> Option<Duration> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: u64 = number.parse().ok()?;
+    let seconds = number.checked_mul(unit_seconds(unit.trim())?)?;
+    Some(Duration::from_secs(seconds))
+}
+
+/// Seconds since local midnight
+pub fn local_seconds_since_midnight() -> u64 {
+    let Some(tm) = local_time() else {
+        let now = unsafe { libc::time(std::ptr::null_mut()) };
+        return now as u64 % SECONDS_PER_DAY;
+    };
+    (tm.tm_hour as u64) * 3600 + (tm.tm_min as u64) * 60 + tm.tm_sec as u64
+}
+
+/// Format a countdown as `HH:MM:SS`
+pub fn format_countdown(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60
+    )
+}
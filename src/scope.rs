@@ -0,0 +1,107 @@
+//! Optional re-execution inside a transient systemd scope so heavy scans and
+//! cleans have their CPU and IO contained by a cgroup.
+
+use anyhow::Result;
+use log::{debug, warn};
+use std::ffi::OsString;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Set in the environment of the re-executed process
+pub const SCOPED_ENV: &str = "CLEANSYS_SCOPED";
+
+/// Default `IOWeight=` and `CPUWeight=` for the scope (systemd default is 100)
+pub const DEFAULT_WEIGHT: u16 = 20;
+
+/// Whether this process is already running inside the cleansys scope
+pub fn is_scoped() -> bool {
+    std::env::var_os(SCOPED_ENV).is_some()
+}
+
+/// Whether systemd is the init system and `systemd-run` is installed
+pub fn systemd_available() -> bool {
+    Path::new("/run/systemd/system").exists()
+        && Command::new("which")
+            .arg("systemd-run")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+}
+
+/// Arguments for `systemd-run` that wrap `program` in a transient scope.
+/// Root uses the system manager, everyone else their user manager.
+pub fn scope_args(
+    io_weight: u16,
+    cpu_weight: u16,
+    user_manager: bool,
+    program: &[OsString],
+) -> Vec<OsString> {
+    let mut args: Vec<OsString> = Vec::new();
+    if user_manager {
+        args.push("--user".into());
+    }
+    args.extend([
+        "--scope".into(),
+        "--quiet".into(),
+        "--collect".into(),
+        "--description=cleansys".into(),
+        "-p".into(),
+        format!("IOWeight={}", io_weight).into(),
+        "-p".into(),
+        format!("CPUWeight={}", cpu_weight).into(),
+        "--".into(),
+    ]);
+    args.extend(program.iter().cloned());
+    args
+}
+
+/// Replace the current process with itself running inside a transient
+/// scope. Returns normally when the scope can't be created, so the caller
+/// simply carries on unscoped.
+pub fn reexec_in_scope(io_weight: u16, cpu_weight: u16, user_manager: bool) -> Result<()> {
+    if is_scoped() {
+        return Ok(());
+    }
+    if !systemd_available() {
+        debug!("systemd not available, running without a scope");
+        return Ok(());
+    }
+
+    // Probe first: once exec'd, a systemd-run failure can't be recovered from
+    let probe = Command::new("systemd-run")
+        .args(scope_args(
+            io_weight,
+            cpu_weight,
+            user_manager,
+            &["true".into()],
+        ))
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+    if !probe.map(|s| s.success()).unwrap_or(false) {
+        warn!("Could not create a systemd scope, running without one");
+        return Ok(());
+    }
+
+    let mut program: Vec<OsString> = vec![std::env::current_exe()?.into_os_string()];
+    program.extend(std::env::args_os().skip(1));
+
+    exec_scoped(scope_args(io_weight, cpu_weight, user_manager, &program))
+}
+
+#[cfg(unix)]
+fn exec_scoped(args: Vec<OsString>) -> Result<()> {
+    use std::os::unix::process::CommandExt;
+
+    let err = Command::new("systemd-run")
+        .args(args)
+        .env(SCOPED_ENV, "1")
+        .exec();
+    warn!("Failed to re-exec in a systemd scope: {}", err);
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn exec_scoped(_args: Vec<OsString>) -> Result<()> {
+    Ok(())
+}
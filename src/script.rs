@@ -0,0 +1,316 @@
+//! Scripted TUI sessions for reproducible demos, screenshots and end-to-end
+//! tests. A script is a text file with one command per line, fed into the
+//! event loop as [`Action`]s:
+//!
+//! ```text
+//! # Clean the second user cleaner and keep a report
+//! category 1
+//! select 2
+//! run
+//! wait done
+//! snapshot results.txt
+//! export report.json
+//! quit
+//! ```
+//!
+//! `key <name>...` presses keys (`down`, `space`, `enter`, `ctrl+c`, `q`,
+//! ...), `wait <n>ms|s` lets time pass, `resize <cols> <rows>` changes the
+//! screen size and `snapshot` writes the screen as text.
+
+use anyhow::{anyhow, bail, Context, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+use unicode_width::UnicodeWidthStr;
+
+use crate::action::Action;
+use crate::app::App;
+use crate::render::ui;
+
+/// One line of a script
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Press keys, looked up in the keymap of the screen on top
+    Keys(Vec<KeyEvent>),
+    /// Switch to the nth category, counting from 1
+    Category(usize),
+    /// Highlight the nth visible cleaner, counting from 1, and select it
+    Select(usize),
+    /// Run the selected cleaners
+    Run,
+    /// Let time pass
+    Wait(Duration),
+    /// Wait until the running clean has finished
+    WaitDone,
+    Resize(u16, u16),
+    /// Write the screen as text
+    Snapshot(PathBuf),
+    /// Write the last run as JSON, like a history entry
+    Export(PathBuf),
+    Quit,
+}
+
+/// A parsed script and how far it got
+#[derive(Debug, Clone, Default)]
+pub struct Script {
+    /// Commands with their line numbers
+    commands: Vec<(usize, Command)>,
+    next: usize,
+    waiting_until: Option<Instant>,
+}
+
+impl Script {
+    /// Parse a script, reporting the first invalid line
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut commands = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let command = parse_command(line).with_context(|| format!("line {}", number + 1))?;
+            commands.push((number + 1, command));
+        }
+        Ok(Self {
+            commands,
+            ..Self::default()
+        })
+    }
+
+    /// Read and parse a script file
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read script {:?}", path))?;
+        Self::parse(&text).with_context(|| format!("Invalid script {:?}", path))
+    }
+
+    /// Whether every command has run
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.commands.len() && self.waiting_until.is_none()
+    }
+
+    /// Run commands until one has to wait for the event loop. Returns
+    /// whether the TUI should quit.
+    pub fn step(&mut self, app: &mut App) -> Result<bool> {
+        if let Some(until) = self.waiting_until {
+            if Instant::now() < until {
+                return Ok(false);
+            }
+            self.waiting_until = None;
+        }
+
+        while let Some((number, command)) = self.commands.get(self.next).cloned() {
+            match command {
+                Command::WaitDone if app.is_running => return Ok(false),
+                Command::Wait(duration) => {
+                    self.next += 1;
+                    self.waiting_until = Some(Instant::now() + duration);
+                    return Ok(false);
+                }
+                _ => {}
+            }
+            self.next += 1;
+            if run_command(app, &command).with_context(|| format!("Script line {}", number))? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// Run a script without a terminal, drawing off-screen and ticking the app
+/// like the event loop does, until the script ends or quits
+pub fn run_headless(app: &mut App, script: &mut Script, tick_rate: Duration) -> Result<()> {
+    loop {
+        // Drawing also notices when a run has finished
+        screen_text(app)?;
+        if script.step(app)? || script.is_finished() {
+            return Ok(());
+        }
+        thread::sleep(tick_rate);
+        app.dispatch(Action::Tick)?;
+    }
+}
+
+/// Apply one command. Returns whether the TUI should quit.
+fn run_command(app: &mut App, command: &Command) -> Result<bool> {
+    match command {
+        Command::Keys(keys) => {
+            for key in keys {
+                if app.handle_key(*key)? {
+                    return Ok(true);
+                }
+            }
+        }
+        Command::Category(n) => {
+            if *n == 0 || *n > app.categories.len() {
+                bail!("There are {} categories", app.categories.len());
+            }
+            while app.category_index != n - 1 {
+                app.dispatch(Action::NextCategory)?;
+            }
+        }
+        Command::Select(n) => {
+            let visible = app.visible_items().len();
+            if *n == 0 || *n > visible {
+                bail!("The category shows {} cleaners", visible);
+            }
+            app.dispatch(Action::FirstItem)?;
+            for _ in 1..*n {
+                app.dispatch(Action::NextItem)?;
+            }
+            let item = app
+                .selected_item_index()
+                .map(|i| &app.categories[app.category_index].items[i]);
+            if item.is_some_and(|item| !item.selected) {
+                app.dispatch(Action::ToggleSelected)?;
+            }
+        }
+        Command::Run => return app.dispatch(Action::RunSelected),
+        Command::Wait(_) | Command::WaitDone => {}
+        Command::Resize(width, height) => return app.dispatch(Action::Resize(*width, *height)),
+        Command::Snapshot(path) => {
+            fs::write(path, screen_text(app)?)
+                .with_context(|| format!("Failed to write snapshot {:?}", path))?;
+        }
+        Command::Export(path) => {
+            let report = serde_json::to_string_pretty(&app.run_record())?;
+            fs::write(path, report)
+                .with_context(|| format!("Failed to write report {:?}", path))?;
+        }
+        Command::Quit => return app.dispatch(Action::Quit),
+    }
+    Ok(false)
+}
+
+/// The screen as plain text, one line per row, at the app's terminal size
+pub fn screen_text(app: &mut App) -> Result<String> {
+    let backend = TestBackend::new(app.terminal_width, app.terminal_height);
+    let mut terminal = Terminal::new(backend)?;
+    terminal.draw(|f| ui(f, app))?;
+
+    let buffer = terminal.backend().buffer();
+    let mut text = String::new();
+    for row in buffer.content.chunks(buffer.area.width.max(1) as usize) {
+        // A wide symbol covers the cells after it
+        let mut covered = 0;
+        for cell in row {
+            if covered > 0 {
+                covered -= 1;
+                continue;
+            }
+            text.push_str(cell.symbol());
+            covered = cell.symbol().width().saturating_sub(1);
+        }
+        text.truncate(text.trim_end().len());
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+fn parse_command(line: &str) -> Result<Command> {
+    let mut words = line.split_whitespace();
+    let name = words.next().unwrap_or_default();
+    let args: Vec<&str> = words.collect();
+    let number = |arg: Option<&&str>| -> Result<usize> {
+        let arg = arg.ok_or_else(|| anyhow!("`{}` needs a number", name))?;
+        arg.parse()
+            .map_err(|_| anyhow!("`{}` is not a number", arg))
+    };
+    let path = || -> Result<PathBuf> {
+        match args.as_slice() {
+            [path] => Ok(PathBuf::from(path)),
+            _ => bail!("`{}` needs a file name", name),
+        }
+    };
+
+    Ok(match name {
+        "key" | "keys" if !args.is_empty() => Command::Keys(
+            args.iter()
+                .map(|key| parse_key(key))
+                .collect::<Result<_>>()?,
+        ),
+        "key" | "keys" => bail!("`{}` needs at least one key", name),
+        "category" => Command::Category(number(args.first())?),
+        "select" => Command::Select(number(args.first())?),
+        "run" => Command::Run,
+        "wait" => match args.as_slice() {
+            ["done"] => Command::WaitDone,
+            [duration] => Command::Wait(parse_duration(duration)?),
+            _ => bail!("`wait` needs a duration such as 500ms or 2s, or `done`"),
+        },
+        "resize" => {
+            let width = number(args.first())?;
+            let height = number(args.get(1))?;
+            Command::Resize(
+                width.try_into().context("Width out of range")?,
+                height.try_into().context("Height out of range")?,
+            )
+        }
+        "snapshot" => Command::Snapshot(path()?),
+        "export" => Command::Export(path()?),
+        "quit" => Command::Quit,
+        _ => bail!("Unknown command `{}`", name),
+    })
+}
+
+/// Parse a key name such as `down`, `space`, `ctrl+space`, `f1` or `q`
+pub fn parse_key(name: &str) -> Result<KeyEvent> {
+    let (modifiers, key) = match name.rsplit_once('+').filter(|(_, key)| !key.is_empty()) {
+        Some((modifiers, key)) => {
+            let mut parsed = KeyModifiers::NONE;
+            for modifier in modifiers.split('+') {
+                parsed |= match modifier.to_lowercase().as_str() {
+                    "ctrl" | "control" => KeyModifiers::CONTROL,
+                    "alt" => KeyModifiers::ALT,
+                    "shift" => KeyModifiers::SHIFT,
+                    _ => bail!("Unknown modifier `{}` in `{}`", modifier, name),
+                };
+            }
+            (parsed, key)
+        }
+        None => (KeyModifiers::NONE, name),
+    };
+
+    let mut chars = key.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match key.to_lowercase().as_str() {
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "enter" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "space" => KeyCode::Char(' '),
+            "backspace" => KeyCode::Backspace,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            f => match f.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                Some(n @ 1..=12) => KeyCode::F(n),
+                _ => bail!("Unknown key `{}`", name),
+            },
+        },
+    };
+    Ok(KeyEvent::new(code, modifiers))
+}
+
+fn parse_duration(text: &str) -> Result<Duration> {
+    let invalid = || anyhow!("`{}` is not a duration such as 500ms or 2s", text);
+    if let Some(ms) = text.strip_suffix("ms") {
+        Ok(Duration::from_millis(ms.parse().map_err(|_| invalid())?))
+    } else if let Some(secs) = text.strip_suffix('s') {
+        let secs: f64 = secs.parse().map_err(|_| invalid())?;
+        Duration::try_from_secs_f64(secs).map_err(|_| invalid())
+    } else {
+        Err(invalid())
+    }
+}
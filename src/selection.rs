@@ -0,0 +1,98 @@
+//! Strategies that pick cleaners automatically. [`Recommended`] is the
+//! curated safe set behind the TUI's `R` key and `cleansys clean
+//! --recommended`; further heuristics are added as strategies of their own.
+
+use std::time::Duration;
+
+use crate::cleaners::{groups, CleanerGroup, RiskLevel};
+
+/// Files modified within this time are kept by a recommended run (30 days)
+pub const RECOMMENDED_MIN_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Cleaners whose scan finds less than this aren't worth recommending (1 MB)
+pub const RECOMMENDED_MIN_SIZE: u64 = 1024 * 1024;
+
+/// Cleaners that only remove data regenerated on demand or already thrown
+/// away: browser and application caches, package caches, the trash and
+/// rotated logs
+const RECOMMENDED: &[&str] = &[
+    "Browser Caches",
+    "Application Caches",
+    "Package Manager Caches",
+    "Trash",
+    "System Logs",
+    "Library Caches",
+    "Homebrew Cache",
+    "Local App Caches",
+    "Recycle Bin",
+    "pkg Cache",
+];
+
+/// What a strategy knows about a cleaner
+#[derive(Debug, Clone, Copy)]
+pub struct Candidate<'a> {
+    pub name: &'a str,
+    pub risk: RiskLevel,
+    /// Bytes a scan expects the cleaner to free; `None` when not scanned
+    pub estimate: Option<u64>,
+}
+
+/// A way of picking cleaners
+pub trait SelectionStrategy {
+    /// Shown when the strategy is applied
+    fn name(&self) -> &'static str;
+
+    /// Whether the strategy picks this cleaner
+    fn selects(&self, candidate: &Candidate) -> bool;
+
+    /// Files modified more recently than this are kept while the picked
+    /// cleaners run
+    fn min_age(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// The curated safe set, leaving out cleaners a scan found next to nothing
+/// for. Cleaners that can't be scanned are picked on trust.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Recommended;
+
+impl SelectionStrategy for Recommended {
+    fn name(&self) -> &'static str {
+        "Recommended"
+    }
+
+    fn selects(&self, candidate: &Candidate) -> bool {
+        RECOMMENDED.contains(&candidate.name)
+            && candidate.risk == RiskLevel::Normal
+            && candidate
+                .estimate
+                .is_none_or(|bytes| bytes >= RECOMMENDED_MIN_SIZE)
+    }
+
+    fn min_age(&self) -> Option<Duration> {
+        Some(RECOMMENDED_MIN_AGE)
+    }
+}
+
+/// The available cleaners `strategy` picks, by group. Cleaners that support
+/// scanning are scanned first so the strategy can judge their estimates.
+pub fn pick(strategy: &dyn SelectionStrategy) -> Vec<CleanerGroup> {
+    groups()
+        .into_iter()
+        .filter_map(|mut group| {
+            group.cleaners.retain(|cleaner| {
+                cleaner.is_available()
+                    && strategy.selects(&Candidate {
+                        name: cleaner.name,
+                        risk: cleaner.risk,
+                        estimate: cleaner
+                            .scan
+                            .and_then(|scan| scan().ok())
+                            .map(|report| report.total_reclaimable()),
+                    })
+            });
+            (!group.cleaners.is_empty()).then_some(group)
+        })
+        .collect()
+}
@@ -0,0 +1,63 @@
+//! Heat colors for sizes in listings, so the big items stand out: green
+//! below 10 MB, yellow below 500 MB, red from 500 MB up.
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::Span,
+};
+
+use crate::utils::format_size;
+
+/// Sizes from here up are shown as warm
+pub const WARM_FROM: u64 = 10 * 1024 * 1024;
+
+/// Sizes from here up are shown as hot
+pub const HOT_FROM: u64 = 500 * 1024 * 1024;
+
+/// How large a size is, for coloring
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Heat {
+    Cool,
+    Warm,
+    Hot,
+}
+
+impl Heat {
+    pub fn of(bytes: u64) -> Self {
+        if bytes >= HOT_FROM {
+            Heat::Hot
+        } else if bytes >= WARM_FROM {
+            Heat::Warm
+        } else {
+            Heat::Cool
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            Heat::Cool => Color::Green,
+            Heat::Warm => Color::Yellow,
+            Heat::Hot => Color::Red,
+        }
+    }
+
+    /// Style for a size, bold once it is hot
+    pub fn style(&self) -> Style {
+        let style = Style::default().fg(self.color());
+        if *self == Heat::Hot {
+            style.add_modifier(Modifier::BOLD)
+        } else {
+            style
+        }
+    }
+}
+
+/// A formatted size colored by its heat
+pub fn size_span(bytes: u64) -> Span<'static> {
+    sized_span(format_size(bytes), bytes)
+}
+
+/// Text about a size (e.g. "(1.20 GB)") colored by the size's heat
+pub fn sized_span(text: impl Into<String>, bytes: u64) -> Span<'static> {
+    Span::styled(text.into(), Heat::of(bytes).style())
+}
@@ -0,0 +1,74 @@
+//! TUI session state persisted between runs, so reopening the app restores
+//! the previous selections and view.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::app::{ChartType, ViewMode};
+use crate::paths;
+use crate::privileges;
+use crate::usage::UsageStats;
+
+/// A selected cleaner, identified by category and cleaner name so the
+/// selection survives cleaners being added or reordered.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SelectedCleaner {
+    pub category: String,
+    pub name: String,
+}
+
+/// Everything restored when the TUI is reopened
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionState {
+    pub selected: Vec<SelectedCleaner>,
+    pub view_mode: Option<ViewMode>,
+    pub chart_type: Option<ChartType>,
+    /// Progress screen split set with `+`/`-`, see [`crate::app::App::progress_split`]
+    pub progress_split: Option<u16>,
+    pub category_index: usize,
+    pub item_index: Option<usize>,
+    pub detailed_scroll: Option<usize>,
+    /// Local usage statistics, see [`crate::usage`]
+    pub usage: UsageStats,
+}
+
+impl SessionState {
+    /// Location of the state file, in the XDG state directory when there is one
+    pub fn path() -> Option<PathBuf> {
+        paths::state_dir().ok().map(|dir| dir.join("state.json"))
+    }
+
+    /// Load the previous session, or an empty one if there is none
+    pub fn load() -> Result<Self> {
+        match Self::path() {
+            Some(path) if path.exists() => Self::load_from(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Load a session from a specific file
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read state file {:?}", path))?;
+        serde_json::from_str(&contents).with_context(|| format!("Invalid state file {:?}", path))
+    }
+
+    /// Save the session to the default location, as the user who ran sudo
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path().context("No state directory available")?;
+        privileges::as_user(|| self.save_to(&path))
+    }
+
+    /// Save the session to a specific file, creating parent directories
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create state directory {:?}", parent))?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write state file {:?}", path))
+    }
+}
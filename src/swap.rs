@@ -0,0 +1,252 @@
+//! Advisor for swapfiles and hibernation images. These are often the
+//! largest single files on a system, but removing one that is in use or
+//! still referenced by /etc/fstab breaks the next boot, so nothing here
+//! runs as a cleaner: findings are reported, and removal is a separate,
+//! doubly confirmed step for files that are provably unused.
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use crate::filesystem::{filesystem, root_path};
+use crate::mounts;
+
+/// Where distributions and installers usually put swapfiles
+pub const SWAPFILE_CANDIDATES: [&str; 4] =
+    ["/swapfile", "/swap.img", "/swap/swapfile", "/var/swap"];
+
+/// Active swapfiles bigger than this many times the RAM are reported
+pub const LARGE_SWAP_FACTOR: u64 = 2;
+
+/// Active swapfiles are only reported as large above this size
+pub const LARGE_SWAP_MIN: u64 = 4 * 1024 * 1024 * 1024;
+
+/// Page sizes whose swap header signature is checked
+const PAGE_SIZES: [u64; 3] = [4096, 16384, 65536];
+
+/// Signature `mkswap` writes at the end of the first page
+const SWAP_SIGNATURES: [&[u8]; 2] = [b"SWAPSPACE2", b"SWAP-SPACE"];
+
+/// Signatures left when a hibernation image was written to the swap space
+const HIBERNATION_SIGNATURES: [&[u8]; 4] =
+    [b"S1SUSPEND", b"S2SUSPEND", b"ULSUSPEND", b"LINHIB0001"];
+
+/// What the header of a swap area says it holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapSignature {
+    /// Plain swap space
+    Swap,
+    /// A hibernation image that hasn't been resumed from
+    Hibernation,
+}
+
+/// An entry of /proc/swaps
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActiveSwap {
+    pub path: PathBuf,
+    /// Whether it is a file rather than a partition
+    pub is_file: bool,
+    pub size: u64,
+    pub used: u64,
+}
+
+/// What kind of problem a finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FindingKind {
+    /// An active swapfile far bigger than the RAM it backs
+    LargeSwapfile,
+    /// A swapfile that isn't enabled
+    InactiveSwapfile,
+    /// A swap area still holding a hibernation image the system never
+    /// resumed from
+    StaleHibernationImage,
+}
+
+/// A swapfile worth a look
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SwapFinding {
+    pub path: PathBuf,
+    pub size: u64,
+    pub kind: FindingKind,
+    /// Referenced by /etc/fstab, so it is enabled again at boot
+    pub in_fstab: bool,
+}
+
+impl SwapFinding {
+    /// Whether the file can be deleted without breaking anything: it is
+    /// not in use and no fstab entry will look for it at boot
+    pub fn removable(&self) -> bool {
+        self.kind != FindingKind::LargeSwapfile && !self.in_fstab
+    }
+
+    /// What to do about it
+    pub fn advice(&self) -> &'static str {
+        match (self.kind, self.in_fstab) {
+            (FindingKind::LargeSwapfile, _) => {
+                "In use. To shrink it: swapoff, recreate it smaller with mkswap, swapon"
+            }
+            (FindingKind::InactiveSwapfile, true) => {
+                "Not enabled but listed in /etc/fstab; remove that line before deleting it"
+            }
+            (FindingKind::InactiveSwapfile, false) => "Not enabled and unused; safe to delete",
+            (FindingKind::StaleHibernationImage, true) => {
+                "Holds an old hibernation image; run mkswap on it to reuse it as swap"
+            }
+            (FindingKind::StaleHibernationImage, false) => {
+                "Holds an old hibernation image and isn't used as swap; safe to delete"
+            }
+        }
+    }
+}
+
+/// Parse /proc/swaps. Sizes there are in KiB.
+pub fn parse_proc_swaps(contents: &str) -> Vec<ActiveSwap> {
+    contents
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [path, kind, size, used, ..] = fields.as_slice() else {
+                return None;
+            };
+            Some(ActiveSwap {
+                path: PathBuf::from(mounts::unescape(path)),
+                is_file: *kind == "file",
+                size: size.parse::<u64>().ok()? * 1024,
+                used: used.parse::<u64>().ok()? * 1024,
+            })
+        })
+        .collect()
+}
+
+/// Swap entries in /etc/fstab that point at files or devices by path
+pub fn parse_fstab_swaps(contents: &str) -> Vec<PathBuf> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                [source, _, "swap", ..] if source.starts_with('/') => {
+                    Some(PathBuf::from(mounts::unescape(source)))
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// `MemTotal` from /proc/meminfo, in bytes
+pub fn parse_mem_total(meminfo: &str) -> Option<u64> {
+    let line = meminfo.lines().find(|l| l.starts_with("MemTotal:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+/// Read the signature at the end of the first page of a swap area
+pub fn swap_signature(path: &Path) -> Option<SwapSignature> {
+    let mut file = File::open(path).ok()?;
+    for page_size in PAGE_SIZES {
+        let mut tail = [0u8; 10];
+        if file.seek(SeekFrom::Start(page_size - 10)).is_err()
+            || file.read_exact(&mut tail).is_err()
+        {
+            continue;
+        }
+        if SWAP_SIGNATURES.iter().any(|sig| tail.starts_with(sig)) {
+            return Some(SwapSignature::Swap);
+        }
+        if HIBERNATION_SIGNATURES
+            .iter()
+            .any(|sig| tail.starts_with(sig))
+        {
+            return Some(SwapSignature::Hibernation);
+        }
+    }
+    None
+}
+
+/// Findings for the given swapfile candidates. `candidates` are real paths
+/// to inspect; `active` and `fstab` use the paths the system sees, which
+/// `system_path` maps a candidate back to.
+pub fn analyze(
+    candidates: &[PathBuf],
+    active: &[ActiveSwap],
+    fstab: &[PathBuf],
+    mem_total: u64,
+    system_path: impl Fn(&Path) -> PathBuf,
+) -> Vec<SwapFinding> {
+    let mut findings = Vec::new();
+    let mut seen = Vec::new();
+
+    for candidate in candidates {
+        let path = system_path(candidate);
+        if seen.contains(&path) || !candidate.symlink_metadata().is_ok_and(|m| m.is_file()) {
+            continue;
+        }
+        seen.push(path.clone());
+        let size = fs::metadata(candidate).map_or(0, |m| m.len());
+        let in_fstab = fstab.contains(&path);
+
+        if let Some(swap) = active.iter().find(|swap| swap.path == path) {
+            let large = swap.size > LARGE_SWAP_MIN && swap.size > LARGE_SWAP_FACTOR * mem_total;
+            if large {
+                findings.push(SwapFinding {
+                    path,
+                    size: swap.size,
+                    kind: FindingKind::LargeSwapfile,
+                    in_fstab,
+                });
+            }
+            continue;
+        }
+
+        let kind = match swap_signature(candidate) {
+            Some(SwapSignature::Swap) => FindingKind::InactiveSwapfile,
+            Some(SwapSignature::Hibernation) => FindingKind::StaleHibernationImage,
+            // Not a swap area; whatever it is, it isn't ours to judge
+            None => continue,
+        };
+        findings.push(SwapFinding {
+            path,
+            size,
+            kind,
+            in_fstab,
+        });
+    }
+    findings
+}
+
+/// Inspect this system's swapfiles
+pub fn scan() -> Vec<SwapFinding> {
+    let read = |path: &str| fs::read_to_string(root_path(path)).unwrap_or_default();
+    let active = parse_proc_swaps(&read("/proc/swaps"));
+    let fstab = parse_fstab_swaps(&read("/etc/fstab"));
+    let mem_total = parse_mem_total(&read("/proc/meminfo")).unwrap_or(0);
+
+    let mut candidates: Vec<PathBuf> = SWAPFILE_CANDIDATES.iter().map(PathBuf::from).collect();
+    candidates.extend(fstab.iter().cloned());
+    candidates.extend(active.iter().filter(|s| s.is_file).map(|s| s.path.clone()));
+    let candidates: Vec<PathBuf> = candidates.iter().map(root_path).collect();
+
+    let root = root_path("/");
+    analyze(&candidates, &active, &fstab, mem_total, |path| {
+        Path::new("/").join(path.strip_prefix(&root).unwrap_or(path))
+    })
+}
+
+/// Delete a finding's file. Refuses anything that isn't provably unused.
+pub fn remove(finding: &SwapFinding) -> Result<u64> {
+    if !finding.removable() {
+        bail!(
+            "{} is in use or listed in /etc/fstab",
+            finding.path.display()
+        );
+    }
+    filesystem().remove_file(&root_path(&finding.path))?;
+    Ok(finding.size)
+}
@@ -0,0 +1,107 @@
+//! The system information panel of the TUI: host, distribution, kernel,
+//! how full the root and home filesystems are, and when cleansys last
+//! cleaned. Read once each time the panel is opened.
+
+use std::fs;
+
+use crate::filesystem::{home_dir, root_path};
+use crate::history::History;
+use crate::utils::{get_disk_space, DiskSpace};
+
+/// What the system information panel shows
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SystemInfo {
+    pub hostname: String,
+    /// `PRETTY_NAME` from os-release, or the OS family without one
+    pub distro: String,
+    /// Kernel release, like `uname -r`
+    pub kernel: String,
+    pub root: Option<DiskSpace>,
+    /// None when home is on the root filesystem
+    pub home: Option<DiskSpace>,
+    /// Local time of the last recorded run
+    pub last_clean: Option<String>,
+}
+
+impl SystemInfo {
+    /// Read the current system information
+    pub fn gather() -> Self {
+        let root = get_disk_space(&root_path("/"));
+        let home = home_dir()
+            .ok()
+            .and_then(|home| get_disk_space(&home))
+            .filter(|home| root.is_none_or(|root| root.device != home.device));
+        Self {
+            hostname: hostname().unwrap_or_else(|| "unknown".to_string()),
+            distro: fs::read_to_string(root_path("/etc/os-release"))
+                .ok()
+                .and_then(|contents| os_release_name(&contents))
+                .unwrap_or_else(|| std::env::consts::OS.to_string()),
+            kernel: kernel().unwrap_or_default(),
+            root,
+            home,
+            last_clean: History::open()
+                .ok()
+                .and_then(|history| last_clean(&history)),
+        }
+    }
+}
+
+/// The `PRETTY_NAME` of an os-release file, falling back to `NAME`
+pub fn os_release_name(contents: &str) -> Option<String> {
+    let value = |key: &str| {
+        contents.lines().find_map(|line| {
+            let value = line.trim().strip_prefix(key)?.strip_prefix('=')?;
+            let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+            (!value.is_empty()).then(|| value.to_string())
+        })
+    };
+    value("PRETTY_NAME").or_else(|| value("NAME"))
+}
+
+/// When the last run in `history` was recorded
+pub fn last_clean(history: &History) -> Option<String> {
+    history.load().ok()?.last().map(|run| run.time.clone())
+}
+
+/// A usage bar like `███████░░░` for a share from 0.0 to 1.0
+pub fn usage_bar(ratio: f64, width: usize) -> String {
+    let filled = ((ratio.clamp(0.0, 1.0) * width as f64).round() as usize).min(width);
+    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+}
+
+#[cfg(unix)]
+fn hostname() -> Option<String> {
+    let mut buffer = [0u8; 256];
+    // SAFETY: the buffer is valid for its length, and gethostname
+    // NUL-terminates within it on success
+    if unsafe { libc::gethostname(buffer.as_mut_ptr().cast(), buffer.len()) } != 0 {
+        return None;
+    }
+    let end = buffer.iter().position(|&b| b == 0).unwrap_or(buffer.len());
+    Some(String::from_utf8_lossy(&buffer[..end]).to_string())
+}
+
+#[cfg(not(unix))]
+fn hostname() -> Option<String> {
+    std::env::var("COMPUTERNAME").ok()
+}
+
+#[cfg(unix)]
+fn kernel() -> Option<String> {
+    use std::ffi::CStr;
+
+    let mut name: libc::utsname = unsafe { std::mem::zeroed() };
+    // SAFETY: name is a valid out pointer
+    if unsafe { libc::uname(&mut name) } != 0 {
+        return None;
+    }
+    // SAFETY: uname NUL-terminates each field
+    let release = unsafe { CStr::from_ptr(name.release.as_ptr()) };
+    Some(release.to_string_lossy().to_string())
+}
+
+#[cfg(not(unix))]
+fn kernel() -> Option<String> {
+    None
+}
@@ -0,0 +1,208 @@
+//! Terminal setup and restoration for the TUI, including restoring the
+//! terminal when the process panics or is killed by a signal.
+
+use anyhow::{Context, Result};
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{prelude::CrosstermBackend, Terminal};
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::process::Command;
+use std::sync::atomic::{AtomicI32, Ordering};
+#[cfg(unix)]
+use std::sync::OnceLock;
+#[cfg(unix)]
+use std::time::Duration;
+
+#[cfg(unix)]
+use crate::cancel;
+
+/// How long a signal waits for the event loop to stop a run cleanly before
+/// the process is terminated anyway
+#[cfg(unix)]
+const SIGNAL_GRACE: Duration = Duration::from_secs(3);
+
+static RECEIVED_SIGNAL: AtomicI32 = AtomicI32::new(0);
+
+/// Narrowest terminal the TUI is drawn in
+pub const MIN_WIDTH: u16 = 40;
+
+/// Shortest terminal the TUI is drawn in
+pub const MIN_HEIGHT: u16 = 10;
+
+/// Terminal types without an alternate screen or cursor addressing good
+/// enough for the TUI
+const LIMITED_TERMS: [&str; 6] = ["dumb", "unknown", "vt52", "vt100", "vt102", "emacs"];
+
+/// Why the TUI can't be drawn in a terminal, or `None` if it can. Takes the
+/// `TERM` value, whether stdin and stdout are terminals and the terminal size.
+pub fn unsupported_reason(
+    term: Option<&str>,
+    is_terminal: bool,
+    size: Option<(u16, u16)>,
+) -> Option<String> {
+    if !is_terminal {
+        return Some("Not running in a terminal".to_string());
+    }
+    match term.map(str::trim) {
+        None | Some("") => return Some("TERM is not set".to_string()),
+        Some(term) if LIMITED_TERMS.contains(&term) => {
+            return Some(format!("Terminal type '{}' has no alternate screen", term))
+        }
+        _ => {}
+    }
+    match size {
+        None => Some("Unable to read the terminal size".to_string()),
+        Some((width, height)) if width < MIN_WIDTH || height < MIN_HEIGHT => Some(format!(
+            "Terminal is {}x{}, the interface needs at least {}x{}",
+            width, height, MIN_WIDTH, MIN_HEIGHT
+        )),
+        Some(_) => None,
+    }
+}
+
+/// Check the current terminal before starting the TUI
+pub fn tui_unsupported() -> Option<String> {
+    let term = std::env::var("TERM").ok();
+    let is_terminal = io::stdin().is_terminal() && io::stdout().is_terminal();
+    unsupported_reason(
+        term.as_deref(),
+        is_terminal,
+        crossterm::terminal::size().ok(),
+    )
+}
+
+/// Where the TUI draws. On Unix this is a copy of stdout taken before the
+/// first cleaner runs, so the screen keeps working while a cleaner on the
+/// worker thread has stdout redirected to capture what it prints.
+#[cfg(unix)]
+pub type Screen = io::BufWriter<std::fs::File>;
+#[cfg(not(unix))]
+pub type Screen = io::Stdout;
+
+/// The terminal the TUI is drawn in
+pub type Tui = Terminal<CrosstermBackend<Screen>>;
+
+#[cfg(unix)]
+fn screen_file() -> io::Result<std::fs::File> {
+    use std::os::fd::AsFd;
+
+    static SCREEN: OnceLock<std::fs::File> = OnceLock::new();
+    if let Some(screen) = SCREEN.get() {
+        return screen.try_clone();
+    }
+    let screen = std::fs::File::from(io::stdout().as_fd().try_clone_to_owned()?);
+    SCREEN.get_or_init(|| screen).try_clone()
+}
+
+#[cfg(unix)]
+fn screen() -> io::Result<Screen> {
+    Ok(io::BufWriter::new(screen_file()?))
+}
+
+#[cfg(not(unix))]
+fn screen() -> io::Result<Screen> {
+    Ok(io::stdout())
+}
+
+/// Switch to raw mode and the alternate screen, and make sure the terminal
+/// is put back even if we panic or receive SIGINT/SIGTERM/SIGHUP.
+pub fn setup() -> Result<Tui> {
+    install_panic_hook();
+    install_signal_handlers()?;
+
+    enable_raw_mode()?;
+    let mut screen = screen()?;
+    execute!(screen, EnterAlternateScreen, EnableMouseCapture)?;
+    Ok(Terminal::new(CrosstermBackend::new(screen))?)
+}
+
+/// Leave raw mode and the alternate screen. Safe to call more than once.
+pub fn restore() -> Result<()> {
+    disable_raw_mode()?;
+    execute!(
+        screen()?,
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        crossterm::cursor::Show
+    )?;
+    Ok(())
+}
+
+/// Hand the terminal to an interactive shell in `dir` and take it back once
+/// the shell exits. The caller must stop reading events meanwhile.
+pub fn run_shell(terminal: &mut Tui, dir: &Path) -> Result<()> {
+    use std::io::Write;
+
+    restore()?;
+    let shell = if cfg!(windows) {
+        std::env::var("COMSPEC").unwrap_or_else(|_| "cmd.exe".to_string())
+    } else {
+        std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string())
+    };
+    let mut screen = screen()?;
+    writeln!(
+        screen,
+        "{}: type 'exit' to return to cleansys",
+        dir.display()
+    )?;
+    screen.flush()?;
+    let mut command = Command::new(&shell);
+    command.current_dir(dir);
+    // A cleaner may be running with stdout captured, the shell gets the screen
+    #[cfg(unix)]
+    command.stdout(screen_file()?).stderr(screen_file()?);
+    let status = command.status();
+
+    enable_raw_mode()?;
+    execute!(screen, EnterAlternateScreen, EnableMouseCapture)?;
+    terminal.clear()?;
+    status.with_context(|| format!("Failed to start {}", shell))?;
+    Ok(())
+}
+
+/// The signal asking the TUI to exit, if one arrived
+pub fn received_signal() -> Option<i32> {
+    match RECEIVED_SIGNAL.load(Ordering::SeqCst) {
+        0 => None,
+        signal => Some(signal),
+    }
+}
+
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        // Restore first so the panic message isn't swallowed by the alternate screen
+        let _ = restore();
+        default_hook(info);
+    }));
+}
+
+#[cfg(unix)]
+fn install_signal_handlers() -> Result<()> {
+    use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+    use signal_hook::iterator::Signals;
+
+    let mut signals = Signals::new([SIGINT, SIGTERM, SIGHUP])?;
+    std::thread::spawn(move || {
+        if let Some(signal) = signals.forever().next() {
+            // Let the deletion in progress finish its current file and the
+            // event loop shut down; force the exit if that takes too long
+            RECEIVED_SIGNAL.store(signal, Ordering::SeqCst);
+            cancel::request();
+            crate::children::terminate_all();
+            std::thread::sleep(SIGNAL_GRACE);
+            let _ = restore();
+            std::process::exit(128 + signal);
+        }
+    });
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn install_signal_handlers() -> Result<()> {
+    Ok(())
+}
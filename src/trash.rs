@@ -0,0 +1,221 @@
+//! Moving files to the desktop Trash following the freedesktop.org Trash
+//! specification, the same layout `gio trash` and trash-cli use, so items
+//! show up in the file manager's Trash and can be restored from there.
+
+use anyhow::{bail, Context, Result};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+#[cfg(unix)]
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+use crate::audit;
+use crate::cancel;
+use crate::filesystem::data_dir;
+use crate::limits;
+use crate::pipeline::Pipeline;
+use crate::protection;
+use crate::quarantine::{QuarantineEntry, QuarantineReport};
+use crate::utils::local_time;
+
+/// A Trash directory holding `files/` and `info/`
+pub struct Trash {
+    dir: PathBuf,
+}
+
+impl Trash {
+    /// The user's home Trash, `$XDG_DATA_HOME/Trash`
+    pub fn home() -> Result<Self> {
+        Ok(Self::at(data_dir()?.join("Trash")))
+    }
+
+    /// A Trash at a specific directory
+    pub fn at(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Move one file or directory to the Trash, writing its `.trashinfo`.
+    /// Protected paths, a cancelled run and the safety limit stop it like
+    /// a deletion.
+    pub fn trash(&self, path: &Path) -> Result<QuarantineEntry> {
+        cancel::check()?;
+        let original = std::path::absolute(path)?;
+        protection::guard(&original)?;
+        let metadata = fs::symlink_metadata(&original)
+            .with_context(|| format!("Failed to stat {:?}", original))?;
+        let Some(name) = original.file_name() else {
+            bail!("Cannot trash {:?}", original);
+        };
+        let (files, size) = if metadata.is_dir() {
+            tree_size(&original)
+        } else {
+            (1, metadata.len())
+        };
+        limits::check(files, size)?;
+
+        let files_dir = self.dir.join("files");
+        let info_dir = self.dir.join("info");
+        fs::create_dir_all(&files_dir)
+            .with_context(|| format!("Failed to create trash directory {:?}", files_dir))?;
+        fs::create_dir_all(&info_dir)
+            .with_context(|| format!("Failed to create trash directory {:?}", info_dir))?;
+
+        audit::record(&original)?;
+
+        // Creating the info file exclusively reserves the name, so parallel
+        // workers trashing files with the same name can't collide
+        let name = name.to_string_lossy();
+        let mut counter = 1;
+        let (trash_name, info_path) = loop {
+            let candidate = if counter == 1 {
+                name.to_string()
+            } else {
+                format!("{}.{}", name, counter)
+            };
+            let info_path = info_dir.join(format!("{}.trashinfo", candidate));
+            match OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&info_path)
+            {
+                Ok(mut file) if !files_dir.join(&candidate).exists() => {
+                    file.write_all(trash_info(&original).as_bytes())?;
+                    break (candidate, info_path);
+                }
+                Ok(_) => {
+                    fs::remove_file(&info_path).ok();
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+                Err(e) => {
+                    return Err(e).with_context(|| format!("Failed to write {:?}", info_path))
+                }
+            }
+            counter += 1;
+        };
+
+        let stored = files_dir.join(&trash_name);
+        if let Err(e) = move_into_trash(&original, &stored, metadata.is_dir()) {
+            fs::remove_file(&info_path).ok();
+            return Err(e);
+        }
+        limits::record(files, size);
+
+        Ok(QuarantineEntry {
+            original,
+            stored,
+            size,
+            // The file manager restores from the Trash
+            attributes: None,
+        })
+    }
+
+    /// Trash files across the pipeline's workers. Individual failures are
+    /// reported rather than aborting the batch.
+    pub fn move_files(&self, paths: Vec<PathBuf>, pipeline: &Pipeline) -> QuarantineReport {
+        let mut report = QuarantineReport::default();
+        pipeline.run(
+            |tx| {
+                for path in paths {
+                    if tx.send(path).is_err() {
+                        return;
+                    }
+                }
+            },
+            |path: PathBuf| Some(self.trash(&path).map_err(|e| (path, format!("{:#}", e)))),
+            |result| match result {
+                Ok(entry) => report.moved.push(entry),
+                Err(failure) => report.failed.push(failure),
+            },
+        );
+        report.moved.sort_by(|a, b| a.original.cmp(&b.original));
+        report
+    }
+}
+
+/// Number of files below `dir` and their total size, without following
+/// symlinks
+fn tree_size(dir: &Path) -> (u64, u64) {
+    let mut files = 0;
+    let mut bytes = 0;
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                stack.push(entry.path());
+            } else {
+                files += 1;
+                bytes += metadata.len();
+            }
+        }
+    }
+    (files, bytes)
+}
+
+fn move_into_trash(original: &Path, stored: &Path, is_dir: bool) -> Result<()> {
+    match fs::rename(original, stored) {
+        Ok(()) => Ok(()),
+        // Another filesystem: copy into the home Trash like gio does
+        Err(e) if e.kind() == ErrorKind::CrossesDevices && !is_dir => {
+            fs::copy(original, stored)
+                .with_context(|| format!("Failed to copy {:?} to the trash", original))?;
+            fs::remove_file(original).with_context(|| format!("Failed to remove {:?}", original))
+        }
+        Err(e) => Err(e).with_context(|| format!("Failed to move {:?} to the trash", original)),
+    }
+}
+
+/// Contents of a `.trashinfo` file for `original`
+pub fn trash_info(original: &Path) -> String {
+    format!(
+        "[Trash Info]\nPath={}\nDeletionDate={}\n",
+        percent_encode(original),
+        local_timestamp()
+    )
+}
+
+/// Percent-encode a path as the spec requires, keeping `/` and unreserved characters
+fn percent_encode(path: &Path) -> String {
+    path_bytes(path)
+        .iter()
+        .map(|&b| match b {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(unix)]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    path.as_os_str().as_bytes().to_vec()
+}
+
+#[cfg(not(unix))]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    path.to_string_lossy().as_bytes().to_vec()
+}
+
+/// Local time as `YYYY-MM-DDThh:mm:ss`
+fn local_timestamp() -> String {
+    let tm = local_time().unwrap_or_else(|| unsafe { std::mem::zeroed() });
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec
+    )
+}
@@ -0,0 +1,270 @@
+//! `cleansys tray`: an indicator in the GNOME or KDE system tray, through
+//! the StatusNotifierItem protocol. It scans what the cleaners could free
+//! every so often and asks for attention once that passes a threshold.
+//! Clicking it opens the TUI in a terminal; its menu offers a quick clean,
+//! which runs the recommended safe set like `cleansys clean --recommended`.
+
+use anyhow::{Context, Result};
+use ksni::blocking::TrayMethods;
+use ksni::menu::StandardItem;
+use ksni::{MenuItem, Status, ToolTip, Tray};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::time::Duration;
+
+use crate::cleaners::listing;
+use crate::history::History;
+use crate::utils::{check_root, command_exists, format_size};
+
+/// Terminal emulators tried in turn, with the arguments that come before
+/// the command to run
+const TERMINALS: &[(&str, &[&str])] = &[
+    ("x-terminal-emulator", &["-e"]),
+    ("gnome-terminal", &["--"]),
+    ("kgx", &["--"]),
+    ("konsole", &["-e"]),
+    ("xfce4-terminal", &["-x"]),
+    ("alacritty", &["-e"]),
+    ("kitty", &[]),
+    ("foot", &[]),
+    ("wezterm", &["start", "--"]),
+    ("xterm", &["-e"]),
+];
+
+/// What the menu asks of the scan loop
+enum Request {
+    Scan,
+    QuickClean,
+}
+
+struct Indicator {
+    /// Bytes the cleaners could free, None until the first scan is done
+    reclaimable: Option<u64>,
+    threshold: u64,
+    /// What the last quick clean freed
+    last_freed: Option<u64>,
+    busy: bool,
+    requests: Sender<Request>,
+}
+
+impl Indicator {
+    fn over_threshold(&self) -> bool {
+        self.reclaimable
+            .is_some_and(|bytes| bytes >= self.threshold)
+    }
+
+    fn request(&mut self, request: Request) {
+        self.busy = true;
+        let _ = self.requests.send(request);
+    }
+}
+
+impl Tray for Indicator {
+    fn id(&self) -> String {
+        "cleansys".to_string()
+    }
+
+    fn title(&self) -> String {
+        "CleanSys".to_string()
+    }
+
+    fn icon_name(&self) -> String {
+        if self.over_threshold() {
+            "user-trash-full".to_string()
+        } else {
+            "user-trash".to_string()
+        }
+    }
+
+    fn status(&self) -> Status {
+        if self.over_threshold() {
+            Status::NeedsAttention
+        } else {
+            Status::Active
+        }
+    }
+
+    fn attention_icon_name(&self) -> String {
+        "user-trash-full".to_string()
+    }
+
+    fn tool_tip(&self) -> ToolTip {
+        ToolTip {
+            title: "CleanSys".to_string(),
+            description: describe(self.reclaimable, self.threshold, self.busy),
+            ..ToolTip::default()
+        }
+    }
+
+    fn activate(&mut self, _x: i32, _y: i32) {
+        open_tui();
+    }
+
+    fn menu(&self) -> Vec<MenuItem<Self>> {
+        let mut items: Vec<MenuItem<Self>> = vec![StandardItem {
+            label: describe(self.reclaimable, self.threshold, self.busy),
+            enabled: false,
+            ..StandardItem::default()
+        }
+        .into()];
+        if let Some(freed) = self.last_freed {
+            items.push(
+                StandardItem {
+                    label: format!("Last quick clean freed {}", format_size(freed)),
+                    enabled: false,
+                    ..StandardItem::default()
+                }
+                .into(),
+            );
+        }
+        items.extend([
+            MenuItem::Separator,
+            StandardItem {
+                label: "Open CleanSys".to_string(),
+                icon_name: "utilities-terminal".to_string(),
+                activate: Box::new(|_: &mut Self| open_tui()),
+                ..StandardItem::default()
+            }
+            .into(),
+            StandardItem {
+                label: "Quick clean (recommended set)".to_string(),
+                icon_name: "edit-clear".to_string(),
+                enabled: !self.busy,
+                activate: Box::new(|tray: &mut Self| tray.request(Request::QuickClean)),
+                ..StandardItem::default()
+            }
+            .into(),
+            StandardItem {
+                label: "Scan now".to_string(),
+                icon_name: "view-refresh".to_string(),
+                enabled: !self.busy,
+                activate: Box::new(|tray: &mut Self| tray.request(Request::Scan)),
+                ..StandardItem::default()
+            }
+            .into(),
+            MenuItem::Separator,
+            StandardItem {
+                label: "Quit".to_string(),
+                icon_name: "application-exit".to_string(),
+                activate: Box::new(|_: &mut Self| std::process::exit(0)),
+                ..StandardItem::default()
+            }
+            .into(),
+        ]);
+        items
+    }
+}
+
+/// The indicator's status line
+pub fn describe(reclaimable: Option<u64>, threshold: u64, busy: bool) -> String {
+    match reclaimable {
+        _ if busy => "Working…".to_string(),
+        None => "Scanning…".to_string(),
+        Some(bytes) if bytes >= threshold => format!(
+            "{} reclaimable, over {}",
+            format_size(bytes),
+            format_size(threshold)
+        ),
+        Some(bytes) => format!("{} reclaimable", format_size(bytes)),
+    }
+}
+
+/// The command opening `program` in a terminal window: `$TERMINAL` when
+/// set, else the first known emulator `exists` finds
+pub fn terminal_command(
+    terminal: Option<&str>,
+    program: &str,
+    exists: impl Fn(&str) -> bool,
+) -> Option<Vec<String>> {
+    let (name, args): (&str, &[&str]) = match terminal.filter(|t| !t.is_empty()) {
+        Some(terminal) => (terminal, &["-e"]),
+        None => TERMINALS.iter().copied().find(|(name, _)| exists(name))?,
+    };
+    let mut command = vec![name.to_string()];
+    command.extend(args.iter().map(|arg| arg.to_string()));
+    command.push(program.to_string());
+    Some(command)
+}
+
+/// Open the TUI in a terminal window
+fn open_tui() {
+    let program = std::env::current_exe()
+        .map(|exe| exe.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| "cleansys".to_string());
+    let terminal = std::env::var("TERMINAL").ok();
+    let Some(command) = terminal_command(terminal.as_deref(), &program, command_exists) else {
+        log::warn!("No terminal emulator found to open the TUI in");
+        return;
+    };
+    if let Err(e) = Command::new(&command[0]).args(&command[1..]).spawn() {
+        log::warn!("Failed to start {}: {}", command[0], e);
+    }
+}
+
+/// Bytes the cleaners this user can run could free
+fn scan() -> u64 {
+    let root = check_root();
+    listing::list_all(true)
+        .iter()
+        .filter(|listing| root || !listing.requires_root)
+        .filter_map(|listing| listing.reclaimable)
+        .sum()
+}
+
+/// Run the recommended set in a child process, so its output and state
+/// stay out of the indicator, and return what it freed per the history
+fn quick_clean() -> Result<u64> {
+    let history = History::open()?;
+    let last_id = history.load()?.last().map_or(0, |run| run.id);
+    let status = Command::new(std::env::current_exe()?)
+        .args(["clean", "--recommended", "--yes"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .status()
+        .context("Failed to start the quick clean")?;
+    if !status.success() {
+        anyhow::bail!("The quick clean failed with {}", status);
+    }
+    // A run that removed nothing isn't recorded
+    let runs = history.load()?;
+    Ok(runs
+        .last()
+        .filter(|run| run.id > last_id)
+        .map_or(0, |run| run.total()))
+}
+
+/// Show the indicator and rescan every `interval` until quit from its menu
+pub fn run(interval: Duration, threshold: u64) -> Result<()> {
+    let (requests, received): (Sender<Request>, Receiver<Request>) = mpsc::channel();
+    let handle = Indicator {
+        reclaimable: None,
+        threshold,
+        last_freed: None,
+        busy: true,
+        requests,
+    }
+    .spawn()
+    .context("No system tray to show the indicator in (StatusNotifierWatcher)")?;
+
+    let mut request = Request::Scan;
+    loop {
+        if let Request::QuickClean = request {
+            match quick_clean() {
+                Ok(freed) => {
+                    handle.update(|tray| tray.last_freed = Some(freed));
+                }
+                Err(e) => log::warn!("{:#}", e),
+            }
+        }
+        let reclaimable = scan();
+        handle.update(|tray| {
+            tray.reclaimable = Some(reclaimable);
+            tray.busy = false;
+        });
+        request = match received.recv_timeout(interval) {
+            Ok(request) => request,
+            Err(RecvTimeoutError::Timeout) => Request::Scan,
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        };
+    }
+}
@@ -0,0 +1,148 @@
+//! Trimming SSDs after large deletions. Removing files frees their blocks in
+//! the filesystem, but the drive only learns they are unused once the
+//! filesystem discards them, which most systems leave to a weekly
+//! `fstrim.timer`. With `offer_trim = true`, a run as root that freed at
+//! least [`MIN_FREED`] on an SSD or NVMe mount offers to run `fstrim` on it
+//! right away, and reports how much the drive was told about.
+
+use anyhow::{bail, Context, Result};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::cleaners::cleaned::CleanedEntry;
+use crate::concurrency::{self, StorageKind};
+use crate::filesystem::is_sandboxed;
+use crate::netfs;
+use crate::utils::{
+    check_root, command_exists, confirm, execute_with_sudo, format_size, print_success,
+    print_warning,
+};
+
+/// Mounts that freed less than this aren't worth a trim
+pub const MIN_FREED: u64 = 1024 * 1024 * 1024;
+
+static ENABLED: Lazy<Mutex<bool>> = Lazy::new(|| Mutex::new(false));
+
+/// Bytes freed on each mount point since the last [`take_candidates`]
+static FREED: Lazy<Mutex<HashMap<PathBuf, u64>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// A mount that freed enough to be worth trimming
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    pub mount: PathBuf,
+    pub freed: u64,
+    pub storage: StorageKind,
+}
+
+/// Offer to trim after large deletions, from the config file
+pub fn set_enabled(enabled: bool) {
+    *ENABLED.lock().unwrap_or_else(|e| e.into_inner()) = enabled;
+}
+
+/// Whether trimming is offered
+pub fn enabled() -> bool {
+    *ENABLED.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Note what a cleaner removed, adding up the bytes by mount
+pub fn record(entries: &[CleanedEntry]) {
+    if !enabled() || entries.is_empty() {
+        return;
+    }
+    let Ok(mounts) = fs::read_to_string("/proc/self/mounts") else {
+        return;
+    };
+    let mut freed = FREED.lock().unwrap_or_else(|e| e.into_inner());
+    for (mount, bytes) in freed_by_mount(&mounts, entries) {
+        *freed.entry(mount).or_default() += bytes;
+    }
+}
+
+/// Bytes freed by `entries` on each mount point of a mount table in
+/// `/proc/mounts` format. Entries that aren't absolute paths, like the
+/// packages a package manager removed, are left out.
+pub fn freed_by_mount(mounts: &str, entries: &[CleanedEntry]) -> HashMap<PathBuf, u64> {
+    let mut freed = HashMap::new();
+    for entry in entries.iter().filter(|entry| entry.path.is_absolute()) {
+        if let Some(mount) = netfs::mount_point(mounts, &entry.path) {
+            *freed.entry(mount).or_default() += entry.bytes;
+        }
+    }
+    freed
+}
+
+/// The mounts worth trimming since the last call, largest first. Empty
+/// unless trimming is enabled and cleansys runs as root.
+pub fn take_candidates() -> Vec<Candidate> {
+    let freed = std::mem::take(&mut *FREED.lock().unwrap_or_else(|e| e.into_inner()));
+    // Never trim the real system from a sandboxed run
+    if !enabled() || !check_root() || is_sandboxed() || !command_exists("fstrim") {
+        return Vec::new();
+    }
+    let mut candidates: Vec<Candidate> = freed
+        .into_iter()
+        .filter(|(_, freed)| *freed >= MIN_FREED)
+        .map(|(mount, freed)| Candidate {
+            storage: concurrency::storage_kind(&mount),
+            mount,
+            freed,
+        })
+        .filter(|candidate| matches!(candidate.storage, StorageKind::Ssd | StorageKind::Nvme))
+        .collect();
+    candidates.sort_by_key(|candidate| std::cmp::Reverse(candidate.freed));
+    candidates
+}
+
+/// Run `fstrim` on `mount`, returning the bytes it trimmed
+pub fn trim(mount: &Path) -> Result<u64> {
+    let output = execute_with_sudo("fstrim", &["-v", &mount.to_string_lossy()])?;
+    if !output.status.success() {
+        bail!(
+            "fstrim failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    parse_trimmed(&stdout).with_context(|| format!("Unexpected fstrim output: {}", stdout.trim()))
+}
+
+/// Offer to trim each mount worth it on the command line, asking first
+/// unless `skip_confirmation` is set
+pub fn offer(skip_confirmation: bool) -> Result<()> {
+    for candidate in take_candidates() {
+        let prompt = format!(
+            "Freed {} on {} ({}). Run fstrim so the drive can reuse it?",
+            format_size(candidate.freed),
+            candidate.mount.display(),
+            candidate.storage.name()
+        );
+        if !skip_confirmation && !confirm(&prompt, true)? {
+            continue;
+        }
+        match trim(&candidate.mount) {
+            Ok(trimmed) => print_success(&format!(
+                "Trimmed {} on {}",
+                format_size(trimmed),
+                candidate.mount.display()
+            )),
+            Err(e) => print_warning(&format!(
+                "Couldn't trim {}: {:#}",
+                candidate.mount.display(),
+                e
+            )),
+        }
+    }
+    Ok(())
+}
+
+/// The bytes trimmed according to `fstrim -v`, which prints e.g.
+/// `/: 12.3 GiB (13207024640 bytes) trimmed on /dev/nvme0n1p2`
+pub fn parse_trimmed(output: &str) -> Option<u64> {
+    // From the right, as the mount point may hold parentheses itself
+    let (before, _) = output.rsplit_once(" bytes)")?;
+    let (_, bytes) = before.rsplit_once('(')?;
+    bytes.trim().parse().ok()
+}
@@ -0,0 +1,105 @@
+//! Usage statistics kept only on this machine: how many runs, which
+//! cleaners are used most, and how much has been freed overall. They live
+//! in the TUI state file next to the saved selection and are never sent
+//! anywhere; cleansys makes no network requests.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::history::RunRecord;
+use crate::state::SessionState;
+use crate::utils::format_size;
+
+/// Cleaners listed as most used
+pub const MOST_USED_LIMIT: usize = 5;
+
+/// How often one cleaner ran and what it freed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CleanerUsage {
+    pub runs: u64,
+    pub bytes_freed: u64,
+}
+
+/// Counters across every run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UsageStats {
+    pub runs: u64,
+    pub bytes_freed: u64,
+    /// When the first counted run happened, `YYYY-MM-DD hh:mm:ss`
+    pub since: Option<String>,
+    pub cleaners: BTreeMap<String, CleanerUsage>,
+}
+
+impl UsageStats {
+    /// Count a finished run. Runs in which nothing ran aren't counted.
+    pub fn record(&mut self, run: &RunRecord) {
+        if run.cleaners.is_empty() {
+            return;
+        }
+        self.runs += 1;
+        self.bytes_freed += run.total();
+        if self.since.is_none() {
+            self.since = Some(run.time.clone());
+        }
+        for cleaner in &run.cleaners {
+            let usage = self.cleaners.entry(cleaner.name.clone()).or_default();
+            usage.runs += 1;
+            usage.bytes_freed += cleaner.bytes;
+        }
+    }
+
+    /// Cleaners run most often, ties broken by bytes freed
+    pub fn most_used(&self, limit: usize) -> Vec<(&str, CleanerUsage)> {
+        let mut cleaners: Vec<(&str, CleanerUsage)> = self
+            .cleaners
+            .iter()
+            .map(|(name, usage)| (name.as_str(), *usage))
+            .collect();
+        cleaners.sort_by(|a, b| {
+            b.1.runs
+                .cmp(&a.1.runs)
+                .then(b.1.bytes_freed.cmp(&a.1.bytes_freed))
+        });
+        cleaners.truncate(limit);
+        cleaners
+    }
+
+    /// Lines for the About screen
+    pub fn summary_lines(&self) -> Vec<String> {
+        if self.runs == 0 {
+            return vec!["No runs counted yet".to_string()];
+        }
+        let mut lines = vec![
+            format!("Total freed: {}", format_size(self.bytes_freed)),
+            format!(
+                "Runs: {}{}",
+                self.runs,
+                self.since
+                    .as_deref()
+                    .map(|since| format!(" since {}", since))
+                    .unwrap_or_default()
+            ),
+            String::new(),
+            "Most used cleaners:".to_string(),
+        ];
+        for (name, usage) in self.most_used(MOST_USED_LIMIT) {
+            lines.push(format!(
+                "  {} ({} runs, {})",
+                name,
+                usage.runs,
+                format_size(usage.bytes_freed)
+            ));
+        }
+        lines
+    }
+}
+
+/// Count a run from the command line in the state file
+pub fn record_run(run: &RunRecord) -> Result<()> {
+    let mut state = SessionState::load()?;
+    state.usage.record(run);
+    state.save()
+}
@@ -1,14 +1,17 @@
 use anyhow::{Context, Result};
 use colored::*;
-use std::io::{self, Write};
+use once_cell::sync::Lazy;
+use std::io::{self, IsTerminal, Write};
 use std::process::Command;
+use std::sync::Mutex;
 #[cfg(unix)]
 use users::get_effective_uid;
 
-/// Check if the program is running with root privileges
+/// Check if the program is running with root privileges, or can take
+/// them back after dropping to the user who ran sudo
 #[cfg(unix)]
 pub fn check_root() -> bool {
-    get_effective_uid() == 0
+    get_effective_uid() == 0 || crate::privileges::dropped()
 }
 
 #[cfg(not(unix))]
@@ -16,6 +19,42 @@ pub fn check_root() -> bool {
     false
 }
 
+static PREFER_ASKPASS: Lazy<Mutex<bool>> = Lazy::new(|| Mutex::new(false));
+
+/// Authenticate through `SUDO_ASKPASS` even on a terminal, from the config
+/// file
+pub fn set_prefer_askpass(prefer: bool) {
+    *PREFER_ASKPASS.lock().unwrap_or_else(|e| e.into_inner()) = prefer;
+}
+
+/// Decide whether sudo should ask for the password through the
+/// `SUDO_ASKPASS` helper (`sudo -A`) instead of a prompt of our own.
+///
+/// A helper has to be set. It is used when asked to in the config, and
+/// whenever there is no terminal to type a password into.
+pub fn should_use_askpass(askpass: Option<&str>, prefer: bool, is_tty: bool) -> bool {
+    askpass.is_some_and(|helper| !helper.trim().is_empty()) && (prefer || !is_tty)
+}
+
+/// Whether this process authenticates through `SUDO_ASKPASS`
+pub fn askpass_enabled() -> bool {
+    should_use_askpass(
+        std::env::var("SUDO_ASKPASS").ok().as_deref(),
+        *PREFER_ASKPASS.lock().unwrap_or_else(|e| e.into_inner()),
+        io::stdin().is_terminal(),
+    )
+}
+
+/// Cache sudo credentials, letting the `SUDO_ASKPASS` helper ask for the
+/// password
+pub fn authenticate_with_askpass() -> Result<bool> {
+    let status = Command::new("sudo")
+        .args(["-A", "-v"])
+        .status()
+        .context("Failed to execute sudo")?;
+    Ok(status.success())
+}
+
 /// Prompt for sudo elevation if not already root
 /// Returns true if elevation succeeded or already root, false otherwise
 #[cfg(unix)]
@@ -24,6 +63,16 @@ pub fn elevate_if_needed() -> Result<bool> {
         return Ok(true);
     }
 
+    if askpass_enabled() {
+        print_warning("System cleaners require root privileges; asking through SUDO_ASKPASS.");
+        if authenticate_with_askpass()? {
+            println!("{}", "✓ Authentication successful".green());
+            return Ok(true);
+        }
+        print_error("Authentication failed. Skipping system cleaners.");
+        return Ok(false);
+    }
+
     print_warning("System cleaners require root privileges.");
     println!("You can either:");
     println!("  1. Run this command again with sudo");
@@ -71,36 +120,112 @@ pub fn elevate_if_needed() -> Result<bool> {
 /// It assumes sudo credentials are already cached (via password dialog or sudo -v)
 #[cfg(unix)]
 pub fn execute_with_sudo(command: &str, args: &[&str]) -> Result<std::process::Output> {
-    use std::process::Stdio;
-
-    if check_root() {
-        // Already root, execute directly
-        Command::new(command)
-            .args(args)
-            .output()
-            .context(format!("Failed to execute command: {}", command))
+    // Already root, or sandboxed under CLEANSYS_ROOT: execute directly
+    if check_root() || crate::filesystem::is_sandboxed() {
+        crate::privileges::as_root(|| {
+            Ok(crate::children::output(Command::new(command).args(args))?)
+        })
+        .context(format!("Failed to execute command: {}", command))
     } else {
         // Use sudo with non-interactive mode and cached credentials
-        // The -n flag prevents sudo from prompting for a password
-        let mut sudo_args = vec!["-n", command];
+        // The -n flag prevents sudo from prompting for a password; with
+        // SUDO_ASKPASS in use, -A lets the helper ask again once they expire
+        let mode = if askpass_enabled() { "-A" } else { "-n" };
+        let mut sudo_args = vec![mode, command];
         sudo_args.extend_from_slice(args);
 
-        Command::new("sudo")
-            .args(sudo_args)
-            .stdin(Stdio::null())
-            .output()
+        crate::children::output(Command::new("sudo").args(sudo_args))
             .context(format!("Failed to execute command with sudo: {}", command))
     }
 }
 
 #[cfg(not(unix))]
 pub fn execute_with_sudo(command: &str, args: &[&str]) -> Result<std::process::Output> {
-    Command::new(command)
-        .args(args)
-        .output()
+    crate::children::output(Command::new(command).args(args))
         .context(format!("Failed to execute command: {}", command))
 }
 
+/// Check whether a command is available on the PATH
+pub fn command_exists(command: &str) -> bool {
+    let lookup = if cfg!(windows) { "where" } else { "which" };
+    Command::new(lookup)
+        .arg(command)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Whether a graphical session is there to show a file manager in
+pub fn has_graphical_session() -> bool {
+    if cfg!(any(target_os = "macos", windows)) {
+        return true;
+    }
+    ["DISPLAY", "WAYLAND_DISPLAY"]
+        .iter()
+        .any(|var| std::env::var_os(var).is_some_and(|value| !value.is_empty()))
+}
+
+/// Show a folder in the desktop's file manager, without waiting for it
+pub fn open_in_file_manager(dir: &std::path::Path) -> Result<()> {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+    let mut child = Command::new(opener)
+        .arg(dir)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run {}", opener))?;
+    // Reap it once it hands the folder over, which xdg-open does quickly
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+/// Decide whether output should be colored.
+///
+/// `CLICOLOR_FORCE` (non-zero) always enables color, `NO_COLOR` (non-empty)
+/// disables it, and otherwise color is only used on a terminal that isn't dumb.
+pub fn should_use_color(
+    no_color: Option<&str>,
+    clicolor_force: Option<&str>,
+    term: Option<&str>,
+    is_tty: bool,
+) -> bool {
+    if clicolor_force.is_some_and(|v| !v.is_empty() && v != "0") {
+        return true;
+    }
+    if no_color.is_some_and(|v| !v.is_empty()) {
+        return false;
+    }
+    is_tty && term != Some("dumb")
+}
+
+static COLOR_ENABLED: Lazy<bool> = Lazy::new(|| {
+    let var = |name| std::env::var(name).ok();
+    should_use_color(
+        var("NO_COLOR").as_deref(),
+        var("CLICOLOR_FORCE").as_deref(),
+        var("TERM").as_deref(),
+        io::stdout().is_terminal(),
+    )
+});
+
+/// Whether this process should emit colors, checked once from the
+/// environment and stdout. Used by both the CLI output and the TUI.
+pub fn color_enabled() -> bool {
+    *COLOR_ENABLED
+}
+
+/// Apply the color decision to the `colored` crate used for CLI output
+pub fn init_color() {
+    colored::control::set_override(color_enabled());
+}
+
 /// Print a header with a colorful banner
 pub fn print_header(text: &str) {
     let width = 60;
@@ -152,13 +277,29 @@ pub fn confirm(prompt: &str, default: bool) -> Result<bool> {
     }
 }
 
-/// Format bytes into human-readable sizes
+static RAW_SIZES: Lazy<Mutex<bool>> = Lazy::new(|| Mutex::new(false));
+
+/// Show sizes as exact byte counts instead of KB, MB and GB, from
+/// `--bytes` or `B` in the TUI
+pub fn set_raw_sizes(raw: bool) {
+    *RAW_SIZES.lock().unwrap_or_else(|e| e.into_inner()) = raw;
+}
+
+/// Whether sizes are shown as exact byte counts
+pub fn raw_sizes() -> bool {
+    *RAW_SIZES.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Format bytes into human-readable sizes, or as a byte count with
+/// [`set_raw_sizes`]. Either way [`parse_size`] reads it back.
 pub fn format_size(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
     const GB: u64 = MB * 1024;
 
-    if bytes >= GB {
+    if raw_sizes() {
+        format!("{} bytes", bytes)
+    } else if bytes >= GB {
         format!("{:.2} GB", bytes as f64 / GB as f64)
     } else if bytes >= MB {
         format!("{:.2} MB", bytes as f64 / MB as f64)
@@ -169,24 +310,138 @@ pub fn format_size(bytes: u64) -> String {
     }
 }
 
+/// A human-readable size followed by the exact byte count, like
+/// "1.46 KB (1495 bytes)", for totals that scripts may want to read
+pub fn format_size_exact(bytes: u64) -> String {
+    let size = format_size(bytes);
+    if size.ends_with(" bytes") {
+        size
+    } else {
+        format!("{} ({} bytes)", size, bytes)
+    }
+}
+
+/// Parse a human-readable size such as "1.50 MB" or "512 bytes" into bytes.
+/// Units are interpreted as powers of 1024, matching `format_size`. A comma
+/// is accepted as the decimal separator too ("1,5 GB"), as tools print it
+/// in many locales.
+pub fn parse_size(text: &str) -> Option<u64> {
+    parse_size_with_base(text, 1024.0)
+}
+
+/// Parse a size printed with decimal (SI) units, as used by Docker and Podman ("1.2GB", "356kB")
+pub fn parse_si_size(text: &str) -> Option<u64> {
+    parse_size_with_base(text, 1000.0)
+}
+
+fn parse_size_with_base(text: &str, base: f64) -> Option<u64> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    // "1,234.5" could be either way round, so only a lone comma is a
+    // decimal separator
+    if number.contains(',') && number.contains('.') {
+        return None;
+    }
+    let number: f64 = number.replacen(',', ".", 1).parse().ok()?;
+
+    let exponent = match unit.trim().to_lowercase().as_str() {
+        "" | "b" | "byte" | "bytes" => 0,
+        "k" | "kb" | "kib" => 1,
+        "m" | "mb" | "mib" => 2,
+        "g" | "gb" | "gib" => 3,
+        "t" | "tb" | "tib" => 4,
+        _ => return None,
+    };
+
+    Some((number * base.powi(exponent)) as u64)
+}
+
+/// The current local time broken down into fields, or `None` if the
+/// timezone conversion fails
+pub fn local_time() -> Option<libc::tm> {
+    local_time_ago(std::time::Duration::ZERO)
+}
+
+/// The local time `ago` before now, like [`local_time`]. `None` as well
+/// when that is further back than a `time_t` reaches.
+pub fn local_time_ago(ago: std::time::Duration) -> Option<libc::tm> {
+    let ago = libc::time_t::try_from(ago.as_secs()).ok()?;
+    let now = unsafe { libc::time(std::ptr::null_mut()) }.checked_sub(ago)?;
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    #[cfg(unix)]
+    let ok = !unsafe { libc::localtime_r(&now, &mut tm) }.is_null();
+    #[cfg(windows)]
+    let ok = unsafe { libc::localtime_s(&mut tm, &now) } == 0;
+    ok.then_some(tm)
+}
+
 /// Get the size of a directory or file in bytes
 pub fn get_size(path: &str) -> Result<u64> {
-    let output = std::process::Command::new("du")
-        .args(["-sb", path])
-        .output()?;
+    crate::scanner::size(std::path::Path::new(path))
+}
 
-    if !output.status.success() {
-        return Ok(0);
-    }
+/// Size and free space of a filesystem
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiskSpace {
+    /// Device id, shared by every path on the filesystem
+    pub device: u64,
+    pub total: u64,
+    /// Bytes available to unprivileged users
+    pub available: u64,
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let parts: Vec<&str> = stdout.split_whitespace().collect();
-    if parts.is_empty() {
-        return Ok(0);
+impl DiskSpace {
+    /// Share of the filesystem in use, from 0.0 to 1.0. Space reserved for
+    /// root counts as used, like in `df`.
+    pub fn used_ratio(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        self.total.saturating_sub(self.available) as f64 / self.total as f64
     }
+}
 
-    match parts[0].parse::<u64>() {
-        Ok(size) => Ok(size),
-        Err(_) => Ok(0),
+/// Size and free space of the filesystem holding `path`, if it has blocks
+#[cfg(unix)]
+pub fn get_disk_space(path: &std::path::Path) -> Option<DiskSpace> {
+    use std::os::unix::fs::MetadataExt;
+
+    let device = std::fs::metadata(path).ok()?.dev();
+    let stat = crate::mounts::statvfs(path).filter(|stat| stat.f_blocks != 0)?;
+    #[allow(clippy::useless_conversion)]
+    let block = u64::from(stat.f_frsize);
+    #[allow(clippy::useless_conversion)]
+    Some(DiskSpace {
+        device,
+        total: u64::from(stat.f_blocks) * block,
+        available: u64::from(stat.f_bavail) * block,
+    })
+}
+
+#[cfg(not(unix))]
+pub fn get_disk_space(_path: &std::path::Path) -> Option<DiskSpace> {
+    None
+}
+
+/// Mount points whose usage the main screen shows, when they are separate
+/// filesystems
+pub const DISK_MOUNTS: [&str; 3] = ["/", "/home", "/var"];
+
+/// Size and free space of each of `mounts`, leaving out those on the same
+/// filesystem as one before them, so `/home` only shows up with its own
+/// partition
+pub fn get_disk_spaces(mounts: &[&str]) -> Vec<(std::path::PathBuf, DiskSpace)> {
+    let mut spaces: Vec<(std::path::PathBuf, DiskSpace)> = Vec::new();
+    for mount in mounts {
+        let Some(space) = get_disk_space(&crate::filesystem::root_path(mount)) else {
+            continue;
+        };
+        if !spaces.iter().any(|(_, seen)| seen.device == space.device) {
+            spaces.push((std::path::PathBuf::from(mount), space));
+        }
     }
+    spaces
 }
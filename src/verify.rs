@@ -0,0 +1,206 @@
+//! Verify-freed mode: record the free space of every mounted filesystem
+//! before and after each cleaner, so the bytes a cleaner reports can be
+//! checked against what actually came back on disk. Cleaners that only
+//! estimate what they freed show up as disagreeing.
+
+use once_cell::sync::Lazy;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::filesystem::home_dir;
+use crate::mounts;
+use crate::utils::{format_size, print_warning};
+
+/// Differences below this are noise from other processes writing to disk
+pub const TOLERANCE: u64 = 16 * 1024 * 1024;
+
+/// Reported and actual bytes further apart than this factor disagree
+pub const DISAGREEMENT_FACTOR: u64 = 2;
+
+static ENABLED: Lazy<Mutex<bool>> = Lazy::new(|| Mutex::new(false));
+
+/// Turn on verify-freed mode for this run
+pub fn enable() {
+    *ENABLED.lock().unwrap_or_else(|e| e.into_inner()) = true;
+}
+
+pub fn is_enabled() -> bool {
+    *ENABLED.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Free space of one filesystem
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountFree {
+    /// First mount point the filesystem was found at
+    pub mount: PathBuf,
+    /// Device id, shared by bind mounts of the same filesystem
+    pub device: u64,
+    /// Bytes available to unprivileged users
+    pub available: u64,
+}
+
+/// Free space on a filesystem changed by a cleaner
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountDelta {
+    pub mount: PathBuf,
+    /// Bytes that became free; negative if the filesystem filled up
+    pub freed: i64,
+}
+
+/// Free space of every mounted filesystem at one point in time
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Snapshot(pub Vec<MountFree>);
+
+impl Snapshot {
+    /// Take a snapshot of the mounted filesystems
+    pub fn take() -> Self {
+        let mut mounts: Vec<MountFree> = Vec::new();
+        for mount in mount_points() {
+            if let Some(free) = statvfs(&mount) {
+                if !mounts.iter().any(|m| m.device == free.device) {
+                    mounts.push(free);
+                }
+            }
+        }
+        Snapshot(mounts)
+    }
+
+    /// Filesystems whose free space changed since `before`
+    pub fn freed_since(&self, before: &Snapshot) -> Vec<MountDelta> {
+        self.0
+            .iter()
+            .filter_map(|after| {
+                let before = before.0.iter().find(|b| b.device == after.device)?;
+                let freed = after.available as i64 - before.available as i64;
+                (freed != 0).then(|| MountDelta {
+                    mount: after.mount.clone(),
+                    freed,
+                })
+            })
+            .collect()
+    }
+}
+
+/// A cleaner's reported bytes next to the change measured on disk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verification {
+    pub reported: u64,
+    pub deltas: Vec<MountDelta>,
+}
+
+impl Verification {
+    /// Bytes freed across all filesystems
+    pub fn actual(&self) -> i64 {
+        self.deltas.iter().map(|d| d.freed).sum()
+    }
+
+    /// Whether the reported and measured sizes are wildly apart. Small
+    /// differences are ignored, since anything else running writes to
+    /// disk too.
+    pub fn disagrees(&self) -> bool {
+        let actual = self.actual().max(0) as u64;
+        let (low, high) = if actual < self.reported {
+            (actual, self.reported)
+        } else {
+            (self.reported, actual)
+        };
+        high - low > TOLERANCE && high > low.saturating_mul(DISAGREEMENT_FACTOR)
+    }
+
+    /// One line for the run log, e.g. "reported 1.2 GB, disk 40.0 MB (/: 40.0 MB)"
+    pub fn summary(&self) -> String {
+        let actual = self.actual();
+        let signed = |bytes: i64| {
+            let size = format_size(bytes.unsigned_abs());
+            if bytes < 0 {
+                format!("-{}", size)
+            } else {
+                size
+            }
+        };
+        let mounts: Vec<String> = self
+            .deltas
+            .iter()
+            .map(|d| format!("{}: {}", d.mount.display(), signed(d.freed)))
+            .collect();
+        let mut summary = format!(
+            "reported {}, disk {}",
+            format_size(self.reported),
+            signed(actual)
+        );
+        if !mounts.is_empty() {
+            summary = format!("{} ({})", summary, mounts.join(", "));
+        }
+        summary
+    }
+}
+
+/// Snapshot before a cleaner runs, or `None` when the mode is off
+pub fn begin() -> Option<Snapshot> {
+    is_enabled().then(Snapshot::take)
+}
+
+/// Compare against the snapshot from [`begin`] once the cleaner reported
+/// `reported` bytes freed
+pub fn finish(before: Option<Snapshot>, reported: u64) -> Option<Verification> {
+    let before = before?;
+    Some(Verification {
+        reported,
+        deltas: Snapshot::take().freed_since(&before),
+    })
+}
+
+/// Print the verification of a text-mode run, warning when it disagrees
+pub fn print_verification(name: &str, verification: &Verification) {
+    if verification.disagrees() {
+        print_warning(&format!(
+            "{}: freed size not confirmed on disk: {}",
+            name,
+            verification.summary()
+        ));
+    } else {
+        println!("  Verified {}: {}", name, verification.summary());
+    }
+}
+
+/// Mount points listed in a mounts table such as /proc/self/mounts
+pub fn parse_mounts(contents: &str) -> Vec<PathBuf> {
+    mounts::parse(contents)
+        .map(|(mount_point, _)| mount_point)
+        .collect()
+}
+
+fn mount_points() -> Vec<PathBuf> {
+    match fs::read_to_string("/proc/self/mounts") {
+        Ok(contents) => parse_mounts(&contents),
+        // No mounts table to read: check where cleaners delete most
+        Err(_) => [Some(PathBuf::from("/")), home_dir().ok()]
+            .into_iter()
+            .flatten()
+            .collect(),
+    }
+}
+
+#[cfg(unix)]
+fn statvfs(mount: &Path) -> Option<MountFree> {
+    use std::os::unix::fs::MetadataExt;
+
+    let device = fs::metadata(mount).ok()?.dev();
+    let stat = mounts::statvfs(mount)?;
+    // Pseudo filesystems such as proc and sysfs have no blocks
+    if stat.f_blocks == 0 {
+        return None;
+    }
+    #[allow(clippy::useless_conversion)]
+    Some(MountFree {
+        mount: mount.to_path_buf(),
+        device,
+        available: u64::from(stat.f_bavail) * u64::from(stat.f_frsize),
+    })
+}
+
+#[cfg(not(unix))]
+fn statvfs(_mount: &Path) -> Option<MountFree> {
+    None
+}
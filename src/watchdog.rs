@@ -0,0 +1,194 @@
+//! Per-cleaner timeouts. A cleaner that runs past its timeout is marked as
+//! timed out: its child processes (find, apt, pkexec, ...) are killed, which
+//! unblocks a cleaner waiting on them, and deletion loops stop at their next
+//! file through the cancellation check. The run then carries on with the
+//! next cleaner.
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::cancel;
+use crate::children;
+
+/// Timeout for cleaners without their own setting (30 minutes)
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// How long each cleaner may run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CleanerTimeouts {
+    /// Timeout for every cleaner without its own, `None` for no timeout
+    pub default: Option<Duration>,
+    /// Timeouts by cleaner name; zero disables the timeout
+    pub per_cleaner: BTreeMap<String, Duration>,
+}
+
+impl Default for CleanerTimeouts {
+    fn default() -> Self {
+        Self {
+            default: Some(DEFAULT_TIMEOUT),
+            per_cleaner: BTreeMap::new(),
+        }
+    }
+}
+
+impl CleanerTimeouts {
+    /// Timeout for a cleaner, `None` if it may run forever
+    pub fn for_cleaner(&self, name: &str) -> Option<Duration> {
+        match self.per_cleaner.get(name) {
+            Some(timeout) => (!timeout.is_zero()).then_some(*timeout),
+            None => self.default,
+        }
+    }
+}
+
+static TIMEOUTS: Lazy<Mutex<CleanerTimeouts>> =
+    Lazy::new(|| Mutex::new(CleanerTimeouts::default()));
+
+/// Set the timeouts used from now on
+pub fn set_timeouts(timeouts: CleanerTimeouts) {
+    *TIMEOUTS.lock().unwrap_or_else(|e| e.into_inner()) = timeouts;
+}
+
+/// The timeouts currently in effect
+pub fn timeouts() -> CleanerTimeouts {
+    TIMEOUTS.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Error for a cleaner stopped by its timeout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimedOut {
+    pub cleaner: String,
+    pub after: Duration,
+}
+
+impl fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} timed out after {}",
+            self.cleaner,
+            describe(self.after)
+        )
+    }
+}
+
+impl std::error::Error for TimedOut {}
+
+/// "30 min", or seconds for timeouts that aren't whole minutes
+pub fn describe(timeout: Duration) -> String {
+    let secs = timeout.as_secs();
+    if secs >= 60 && secs.is_multiple_of(60) {
+        format!("{} min", secs / 60)
+    } else {
+        format!("{} s", secs)
+    }
+}
+
+/// Run the cleaner `name` under its configured timeout
+pub fn run<T>(name: &str, f: impl FnOnce() -> Result<T>) -> Result<T> {
+    match timeouts().for_cleaner(name) {
+        Some(timeout) => run_with_timeout(name, timeout, f),
+        None => f(),
+    }
+}
+
+/// Run `f`, stopping it once `timeout` has passed. Fails with [`TimedOut`]
+/// if it did, whatever `f` returned after being stopped.
+pub fn run_with_timeout<T>(
+    name: &str,
+    timeout: Duration,
+    f: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    let (done, finished) = mpsc::channel::<()>();
+    let expired = Arc::new(AtomicBool::new(false));
+    let watchdog = {
+        let expired = Arc::clone(&expired);
+        thread::spawn(move || {
+            if finished.recv_timeout(timeout) == Err(RecvTimeoutError::Timeout) {
+                expired.store(true, Ordering::SeqCst);
+                cancel::expire();
+                children::terminate_all();
+                // Whatever wasn't started through `children`
+                kill_children();
+            }
+        })
+    };
+
+    let result = f();
+    // The watchdog is done either way once the sender is gone
+    drop(done);
+    let _ = watchdog.join();
+    cancel::clear_expired();
+
+    if expired.load(Ordering::SeqCst) {
+        return Err(TimedOut {
+            cleaner: name.to_string(),
+            after: timeout,
+        }
+        .into());
+    }
+    result
+}
+
+/// Parent pid from the contents of /proc/<pid>/stat. The command name in
+/// parentheses may itself contain spaces and parentheses.
+pub fn parse_stat_ppid(stat: &str) -> Option<u32> {
+    let (_, rest) = stat.rsplit_once(')')?;
+    rest.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// All descendants of `root` given (pid, parent) pairs, deepest first
+pub fn descendants(root: u32, processes: &[(u32, u32)]) -> Vec<u32> {
+    let mut found = Vec::new();
+    let mut stack = vec![root];
+    while let Some(parent) = stack.pop() {
+        for &(pid, ppid) in processes {
+            if ppid == parent && !found.contains(&pid) {
+                found.push(pid);
+                stack.push(pid);
+            }
+        }
+    }
+    found.reverse();
+    found
+}
+
+/// Kill every process started by this one. A process that can't be killed,
+/// such as a sudo child when not running as root, is left alone.
+#[cfg(target_os = "linux")]
+fn kill_children() {
+    let processes: Vec<(u32, u32)> = std::fs::read_dir("/proc")
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let pid: u32 = entry.file_name().to_str()?.parse().ok()?;
+            let stat = std::fs::read_to_string(entry.path().join("stat")).ok()?;
+            Some((pid, parse_stat_ppid(&stat)?))
+        })
+        .collect();
+    for pid in descendants(std::process::id(), &processes) {
+        // SAFETY: kill only sends a signal
+        unsafe {
+            libc::kill(pid as libc::pid_t, libc::SIGKILL);
+        }
+    }
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn kill_children() {
+    // No /proc to walk; pkill reaches the direct children
+    let _ = std::process::Command::new("pkill")
+        .args(["-KILL", "-P", &std::process::id().to_string()])
+        .status();
+}
+
+#[cfg(not(unix))]
+fn kill_children() {}
@@ -0,0 +1,144 @@
+//! Live size updates: watch the top cache directories with inotify while the
+//! TUI is open, so scan estimates can be refreshed as applications write to
+//! or delete from their caches.
+
+use anyhow::{bail, Result};
+#[cfg(target_os = "linux")]
+use std::ffi::CString;
+#[cfg(target_os = "linux")]
+use std::os::unix::ffi::OsStrExt;
+#[cfg(target_os = "linux")]
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::filesystem::{cache_dir, home_dir};
+
+/// Upper bound on inotify watches, well below the default per-user limit
+const MAX_WATCHES: usize = 128;
+
+/// How long the watcher thread waits for events before checking for shutdown
+#[cfg(target_os = "linux")]
+const POLL_TIMEOUT_MS: i32 = 500;
+
+#[cfg(target_os = "linux")]
+const WATCH_MASK: u32 = libc::IN_CREATE
+    | libc::IN_DELETE
+    | libc::IN_CLOSE_WRITE
+    | libc::IN_MOVED_FROM
+    | libc::IN_MOVED_TO;
+
+/// Directories worth watching: the cache directory, each directory directly
+/// inside it, and the Kubernetes tool caches.
+pub fn watch_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    if let Ok(cache) = cache_dir() {
+        if let Ok(entries) = std::fs::read_dir(&cache) {
+            dirs.extend(
+                entries
+                    .flatten()
+                    .map(|e| e.path())
+                    .filter(|p| p.is_dir() && !p.is_symlink()),
+            );
+        }
+        dirs.insert(0, cache);
+    }
+    if let Ok(home) = home_dir() {
+        dirs.push(home.join(".minikube/cache"));
+        dirs.push(home.join(".kube/cache"));
+    }
+
+    dirs.retain(|d| d.is_dir());
+    dirs.truncate(MAX_WATCHES);
+    dirs
+}
+
+/// Watches directories with inotify on a background thread and records
+/// whether anything changed since the last check.
+pub struct CacheWatcher {
+    changed: Arc<AtomicBool>,
+    stop: Arc<AtomicBool>,
+    watched: usize,
+}
+
+impl CacheWatcher {
+    /// Start watching the given directories. Directories that can't be
+    /// watched are skipped; it's an error only if none could be.
+    #[cfg(target_os = "linux")]
+    pub fn start(dirs: &[PathBuf]) -> Result<Self> {
+        let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+        if fd < 0 {
+            bail!("inotify unavailable: {}", std::io::Error::last_os_error());
+        }
+
+        let watched = dirs.iter().filter(|dir| add_watch(fd, dir)).count();
+        if watched == 0 {
+            unsafe { libc::close(fd) };
+            bail!("No cache directories could be watched");
+        }
+
+        let changed = Arc::new(AtomicBool::new(false));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (thread_changed, thread_stop) = (changed.clone(), stop.clone());
+        std::thread::spawn(move || watch_loop(fd, &thread_changed, &thread_stop));
+
+        Ok(Self {
+            changed,
+            stop,
+            watched,
+        })
+    }
+
+    /// inotify is Linux only; elsewhere estimates are only refreshed by rescans
+    #[cfg(not(target_os = "linux"))]
+    pub fn start(_dirs: &[PathBuf]) -> Result<Self> {
+        bail!("Live size updates need inotify, which is only available on Linux")
+    }
+
+    /// Number of directories being watched
+    pub fn watched(&self) -> usize {
+        self.watched
+    }
+
+    /// Whether anything changed since the last call
+    pub fn take_changed(&self) -> bool {
+        self.changed.swap(false, Ordering::Relaxed)
+    }
+}
+
+impl Drop for CacheWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn add_watch(fd: i32, dir: &Path) -> bool {
+    let Ok(path) = CString::new(dir.as_os_str().as_bytes()) else {
+        return false;
+    };
+    unsafe { libc::inotify_add_watch(fd, path.as_ptr(), WATCH_MASK) >= 0 }
+}
+
+#[cfg(target_os = "linux")]
+fn watch_loop(fd: i32, changed: &AtomicBool, stop: &AtomicBool) {
+    let mut buf = [0u8; 4096];
+    while !stop.load(Ordering::Relaxed) {
+        let mut pollfd = libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        if unsafe { libc::poll(&mut pollfd, 1, POLL_TIMEOUT_MS) } <= 0 {
+            continue;
+        }
+
+        // Drain every queued event; only the fact that something changed matters
+        while unsafe { libc::read(fd, buf.as_mut_ptr().cast(), buf.len()) } > 0 {
+            changed.store(true, Ordering::Relaxed);
+        }
+    }
+    unsafe { libc::close(fd) };
+}
@@ -0,0 +1,519 @@
+//! Tests for the keymaps and the action reducer, src/action.rs
+
+use anyhow::Result;
+use cleansys::action::{keymap, Action, Screen};
+use cleansys::app::{App, CleanerCategory, CleanerItem, ScanState, Status};
+use cleansys::cleaners::{RiskLevel, ScanReport};
+use cleansys::history::{CleanerRun, History, RunRecord};
+use cleansys::script::screen_text;
+use cleansys::{limits, scanner};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.is_root = false;
+    app
+}
+
+#[test]
+fn test_keys_map_to_actions_of_the_screen_on_top() {
+    let mut app = app();
+    assert_eq!(Screen::of(&app), Screen::Main);
+    assert_eq!(keymap(&app, key(KeyCode::Down)), Some(Action::NextItem));
+    assert_eq!(keymap(&app, key(KeyCode::Tab)), Some(Action::NextCategory));
+    assert_eq!(keymap(&app, key(KeyCode::Char('q'))), Some(Action::Quit));
+    assert_eq!(
+        keymap(&app, key(KeyCode::Char('b'))),
+        Some(Action::ToggleRawSizes)
+    );
+    assert_eq!(
+        keymap(&app, KeyEvent::new(KeyCode::Char('B'), KeyModifiers::SHIFT)),
+        Some(Action::OpenActionMenu)
+    );
+
+    app.show_progress_screen = true;
+    assert_eq!(Screen::of(&app), Screen::Progress);
+    assert_eq!(
+        keymap(&app, key(KeyCode::Down)),
+        Some(Action::ScrollDown(1))
+    );
+    assert_eq!(
+        keymap(&app, key(KeyCode::Tab)),
+        Some(Action::CycleProgressPane)
+    );
+    assert_eq!(keymap(&app, key(KeyCode::Esc)), Some(Action::CloseProgress));
+
+    app.is_running = true;
+    assert_eq!(
+        keymap(&app, key(KeyCode::Char('q'))),
+        Some(Action::OpenQuitDialog)
+    );
+    assert_eq!(keymap(&app, key(KeyCode::Esc)), Some(Action::CancelRun));
+
+    app.toggle_help();
+    assert_eq!(Screen::of(&app), Screen::Help);
+    assert_eq!(
+        keymap(&app, key(KeyCode::Char('q'))),
+        Some(Action::HideHelp)
+    );
+    assert_eq!(
+        keymap(&app, key(KeyCode::PageDown)),
+        Some(Action::ScrollHelpDown(10))
+    );
+}
+
+#[test]
+fn test_search_takes_typed_keys_but_not_bindings() {
+    let mut app = app();
+    app.toggle_search();
+    assert_eq!(
+        keymap(&app, key(KeyCode::Char('z'))),
+        Some(Action::TypeChar('z'))
+    );
+    assert_eq!(
+        keymap(&app, key(KeyCode::Char('+'))),
+        Some(Action::TypeChar('+'))
+    );
+    assert_eq!(
+        keymap(&app, key(KeyCode::Backspace)),
+        Some(Action::DeleteChar)
+    );
+    // Letters bound to something else, or to nothing outside a run
+    for c in [
+        'q', 't', 'c', 's', 'J', 'R', 'a', 'n', 'y', 'f', 'o', 'v', ' ', '/', '1',
+    ] {
+        assert_eq!(
+            keymap(&app, key(KeyCode::Char(c))),
+            Some(Action::TypeChar(c))
+        );
+    }
+    assert_eq!(
+        keymap(&app, KeyEvent::new(KeyCode::Char('Q'), KeyModifiers::SHIFT)),
+        Some(Action::TypeChar('Q'))
+    );
+    assert_eq!(keymap(&app, key(KeyCode::Esc)), Some(Action::ClearSearch));
+
+    // The same on the progress screen
+    app.show_progress_screen = true;
+    assert_eq!(
+        keymap(&app, key(KeyCode::Char('q'))),
+        Some(Action::TypeChar('q'))
+    );
+    assert_eq!(keymap(&app, key(KeyCode::Esc)), Some(Action::ClearSearch));
+    assert_eq!(
+        keymap(&app, key(KeyCode::Down)),
+        Some(Action::ScrollDown(1))
+    );
+}
+
+#[test]
+fn test_undo_asks_first_and_only_for_quarantined_runs() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let history = History::at(&dir.path().join("history.jsonl"));
+    history.record(RunRecord::new(vec![CleanerRun::succeeded(
+        "Thumbnails",
+        1024,
+    )]))?;
+    let mut app = app();
+    app.history = Some(history);
+
+    assert_eq!(
+        keymap(&app, key(KeyCode::Char('U'))),
+        Some(Action::UndoLastRun)
+    );
+    app.toggle_search();
+    assert_eq!(
+        keymap(&app, key(KeyCode::Char('U'))),
+        Some(Action::TypeChar('U'))
+    );
+    app.toggle_search();
+
+    // The last run deleted its files, so there is nothing to ask about
+    app.dispatch(Action::UndoLastRun)?;
+    assert!(!app.undo_dialog.is_visible());
+    assert_eq!(Screen::of(&app), Screen::Main);
+    Ok(())
+}
+
+#[test]
+fn test_number_keys_jump_to_categories() {
+    let mut app = app();
+    for name in ["User", "System", "Dev Caches"] {
+        app.categories.push(CleanerCategory {
+            name: name.to_string(),
+            description: String::new(),
+            items: Vec::new(),
+        });
+    }
+    assert_eq!(
+        keymap(&app, key(KeyCode::Char('3'))),
+        Some(Action::JumpToCategory(2))
+    );
+    app.dispatch(Action::JumpToCategory(2)).unwrap();
+    assert_eq!(app.category_index, 2);
+    // Numbers past the last category do nothing
+    app.dispatch(Action::JumpToCategory(8)).unwrap();
+    assert_eq!(app.category_index, 2);
+
+    app.toggle_search();
+    assert_eq!(
+        keymap(&app, key(KeyCode::Char('1'))),
+        Some(Action::TypeChar('1'))
+    );
+}
+
+#[test]
+fn test_category_sidebar_sums_the_selection() -> Result<()> {
+    let item = |name: &str, selected, bytes_cleaned| CleanerItem {
+        name: name.to_string(),
+        description: String::new(),
+        docs: None,
+        requires_root: false,
+        risk: RiskLevel::Normal,
+        selected,
+        function: frees_a_kilobyte,
+        scan: None,
+        scan_state: None,
+        bytes_cleaned,
+        bytes_deferred: 0,
+        permission_denied: 0,
+        status: None,
+        parent: None,
+        tags: vec![],
+    };
+    let mut app = app();
+    app.terminal_width = 120;
+    app.terminal_height = 30;
+    app.categories = vec![
+        CleanerCategory {
+            name: "Dev".to_string(),
+            description: String::new(),
+            items: vec![
+                item("Cargo", true, 2048),
+                item("npm", true, 1024),
+                item("pip", false, 4096),
+            ],
+        },
+        CleanerCategory {
+            name: "Logs".to_string(),
+            description: String::new(),
+            items: vec![item("Journal", false, 0)],
+        },
+    ];
+    assert_eq!(app.category_selection(0), (2, 3072));
+    let screen = screen_text(&mut app)?;
+    assert!(
+        screen.contains("1 Dev (2 selected, ~3.00 KB)"),
+        "{}",
+        screen
+    );
+    assert!(screen.contains("2 Logs (none selected)"), "{}", screen);
+
+    assert_eq!(
+        keymap(&app, key(KeyCode::Char('C'))),
+        Some(Action::ToggleCategoriesCollapsed)
+    );
+    app.dispatch(Action::ToggleCategoriesCollapsed)?;
+    let screen = screen_text(&mut app)?;
+    assert!(screen.contains("1 (2)"), "{}", screen);
+    assert!(!screen.contains("Dev (2 selected"), "{}", screen);
+    Ok(())
+}
+
+#[test]
+fn test_dispatch_replays_a_script_without_a_terminal() {
+    let mut app = app();
+    for action in [
+        Action::ToggleSearch,
+        Action::TypeChar('l'),
+        Action::TypeChar('o'),
+        Action::TypeChar('g'),
+        Action::DeleteChar,
+        Action::ShowAbout,
+    ] {
+        assert!(!app.dispatch(action).unwrap());
+    }
+    assert_eq!(app.search_query, "lo");
+    assert_eq!(Screen::of(&app), Screen::About);
+
+    // Typing goes to the text field on top, not the search underneath
+    app.dispatch(Action::CloseAbout).unwrap();
+    app.dispatch(Action::ToggleSchedule).unwrap();
+    assert_eq!(Screen::of(&app), Screen::ScheduleInput);
+    app.dispatch(Action::TypeChar('5')).unwrap();
+    assert_eq!(app.schedule_input.as_deref(), Some("5"));
+    assert_eq!(app.search_query, "lo");
+
+    app.dispatch(Action::CancelSchedule).unwrap();
+    assert!(app.dispatch(Action::Quit).unwrap());
+}
+
+fn frees_a_kilobyte(_: bool) -> Result<u64> {
+    Ok(1024)
+}
+
+/// Past the default safety limit of files, unless the run was approved
+fn crosses_the_limit(_: bool) -> Result<u64> {
+    limits::check(2_000_000, 0)?;
+    Ok(2048)
+}
+
+fn item(name: &str, requires_root: bool, function: fn(bool) -> Result<u64>) -> CleanerItem {
+    CleanerItem {
+        name: name.to_string(),
+        description: String::new(),
+        docs: None,
+        requires_root,
+        risk: RiskLevel::Normal,
+        selected: true,
+        function,
+        scan: None,
+        scan_state: None,
+        bytes_cleaned: 0,
+        bytes_deferred: 0,
+        permission_denied: 0,
+        status: None,
+        parent: None,
+        tags: vec![],
+    }
+}
+
+/// A run of `items`, started as root so no password is asked for up front
+fn run_of(items: Vec<CleanerItem>) -> App {
+    let mut app = App::new();
+    app.categories = vec![CleanerCategory {
+        name: "Mixed".to_string(),
+        description: String::new(),
+        items,
+    }];
+    app.is_root = true;
+    app.dispatch(Action::RunSelected).unwrap();
+    app
+}
+
+/// A run of a system cleaner followed by a user cleaner
+fn mixed_run() -> App {
+    run_of(vec![
+        item("System", true, frees_a_kilobyte),
+        item("User", false, frees_a_kilobyte),
+    ])
+}
+
+/// Tick the app like the event loop until `done` holds
+fn tick_until(app: &mut App, done: impl Fn(&App) -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !done(app) {
+        assert!(Instant::now() < deadline, "timed out");
+        app.dispatch(Action::Tick).unwrap();
+        // Drawing notices when the run has finished
+        screen_text(app).unwrap();
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn test_sudo_failure_mid_run_pauses_only_system_cleaners() {
+    let mut app = mixed_run();
+    // Sudo is gone by the time the system cleaner's turn comes
+    app.is_root = false;
+
+    tick_until(&mut app, |app| {
+        matches!(app.categories[0].items[1].status, Some(Status::Success(_)))
+    });
+    assert!(app.sudo_paused);
+    assert!(app.is_running);
+    assert_eq!(Screen::of(&app), Screen::SudoDialog);
+    assert!(matches!(
+        app.categories[0].items[0].status,
+        Some(Status::Pending)
+    ));
+    assert_eq!(
+        keymap(&app, key(KeyCode::Char('s'))),
+        Some(Action::SkipSystemCleaners)
+    );
+    assert_eq!(keymap(&app, key(KeyCode::Esc)), Some(Action::AbortRun));
+
+    app.dispatch(Action::SkipSystemCleaners).unwrap();
+    tick_until(&mut app, |app| !app.is_running);
+    assert!(!app.sudo_paused);
+    assert_eq!(Screen::of(&app), Screen::Progress);
+    assert!(matches!(
+        &app.categories[0].items[0].status,
+        Some(Status::Error(error)) if error.starts_with("Skipped")
+    ));
+    assert_eq!(app.total_bytes_cleaned, 1024);
+}
+
+#[test]
+fn test_cancelling_the_password_returns_to_the_sudo_dialog() {
+    let mut app = mixed_run();
+    app.pause_root_cleaners();
+    app.dispatch(Action::Reauthenticate).unwrap();
+    assert_eq!(Screen::of(&app), Screen::PasswordPrompt);
+
+    app.dispatch(Action::CancelPassword).unwrap();
+    assert_eq!(Screen::of(&app), Screen::SudoDialog);
+
+    app.dispatch(Action::AbortRun).unwrap();
+    assert!(!app.sudo_paused);
+    assert!(app
+        .categories
+        .iter()
+        .flat_map(|category| &category.items)
+        .all(|item| matches!(item.status, Some(Status::Error(_)))));
+}
+
+/// Finishes at once
+fn quick_scan() -> Result<ScanReport> {
+    let mut report = ScanReport::new();
+    report.add_entry("Cache", 2048, 2048);
+    Ok(report)
+}
+
+/// Runs until it is cancelled, like a walk of a huge home directory
+fn endless_scan() -> Result<ScanReport> {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !scanner::is_cancelled() && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(5));
+    }
+    Ok(ScanReport::new())
+}
+
+#[test]
+fn test_escape_stops_scans_but_keeps_finished_ones() {
+    let item = |name: &str, scan: fn() -> Result<ScanReport>| CleanerItem {
+        name: name.to_string(),
+        description: String::new(),
+        docs: None,
+        requires_root: false,
+        risk: RiskLevel::Normal,
+        selected: false,
+        function: frees_a_kilobyte,
+        scan: Some(scan),
+        scan_state: None,
+        bytes_cleaned: 0,
+        bytes_deferred: 0,
+        permission_denied: 0,
+        status: None,
+        parent: None,
+        tags: vec![],
+    };
+    let mut app = app();
+    app.categories = vec![CleanerCategory {
+        name: "Scans".to_string(),
+        description: String::new(),
+        items: vec![item("Quick", quick_scan), item("Huge", endless_scan)],
+    }];
+    app.ensure_selected_scan();
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !matches!(
+        app.categories[0].items[0].scan_state,
+        Some(ScanState::Done(_))
+    ) {
+        assert!(Instant::now() < deadline, "timed out");
+        app.poll_scan_results();
+        thread::sleep(Duration::from_millis(5));
+    }
+    app.dispatch(Action::NextItem).unwrap();
+    app.ensure_selected_scan();
+    assert_eq!(app.scan_progress(), Some((1, 2)));
+    assert_eq!(keymap(&app, key(KeyCode::Esc)), Some(Action::CancelScans));
+
+    app.dispatch(Action::CancelScans).unwrap();
+    assert_eq!(app.scan_progress(), None);
+    assert!(matches!(
+        app.categories[0].items[1].scan_state,
+        Some(ScanState::Cancelled)
+    ));
+    assert_eq!(app.categories[0].items[0].estimated_size(), Some(2048));
+    assert_eq!(app.categories[0].items[1].estimated_size(), None);
+
+    // The stopped scan never reports its partial result
+    thread::sleep(Duration::from_millis(100));
+    app.poll_scan_results();
+    assert!(matches!(
+        app.categories[0].items[1].scan_state,
+        Some(ScanState::Cancelled)
+    ));
+
+    // Cleaners with unknown sizes can still be selected
+    app.dispatch(Action::ToggleSelected).unwrap();
+    assert!(app.categories[0].items[1].selected);
+}
+
+#[test]
+fn test_safety_limit_pauses_the_run_until_answered() {
+    let mut app = run_of(vec![
+        item("Huge", false, crosses_the_limit),
+        item("Small", false, frees_a_kilobyte),
+    ]);
+    tick_until(&mut app, |app| app.limit_dialog.is_visible());
+    assert!(app.limit_paused);
+    assert_eq!(Screen::of(&app), Screen::LimitDialog);
+    assert!(matches!(
+        app.categories[0].items[0].status,
+        Some(Status::Pending)
+    ));
+    assert_eq!(
+        keymap(&app, key(KeyCode::Char('y'))),
+        Some(Action::ExceedLimit)
+    );
+    assert_eq!(keymap(&app, key(KeyCode::Esc)), Some(Action::StopAtLimit));
+
+    // Agreeing runs the cleaner again, past the limit. Other tests starting
+    // runs reset the process-wide approval, so agree as often as asked.
+    app.dispatch(Action::ExceedLimit).unwrap();
+    while app.is_running {
+        tick_until(&mut app, |app| {
+            app.limit_dialog.is_visible() || !app.is_running
+        });
+        if app.limit_dialog.is_visible() {
+            app.dispatch(Action::ExceedLimit).unwrap();
+        }
+    }
+    assert!(matches!(
+        app.categories[0].items[0].status,
+        Some(Status::Success(_))
+    ));
+    assert_eq!(app.total_bytes_cleaned, 2048 + 1024);
+
+    // Declining skips the rest of the run. In the same test, since the
+    // approval above would let a run alongside go past the limit.
+    let mut app = run_of(vec![
+        item("Huge", false, crosses_the_limit),
+        item("Small", false, frees_a_kilobyte),
+    ]);
+    tick_until(&mut app, |app| app.limit_dialog.is_visible());
+    app.dispatch(Action::StopAtLimit).unwrap();
+    tick_until(&mut app, |app| !app.is_running);
+    assert!(!app.limit_paused);
+    assert!(app.categories[0].items.iter().all(|item| matches!(
+        &item.status,
+        Some(Status::Error(error)) if error.starts_with("Skipped")
+    )));
+}
+
+#[test]
+fn test_trim_dialog_runs_or_skips_fstrim() -> Result<()> {
+    let mut app = app();
+    app.show_progress_screen = true;
+    app.trim_dialog
+        .show("Trim", vec!["  • / (NVMe) freed".to_string()]);
+    assert_eq!(Screen::of(&app), Screen::TrimDialog);
+    assert_eq!(
+        keymap(&app, key(KeyCode::Char('y'))),
+        Some(Action::TrimMounts)
+    );
+    assert_eq!(keymap(&app, key(KeyCode::Esc)), Some(Action::SkipTrim));
+
+    app.dispatch(Action::SkipTrim)?;
+    assert!(!app.trim_dialog.is_visible());
+    assert_eq!(Screen::of(&app), Screen::Progress);
+    Ok(())
+}
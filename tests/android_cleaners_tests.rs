@@ -0,0 +1,73 @@
+//! Tests for the Android cleaners in src/cleaners/android_cleaners.rs
+#![cfg(feature = "dev-caches")]
+
+use cleansys::cleaners::android_cleaners::{unused_system_images, used_system_images};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+fn avd(avd_dir: &Path, name: &str, image: &str) {
+    let dir = avd_dir.join(format!("{}.avd", name));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("config.ini"),
+        format!("hw.ramSize=2048\nimage.sysdir.1={}/\n", image),
+    )
+    .unwrap();
+}
+
+fn image(sdk: &Path, image: &str) {
+    let dir = sdk.join(image);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("system.img"), [0u8; 100]).unwrap();
+}
+
+#[test]
+fn test_used_system_images_reads_avd_configs() {
+    let dir = tempdir().unwrap();
+    avd(
+        dir.path(),
+        "Pixel_7",
+        "system-images/android-34/google_apis/x86_64",
+    );
+
+    assert_eq!(
+        used_system_images(dir.path())
+            .into_iter()
+            .collect::<Vec<_>>(),
+        vec![PathBuf::from("system-images/android-34/google_apis/x86_64")]
+    );
+}
+
+#[test]
+fn test_unused_system_images_skips_images_in_use() {
+    let sdk = tempdir().unwrap();
+    let avds = tempdir().unwrap();
+    image(sdk.path(), "system-images/android-30/google_apis/x86");
+    image(sdk.path(), "system-images/android-34/google_apis/x86_64");
+    image(
+        sdk.path(),
+        "system-images/android-34/google_apis_playstore/x86_64",
+    );
+    avd(
+        avds.path(),
+        "Pixel_7",
+        "system-images/android-34/google_apis/x86_64",
+    );
+
+    let unused: Vec<PathBuf> = unused_system_images(sdk.path(), avds.path())
+        .into_iter()
+        .map(|(path, size)| {
+            assert!(size >= 100);
+            path
+        })
+        .collect();
+    assert_eq!(
+        unused,
+        vec![
+            sdk.path().join("system-images/android-30/google_apis/x86"),
+            sdk.path()
+                .join("system-images/android-34/google_apis_playstore/x86_64"),
+        ]
+    );
+}
@@ -0,0 +1,73 @@
+//! Tests for audit mode in src/audit.rs
+
+use cleansys::audit::{self, MAX_AUDIT_SIZE};
+use cleansys::deleter;
+use cleansys::pipeline::Pipeline;
+use cleansys::quarantine::Quarantine;
+use std::fs;
+use std::sync::Mutex;
+use tempfile::tempdir;
+
+// Audit mode is process-wide
+static LOCK: Mutex<()> = Mutex::new(());
+
+#[test]
+fn test_records_small_files_before_deleting() {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = tempdir().unwrap();
+    let log = dir.path().join("audit/run.jsonl");
+    audit::enable_at(&log);
+
+    let tree = dir.path().join("tree");
+    fs::create_dir_all(tree.join("sub")).unwrap();
+    fs::write(tree.join("settings.conf"), "key=value").unwrap();
+    fs::write(tree.join("sub/big.bin"), vec![0u8; MAX_AUDIT_SIZE as usize]).unwrap();
+    deleter::remove_dir_all(&tree).unwrap();
+    audit::disable();
+
+    let entries = audit::load(&log).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].path, tree.join("settings.conf"));
+    assert_eq!(entries[0].size, 9);
+    assert_eq!(entries[0].sha256.len(), 64);
+}
+
+#[test]
+fn test_verifies_quarantined_files() {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = tempdir().unwrap();
+    let log = dir.path().join("run.jsonl");
+    audit::enable_at(&log);
+
+    let original = dir.path().join("app.ini");
+    fs::write(&original, "[section]").unwrap();
+    let quarantine = Quarantine::at(dir.path().join("q")).unwrap();
+    quarantine
+        .move_files(vec![original.clone()], &Pipeline::new())
+        .unwrap();
+    audit::disable();
+
+    let entries = audit::load(&log).unwrap();
+    let stored = quarantine.stored_path(&original);
+    assert_eq!(audit::verify(&entries, &original, &stored), Some(true));
+
+    fs::write(&stored, "tampered").unwrap();
+    assert_eq!(audit::verify(&entries, &original, &stored), Some(false));
+    assert_eq!(
+        audit::verify(&entries, &dir.path().join("other"), &stored),
+        None
+    );
+}
+
+#[test]
+fn test_nothing_logged_when_disabled() {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    audit::disable();
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("a.txt");
+    fs::write(&file, "a").unwrap();
+
+    deleter::remove_file(&file).unwrap();
+    assert!(!audit::is_enabled());
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+}
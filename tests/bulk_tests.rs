@@ -0,0 +1,114 @@
+//! Tests for bulk selection actions and the action menu
+
+use cleansys::bulk::{BulkAction, BulkActionKind, Selectable};
+use cleansys::components::action_menu::ActionMenu;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+struct Entry {
+    path: PathBuf,
+    modified: SystemTime,
+    selected: bool,
+}
+
+impl Selectable for Entry {
+    fn label(&self) -> &str {
+        self.path.to_str().unwrap_or_default()
+    }
+
+    fn path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    fn modified(&self) -> Option<SystemTime> {
+        Some(self.modified)
+    }
+
+    fn is_selected(&self) -> bool {
+        self.selected
+    }
+
+    fn set_selected(&mut self, selected: bool) {
+        self.selected = selected;
+    }
+}
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+fn entries(now: SystemTime) -> Vec<Entry> {
+    [
+        ("/var/log/app.LOG", 40),
+        ("/tmp/build/out.o", 2),
+        ("/var/log/old.gz", 90),
+    ]
+    .into_iter()
+    .map(|(path, age_days)| Entry {
+        path: PathBuf::from(path),
+        modified: now - DAY * age_days,
+        selected: false,
+    })
+    .collect()
+}
+
+fn selected(items: &[Entry]) -> Vec<bool> {
+    items.iter().map(|e| e.selected).collect()
+}
+
+#[test]
+fn test_select_by_extension_and_age() {
+    let now = SystemTime::now();
+    let mut items = entries(now);
+
+    let changed = BulkAction::SelectExtension("log".to_string()).apply(&mut items, now);
+    assert_eq!(changed, 1);
+    assert_eq!(selected(&items), [true, false, false]);
+
+    // Adds to the selection rather than replacing it
+    BulkAction::SelectOlderThan(60).apply(&mut items, now);
+    assert_eq!(selected(&items), [true, false, true]);
+
+    BulkAction::Invert.apply(&mut items, now);
+    assert_eq!(selected(&items), [false, true, false]);
+}
+
+#[test]
+fn test_select_matching_ignores_case() {
+    let now = SystemTime::now();
+    let mut items = entries(now);
+    BulkAction::SelectMatching("VAR/LOG".to_string()).apply(&mut items, now);
+    assert_eq!(selected(&items), [true, false, true]);
+}
+
+#[test]
+fn test_action_kinds_parse_their_input() {
+    assert_eq!(
+        BulkActionKind::SelectExtension.with_input(".gz"),
+        Some(BulkAction::SelectExtension("gz".to_string()))
+    );
+    assert_eq!(
+        BulkActionKind::SelectOlderThan.with_input("30"),
+        Some(BulkAction::SelectOlderThan(30))
+    );
+    assert_eq!(BulkActionKind::SelectOlderThan.with_input("soon"), None);
+    assert_eq!(BulkActionKind::SelectMatching.with_input("  "), None);
+}
+
+#[test]
+fn test_action_menu_asks_for_the_argument() {
+    let mut menu = ActionMenu::new();
+    menu.show(&BulkActionKind::ALL);
+    menu.next();
+    menu.next();
+
+    // Select by extension needs an argument first
+    assert_eq!(menu.submit(), None);
+    assert!(menu.is_editing());
+    for c in "log".chars() {
+        menu.add_char(c);
+    }
+    assert_eq!(
+        menu.submit(),
+        Some(BulkAction::SelectExtension("log".to_string()))
+    );
+    assert!(!menu.is_visible());
+}
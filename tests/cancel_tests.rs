@@ -0,0 +1,221 @@
+//! Tests for cancelling a run: deletions stop between files and quitting
+//! during a run asks first
+
+use anyhow::Result;
+use cleansys::action::Action;
+use cleansys::app::{App, CleanerCategory, CleanerItem, Status};
+use cleansys::cancel;
+use cleansys::cleaners::{cleaned, RiskLevel};
+use cleansys::deleter::{self, remove_dir_all_with_progress};
+use cleansys::pipeline::Pipeline;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use once_cell::sync::Lazy;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use tempfile::tempdir;
+
+// The cancellation flag is process-wide
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// The files the cleaner below removes
+static FILES: Lazy<Mutex<Vec<PathBuf>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Set once the cleaner below has removed its first file
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Removes its first file, then waits for the run to be cancelled before
+/// going on with the rest
+fn removes_until_cancelled(_: bool) -> Result<u64> {
+    let files = FILES.lock().unwrap().clone();
+    let mut freed = 0;
+    for (i, file) in files.iter().enumerate() {
+        if i == 1 {
+            STARTED.store(true, Ordering::SeqCst);
+            while !cancel::is_requested() {
+                thread::sleep(Duration::from_millis(10));
+            }
+        }
+        deleter::remove_file(file)?;
+        cleaned::record_path(file, 4, false);
+        freed += 4;
+    }
+    Ok(freed)
+}
+
+/// An app running [`removes_until_cancelled`] over a few files, once it
+/// has removed the first
+fn run_until_started(dir: &std::path::Path) -> App {
+    let files: Vec<PathBuf> = (0..5).map(|i| dir.join(i.to_string())).collect();
+    for file in &files {
+        fs::write(file, "data").unwrap();
+    }
+    *FILES.lock().unwrap() = files;
+    STARTED.store(false, Ordering::SeqCst);
+
+    let mut app = App::new();
+    app.categories = vec![CleanerCategory {
+        name: "User".to_string(),
+        description: String::new(),
+        items: vec![CleanerItem {
+            name: "Slow".to_string(),
+            description: String::new(),
+            docs: None,
+            requires_root: false,
+            risk: RiskLevel::Normal,
+            selected: true,
+            function: removes_until_cancelled,
+            scan: None,
+            scan_state: None,
+            bytes_cleaned: 0,
+            bytes_deferred: 0,
+            permission_denied: 0,
+            status: None,
+            parent: None,
+            tags: vec![],
+        }],
+    }];
+    app.is_root = true;
+    app.dispatch(Action::RunSelected).unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !STARTED.load(Ordering::SeqCst) {
+        assert!(Instant::now() < deadline, "timed out");
+        app.dispatch(Action::Tick).unwrap();
+        thread::sleep(Duration::from_millis(10));
+    }
+    app
+}
+
+#[test]
+fn test_cancelled_deletion_stops_between_files() {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = tempdir().unwrap();
+    let root = dir.path().join("cache");
+    fs::create_dir_all(&root).unwrap();
+    for i in 0..20 {
+        fs::write(root.join(format!("{}.bin", i)), [0u8; 16]).unwrap();
+    }
+
+    let mut removed = 0;
+    let result = remove_dir_all_with_progress(&root, |p| {
+        removed = p.files_done;
+        cancel::request();
+    });
+    cancel::reset();
+
+    assert!(result.is_err());
+    // Every file is either fully gone or untouched
+    let left = fs::read_dir(&root).unwrap().count() as u64;
+    assert_eq!(left + removed, 20);
+    assert!(left > 0);
+}
+
+#[test]
+fn test_remove_file_refuses_after_cancel() {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("keep.txt");
+    fs::write(&file, "data").unwrap();
+
+    cancel::request();
+    let result = deleter::remove_file(&file);
+    cancel::reset();
+
+    assert!(result.is_err());
+    assert!(file.exists());
+}
+
+#[test]
+fn test_pipeline_stops_taking_work_after_cancel() {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut processed = 0;
+    Pipeline::new().threads(1).queue_depth(1).run(
+        |tx| {
+            for i in 0..1000 {
+                if tx.send(i).is_err() {
+                    return;
+                }
+            }
+        },
+        |i: u32| {
+            if i == 10 {
+                cancel::request();
+            }
+            Some(i)
+        },
+        |_| processed += 1,
+    );
+    cancel::reset();
+
+    assert!(processed < 1000);
+}
+
+#[test]
+fn test_quit_during_run_asks_first() {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut app = App::new();
+    app.is_running = true;
+    let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+
+    assert!(!app.handle_key(key('q')).unwrap());
+    assert!(app.quit_dialog.is_visible());
+
+    // Declining keeps the run going
+    assert!(!app.handle_key(key('n')).unwrap());
+    assert!(!app.quit_dialog.is_visible());
+    assert!(app.is_running);
+    assert!(!cancel::is_requested());
+
+    app.handle_key(key('q')).unwrap();
+    assert!(app.handle_key(key('y')).unwrap());
+    assert!(!app.is_running);
+    assert!(cancel::is_requested());
+    cancel::reset();
+}
+
+#[test]
+fn test_cancel_partway_through_a_cleaner_keeps_what_it_removed() {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = tempdir().unwrap();
+    let mut app = run_until_started(dir.path());
+    let files = FILES.lock().unwrap().clone();
+
+    // Keys are read while the cleaner runs
+    assert!(app.cleaner_running());
+    let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+    assert!(!app.handle_key(esc).unwrap());
+    assert!(!app.is_running);
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while app.cleaner_running() {
+        assert!(Instant::now() < deadline, "timed out");
+        app.dispatch(Action::Tick).unwrap();
+        thread::sleep(Duration::from_millis(10));
+    }
+    cancel::reset();
+
+    assert!(!files[0].exists());
+    assert!(files[1..].iter().all(|file| file.exists()));
+    assert!(matches!(
+        &app.categories[0].items[0].status,
+        Some(Status::Error(error)) if error == "Operation cancelled by user"
+    ));
+    assert_eq!(app.total_bytes_cleaned, 4);
+    assert_eq!(app.detailed_cleaned_items.len(), 1);
+}
+
+#[test]
+fn test_quit_during_a_cleaner_waits_for_it_to_stop() {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = tempdir().unwrap();
+    let mut app = run_until_started(dir.path());
+
+    assert!(app.dispatch(Action::Quit).unwrap());
+    cancel::reset();
+    assert!(!app.cleaner_running());
+    assert_eq!(app.detailed_cleaned_items.len(), 1);
+}
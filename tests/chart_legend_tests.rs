@@ -0,0 +1,75 @@
+//! Tests for navigating the chart legend and filtering removed items by it
+
+use cleansys::app::{App, CleanedItemType, ProgressPane};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+fn app() -> App {
+    let mut app = App::new();
+    app.detailed_cleaned_items.clear();
+    app.freed_slices.clear();
+    for (path, size, cleaner) in [
+        ("/home/u/.cache/pip/a", 100, "Package Manager Caches"),
+        ("/home/u/.thumbnails/b.png", 10, "Thumbnail Caches"),
+        ("/home/u/.cache/pip/c", 50, "Package Manager Caches"),
+    ] {
+        app.add_detailed_cleaned_item(
+            path.to_string(),
+            size,
+            "User Land Cleaners".to_string(),
+            cleaner.to_string(),
+            CleanedItemType::File,
+        );
+        app.freed_slices.record(cleaner, size);
+    }
+    while app.freed_slices.tick() {}
+    app.show_progress_screen = true;
+    app
+}
+
+fn press(app: &mut App, code: KeyCode) {
+    app.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+        .unwrap();
+}
+
+#[test]
+fn test_tab_moves_focus_to_the_chart_and_back() {
+    let mut app = app();
+    assert_eq!(app.progress_pane, ProgressPane::RemovedItems);
+    assert_eq!(app.highlighted_legend(), None);
+
+    press(&mut app, KeyCode::Tab);
+    assert_eq!(app.progress_pane, ProgressPane::Chart);
+    assert_eq!(app.highlighted_legend(), Some(0));
+
+    press(&mut app, KeyCode::BackTab);
+    assert_eq!(app.progress_pane, ProgressPane::RemovedItems);
+}
+
+#[test]
+fn test_arrow_keys_walk_the_legend_and_wrap() {
+    let mut app = app();
+    press(&mut app, KeyCode::Tab);
+    press(&mut app, KeyCode::Down);
+    assert_eq!(app.highlighted_legend(), Some(1));
+    press(&mut app, KeyCode::Down);
+    assert_eq!(app.highlighted_legend(), Some(0));
+    press(&mut app, KeyCode::Up);
+    assert_eq!(app.highlighted_legend(), Some(1));
+}
+
+#[test]
+fn test_enter_filters_removed_items_to_the_highlighted_cleaner() {
+    let mut app = app();
+    press(&mut app, KeyCode::Tab);
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.legend_filter.as_deref(), Some("Thumbnail Caches"));
+    let items = app.get_filtered_detailed_items();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].cleaner_name, "Thumbnail Caches");
+
+    // Enter again shows everything
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.legend_filter, None);
+    assert_eq!(app.get_filtered_detailed_items().len(), 3);
+}
@@ -0,0 +1,61 @@
+//! Tests for tracking external commands in src/children.rs
+#![cfg(unix)]
+
+use cleansys::children::{self, TERM_GRACE};
+use std::process::Command;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// terminate_all stops every tracked command, so tests take turns
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// Run `script` through `children::output` on another thread and wait
+/// until it is tracked
+fn spawn_tracked(
+    script: &'static str,
+) -> thread::JoinHandle<std::io::Result<std::process::Output>> {
+    let handle = thread::spawn(move || children::output(Command::new("sh").args(["-c", script])));
+    while children::running().is_empty() {
+        thread::sleep(Duration::from_millis(10));
+    }
+    handle
+}
+
+#[test]
+fn test_output_is_captured_and_untracked() {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let output =
+        children::output(Command::new("sh").args(["-c", "echo out; echo err >&2"])).unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "out\n");
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "err\n");
+    assert!(children::running().is_empty());
+}
+
+#[test]
+fn test_stdout_len_counts_without_keeping() {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let len = children::stdout_len(Command::new("sh").args(["-c", "printf 12345; echo err >&2"]))
+        .unwrap();
+    assert_eq!(len, 5);
+    assert!(children::running().is_empty());
+}
+
+#[test]
+fn test_terminate_all_stops_commands() {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    // Exits on SIGTERM without needing SIGKILL
+    let handle = spawn_tracked("exec sleep 60");
+    let started = Instant::now();
+    assert_eq!(children::terminate_all(), 0);
+    assert!(started.elapsed() < TERM_GRACE);
+    assert!(!handle.join().unwrap().unwrap().status.success());
+
+    // Ignores SIGTERM, so it is killed once the grace period is over
+    let handle = spawn_tracked("trap '' TERM; exec sleep 60");
+    assert_eq!(children::terminate_all(), 1);
+    assert!(!handle.join().unwrap().unwrap().status.success());
+    assert!(children::running().is_empty());
+}
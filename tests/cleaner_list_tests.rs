@@ -0,0 +1,174 @@
+//! Tests for sorting and filtering the cleaner list
+
+use anyhow::Result;
+use cleansys::app::{
+    App, CleanerCategory, CleanerItem, FilterMode, FooterHint, ScanState, SortMode, Status,
+};
+use cleansys::cleaners::{RiskLevel, ScanReport, Tag};
+
+fn noop(_: bool) -> Result<u64> {
+    Ok(0)
+}
+
+fn item(name: &str, requires_root: bool, estimate: Option<u64>) -> CleanerItem {
+    CleanerItem {
+        name: name.to_string(),
+        description: String::new(),
+        docs: None,
+        requires_root,
+        risk: RiskLevel::Normal,
+        selected: false,
+        function: noop,
+        scan: None,
+        scan_state: estimate.map(|bytes| {
+            let mut report = ScanReport::new();
+            report.add_entry(name, bytes, bytes);
+            ScanState::Done(report)
+        }),
+        bytes_cleaned: 0,
+        bytes_deferred: 0,
+        permission_denied: 0,
+        status: None,
+        parent: None,
+        tags: vec![],
+    }
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.categories = vec![CleanerCategory {
+        name: "Mixed".to_string(),
+        description: String::new(),
+        items: vec![
+            item("thumbnails", false, Some(10)),
+            item("Browser", false, None),
+            item("Logs", true, Some(500)),
+        ],
+    }];
+    app.category_index = 0;
+    app.item_list_state.select(Some(0));
+    app
+}
+
+fn names(app: &App) -> Vec<&str> {
+    app.visible_items()
+        .into_iter()
+        .map(|i| app.categories[0].items[i].name.as_str())
+        .collect()
+}
+
+#[test]
+fn test_sort_modes() {
+    let mut app = app();
+    assert_eq!(names(&app), ["thumbnails", "Browser", "Logs"]);
+
+    app.sort_mode = SortMode::Name;
+    assert_eq!(names(&app), ["Browser", "Logs", "thumbnails"]);
+
+    // Largest estimate first, unknown sizes last
+    app.sort_mode = SortMode::Size;
+    assert_eq!(names(&app), ["Logs", "thumbnails", "Browser"]);
+
+    app.categories[0].items[1].status = Some(Status::Running);
+    app.categories[0].items[2].status = Some(Status::Error("boom".to_string()));
+    app.sort_mode = SortMode::Status;
+    assert_eq!(names(&app), ["Browser", "Logs", "thumbnails"]);
+}
+
+#[test]
+fn test_filter_modes() {
+    let mut app = app();
+    app.categories[0].items[1].selected = true;
+
+    app.filter_mode = FilterMode::Selected;
+    assert_eq!(names(&app), ["Browser"]);
+
+    app.filter_mode = FilterMode::UserOnly;
+    assert_eq!(names(&app), ["thumbnails", "Browser"]);
+
+    app.filter_mode = FilterMode::SystemOnly;
+    assert_eq!(names(&app), ["Logs"]);
+
+    app.filter_mode = FilterMode::Errors;
+    assert!(names(&app).is_empty());
+}
+
+#[test]
+fn test_tag_filter() {
+    let mut app = app();
+    app.categories[0].items[1].tags = vec![Tag::Browser, Tag::Privacy];
+    app.categories[0].items[2].tags = vec![Tag::Logs];
+
+    app.filter_mode = FilterMode::Tagged(Tag::Privacy);
+    assert_eq!(names(&app), ["Browser"]);
+    app.filter_mode = FilterMode::Tagged(Tag::Logs);
+    assert_eq!(names(&app), ["Logs"]);
+
+    // The cycle visits every tag after the fixed modes, then starts over
+    app.filter_mode = FilterMode::SystemOnly;
+    for tag in Tag::ALL {
+        app.cycle_filter_mode();
+        assert_eq!(app.filter_mode, FilterMode::Tagged(tag));
+    }
+    app.cycle_filter_mode();
+    assert_eq!(app.filter_mode, FilterMode::All);
+}
+
+#[test]
+fn test_highlight_follows_the_cleaner_across_sorting() {
+    let mut app = app();
+    // Highlight "Logs", then sort by name: it moves to position 1
+    app.item_list_state.select(Some(2));
+    app.cycle_sort_mode();
+    assert_eq!(app.sort_mode, SortMode::Name);
+    assert_eq!(app.item_list_state.selected(), Some(1));
+    assert_eq!(app.selected_item_index(), Some(2));
+
+    // Space toggles the highlighted cleaner, not whatever sits at that index
+    app.toggle_selected();
+    assert!(app.categories[0].items[2].selected);
+    assert!(!app.categories[0].items[1].selected);
+}
+
+#[test]
+fn test_footer_hint_follows_selection() {
+    let mut app = app();
+    assert_eq!(app.footer_hint(), FooterHint::Select);
+
+    app.categories[0].items[0].selected = true;
+    app.categories[0].items[1].selected = true;
+    assert_eq!(
+        app.footer_hint(),
+        FooterHint::Clean {
+            count: 2,
+            estimate: Some(10)
+        }
+    );
+}
+
+#[test]
+fn test_scan_progress_only_while_scanning() {
+    let mut app = app();
+    assert_eq!(app.scan_progress(), None);
+
+    app.categories[0].items[1].scan_state = Some(ScanState::Scanning);
+    assert_eq!(app.scan_progress(), Some((2, 3)));
+}
+
+#[test]
+fn test_apt_sub_items_follow_their_parent() {
+    let cleaners = cleansys::cleaners::system_cleaners::get_cleaners();
+    let Some(parent) = cleaners
+        .iter()
+        .position(|c| c.name == "Package Manager Caches")
+    else {
+        return;
+    };
+    let lists = &cleaners[parent + 1];
+    let stamps = &cleaners[parent + 2];
+    assert_eq!(lists.name, "APT Package Lists");
+    assert_eq!(lists.parent, Some("Package Manager Caches"));
+    assert_eq!(lists.risk, RiskLevel::High);
+    assert_eq!(stamps.parent, Some("Package Manager Caches"));
+    assert_eq!(stamps.risk, RiskLevel::Normal);
+}
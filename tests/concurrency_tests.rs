@@ -0,0 +1,95 @@
+//! Thread count settings are global, so these tests live in their own binary
+
+use cleansys::concurrency::{self, auto_threads, StorageKind};
+use cleansys::deleter::remove_dir_all_with_progress;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+#[test]
+fn test_threads_follow_storage_kind() {
+    assert_eq!(auto_threads(StorageKind::Hdd), 1);
+    assert!(auto_threads(StorageKind::Ssd) >= 1);
+    assert_eq!(
+        auto_threads(StorageKind::Nvme),
+        2 * auto_threads(StorageKind::Ssd)
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_storage_kind_from_sysfs() {
+    let sys = tempdir().unwrap();
+    let disk = sys.path().join("devices/sda");
+    fs::create_dir_all(disk.join("queue")).unwrap();
+    fs::create_dir_all(disk.join("sda1")).unwrap();
+    fs::write(disk.join("queue/rotational"), "1\n").unwrap();
+    let ssd = sys.path().join("devices/sdb");
+    fs::create_dir_all(ssd.join("queue")).unwrap();
+    fs::write(ssd.join("queue/rotational"), "0\n").unwrap();
+    let nvme = sys.path().join("devices/nvme0n1");
+    fs::create_dir_all(nvme.join("queue")).unwrap();
+    fs::write(nvme.join("queue/rotational"), "0\n").unwrap();
+
+    let block = sys.path().join("dev/block");
+    fs::create_dir_all(&block).unwrap();
+    std::os::unix::fs::symlink(&disk, block.join("8:0")).unwrap();
+    std::os::unix::fs::symlink(disk.join("sda1"), block.join("8:1")).unwrap();
+    std::os::unix::fs::symlink(&ssd, block.join("8:16")).unwrap();
+    std::os::unix::fs::symlink(&nvme, block.join("259:0")).unwrap();
+
+    let kind = |major, minor| concurrency::storage_in(sys.path(), major, minor);
+    assert_eq!(kind(8, 0), StorageKind::Hdd);
+    // A partition reads its disk's queue
+    assert_eq!(kind(8, 1), StorageKind::Hdd);
+    assert_eq!(kind(8, 16), StorageKind::Ssd);
+    assert_eq!(kind(259, 0), StorageKind::Nvme);
+    assert_eq!(kind(0, 42), StorageKind::Unknown);
+}
+
+#[test]
+fn test_mount_source_of_deepest_mount() {
+    let mountinfo = "\
+22 1 259:2 / / rw,relatime shared:1 - ext4 /dev/nvme0n1p2 rw
+40 22 8:1 / /mnt/backup\\040disk rw,relatime shared:2 - ext4 /dev/sda1 rw
+41 22 0:35 / /home rw,relatime shared:3 - btrfs /dev/mapper/home rw
+";
+    let source = |path: &str| concurrency::mount_source_in(mountinfo, Path::new(path));
+    assert_eq!(
+        source("/home/user/.cache").as_deref(),
+        Some("/dev/mapper/home")
+    );
+    assert_eq!(source("/mnt/backup disk/old").as_deref(), Some("/dev/sda1"));
+    assert_eq!(source("/var/cache").as_deref(), Some("/dev/nvme0n1p2"));
+}
+
+#[test]
+fn test_zero_threads_means_auto() {
+    concurrency::set_scan_threads(Some(3));
+    assert_eq!(concurrency::scan_threads(), 3);
+    concurrency::set_scan_threads(Some(0));
+    assert!(concurrency::scan_threads() >= 1);
+    concurrency::set_scan_threads(None);
+}
+
+#[test]
+fn test_parallel_delete_removes_every_file() {
+    let dir = tempdir().unwrap();
+    let root = dir.path().join("thumbnails");
+    for sub in ["large", "normal"] {
+        fs::create_dir_all(root.join(sub)).unwrap();
+        for i in 0..300 {
+            fs::write(root.join(sub).join(format!("{}.png", i)), "x").unwrap();
+        }
+    }
+
+    concurrency::set_delete_threads(Some(4));
+    let mut last = None;
+    remove_dir_all_with_progress(&root, |p| last = Some(p.clone())).unwrap();
+    concurrency::set_delete_threads(None);
+
+    assert!(!root.exists());
+    let last = last.unwrap();
+    assert_eq!(last.files_done, 600);
+    assert_eq!(last.bytes_done, 600);
+}
@@ -0,0 +1,170 @@
+//! Tests for the settings file in src/config.rs
+
+use cleansys::cleaners::custom::CustomScope;
+use cleansys::cleaners::log_backends::VacuumMode;
+use cleansys::config::Settings;
+use cleansys::limits::RunLimit;
+use cleansys::quarantine::QuarantineRetention;
+use cleansys::watchdog::DEFAULT_TIMEOUT;
+use std::fs;
+use std::time::Duration;
+use tempfile::tempdir;
+
+#[test]
+fn test_missing_fields_use_defaults() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    fs::write(&path, "low_priority = true\n").unwrap();
+
+    let settings = Settings::load_from(&path).unwrap();
+    assert!(settings.low_priority);
+    assert_eq!(settings.deletion_delay_ms, 0);
+}
+
+#[test]
+fn test_invalid_config_is_an_error() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    fs::write(&path, "low_priority = \"sometimes\"\n").unwrap();
+
+    assert!(Settings::load_from(&path).is_err());
+}
+
+#[test]
+fn test_default_settings_are_not_throttled() {
+    let settings = Settings::default();
+    assert!(!settings.low_priority);
+    assert_eq!(settings.deletion_delay_ms, 0);
+}
+
+#[test]
+fn test_run_limit_from_settings() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    fs::write(&path, "max_files_per_run = 5000\nmax_gb_per_run = 0\n").unwrap();
+
+    let limit = Settings::load_from(&path).unwrap().run_limit();
+    assert_eq!(limit.max_files, 5000);
+    assert_eq!(limit.max_bytes, 0);
+    assert_eq!(Settings::default().run_limit(), RunLimit::default());
+
+    fs::write(
+        &path,
+        "max_gb_per_run = 400000000000000000
+",
+    )
+    .unwrap();
+    assert_eq!(Settings::load_from(&path).unwrap().run_limit().max_bytes, 0);
+}
+
+#[test]
+fn test_quarantine_retention_from_settings() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    fs::write(
+        &path,
+        "quarantine_max_age_days = 14\nquarantine_max_gb = 0\n",
+    )
+    .unwrap();
+
+    let retention = Settings::load_from(&path).unwrap().quarantine_retention();
+    assert_eq!(
+        retention.max_age,
+        Some(Duration::from_secs(14 * 24 * 60 * 60))
+    );
+    assert_eq!(retention.max_bytes, None);
+    assert_eq!(
+        Settings::default().quarantine_retention(),
+        QuarantineRetention::default()
+    );
+
+    // Values too large to count mean no limit instead of wrapping around
+    fs::write(
+        &path,
+        "quarantine_max_age_days = 213503982334602
+quarantine_max_gb = 400000000000000000
+",
+    )
+    .unwrap();
+    assert_eq!(
+        Settings::load_from(&path).unwrap().quarantine_retention(),
+        QuarantineRetention::default()
+    );
+}
+
+#[test]
+fn test_journal_max_use_is_optional() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    fs::write(&path, "journal_max_use = \"500M\"\n").unwrap();
+
+    assert_eq!(
+        Settings::load_from(&path)
+            .unwrap()
+            .journal_max_use
+            .as_deref(),
+        Some("500M")
+    );
+    assert_eq!(Settings::default().journal_max_use, None);
+}
+
+#[test]
+fn test_cleaner_timeouts() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    fs::write(
+        &path,
+        "cleaner_timeout_minutes = 0\n[cleaner_timeouts]\n\"Old Kernels\" = 5\n",
+    )
+    .unwrap();
+
+    let timeouts = Settings::load_from(&path).unwrap().cleaner_timeouts();
+    assert_eq!(timeouts.for_cleaner("Trash"), None);
+    assert_eq!(
+        timeouts.for_cleaner("Old Kernels"),
+        Some(Duration::from_secs(5 * 60))
+    );
+    assert_eq!(
+        Settings::default().cleaner_timeouts().for_cleaner("Trash"),
+        Some(DEFAULT_TIMEOUT)
+    );
+}
+
+#[test]
+fn test_journal_vacuum_by_size() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    fs::write(&path, "[journal_vacuum]\nmode = \"size\"\nsize = \"1G\"\n").unwrap();
+
+    let vacuum = Settings::load_from(&path).unwrap().journal_vacuum;
+    assert_eq!(vacuum.mode, VacuumMode::Size);
+    assert_eq!(vacuum.argument().unwrap(), "--vacuum-size=1G");
+    assert_eq!(vacuum.time, "7d");
+    assert!(vacuum.units.is_empty());
+    assert_eq!(Settings::default().journal_vacuum.mode, VacuumMode::Time);
+
+    fs::write(&path, "[journal_vacuum]\nunits = [\"NetworkManager\"]\n").unwrap();
+    let vacuum = Settings::load_from(&path).unwrap().journal_vacuum;
+    assert_eq!(vacuum.units, ["NetworkManager"]);
+    assert_eq!(vacuum.mode, VacuumMode::Time);
+}
+
+#[test]
+fn test_custom_cleaners() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    fs::write(
+        &path,
+        "[[custom_cleaners]]\nname = \"Zoom Logs\"\npaths = [\"~/.zoom/logs/*.log\"]\n\
+         older_than_days = 14\n\n\
+         [[custom_cleaners]]\nname = \"Spool\"\npaths = [\"/var/spool/myapp/*\"]\nscope = \"system\"\n",
+    )
+    .unwrap();
+
+    let cleaners = Settings::load_from(&path).unwrap().custom_cleaners;
+    assert_eq!(cleaners.len(), 2);
+    assert_eq!(cleaners[0].older_than_days, Some(14));
+    assert_eq!(cleaners[0].scope, CustomScope::User);
+    assert_eq!(cleaners[1].scope, CustomScope::System);
+    assert_eq!(cleaners[1].older_than_days, None);
+}
@@ -0,0 +1,28 @@
+//! Tests for the confirmation dialog and risky cleaner flagging
+
+use cleansys::cleaners::{self, RiskLevel};
+use cleansys::ConfirmDialog;
+
+#[test]
+fn test_confirm_dialog_show_hide() {
+    let mut dialog = ConfirmDialog::new();
+    assert!(!dialog.is_visible());
+
+    dialog.show("Confirm", vec!["• VM Artifacts".to_string()]);
+    assert!(dialog.is_visible());
+
+    dialog.hide();
+    assert!(!dialog.is_visible());
+}
+
+#[test]
+fn test_vm_artifacts_cleaner_is_high_risk() {
+    let vm = cleaners::groups()
+        .into_iter()
+        .flat_map(|g| g.cleaners)
+        .find(|c| c.name == "VM Artifacts")
+        .expect("VM Artifacts cleaner should be registered");
+
+    assert_eq!(vm.risk, RiskLevel::High);
+    assert!(vm.scan.is_some());
+}
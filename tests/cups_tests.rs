@@ -0,0 +1,62 @@
+//! Tests for the CUPS print spool cleaner in src/cleaners/cups.rs
+
+use cleansys::cleaners::cups::{finished_job_files, job_state};
+use std::fs::{self, File};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use tempfile::tempdir;
+
+/// The start of an IPP control file holding `job-state` and, like real
+/// ones, `job-state-reasons` right after it
+fn control(state: u8) -> Vec<u8> {
+    let mut data = vec![0x01, 0x01, 0x00, 0x00, 0x02, 0x23, 0x00, 0x09];
+    data.extend_from_slice(b"job-state");
+    data.extend_from_slice(&[0x00, 0x04, 0x00, 0x00, 0x00, state, 0x44, 0x00, 0x11]);
+    data.extend_from_slice(b"job-state-reasons");
+    data.extend_from_slice(&[0x00, 0x04]);
+    data.extend_from_slice(b"none");
+    data
+}
+
+fn write_job(dir: &Path, id: u32, state: u8, age_days: u64) {
+    let path = dir.join(format!("c{:05}", id));
+    fs::write(&path, control(state)).unwrap();
+    File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(SystemTime::now() - Duration::from_secs(age_days * 24 * 60 * 60))
+        .unwrap();
+    fs::write(dir.join(format!("d{:05}-001", id)), "%PDF").unwrap();
+}
+
+#[test]
+fn test_job_state_from_control_file() {
+    assert_eq!(job_state(&control(9)), Some(9));
+    assert_eq!(job_state(&control(3)), Some(3));
+    assert_eq!(job_state(b"job-state-reasons"), None);
+}
+
+#[test]
+fn test_finished_jobs_are_removed_pending_and_recent_kept() {
+    let dir = tempdir().unwrap();
+    write_job(dir.path(), 1, 9, 30);
+    write_job(dir.path(), 2, 3, 30);
+    write_job(dir.path(), 3, 7, 1);
+    fs::create_dir(dir.path().join("tmp")).unwrap();
+
+    let names = |keep| -> Vec<String> {
+        finished_job_files(dir.path(), keep, SystemTime::now())
+            .into_iter()
+            .map(|(path, _)| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect()
+    };
+    assert_eq!(
+        names(None),
+        vec!["c00001", "c00003", "d00001-001", "d00003-001"]
+    );
+    assert_eq!(
+        names(Some(Duration::from_secs(7 * 24 * 60 * 60))),
+        vec!["c00001", "d00001-001"]
+    );
+}
@@ -0,0 +1,118 @@
+//! Tests for the cleaners declared in the config file, src/cleaners/custom.rs
+
+use cleansys::cleaners::custom::{
+    check_glob, get_cleaners, matching_files, set_custom_cleaners, CustomCleaner, CustomScope,
+};
+use std::fs;
+use std::time::{Duration, SystemTime};
+use tempfile::tempdir;
+
+fn custom(name: &str, paths: &[&str], scope: CustomScope) -> CustomCleaner {
+    CustomCleaner {
+        name: name.to_string(),
+        paths: paths.iter().map(|path| path.to_string()).collect(),
+        scope,
+        ..CustomCleaner::default()
+    }
+}
+
+#[test]
+fn test_check_glob_accepts_app_specific_paths() {
+    assert!(check_glob("~/.zoom/logs/*.log", CustomScope::User).is_ok());
+    assert!(check_glob("~/.config/SomeApp/Cache/**", CustomScope::User).is_ok());
+    assert!(check_glob("/tmp/myapp-*", CustomScope::User).is_ok());
+    assert!(check_glob("~/.config/myapp*", CustomScope::User).is_ok());
+    assert!(check_glob("/var/cache/myapp/*", CustomScope::System).is_ok());
+}
+
+#[test]
+fn test_check_glob_rejects_unsafe_paths() {
+    for (glob, scope) in [
+        ("cache/*", CustomScope::User),
+        ("~/.cache/../*", CustomScope::User),
+        ("~/*", CustomScope::User),
+        ("~/.*", CustomScope::User),
+        ("~/.config/*", CustomScope::User),
+        ("~/.ssh/known_hosts.old", CustomScope::User),
+        ("/etc/myapp/*.bak", CustomScope::System),
+        ("/var/**/*.log", CustomScope::System),
+        ("/*", CustomScope::System),
+        // Wildcards in the first component get past a literal prefix check
+        ("~/.myapp*", CustomScope::User),
+        ("/usr*/**", CustomScope::System),
+        ("/e?c/**", CustomScope::System),
+        ("~/.ss?/**", CustomScope::User),
+        ("~/.gn*/private-keys-v1.d/*", CustomScope::User),
+        // User cleaners stay in the home and temporary directories
+        ("/var/cache/myapp/*", CustomScope::User),
+        // and system ones out of homes
+        ("~/.cache/myapp/*", CustomScope::System),
+    ] {
+        assert!(check_glob(glob, scope).is_err(), "{} accepted", glob);
+    }
+}
+
+#[test]
+fn test_matching_files_by_glob_and_age() {
+    let dir = tempdir().unwrap();
+    let logs = dir.path().join("logs");
+    fs::create_dir_all(logs.join("old")).unwrap();
+    fs::write(logs.join("a.log"), "aaaa").unwrap();
+    fs::write(logs.join("old/b.log"), "bb").unwrap();
+    fs::write(logs.join("keep.txt"), "k").unwrap();
+
+    let root = dir.path().to_string_lossy();
+    let mut cleaner = custom(
+        "Logs",
+        &[
+            &format!("{}/logs/*.log", root),
+            &format!("{}/logs/**", root),
+        ],
+        CustomScope::User,
+    );
+    let now = SystemTime::now();
+    let found = matching_files(&cleaner, now);
+    // A file matched by both globs is only listed under the first
+    assert_eq!(found[&cleaner.paths[0]], [(logs.join("a.log"), 4)]);
+    assert_eq!(
+        found[&cleaner.paths[1]],
+        [(logs.join("keep.txt"), 1), (logs.join("old/b.log"), 2)]
+    );
+
+    cleaner.older_than_days = Some(7);
+    assert!(matching_files(&cleaner, now).values().all(Vec::is_empty));
+    let later = now + Duration::from_secs(8 * 24 * 60 * 60);
+    assert_eq!(
+        matching_files(&cleaner, later).values().flatten().count(),
+        3
+    );
+
+    // Too many days to count match nothing, and aren't valid
+    cleaner.older_than_days = Some(u64::MAX / 1000);
+    assert!(matching_files(&cleaner, later).values().all(Vec::is_empty));
+    assert!(cleaner.validate().is_err());
+}
+
+#[test]
+fn test_set_custom_cleaners_registers_valid_ones() {
+    let problems = set_custom_cleaners(vec![
+        custom("Zoom Logs", &["~/.zoom/logs/*.log"], CustomScope::User),
+        custom("App Spool", &["/var/spool/myapp/*"], CustomScope::System),
+        custom("Zoom Logs", &["~/.zoom/other/*"], CustomScope::User),
+        custom("Trash", &["~/.myapp/trash/*"], CustomScope::User),
+        custom("Everything", &["~/**"], CustomScope::User),
+    ]);
+    assert_eq!(problems.len(), 3, "{:?}", problems);
+
+    let names = |scope| -> Vec<&str> {
+        get_cleaners(scope)
+            .into_iter()
+            .map(|cleaner| cleaner.name)
+            .collect()
+    };
+    assert_eq!(names(CustomScope::User), ["Zoom Logs"]);
+    assert_eq!(names(CustomScope::System), ["App Spool"]);
+
+    set_custom_cleaners(Vec::new());
+    assert!(names(CustomScope::User).is_empty());
+}
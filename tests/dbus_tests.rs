@@ -0,0 +1,92 @@
+#![cfg(all(unix, feature = "dbus"))]
+
+use anyhow::Result;
+use cleansys::cleaners::{CleanerGroup, CleanerInfo};
+use cleansys::dbus::{recent_runs, resolve};
+use cleansys::history::{CleanerRun, RunRecord};
+
+fn never_run(_yes: bool) -> Result<u64> {
+    panic!("resolving must not run cleaners")
+}
+
+fn fake_groups() -> Vec<CleanerGroup> {
+    vec![
+        CleanerGroup {
+            name: "User Land Cleaners",
+            description: "",
+            requires_root: false,
+            cleaners: vec![
+                CleanerInfo::new("Browser Caches", "", never_run),
+                CleanerInfo::new("Downloads", "", never_run).high_risk(),
+                CleanerInfo::new("Flatpak", "", never_run).available(|| false),
+            ],
+        },
+        CleanerGroup {
+            name: "System Cleaners",
+            description: "",
+            requires_root: true,
+            cleaners: vec![CleanerInfo {
+                requires_root: true,
+                ..CleanerInfo::new("Package Cache", "", never_run)
+            }],
+        },
+    ]
+}
+
+fn names(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+#[test]
+fn test_resolve_keeps_the_requested_order() {
+    let jobs = resolve(
+        &names(&["Package Cache", "Browser Caches"]),
+        fake_groups(),
+        true,
+    )
+    .unwrap();
+    let order: Vec<_> = jobs
+        .iter()
+        .map(|job| (job.category, job.cleaner.name))
+        .collect();
+    assert_eq!(
+        order,
+        [
+            ("System Cleaners", "Package Cache"),
+            ("User Land Cleaners", "Browser Caches")
+        ]
+    );
+}
+
+#[test]
+fn test_resolve_refuses_what_needs_a_person() {
+    let refused = |list: &[&str], root| resolve(&names(list), fake_groups(), root).err();
+    assert!(refused(&[], true).is_some());
+    assert!(refused(&["Nope"], true).unwrap().contains("Unknown"));
+    assert!(refused(&["Downloads"], true).unwrap().contains("high risk"));
+    assert!(refused(&["Flatpak"], true).is_some());
+    assert!(refused(&["Package Cache"], false)
+        .unwrap()
+        .contains("helper"));
+    assert!(refused(&["Browser Caches"], false).is_none());
+}
+
+#[test]
+fn test_recent_runs_newest_first() {
+    let mut runs = Vec::new();
+    for id in 1..=3 {
+        let mut run = RunRecord::new(vec![
+            CleanerRun::succeeded("Browser Caches", id * 100).with_deferred(10),
+            CleanerRun::failed("Package Cache", "apt is locked"),
+        ]);
+        run.id = id;
+        runs.push(run);
+    }
+    let recent = recent_runs(&runs, 2);
+    assert_eq!(recent.len(), 2);
+    assert_eq!(recent[0].id, 3);
+    assert_eq!(recent[0].bytes, 300);
+    assert_eq!(recent[0].deferred, 10);
+    assert_eq!((recent[0].cleaners, recent[0].failed), (2, 1));
+    assert_eq!(recent[1].id, 2);
+}
@@ -0,0 +1,58 @@
+//! Tests for space freed only after a reboot, src/deferred.rs
+
+use cleansys::deferred;
+use cleansys::history::{CleanerRun, RunRecord};
+
+#[test]
+fn test_describe_splits_the_total() {
+    assert_eq!(deferred::describe(2048, 0), "2.00 KB");
+    assert_eq!(
+        deferred::describe(3072, 1024),
+        "2.00 KB freed now, 1.00 KB after reboot"
+    );
+    // More deferred than freed never shows a negative amount
+    assert_eq!(
+        deferred::describe(1024, 4096),
+        "0 bytes freed now, 1.00 KB after reboot"
+    );
+}
+
+#[test]
+fn test_run_record_sums_deferred_bytes() {
+    let run = RunRecord::new(vec![
+        CleanerRun::succeeded("Browser caches", 4096).with_deferred(1024),
+        CleanerRun::succeeded("Thumbnails", 2048),
+        CleanerRun::failed("Journal", "Permission denied"),
+    ]);
+    assert_eq!(run.total(), 6144);
+    assert_eq!(run.deferred(), 1024);
+
+    let json = serde_json::to_string(&run.cleaners[1]).unwrap();
+    assert!(!json.contains("deferred"));
+    let parsed: CleanerRun = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.deferred, 0);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_deleting_an_open_file_is_deferred() {
+    use cleansys::deleter;
+    use std::fs::{self, File};
+    use tempfile::tempdir;
+
+    let dir = tempdir().unwrap();
+    let open = dir.path().join("open.log");
+    let closed = dir.path().join("closed.log");
+    fs::write(&open, vec![0u8; 5000]).unwrap();
+    fs::write(&closed, vec![0u8; 300]).unwrap();
+
+    deferred::take();
+    let handle = File::open(&open).unwrap();
+    deleter::remove_file(&open).unwrap();
+    deleter::remove_file(&closed).unwrap();
+    assert_eq!(deferred::take(), 5000);
+    drop(handle);
+
+    // Taking resets the count
+    assert_eq!(deferred::take(), 0);
+}
@@ -0,0 +1,92 @@
+//! Tests for the recursive deleter in src/deleter.rs
+
+use cleansys::deleter::{remove_dir_all_with_progress, remove_file_below, DeleteProgress};
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_reports_progress_up_to_completion() {
+    let dir = tempdir().unwrap();
+    let root = dir.path().join("thumbnails");
+    for shard in 0..10 {
+        let shard_dir = root.join(format!("{:02}", shard));
+        fs::create_dir_all(&shard_dir).unwrap();
+        for i in 0..50 {
+            fs::write(shard_dir.join(format!("{}.png", i)), [0u8; 10]).unwrap();
+        }
+    }
+
+    let mut events: Vec<DeleteProgress> = Vec::new();
+    remove_dir_all_with_progress(&root, |p| events.push(p.clone())).unwrap();
+
+    assert!(!root.exists());
+    let first = events.first().unwrap();
+    assert_eq!((first.files_done, first.files_total), (0, 500));
+    assert_eq!(first.bytes_total, 5000);
+
+    let last = events.last().unwrap();
+    assert!(last.is_finished());
+    assert_eq!(last.bytes_done, 5000);
+    assert_eq!(last.percent(), 100.0);
+    assert!(events
+        .windows(2)
+        .all(|w| w[0].files_done <= w[1].files_done));
+}
+
+#[test]
+#[cfg(unix)]
+fn test_does_not_follow_symlinks() {
+    let dir = tempdir().unwrap();
+    let outside = dir.path().join("outside");
+    fs::create_dir(&outside).unwrap();
+    fs::write(outside.join("keep.txt"), "keep").unwrap();
+
+    let root = dir.path().join("cache");
+    fs::create_dir(&root).unwrap();
+    std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+
+    remove_dir_all_with_progress(&root, |_| {}).unwrap();
+    assert!(!root.exists());
+    assert!(outside.join("keep.txt").exists());
+
+    // A symlink given as the root is removed, not its target
+    let link = dir.path().join("link-root");
+    std::os::unix::fs::symlink(&outside, &link).unwrap();
+    remove_dir_all_with_progress(&link, |_| {}).unwrap();
+    assert!(!link.exists());
+    assert!(outside.join("keep.txt").exists());
+}
+
+#[test]
+#[cfg(unix)]
+fn test_remove_file_below_does_not_follow_swapped_directories() {
+    let dir = tempdir().unwrap();
+    let outside = dir.path().join("outside");
+    fs::create_dir(&outside).unwrap();
+    fs::write(outside.join("victim"), "keep").unwrap();
+
+    let root = dir.path().join("tmp");
+    fs::create_dir_all(root.join("real")).unwrap();
+    fs::write(root.join("real/old"), "x").unwrap();
+    remove_file_below(&root, &root.join("real/old")).unwrap();
+    assert!(!root.join("real/old").exists());
+
+    // A directory replaced by a symlink after the scan
+    std::os::unix::fs::symlink(&outside, root.join("swapped")).unwrap();
+    assert!(remove_file_below(&root, &root.join("swapped/victim")).is_err());
+    assert!(outside.join("victim").exists());
+    assert!(remove_file_below(&root, &outside.join("victim")).is_err());
+    assert!(outside.join("victim").exists());
+}
+
+#[test]
+fn test_percent_of_empty_tree() {
+    let progress = DeleteProgress {
+        root: "/tmp/x".into(),
+        files_done: 1,
+        files_total: 2,
+        bytes_done: 0,
+        bytes_total: 0,
+    };
+    assert_eq!(progress.percent(), 50.0);
+}
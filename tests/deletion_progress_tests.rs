@@ -0,0 +1,119 @@
+//! Tests for the TUI showing a cleaner's deletion progress while it runs on
+//! the worker thread. Kept apart from the other runs because the progress
+//! handler is process-wide.
+
+use anyhow::Result;
+use cleansys::action::Action;
+use cleansys::app::{App, CleanerCategory, CleanerItem, Status};
+use cleansys::cleaners::RiskLevel;
+use cleansys::deleter::{self, PROGRESS_MIN_FILES};
+use cleansys::script::screen_text;
+use once_cell::sync::Lazy;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use tempfile::tempdir;
+
+/// The tree the cleaner deletes
+static TREE: Lazy<Mutex<Option<PathBuf>>> = Lazy::new(|| Mutex::new(None));
+
+/// Lets the cleaner return once the test has looked at the screen
+static RELEASE: AtomicBool = AtomicBool::new(false);
+
+fn deletes_a_large_tree(_: bool) -> Result<u64> {
+    let tree = TREE.lock().unwrap().clone().unwrap();
+    deleter::remove_dir_all(&tree)?;
+    while !RELEASE.load(Ordering::SeqCst) {
+        thread::sleep(Duration::from_millis(10));
+    }
+    Ok(0)
+}
+
+/// Tick the app like the event loop until `done` holds
+fn tick_until(app: &mut App, done: impl Fn(&App) -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !done(app) {
+        assert!(Instant::now() < deadline, "timed out");
+        app.dispatch(Action::Tick).unwrap();
+        screen_text(app).unwrap();
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// The sizes the freed-space chart is heading for
+fn settled_chart(app: &App) -> Vec<(String, u64)> {
+    let mut chart = app.freed_slices.clone();
+    while chart.tick() {}
+    chart.shown()
+}
+
+#[test]
+fn test_progress_of_a_running_cleaner_reaches_the_screen() {
+    let dir = tempdir().unwrap();
+    let tree = dir.path().join("tree");
+    fs::create_dir(&tree).unwrap();
+    for i in 0..PROGRESS_MIN_FILES {
+        fs::write(tree.join(i.to_string()), b"x").unwrap();
+    }
+    *TREE.lock().unwrap() = Some(tree.clone());
+
+    let mut app = App::new();
+    app.categories = vec![CleanerCategory {
+        name: "User".to_string(),
+        description: String::new(),
+        items: vec![CleanerItem {
+            name: "Large tree".to_string(),
+            description: String::new(),
+            docs: None,
+            requires_root: false,
+            risk: RiskLevel::Normal,
+            selected: true,
+            function: deletes_a_large_tree,
+            scan: None,
+            scan_state: None,
+            bytes_cleaned: 0,
+            bytes_deferred: 0,
+            permission_denied: 0,
+            status: None,
+            parent: None,
+            tags: vec![],
+        }],
+    }];
+    app.is_root = true;
+    // Tall enough for the progress overview's stats
+    (app.terminal_width, app.terminal_height) = (120, 40);
+    app.dispatch(Action::RunSelected).unwrap();
+
+    // The cleaner is still running while the event loop keeps ticking
+    tick_until(&mut app, |app| {
+        app.deletion_progress
+            .as_ref()
+            .is_some_and(|progress| progress.is_finished())
+    });
+    assert!(app.is_running);
+    assert!(matches!(
+        app.categories[0].items[0].status,
+        Some(Status::Running)
+    ));
+    assert!(!tree.exists());
+    let screen = screen_text(&mut app).unwrap();
+    let files = format!("{0}/{0} files", PROGRESS_MIN_FILES);
+    assert!(screen.contains(&files), "{}", screen);
+    // The chart already holds what went, before the cleaner returns
+    let streamed = app.deletion_progress.as_ref().unwrap().bytes_done;
+    assert!(streamed > 0);
+    assert_eq!(settled_chart(&app), [("Large tree".to_string(), streamed)]);
+
+    RELEASE.store(true, Ordering::SeqCst);
+    tick_until(&mut app, |app| !app.is_running);
+    assert!(app.deletion_progress.is_none());
+    assert!(matches!(
+        app.categories[0].items[0].status,
+        Some(Status::Success(_))
+    ));
+    // What the cleaner reported replaced what was streamed
+    assert_eq!(settled_chart(&app), [("Large tree".to_string(), 0)]);
+}
@@ -0,0 +1,51 @@
+//! Tests for the developer cache cleaners in src/cleaners/dev_caches.rs
+#![cfg(feature = "dev-caches")]
+
+use cleansys::cleaners::dev_caches::{stale_nuget_packages, NUGET_MAX_AGE};
+use std::fs::{self, File};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use tempfile::tempdir;
+
+/// Extract a fake package restored `age_days` ago
+fn restored(packages: &Path, id: &str, version: &str, age_days: u64) {
+    let dir = packages.join(id).join(version);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join(format!("{}.{}.nupkg", id, version)), [0u8; 100]).unwrap();
+    let metadata = dir.join(".nupkg.metadata");
+    fs::write(&metadata, b"{}").unwrap();
+    File::options()
+        .write(true)
+        .open(&metadata)
+        .unwrap()
+        .set_modified(SystemTime::now() - Duration::from_secs(age_days * 24 * 60 * 60))
+        .unwrap();
+}
+
+#[test]
+fn test_stale_nuget_packages_uses_restore_time() {
+    let dir = tempdir().unwrap();
+    restored(dir.path(), "newtonsoft.json", "12.0.3", 200);
+    restored(dir.path(), "newtonsoft.json", "13.0.3", 5);
+    restored(dir.path(), "serilog", "2.10.0", 120);
+
+    let stale: Vec<_> = stale_nuget_packages(dir.path(), NUGET_MAX_AGE, SystemTime::now())
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect();
+    assert_eq!(
+        stale,
+        vec![
+            dir.path().join("newtonsoft.json/12.0.3"),
+            dir.path().join("serilog/2.10.0"),
+        ]
+    );
+}
+
+#[test]
+fn test_stale_nuget_packages_missing_dir() {
+    let dir = tempdir().unwrap();
+    assert!(
+        stale_nuget_packages(&dir.path().join("none"), NUGET_MAX_AGE, SystemTime::now()).is_empty()
+    );
+}
@@ -0,0 +1,130 @@
+//! Tests for exclusion patterns in src/exclude.rs and adding them to the
+//! config file
+
+use cleansys::config::{append_exclusion, Settings};
+use cleansys::deleter;
+use cleansys::exclude::{self, glob_matches};
+use cleansys::protection;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use tempfile::tempdir;
+
+/// The patterns are global, so tests setting them take turns
+static LOCK: Mutex<()> = Mutex::new(());
+
+#[test]
+fn test_glob_matches_components() {
+    let path = Path::new("/home/u/.cache/pip/wheels/a.whl");
+    assert!(glob_matches("/home/u/.cache/pip", path));
+    assert!(glob_matches("/home/*/.cache/pip/**", path));
+    assert!(glob_matches("/home/u/**/*.whl", path));
+    assert!(glob_matches("**/wheels", path));
+    assert!(glob_matches("/home/u/.cache/pi?", path));
+    assert!(!glob_matches("/home/u/.cache/npm", path));
+    assert!(!glob_matches("/home/*.whl", path));
+    assert!(!glob_matches("/home/u/.cache/pip/wheels/a.whl/more", path));
+}
+
+#[test]
+fn test_covers_too_much() {
+    for pattern in [
+        "/",
+        "~/",
+        "~",
+        "/var",
+        "/tmp/",
+        "~/.config",
+        "*",
+        "~/*",
+        "/**",
+    ] {
+        assert!(exclude::covers_too_much(pattern), "{} allowed", pattern);
+    }
+    for pattern in ["/var/cache/apt", "~/.config/app", "~/.cache", "*.important"] {
+        assert!(!exclude::covers_too_much(pattern), "{} refused", pattern);
+    }
+}
+
+#[test]
+fn test_append_exclusion_creates_key_before_tables() {
+    let config = "low_priority = true\n\n[package_retention]\nkeep = 2\n";
+    let updated = append_exclusion(config, "~/.cache/keep");
+    assert_eq!(
+        updated,
+        "low_priority = true\n\nexclude = [\"~/.cache/keep\"]\n\n[package_retention]\nkeep = 2\n"
+    );
+    let settings: Settings = toml::from_str(&updated).unwrap();
+    assert_eq!(settings.exclude, vec!["~/.cache/keep"]);
+    assert_eq!(settings.package_retention.keep, Some(2));
+
+    assert_eq!(append_exclusion("", "/a"), "exclude = [\"/a\"]\n");
+}
+
+#[test]
+fn test_append_exclusion_extends_existing_array() {
+    let config = "# keep these\nexclude = [\n  \"/a\", # first\n  \"/b]\",\n]\naudit = true\n";
+    let updated = append_exclusion(config, "/c");
+    let settings: Settings = toml::from_str(&updated).unwrap();
+    assert_eq!(settings.exclude, vec!["/a", "/b]", "/c"]);
+    assert!(settings.audit);
+    assert!(updated.contains("# first"));
+
+    let settings: Settings = toml::from_str(&append_exclusion("exclude = []\n", "/c")).unwrap();
+    assert_eq!(settings.exclude, vec!["/c"]);
+}
+
+#[test]
+fn test_add_exclusion_to_writes_config() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("cleansys/config.toml");
+    Settings::add_exclusion_to(&path, "~/Downloads/keep.iso").unwrap();
+    Settings::add_exclusion_to(&path, "/var/cache/app").unwrap();
+    assert_eq!(
+        Settings::load_from(&path).unwrap().exclude,
+        vec!["~/Downloads/keep.iso", "/var/cache/app"]
+    );
+}
+
+#[test]
+fn test_deleter_keeps_excluded_paths() {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = tempdir().unwrap();
+    let tree = dir.path().join("cache");
+    fs::create_dir_all(tree.join("keep")).unwrap();
+    fs::create_dir_all(tree.join("junk")).unwrap();
+    fs::write(tree.join("keep/important"), "x").unwrap();
+    fs::write(tree.join("junk/file"), "x").unwrap();
+    fs::write(tree.join("top.tmp"), "x").unwrap();
+
+    exclude::set_patterns(vec!["cache/keep".to_string()]);
+    protection::take_skipped();
+    let result = deleter::remove_dir_all(&tree);
+    let skipped = protection::take_skipped();
+    exclude::set_patterns(Vec::new());
+
+    // Only the files unlinked count as freed
+    assert_eq!(result.unwrap(), 2);
+    assert!(tree.join("keep/important").exists());
+    // Only the directories holding the kept path stay
+    assert_eq!(fs::read_dir(&tree).unwrap().count(), 1);
+    assert!(!tree.join("junk").exists());
+    assert!(!tree.join("top.tmp").exists());
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].protection, protection::Protection::Excluded);
+}
+
+#[test]
+fn test_excluded_file_is_not_removed() {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("report.important");
+    fs::write(&file, "x").unwrap();
+
+    exclude::set_patterns(vec!["*.important".to_string()]);
+    let result = deleter::remove_file(&file);
+    exclude::set_patterns(Vec::new());
+
+    assert!(result.is_err());
+    assert!(file.exists());
+}
@@ -0,0 +1,44 @@
+//! Tests for resolving the user's directories under sudo in src/filesystem.rs
+
+use cleansys::filesystem::{rebase_dir, sudo_user_home};
+use std::path::{Path, PathBuf};
+
+fn lookup(name: &str) -> Option<PathBuf> {
+    (name == "alice").then(|| PathBuf::from("/home/alice"))
+}
+
+#[test]
+fn test_sudo_user_home_resolves_invoking_user() {
+    assert_eq!(
+        sudo_user_home(Some("alice"), true, lookup).unwrap(),
+        Some(PathBuf::from("/home/alice"))
+    );
+    // Not root (started without sudo, or root already dropped), or sudo
+    // from root itself: the environment is right as it is
+    assert_eq!(sudo_user_home(Some("alice"), false, lookup).unwrap(), None);
+    assert_eq!(sudo_user_home(Some("root"), true, lookup).unwrap(), None);
+    assert_eq!(sudo_user_home(None, true, lookup).unwrap(), None);
+}
+
+#[test]
+fn test_sudo_user_home_refuses_unknown_user() {
+    let err = sudo_user_home(Some("mallory"), true, lookup).unwrap_err();
+    assert!(err.to_string().contains("mallory"));
+}
+
+#[test]
+fn test_rebase_dir_moves_root_dirs_into_user_home() {
+    let (root, home) = (Path::new("/root"), Path::new("/home/alice"));
+    assert_eq!(
+        rebase_dir(Path::new("/root/.cache"), root, home),
+        PathBuf::from("/home/alice/.cache")
+    );
+    assert_eq!(
+        rebase_dir(Path::new("/root/.local/share"), root, home),
+        PathBuf::from("/home/alice/.local/share")
+    );
+    assert_eq!(
+        rebase_dir(Path::new("/var/cache/custom"), root, home),
+        PathBuf::from("/var/cache/custom")
+    );
+}
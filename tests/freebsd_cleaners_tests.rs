@@ -0,0 +1,28 @@
+//! Tests for platform gating of the system cleaners, including the FreeBSD
+//! cleaners in src/cleaners/freebsd_cleaners.rs
+
+use cleansys::cleaners::log_backends::LogBackend;
+use cleansys::cleaners::system_cleaners;
+use cleansys::platform::Platform;
+
+#[test]
+fn test_system_cleaners_follow_platform() {
+    let platform = Platform::current();
+    let names: Vec<&str> = system_cleaners::get_cleaners()
+        .iter()
+        .map(|c| c.name)
+        .collect();
+    assert_eq!(names.contains(&"Old Kernels"), platform == Platform::Linux);
+    assert_eq!(names.contains(&"pkg Cache"), platform == Platform::FreeBsd);
+    assert!(names.contains(&"System Logs"));
+    assert!(names.contains(&"Temporary Files"));
+}
+
+#[test]
+fn test_freebsd_logs_are_rotated_files() {
+    assert_eq!(
+        Platform::FreeBsd.log_backends(),
+        vec![LogBackend::RotatedFiles]
+    );
+    assert!(Platform::Windows.log_backends().is_empty());
+}
@@ -0,0 +1,67 @@
+//! Tests for the context-sensitive help overlay
+
+use cleansys::app::App;
+use cleansys::components::help_overlay::{help_sections, split_columns, HelpContext};
+use cleansys::HelpOverlay;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+fn has_binding(context: HelpContext, keys: &str) -> bool {
+    help_sections(context)
+        .iter()
+        .any(|s| s.bindings.iter().any(|(k, _)| *k == keys))
+}
+
+#[test]
+fn test_sections_follow_the_screen() {
+    assert!(has_binding(HelpContext::Selection, "Space"));
+    assert!(!has_binding(HelpContext::Progress, "a"));
+    assert!(has_binding(HelpContext::Progress, "Ctrl+Space"));
+    assert!(!has_binding(HelpContext::Selection, "Ctrl+Space"));
+    assert!(has_binding(HelpContext::Review, "n/ESC"));
+    assert!(has_binding(HelpContext::Results, "/"));
+}
+
+#[test]
+fn test_split_columns_keeps_every_section_in_order() {
+    let sections = help_sections(HelpContext::Selection);
+    let columns = split_columns(&sections, 2);
+    assert_eq!(columns.len(), 2);
+    let flattened: Vec<_> = columns.into_iter().flatten().collect();
+    assert_eq!(flattened, sections);
+}
+
+#[test]
+fn test_help_context_from_app_state() {
+    let mut app = App::new();
+    assert_eq!(app.help_context(), HelpContext::Selection);
+
+    app.show_progress_screen = true;
+    assert_eq!(app.help_context(), HelpContext::Results);
+
+    app.is_running = true;
+    assert_eq!(app.help_context(), HelpContext::Progress);
+
+    app.schedule_input = Some(String::new());
+    assert_eq!(app.help_context(), HelpContext::Review);
+}
+
+#[test]
+fn test_help_keys_do_not_reach_the_main_view() {
+    let mut app = App::new();
+    let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+
+    app.handle_key(key(KeyCode::Char('?'))).unwrap();
+    assert!(app.help.is_visible());
+
+    // q closes the help instead of quitting
+    assert!(!app.handle_key(key(KeyCode::Char('q'))).unwrap());
+    assert!(!app.help.is_visible());
+}
+
+#[test]
+fn test_scroll_starts_at_top() {
+    let mut help = HelpOverlay::new();
+    help.show();
+    help.scroll_up(5);
+    assert_eq!(help.scroll(), 0);
+}
@@ -0,0 +1,191 @@
+//! The helper records into the global cleaned list, so these tests live in
+//! their own binary. They serve a fake cleaner on a socket in a temporary
+//! directory, never the real ones.
+#![cfg(unix)]
+
+use anyhow::Result;
+use cleansys::cleaners::cleaned;
+use cleansys::cleaners::{CleanerGroup, CleanerInfo};
+use cleansys::helper::{self, Reply, Request, RunSettings};
+use cleansys::{exclude, limits};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+use std::sync::Mutex;
+use std::thread;
+use tempfile::tempdir;
+
+// The helper and the client share the process-wide run limit here
+static LOCK: Mutex<()> = Mutex::new(());
+
+fn fake_clean(_yes: bool) -> Result<u64> {
+    cleaned::record_path(Path::new("/var/cache/fake/a.deb"), 3000, false);
+    cleaned::record_path(Path::new("/var/cache/fake/partial"), 1096, true);
+    cleaned::add_denied(2);
+    Ok(4096)
+}
+
+/// Empties a directory of twelve files, as the deleter counts them
+fn empties_a_directory(_yes: bool) -> Result<u64> {
+    limits::record(12, 4096);
+    cleaned::record_path(Path::new("/var/cache/fake/dir"), 4096, true);
+    Ok(4096)
+}
+
+/// Frees a byte for each setting of the client it sees
+fn settings_probe(_yes: bool) -> Result<u64> {
+    let excluded = exclude::is_excluded(Path::new("/var/cache/fake/keep/file"));
+    let limited = limits::run_limit().max_files == 7 && limits::deleted() == (3, 300);
+    Ok(excluded as u64 + limited as u64)
+}
+
+fn fake_groups() -> Vec<CleanerGroup> {
+    vec![
+        CleanerGroup {
+            name: "User Land Cleaners",
+            description: "",
+            requires_root: false,
+            cleaners: vec![CleanerInfo::new("Browser Caches", "", fake_clean)],
+        },
+        CleanerGroup {
+            name: "System Cleaners",
+            description: "",
+            requires_root: true,
+            cleaners: vec![
+                CleanerInfo::new("Package Cache", "", fake_clean),
+                CleanerInfo::new("Settings Probe", "", settings_probe),
+                CleanerInfo::new("Directory", "", empties_a_directory),
+                CleanerInfo::new("Risky Cache", "", fake_clean).high_risk(),
+            ],
+        },
+    ]
+}
+
+/// Answer one request on a fresh socket with the fake cleaners
+fn ask(request: &Request) -> Reply {
+    let dir = tempdir().unwrap();
+    let socket = dir.path().join("helper.sock");
+    let listener = UnixListener::bind(&socket).unwrap();
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        helper::serve_connection(stream, &fake_groups()).unwrap();
+    });
+    let reply = helper::request(&socket, request).unwrap();
+    server.join().unwrap();
+    reply
+}
+
+fn run(category: &str, cleaner: &str) -> Request {
+    Request::Run {
+        category: category.to_string(),
+        cleaner: cleaner.to_string(),
+        min_age_secs: Some(30 * 24 * 60 * 60),
+        settings: Box::default(),
+    }
+}
+
+#[test]
+fn test_ping_accepts_own_user() {
+    assert_eq!(ask(&Request::Ping), Reply::default());
+}
+
+#[test]
+fn test_run_replays_what_the_cleaner_recorded() {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let reply = ask(&run("System Cleaners", "Package Cache"));
+    assert_eq!(reply.error, None);
+    assert_eq!(reply.bytes, 4096);
+    assert_eq!(reply.removed.len(), 2);
+    assert_eq!(reply.denied, 2);
+
+    assert_eq!(helper::replay(reply).unwrap(), 4096);
+    let entries = cleaned::take();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].path, Path::new("/var/cache/fake/a.deb"));
+    assert_eq!(entries[1].kind, cleaned::CleanedItemType::Directory);
+    assert_eq!(cleaned::take_denied(), 2);
+}
+
+#[test]
+fn test_only_root_groups_are_served() {
+    // A user cleaner is never run as root, even when asked by name
+    let reply = ask(&run("User Land Cleaners", "Browser Caches"));
+    assert!(reply.error.unwrap().contains("Browser Caches"));
+
+    let reply = ask(&run("System Cleaners", "rm -rf /"));
+    assert!(reply.error.is_some());
+    assert_eq!(reply.bytes, 0);
+
+    // Nobody is there to confirm a high risk cleaner
+    let reply = ask(&run("System Cleaners", "Risky Cache"));
+    assert!(reply.error.unwrap().contains("high risk"));
+    assert_eq!(reply.bytes, 0);
+}
+
+#[test]
+fn test_run_follows_the_clients_settings() {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let request = Request::Run {
+        category: "System Cleaners".to_string(),
+        cleaner: "Settings Probe".to_string(),
+        min_age_secs: None,
+        settings: Box::new(RunSettings {
+            exclude: vec!["/var/cache/fake/keep".to_string()],
+            max_files: 7,
+            deleted: (3, 300),
+            ..RunSettings::default()
+        }),
+    };
+    assert_eq!(ask(&request).bytes, 2);
+}
+
+#[test]
+fn test_overlong_requests_are_refused() {
+    let dir = tempdir().unwrap();
+    let socket = dir.path().join("helper.sock");
+    let listener = UnixListener::bind(&socket).unwrap();
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        helper::serve_connection(stream, &fake_groups()).unwrap();
+    });
+    let mut client = UnixStream::connect(&socket).unwrap();
+    let request = vec![b' '; helper::MAX_REQUEST_LEN as usize + 1];
+    // The helper stops reading at the cap and may close the socket first
+    let _ = client.write_all(&request);
+    let mut reply = String::new();
+    BufReader::new(client).read_line(&mut reply).unwrap();
+    server.join().unwrap();
+    let reply: Reply = serde_json::from_str(&reply).unwrap();
+    assert!(reply.error.unwrap().starts_with("Invalid request"));
+}
+
+#[test]
+fn test_clients_cant_loosen_the_helpers_limit() {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    // No cap and a huge one both leave the helper's own in place
+    for max_files in [0, u64::MAX] {
+        let request = Request::Run {
+            category: "System Cleaners".to_string(),
+            cleaner: "Settings Probe".to_string(),
+            min_age_secs: None,
+            settings: Box::new(RunSettings {
+                max_files,
+                ..RunSettings::default()
+            }),
+        };
+        assert_eq!(ask(&request).bytes, 0);
+        assert_eq!(limits::run_limit().max_files, limits::DEFAULT_MAX_FILES);
+    }
+}
+
+#[test]
+fn test_replay_counts_the_files_unlinked() {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let reply = ask(&run("System Cleaners", "Directory"));
+    assert_eq!(reply.deleted, (12, 4096));
+
+    limits::reset_run();
+    assert_eq!(helper::replay(reply).unwrap(), 4096);
+    assert_eq!(limits::deleted(), (12, 4096));
+    cleaned::take();
+}
@@ -0,0 +1,153 @@
+//! Tests for the run history and run comparison in src/history.rs
+
+use cleansys::history::{CleanerRun, ErrorCategory, History, RunDiff, RunRecord, HISTORY_LIMIT};
+use tempfile::TempDir;
+
+const MB: u64 = 1024 * 1024;
+
+fn run(id: u64, cleaners: Vec<CleanerRun>) -> RunRecord {
+    RunRecord {
+        id,
+        time: "2026-01-01 12:00:00".to_string(),
+        cleaners,
+        quarantine: None,
+    }
+}
+
+#[test]
+fn test_record_assigns_increasing_ids() {
+    let dir = TempDir::new().unwrap();
+    let history = History::at(&dir.path().join("history.jsonl"));
+
+    let first = history.record(RunRecord::new(vec![])).unwrap();
+    let second = history
+        .record(RunRecord::new(vec![CleanerRun::succeeded("Cache", 10)]))
+        .unwrap();
+    assert_eq!((first.id, second.id), (1, 2));
+
+    let runs = history.load().unwrap();
+    assert_eq!(runs.len(), 2);
+    assert_eq!(history.get(2).unwrap(), Some(second));
+    assert_eq!(history.previous(2).unwrap(), Some(first));
+    assert_eq!(history.previous(1).unwrap(), None);
+}
+
+#[test]
+fn test_record_drops_oldest_runs() {
+    let dir = TempDir::new().unwrap();
+    let history = History::at(&dir.path().join("history.jsonl"));
+    for _ in 0..HISTORY_LIMIT + 5 {
+        history.record(RunRecord::new(vec![])).unwrap();
+    }
+
+    let runs = history.load().unwrap();
+    assert_eq!(runs.len(), HISTORY_LIMIT);
+    assert_eq!(runs[0].id, 6);
+    assert_eq!(runs.last().unwrap().id, HISTORY_LIMIT as u64 + 5);
+}
+
+#[test]
+fn test_load_skips_unreadable_lines() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("history.jsonl");
+    let history = History::at(&path);
+    history.record(RunRecord::new(vec![])).unwrap();
+    let mut contents = std::fs::read_to_string(&path).unwrap();
+    contents.push_str("not json\n");
+    std::fs::write(&path, contents).unwrap();
+
+    assert_eq!(history.load().unwrap().len(), 1);
+    assert_eq!(history.record(RunRecord::new(vec![])).unwrap().id, 2);
+}
+
+#[test]
+fn test_error_categories() {
+    assert_eq!(
+        ErrorCategory::of("Cache timed out after 30 min"),
+        ErrorCategory::TimedOut
+    );
+    assert_eq!(
+        ErrorCategory::of("Permission denied (os error 13)"),
+        ErrorCategory::PermissionDenied
+    );
+    assert_eq!(
+        ErrorCategory::of("This operation requires root privileges"),
+        ErrorCategory::RequiresRoot
+    );
+    assert_eq!(ErrorCategory::of("disk on fire"), ErrorCategory::Other);
+}
+
+#[test]
+fn test_diff_reports_notable_changes_biggest_first() {
+    let previous = run(
+        1,
+        vec![
+            CleanerRun::succeeded("Cache", 100 * MB),
+            CleanerRun::succeeded("Trash", 5 * MB),
+            CleanerRun::succeeded("Thumbnails", 10 * MB),
+        ],
+    );
+    let current = run(
+        2,
+        vec![
+            CleanerRun::succeeded("Cache", 20 * MB),
+            CleanerRun::succeeded("Trash", 5 * MB + 10),
+            CleanerRun::succeeded("Logs", 3 * MB),
+        ],
+    );
+
+    let diff = RunDiff::between(&previous, &current);
+    let names: Vec<&str> = diff.changes.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["Cache", "Thumbnails", "Logs"]);
+    assert_eq!(diff.changes[0].change(), -80 * MB as i64);
+    assert_eq!(diff.changes[1].current, None);
+    assert_eq!(diff.changes[2].previous, None);
+    assert_eq!(diff.previous_total, 115 * MB);
+    assert_eq!(diff.current_total, 28 * MB + 10);
+}
+
+#[test]
+fn test_diff_reports_only_new_error_categories() {
+    let previous = run(
+        1,
+        vec![CleanerRun::failed("Apt", "Permission denied (os error 13)")],
+    );
+    let current = run(
+        2,
+        vec![
+            CleanerRun::failed("Apt", "Permission denied (os error 13)"),
+            CleanerRun::failed("Cache", "Cache timed out after 30 min"),
+            CleanerRun::failed("Logs", "Logs timed out after 90 s"),
+        ],
+    );
+
+    let diff = RunDiff::between(&previous, &current);
+    assert_eq!(diff.new_errors, vec![ErrorCategory::TimedOut]);
+}
+
+#[test]
+fn test_summary_lines() {
+    let previous = run(4, vec![CleanerRun::succeeded("Cache", 10 * MB)]);
+    let current = run(
+        5,
+        vec![
+            CleanerRun::succeeded("Cache", 30 * MB),
+            CleanerRun::failed("Logs", "operation timed out"),
+        ],
+    );
+
+    let lines = RunDiff::between(&previous, &current).summary_lines();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].contains("more than run #4"), "{}", lines[0]);
+    assert!(lines[1].contains("▲ Cache"), "{}", lines[1]);
+    assert!(lines[2].contains("New errors: timed out"), "{}", lines[2]);
+
+    let same = RunDiff::between(&previous, &run(6, previous.cleaners.clone()));
+    let lines = same.summary_lines();
+    assert_eq!(lines.len(), 1);
+    assert!(
+        lines[0].contains("about the same as run #4"),
+        "{}",
+        lines[0]
+    );
+}
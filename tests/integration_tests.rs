@@ -38,8 +38,8 @@ fn test_list_command() {
     cmd.assert()
         .success()
         .stdout(predicate::str::contains("AVAILABLE CLEANERS"))
-        .stdout(predicate::str::contains("User cleaners"))
-        .stdout(predicate::str::contains("System cleaners"));
+        .stdout(predicate::str::contains("User Land Cleaners"))
+        .stdout(predicate::str::contains("System Cleaners"));
 }
 
 #[test]
@@ -47,10 +47,13 @@ fn test_list_shows_user_cleaners() {
     let mut cmd = Command::cargo_bin("cleansys").unwrap();
     cmd.arg("list");
 
-    cmd.assert()
+    let assert = cmd
+        .assert()
         .success()
-        .stdout(predicate::str::contains("Browser Caches"))
         .stdout(predicate::str::contains("Application Caches"));
+    if cfg!(feature = "browsers") {
+        assert.stdout(predicate::str::contains("Browser Caches"));
+    }
 }
 
 #[test]
@@ -64,6 +67,131 @@ fn test_list_shows_system_cleaners() {
         .stdout(predicate::str::contains("System Logs"));
 }
 
+#[test]
+#[cfg(feature = "containers")]
+fn test_list_shows_container_cleaners() {
+    let mut cmd = Command::cargo_bin("cleansys").unwrap();
+    cmd.arg("list");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Containers"))
+        .stdout(predicate::str::contains("Docker"))
+        .stdout(predicate::str::contains("Podman"));
+}
+
+#[test]
+#[cfg(feature = "containers")]
+fn test_list_shows_k8s_cleaners() {
+    let mut cmd = Command::cargo_bin("cleansys").unwrap();
+    cmd.arg("list");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Cloud/K8s Tools"))
+        .stdout(predicate::str::contains("Minikube Cache"))
+        .stdout(predicate::str::contains("Helm Cache"));
+}
+
+#[test]
+#[cfg(feature = "dev-caches")]
+fn test_list_shows_android_cleaners() {
+    let mut cmd = Command::cargo_bin("cleansys").unwrap();
+    cmd.arg("list");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Android Development"))
+        .stdout(predicate::str::contains("Unused SDK System Images"));
+}
+
+#[test]
+fn test_list_table_columns() {
+    let mut cmd = Command::cargo_bin("cleansys").unwrap();
+    cmd.arg("list");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("AVAILABLE"))
+        .stdout(predicate::str::contains("RISK"))
+        .stdout(predicate::str::contains("RECLAIMABLE"));
+}
+
+#[test]
+fn test_list_json_output() {
+    let mut cmd = Command::cargo_bin("cleansys").unwrap();
+    cmd.args(["list", "--output", "json"]);
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+
+    let listings: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let listings = listings.as_array().unwrap();
+    assert!(!listings.is_empty());
+    assert_eq!(listings[0]["id"], 1);
+    assert!(listings[0]["requires_root"].is_boolean());
+    assert!(listings[0]["reclaimable"].is_null());
+    assert!(listings
+        .iter()
+        .any(|l| l["name"] == "VM Artifacts" && l["risk"] == "high"));
+    #[cfg(feature = "browsers")]
+    {
+        let browser = listings
+            .iter()
+            .find(|l| l["name"] == "Browser Caches")
+            .unwrap();
+        assert!(browser["tags"]
+            .as_array()
+            .unwrap()
+            .contains(&serde_json::json!("browser")));
+    }
+}
+
+#[test]
+fn test_explain_prints_cleaner_docs() {
+    let mut cmd = Command::cargo_bin("cleansys").unwrap();
+    cmd.args(["explain", "system logs"])
+        .env_remove("CLICOLOR_FORCE");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("SYSTEM LOGS"))
+        .stdout(predicate::str::contains("What is kept"))
+        .stdout(predicate::str::contains("  • Recent journal entries"));
+}
+
+#[test]
+fn test_clean_rejects_unknown_tags() {
+    let mut cmd = Command::cargo_bin("cleansys").unwrap();
+    cmd.args(["clean", "--tags", "dev,shiny"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown tag 'shiny'"));
+}
+
+#[test]
+fn test_piped_output_has_no_ansi_codes() {
+    let mut cmd = Command::cargo_bin("cleansys").unwrap();
+    cmd.arg("list").env_remove("CLICOLOR_FORCE");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\x1b[").not());
+}
+
+#[test]
+fn test_clicolor_force_colors_piped_output() {
+    let mut cmd = Command::cargo_bin("cleansys").unwrap();
+    cmd.arg("list")
+        .env_remove("NO_COLOR")
+        .env("CLICOLOR_FORCE", "1");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\x1b["));
+}
+
 #[test]
 fn test_invalid_command() {
     let mut cmd = Command::cargo_bin("cleansys").unwrap();
@@ -0,0 +1,108 @@
+//! Tests for the removed-items journal grouped by cleaner
+
+use cleansys::app::{App, CleanedItemType, JournalRow};
+
+fn app() -> App {
+    let mut app = App::new();
+    app.detailed_cleaned_items.clear();
+    for (path, size, cleaner) in [
+        ("/home/u/.cache/pip/a", 100, "Package Manager Caches"),
+        ("/home/u/.thumbnails/b.png", 10, "Thumbnail Caches"),
+        ("/home/u/.cache/pip/c", 50, "Package Manager Caches"),
+    ] {
+        app.add_detailed_cleaned_item(
+            path.to_string(),
+            size,
+            "User Land Cleaners".to_string(),
+            cleaner.to_string(),
+            CleanedItemType::File,
+        );
+    }
+    app
+}
+
+fn headers(app: &App) -> Vec<(String, usize, u64, bool)> {
+    app.journal_rows()
+        .into_iter()
+        .filter_map(|row| match row {
+            JournalRow::Header {
+                cleaner,
+                count,
+                size,
+                collapsed,
+                ..
+            } => Some((cleaner.to_string(), count, size, collapsed)),
+            JournalRow::Item(_) => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_journal_groups_items_by_cleaner_in_run_order() {
+    let app = app();
+    assert_eq!(
+        headers(&app),
+        vec![
+            ("Package Manager Caches".to_string(), 2, 150, false),
+            ("Thumbnail Caches".to_string(), 1, 10, false),
+        ]
+    );
+    assert_eq!(app.journal_rows().len(), 5);
+}
+
+#[test]
+fn test_toggle_journal_group_collapses_and_keeps_cursor_on_header() {
+    let mut app = app();
+    // Cursor on the second pip item
+    app.detailed_list_scroll_state.select(Some(2));
+    app.toggle_journal_group();
+
+    assert_eq!(app.journal_rows().len(), 3);
+    assert_eq!(app.detailed_list_scroll_state.selected(), Some(0));
+    assert!(headers(&app)[0].3);
+
+    app.toggle_journal_group();
+    assert_eq!(app.journal_rows().len(), 5);
+}
+
+#[test]
+fn test_journal_search_hides_empty_groups() {
+    let mut app = app();
+    app.search_query = "thumbnails".to_string();
+    assert_eq!(headers(&app).len(), 1);
+    assert_eq!(app.journal_rows().len(), 2);
+}
+
+#[test]
+fn test_open_folder_falls_back_to_the_closest_folder_left() {
+    use cleansys::action::{keymap, Action};
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = App::new();
+    app.detailed_cleaned_items.clear();
+    app.show_progress_screen = true;
+    let removed = dir.path().join("cache/gone/entry.bin");
+    app.add_detailed_cleaned_item(
+        removed.to_string_lossy().to_string(),
+        1,
+        "User Land Cleaners".to_string(),
+        "Application Caches".to_string(),
+        CleanedItemType::File,
+    );
+    let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+    assert_eq!(
+        keymap(&app, key('O')),
+        Some(Action::OpenHighlightedFolder(true))
+    );
+
+    // The header row has no folder of its own
+    app.detailed_list_scroll_state.select(Some(0));
+    app.dispatch(Action::OpenHighlightedFolder(true)).unwrap();
+    assert_eq!(app.take_shell_request(), None);
+
+    app.detailed_list_scroll_state.select(Some(1));
+    app.dispatch(Action::OpenHighlightedFolder(true)).unwrap();
+    assert_eq!(app.take_shell_request(), Some(dir.path().to_path_buf()));
+    assert_eq!(app.take_shell_request(), None);
+}
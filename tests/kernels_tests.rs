@@ -0,0 +1,123 @@
+//! Tests for the kernel package sizes in src/cleaners/kernels.rs
+
+use cleansys::cleaners::kernels::{
+    compare_versions, keep, old_kernels, parse_dpkg_kernels, parse_rpm_kernels, set_keep,
+    KEEP_KERNELS,
+};
+use std::cmp::Ordering;
+
+const DPKG_OUTPUT: &str = "\
+linux-image-6.1.0-9-amd64\t398000\tii \n\
+linux-image-6.1.0-17-amd64\t398500\tii \n\
+linux-image-6.1.0-18-amd64\t399000\tii \n\
+linux-image-6.1.0-7-amd64\t397000\trc \n\
+linux-image-amd64\t1\tii \n\
+linux-headers-6.1.0-9-common\t60000\tii \n\
+linux-headers-6.1.0-9-amd64\t9000\tii \n\
+linux-headers-6.1.0-18-common\t60100\tii \n\
+linux-headers-amd64\t1\tii \n";
+
+#[test]
+fn test_parse_dpkg_kernels() {
+    let packages = parse_dpkg_kernels(DPKG_OUTPUT);
+    // Meta packages and removed ones (rc) are left out
+    assert_eq!(packages.len(), 6);
+    assert_eq!(packages[0].name, "linux-image-6.1.0-9-amd64");
+    assert_eq!(packages[0].version, "6.1.0-9-amd64");
+    assert_eq!(packages[0].size, 398000 * 1024);
+    assert_eq!(packages[3].version, "6.1.0-9");
+}
+
+#[test]
+fn test_compare_kernel_versions() {
+    assert_eq!(
+        compare_versions("6.1.0-18-amd64", "6.1.0-9-amd64"),
+        Ordering::Greater
+    );
+    assert_eq!(compare_versions("5.15.0-91", "6.1.0-9"), Ordering::Less);
+    assert_eq!(compare_versions("6.8.0", "6.8.0"), Ordering::Equal);
+}
+
+#[test]
+fn test_old_kernels_keep_running_and_newest() {
+    let packages = parse_dpkg_kernels(DPKG_OUTPUT);
+    let names = |running| -> Vec<String> {
+        old_kernels(&packages, running, 1)
+            .into_iter()
+            .map(|package| package.name)
+            .collect()
+    };
+
+    // Running the newest: the two older kernels go, with their headers
+    assert_eq!(
+        names("6.1.0-18-amd64"),
+        [
+            "linux-image-6.1.0-9-amd64",
+            "linux-image-6.1.0-17-amd64",
+            "linux-headers-6.1.0-9-common",
+            "linux-headers-6.1.0-9-amd64",
+        ]
+    );
+    // Running an older one keeps it and the newest
+    assert_eq!(names("6.1.0-9-amd64"), ["linux-image-6.1.0-17-amd64"]);
+}
+
+#[test]
+fn test_default_keeps_two_newest() {
+    let packages = parse_dpkg_kernels(DPKG_OUTPUT);
+    let names: Vec<String> = old_kernels(&packages, "6.1.0-18-amd64", KEEP_KERNELS)
+        .into_iter()
+        .map(|package| package.name)
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "linux-image-6.1.0-9-amd64",
+            "linux-headers-6.1.0-9-common",
+            "linux-headers-6.1.0-9-amd64",
+        ]
+    );
+
+    // The config can change it, but never to keeping none
+    set_keep(Some(0));
+    assert_eq!(keep(), 1);
+    set_keep(None);
+    assert_eq!(keep(), KEEP_KERNELS);
+}
+
+const RPM_OUTPUT: &str = "\
+kernel-core\t6.8.9-300.fc40.x86_64\t70000000\n\
+kernel-core\t6.9.7-200.fc40.x86_64\t71000000\n\
+kernel-core\t6.9.9-200.fc40.x86_64\t71500000\n\
+package kernel-modules-extra is not installed\n\
+kernel-modules\t6.8.9-300.fc40.x86_64\t60000000\n\
+kernel-modules\t6.9.7-200.fc40.x86_64\t61000000\n\
+kernel-modules\t6.9.9-200.fc40.x86_64\t61500000\n";
+
+#[test]
+fn test_parse_rpm_kernels() {
+    let packages = parse_rpm_kernels(RPM_OUTPUT);
+    // Packages that aren't installed are left out
+    assert_eq!(packages.len(), 6);
+    assert_eq!(packages[0].name, "kernel-core-6.8.9-300.fc40.x86_64");
+    assert_eq!(packages[0].version, "6.8.9-300.fc40.x86_64");
+    assert_eq!(packages[0].size, 70000000);
+}
+
+#[test]
+fn test_old_rpm_kernels_keep_running_and_newest() {
+    let packages = parse_rpm_kernels(RPM_OUTPUT);
+    let names: Vec<String> = old_kernels(&packages, "6.9.9-200.fc40.x86_64", 1)
+        .into_iter()
+        .map(|package| package.name)
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "kernel-core-6.8.9-300.fc40.x86_64",
+            "kernel-core-6.9.7-200.fc40.x86_64",
+            "kernel-modules-6.8.9-300.fc40.x86_64",
+            "kernel-modules-6.9.7-200.fc40.x86_64",
+        ]
+    );
+}
@@ -0,0 +1,95 @@
+//! Tests for the per-run safety limit in src/limits.rs
+
+use cleansys::deleter;
+use cleansys::limits::{self, LimitReached, RunLimit};
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tempfile::tempdir;
+
+// The limit and run totals are process-wide
+static LOCK: Mutex<()> = Mutex::new(());
+
+fn small_limit() -> RunLimit {
+    RunLimit {
+        max_files: 5,
+        max_bytes: 0,
+    }
+}
+
+#[test]
+fn test_exceeded_by_ignores_disabled_caps() {
+    let limit = RunLimit {
+        max_files: 10,
+        max_bytes: 0,
+    };
+    assert!(!limit.exceeded_by((5, 0), 5, u64::MAX / 2));
+    assert!(limit.exceeded_by((5, 0), 6, 0));
+    assert_eq!(limit.describe(), "10 files");
+    assert_eq!(
+        RunLimit {
+            max_files: 0,
+            max_bytes: 0
+        }
+        .describe(),
+        "no limit"
+    );
+}
+
+#[test]
+fn test_deleter_refuses_without_approval() {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    limits::set_run_limit(small_limit());
+    limits::reset_run();
+
+    let dir = tempdir().unwrap();
+    let tree = dir.path().join("tree");
+    fs::create_dir(&tree).unwrap();
+    for i in 0..10 {
+        fs::write(tree.join(i.to_string()), "x").unwrap();
+    }
+
+    let err = deleter::remove_dir_all(&tree).unwrap_err();
+    assert!(err.to_string().contains("Safety limit"));
+    let reached = err.downcast_ref::<LimitReached>().unwrap();
+    assert!(reached.prompt.contains("Continue?"));
+    assert_eq!(fs::read_dir(&tree).unwrap().count(), 10);
+    assert_eq!(limits::deleted(), (0, 0));
+}
+
+#[test]
+fn test_approval_lasts_for_the_rest_of_the_run() {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    limits::set_run_limit(small_limit());
+    limits::reset_run();
+
+    let asked = Arc::new(AtomicUsize::new(0));
+    let counter = asked.clone();
+    limits::set_approver(move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        true
+    });
+
+    limits::record(5, 0);
+    assert!(limits::check(1, 0).is_ok());
+    assert!(limits::check(100, 0).is_ok());
+    assert_eq!(asked.load(Ordering::SeqCst), 1);
+
+    // A new run asks again
+    limits::reset_run();
+    limits::record(5, 0);
+    assert!(limits::check(1, 0).is_ok());
+    assert_eq!(asked.load(Ordering::SeqCst), 2);
+
+    // Agreeing in the TUI counts the same, without asking the approver
+    limits::reset_run();
+    limits::record(5, 0);
+    limits::approve_run();
+    assert!(limits::check(1, 0).is_ok());
+    assert_eq!(asked.load(Ordering::SeqCst), 2);
+
+    // Leave the refusing default behaviour for the other tests
+    limits::set_approver(|_| false);
+    limits::reset_run();
+    limits::set_run_limit(RunLimit::default());
+}
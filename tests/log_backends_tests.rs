@@ -0,0 +1,216 @@
+//! Tests for the log backends in src/cleaners/log_backends.rs and init
+//! system detection in src/platform.rs
+
+use cleansys::cleaners::log_backends::{
+    archived_journals, is_rotated_log, is_svlogd_archive, is_valid_unit_name, journald_drop_in,
+    namespace_vacuum_args, parse_journal_usage, parse_log_namespace, parse_max_use, rotated_logs,
+    svlogd_archives, JournalVacuum, LogBackend, VacuumMode,
+};
+use cleansys::platform::InitSystem;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_rotated_log_names() {
+    for name in [
+        "messages.0",
+        "messages.0.bz2",
+        "auth.log.3.gz",
+        "security.12.xz",
+        "cron.1.zst",
+        "syslog-20240101",
+        "secure-20240101.gz",
+        "Xorg.0.log.old",
+    ] {
+        assert!(is_rotated_log(name), "{} should be rotated", name);
+    }
+    for name in [
+        "messages",
+        "auth.log",
+        "Xorg.0.log",
+        "dmesg.today",
+        ".0",
+        ".old",
+        "pkg.bz2",
+        "system@0005f1.journal",
+    ] {
+        assert!(!is_rotated_log(name), "{} should be kept", name);
+    }
+}
+
+#[test]
+fn test_rotated_logs_finds_nested_generations() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("messages"), "current").unwrap();
+    fs::write(dir.path().join("messages.0.bz2"), "old").unwrap();
+    fs::create_dir(dir.path().join("nginx")).unwrap();
+    fs::write(dir.path().join("nginx/access.log.1"), "older").unwrap();
+    fs::write(dir.path().join("nginx/access.log"), "current").unwrap();
+
+    assert_eq!(
+        rotated_logs(dir.path()),
+        vec![
+            (dir.path().join("messages.0.bz2"), 3),
+            (dir.path().join("nginx/access.log.1"), 5),
+        ]
+    );
+}
+
+#[test]
+fn test_svlogd_archives_skip_current() {
+    assert!(is_svlogd_archive("@400000006553f0a1.s"));
+    assert!(is_svlogd_archive("@400000006553f0a1.u"));
+    assert!(!is_svlogd_archive("current"));
+    assert!(!is_svlogd_archive("lock"));
+
+    let dir = TempDir::new().unwrap();
+    let everything = dir.path().join("socklog/everything");
+    fs::create_dir_all(&everything).unwrap();
+    fs::write(everything.join("current"), "live").unwrap();
+    fs::write(everything.join("@400000006553f0a1.s"), "old").unwrap();
+
+    assert_eq!(
+        svlogd_archives(dir.path()),
+        vec![(everything.join("@400000006553f0a1.s"), 3)]
+    );
+}
+
+#[test]
+fn test_archived_journals() {
+    let dir = TempDir::new().unwrap();
+    let machine = dir.path().join("0123abcd");
+    fs::create_dir_all(&machine).unwrap();
+    fs::write(machine.join("system.journal"), "active").unwrap();
+    fs::write(machine.join("system@0005f1-0001.journal"), "old").unwrap();
+    fs::write(machine.join("user-1000@0005f1-0002.journal~"), "dirty").unwrap();
+
+    let archived: Vec<_> = archived_journals(dir.path())
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect();
+    assert_eq!(
+        archived,
+        vec![
+            machine.join("system@0005f1-0001.journal"),
+            machine.join("user-1000@0005f1-0002.journal~"),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_journal_usage() {
+    assert_eq!(
+        parse_journal_usage("Archived and active journals take up 1.5G in the file system."),
+        Some(1024 * 1024 * 1024 * 3 / 2)
+    );
+    assert_eq!(parse_journal_usage("No journal files were found."), None);
+}
+
+#[test]
+fn test_init_system_detection() {
+    let root = TempDir::new().unwrap();
+    assert_eq!(InitSystem::detect_at(root.path()), InitSystem::Other);
+
+    fs::create_dir_all(root.path().join("run/openrc")).unwrap();
+    assert_eq!(InitSystem::detect_at(root.path()), InitSystem::OpenRc);
+
+    fs::create_dir_all(root.path().join("etc/runit")).unwrap();
+    assert_eq!(InitSystem::detect_at(root.path()), InitSystem::Runit);
+
+    fs::create_dir_all(root.path().join("run/systemd/system")).unwrap();
+    assert_eq!(InitSystem::detect_at(root.path()), InitSystem::Systemd);
+}
+
+#[test]
+fn test_backends_per_init_system() {
+    assert_eq!(
+        InitSystem::Systemd.log_backends(),
+        vec![LogBackend::Journald, LogBackend::RotatedFiles]
+    );
+    assert_eq!(
+        InitSystem::Runit.log_backends(),
+        vec![LogBackend::Svlogd, LogBackend::RotatedFiles]
+    );
+    assert_eq!(
+        InitSystem::OpenRc.log_backends(),
+        vec![LogBackend::RotatedFiles]
+    );
+}
+
+#[test]
+fn test_parse_max_use_reads_journal_section() {
+    let config = "[Journal]\n#SystemMaxUse=\nStorage=persistent\nSystemMaxUse = 1G\n\n[Other]\nSystemMaxUse=9G\n";
+    assert_eq!(parse_max_use(config).as_deref(), Some("1G"));
+    assert_eq!(
+        parse_max_use("[Journal]\nSystemMaxUse=\n").as_deref(),
+        Some("")
+    );
+    assert_eq!(parse_max_use("[Journal]\nStorage=auto\n"), None);
+}
+
+#[test]
+fn test_drop_in_sets_system_max_use() {
+    let drop_in = journald_drop_in("500M");
+    assert_eq!(parse_max_use(&drop_in).as_deref(), Some("500M"));
+    assert!(drop_in
+        .lines()
+        .all(|l| l.is_empty() || l.starts_with(['#', '[']) || l.contains('=')));
+}
+
+#[test]
+fn test_journal_vacuum_arguments() {
+    let by_time = JournalVacuum::default();
+    assert_eq!(by_time.mode, VacuumMode::Time);
+    assert_eq!(by_time.argument().unwrap(), "--vacuum-time=7d");
+
+    let by_size = by_time.toggled();
+    assert_eq!(by_size.mode, VacuumMode::Size);
+    assert_eq!(by_size.argument().unwrap(), "--vacuum-size=500M");
+    assert_eq!(by_size.toggled(), by_time);
+
+    let invalid = JournalVacuum {
+        size: "lots".to_string(),
+        ..by_size
+    };
+    assert!(invalid.argument().is_err());
+}
+
+#[test]
+fn test_journal_vacuum_reclaimable() {
+    const MB: u64 = 1024 * 1024;
+    let by_time = JournalVacuum::default();
+    assert_eq!(by_time.reclaimable(900 * MB, 600 * MB), 600 * MB);
+
+    // Down to 500M: only the excess, and never the active files
+    let by_size = by_time.toggled();
+    assert_eq!(by_size.reclaimable(900 * MB, 600 * MB), 400 * MB);
+    assert_eq!(by_size.reclaimable(900 * MB, 100 * MB), 100 * MB);
+    assert_eq!(by_size.reclaimable(300 * MB, 200 * MB), 0);
+}
+
+#[test]
+fn test_unit_journal_namespaces() {
+    assert_eq!(
+        parse_log_namespace("LogNamespace=noisy\n"),
+        Some("noisy".to_string())
+    );
+    assert_eq!(parse_log_namespace("LogNamespace=\n"), None);
+    assert_eq!(parse_log_namespace(""), None);
+
+    for unit in ["NetworkManager", "getty@tty1.service", "dev-sda1.device"] {
+        assert!(is_valid_unit_name(unit), "{}", unit);
+    }
+    for unit in ["", "--vacuum-size=0", "a b", "x;rm"] {
+        assert!(!is_valid_unit_name(unit), "{}", unit);
+    }
+
+    let by_time = JournalVacuum::default();
+    assert_eq!(
+        namespace_vacuum_args(&by_time, "noisy").unwrap(),
+        ["--namespace=noisy", "--vacuum-time=7d"]
+    );
+    assert_eq!(
+        namespace_vacuum_args(&by_time.toggled(), "noisy").unwrap(),
+        ["--namespace=noisy", "--vacuum-size=500M"]
+    );
+}
@@ -0,0 +1,54 @@
+//! Tests for the operation log buffer in src/log_buffer.rs
+
+use cleansys::app::App;
+use cleansys::log_buffer::LogBuffer;
+use std::time::{Duration, Instant};
+
+fn lines(buffer: &LogBuffer) -> Vec<&str> {
+    buffer.iter().map(String::as_str).collect()
+}
+
+#[test]
+fn test_keeps_newest_lines() {
+    let mut buffer = LogBuffer::new(3, 0);
+    for i in 0..5 {
+        buffer.push(format!("line {}", i));
+    }
+    assert_eq!(lines(&buffer), vec!["line 2", "line 3", "line 4"]);
+    assert_eq!(buffer.evicted(), 2);
+    assert_eq!(buffer.dropped(), 2);
+
+    buffer.clear();
+    assert!(buffer.is_empty());
+    assert_eq!(buffer.dropped(), 0);
+}
+
+#[test]
+fn test_rate_limit_suppresses_and_reports() {
+    let mut buffer = LogBuffer::new(100, 2);
+    let start = Instant::now();
+    for i in 0..5 {
+        buffer.push_at(format!("burst {}", i), start);
+    }
+    assert_eq!(lines(&buffer), vec!["burst 0", "burst 1"]);
+    assert_eq!(buffer.suppressed(), 3);
+
+    // The next window starts by saying how much was left out
+    buffer.push_at("later", start + Duration::from_secs(1));
+    assert_eq!(
+        lines(&buffer),
+        vec!["burst 0", "burst 1", "⋯ 3 lines suppressed", "later"]
+    );
+    assert_eq!(buffer.dropped(), 3);
+}
+
+#[test]
+fn test_huge_run_stays_bounded() {
+    let mut app = App::new();
+    for i in 0..100_000 {
+        app.log(format!("Removed /tmp/file{}", i));
+    }
+    assert!(app.operation_logs.len() <= app.operation_logs.capacity());
+    // Suppression notes may add a few lines of their own
+    assert!(app.operation_logs.len() as u64 + app.operation_logs.dropped() >= 100_000);
+}
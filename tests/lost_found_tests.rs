@@ -0,0 +1,63 @@
+//! Tests for the lost+found reporter in src/lost_found.rs
+
+use cleansys::lost_found::{analyze, parse_ext_mounts, remove, LostEntry};
+use std::fs;
+use std::path::PathBuf;
+use tempfile::tempdir;
+
+#[test]
+fn test_parse_ext_mounts() {
+    let mounts = "/dev/nvme0n1p2 / ext4 rw,relatime 0 0\n\
+                  proc /proc proc rw 0 0\n\
+                  /dev/sda1 /mnt/old\\040disk ext3 rw 0 0\n\
+                  /dev/nvme0n1p1 /boot/efi vfat rw 0 0\n\
+                  /dev/nvme0n1p2 / ext4 rw,relatime 0 0\n";
+    assert_eq!(
+        parse_ext_mounts(mounts),
+        vec![PathBuf::from("/"), PathBuf::from("/mnt/old disk")]
+    );
+}
+
+#[test]
+fn test_analyze_lists_entries_biggest_first() {
+    let dir = tempdir().unwrap();
+    let mount = dir.path().join("data");
+    let lost = mount.join("lost+found");
+    fs::create_dir_all(lost.join("#2048")).unwrap();
+    fs::write(lost.join("#2048/file"), vec![0u8; 100]).unwrap();
+    fs::write(lost.join("#1234"), vec![0u8; 10_000]).unwrap();
+    let empty = dir.path().join("empty");
+    fs::create_dir_all(empty.join("lost+found")).unwrap();
+    let missing = dir.path().join("missing");
+
+    let found = analyze(&[mount.clone(), empty, missing], |path| path.to_path_buf());
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].mount, mount);
+    assert!(!found[0].unreadable);
+    let names: Vec<PathBuf> = found[0].entries.iter().map(|e| e.path.clone()).collect();
+    assert_eq!(names, vec![lost.join("#1234"), lost.join("#2048")]);
+    assert_eq!(found[0].entries[0].age_days, Some(0));
+    assert!(found[0].entries[1].is_dir);
+    assert!(found[0].total() >= 10_100);
+}
+
+#[test]
+fn test_remove_only_deletes_lost_found_entries() {
+    let dir = tempdir().unwrap();
+    let lost = dir.path().join("lost+found");
+    fs::create_dir_all(lost.join("#2048")).unwrap();
+    fs::write(lost.join("#2048/file"), "recovered").unwrap();
+    let other = dir.path().join("keep");
+    fs::write(&other, "data").unwrap();
+
+    let entry = |path: PathBuf, is_dir: bool| LostEntry {
+        path,
+        size: 9,
+        is_dir,
+        age_days: None,
+    };
+    assert!(remove(&entry(other.clone(), false)).is_err());
+    assert!(other.exists());
+    assert_eq!(remove(&entry(lost.join("#2048"), true)).unwrap(), 9);
+    assert!(!lost.join("#2048").exists());
+}
@@ -0,0 +1,52 @@
+//! Tests for the cleaner documentation markdown in src/markdown.rs
+
+use cleansys::markdown::{inline, parse, to_lines, Block, Emphasis};
+
+const DOC: &str = "\
+# What is removed
+
+Old entries of the
+listed units.
+
+
+- Recent entries, wrapped
+  onto a second line
+- The live files
+";
+
+#[test]
+fn test_parse_joins_wrapped_lines_and_collapses_blanks() {
+    assert_eq!(
+        parse(DOC),
+        [
+            Block::Heading("What is removed".to_string()),
+            Block::Blank,
+            Block::Paragraph("Old entries of the listed units.".to_string()),
+            Block::Blank,
+            Block::Bullet("Recent entries, wrapped onto a second line".to_string()),
+            Block::Bullet("The live files".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_inline_bold_and_code() {
+    assert_eq!(
+        inline("Needs `purge-old-kernels` from **byobu**"),
+        [
+            (Emphasis::Plain, "Needs "),
+            (Emphasis::Code, "purge-old-kernels"),
+            (Emphasis::Plain, " from "),
+            (Emphasis::Bold, "byobu"),
+        ]
+    );
+    // An unclosed marker stays as it is
+    assert_eq!(inline("2 ** 3"), [(Emphasis::Plain, "2 ** 3")]);
+}
+
+#[test]
+fn test_to_lines_renders_bullets() {
+    let lines = to_lines(DOC);
+    assert_eq!(lines.len(), 6);
+    assert_eq!(lines[5].to_string(), "  • The live files");
+}
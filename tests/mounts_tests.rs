@@ -0,0 +1,31 @@
+//! Tests for mount table parsing in src/mounts.rs
+
+use cleansys::mounts::{parse, unescape};
+use std::path::PathBuf;
+
+#[test]
+fn test_unescape_octal_escapes() {
+    assert_eq!(unescape("/media/My\\040Disk"), "/media/My Disk");
+    assert_eq!(
+        unescape("/mnt/tab\\011and\\134slash"),
+        "/mnt/tab\tand\\slash"
+    );
+    assert_eq!(unescape("/mnt/new\\012line"), "/mnt/new\nline");
+    // Anything that isn't three octal digits is left alone
+    assert_eq!(unescape("/mnt/odd\\9x"), "/mnt/odd\\9x");
+    assert_eq!(unescape("/mnt/end\\"), "/mnt/end\\");
+}
+
+#[test]
+fn test_parse_mount_table() {
+    let mounts = "/dev/nvme0n1p2 / ext4 rw,relatime 0 0\n\
+                  /dev/sdb1 /media/Tab\\011Disk vfat rw 0 0\n\
+                  truncated\n";
+    assert_eq!(
+        parse(mounts).collect::<Vec<_>>(),
+        vec![
+            (PathBuf::from("/"), "ext4"),
+            (PathBuf::from("/media/Tab\tDisk"), "vfat"),
+        ]
+    );
+}
@@ -0,0 +1,56 @@
+//! Tests for network filesystem detection in src/netfs.rs
+
+use cleansys::config::Settings;
+use cleansys::netfs::{is_network_type, mount_point, mount_type, NetworkAction};
+use std::path::Path;
+
+const MOUNTS: &str = "\
+/dev/nvme0n1p2 / ext4 rw,relatime 0 0
+nas:/export/home /home nfs4 rw,relatime,vers=4.2 0 0
+/dev/sdb1 /home/sam/Local\\040Disk ext4 rw 0 0
+sam@build:/srv /home/sam/build fuse.sshfs rw,nosuid,nodev 0 0
+/dev/sdc1 /media/usb fuseblk rw 0 0
+";
+
+#[test]
+fn test_network_types() {
+    for fstype in ["nfs", "nfs4", "cifs", "smb3", "fuse.sshfs", "9p"] {
+        assert!(is_network_type(fstype), "{}", fstype);
+    }
+    for fstype in ["ext4", "btrfs", "tmpfs", "fuse", "fuseblk", "fuse.ntfs-3g"] {
+        assert!(!is_network_type(fstype), "{}", fstype);
+    }
+}
+
+#[test]
+fn test_mount_type_uses_the_deepest_mount() {
+    let fstype = |path: &str| mount_type(MOUNTS, Path::new(path));
+    assert_eq!(fstype("/var/cache").as_deref(), Some("ext4"));
+    assert_eq!(fstype("/home/sam/.cache").as_deref(), Some("nfs4"));
+    assert_eq!(
+        fstype("/home/sam/build/target").as_deref(),
+        Some("fuse.sshfs")
+    );
+    // Whole components only, and escaped spaces in mount points
+    assert_eq!(fstype("/home/sam/builds").as_deref(), Some("nfs4"));
+    assert_eq!(fstype("/home/sam/Local Disk/x").as_deref(), Some("ext4"));
+    assert_eq!(mount_type("", Path::new("/home")), None);
+}
+
+#[test]
+fn test_mount_point_of_a_path() {
+    let mount = |path: &str| mount_point(MOUNTS, Path::new(path));
+    assert_eq!(mount("/var/cache/apt"), Some("/".into()));
+    assert_eq!(
+        mount("/home/sam/Local Disk/x"),
+        Some("/home/sam/Local Disk".into())
+    );
+}
+
+#[test]
+fn test_network_filesystems_are_skipped_by_default() {
+    assert_eq!(Settings::default().network_filesystems, NetworkAction::Skip);
+    let settings: Settings = toml::from_str("network_filesystems = \"warn\"").unwrap();
+    assert_eq!(settings.network_filesystems, NetworkAction::Warn);
+    assert!(toml::from_str::<Settings>("network_filesystems = \"always\"").is_err());
+}
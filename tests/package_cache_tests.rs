@@ -0,0 +1,179 @@
+//! Tests for package cache retention in src/cleaners/package_cache.rs
+
+#[cfg(unix)]
+use cleansys::cleaners::package_cache::RpmFrontend;
+use cleansys::cleaners::package_cache::{
+    apt_list_files, prune, rpm_package_dirs, stale_packages, stale_periodic_stamps, PackageManager,
+    PackageRetention, PERIODIC_STAMP_MAX_AGE,
+};
+use std::fs::{self, File};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use tempfile::tempdir;
+
+/// Create a cached package downloaded `age_days` ago
+fn cached(dir: &Path, name: &str, age_days: u64) {
+    let path = dir.join(name);
+    fs::write(&path, [0u8; 100]).unwrap();
+    let modified = SystemTime::now() - Duration::from_secs(age_days * 24 * 60 * 60);
+    File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+}
+
+#[test]
+fn test_package_names() {
+    let pacman = PackageManager::Pacman;
+    assert_eq!(
+        pacman.package_name("linux-firmware-20240510.b9d2bf23-1-any.pkg.tar.zst"),
+        Some("linux-firmware")
+    );
+    assert_eq!(
+        pacman.package_name("linux-6.9.1.arch1-1-x86_64.pkg.tar.zst.sig"),
+        None
+    );
+    assert_eq!(
+        PackageManager::Apt.package_name("libc6_2.39-0ubuntu8_amd64.deb"),
+        Some("libc6")
+    );
+    assert_eq!(
+        PackageManager::Dnf.package_name("kernel-core-6.8.9-300.fc40.x86_64.rpm"),
+        Some("kernel-core")
+    );
+    assert_eq!(PackageManager::Apt.package_name("lock"), None);
+}
+
+#[test]
+fn test_keeps_newest_versions_per_package() {
+    let dir = tempdir().unwrap();
+    cached(dir.path(), "vim_9.1.0-1_amd64.deb", 30);
+    cached(dir.path(), "vim_9.1.1-1_amd64.deb", 10);
+    cached(dir.path(), "vim_9.1.2-1_amd64.deb", 1);
+    cached(dir.path(), "curl_8.5.0-2_amd64.deb", 60);
+
+    let stale = stale_packages(dir.path(), PackageManager::Apt, 2);
+    assert_eq!(stale, vec![(dir.path().join("vim_9.1.0-1_amd64.deb"), 100)]);
+}
+
+#[test]
+fn test_prune_removes_signatures_too() {
+    let dir = tempdir().unwrap();
+    let old = "zstd-1.5.5-1-x86_64.pkg.tar.zst";
+    cached(dir.path(), old, 20);
+    cached(dir.path(), &format!("{}.sig", old), 20);
+    cached(dir.path(), "zstd-1.5.6-1-x86_64.pkg.tar.zst", 2);
+
+    let freed = prune(dir.path(), PackageManager::Pacman, 1).unwrap();
+    assert_eq!(freed, 200);
+    assert!(!dir.path().join(old).exists());
+    assert!(dir.path().join("zstd-1.5.6-1-x86_64.pkg.tar.zst").exists());
+}
+
+#[test]
+fn test_retention_per_manager_overrides_global() {
+    let retention: PackageRetention = toml::from_str("keep = 2\npacman = 3\n").unwrap();
+    assert_eq!(retention.keep_for(PackageManager::Pacman), Some(3));
+    assert_eq!(retention.keep_for(PackageManager::Apt), Some(2));
+    assert_eq!(
+        PackageRetention::default().keep_for(PackageManager::Dnf),
+        None
+    );
+}
+
+#[test]
+#[cfg(unix)]
+fn test_rpm_frontend_follows_symlinks() {
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("dnf5")).unwrap();
+    File::create(dir.path().join("dnf-3")).unwrap();
+    std::os::unix::fs::symlink(dir.path().join("dnf5"), dir.path().join("dnf")).unwrap();
+    std::os::unix::fs::symlink(dir.path().join("dnf-3"), dir.path().join("yum")).unwrap();
+
+    assert_eq!(
+        RpmFrontend::identify(&dir.path().join("dnf")),
+        Some(RpmFrontend::Dnf5)
+    );
+    assert_eq!(
+        RpmFrontend::identify(&dir.path().join("yum")),
+        Some(RpmFrontend::Dnf4)
+    );
+    assert_eq!(RpmFrontend::Dnf5.cache_dir(), "/var/cache/libdnf5");
+}
+
+#[test]
+fn test_rpm_package_dirs_finds_nested_yum_layout() {
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("fedora-a1b2c3/packages")).unwrap();
+    fs::create_dir_all(dir.path().join("x86_64/7/base/packages")).unwrap();
+    fs::create_dir_all(dir.path().join("updates/repodata")).unwrap();
+
+    assert_eq!(
+        rpm_package_dirs(dir.path()),
+        vec![
+            dir.path().join("fedora-a1b2c3/packages"),
+            dir.path().join("x86_64/7/base/packages"),
+        ]
+    );
+}
+
+#[test]
+fn test_apt_list_files_keep_lock() {
+    let dir = tempdir().unwrap();
+    let lists = dir.path();
+    fs::create_dir(lists.join("partial")).unwrap();
+    fs::write(lists.join("lock"), "").unwrap();
+    fs::write(
+        lists.join("deb.debian.org_debian_dists_bookworm_InRelease"),
+        "abc",
+    )
+    .unwrap();
+    fs::write(lists.join("partial/security_Packages.xz"), "ab").unwrap();
+
+    assert_eq!(
+        apt_list_files(lists),
+        vec![
+            (
+                lists.join("deb.debian.org_debian_dists_bookworm_InRelease"),
+                3
+            ),
+            (lists.join("partial/security_Packages.xz"), 2),
+        ]
+    );
+}
+
+#[test]
+fn test_stale_periodic_stamps() {
+    let dir = tempdir().unwrap();
+    for name in ["update-success-stamp", "upgrade-stamp", "README"] {
+        fs::write(dir.path().join(name), "").unwrap();
+    }
+    let day = Duration::from_secs(24 * 60 * 60);
+    let now = SystemTime::now();
+
+    // Fresh stamps stay
+    assert!(stale_periodic_stamps(dir.path(), PERIODIC_STAMP_MAX_AGE, now).is_empty());
+
+    // Seen from far in the future, both stamps are old
+    let later = now + PERIODIC_STAMP_MAX_AGE + day;
+    let stale: Vec<_> = stale_periodic_stamps(dir.path(), PERIODIC_STAMP_MAX_AGE, later)
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect();
+    assert_eq!(
+        stale,
+        vec![
+            dir.path().join("update-success-stamp"),
+            dir.path().join("upgrade-stamp")
+        ]
+    );
+
+    // Stamps dated after "now" are stale too
+    let earlier = now - day;
+    assert_eq!(
+        stale_periodic_stamps(dir.path(), PERIODIC_STAMP_MAX_AGE, earlier).len(),
+        2
+    );
+}
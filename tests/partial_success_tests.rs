@@ -0,0 +1,104 @@
+//! Tests for cleaners that remove some paths but fail on others. Kept apart
+//! from the other runs because failures are recorded globally.
+
+use anyhow::Result;
+use cleansys::action::Action;
+use cleansys::app::{App, CleanerCategory, CleanerItem, Status};
+use cleansys::cleaners::{cleaned, RiskLevel};
+use cleansys::history::CleanerRun;
+use cleansys::script::screen_text;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Removes one file but fails on another
+fn partly_fails(_: bool) -> Result<u64> {
+    cleaned::record_path(Path::new("/tmp/removed"), 1024, false);
+    cleaned::record_failure(Path::new("/tmp/busy"), "Permission denied");
+    Ok(1024)
+}
+
+/// Fails on the only file it tried
+fn only_fails(_: bool) -> Result<u64> {
+    cleaned::record_failure(Path::new("/tmp/busy"), "Permission denied");
+    Ok(0)
+}
+
+fn frees_a_kilobyte(_: bool) -> Result<u64> {
+    Ok(1024)
+}
+
+fn item(name: &str, function: fn(bool) -> Result<u64>) -> CleanerItem {
+    CleanerItem {
+        name: name.to_string(),
+        description: String::new(),
+        docs: None,
+        requires_root: false,
+        risk: RiskLevel::Normal,
+        selected: true,
+        function,
+        scan: None,
+        scan_state: None,
+        bytes_cleaned: 0,
+        bytes_deferred: 0,
+        permission_denied: 0,
+        status: None,
+        parent: None,
+        tags: vec![],
+    }
+}
+
+#[test]
+fn test_partly_failed_cleaners_are_counted_apart() {
+    let mut app = App::new();
+    app.categories = vec![CleanerCategory {
+        name: "User".to_string(),
+        description: String::new(),
+        items: vec![
+            item("Partly", partly_fails),
+            item("Only fails", only_fails),
+            item("Fine", frees_a_kilobyte),
+        ],
+    }];
+    app.is_root = true;
+    app.dispatch(Action::RunSelected).unwrap();
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while app.is_running {
+        assert!(Instant::now() < deadline, "timed out");
+        app.dispatch(Action::Tick).unwrap();
+        screen_text(&mut app).unwrap();
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    let items = &app.categories[0].items;
+    match &items[0].status {
+        Some(Status::PartialSuccess(msg, failures)) => {
+            assert!(msg.ends_with("1 failed"), "{}", msg);
+            assert_eq!(failures[0].0, Path::new("/tmp/busy"));
+        }
+        _ => panic!("not a partial success"),
+    }
+    assert!(matches!(items[1].status, Some(Status::Error(_))));
+    assert!(matches!(items[2].status, Some(Status::Success(_))));
+    assert_eq!((app.partial_count, app.errors_count), (1, 1));
+    assert_eq!(app.total_bytes_cleaned, 2048);
+
+    let run = app.run_record();
+    assert!(run.cleaners[0].is_partial());
+    assert_eq!(run.cleaners[0].failed_paths, 1);
+    assert!(!run.cleaners[2].is_partial());
+}
+
+#[test]
+fn test_failed_paths_are_left_out_of_history_when_none() {
+    let run = CleanerRun::succeeded("Thumbnails", 2048);
+    let json = serde_json::to_string(&run).unwrap();
+    assert!(!json.contains("failed_paths"));
+
+    let partial = run.with_failed_paths(2);
+    let parsed: CleanerRun =
+        serde_json::from_str(&serde_json::to_string(&partial).unwrap()).unwrap();
+    assert_eq!(parsed, partial);
+    assert!(parsed.is_partial());
+    assert!(!CleanerRun::failed("Journal", "Permission denied").is_partial());
+}
@@ -0,0 +1,66 @@
+//! Tests for the XDG locations of cleansys' own files in src/paths.rs
+
+use cleansys::filesystem::{data_dir, ROOT_ENV};
+use cleansys::paths::{self, legacy_moves, migrate};
+use std::fs;
+use std::sync::Mutex;
+use tempfile::tempdir;
+
+// CLEANSYS_ROOT is process-wide, so sandboxed tests must not overlap
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+#[test]
+fn test_own_files_follow_xdg_dirs() {
+    let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let root = tempdir().unwrap();
+    std::env::set_var(ROOT_ENV, root.path());
+
+    let own = paths::all().unwrap();
+    std::env::remove_var(ROOT_ENV);
+
+    let home = root.path().join("home");
+    let path = |name: &str| own.iter().find(|p| p.name == name).unwrap().path.clone();
+    assert_eq!(path("config"), home.join(".config/cleansys/config.toml"));
+    assert_eq!(path("scan cache"), home.join(".cache/cleansys/scan.db"));
+    assert_eq!(
+        path("history"),
+        home.join(".local/state/cleansys/history.jsonl")
+    );
+    assert_eq!(path("audit logs"), home.join(".local/state/cleansys/audit"));
+    assert_eq!(
+        path("quarantine"),
+        home.join(".local/share/cleansys/quarantine")
+    );
+}
+
+#[test]
+fn test_old_history_and_audit_logs_are_moved_to_state_dir() {
+    let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let root = tempdir().unwrap();
+    std::env::set_var(ROOT_ENV, root.path());
+
+    let old_data = data_dir().unwrap().join("cleansys");
+    fs::create_dir_all(old_data.join("audit")).unwrap();
+    fs::write(old_data.join("audit/1.jsonl"), "{}").unwrap();
+    fs::write(old_data.join("history.jsonl"), "old").unwrap();
+    fs::create_dir_all(old_data.join("quarantine/1")).unwrap();
+    let state = paths::state_dir().unwrap();
+    fs::create_dir_all(&state).unwrap();
+    fs::write(state.join("history.jsonl"), "new").unwrap();
+
+    let moves = legacy_moves(&root.path().join("old-cache"), &old_data).unwrap();
+    let moved = migrate(&moves);
+    std::env::remove_var(ROOT_ENV);
+
+    // Quarantine is data both before and after, so it stays put
+    assert_eq!(moved, vec![state.join("audit")]);
+    assert!(state.join("audit/1.jsonl").is_file());
+    assert!(!old_data.join("audit").exists());
+    assert!(old_data.join("quarantine/1").is_dir());
+    // An existing history isn't overwritten by the old one
+    assert_eq!(
+        fs::read_to_string(state.join("history.jsonl")).unwrap(),
+        "new"
+    );
+    assert!(old_data.join("history.jsonl").exists());
+}
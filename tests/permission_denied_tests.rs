@@ -0,0 +1,80 @@
+//! Tests for permission errors counted per cleaner. Kept apart from the
+//! other runs because the count is kept globally.
+
+use anyhow::{Context, Result};
+use cleansys::action::Action;
+use cleansys::app::{App, CleanerCategory, CleanerItem, Status};
+use cleansys::cleaners::{cleaned, RiskLevel};
+use cleansys::script::screen_text;
+use std::io;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn denied(path: &str) -> anyhow::Error {
+    Err::<(), _>(io::Error::from(io::ErrorKind::PermissionDenied))
+        .with_context(|| format!("Failed to remove {:?}", path))
+        .unwrap_err()
+}
+
+/// Removes its own file in /tmp and runs into three of other users'
+fn walks_shared_tmp(_: bool) -> Result<u64> {
+    cleaned::record_path(Path::new("/tmp/mine"), 1024, false);
+    for path in ["/tmp/theirs-1", "/tmp/theirs-2", "/tmp/theirs-3"] {
+        cleaned::record_error(Path::new(path), &denied(path));
+    }
+    Ok(1024)
+}
+
+#[test]
+fn test_permission_errors_are_only_counted() {
+    assert!(cleaned::is_permission_denied(&denied("/tmp/x")));
+    assert!(!cleaned::is_permission_denied(&anyhow::Error::from(
+        io::Error::from(io::ErrorKind::NotFound)
+    )));
+
+    let mut app = App::new();
+    app.categories = vec![CleanerCategory {
+        name: "User".to_string(),
+        description: String::new(),
+        items: vec![CleanerItem {
+            name: "Temporary Files".to_string(),
+            description: String::new(),
+            docs: None,
+            requires_root: false,
+            risk: RiskLevel::Normal,
+            selected: true,
+            function: walks_shared_tmp,
+            scan: None,
+            scan_state: None,
+            bytes_cleaned: 0,
+            bytes_deferred: 0,
+            permission_denied: 0,
+            status: None,
+            parent: None,
+            tags: vec![],
+        }],
+    }];
+    app.is_root = true;
+    app.dispatch(Action::RunSelected).unwrap();
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while app.is_running {
+        assert!(Instant::now() < deadline, "timed out");
+        app.dispatch(Action::Tick).unwrap();
+        screen_text(&mut app).unwrap();
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    let item = &app.categories[0].items[0];
+    assert_eq!(item.permission_denied, 3);
+    match &item.status {
+        Some(Status::Success(msg)) => assert!(msg.ends_with("3 permission denied"), "{}", msg),
+        _ => panic!("not a success"),
+    }
+    let denied_lines = app
+        .operation_logs
+        .iter()
+        .filter(|line| line.contains("permission denied"))
+        .count();
+    assert_eq!(denied_lines, 1);
+}
@@ -0,0 +1,44 @@
+//! Tests for the animated freed-bytes slices in src/pie_chart.rs
+
+use cleansys::pie_chart::FreedSlices;
+
+#[test]
+fn test_freed_slices_grow_toward_reported_bytes() {
+    let mut slices = FreedSlices::default();
+    slices.record("Trash", 1000);
+    assert_eq!(slices.shown(), [("Trash".to_string(), 0)]);
+
+    // Each tick covers part of the way, never overshooting
+    assert!(slices.tick());
+    let first = slices.shown()[0].1;
+    assert!(first > 0 && first < 1000);
+    assert!(slices.tick());
+    assert!(slices.shown()[0].1 > first);
+
+    let mut ticks = 0;
+    while slices.tick() {
+        ticks += 1;
+        assert!(ticks < 100, "animation never settles");
+    }
+    assert_eq!(slices.shown(), [("Trash".to_string(), 1000)]);
+}
+
+#[test]
+fn test_freed_slices_add_up_per_cleaner() {
+    let mut slices = FreedSlices::default();
+    slices.record("Trash", 600);
+    slices.record("Browser Caches", 50);
+    slices.record("Trash", 400);
+    while slices.tick() {}
+    assert_eq!(
+        slices.shown(),
+        [
+            ("Trash".to_string(), 1000),
+            ("Browser Caches".to_string(), 50)
+        ]
+    );
+
+    slices.clear();
+    assert!(slices.shown().is_empty());
+    assert!(!slices.tick());
+}
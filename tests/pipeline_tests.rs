@@ -0,0 +1,113 @@
+//! Tests for the worker pipeline, duplicates finder and quarantine mover
+
+use cleansys::duplicates::{find_duplicates, hash_file};
+use cleansys::pipeline::Pipeline;
+use cleansys::quarantine::Quarantine;
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tempfile::tempdir;
+
+#[test]
+fn test_pipeline_processes_every_item() {
+    let pipeline = Pipeline::new().threads(4).queue_depth(2);
+    let mut results = Vec::new();
+
+    pipeline.run(
+        |tx| {
+            for i in 0..1000u64 {
+                tx.send(i).unwrap();
+            }
+        },
+        |i| (i % 2 == 0).then_some(i * 10),
+        |r| results.push(r),
+    );
+
+    results.sort();
+    assert_eq!(results.len(), 500);
+    assert_eq!(results[..3], [0, 20, 40]);
+}
+
+#[test]
+fn test_pipeline_applies_backpressure() {
+    let pipeline = Pipeline::new().threads(1).queue_depth(1);
+    let produced = AtomicUsize::new(0);
+    let mut max_ahead = 0;
+    let mut consumed = 0;
+
+    pipeline.run(
+        |tx| {
+            for i in 0..50 {
+                produced.fetch_add(1, Ordering::SeqCst);
+                tx.send(i).unwrap();
+            }
+        },
+        Some,
+        |_| {
+            std::thread::sleep(std::time::Duration::from_millis(1));
+            consumed += 1;
+            max_ahead = max_ahead.max(produced.load(Ordering::SeqCst) - consumed);
+        },
+    );
+
+    assert_eq!(consumed, 50);
+    // One item queued per stage plus one in each thread's hands
+    assert!(max_ahead <= 5, "producer ran {} items ahead", max_ahead);
+}
+
+#[test]
+fn test_thread_count_is_at_least_one() {
+    assert_eq!(Pipeline::new().threads(0).thread_count(), 1);
+}
+
+#[test]
+fn test_find_duplicates_groups_identical_files() {
+    let dir = tempdir().unwrap();
+    fs::create_dir(dir.path().join("sub")).unwrap();
+    fs::write(dir.path().join("a.txt"), "same contents").unwrap();
+    fs::write(dir.path().join("sub/b.txt"), "same contents").unwrap();
+    // Same size, different contents
+    fs::write(dir.path().join("c.txt"), "diff contents").unwrap();
+    // A hard link is not a wasted copy
+    fs::hard_link(dir.path().join("c.txt"), dir.path().join("c-link.txt")).unwrap();
+    fs::write(dir.path().join("empty1"), "").unwrap();
+    fs::write(dir.path().join("empty2"), "").unwrap();
+
+    let groups = find_duplicates(&[dir.path().to_path_buf()], &Pipeline::new().threads(2));
+
+    assert_eq!(groups.len(), 1);
+    assert_eq!(
+        groups[0].paths,
+        vec![dir.path().join("a.txt"), dir.path().join("sub/b.txt")]
+    );
+    assert_eq!(groups[0].wasted(), 13);
+    assert_eq!(
+        Some(groups[0].hash.clone()),
+        hash_file(&dir.path().join("a.txt"))
+    );
+}
+
+#[test]
+fn test_quarantine_moves_files_and_writes_manifest() {
+    let dir = tempdir().unwrap();
+    let victim = dir.path().join("data/victim.txt");
+    fs::create_dir_all(victim.parent().unwrap()).unwrap();
+    fs::write(&victim, "bytes").unwrap();
+    let missing = dir.path().join("missing.txt");
+
+    let quarantine = Quarantine::at(dir.path().join("quarantine")).unwrap();
+    let report = quarantine
+        .move_files(vec![victim.clone(), missing.clone()], &Pipeline::new())
+        .unwrap();
+
+    assert!(!victim.exists());
+    assert_eq!(report.moved.len(), 1);
+    assert_eq!(report.bytes_moved(), 5);
+    assert_eq!(report.failed[0].0, missing);
+
+    let stored = quarantine.stored_path(&victim);
+    assert_eq!(fs::read_to_string(&stored).unwrap(), "bytes");
+
+    let manifest = Quarantine::load_manifest(quarantine.dir()).unwrap();
+    assert_eq!(manifest, report.moved);
+    assert_eq!(manifest[0].original, victim);
+}
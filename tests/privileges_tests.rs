@@ -0,0 +1,68 @@
+//! Tests for dropping root under sudo in src/privileges.rs
+
+use cleansys::privileges::{parse_invoker, Invoker};
+
+#[test]
+fn test_parse_invoker_from_sudo_vars() {
+    assert_eq!(
+        parse_invoker(Some("1000"), Some("1000")),
+        Some(Invoker {
+            uid: 1000,
+            gid: 1000
+        })
+    );
+    // root running sudo has nobody to drop to
+    assert_eq!(parse_invoker(Some("0"), Some("0")), None);
+    assert_eq!(parse_invoker(Some("1000"), None), None);
+    assert_eq!(parse_invoker(Some("user"), Some("1000")), None);
+}
+
+/// Changes the credentials of the whole test process, so it is the only
+/// test that drops anything
+#[cfg(target_os = "linux")]
+#[test]
+fn test_root_is_dropped_and_taken_back() {
+    if users::get_effective_uid() != 0 {
+        return;
+    }
+    std::env::set_var("SUDO_UID", "65534");
+    std::env::set_var("SUDO_GID", "65534");
+
+    let invoker = cleansys::privileges::drop_to_invoker().unwrap();
+    assert_eq!(invoker.map(|invoker| invoker.uid), Some(65534));
+    assert_eq!(users::get_effective_uid(), 65534);
+    assert!(cleansys::utils::check_root());
+
+    let inside = cleansys::privileges::as_root(|| {
+        // Nested calls keep root until the outer one ends
+        cleansys::privileges::as_root(|| Ok(()))?;
+        Ok(users::get_effective_uid())
+    })
+    .unwrap();
+    assert_eq!(inside, 0);
+    assert_eq!(users::get_effective_uid(), 65534);
+    assert_eq!(users::get_current_uid(), 65534);
+
+    // Root on another thread is root for this one too, so writes of the
+    // user's files wait for it to be dropped
+    let (elevated, wait) = std::sync::mpsc::channel();
+    let root = std::thread::spawn(move || {
+        cleansys::privileges::as_root(|| {
+            elevated.send(()).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(200));
+            Ok(())
+        })
+    });
+    wait.recv().unwrap();
+    let writing = cleansys::privileges::as_user(users::get_effective_uid);
+    assert_eq!(writing, 65534);
+    root.join().unwrap().unwrap();
+    // Within root on the same thread there is nothing to wait for
+    assert_eq!(
+        cleansys::privileges::as_root(|| Ok(cleansys::privileges::as_user(
+            users::get_effective_uid
+        )))
+        .unwrap(),
+        0
+    );
+}
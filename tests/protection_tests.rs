@@ -0,0 +1,93 @@
+//! Tests for protected path detection in src/protection.rs
+
+use cleansys::deleter;
+use cleansys::protection::{self, check, summarize, Protection, Skipped};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tempfile::tempdir;
+
+/// The minimum age and the skipped paths are global, so tests using them
+/// take turns
+static LOCK: Mutex<()> = Mutex::new(());
+
+fn skipped(path: &str, protection: Protection) -> Skipped {
+    Skipped {
+        path: PathBuf::from(path),
+        protection,
+    }
+}
+
+#[test]
+fn test_writable_paths_are_not_protected() {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("cache.bin");
+    fs::write(&file, "data").unwrap();
+
+    assert_eq!(check(&file), None);
+    assert_eq!(protection::contents_protection(dir.path()), None);
+    deleter::remove_file(&file).unwrap();
+    assert!(!file.exists());
+}
+
+#[test]
+fn test_skipped_error_names_the_reason() {
+    let error = anyhow::Error::from(skipped("/mnt/ro/cache", Protection::ReadOnly));
+    assert_eq!(error.to_string(), "\"/mnt/ro/cache\" skipped: read-only");
+    let found = error
+        .context("Failed to clean cache")
+        .chain()
+        .find_map(|c| c.downcast_ref::<Skipped>())
+        .map(|s| s.protection);
+    assert_eq!(found, Some(Protection::ReadOnly));
+}
+
+#[test]
+fn test_summarize_counts_reasons() {
+    assert_eq!(summarize(&[]), None);
+    let skips = [
+        skipped("/a", Protection::Immutable),
+        skipped("/b", Protection::ReadOnly),
+        skipped("/c", Protection::ReadOnly),
+    ];
+    assert_eq!(
+        summarize(&skips).as_deref(),
+        Some("3 skipped: read-only, immutable")
+    );
+}
+
+#[test]
+fn test_recorded_skips_are_taken_once() {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    protection::take_skipped();
+    protection::record(&PathBuf::from("/ro/file"), Protection::ReadOnly);
+    assert_eq!(
+        protection::take_skipped(),
+        vec![skipped("/ro/file", Protection::ReadOnly)]
+    );
+    assert!(protection::take_skipped().is_empty());
+}
+
+#[test]
+fn test_recent_files_are_kept() {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = tempdir().unwrap();
+    let cache = dir.path().join("cache");
+    fs::create_dir(&cache).unwrap();
+    let file = cache.join("fresh.bin");
+    fs::write(&file, "data").unwrap();
+
+    protection::set_min_age(Some(Duration::from_secs(3600)));
+    assert_eq!(check(&file), Some(Protection::Recent));
+    protection::take_skipped();
+    deleter::remove_dir_all(&cache).unwrap();
+    let skipped = protection::take_skipped();
+    protection::set_min_age(None);
+
+    assert!(file.exists());
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].protection, Protection::Recent);
+    assert_eq!(check(&file), None);
+}
@@ -0,0 +1,204 @@
+//! Tests for restoring quarantined files, src/quarantine.rs
+#![cfg(unix)]
+
+use cleansys::history::{CleanerRun, RunRecord};
+use cleansys::pipeline::Pipeline;
+use cleansys::quarantine::{FileAttributes, Quarantine, QuarantineEntry, QuarantineRetention};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tempfile::tempdir;
+
+fn mode(path: &Path) -> u32 {
+    fs::metadata(path).unwrap().permissions().mode() & 0o7777
+}
+
+/// Set a `user.` extended attribute, false where the filesystem has none
+#[cfg(target_os = "linux")]
+fn set_xattr(path: &Path, name: &str, value: &[u8]) -> bool {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CString::new(path.as_os_str().as_bytes()).unwrap();
+    let name = CString::new(name).unwrap();
+    // SAFETY: both strings are NUL-terminated and value is valid for its length
+    let result = unsafe {
+        libc::setxattr(
+            path.as_ptr(),
+            name.as_ptr(),
+            value.as_ptr().cast(),
+            value.len(),
+            0,
+        )
+    };
+    result == 0
+}
+
+#[test]
+fn test_restore_puts_back_mode_and_xattrs() {
+    let dir = tempdir().unwrap();
+    let victim = dir.path().join("etc/app.conf");
+    fs::create_dir_all(victim.parent().unwrap()).unwrap();
+    fs::write(&victim, "setting = 1").unwrap();
+    fs::set_permissions(&victim, fs::Permissions::from_mode(0o640)).unwrap();
+    #[cfg(target_os = "linux")]
+    let has_xattrs = set_xattr(&victim, "user.cleansys.test", b"kept");
+
+    let quarantine = Quarantine::at(dir.path().join("quarantine")).unwrap();
+    let report = quarantine
+        .move_files(vec![victim.clone()], &Pipeline::new())
+        .unwrap();
+    let attributes = report.moved[0].attributes.clone().unwrap();
+    assert_eq!(attributes.mode, 0o640);
+    #[cfg(target_os = "linux")]
+    if has_xattrs {
+        assert_eq!(attributes.xattrs["user.cleansys.test"], b"kept");
+    }
+
+    // A copy across filesystems would have lost these
+    let stored = quarantine.stored_path(&victim);
+    fs::set_permissions(&stored, fs::Permissions::from_mode(0o600)).unwrap();
+    #[cfg(target_os = "linux")]
+    if has_xattrs {
+        assert!(set_xattr(&stored, "user.cleansys.test", b"lost"));
+    }
+
+    let restored = Quarantine::restore(quarantine.dir()).unwrap();
+    assert_eq!(restored.restored, vec![victim.clone()]);
+    assert!(restored.incomplete.is_empty(), "{:?}", restored.incomplete);
+    assert_eq!(fs::read_to_string(&victim).unwrap(), "setting = 1");
+    assert_eq!(mode(&victim), 0o640);
+    assert_eq!(FileAttributes::capture(&victim), Some(attributes));
+    assert!(Quarantine::load_manifest(quarantine.dir())
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_restore_keeps_files_whose_path_is_taken() {
+    let dir = tempdir().unwrap();
+    let victim = dir.path().join("notes.txt");
+    fs::write(&victim, "old").unwrap();
+    let quarantine = Quarantine::at(dir.path().join("quarantine")).unwrap();
+    quarantine
+        .move_files(vec![victim.clone()], &Pipeline::new())
+        .unwrap();
+    fs::write(&victim, "new").unwrap();
+
+    let report = Quarantine::restore(quarantine.dir()).unwrap();
+    assert!(report.restored.is_empty());
+    assert_eq!(report.failed[0].0, victim);
+    assert_eq!(fs::read_to_string(&victim).unwrap(), "new");
+    let manifest = Quarantine::load_manifest(quarantine.dir()).unwrap();
+    assert_eq!(manifest[0].original, victim);
+    assert!(quarantine.stored_path(&victim).exists());
+}
+
+#[test]
+fn test_manifest_without_attributes_still_loads() {
+    let json = r#"[{"original": "/a", "stored": "/q/files/a", "size": 3}]"#;
+    let entries: Vec<QuarantineEntry> = serde_json::from_str(json).unwrap();
+    assert_eq!(entries[0].attributes, None);
+    assert!(!serde_json::to_string(&entries)
+        .unwrap()
+        .contains("attributes"));
+}
+
+#[test]
+fn test_latest_run_is_the_newest_with_files_left() {
+    let dir = tempdir().unwrap();
+    let base = dir.path().join("quarantine");
+    let quarantine = |run: &str, file: &str| {
+        let victim = dir.path().join(file);
+        fs::write(&victim, file).unwrap();
+        let run = Quarantine::at(base.join(run)).unwrap();
+        run.move_files(vec![victim], &Pipeline::new()).unwrap();
+        run.dir().to_path_buf()
+    };
+    assert_eq!(Quarantine::latest(&base).unwrap(), None);
+
+    let older = quarantine("1700000000", "a.txt");
+    let newest = quarantine("1700000100-2", "b.txt");
+    quarantine("1700000100-1", "c.txt");
+    fs::create_dir_all(base.join("not-a-run")).unwrap();
+    assert_eq!(Quarantine::runs(&base).unwrap()[0], newest);
+    assert_eq!(Quarantine::latest(&base).unwrap(), Some(newest.clone()));
+
+    // Once restored, the run before it is next
+    Quarantine::restore(&newest).unwrap();
+    assert!(dir.path().join("b.txt").exists());
+    let next = Quarantine::latest(&base).unwrap().unwrap();
+    assert_eq!(next, base.join("1700000100-1"));
+    Quarantine::restore(&next).unwrap();
+    assert_eq!(Quarantine::latest(&base).unwrap(), Some(older));
+}
+
+#[test]
+fn test_undo_target_is_the_last_history_run_only() {
+    let dir = tempdir().unwrap();
+    let base = dir.path().join("quarantine");
+    let victim = dir.path().join("a.txt");
+    fs::write(&victim, "a").unwrap();
+    let run = Quarantine::at(base.join("1700000000")).unwrap();
+    run.move_files(vec![victim.clone()], &Pipeline::new())
+        .unwrap();
+
+    let quarantined = RunRecord::new(vec![CleanerRun::succeeded("Duplicate Files", 1)])
+        .with_quarantine("1700000000");
+    let deleted = RunRecord::new(vec![CleanerRun::succeeded("Thumbnails", 1)]);
+    assert!(Quarantine::undo_target(&base, &[]).is_err());
+    assert_eq!(
+        Quarantine::undo_target(&base, std::slice::from_ref(&quarantined)).unwrap(),
+        run.dir()
+    );
+
+    // A later run that deleted its files hides the older quarantine run
+    let error = Quarantine::undo_target(&base, &[quarantined.clone(), deleted])
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("can't be undone"), "{}", error);
+
+    // And once restored, there is nothing left to undo
+    Quarantine::restore(run.dir()).unwrap();
+    assert!(victim.exists());
+    assert!(Quarantine::undo_target(&base, &[quarantined]).is_err());
+}
+
+#[test]
+fn test_purge_removes_old_runs_then_the_oldest_over_the_cap() {
+    let dir = tempdir().unwrap();
+    let base = dir.path().join("quarantine");
+    let now = SystemTime::now();
+    let secs = now.duration_since(UNIX_EPOCH).unwrap().as_secs();
+    let day = 24 * 60 * 60;
+    let run = |name: String, bytes: usize| {
+        let run = base.join(name);
+        fs::create_dir_all(run.join("files")).unwrap();
+        fs::write(run.join("files/data"), vec![0u8; bytes]).unwrap();
+        run
+    };
+    let expired = run(format!("{}", secs - 20 * day), 1000);
+    let old = run(format!("{}", secs - 5 * day), 100_000);
+    let recent = run(format!("{}-1", secs - day), 100_000);
+    let newest = run(format!("{}", secs), 100_000);
+    let (runs, bytes) = Quarantine::usage(&base).unwrap();
+    assert_eq!(runs, 4);
+
+    // Nothing is purged without limits
+    let report = Quarantine::purge(&base, &QuarantineRetention::default(), now).unwrap();
+    assert!(report.purged.is_empty());
+
+    let retention = QuarantineRetention {
+        max_age: Some(Duration::from_secs(14 * day)),
+        max_bytes: Some(250_000),
+    };
+    let report = Quarantine::purge(&base, &retention, now).unwrap();
+    assert_eq!(report.purged, vec![expired, old]);
+    assert!(report.failed.is_empty());
+    assert_eq!(Quarantine::runs(&base).unwrap(), vec![newest, recent]);
+    assert_eq!(
+        Quarantine::usage(&base).unwrap().1,
+        bytes - report.bytes_freed
+    );
+}
@@ -0,0 +1,28 @@
+//! Tests for showing sizes as byte counts, `--bytes` and `B` in the TUI.
+//! The setting is global, so these live apart from other tests reading sizes.
+
+use cleansys::action::Action;
+use cleansys::app::App;
+use cleansys::deferred::describe_total;
+use cleansys::utils::{format_size, format_size_exact, parse_size, raw_sizes};
+
+#[test]
+fn test_toggle_raw_sizes() {
+    assert_eq!(format_size_exact(1495), "1.46 KB (1495 bytes)");
+    assert_eq!(format_size_exact(512), "512 bytes");
+    assert_eq!(
+        describe_total(2048, 1024),
+        "1.00 KB freed now, 1.00 KB after reboot (2048 bytes)"
+    );
+
+    let mut app = App::new();
+    app.dispatch(Action::ToggleRawSizes).unwrap();
+    assert!(raw_sizes());
+    assert_eq!(format_size(1495), "1495 bytes");
+    assert_eq!(format_size_exact(1495), "1495 bytes");
+    assert_eq!(parse_size(&format_size(3 << 30)), Some(3 << 30));
+
+    app.dispatch(Action::ToggleRawSizes).unwrap();
+    assert!(!raw_sizes());
+    assert_eq!(format_size(1495), "1.46 KB");
+}
@@ -0,0 +1,26 @@
+//! Tests for the font and icon cache rebuild step in src/cleaners/rebuild.rs
+
+use cleansys::cleaners::rebuild::{icon_theme_caches, ICON_THEME_CACHE};
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_icon_theme_caches_lists_theme_caches_only() {
+    let dir = tempdir().unwrap();
+    for theme in ["Adwaita", "hicolor", "empty"] {
+        fs::create_dir(dir.path().join(theme)).unwrap();
+    }
+    fs::write(dir.path().join("Adwaita").join(ICON_THEME_CACHE), "cache").unwrap();
+    fs::write(dir.path().join("hicolor").join(ICON_THEME_CACHE), "c").unwrap();
+    fs::write(dir.path().join("hicolor/index.theme"), "[Icon Theme]").unwrap();
+    fs::write(dir.path().join(ICON_THEME_CACHE), "not in a theme").unwrap();
+
+    assert_eq!(
+        icon_theme_caches(dir.path()),
+        vec![
+            (dir.path().join("Adwaita").join(ICON_THEME_CACHE), 5),
+            (dir.path().join("hicolor").join(ICON_THEME_CACHE), 1),
+        ]
+    );
+    assert!(icon_theme_caches(&dir.path().join("missing")).is_empty());
+}
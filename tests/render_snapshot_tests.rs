@@ -0,0 +1,188 @@
+//! Snapshot tests for the TUI rendered by src/render.rs. Each screen is
+//! drawn on a TestBackend at several terminal sizes, so layout changes and
+//! responsive breakpoints show up as snapshot diffs. After an intended
+//! change, review and accept the new snapshots with `cargo insta review`
+//! (or rerun with `INSTA_UPDATE=always`).
+
+use anyhow::Result;
+use cleansys::app::{App, CleanedItemType, CleanerCategory, CleanerItem, Status};
+use cleansys::cleaners::RiskLevel;
+use cleansys::render::ui;
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+use std::time::{Duration, Instant};
+
+/// Terminal sizes around the layout breakpoints: the smallest supported,
+/// compact, standard and spacious
+const SIZES: [(u16, u16); 4] = [(40, 10), (60, 20), (80, 30), (120, 40)];
+
+fn noop(_: bool) -> Result<u64> {
+    Ok(0)
+}
+
+fn item(name: &str, description: &str, requires_root: bool) -> CleanerItem {
+    CleanerItem {
+        name: name.to_string(),
+        description: description.to_string(),
+        docs: None,
+        requires_root,
+        risk: RiskLevel::Normal,
+        selected: false,
+        function: noop,
+        scan: None,
+        scan_state: None,
+        bytes_cleaned: 0,
+        bytes_deferred: 0,
+        permission_denied: 0,
+        status: None,
+        parent: None,
+        tags: vec![],
+    }
+}
+
+/// An app with fixed cleaners and removed items, so nothing from the
+/// machine running the tests ends up in the snapshots
+fn app() -> App {
+    let mut app = App::new();
+    app.categories = vec![
+        CleanerCategory {
+            name: "User Land Cleaners".to_string(),
+            description: "Clean user-specific files and caches".to_string(),
+            items: vec![
+                item(
+                    "Application Caches",
+                    "Clean application caches in ~/.cache",
+                    false,
+                ),
+                item("Thumbnail Caches", "Clean thumbnail caches", false),
+                item("Trash", "Empty trash folder", false),
+            ],
+        },
+        CleanerCategory {
+            name: "System Cleaners".to_string(),
+            description: "Clean system files and caches (requires root)".to_string(),
+            items: vec![item(
+                "System Logs",
+                "Clean old system logs (journal, rotated syslog files, svlogd archives)",
+                true,
+            )],
+        },
+    ];
+    app.categories[0].items[0].selected = true;
+    app.categories[0].items[2].selected = true;
+    app.category_index = 0;
+    app.item_list_state.select(Some(0));
+
+    app.detailed_cleaned_items.clear();
+    app.freed_slices.clear();
+    for (path, size, cleaner) in [
+        (
+            "/home/user/.cache/pip/wheels/abc.whl",
+            15_728_640,
+            "Application Caches",
+        ),
+        (
+            "/home/user/.cache/thumbnails/large/1.png",
+            204_800,
+            "Thumbnail Caches",
+        ),
+        (
+            "/home/user/.local/share/Trash/files/old.iso",
+            734_003_200,
+            "Trash",
+        ),
+    ] {
+        app.add_detailed_cleaned_item(
+            path.to_string(),
+            size,
+            "User Land Cleaners".to_string(),
+            cleaner.to_string(),
+            CleanedItemType::File,
+        );
+    }
+    app.is_root = false;
+    app.update_counters();
+    app
+}
+
+/// The app in the middle of a run: one cleaner done, one running
+fn running_app() -> App {
+    let mut app = app();
+    app.is_running = true;
+    app.show_progress_screen = true;
+    app.categories[0].items[0].status = Some(Status::Success("Freed 15.0 MB".to_string()));
+    app.categories[0].items[2].status = Some(Status::Running);
+    for (name, size) in [
+        ("Application Caches", 15_728_640),
+        ("Thumbnail Caches", 204_800),
+    ] {
+        app.freed_slices.record(name, size);
+    }
+    app
+}
+
+/// The app after a finished run
+fn finished_app() -> App {
+    let mut app = running_app();
+    app.is_running = false;
+    app.categories[0].items[2].status = Some(Status::Success("Freed 700.0 MB".to_string()));
+    app.freed_slices.record("Trash", 734_003_200);
+    app
+}
+
+/// Draw `app` at `width`x`height` and return what the terminal shows
+fn draw(mut app: App, width: u16, height: u16) -> String {
+    app.handle_resize(width, height);
+    // Pin everything that moves with time: finished chart animation, first
+    // spinner frame and a zero-second run
+    while app.freed_slices.tick() {}
+    let now = Instant::now();
+    app.animation_frame = 0;
+    app.last_frame_time = now + Duration::from_secs(60);
+    if app.show_progress_screen {
+        app.operation_start_time = Some(now);
+        app.operation_end_time = Some(now);
+    }
+
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+    terminal.backend().to_string()
+}
+
+fn snapshot_all(screen: &str, make: fn() -> App) {
+    for (width, height) in SIZES {
+        insta::assert_snapshot!(
+            format!("{}_{}x{}", screen, width, height),
+            draw(make(), width, height)
+        );
+    }
+}
+
+#[test]
+fn test_main_screen() {
+    snapshot_all("main", app);
+}
+
+#[test]
+fn test_help_overlay() {
+    snapshot_all("help", || {
+        let mut app = app();
+        app.toggle_help();
+        app
+    });
+}
+
+#[test]
+fn test_progress_screen() {
+    snapshot_all("progress", running_app);
+}
+
+#[test]
+fn test_results_screen() {
+    snapshot_all("results", finished_app);
+}
+
+#[test]
+fn test_too_small_terminal() {
+    insta::assert_snapshot!("too_small_30x8", draw(app(), 30, 8));
+}
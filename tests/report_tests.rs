@@ -0,0 +1,101 @@
+use cleansys::history::{CleanerRun, RunRecord};
+use cleansys::report::{parse_period, Report};
+use std::time::Duration;
+
+fn run(id: u64, time: &str, cleaners: Vec<CleanerRun>) -> RunRecord {
+    RunRecord {
+        id,
+        time: time.to_string(),
+        cleaners,
+        quarantine: None,
+    }
+}
+
+fn history() -> Vec<RunRecord> {
+    vec![
+        run(
+            1,
+            "2026-09-01 02:00:00",
+            vec![CleanerRun::succeeded("Old", 1 << 30)],
+        ),
+        run(
+            2,
+            "2026-10-14 02:00:00",
+            vec![
+                CleanerRun::succeeded("Thumbnail Caches", 4096),
+                CleanerRun::failed("Package Cache", "apt <locked>"),
+            ],
+        ),
+        run(
+            3,
+            "2026-10-15 02:00:00",
+            vec![
+                CleanerRun::succeeded("Thumbnail Caches", 1024).with_failed_paths(2),
+                CleanerRun::succeeded("Journal", 1 << 20).with_deferred(512),
+            ],
+        ),
+    ]
+}
+
+#[test]
+fn test_report_covers_only_the_period() {
+    let report = Report::of(&history(), "2026-10-09 00:00:00", "2026-10-16 00:00:00");
+    assert_eq!(report.runs, 2);
+    assert_eq!(report.bytes, 4096 + 1024 + (1 << 20));
+    assert_eq!(report.deferred, 512);
+    assert_eq!(
+        report.days,
+        [
+            ("2026-10-14".to_string(), 4096),
+            ("2026-10-15".to_string(), 1024 + (1 << 20))
+        ]
+    );
+
+    let names: Vec<&str> = report.cleaners.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, ["Journal", "Thumbnail Caches", "Package Cache"]);
+    let thumbnails = &report.cleaners[1];
+    assert_eq!((thumbnails.runs, thumbnails.partial), (2, 1));
+    assert_eq!(report.failures.len(), 1);
+    assert_eq!(report.failures[0].cleaner, "Package Cache");
+}
+
+#[test]
+fn test_markdown_and_html_list_cleaners_and_errors() {
+    let report = Report::of(&history(), "2026-10-09 00:00:00", "2026-10-16 00:00:00");
+    let markdown = report.markdown();
+    assert!(markdown.starts_with("# CleanSys report 2026-10-09 to 2026-10-16"));
+    assert!(markdown.contains("| Journal | 1 | 1.00 MB | 0 | 0 |"));
+    assert!(markdown.contains("**Package Cache**: apt <locked>"));
+
+    let html = report.html();
+    assert_eq!(html.matches("<svg").count(), 2);
+    assert!(html.contains("apt &lt;locked&gt;"));
+    assert!(!html.contains("<locked>"));
+}
+
+#[test]
+fn test_empty_period_still_reports() {
+    let report = Report::of(&history(), "2026-10-16 00:00:00", "2026-10-17 00:00:00");
+    assert_eq!(report.runs, 0);
+    assert!(report.markdown().contains("0 bytes freed in 0 runs"));
+    assert!(!report.html().contains("<svg"));
+}
+
+#[test]
+fn test_parse_period() {
+    assert_eq!(parse_period("7d"), Some(Duration::from_secs(7 * 86400)));
+    assert_eq!(parse_period("2w"), Some(Duration::from_secs(14 * 86400)));
+    assert_eq!(parse_period("12h"), Some(Duration::from_secs(12 * 3600)));
+    assert_eq!(parse_period("7"), None);
+    assert_eq!(parse_period("d"), None);
+    assert_eq!(parse_period("7 days"), None);
+    assert_eq!(parse_period("99999999999999999w"), None);
+}
+
+#[test]
+fn test_report_since_before_time_began() {
+    // Fits in the seconds of a Duration, but not in a time_t
+    let period = parse_period("153722867280912931m").unwrap();
+    let report = Report::since(&[], period);
+    assert_eq!(report.cleaners.len(), 0);
+}
@@ -0,0 +1,45 @@
+//! Tests for running application detection in src/running.rs
+
+use cleansys::config::Settings;
+use cleansys::running::{names_match, Processes, RunningAction};
+
+#[test]
+fn test_names_match_ignoring_case_and_punctuation() {
+    assert!(names_match("google-chrome", "chrome"));
+    assert!(names_match("Code", "code"));
+    assert!(names_match("JetBrains", "jetbrains-toolbox"));
+    assert!(!names_match("pip", "pipewire"));
+    assert!(!names_match("go-build", "gopls"));
+    assert!(!names_match("", "bash"));
+}
+
+#[test]
+fn test_owner_is_the_matching_process() {
+    let processes = Processes::from_names(vec![
+        "bash".to_string(),
+        "firefox".to_string(),
+        "firefox".to_string(),
+    ]);
+    assert_eq!(processes.owner("firefox"), Some("firefox"));
+    assert_eq!(processes.owner("chromium"), None);
+}
+
+#[test]
+fn test_action_per_cleaner_from_config() {
+    let settings: Settings = toml::from_str(
+        "[running_apps]\naction = \"skip\"\ncleaners = { \"Application Caches\" = \"warn\" }\n",
+    )
+    .unwrap();
+    let running = settings.running_apps;
+    assert_eq!(running.action_for("Browser Caches"), RunningAction::Skip);
+    assert_eq!(
+        running.action_for("Application Caches"),
+        RunningAction::Warn
+    );
+    assert_eq!(
+        Settings::default()
+            .running_apps
+            .action_for("Browser Caches"),
+        RunningAction::Warn
+    );
+}
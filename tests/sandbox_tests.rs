@@ -0,0 +1,482 @@
+//! Run cleaners against a fake root built in a tempdir via CLEANSYS_ROOT
+
+use cleansys::cleaners::cleaned::{self, CleanedEntry, CleanedItemType};
+use cleansys::cleaners::groups;
+use cleansys::exclude;
+use cleansys::filesystem::{
+    home_dir, is_sandboxed, reset_filesystem, root_path, set_filesystem, FileSystem,
+    RealFileSystem, ROOT_ENV,
+};
+use cleansys::limits::{self, RunLimit};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tempfile::{tempdir, TempDir};
+
+// CLEANSYS_ROOT is process-wide, so sandboxed tests must not overlap
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+fn write_file(root: &Path, relative: &str, contents: &str) {
+    let path = root.join(relative);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+}
+
+fn sandbox() -> TempDir {
+    let root = tempdir().unwrap();
+    std::env::set_var(ROOT_ENV, root.path());
+    root
+}
+
+fn run_cleaner(group: &str, name: &str) -> u64 {
+    let cleaner = groups()
+        .into_iter()
+        .find(|g| g.name == group)
+        .and_then(|g| g.cleaners.into_iter().find(|c| c.name == name))
+        .unwrap_or_else(|| panic!("no cleaner {} in {}", name, group));
+    cleaned::take();
+    (cleaner.function)(true).unwrap()
+}
+
+#[test]
+fn test_root_path_rebases_into_sandbox() {
+    let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let root = sandbox();
+
+    assert!(is_sandboxed());
+    assert_eq!(root_path("/var/log"), root.path().join("var/log"));
+    assert_eq!(home_dir().unwrap(), root.path().join("home"));
+
+    std::env::remove_var(ROOT_ENV);
+    assert!(!is_sandboxed());
+    assert_eq!(root_path("/var/log"), Path::new("/var/log"));
+}
+
+#[test]
+fn test_trash_cleaner_empties_only_trash() {
+    let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let root = sandbox();
+    write_file(root.path(), "home/.local/share/Trash/files/old.txt", "x");
+    write_file(
+        root.path(),
+        "home/.local/share/Trash/info/old.txt.trashinfo",
+        "x",
+    );
+    write_file(root.path(), "home/Documents/keep.txt", "keep");
+
+    let freed = run_cleaner("User Land Cleaners", "Trash");
+    std::env::remove_var(ROOT_ENV);
+
+    let trash = root.path().join("home/.local/share/Trash");
+    assert_eq!(
+        cleaned::take(),
+        vec![CleanedEntry {
+            path: trash.clone(),
+            bytes: freed,
+            kind: CleanedItemType::Directory,
+        }]
+    );
+    assert!(trash.join("files").is_dir());
+    assert!(!trash.join("files/old.txt").exists());
+    assert!(!trash.join("info/old.txt.trashinfo").exists());
+    assert!(root.path().join("home/Documents/keep.txt").exists());
+}
+
+#[test]
+fn test_thumbnail_cleaner_leaves_other_caches() {
+    let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let root = sandbox();
+    write_file(root.path(), "home/.cache/thumbnails/normal/a.png", "png");
+    write_file(root.path(), "home/.cache/other-app/data", "keep");
+
+    run_cleaner("User Land Cleaners", "Thumbnail Caches");
+    std::env::remove_var(ROOT_ENV);
+
+    assert!(!root
+        .path()
+        .join("home/.cache/thumbnails/normal/a.png")
+        .exists());
+    assert!(root.path().join("home/.cache/other-app/data").exists());
+}
+
+#[test]
+fn test_font_icon_cleaner_keeps_icons() {
+    let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let root = sandbox();
+    write_file(root.path(), "home/.cache/fontconfig/abc-le64.cache-9", "fc");
+    write_file(root.path(), "home/.cache/icon-cache.kcache", "kde");
+    write_file(
+        root.path(),
+        "home/.local/share/icons/Papirus/icon-theme.cache",
+        "gtk",
+    );
+    write_file(
+        root.path(),
+        "home/.local/share/icons/Papirus/index.theme",
+        "keep",
+    );
+
+    run_cleaner("User Land Cleaners", "Font & Icon Caches");
+    std::env::remove_var(ROOT_ENV);
+
+    let home = root.path().join("home");
+    assert!(home.join(".cache/fontconfig").is_dir());
+    assert!(!home.join(".cache/fontconfig/abc-le64.cache-9").exists());
+    assert!(!home.join(".cache/icon-cache.kcache").exists());
+    assert!(!home
+        .join(".local/share/icons/Papirus/icon-theme.cache")
+        .exists());
+    assert!(home.join(".local/share/icons/Papirus/index.theme").exists());
+}
+
+#[test]
+fn test_cache_cleaners_count_only_what_they_removed() {
+    let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let root = sandbox();
+    write_file(root.path(), "home/.cache/fontconfig/abc-le64.cache-9", "fc");
+    write_file(root.path(), "home/.cache/fontconfig/big.important", "kept");
+
+    exclude::set_patterns(vec!["*.important".to_string()]);
+    run_cleaner("User Land Cleaners", "Font & Icon Caches");
+    exclude::set_patterns(Vec::new());
+    std::env::remove_var(ROOT_ENV);
+
+    let fontconfig = root.path().join("home/.cache/fontconfig");
+    assert!(fontconfig.join("big.important").exists());
+    let entries = cleaned::take();
+    let entry = entries
+        .iter()
+        .find(|entry| entry.path == fontconfig)
+        .unwrap();
+    assert_eq!(entry.bytes, 2);
+}
+
+/// The real filesystem, except that directories named `busy` can't be removed
+struct BusyDirs;
+
+impl FileSystem for BusyDirs {
+    fn exists(&self, path: &Path) -> bool {
+        RealFileSystem.exists(path)
+    }
+
+    fn size(&self, path: &Path) -> anyhow::Result<u64> {
+        RealFileSystem.size(path)
+    }
+
+    fn read_dir(&self, path: &Path) -> anyhow::Result<Vec<PathBuf>> {
+        RealFileSystem.read_dir(path)
+    }
+
+    fn remove_file(&self, path: &Path) -> anyhow::Result<u64> {
+        RealFileSystem.remove_file(path)
+    }
+
+    fn remove_file_below(&self, root: &Path, path: &Path) -> anyhow::Result<u64> {
+        RealFileSystem.remove_file_below(root, path)
+    }
+
+    fn remove_dir_all(&self, path: &Path) -> anyhow::Result<u64> {
+        if path.ends_with("busy") {
+            anyhow::bail!("Device or resource busy");
+        }
+        RealFileSystem.remove_dir_all(path)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> anyhow::Result<()> {
+        RealFileSystem.create_dir_all(path)
+    }
+}
+
+#[test]
+fn test_app_caches_that_fail_to_go_are_reported_not_counted() {
+    let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let root = sandbox();
+    write_file(root.path(), "home/.cache/busy/data", "1234");
+    write_file(root.path(), "home/.cache/idle/data", "12");
+
+    cleaned::take_failures();
+    set_filesystem(&BusyDirs);
+    let freed = run_cleaner("User Land Cleaners", "Application Caches");
+    reset_filesystem();
+    std::env::remove_var(ROOT_ENV);
+
+    let cache = root.path().join("home/.cache");
+    assert_eq!(freed, 2);
+    assert!(cache.join("busy/data").exists());
+    assert!(!cache.join("idle").exists());
+    let entries = cleaned::take();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].path, cache.join("idle"));
+    let failures = cleaned::take_failures();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].0, cache.join("busy"));
+    assert!(failures[0].1.contains("busy"));
+}
+
+#[test]
+fn test_crash_report_cleaner_runs_without_root() {
+    let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let root = sandbox();
+    write_file(root.path(), "var/crash/app.crash", "crash");
+    write_file(root.path(), "var/lib/systemd/coredump/core.app.1", "core");
+    write_file(root.path(), "var/log/syslog", "keep");
+    let big = "x".repeat(20_000);
+    write_file(root.path(), "srv/app/core", &big);
+    write_file(root.path(), "usr/lib/python3/core.py", &big);
+
+    run_cleaner("System Cleaners", "Crash Reports");
+    std::env::remove_var(ROOT_ENV);
+
+    // Each removed file is recorded, never the directory holding it
+    let mut removed: Vec<_> = cleaned::take().into_iter().map(|e| e.path).collect();
+    removed.sort();
+    assert_eq!(
+        removed,
+        [
+            root.path().join("srv/app/core"),
+            root.path().join("var/crash/app.crash"),
+            root.path().join("var/lib/systemd/coredump/core.app.1"),
+        ]
+    );
+    // Named like a core dump, but source code
+    assert!(root.path().join("usr/lib/python3/core.py").exists());
+    assert!(root.path().join("var/crash").is_dir());
+    assert!(!root.path().join("var/crash/app.crash").exists());
+    assert!(!root
+        .path()
+        .join("var/lib/systemd/coredump/core.app.1")
+        .exists());
+    assert!(root.path().join("var/log/syslog").exists());
+}
+
+#[test]
+fn test_crash_report_cleaner_quotes_root_with_spaces() {
+    let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = tempdir().unwrap();
+    let root = dir.path().join("my root");
+    write_file(&root, "var/crash/app.crash", "crash");
+    write_file(dir.path(), "my/keep.txt", "keep");
+    std::env::set_var(ROOT_ENV, &root);
+
+    run_cleaner("System Cleaners", "Crash Reports");
+    std::env::remove_var(ROOT_ENV);
+
+    assert!(!root.join("var/crash/app.crash").exists());
+    assert!(dir.path().join("my/keep.txt").exists());
+}
+
+#[test]
+#[cfg(unix)]
+fn test_temp_cleaner_keeps_paths_named_in_file_names() {
+    use std::fs::{File, FileTimes};
+    use std::time::{Duration, SystemTime};
+
+    let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = tempdir().unwrap();
+    let root = dir.path().join("root");
+    let victim = dir.path().join("victim");
+    fs::write(&victim, "keep").unwrap();
+    // Printed one path per line, the part after the newline would read as
+    // an entry of its own naming the victim
+    let litter = root
+        .join("tmp/x\n4\t")
+        .join(victim.strip_prefix("/").unwrap());
+    fs::create_dir_all(litter.parent().unwrap()).unwrap();
+    fs::write(&litter, "old").unwrap();
+    let long_ago = SystemTime::now() - Duration::from_secs(7 * 24 * 60 * 60);
+    File::options()
+        .write(true)
+        .open(&litter)
+        .unwrap()
+        .set_times(FileTimes::new().set_accessed(long_ago))
+        .unwrap();
+    std::env::set_var(ROOT_ENV, &root);
+
+    run_cleaner("System Cleaners", "Temporary Files");
+    std::env::remove_var(ROOT_ENV);
+
+    assert!(victim.exists());
+    assert!(!litter.exists());
+    let removed: Vec<_> = cleaned::take().into_iter().map(|e| e.path).collect();
+    assert_eq!(removed, [litter]);
+}
+
+#[test]
+fn test_system_cleaners_stop_at_the_run_limit() {
+    let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let root = sandbox();
+    for name in ["a", "b", "c"] {
+        write_file(root.path(), &format!("var/crash/{}.crash", name), "crash");
+    }
+    limits::set_run_limit(RunLimit {
+        max_files: 1,
+        max_bytes: 0,
+    });
+    limits::reset_run();
+
+    run_cleaner("System Cleaners", "Crash Reports");
+    std::env::remove_var(ROOT_ENV);
+    let refused = limits::take_refused();
+    limits::set_run_limit(RunLimit::default());
+    limits::reset_run();
+
+    assert!(refused.is_some());
+    assert_eq!(cleaned::take().len(), 1);
+    assert_eq!(
+        fs::read_dir(root.path().join("var/crash")).unwrap().count(),
+        2
+    );
+}
+
+#[test]
+fn test_vm_artifacts_keeps_saves_of_defined_domains() {
+    let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let root = sandbox();
+    write_file(root.path(), "etc/libvirt/qemu/dev.xml", "<domain/>");
+    write_file(root.path(), "var/lib/libvirt/qemu/save/dev.save", "keep");
+    write_file(root.path(), "var/lib/libvirt/qemu/save/gone.save", "stale");
+    write_file(root.path(), "var/lib/libvirt/qemu/dump/gone.core", "dump");
+
+    run_cleaner("System Cleaners", "VM Artifacts");
+    std::env::remove_var(ROOT_ENV);
+
+    let qemu = root.path().join("var/lib/libvirt/qemu");
+    assert!(qemu.join("save/dev.save").exists());
+    assert!(!qemu.join("save/gone.save").exists());
+    assert!(!qemu.join("dump/gone.core").exists());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_system_logs_on_runit_removes_svlogd_archives() {
+    let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let root = sandbox();
+    fs::create_dir_all(root.path().join("etc/runit")).unwrap();
+    write_file(root.path(), "var/log/socklog/everything/current", "live");
+    write_file(root.path(), "var/log/socklog/everything/@4000.s", "old");
+    write_file(root.path(), "var/log/messages", "live");
+    write_file(root.path(), "var/log/messages.1.gz", "old");
+
+    let freed = run_cleaner("System Cleaners", "System Logs");
+    std::env::remove_var(ROOT_ENV);
+
+    let log = root.path().join("var/log");
+    assert_eq!(freed, 6);
+    let mut removed: Vec<_> = cleaned::take()
+        .into_iter()
+        .map(|entry| (entry.path, entry.bytes, entry.kind))
+        .collect();
+    removed.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        removed,
+        vec![
+            (log.join("messages.1.gz"), 3, CleanedItemType::Log),
+            (
+                log.join("socklog/everything/@4000.s"),
+                3,
+                CleanedItemType::Log
+            ),
+        ]
+    );
+    assert!(log.join("socklog/everything/current").exists());
+    assert!(!log.join("socklog/everything/@4000.s").exists());
+    assert!(log.join("messages").exists());
+    assert!(!log.join("messages.1.gz").exists());
+}
+
+#[test]
+fn test_journal_limit_drop_in_overrides_main_config() {
+    use cleansys::cleaners::log_backends::{configured_journal_limit, write_journal_limit};
+
+    let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let root = sandbox();
+    assert_eq!(configured_journal_limit(), None);
+
+    write_file(
+        root.path(),
+        "etc/systemd/journald.conf",
+        "[Journal]\nSystemMaxUse=4G\n",
+    );
+    assert_eq!(configured_journal_limit().as_deref(), Some("4G"));
+
+    let path = write_journal_limit("500M").unwrap();
+    assert!(write_journal_limit("lots").is_err());
+    let limit = configured_journal_limit();
+    std::env::remove_var(ROOT_ENV);
+
+    assert!(path.starts_with(root.path()));
+    assert_eq!(limit.as_deref(), Some("500M"));
+}
+
+#[test]
+fn test_app_caches_of_running_applications_are_skipped() {
+    use cleansys::running::{set_running_apps, RunningAction, RunningApps};
+
+    let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let root = sandbox();
+    write_file(root.path(), "proc/42/comm", "spotify\n");
+    write_file(root.path(), "proc/43/comm", "pbmail\n");
+    write_file(
+        root.path(),
+        "usr/share/applications/mail-client.desktop",
+        "[Desktop Entry]\nExec=/opt/pb/pbmail %u\nStartupWMClass=Postbox\n",
+    );
+    write_file(root.path(), "home/.cache/spotify/data", "1234");
+    write_file(root.path(), "home/.cache/postbox/data", "1234");
+    write_file(root.path(), "home/.cache/closed-app/data", "1234");
+
+    set_running_apps(RunningApps {
+        action: RunningAction::Skip,
+        ..RunningApps::default()
+    });
+    let freed = run_cleaner("User Land Cleaners", "Application Caches");
+    set_running_apps(RunningApps::default());
+    std::env::remove_var(ROOT_ENV);
+
+    let cache = root.path().join("home/.cache");
+    assert!(freed > 0);
+    assert!(cache.join("spotify/data").exists());
+    assert!(cache.join("postbox/data").exists());
+    assert!(!cache.join("closed-app").exists());
+}
+
+#[test]
+#[cfg(feature = "server")]
+fn test_mail_and_spool_cleaners_keep_crontabs_and_mailboxes() {
+    use std::fs::File;
+    use std::time::{Duration, SystemTime};
+
+    let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let root = sandbox();
+    let age = |relative: &str| {
+        File::options()
+            .write(true)
+            .open(root.path().join(relative))
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(60 * 24 * 60 * 60))
+            .unwrap();
+    };
+    for relative in [
+        "var/spool/clientmqueue/dfA123",
+        "var/spool/cron/crontabs/root",
+        "home/alice/dead.letter",
+    ] {
+        write_file(root.path(), relative, "data");
+        age(relative);
+    }
+    write_file(root.path(), "var/spool/clientmqueue/dfB456", "fresh");
+    write_file(root.path(), "var/spool/mail/alice", "mail");
+
+    let cron = run_cleaner("Mail & Spool", "Stale Cron Output");
+    let letters = run_cleaner("Mail & Spool", "Dead Letters");
+    let mailboxes = run_cleaner("Mail & Spool", "Oversized Mailboxes");
+    std::env::remove_var(ROOT_ENV);
+
+    let spool = root.path().join("var/spool");
+    assert_eq!((cron, letters, mailboxes), (4, 4, 0));
+    assert!(!spool.join("clientmqueue/dfA123").exists());
+    assert!(spool.join("clientmqueue/dfB456").exists());
+    assert!(spool.join("cron/crontabs/root").exists());
+    assert!(spool.join("mail/alice").exists());
+    assert!(!root.path().join("home/alice/dead.letter").exists());
+}
@@ -0,0 +1,62 @@
+//! Tests for log output sanitization in src/sanitize.rs
+
+use cleansys::app::App;
+use cleansys::sanitize::{
+    clean_line, log_lines, split_line, strip_ansi, MAX_LOG_WIDTH, MAX_SPLIT_LINES,
+};
+
+#[test]
+fn test_strip_ansi() {
+    assert_eq!(
+        strip_ansi("\x1b[1;32mReading\x1b[0m lists"),
+        "Reading lists"
+    );
+    assert_eq!(strip_ansi("\x1b[2K\x1b[1Gdone"), "done");
+    // Window title and hyperlink escapes
+    assert_eq!(strip_ansi("\x1b]0;apt\x07ok"), "ok");
+    assert_eq!(
+        strip_ansi("\x1b]8;;file:///tmp\x1b\\/tmp\x1b]8;;\x1b\\"),
+        "/tmp"
+    );
+    assert_eq!(strip_ansi("plain"), "plain");
+}
+
+#[test]
+fn test_clean_line() {
+    // Progress output keeps only what the terminal would end up showing
+    assert_eq!(clean_line("  10%\r  55%\r 100%\r"), " 100%");
+    assert_eq!(clean_line("a\tb\x08c\x00"), "a bc");
+    assert_eq!(clean_line("Größe: 1 GB"), "Größe: 1 GB");
+}
+
+#[test]
+fn test_split_long_lines() {
+    assert_eq!(split_line("abcdefg", 3), vec!["abc", "def", "g"]);
+    assert_eq!(split_line("", 3), vec![""]);
+
+    let long = "x".repeat(10 * 3);
+    let pieces = split_line(&long, 3);
+    assert_eq!(pieces.len(), MAX_SPLIT_LINES);
+    assert_eq!(pieces.last().unwrap(), "xx…");
+}
+
+#[test]
+fn test_log_lines() {
+    let lines = log_lines("first\n\x1b[31msecond\x1b[0m\n");
+    assert_eq!(lines, vec!["first", "second"]);
+
+    let long = "y".repeat(MAX_LOG_WIDTH + 1);
+    let lines = log_lines(&long);
+    assert_eq!(lines.len(), 2);
+    assert!(lines.iter().all(|l| l.chars().count() <= MAX_LOG_WIDTH));
+}
+
+#[test]
+fn test_app_log_is_sanitized() {
+    let mut app = App::new();
+    app.log("\x1b[33m⚠ warning\x1b[0m\r\nnext");
+    assert_eq!(
+        app.operation_logs.iter().collect::<Vec<_>>(),
+        vec!["⚠ warning", "next"]
+    );
+}
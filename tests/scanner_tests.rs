@@ -0,0 +1,209 @@
+//! Tests for the native size walker, scan cache and benchmarks in src/scanner.rs
+
+use cleansys::scanner::{self, format_bench_table, native_size, CleanerBench, ScanCache};
+#[cfg(unix)]
+use cleansys::scanner::{du_size, Reclaimable};
+use std::fs;
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::MetadataExt;
+#[cfg(unix)]
+use std::path::Path;
+use tempfile::tempdir;
+
+#[test]
+#[cfg(unix)]
+fn test_native_size_matches_du() {
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("a/b")).unwrap();
+    fs::write(dir.path().join("a/one.txt"), vec![0u8; 1000]).unwrap();
+    fs::write(dir.path().join("a/b/two.txt"), vec![0u8; 4096]).unwrap();
+    fs::hard_link(dir.path().join("a/one.txt"), dir.path().join("link.txt")).unwrap();
+    std::os::unix::fs::symlink("/usr", dir.path().join("usr")).unwrap();
+
+    assert_eq!(native_size(dir.path()), du_size(dir.path()).unwrap());
+}
+
+#[test]
+fn test_native_size_missing_path_is_zero() {
+    assert_eq!(
+        native_size(std::path::Path::new("/nonexistent/cleansys")),
+        0
+    );
+}
+
+#[test]
+fn test_bench_table_flags_mismatches() {
+    let results = vec![CleanerBench {
+        category: "User Land Cleaners",
+        name: "Trash",
+        native_ms: 1.5,
+        du_ms: 4.0,
+        native_bytes: 1024,
+        du_bytes: 2048,
+    }];
+
+    let table = format_bench_table(&results);
+    assert!(table.contains("NATIVE"));
+    assert!(table.contains("Trash"));
+    assert!(table.contains("(du: 2.00 KB)"));
+    assert!(table.lines().last().unwrap().starts_with("TOTAL"));
+}
+
+#[test]
+#[cfg(feature = "containers")]
+fn test_bench_scan_json_in_sandbox() {
+    let root = tempdir().unwrap();
+    let helm = root.path().join("home/.cache/helm/repository");
+    fs::create_dir_all(&helm).unwrap();
+    fs::write(helm.join("index.yaml"), "entries: {}").unwrap();
+
+    let output = assert_cmd::Command::cargo_bin("cleansys")
+        .unwrap()
+        .env("CLEANSYS_ROOT", root.path())
+        .args(["bench-scan", "--output", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let results: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let helm = results
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|r| r["name"] == "Helm Cache")
+        .expect("Helm cache benchmarked");
+    assert_eq!(helm["native_bytes"], helm["du_bytes"]);
+    assert!(helm["native_bytes"].as_u64().unwrap() > 0);
+    assert!(helm["native_ms"].is_number());
+}
+
+#[test]
+fn test_scan_cache_rereads_only_changed_directories() {
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("sub")).unwrap();
+    fs::write(dir.path().join("sub/a.txt"), vec![0u8; 100]).unwrap();
+
+    let mut cache = ScanCache::default();
+    let first = cache.size(dir.path());
+    assert_eq!(first, native_size(dir.path()));
+    assert_eq!(cache.len(), 2);
+
+    // Growing a file in place leaves the directory mtime alone, so its
+    // cached entries are reused, but the file is still measured again
+    fs::OpenOptions::new()
+        .append(true)
+        .open(dir.path().join("sub/a.txt"))
+        .unwrap()
+        .write_all(&[0u8; 50])
+        .unwrap();
+    assert_eq!(cache.size(dir.path()), first + 50);
+    assert_eq!(cache.len(), 2);
+
+    // Adding a file changes the mtime, so the directory is read again
+    fs::write(dir.path().join("sub/b.txt"), vec![0u8; 10]).unwrap();
+    assert_eq!(cache.size(dir.path()), native_size(dir.path()));
+}
+
+#[test]
+fn test_scan_cache_round_trip() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.txt"), "data").unwrap();
+
+    let mut cache = ScanCache::default();
+    let size = cache.size(dir.path());
+
+    let cache_dir = tempdir().unwrap();
+    let path = cache_dir.path().join("cleansys/scan.db");
+    cache.save_to(&path).unwrap();
+    let mut loaded = ScanCache::load_from(&path).unwrap();
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded.size(dir.path()), size);
+}
+
+#[test]
+fn test_cancelled_scans_stop_walking() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("file"), vec![0u8; 1000]).unwrap();
+    let full = native_size(dir.path());
+    assert!(full >= 1000);
+
+    // Outside of a cancellable scan nothing is ever cancelled
+    scanner::cancel_scans();
+    assert!(!scanner::is_cancelled());
+    assert_eq!(native_size(dir.path()), full);
+
+    let cancelled = scanner::cancellable(|| {
+        scanner::cancel_scans();
+        (scanner::is_cancelled(), native_size(dir.path()))
+    });
+    assert_eq!(cancelled, (true, 0));
+    assert!(!scanner::is_cancelled());
+}
+
+#[test]
+#[cfg(unix)]
+fn test_reclaimable_counts_hard_links_once_all_are_removed() {
+    let dir = tempdir().unwrap();
+    let cache = dir.path().join("cache");
+    let store = dir.path().join("store");
+    fs::create_dir_all(&cache).unwrap();
+    fs::create_dir_all(&store).unwrap();
+    fs::write(cache.join("own"), vec![1u8; 1000]).unwrap();
+    fs::write(store.join("shared"), vec![1u8; 40_000]).unwrap();
+    fs::hard_link(store.join("shared"), cache.join("linked")).unwrap();
+    let blocks = |path: &Path| fs::metadata(path).unwrap().blocks() * 512;
+
+    // The linked file is still kept alive by the store
+    let mut reclaimable = Reclaimable::new();
+    let usage = reclaimable.add(&cache);
+    assert_eq!(
+        usage.reclaimable,
+        blocks(&cache) + blocks(&cache.join("own"))
+    );
+    assert_eq!(
+        usage.on_disk,
+        usage.reclaimable + blocks(&store.join("shared"))
+    );
+    // Removing the store as well frees it, without counting it on disk twice
+    let usage = reclaimable.add(&store);
+    assert_eq!(
+        usage.reclaimable,
+        blocks(&store) + blocks(&store.join("shared"))
+    );
+    assert_eq!(usage.on_disk, blocks(&store));
+    assert_eq!(
+        Reclaimable::new().add(&dir.path().join("missing")).on_disk,
+        0
+    );
+}
+
+#[test]
+#[cfg(unix)]
+fn test_sparse_files_count_their_blocks() {
+    let dir = tempdir().unwrap();
+    let image = dir.path().join("disk.img");
+    let file = fs::File::create(&image).unwrap();
+    file.set_len(100 * 1024 * 1024).unwrap();
+    drop(file);
+
+    let usage = Reclaimable::new().add(&image);
+    assert_eq!(native_size(&image), 100 * 1024 * 1024);
+    assert_eq!(usage.on_disk, fs::metadata(&image).unwrap().blocks() * 512);
+    assert!(usage.reclaimable < 1024 * 1024);
+}
+
+#[test]
+#[cfg(unix)]
+fn test_reclaimable_measures_apparent_size_in_the_same_walk() {
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("sub")).unwrap();
+    fs::write(dir.path().join("sub/a.txt"), vec![0u8; 5000]).unwrap();
+    fs::hard_link(dir.path().join("sub/a.txt"), dir.path().join("b.txt")).unwrap();
+
+    let (size, usage) = Reclaimable::new().measure(dir.path());
+    assert_eq!(size, native_size(dir.path()));
+    assert_eq!(usage, Reclaimable::new().add(dir.path()));
+    // Both links are inside, so the file is freed
+    assert!(usage.reclaimable >= 4096);
+}
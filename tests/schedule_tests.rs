@@ -0,0 +1,71 @@
+//! Tests for scheduled run parsing in src/schedule.rs
+
+use cleansys::schedule::{format_countdown, Schedule};
+use std::time::Duration;
+
+#[test]
+fn test_parse_wall_clock_times() {
+    assert_eq!(
+        Schedule::parse("02:00"),
+        Some(Schedule::At { hour: 2, minute: 0 })
+    );
+    assert_eq!(
+        Schedule::parse("at 23:30"),
+        Some(Schedule::At {
+            hour: 23,
+            minute: 30
+        })
+    );
+    assert_eq!(Schedule::parse("24:00"), None);
+    assert_eq!(Schedule::parse("12:60"), None);
+}
+
+#[test]
+fn test_parse_countdowns() {
+    assert_eq!(
+        Schedule::parse("30m"),
+        Some(Schedule::After(Duration::from_secs(1800)))
+    );
+    assert_eq!(
+        Schedule::parse("+90s"),
+        Some(Schedule::After(Duration::from_secs(90)))
+    );
+    assert_eq!(
+        Schedule::parse("in 2h"),
+        Some(Schedule::After(Duration::from_secs(7200)))
+    );
+    assert_eq!(
+        Schedule::parse("15"),
+        Some(Schedule::After(Duration::from_secs(900)))
+    );
+    assert_eq!(Schedule::parse("soon"), None);
+    assert_eq!(Schedule::parse("5 days"), None);
+    // Overflowing countdowns are refused
+    assert_eq!(Schedule::parse("99999999999999999h"), None);
+    assert_eq!(Schedule::parse("99999999999999999999s"), None);
+    // So are ones longer than a year, which an Instant may not hold
+    assert_eq!(Schedule::parse("3000000000000000h"), None);
+    assert_eq!(Schedule::parse("8761h"), None);
+    assert_eq!(
+        Schedule::parse("8760h"),
+        Some(Schedule::After(Duration::from_secs(8760 * 3600)))
+    );
+}
+
+#[test]
+fn test_delay_wraps_to_next_day() {
+    let at_two = Schedule::At { hour: 2, minute: 0 };
+
+    // 01:00 -> one hour
+    assert_eq!(at_two.delay_from(3600), Duration::from_secs(3600));
+    // 03:00 -> 23 hours, tomorrow
+    assert_eq!(at_two.delay_from(3 * 3600), Duration::from_secs(23 * 3600));
+    // Exactly 02:00 runs tomorrow rather than immediately
+    assert_eq!(at_two.delay_from(2 * 3600), Duration::from_secs(24 * 3600));
+}
+
+#[test]
+fn test_format_countdown() {
+    assert_eq!(format_countdown(Duration::from_secs(0)), "00:00:00");
+    assert_eq!(format_countdown(Duration::from_secs(3725)), "01:02:05");
+}
@@ -0,0 +1,30 @@
+//! Tests for systemd scope argument building in src/scope.rs
+
+use cleansys::scope::scope_args;
+use std::ffi::OsString;
+
+fn strings(args: Vec<OsString>) -> Vec<String> {
+    args.into_iter()
+        .map(|a| a.to_string_lossy().into_owned())
+        .collect()
+}
+
+#[test]
+fn test_user_scope_wraps_program() {
+    let program: Vec<OsString> = vec!["/usr/bin/cleansys".into(), "user".into()];
+    let args = strings(scope_args(20, 30, true, &program));
+
+    assert_eq!(args[0], "--user");
+    assert!(args.contains(&"--scope".to_string()));
+    assert!(args.contains(&"IOWeight=20".to_string()));
+    assert!(args.contains(&"CPUWeight=30".to_string()));
+
+    let separator = args.iter().position(|a| a == "--").unwrap();
+    assert_eq!(&args[separator + 1..], ["/usr/bin/cleansys", "user"]);
+}
+
+#[test]
+fn test_root_scope_uses_system_manager() {
+    let args = strings(scope_args(20, 20, false, &["true".into()]));
+    assert!(!args.contains(&"--user".to_string()));
+}
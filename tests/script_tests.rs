@@ -0,0 +1,106 @@
+//! Tests for scripted TUI sessions, src/script.rs
+
+use anyhow::Result;
+use cleansys::app::{App, CleanerCategory, CleanerItem, Status};
+use cleansys::cleaners::RiskLevel;
+use cleansys::history::RunRecord;
+use cleansys::script::{parse_key, run_headless, Script};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::fs;
+use std::time::Duration;
+use tempfile::tempdir;
+
+fn frees_a_kilobyte(_: bool) -> Result<u64> {
+    Ok(1024)
+}
+
+fn app() -> App {
+    let item = |name: &str| CleanerItem {
+        name: name.to_string(),
+        description: String::new(),
+        docs: None,
+        requires_root: false,
+        risk: RiskLevel::Normal,
+        selected: false,
+        function: frees_a_kilobyte,
+        scan: None,
+        scan_state: None,
+        bytes_cleaned: 0,
+        bytes_deferred: 0,
+        permission_denied: 0,
+        status: None,
+        parent: None,
+        tags: vec![],
+    };
+    let mut app = App::new();
+    app.is_root = false;
+    app.categories = vec![CleanerCategory {
+        name: "User Land Cleaners".to_string(),
+        description: String::new(),
+        items: vec![item("First"), item("Second"), item("Third")],
+    }];
+    app.item_list_state.select(Some(0));
+    app
+}
+
+#[test]
+fn test_parse_keys() {
+    assert_eq!(
+        parse_key("down").unwrap(),
+        KeyEvent::new(KeyCode::Down, KeyModifiers::NONE)
+    );
+    assert_eq!(
+        parse_key("ctrl+space").unwrap(),
+        KeyEvent::new(KeyCode::Char(' '), KeyModifiers::CONTROL)
+    );
+    assert_eq!(parse_key("+").unwrap().code, KeyCode::Char('+'));
+    assert_eq!(parse_key("F1").unwrap().code, KeyCode::F(1));
+    assert!(parse_key("hyper+q").is_err());
+    assert!(parse_key("f13").is_err());
+}
+
+#[test]
+fn test_parse_reports_the_bad_line() {
+    assert!(Script::parse("# nothing to do\n\nselect 2 # the second\nwait 1.5s").is_ok());
+    for script in ["select", "select two", "wait soon", "snapshot", "dance"] {
+        let error = Script::parse(&format!("run\n{}", script)).unwrap_err();
+        assert_eq!(error.to_string(), "line 2", "{}", script);
+    }
+}
+
+#[test]
+fn test_headless_script_runs_and_exports_a_report() {
+    let dir = tempdir().unwrap();
+    let report = dir.path().join("report.json");
+    let screen = dir.path().join("screen.txt");
+    let mut script = Script::parse(&format!(
+        "select 2\nrun\nwait done\nsnapshot {}\nexport {}\nquit",
+        screen.display(),
+        report.display()
+    ))
+    .unwrap();
+
+    let mut app = app();
+    run_headless(&mut app, &mut script, Duration::from_millis(10)).unwrap();
+
+    assert!(script.is_finished());
+    assert!(matches!(
+        app.categories[0].items[1].status,
+        Some(Status::Success(_))
+    ));
+    let record: RunRecord = serde_json::from_str(&fs::read_to_string(report).unwrap()).unwrap();
+    assert_eq!(record.total(), 1024);
+    assert_eq!(record.cleaners[0].name, "Second");
+    assert!(fs::read_to_string(screen).unwrap().contains("Second"));
+}
+
+#[test]
+fn test_script_errors_name_the_line() {
+    let mut script = Script::parse("\nselect 9").unwrap();
+    let error = run_headless(&mut app(), &mut script, Duration::from_millis(10)).unwrap_err();
+    assert_eq!(error.to_string(), "Script line 2");
+    assert_eq!(
+        format!("{:#}", error),
+        "Script line 2: The category shows 3 cleaners"
+    );
+}
@@ -0,0 +1,38 @@
+//! Tests for the selection strategies in src/selection.rs
+
+use cleansys::cleaners::RiskLevel;
+use cleansys::selection::{
+    Candidate, Recommended, SelectionStrategy, RECOMMENDED_MIN_AGE, RECOMMENDED_MIN_SIZE,
+};
+
+fn candidate(name: &str, risk: RiskLevel, estimate: Option<u64>) -> Candidate<'_> {
+    Candidate {
+        name,
+        risk,
+        estimate,
+    }
+}
+
+#[test]
+fn test_recommended_picks_the_curated_set() {
+    let strategy = Recommended;
+    assert!(strategy.selects(&candidate("Browser Caches", RiskLevel::Normal, None)));
+    assert!(strategy.selects(&candidate(
+        "Package Manager Caches",
+        RiskLevel::Normal,
+        Some(RECOMMENDED_MIN_SIZE)
+    )));
+    assert!(!strategy.selects(&candidate("Old Kernels", RiskLevel::Normal, None)));
+    assert_eq!(strategy.min_age(), Some(RECOMMENDED_MIN_AGE));
+}
+
+#[test]
+fn test_recommended_skips_small_and_risky_cleaners() {
+    let strategy = Recommended;
+    assert!(!strategy.selects(&candidate(
+        "Trash",
+        RiskLevel::Normal,
+        Some(RECOMMENDED_MIN_SIZE - 1)
+    )));
+    assert!(!strategy.selects(&candidate("Trash", RiskLevel::High, Some(u64::MAX))));
+}
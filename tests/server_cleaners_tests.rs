@@ -0,0 +1,84 @@
+//! Tests for the server cleaners in src/cleaners/server_cleaners.rs
+#![cfg(feature = "server")]
+
+use cleansys::cleaners::groups;
+use cleansys::cleaners::server_cleaners::{
+    expired_sessions, old_db_logs, parse_gc_maxlifetime, set_enabled, DB_LOG_MAX_AGE,
+};
+use cleansys::cleaners::temp_litter::Liveness;
+use std::fs::{self, File};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use tempfile::tempdir;
+
+const DAY: u64 = 24 * 60 * 60;
+
+fn write_aged(path: &Path, age_secs: u64) {
+    fs::write(path, "log").unwrap();
+    File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(SystemTime::now() - Duration::from_secs(age_secs))
+        .unwrap();
+}
+
+#[test]
+fn test_parse_gc_maxlifetime() {
+    let ini = "[Session]\n; session.gc_maxlifetime = 60\nsession.gc_maxlifetime = 7200\n";
+    assert_eq!(parse_gc_maxlifetime(ini), Some(7200));
+    assert_eq!(
+        parse_gc_maxlifetime("[Session]\nsession.name = PHPSESSID\n"),
+        None
+    );
+}
+
+#[test]
+fn test_old_db_logs_keep_open_logs() {
+    let dir = tempdir().unwrap();
+    write_aged(&dir.path().join("error.log"), 30 * DAY);
+    write_aged(&dir.path().join("error.log.1.gz"), DAY);
+    write_aged(&dir.path().join("slow.log"), 40 * DAY);
+    write_aged(&dir.path().join("general.log"), 50 * DAY);
+    write_aged(&dir.path().join("recent.log"), 60);
+    write_aged(&dir.path().join("mysql.pid"), 90 * DAY);
+    let names = |liveness: Option<&Liveness>| -> Vec<String> {
+        old_db_logs(dir.path(), DB_LOG_MAX_AGE, SystemTime::now(), liveness)
+            .into_iter()
+            .map(|(path, _)| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect()
+    };
+
+    // An idle slow log the server still holds stays, though it isn't the newest
+    let liveness = Liveness {
+        open: vec![dir.path().join("error.log"), dir.path().join("slow.log")],
+        ..Liveness::default()
+    };
+    assert_eq!(names(Some(&liveness)), ["error.log.1.gz", "general.log"]);
+    // Without /proc only rotated logs go
+    assert_eq!(names(None), ["error.log.1.gz"]);
+}
+
+#[test]
+fn test_expired_sessions_past_lifetime() {
+    let dir = tempdir().unwrap();
+    write_aged(&dir.path().join("sess_old"), 3 * 3600);
+    write_aged(&dir.path().join("sess_live"), 60);
+    write_aged(&dir.path().join("other"), 3 * 3600);
+
+    let sessions = expired_sessions(dir.path(), Duration::from_secs(1440), SystemTime::now());
+    assert_eq!(sessions, vec![(dir.path().join("sess_old"), 3)]);
+}
+
+#[test]
+fn test_server_group_is_opt_in() {
+    let has_group = || groups().iter().any(|g| g.name == "Web & Database Servers");
+    assert!(!has_group());
+    set_enabled(true);
+    let enabled = has_group();
+    set_enabled(false);
+    assert_eq!(
+        enabled,
+        cleansys::platform::Platform::current().has_system_cleaners()
+    );
+}
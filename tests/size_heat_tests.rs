@@ -0,0 +1,21 @@
+//! Tests for size heat colors
+
+use cleansys::size_heat::{size_span, Heat, HOT_FROM, WARM_FROM};
+use ratatui::style::Color;
+
+#[test]
+fn test_heat_thresholds() {
+    assert_eq!(Heat::of(0), Heat::Cool);
+    assert_eq!(Heat::of(WARM_FROM - 1), Heat::Cool);
+    assert_eq!(Heat::of(WARM_FROM), Heat::Warm);
+    assert_eq!(Heat::of(HOT_FROM - 1), Heat::Warm);
+    assert_eq!(Heat::of(HOT_FROM), Heat::Hot);
+}
+
+#[test]
+fn test_size_span_is_formatted_and_colored() {
+    let span = size_span(2 * 1024 * 1024 * 1024);
+    assert_eq!(span.content, "2.00 GB");
+    assert_eq!(span.style.fg, Some(Color::Red));
+    assert_eq!(size_span(512).style.fg, Some(Color::Green));
+}
@@ -0,0 +1,71 @@
+//! Property-based tests for the size math in src/utils: `format_size` and
+//! the parsers reading sizes back from command output and the config
+
+use cleansys::utils::{format_size, parse_si_size, parse_size};
+use proptest::prelude::*;
+
+const KB: u64 = 1024;
+
+/// The unit `format_size` prints `bytes` in
+fn unit_of(bytes: u64) -> u64 {
+    [KB * KB * KB, KB * KB, KB]
+        .into_iter()
+        .find(|&unit| bytes >= unit)
+        .unwrap_or(1)
+}
+
+#[test]
+fn test_parse_size_examples() {
+    assert_eq!(parse_size("1.5 GB"), Some(1_610_612_736));
+    assert_eq!(parse_size("1,5 GB"), Some(1_610_612_736));
+    assert_eq!(parse_size("512 bytes"), Some(512));
+    assert_eq!(parse_size("200M"), Some(200 * KB * KB));
+    assert_eq!(parse_si_size("1.2GB"), Some(1_200_000_000));
+    assert_eq!(parse_si_size("356kB"), Some(356_000));
+    // Thousands separators are ambiguous, so not guessed at
+    assert_eq!(parse_size("1,234.5 MB"), None);
+    assert_eq!(parse_size("1,2,3 MB"), None);
+    assert_eq!(parse_size("12 parsecs"), None);
+}
+
+proptest! {
+    #[test]
+    fn prop_format_then_parse_round_trips(bytes in 0u64..1 << 50) {
+        let parsed = parse_size(&format_size(bytes)).unwrap();
+        // Two decimals are kept, so up to half a hundredth of the unit is lost
+        let tolerance = unit_of(bytes) / 200 + 1;
+        prop_assert!(
+            parsed.abs_diff(bytes) <= tolerance,
+            "{} -> {:?} -> {}", bytes, format_size(bytes), parsed
+        );
+    }
+
+    #[test]
+    fn prop_comma_decimal_separator_parses_the_same(bytes in 0u64..1 << 50) {
+        let text = format_size(bytes);
+        prop_assert_eq!(parse_size(&text.replace('.', ",")), parse_size(&text));
+    }
+
+    #[test]
+    fn prop_whole_units_parse_exactly(
+        number in 0u64..100_000,
+        (exponent, unit) in prop::sample::select(vec![
+            (0, "bytes"), (1, "KB"), (2, "MB"), (3, "GiB"), (1, "k"), (2, "m"),
+        ]),
+        space in prop::sample::select(vec!["", " ", "  "]),
+        lowercase: bool,
+    ) {
+        let mut text = format!(" {}{}{} ", number, space, unit);
+        if lowercase {
+            text = text.to_lowercase();
+        }
+        prop_assert_eq!(parse_size(&text), Some(number * 1024u64.pow(exponent)));
+        prop_assert_eq!(parse_si_size(&text), Some(number * 1000u64.pow(exponent)));
+    }
+
+    #[test]
+    fn prop_parse_never_panics(text in "\\PC*") {
+        let _ = parse_size(&text);
+        let _ = parse_si_size(&text);
+    }
+}
@@ -0,0 +1,95 @@
+//! Tests for the mail and spool cleaners in src/cleaners/spool_cleaners.rs
+#![cfg(feature = "server")]
+
+use cleansys::cleaners::spool_cleaners::{
+    large_files, stale_files, truncate_mailbox, Mailbox, SPOOL_MAX_AGE,
+};
+use std::fs::{self, File};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use tempfile::tempdir;
+
+fn write_aged(path: &Path, len: usize, age_days: u64) {
+    fs::write(path, vec![0u8; len]).unwrap();
+    File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(SystemTime::now() - Duration::from_secs(age_days * 24 * 60 * 60))
+        .unwrap();
+}
+
+#[test]
+fn test_stale_files_skips_recent_files_and_directories() {
+    let dir = tempdir().unwrap();
+    write_aged(&dir.path().join("dfA1"), 10, 45);
+    write_aged(&dir.path().join("qfA1"), 20, 2);
+    fs::create_dir(dir.path().join("old")).unwrap();
+
+    assert_eq!(
+        stale_files(dir.path(), SPOOL_MAX_AGE, SystemTime::now()),
+        vec![(dir.path().join("dfA1"), 10)]
+    );
+    assert!(stale_files(
+        &dir.path().join("missing"),
+        SPOOL_MAX_AGE,
+        SystemTime::now()
+    )
+    .is_empty());
+}
+
+#[test]
+fn test_large_files_over_limit() {
+    let dir = tempdir().unwrap();
+    write_aged(&dir.path().join("alice"), 2000, 0);
+    write_aged(&dir.path().join("bob"), 10, 0);
+
+    assert_eq!(
+        large_files(dir.path(), 1000),
+        vec![(dir.path().join("alice"), 2000)]
+    );
+}
+
+#[test]
+fn test_truncate_mailbox_in_place_and_unlocks() {
+    let dir = tempdir().unwrap();
+    let mailbox = dir.path().join("alice");
+    write_aged(&mailbox, 2000, 0);
+
+    truncate_mailbox(&Mailbox::at(&mailbox).unwrap()).unwrap();
+    assert_eq!(fs::metadata(&mailbox).unwrap().len(), 0);
+    assert!(!dir.path().join("alice.lock").exists());
+
+    // The MTA's own dotlock is left alone
+    fs::write(dir.path().join("alice.lock"), "").unwrap();
+    write_aged(&mailbox, 2000, 0);
+    assert!(truncate_mailbox(&Mailbox::at(&mailbox).unwrap()).is_err());
+    assert_eq!(fs::metadata(&mailbox).unwrap().len(), 2000);
+    assert!(dir.path().join("alice.lock").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_truncate_mailbox_refuses_a_swapped_file() {
+    let dir = tempdir().unwrap();
+    let mailbox = dir.path().join("alice");
+    let victim = dir.path().join("shadow");
+    write_aged(&mailbox, 2000, 0);
+    write_aged(&victim, 2000, 0);
+    assert!(Mailbox::at(&victim).is_ok());
+
+    // Swapped for a symlink to another file after the scan
+    let scanned = Mailbox::at(&mailbox).unwrap();
+    fs::remove_file(&mailbox).unwrap();
+    std::os::unix::fs::symlink(&victim, &mailbox).unwrap();
+    assert!(truncate_mailbox(&scanned).is_err());
+    assert_eq!(fs::metadata(&victim).unwrap().len(), 2000);
+    assert!(Mailbox::at(&mailbox).is_err());
+
+    // Or for another regular file under the same name
+    fs::remove_file(&mailbox).unwrap();
+    fs::hard_link(&victim, &mailbox).unwrap();
+    assert!(truncate_mailbox(&scanned).is_err());
+    assert_eq!(fs::metadata(&victim).unwrap().len(), 2000);
+    assert!(!dir.path().join("alice.lock").exists());
+}
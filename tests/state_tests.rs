@@ -0,0 +1,136 @@
+//! Tests for session state persistence in src/state.rs
+
+use anyhow::Result;
+use cleansys::app::{
+    App, ChartType, CleanerCategory, CleanerItem, ViewMode, PROGRESS_SPLIT_MAX, PROGRESS_SPLIT_MIN,
+    PROGRESS_SPLIT_STEP,
+};
+use cleansys::cleaners::RiskLevel;
+use cleansys::state::{SelectedCleaner, SessionState};
+use cleansys::usage::UsageStats;
+use tempfile::tempdir;
+
+fn noop(_: bool) -> Result<u64> {
+    Ok(0)
+}
+
+fn item(name: &str) -> CleanerItem {
+    CleanerItem {
+        name: name.to_string(),
+        description: String::new(),
+        docs: None,
+        requires_root: false,
+        risk: RiskLevel::Normal,
+        selected: false,
+        function: noop,
+        scan: None,
+        scan_state: None,
+        bytes_cleaned: 0,
+        bytes_deferred: 0,
+        permission_denied: 0,
+        status: None,
+        parent: None,
+        tags: vec![],
+    }
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.categories = vec![
+        CleanerCategory {
+            name: "User".to_string(),
+            description: String::new(),
+            items: vec![item("Trash"), item("Thumbnails")],
+        },
+        CleanerCategory {
+            name: "System".to_string(),
+            description: String::new(),
+            items: vec![item("Logs"), item("Trash")],
+        },
+    ];
+    app
+}
+
+#[test]
+fn test_state_file_round_trip() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("nested/state.json");
+
+    let state = SessionState {
+        selected: vec![SelectedCleaner {
+            category: "User".to_string(),
+            name: "Trash".to_string(),
+        }],
+        view_mode: Some(ViewMode::Detailed),
+        chart_type: Some(ChartType::Bar),
+        progress_split: Some(65),
+        category_index: 1,
+        item_index: Some(1),
+        detailed_scroll: Some(4),
+        usage: UsageStats::default(),
+    };
+    state.save_to(&path).unwrap();
+
+    assert_eq!(SessionState::load_from(&path).unwrap(), state);
+}
+
+#[test]
+fn test_app_restores_session() {
+    let mut original = app();
+    original.categories[1].items[1].selected = true;
+    original.category_index = 1;
+    original.item_list_state.select(Some(1));
+    original.chart_type = ChartType::PieSize;
+    original.cycle_view_mode();
+    let state = original.session_state();
+
+    let mut restored = app();
+    restored.apply_session_state(&state);
+
+    // Only the System "Trash" is selected, not the User one with the same name
+    assert!(!restored.categories[0].items[0].selected);
+    assert!(restored.categories[1].items[1].selected);
+    assert_eq!(restored.category_index, 1);
+    assert_eq!(restored.item_list_state.selected(), Some(1));
+    assert_eq!(restored.chart_type, ChartType::PieSize);
+    assert_eq!(restored.view_mode, original.view_mode);
+    assert_eq!(restored.selected_cleaners_count, 1);
+}
+
+#[test]
+fn test_out_of_range_positions_are_ignored() {
+    let state = SessionState {
+        category_index: 7,
+        item_index: Some(42),
+        ..SessionState::default()
+    };
+
+    let mut app = app();
+    app.apply_session_state(&state);
+    assert_eq!(app.category_index, 0);
+    assert_eq!(app.item_list_state.selected(), Some(0));
+}
+
+#[test]
+fn test_progress_split_resizes_within_bounds_and_is_restored() {
+    let mut original = app();
+    original.terminal_height = 30;
+    assert_eq!(original.progress_split(), 50);
+    original.resize_progress_split(true);
+    assert_eq!(original.progress_split(), 50 + PROGRESS_SPLIT_STEP);
+    for _ in 0..20 {
+        original.resize_progress_split(false);
+    }
+    assert_eq!(original.progress_split(), PROGRESS_SPLIT_MIN);
+
+    let mut restored = app();
+    restored.apply_session_state(&original.session_state());
+    assert_eq!(restored.progress_split, Some(PROGRESS_SPLIT_MIN));
+
+    // A hand-edited state file can't push the split out of range
+    restored.apply_session_state(&SessionState {
+        progress_split: Some(99),
+        ..SessionState::default()
+    });
+    assert_eq!(restored.progress_split(), PROGRESS_SPLIT_MAX);
+}
@@ -0,0 +1,162 @@
+//! Tests for the swapfile advisor in src/swap.rs
+
+use cleansys::swap::{
+    analyze, parse_fstab_swaps, parse_mem_total, parse_proc_swaps, remove, swap_signature,
+    ActiveSwap, FindingKind, SwapFinding, SwapSignature,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+const GIB: u64 = 1024 * 1024 * 1024;
+
+/// Write a swap area of `len` bytes with `signature` at the end of its first page
+fn swap_area(path: &Path, page_size: usize, signature: &[u8], len: usize) {
+    let mut contents = vec![0u8; len.max(page_size)];
+    contents[page_size - 10..page_size - 10 + signature.len()].copy_from_slice(signature);
+    fs::write(path, contents).unwrap();
+}
+
+fn same(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
+
+#[test]
+fn test_parse_proc_swaps() {
+    let swaps = "Filename\t\t\t\tType\t\tSize\t\tUsed\t\tPriority\n\
+                 /swapfile                               file\t\t8388604\t\t1024\t\t-2\n\
+                 /dev/nvme0n1p3                          partition\t16777212\t0\t\t-3\n";
+    assert_eq!(
+        parse_proc_swaps(swaps),
+        vec![
+            ActiveSwap {
+                path: PathBuf::from("/swapfile"),
+                is_file: true,
+                size: 8388604 * 1024,
+                used: 1024 * 1024,
+            },
+            ActiveSwap {
+                path: PathBuf::from("/dev/nvme0n1p3"),
+                is_file: false,
+                size: 16777212 * 1024,
+                used: 0,
+            },
+        ]
+    );
+    assert!(parse_proc_swaps("Filename Type Size Used Priority\n").is_empty());
+}
+
+#[test]
+fn test_parse_fstab_and_meminfo() {
+    let fstab = "UUID=abcd / ext4 defaults 0 1\n\
+                 /swapfile none swap sw 0 0\n\
+                 # /swap.img none swap sw 0 0\n\
+                 UUID=ef01 none swap sw 0 0\n";
+    assert_eq!(parse_fstab_swaps(fstab), vec![PathBuf::from("/swapfile")]);
+
+    let meminfo = "MemTotal:       16318412 kB\nMemFree:         1234 kB\n";
+    assert_eq!(parse_mem_total(meminfo), Some(16318412 * 1024));
+    assert_eq!(parse_mem_total("MemFree: 1 kB\n"), None);
+}
+
+#[test]
+fn test_signatures() {
+    let dir = tempdir().unwrap();
+    let swap = dir.path().join("swapfile");
+    let hibernation = dir.path().join("hibernated");
+    let large_pages = dir.path().join("arm64");
+    let plain = dir.path().join("data");
+    swap_area(&swap, 4096, b"SWAPSPACE2", 8192);
+    swap_area(&hibernation, 4096, b"S1SUSPEND", 8192);
+    swap_area(&large_pages, 65536, b"SWAPSPACE2", 65536);
+    fs::write(&plain, vec![1u8; 8192]).unwrap();
+
+    assert_eq!(swap_signature(&swap), Some(SwapSignature::Swap));
+    assert_eq!(
+        swap_signature(&hibernation),
+        Some(SwapSignature::Hibernation)
+    );
+    assert_eq!(swap_signature(&large_pages), Some(SwapSignature::Swap));
+    assert_eq!(swap_signature(&plain), None);
+}
+
+#[test]
+fn test_analyze_reports_unused_swap() {
+    let dir = tempdir().unwrap();
+    let inactive = dir.path().join("swapfile");
+    let hibernation = dir.path().join("swap.img");
+    let plain = dir.path().join("var-swap");
+    swap_area(&inactive, 4096, b"SWAPSPACE2", 8192);
+    swap_area(&hibernation, 4096, b"LINHIB0001", 8192);
+    fs::write(&plain, vec![0u8; 8192]).unwrap();
+
+    let candidates = vec![inactive.clone(), hibernation.clone(), plain];
+    let fstab = vec![hibernation.clone()];
+    let findings = analyze(&candidates, &[], &fstab, 8 * GIB, same);
+
+    assert_eq!(
+        findings,
+        vec![
+            SwapFinding {
+                path: inactive,
+                size: 8192,
+                kind: FindingKind::InactiveSwapfile,
+                in_fstab: false,
+            },
+            SwapFinding {
+                path: hibernation,
+                size: 8192,
+                kind: FindingKind::StaleHibernationImage,
+                in_fstab: true,
+            },
+        ]
+    );
+    assert!(findings[0].removable());
+    // Deleting it would leave a dangling fstab entry
+    assert!(!findings[1].removable());
+}
+
+#[test]
+fn test_analyze_active_swapfiles() {
+    let dir = tempdir().unwrap();
+    let swapfile = dir.path().join("swapfile");
+    swap_area(&swapfile, 4096, b"SWAPSPACE2", 8192);
+    let active = |size| {
+        vec![ActiveSwap {
+            path: swapfile.clone(),
+            is_file: true,
+            size,
+            used: 0,
+        }]
+    };
+    let candidates = vec![swapfile.clone()];
+
+    // Reasonable for the RAM: nothing to say
+    assert!(analyze(&candidates, &active(8 * GIB), &[], 8 * GIB, same).is_empty());
+
+    let findings = analyze(&candidates, &active(32 * GIB), &[], 8 * GIB, same);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].kind, FindingKind::LargeSwapfile);
+    assert_eq!(findings[0].size, 32 * GIB);
+    assert!(!findings[0].removable());
+}
+
+#[test]
+fn test_remove_refuses_files_in_use() {
+    let dir = tempdir().unwrap();
+    let swapfile = dir.path().join("swapfile");
+    swap_area(&swapfile, 4096, b"SWAPSPACE2", 8192);
+
+    let mut finding = SwapFinding {
+        path: swapfile.clone(),
+        size: 8192,
+        kind: FindingKind::LargeSwapfile,
+        in_fstab: false,
+    };
+    assert!(remove(&finding).is_err());
+    assert!(swapfile.exists());
+
+    finding.kind = FindingKind::InactiveSwapfile;
+    assert_eq!(remove(&finding).unwrap(), 8192);
+    assert!(!swapfile.exists());
+}
@@ -0,0 +1,116 @@
+//! Tests for the system information panel, src/sysinfo.rs, and the disk
+//! gauges on the main screen
+
+use cleansys::app::{App, CleanerCategory};
+use cleansys::history::{CleanerRun, History, RunRecord};
+use cleansys::script::screen_text;
+use cleansys::sysinfo::{last_clean, os_release_name, usage_bar, SystemInfo};
+use cleansys::utils::DiskSpace;
+use tempfile::tempdir;
+
+#[test]
+fn test_os_release_name() {
+    let os_release = "NAME=\"Fedora Linux\"\nVERSION_ID=40\nPRETTY_NAME=\"Fedora Linux 40 (Workstation Edition)\"\n";
+    assert_eq!(
+        os_release_name(os_release).as_deref(),
+        Some("Fedora Linux 40 (Workstation Edition)")
+    );
+    assert_eq!(os_release_name("NAME=Alpine\n").as_deref(), Some("Alpine"));
+    assert_eq!(os_release_name("PRETTY_NAMES=x\n"), None);
+}
+
+#[test]
+fn test_usage_bar_and_ratio() {
+    let space = DiskSpace {
+        device: 1,
+        total: 1000,
+        available: 250,
+    };
+    assert_eq!(space.used_ratio(), 0.75);
+    assert_eq!(usage_bar(space.used_ratio(), 8), "██████░░");
+    assert_eq!(usage_bar(1.5, 4), "████");
+    assert_eq!(usage_bar(0.0, 4), "░░░░");
+}
+
+#[test]
+fn test_last_clean_is_the_newest_run() {
+    let dir = tempdir().unwrap();
+    let history = History::at(&dir.path().join("history.jsonl"));
+    assert_eq!(last_clean(&history), None);
+    history
+        .record(RunRecord::new(vec![CleanerRun::succeeded("Trash", 1)]))
+        .unwrap();
+    let newest = history
+        .record(RunRecord::new(vec![CleanerRun::succeeded("Trash", 2)]))
+        .unwrap();
+    assert_eq!(last_clean(&history), Some(newest.time));
+}
+
+#[test]
+fn test_panel_shows_host_disks_and_last_clean() {
+    let mut app = App::new();
+    app.terminal_width = 100;
+    app.terminal_height = 30;
+    app.categories.push(CleanerCategory {
+        name: "User".to_string(),
+        description: String::new(),
+        items: Vec::new(),
+    });
+    app.system_info = Some(SystemInfo {
+        hostname: "workstation".to_string(),
+        distro: "Debian GNU/Linux 12".to_string(),
+        kernel: "6.1.0-18-amd64".to_string(),
+        root: Some(DiskSpace {
+            device: 1,
+            total: 100 << 30,
+            available: 5 << 30,
+        }),
+        home: None,
+        last_clean: None,
+    });
+    let screen = screen_text(&mut app).unwrap();
+    assert!(screen.contains("workstation"), "{}", screen);
+    assert!(screen.contains("kernel 6.1.0-18-amd64"), "{}", screen);
+    assert!(
+        screen.contains("██████████ 95%, 5.00 GB free"),
+        "{}",
+        screen
+    );
+    assert!(screen.contains("Last clean: never"), "{}", screen);
+
+    app.toggle_system_info();
+    assert!(!screen_text(&mut app).unwrap().contains("workstation"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_disk_spaces_skip_repeated_filesystems() {
+    use cleansys::utils::get_disk_spaces;
+
+    let spaces = get_disk_spaces(&["/", "/"]);
+    assert_eq!(spaces.len(), 1);
+    assert_eq!(spaces[0].0, std::path::Path::new("/"));
+    assert!(spaces[0].1.total >= spaces[0].1.available);
+}
+
+#[test]
+fn test_disk_gauges_under_the_categories() {
+    let mut app = App::new();
+    app.terminal_width = 120;
+    app.terminal_height = 30;
+    app.categories.push(CleanerCategory {
+        name: "User".to_string(),
+        description: String::new(),
+        items: Vec::new(),
+    });
+    let space = |available| DiskSpace {
+        device: available,
+        total: 100 << 30,
+        available: available << 30,
+    };
+    app.disks = vec![("/".into(), space(40)), ("/home".into(), space(5))];
+    let screen = screen_text(&mut app).unwrap();
+    assert!(screen.contains("Disks"), "{}", screen);
+    assert!(screen.contains("/ 60% (40.00 GB free)"), "{}", screen);
+    assert!(screen.contains("/home 95% (5.00 GB free)"), "{}", screen);
+}
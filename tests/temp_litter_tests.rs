@@ -0,0 +1,157 @@
+//! Tests for the advanced temp cleaners in src/cleaners/temp_litter.rs
+
+use cleansys::cleaners::groups;
+use cleansys::cleaners::temp_litter::{
+    cgroup_units, classify, find_litter, set_enabled, systemd_private_parts, LitterKind, Liveness,
+};
+use std::fs::{self, File};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use tempfile::tempdir;
+
+const DAY: u64 = 24 * 60 * 60;
+const BOOT_ID: &str = "0123456789abcdef0123456789abcdef";
+
+fn age(path: &Path, age_secs: u64) {
+    File::open(path)
+        .unwrap()
+        .set_modified(SystemTime::now() - Duration::from_secs(age_secs))
+        .unwrap();
+}
+
+fn names(litter: &[(std::path::PathBuf, u64)]) -> Vec<String> {
+    litter
+        .iter()
+        .map(|(path, _)| path.file_name().unwrap().to_string_lossy().to_string())
+        .collect()
+}
+
+#[test]
+fn test_classify_by_name() {
+    assert_eq!(classify("build.tmp", false), Some(LitterKind::TmpFile));
+    assert_eq!(classify(".tmp", false), None);
+    assert_eq!(classify("build.tmp", true), None);
+    assert_eq!(classify("tmp.Xa81bQ", true), Some(LitterKind::MktempDir));
+    assert_eq!(
+        classify("tmp.A1b2C3d4E5", true),
+        Some(LitterKind::MktempDir)
+    );
+    assert_eq!(classify("tmp.keep", true), None);
+    assert_eq!(classify("tmp.Xa81bQ", false), None);
+    assert_eq!(
+        classify(".org.chromium.Chromium.q2Wd9a", true),
+        Some(LitterKind::ChromiumDir)
+    );
+    assert_eq!(classify(".org.chromium.Chromium.", true), None);
+    assert_eq!(
+        classify(
+            &format!("systemd-private-{}-colord.service-aBc123", BOOT_ID),
+            true
+        ),
+        Some(LitterKind::SystemdPrivate)
+    );
+    assert_eq!(classify("systemd-private-nothex-x.service-1", true), None);
+}
+
+#[test]
+fn test_systemd_private_parts() {
+    let name = format!("systemd-private-{}-systemd-logind.service-Zx9kQ1", BOOT_ID);
+    assert_eq!(
+        systemd_private_parts(&name),
+        Some((BOOT_ID, "systemd-logind.service"))
+    );
+    assert_eq!(
+        systemd_private_parts(&format!("systemd-private-{}--Zx9kQ1", BOOT_ID)),
+        None
+    );
+}
+
+#[test]
+fn test_cgroup_units_of_delegated_services() {
+    let units: Vec<String> = cgroup_units(
+        "0::/system.slice/foo.service/payload\n\
+         1:name=systemd:/user.slice/user-1000.slice/session-2.scope\n",
+    )
+    .collect();
+    assert_eq!(units, ["foo.service", "session-2.scope"]);
+}
+
+#[test]
+fn test_find_litter_keeps_new_and_open_entries() {
+    let dir = tempdir().unwrap();
+    for name in ["old.tmp", "open.tmp", "new.tmp", "old.txt"] {
+        fs::write(dir.path().join(name), "x").unwrap();
+    }
+    for name in ["old.tmp", "open.tmp", "old.txt"] {
+        age(&dir.path().join(name), 10 * DAY);
+    }
+    let liveness = Liveness {
+        open: vec![dir.path().join("open.tmp")],
+        ..Liveness::default()
+    };
+
+    let (litter, in_use) = find_litter(
+        dir.path(),
+        LitterKind::TmpFile,
+        &liveness,
+        SystemTime::now(),
+    );
+    assert_eq!(names(&litter), vec!["old.tmp"]);
+    assert_eq!(in_use, 1);
+}
+
+#[test]
+fn test_find_litter_checks_what_uses_directories() {
+    let dir = tempdir().unwrap();
+    let this_boot = format!("systemd-private-{}-colord.service-aBc123", BOOT_ID);
+    let crashed = format!("systemd-private-{}-cups.service-dEf456", BOOT_ID);
+    let last_boot = format!("systemd-private-{}-colord.service-gHi789", "f".repeat(32));
+    for name in [
+        &this_boot,
+        &crashed,
+        &last_boot,
+        "tmp.Xa81bQ",
+        "tmp.Cw0rkD",
+        ".org.chromium.Chromium.q2Wd9a",
+    ] {
+        fs::create_dir(dir.path().join(name)).unwrap();
+        age(&dir.path().join(name), 2 * DAY);
+    }
+    let mut liveness = Liveness {
+        open: vec![dir.path().join("tmp.Cw0rkD")],
+        units: ["colord.service".to_string()].into_iter().collect(),
+        boot_id: Some(BOOT_ID.to_string()),
+        ..Liveness::default()
+    };
+    let find = |kind, liveness: &Liveness| {
+        let (litter, in_use) = find_litter(dir.path(), kind, liveness, SystemTime::now());
+        (names(&litter), in_use)
+    };
+
+    let (mut private, in_use) = find(LitterKind::SystemdPrivate, &liveness);
+    private.sort();
+    let mut expected = vec![crashed.clone(), last_boot.clone()];
+    expected.sort();
+    assert_eq!((private, in_use), (expected, 1));
+    assert_eq!(
+        find(LitterKind::MktempDir, &liveness),
+        (vec!["tmp.Xa81bQ".to_string()], 1)
+    );
+
+    assert_eq!(find(LitterKind::ChromiumDir, &liveness).0.len(), 1);
+    liveness.processes = vec!["chromium".to_string()];
+    assert_eq!(find(LitterKind::ChromiumDir, &liveness), (vec![], 1));
+}
+
+#[test]
+fn test_advanced_temp_group_is_opt_in() {
+    let has_group = || groups().iter().any(|g| g.name == "Advanced Temp Cleanup");
+    assert!(!has_group());
+    set_enabled(true);
+    let enabled = has_group();
+    set_enabled(false);
+    assert_eq!(
+        enabled,
+        cleansys::platform::Platform::current().has_system_cleaners()
+    );
+}
@@ -0,0 +1,31 @@
+//! Tests for detecting terminals the TUI can't be drawn in
+
+use cleansys::terminal::{unsupported_reason, MIN_HEIGHT, MIN_WIDTH};
+
+#[test]
+fn test_capable_terminal_is_supported() {
+    assert_eq!(
+        unsupported_reason(Some("xterm-256color"), true, Some((120, 40))),
+        None
+    );
+}
+
+#[test]
+fn test_limited_terminals_fall_back() {
+    assert!(unsupported_reason(Some("xterm"), false, Some((120, 40))).is_some());
+    assert!(unsupported_reason(None, true, Some((120, 40))).is_some());
+    assert!(unsupported_reason(Some("dumb"), true, Some((120, 40)))
+        .unwrap()
+        .contains("dumb"));
+    assert!(unsupported_reason(Some("xterm"), true, None).is_some());
+}
+
+#[test]
+fn test_tiny_terminal_falls_back() {
+    assert!(unsupported_reason(Some("xterm"), true, Some((MIN_WIDTH - 1, 40))).is_some());
+    assert!(unsupported_reason(Some("xterm"), true, Some((80, MIN_HEIGHT - 1))).is_some());
+    assert_eq!(
+        unsupported_reason(Some("xterm"), true, Some((MIN_WIDTH, MIN_HEIGHT))),
+        None
+    );
+}
@@ -0,0 +1,53 @@
+//! Tests for footer toasts and the message history
+
+use cleansys::app::App;
+use cleansys::components::toasts::{Toasts, HISTORY_LIMIT};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+#[test]
+fn test_toasts_are_shown_one_at_a_time() {
+    let mut toasts = Toasts::new();
+    assert_eq!(toasts.current(), None);
+
+    toasts.push("first");
+    toasts.push("second");
+    toasts.tick();
+
+    // The second waits until the first has been up long enough
+    assert_eq!(toasts.current(), Some("first"));
+    assert_eq!(toasts.history().count(), 2);
+}
+
+#[test]
+fn test_history_is_bounded() {
+    let mut toasts = Toasts::new();
+    for i in 0..HISTORY_LIMIT + 10 {
+        toasts.push(format!("message {}", i));
+    }
+
+    let history: Vec<_> = toasts.history().collect();
+    assert_eq!(history.len(), HISTORY_LIMIT);
+    assert_eq!(history[0].text, "message 10");
+}
+
+#[test]
+fn test_nothing_selected_is_a_toast_not_a_result() {
+    let mut app = App::new();
+    app.run_selected().unwrap();
+
+    assert!(app.toasts.current().unwrap().contains("No items selected"));
+    assert!(app.run_results.is_empty());
+}
+
+#[test]
+fn test_history_popup_toggles_with_shift_m() {
+    let mut app = App::new();
+    let key = KeyEvent::new(KeyCode::Char('M'), KeyModifiers::SHIFT);
+
+    app.handle_key(key).unwrap();
+    assert!(app.toasts.is_history_visible());
+
+    app.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE))
+        .unwrap();
+    assert!(!app.toasts.is_history_visible());
+}
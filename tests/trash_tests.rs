@@ -0,0 +1,99 @@
+//! Tests for freedesktop Trash support in src/trash.rs
+
+use cleansys::config::Settings;
+use cleansys::exclude;
+use cleansys::pipeline::Pipeline;
+use cleansys::quarantine::QuarantineTarget;
+use cleansys::trash::{trash_info, Trash};
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+#[test]
+fn test_trash_writes_file_and_info() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("my notes.txt");
+    fs::write(&file, "notes").unwrap();
+
+    let trash = Trash::at(dir.path().join("Trash"));
+    let entry = trash.trash(&file).unwrap();
+
+    assert!(!file.exists());
+    assert_eq!(entry.stored, dir.path().join("Trash/files/my notes.txt"));
+    assert_eq!(fs::read_to_string(&entry.stored).unwrap(), "notes");
+
+    let info = fs::read_to_string(dir.path().join("Trash/info/my notes.txt.trashinfo")).unwrap();
+    assert!(info.starts_with("[Trash Info]\n"));
+    assert!(info.contains("my%20notes.txt\n"));
+    assert!(info.contains("DeletionDate="));
+}
+
+#[test]
+fn test_trash_renames_on_name_clash() {
+    let dir = tempdir().unwrap();
+    let trash = Trash::at(dir.path().join("Trash"));
+    let paths: Vec<_> = (0..3)
+        .map(|i| {
+            let sub = dir.path().join(i.to_string());
+            fs::create_dir(&sub).unwrap();
+            fs::write(sub.join("cache.db"), i.to_string()).unwrap();
+            sub.join("cache.db")
+        })
+        .collect();
+
+    let report = trash.move_files(paths, &Pipeline::new().threads(3));
+    assert!(report.failed.is_empty());
+
+    let mut names: Vec<_> = fs::read_dir(dir.path().join("Trash/files"))
+        .unwrap()
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["cache.db", "cache.db.2", "cache.db.3"]);
+    assert_eq!(
+        fs::read_dir(dir.path().join("Trash/info")).unwrap().count(),
+        3
+    );
+}
+
+#[test]
+fn test_trashed_directory_counts_everything_in_it() {
+    let dir = tempdir().unwrap();
+    let tree = dir.path().join("project");
+    fs::create_dir_all(tree.join("build/objects")).unwrap();
+    fs::write(tree.join("build/objects/a.o"), [0u8; 1000]).unwrap();
+    fs::write(tree.join("build/log.txt"), [0u8; 24]).unwrap();
+
+    let trash = Trash::at(dir.path().join("Trash"));
+    let entry = trash.trash(&tree).unwrap();
+
+    assert!(!tree.exists());
+    assert_eq!(entry.size, 1024);
+}
+
+#[test]
+fn test_excluded_paths_are_not_trashed() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("thesis.keepme");
+    fs::write(&file, "draft").unwrap();
+
+    exclude::set_patterns(vec!["*.keepme".to_string()]);
+    let result = Trash::at(dir.path().join("Trash")).trash(&file);
+    exclude::set_patterns(Vec::new());
+
+    assert!(result.is_err());
+    assert!(file.exists());
+}
+
+#[test]
+fn test_trash_info_encodes_path() {
+    let info = trash_info(Path::new("/home/u/a b/ü.txt"));
+    assert!(info.contains("Path=/home/u/a%20b/%C3%BC.txt\n"));
+}
+
+#[test]
+fn test_quarantine_target_setting() {
+    let settings: Settings = toml::from_str("quarantine = \"system-trash\"").unwrap();
+    assert_eq!(settings.quarantine, QuarantineTarget::SystemTrash);
+    assert_eq!(Settings::default().quarantine, QuarantineTarget::Private);
+}
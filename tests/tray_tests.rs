@@ -0,0 +1,27 @@
+#![cfg(all(unix, feature = "tray"))]
+
+use cleansys::tray::{describe, terminal_command};
+
+#[test]
+fn test_terminal_prefers_the_terminal_variable() {
+    let command = terminal_command(Some("foot"), "/usr/bin/cleansys", |_| true).unwrap();
+    assert_eq!(command, ["foot", "-e", "/usr/bin/cleansys"]);
+}
+
+#[test]
+fn test_terminal_falls_back_to_known_emulators() {
+    let command = terminal_command(Some(""), "cleansys", |name| name == "gnome-terminal").unwrap();
+    assert_eq!(command, ["gnome-terminal", "--", "cleansys"]);
+    let command = terminal_command(None, "cleansys", |name| name == "kitty").unwrap();
+    assert_eq!(command, ["kitty", "cleansys"]);
+    assert!(terminal_command(None, "cleansys", |_| false).is_none());
+}
+
+#[test]
+fn test_describe_flags_the_threshold() {
+    let gb = 1024 * 1024 * 1024;
+    assert_eq!(describe(None, 2 * gb, false), "Scanning…");
+    assert_eq!(describe(Some(gb), 2 * gb, true), "Working…");
+    assert!(!describe(Some(gb), 2 * gb, false).contains("over"));
+    assert!(describe(Some(3 * gb), 2 * gb, false).contains("over"));
+}
@@ -0,0 +1,53 @@
+//! Tests for the fstrim offer in src/trim.rs
+
+use cleansys::cleaners::cleaned::{CleanedEntry, CleanedItemType};
+use cleansys::trim::{freed_by_mount, parse_trimmed};
+use std::path::{Path, PathBuf};
+
+const MOUNTS: &str = "\
+/dev/nvme0n1p2 / ext4 rw,relatime 0 0
+/dev/nvme0n1p3 /home ext4 rw,relatime 0 0
+tmpfs /tmp tmpfs rw 0 0
+";
+
+fn entry(path: &str, bytes: u64) -> CleanedEntry {
+    CleanedEntry {
+        path: PathBuf::from(path),
+        bytes,
+        kind: CleanedItemType::Directory,
+    }
+}
+
+#[test]
+fn test_freed_bytes_add_up_by_mount() {
+    let entries = [
+        entry("/home/sam/.cache/mozilla", 300),
+        entry("/home/sam/.cache/thumbnails", 200),
+        entry("/var/cache/apt/archives", 1000),
+        entry("/tmp/build-1234", 50),
+        // Packages removed by a package manager aren't paths
+        entry("linux-image-6.1.0-13", 4000),
+    ];
+    let freed = freed_by_mount(MOUNTS, &entries);
+    assert_eq!(freed.len(), 3);
+    assert_eq!(freed[Path::new("/home")], 500);
+    assert_eq!(freed[Path::new("/")], 1000);
+    assert_eq!(freed[Path::new("/tmp")], 50);
+}
+
+#[test]
+fn test_parses_fstrim_output() {
+    assert_eq!(
+        parse_trimmed("/: 12.3 GiB (13207024640 bytes) trimmed on /dev/nvme0n1p2\n"),
+        Some(13207024640)
+    );
+    assert_eq!(parse_trimmed("/home: 0 B (0 bytes) trimmed\n"), Some(0));
+    assert_eq!(
+        parse_trimmed("/mnt/a (b): 1 MiB (1048576 bytes) trimmed\n"),
+        Some(1048576)
+    );
+    assert_eq!(
+        parse_trimmed("fstrim: /: the discard operation is not supported"),
+        None
+    );
+}
@@ -0,0 +1,83 @@
+//! Tests for the local usage statistics in src/usage.rs
+
+use cleansys::history::{CleanerRun, RunRecord};
+use cleansys::state::SessionState;
+use cleansys::usage::{CleanerUsage, UsageStats};
+use tempfile::tempdir;
+
+fn run(time: &str, cleaners: Vec<CleanerRun>) -> RunRecord {
+    RunRecord {
+        id: 0,
+        time: time.to_string(),
+        cleaners,
+        quarantine: None,
+    }
+}
+
+fn stats() -> UsageStats {
+    let mut stats = UsageStats::default();
+    stats.record(&run(
+        "2026-01-01 10:00:00",
+        vec![
+            CleanerRun::succeeded("Trash", 300),
+            CleanerRun::succeeded("Browser Caches", 1000),
+        ],
+    ));
+    stats.record(&run(
+        "2026-02-01 10:00:00",
+        vec![
+            CleanerRun::succeeded("Trash", 200),
+            CleanerRun::failed("System Logs", "Permission denied"),
+        ],
+    ));
+    stats
+}
+
+#[test]
+fn test_record_counts_runs_and_bytes() {
+    let stats = stats();
+    assert_eq!(stats.runs, 2);
+    assert_eq!(stats.bytes_freed, 1500);
+    assert_eq!(stats.since.as_deref(), Some("2026-01-01 10:00:00"));
+    assert_eq!(
+        stats.cleaners["Trash"],
+        CleanerUsage {
+            runs: 2,
+            bytes_freed: 500
+        }
+    );
+}
+
+#[test]
+fn test_empty_runs_are_not_counted() {
+    let mut stats = UsageStats::default();
+    stats.record(&run("2026-01-01 10:00:00", vec![]));
+    assert_eq!(stats, UsageStats::default());
+    assert_eq!(stats.summary_lines(), vec!["No runs counted yet"]);
+}
+
+#[test]
+fn test_most_used_orders_by_runs_then_bytes() {
+    let stats = stats();
+    let names: Vec<&str> = stats.most_used(2).into_iter().map(|(n, _)| n).collect();
+    assert_eq!(names, vec!["Trash", "Browser Caches"]);
+}
+
+#[test]
+fn test_usage_survives_state_file_round_trip() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("state.json");
+    let state = SessionState {
+        usage: stats(),
+        ..Default::default()
+    };
+    state.save_to(&path).unwrap();
+    assert_eq!(SessionState::load_from(&path).unwrap().usage, stats());
+
+    // State files from before usage statistics still load
+    std::fs::write(&path, r#"{"category_index": 1}"#).unwrap();
+    assert_eq!(
+        SessionState::load_from(&path).unwrap().usage,
+        UsageStats::default()
+    );
+}
@@ -0,0 +1,77 @@
+//! Tests for the crash file cleaners in src/cleaners/user_cleaners.rs
+
+#[cfg(unix)]
+use cleansys::cleaners::user_cleaners::owned_crash_reports;
+use cleansys::cleaners::user_cleaners::{coredump_uid, user_coredumps};
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_coredump_uid() {
+    let boot_id = "0123456789abcdef0123456789abcdef";
+    assert_eq!(
+        coredump_uid(&format!(
+            "core.firefox.1000.{}.4242.1700000000000000.zst",
+            boot_id
+        )),
+        Some(1000)
+    );
+    // Command names may contain dots
+    assert_eq!(
+        coredump_uid(&format!(
+            "core.python3.12.1001.{}.77.1700000000000000.xz",
+            boot_id
+        )),
+        Some(1001)
+    );
+    assert_eq!(coredump_uid("core.1234"), None);
+    assert_eq!(
+        coredump_uid(&format!("vmcore.a.1000.{}.1.2", boot_id)),
+        None
+    );
+}
+
+#[test]
+fn test_user_coredumps_match_uid() {
+    let dir = tempdir().unwrap();
+    let boot_id = "0123456789abcdef0123456789abcdef";
+    let mine = format!("core.vim.1000.{}.10.1700000000000000.zst", boot_id);
+    fs::write(dir.path().join(&mine), [0u8; 64]).unwrap();
+    fs::write(
+        dir.path()
+            .join(format!("core.sshd.0.{}.11.1700000000000000.zst", boot_id)),
+        [0u8; 64],
+    )
+    .unwrap();
+
+    assert_eq!(
+        user_coredumps(dir.path(), 1000),
+        vec![(dir.path().join(mine), 64)]
+    );
+    assert!(user_coredumps(&dir.path().join("none"), 1000).is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_owned_crash_reports() {
+    use std::os::unix::fs::MetadataExt;
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("_usr_bin_vim.1000.crash"), [0u8; 10]).unwrap();
+    fs::write(dir.path().join("_usr_bin_vim.1000.upload"), []).unwrap();
+    fs::write(dir.path().join("notes.txt"), [0u8; 10]).unwrap();
+    let uid = fs::metadata(dir.path()).unwrap().uid();
+
+    let reports: Vec<_> = owned_crash_reports(dir.path(), uid)
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect();
+    assert_eq!(
+        reports,
+        vec![
+            dir.path().join("_usr_bin_vim.1000.crash"),
+            dir.path().join("_usr_bin_vim.1000.upload"),
+        ]
+    );
+    assert!(owned_crash_reports(dir.path(), uid + 1).is_empty());
+}
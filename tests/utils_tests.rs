@@ -158,3 +158,49 @@ fn test_mixed_size_formatting() {
         assert_eq!(format_size(bytes), expected);
     }
 }
+
+#[test]
+fn test_parse_size_round_trips_format_size() {
+    assert_eq!(parse_size("512 bytes"), Some(512));
+    assert_eq!(parse_size("1.50 KB"), Some(1536));
+    assert_eq!(parse_size("2.00 MB"), Some(2 * 1024 * 1024));
+    assert_eq!(parse_size("1.00 GB"), Some(1024 * 1024 * 1024));
+}
+
+#[test]
+fn test_parse_si_size() {
+    // Docker and Podman print decimal units without a space
+    assert_eq!(parse_si_size("0B"), Some(0));
+    assert_eq!(parse_si_size("356kB"), Some(356_000));
+    assert_eq!(parse_si_size("1.2GB"), Some(1_200_000_000));
+    assert_eq!(parse_si_size("garbage"), None);
+}
+
+#[test]
+fn test_should_use_color() {
+    // Terminal defaults to color, pipes and dumb terminals don't
+    assert!(should_use_color(None, None, Some("xterm-256color"), true));
+    assert!(!should_use_color(None, None, Some("xterm-256color"), false));
+    assert!(!should_use_color(None, None, Some("dumb"), true));
+
+    // NO_COLOR wins over a terminal, but an empty value is ignored
+    assert!(!should_use_color(Some("1"), None, None, true));
+    assert!(should_use_color(Some(""), None, None, true));
+
+    // CLICOLOR_FORCE wins over everything, except when set to 0
+    assert!(should_use_color(Some("1"), Some("1"), None, false));
+    assert!(!should_use_color(None, Some("0"), None, false));
+}
+
+#[test]
+fn test_should_use_askpass() {
+    let helper = Some("/usr/bin/ssh-askpass");
+    // Without a terminal the helper is the only way to ask
+    assert!(should_use_askpass(helper, false, false));
+    assert!(!should_use_askpass(helper, false, true));
+    // Configured to use it on a terminal too
+    assert!(should_use_askpass(helper, true, true));
+    // Nothing to use without a helper
+    assert!(!should_use_askpass(None, true, false));
+    assert!(!should_use_askpass(Some(" "), true, false));
+}
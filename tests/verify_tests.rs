@@ -0,0 +1,106 @@
+//! Tests for verify-freed mode in src/verify.rs
+
+use cleansys::verify::{parse_mounts, MountDelta, MountFree, Snapshot, Verification};
+use std::path::PathBuf;
+
+const MIB: u64 = 1024 * 1024;
+
+fn mount(path: &str, device: u64, available: u64) -> MountFree {
+    MountFree {
+        mount: PathBuf::from(path),
+        device,
+        available,
+    }
+}
+
+fn verification(reported: u64, freed: i64) -> Verification {
+    Verification {
+        reported,
+        deltas: vec![MountDelta {
+            mount: PathBuf::from("/"),
+            freed,
+        }],
+    }
+}
+
+#[test]
+fn test_parse_mounts() {
+    let mounts = "/dev/nvme0n1p2 / ext4 rw,relatime 0 0\n\
+                  proc /proc proc rw,nosuid 0 0\n\
+                  /dev/sdb1 /media/My\\040Disk vfat rw 0 0\n";
+    assert_eq!(
+        parse_mounts(mounts),
+        vec![
+            PathBuf::from("/"),
+            PathBuf::from("/proc"),
+            PathBuf::from("/media/My Disk"),
+        ]
+    );
+}
+
+#[test]
+fn test_deltas_per_filesystem() {
+    let before = Snapshot(vec![
+        mount("/", 1, 100 * MIB),
+        mount("/home", 2, 500 * MIB),
+        mount("/boot", 3, 50 * MIB),
+    ]);
+    let after = Snapshot(vec![
+        mount("/", 1, 90 * MIB),
+        mount("/home", 2, 700 * MIB),
+        mount("/boot", 3, 50 * MIB),
+        // Mounted during the run: nothing to compare with
+        mount("/media/usb", 4, 10 * MIB),
+    ]);
+
+    assert_eq!(
+        after.freed_since(&before),
+        vec![
+            MountDelta {
+                mount: PathBuf::from("/"),
+                freed: -10 * MIB as i64,
+            },
+            MountDelta {
+                mount: PathBuf::from("/home"),
+                freed: 200 * MIB as i64,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_disagreement() {
+    // Close enough
+    assert!(!verification(100 * MIB, 90 * MIB as i64).disagrees());
+    // Small sizes are within the noise of other writers
+    assert!(!verification(5 * MIB, 0).disagrees());
+    // A hardcoded estimate far above what the disk shows
+    assert!(verification(1024 * MIB, 40 * MIB as i64).disagrees());
+    // Freed far more than reported
+    assert!(verification(0, 200 * MIB as i64).disagrees());
+    // The disk filled up while the cleaner claimed to free space
+    assert!(verification(100 * MIB, -(50 * MIB as i64)).disagrees());
+}
+
+#[test]
+fn test_summary() {
+    assert_eq!(
+        verification(100 * MIB, -(2 * MIB as i64)).summary(),
+        "reported 100.00 MB, disk -2.00 MB (/: -2.00 MB)"
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_snapshot_includes_root() {
+    let snapshot = Snapshot::take();
+    assert!(snapshot
+        .0
+        .iter()
+        .any(|m| m.mount == std::path::Path::new("/")));
+    // proc and sysfs have no blocks and are left out
+    assert!(!snapshot
+        .0
+        .iter()
+        .any(|m| m.mount == std::path::Path::new("/proc")));
+}
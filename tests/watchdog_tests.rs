@@ -0,0 +1,92 @@
+//! Tests for per-cleaner timeouts in src/watchdog.rs
+
+use cleansys::cancel;
+use cleansys::watchdog::{
+    descendants, describe, parse_stat_ppid, run_with_timeout, CleanerTimeouts,
+};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+#[test]
+fn test_timeouts_per_cleaner() {
+    let timeouts = CleanerTimeouts {
+        default: Some(Duration::from_secs(60)),
+        per_cleaner: BTreeMap::from([
+            ("Old Kernels".to_string(), Duration::from_secs(600)),
+            ("Trash".to_string(), Duration::ZERO),
+        ]),
+    };
+    assert_eq!(
+        timeouts.for_cleaner("Old Kernels"),
+        Some(Duration::from_secs(600))
+    );
+    assert_eq!(timeouts.for_cleaner("Trash"), None);
+    assert_eq!(
+        timeouts.for_cleaner("System Logs"),
+        Some(Duration::from_secs(60))
+    );
+    assert_eq!(describe(Duration::from_secs(1800)), "30 min");
+    assert_eq!(describe(Duration::from_secs(90)), "90 s");
+}
+
+#[test]
+fn test_parse_stat_ppid() {
+    assert_eq!(
+        parse_stat_ppid("4242 (find) S 4100 4242 4100 0 -1 4194304"),
+        Some(4100)
+    );
+    // Command names may contain spaces and parentheses
+    assert_eq!(
+        parse_stat_ppid("77 (Web Content (x)) R 12 77 12 0 -1"),
+        Some(12)
+    );
+    assert_eq!(parse_stat_ppid("garbage"), None);
+}
+
+#[test]
+fn test_descendants_deepest_first() {
+    let processes = [(10, 1), (20, 10), (30, 20), (40, 10), (50, 2)];
+    let found = descendants(10, &processes);
+    assert_eq!(found.len(), 3);
+    assert!(!found.contains(&50));
+    // A grandchild is killed before its parent
+    let position = |pid| found.iter().position(|&p| p == pid).unwrap();
+    assert!(position(30) < position(20));
+}
+
+#[test]
+fn test_finishing_cleaner_keeps_its_result() {
+    let result = run_with_timeout("Quick", Duration::from_secs(10), || Ok(42));
+    assert_eq!(result.unwrap(), 42);
+    assert!(cancel::check().is_ok());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_hung_cleaner_times_out() {
+    use cleansys::watchdog::TimedOut;
+    use std::time::Instant;
+
+    let started = Instant::now();
+    let result = run_with_timeout("Hung", Duration::from_millis(200), || {
+        // A child that never finishes on its own, like `find /`
+        std::process::Command::new("sleep").arg("60").status()?;
+        // Deletion loops notice the timeout at their next file
+        while cancel::check().is_ok() {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        Ok(0u64)
+    });
+
+    assert!(started.elapsed() < Duration::from_secs(30));
+    let error = result.unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<TimedOut>(),
+        Some(&TimedOut {
+            cleaner: "Hung".to_string(),
+            after: Duration::from_millis(200),
+        })
+    );
+    // The next cleaner starts afresh
+    assert!(cancel::check().is_ok());
+}
@@ -0,0 +1,39 @@
+//! Tests for the inotify cache watcher in src/watcher.rs
+
+use cleansys::watcher::CacheWatcher;
+use std::fs;
+use std::time::{Duration, Instant};
+use tempfile::tempdir;
+
+fn wait_for_change(watcher: &CacheWatcher) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(3);
+    while Instant::now() < deadline {
+        if watcher.take_changed() {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    false
+}
+
+#[test]
+fn test_watcher_reports_created_and_deleted_files() {
+    let dir = tempdir().unwrap();
+    let watcher = CacheWatcher::start(&[dir.path().to_path_buf()]).unwrap();
+    assert_eq!(watcher.watched(), 1);
+    assert!(!watcher.take_changed());
+
+    fs::write(dir.path().join("entry"), "data").unwrap();
+    assert!(wait_for_change(&watcher));
+    assert!(!watcher.take_changed());
+
+    fs::remove_file(dir.path().join("entry")).unwrap();
+    assert!(wait_for_change(&watcher));
+}
+
+#[test]
+fn test_watcher_needs_a_watchable_directory() {
+    let dir = tempdir().unwrap();
+    assert!(CacheWatcher::start(&[dir.path().join("missing")]).is_err());
+    assert!(CacheWatcher::start(&[]).is_err());
+}